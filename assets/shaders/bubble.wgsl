@@ -0,0 +1,72 @@
+// Mesh bubble shading: radial highlight, darker rim, vertex wobble, and a hit
+// flash. Parameters come from `BubbleMaterial` in `src/game/bubble_material.rs`.
+
+#import bevy_sprite::{
+    mesh2d_functions as mesh_functions,
+    mesh2d_vertex_output::VertexOutput,
+    mesh2d_view_bindings::{globals, view},
+}
+
+#ifdef TONEMAP_IN_SHADER
+#import bevy_core_pipeline::tonemapping
+#endif
+
+struct BubbleParams {
+    color: vec4<f32>,
+    highlight: f32,
+    rim: f32,
+    wobble: f32,
+    flash: f32,
+};
+
+@group(#{MATERIAL_BIND_GROUP}) @binding(0) var<uniform> material: BubbleParams;
+
+struct Vertex {
+    @builtin(instance_index) instance_index: u32,
+    @location(0) position: vec3<f32>,
+    @location(2) uv: vec2<f32>,
+};
+
+// How fast the outline sways, in radians per second
+const WOBBLE_SPEED: f32 = 6.0;
+
+@vertex
+fn vertex(vertex: Vertex) -> VertexOutput {
+    var out: VertexOutput;
+    out.uv = vertex.uv;
+
+    // Push each corner in and out along its own phase so the outline ripples
+    // instead of the whole bubble scaling
+    let angle = atan2(vertex.position.y, vertex.position.x);
+    let sway = sin(globals.time * WOBBLE_SPEED + angle * 3.0) * material.wobble;
+    let position = vec3<f32>(vertex.position.xy * (1.0 + sway), vertex.position.z);
+
+    let world_from_local = mesh_functions::get_world_from_local(vertex.instance_index);
+    out.world_position = mesh_functions::mesh2d_position_local_to_world(
+        world_from_local,
+        vec4<f32>(position, 1.0)
+    );
+    out.position = mesh_functions::mesh2d_position_world_to_clip(out.world_position);
+    return out;
+}
+
+@fragment
+fn fragment(in: VertexOutput) -> @location(0) vec4<f32> {
+    // 0 at the center, 1 at the corners
+    let offset = (in.uv - vec2<f32>(0.5)) * 2.0;
+    let distance = length(offset);
+
+    var color = material.color.rgb;
+    // Darken toward the rim
+    color = color * (1.0 - material.rim * smoothstep(0.6, 1.0, distance));
+    // Soft spot of light up and to the left (uv y points down)
+    let spot = 1.0 - smoothstep(0.0, 0.55, length(offset - vec2<f32>(-0.35, -0.35)));
+    color = mix(color, vec3<f32>(1.0), material.highlight * spot);
+    color = mix(color, vec3<f32>(1.0), material.flash);
+
+    var output_color = vec4<f32>(color, material.color.a);
+#ifdef TONEMAP_IN_SHADER
+    output_color = tonemapping::tone_mapping(output_color, view.color_grading);
+#endif
+    return output_color;
+}
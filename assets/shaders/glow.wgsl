@@ -0,0 +1,35 @@
+// Glow rings for cell highlights. Each quad is one highlighted cell, with its
+// color and intensity in the vertex color. Parameters come from
+// `GlowMaterial` in `src/game/highlight.rs`.
+
+#import bevy_sprite::{
+    mesh2d_vertex_output::VertexOutput,
+    mesh2d_view_bindings::globals,
+}
+
+struct GlowParams {
+    ring: f32,
+    width: f32,
+    pulse: f32,
+    pulse_depth: f32,
+};
+
+@group(#{MATERIAL_BIND_GROUP}) @binding(0) var<uniform> material: GlowParams;
+
+@fragment
+fn fragment(in: VertexOutput) -> @location(0) vec4<f32> {
+#ifdef VERTEX_COLORS
+    let tint = in.color;
+#else
+    let tint = vec4<f32>(1.0);
+#endif
+
+    // 0 at the cell's center, 1 at the quad's edge
+    let distance = length(in.uv - vec2<f32>(0.5)) * 2.0;
+    // Brightest on the ring, falling off smoothly to both sides
+    let offset = (distance - material.ring) / material.width;
+    let ring = exp(-offset * offset);
+    let breath = 1.0 - material.pulse_depth * (0.5 + 0.5 * sin(globals.time * material.pulse));
+
+    return vec4<f32>(tint.rgb, tint.a * ring * breath);
+}
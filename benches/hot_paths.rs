@@ -0,0 +1,188 @@
+//! Benchmarks for the board's hot paths, on a representative 150-bubble
+//! board:
+//!
+//! ```text
+//! cargo bench --bench hot_paths --features bench
+//! ```
+//!
+//! Before measuring, each path is timed against a budget, and the run fails
+//! if one goes over. Budgets are far above what the paths take, so noisy CI
+//! runners don't trip them, but a path that's gone quadratic will. Budgets
+//! only apply to `cargo bench`; under `cargo test` each benchmark just runs
+//! once.
+
+use std::{
+    collections::HashMap,
+    hint::black_box,
+    time::{Duration, Instant},
+};
+
+use bevy::prelude::*;
+use criterion::Criterion;
+use rand::{Rng, SeedableRng, rngs::StdRng};
+
+use snord::bench::{
+    BubbleColor, Bumpers, GRID_ORIGIN_Y, GameMode, HexCoord, HexGrid, Portals, SHOOTER_Y,
+    StickyWalls, Walls, find_anchored_bubbles, find_cluster, find_floating_bubbles,
+    predict_landing,
+};
+
+/// Bubbles on the benchmark board.
+const BOARD_BUBBLES: usize = 150;
+
+/// Colors on the benchmark board, as in a mid-game classic run.
+const BOARD_COLORS: usize = 5;
+
+/// Calls timed for each budget check.
+const BUDGET_CALLS: u32 = 200;
+
+/// A board filled row by row from the top, with seeded colors.
+struct Board {
+    grid: HexGrid,
+    colors: HashMap<HexCoord, BubbleColor>,
+    /// The bottom bubble's cluster, and the board with it popped.
+    popped: Vec<HexCoord>,
+    popped_grid: HexGrid,
+}
+
+impl Board {
+    fn representative() -> Self {
+        let mut rng = StdRng::seed_from_u64(150);
+        let mut grid = HexGrid::default();
+        let colors: HashMap<HexCoord, BubbleColor> = grid
+            .bounds
+            .iter()
+            .take(BOARD_BUBBLES)
+            .map(|coord| (coord, BubbleColor::ALL[rng.random_range(0..BOARD_COLORS)]))
+            .collect();
+        for &coord in colors.keys() {
+            grid.insert(coord, Entity::PLACEHOLDER);
+        }
+        let mut board = Self {
+            grid,
+            colors,
+            popped: Vec::new(),
+            popped_grid: HexGrid::default(),
+        };
+        board.popped = board.cluster_from_bottom();
+        for &coord in board.colors.keys() {
+            if !board.popped.contains(&coord) {
+                board.popped_grid.insert(coord, Entity::PLACEHOLDER);
+            }
+        }
+        board
+    }
+
+    /// The lowest, rightmost bubble, where a shot would land next to.
+    fn bottom(&self) -> HexCoord {
+        self.colors
+            .keys()
+            .copied()
+            .max_by_key(|coord| (coord.r, coord.q))
+            .expect("board isn't empty")
+    }
+
+    fn cluster_from_bottom(&self) -> Vec<HexCoord> {
+        let start = self.bottom();
+        find_cluster(&self.grid.layout, start, self.colors[&start], |coord| {
+            self.colors.get(&coord).copied()
+        })
+    }
+
+    fn anchored(&self) -> usize {
+        find_anchored_bubbles(&self.grid, self.grid.top_row_coords()).len()
+    }
+
+    /// What falls once the bottom bubble's cluster pops.
+    fn floating_after_pop(&self) -> usize {
+        find_floating_bubbles(
+            &self.popped_grid,
+            self.popped_grid.top_row_coords(),
+            &self.popped,
+        )
+        .len()
+    }
+
+    fn snap_below_bottom(&self) -> Option<HexCoord> {
+        let below = HexCoord::new(0, self.bottom().r + 1);
+        let pos = below.to_pixel_with_offset(self.grid.hex_size, GRID_ORIGIN_Y);
+        self.grid.closest_empty_cell(pos, GRID_ORIGIN_Y)
+    }
+
+    /// A bank shot off the left wall.
+    fn predict_bank_shot(&self) -> bool {
+        predict_landing(
+            &self.grid,
+            GRID_ORIGIN_Y,
+            Walls::default(),
+            &Portals::default(),
+            &StickyWalls::default(),
+            &Bumpers::default(),
+            Vec2::new(0.0, SHOOTER_Y),
+            Vec2::new(-0.6, 1.0),
+            self.grid.hex_size * GameMode::Classic.collision_reach(),
+        )
+        .is_some()
+    }
+}
+
+/// Fail if `path` takes longer than `budget` a call on average.
+fn check_budget<T>(name: &str, budget: Duration, mut path: impl FnMut() -> T) {
+    let start = Instant::now();
+    for _ in 0..BUDGET_CALLS {
+        black_box(path());
+    }
+    let per_call = start.elapsed() / BUDGET_CALLS;
+    assert!(
+        per_call <= budget,
+        "{name} took {per_call:?} a call, over its budget of {budget:?}"
+    );
+}
+
+fn check_budgets(board: &Board) {
+    check_budget("find_cluster", Duration::from_micros(100), || {
+        board.cluster_from_bottom()
+    });
+    check_budget("find_anchored_bubbles", Duration::from_micros(200), || {
+        board.anchored()
+    });
+    check_budget("find_floating_bubbles", Duration::from_micros(200), || {
+        board.floating_after_pop()
+    });
+    check_budget("closest_empty_cell", Duration::from_micros(100), || {
+        board.snap_below_bottom()
+    });
+    check_budget("predict_landing", Duration::from_millis(1), || {
+        board.predict_bank_shot()
+    });
+}
+
+fn bench_hot_paths(criterion: &mut Criterion, board: &Board) {
+    let mut group = criterion.benchmark_group("150 bubbles");
+    group.bench_function("find_cluster", |b| b.iter(|| board.cluster_from_bottom()));
+    group.bench_function("find_anchored_bubbles", |b| b.iter(|| board.anchored()));
+    group.bench_function("find_floating_bubbles", |b| {
+        b.iter(|| board.floating_after_pop())
+    });
+    group.bench_function("closest_empty_cell", |b| {
+        b.iter(|| board.snap_below_bottom())
+    });
+    group.bench_function("predict_landing", |b| b.iter(|| board.predict_bank_shot()));
+    group.finish();
+}
+
+fn main() {
+    let board = Board::representative();
+    assert_eq!(board.anchored(), BOARD_BUBBLES);
+    assert!(board.snap_below_bottom().is_some());
+    assert!(board.predict_bank_shot());
+
+    // Unoptimized test builds are too slow to hold to the budgets
+    if std::env::args().any(|arg| arg == "--bench") {
+        check_budgets(&board);
+    }
+
+    let mut criterion = Criterion::default().configure_from_args();
+    bench_hot_paths(&mut criterion, &board);
+    criterion.final_summary();
+}
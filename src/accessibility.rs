@@ -0,0 +1,210 @@
+//! Accessibility settings: reduced motion, colorblind symbols, volume, and
+//! the descent preview assist.
+//!
+//! They're picked on first launch in the accessibility prompt, can be changed
+//! later in the settings menu, and are saved to `accessibility.json` next to
+//! the other save files. The file also records whether the prompt has been
+//! answered, so it only appears once, and which version's What's New was
+//! last shown, so that appears once per update.
+
+use bevy::{audio::Volume, prelude::*};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+
+use crate::save::{Migration, SaveFile, save_on_change};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<AccessibilitySettings>();
+    app.init_resource::<ColorblindMode>();
+    app.register_type::<AccessibilitySettings>();
+    app.register_type::<ColorblindMode>();
+
+    app.add_systems(Startup, load_accessibility_settings);
+    app.add_systems(
+        Update,
+        (
+            record_volume.run_if(resource_changed::<GlobalVolume>),
+            record_colorblind_mode.run_if(resource_changed::<ColorblindMode>),
+            save_on_change::<AccessibilitySettings>
+                .run_if(resource_changed::<AccessibilitySettings>),
+        )
+            .chain(),
+    );
+}
+
+/// Accessibility settings, saved between sessions.
+#[derive(Resource, Debug, Clone, PartialEq, Reflect, Serialize, Deserialize)]
+#[reflect(Resource)]
+pub struct AccessibilitySettings {
+    /// Turn off screen shake.
+    pub reduced_motion: bool,
+    /// Symbols on each bubble so colors can be told apart without hue,
+    /// applied to [`ColorblindMode`] on startup.
+    #[serde(default)]
+    pub colorblind: ColorblindMode,
+    /// Global volume (linear), applied to [`GlobalVolume`] on startup.
+    pub volume: f32,
+    /// Whether the first-run prompt has been answered.
+    #[serde(default)]
+    pub prompt_seen: bool,
+    /// Ghost the board one row lower when the next shot brings a descent.
+    #[serde(default)]
+    pub descent_preview: bool,
+    /// The game version whose What's New was last shown.
+    #[serde(default)]
+    pub whats_new_seen: Option<String>,
+}
+
+impl Default for AccessibilitySettings {
+    fn default() -> Self {
+        Self {
+            reduced_motion: false,
+            colorblind: ColorblindMode::Off,
+            volume: 1.0,
+            prompt_seen: false,
+            descent_preview: false,
+            whats_new_seen: None,
+        }
+    }
+}
+
+/// What's drawn over each bubble to tell its color apart without hue.
+#[derive(Resource, Debug, Clone, Copy, Default, PartialEq, Eq, Reflect, Serialize, Deserialize)]
+#[reflect(Resource)]
+pub enum ColorblindMode {
+    #[default]
+    Off,
+    /// A shape outline per color.
+    Shapes,
+    /// A letter per color.
+    Letters,
+}
+
+impl ColorblindMode {
+    pub fn label(self) -> &'static str {
+        match self {
+            ColorblindMode::Off => "Off",
+            ColorblindMode::Shapes => "Shapes",
+            ColorblindMode::Letters => "Letters",
+        }
+    }
+
+    /// The mode after this one, for cycling through them with a button.
+    pub fn next(self) -> Self {
+        match self {
+            ColorblindMode::Off => ColorblindMode::Shapes,
+            ColorblindMode::Shapes => ColorblindMode::Letters,
+            ColorblindMode::Letters => ColorblindMode::Off,
+        }
+    }
+}
+
+impl AccessibilitySettings {
+    /// Whether this version's What's New hasn't been shown yet.
+    pub fn whats_new_pending(&self) -> bool {
+        self.whats_new_seen.as_deref() != Some(env!("CARGO_PKG_VERSION"))
+    }
+
+    /// Remember that this version's What's New has been shown.
+    pub fn mark_whats_new_seen(&mut self) {
+        if self.whats_new_pending() {
+            self.whats_new_seen = Some(env!("CARGO_PKG_VERSION").to_string());
+        }
+    }
+}
+
+impl SaveFile for AccessibilitySettings {
+    const FILE_NAME: &'static str = "accessibility.json";
+    const DESCRIPTION: &'static str = "accessibility settings";
+    const MIGRATIONS: &'static [Migration] = &[patterns_to_colorblind_mode];
+}
+
+/// Version 2 replaced the on/off `colorblind_patterns` with a
+/// [`ColorblindMode`]. Patterns that were on become shapes.
+fn patterns_to_colorblind_mode(mut value: Value) -> Result<Value, String> {
+    let settings = value.as_object_mut().ok_or("not an object")?;
+    let patterns = settings
+        .remove("colorblind_patterns")
+        .and_then(|patterns| patterns.as_bool())
+        .unwrap_or(false);
+    let mode = if patterns {
+        ColorblindMode::Shapes
+    } else {
+        ColorblindMode::Off
+    };
+    settings.insert("colorblind".to_string(), json!(mode));
+    Ok(value)
+}
+
+fn load_accessibility_settings(
+    mut settings: ResMut<AccessibilitySettings>,
+    mut global_volume: ResMut<GlobalVolume>,
+    mut colorblind: ResMut<ColorblindMode>,
+) {
+    *settings = AccessibilitySettings::load();
+    global_volume.volume = Volume::Linear(settings.volume);
+    *colorblind = settings.colorblind;
+}
+
+/// Keep the saved volume in step with the volume buttons.
+fn record_volume(global_volume: Res<GlobalVolume>, mut settings: ResMut<AccessibilitySettings>) {
+    let volume = global_volume.volume.to_linear();
+    if settings.volume != volume {
+        settings.volume = volume;
+    }
+}
+
+/// Keep the saved colorblind mode in step with the settings button.
+fn record_colorblind_mode(
+    colorblind: Res<ColorblindMode>,
+    mut settings: ResMut<AccessibilitySettings>,
+) {
+    if settings.colorblind != *colorblind {
+        settings.colorblind = *colorblind;
+    }
+}
+
+/// Run condition: screen shake and similar motion are allowed.
+pub fn full_motion(settings: Res<AccessibilitySettings>) -> bool {
+    !settings.reduced_motion
+}
+
+/// Run condition: colorblind shapes are turned on.
+pub fn shapes_enabled(colorblind: Res<ColorblindMode>) -> bool {
+    *colorblind == ColorblindMode::Shapes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::save::{decode, encode};
+
+    #[test]
+    fn old_patterns_setting_loads_as_shapes() {
+        let v1 = r#"{ "version": 1, "data": { "reduced_motion": false, "colorblind_patterns": true, "volume": 1.0 } }"#;
+        let settings = decode::<AccessibilitySettings>(v1).unwrap();
+        assert_eq!(settings.colorblind, ColorblindMode::Shapes);
+
+        let saved = AccessibilitySettings {
+            colorblind: ColorblindMode::Letters,
+            ..default()
+        };
+        let json = encode(&saved).unwrap();
+        assert_eq!(decode::<AccessibilitySettings>(&json), Ok(saved));
+    }
+
+    #[test]
+    fn whats_new_is_pending_until_this_version_is_seen() {
+        let mut settings = AccessibilitySettings {
+            whats_new_seen: Some("0.0.1".to_string()),
+            ..default()
+        };
+        assert!(settings.whats_new_pending());
+        settings.mark_whats_new_seen();
+        assert!(!settings.whats_new_pending());
+        assert_eq!(
+            settings.whats_new_seen.as_deref(),
+            Some(env!("CARGO_PKG_VERSION"))
+        );
+    }
+}
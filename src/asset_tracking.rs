@@ -4,6 +4,8 @@ use std::collections::VecDeque;
 
 use bevy::prelude::*;
 
+use crate::screens::Screen;
+
 pub(super) fn plugin(app: &mut App) {
     app.init_resource::<ResourceHandles>();
     app.add_systems(PreUpdate, load_resource_assets);
@@ -14,28 +16,76 @@ pub trait LoadResource {
     /// have been loaded, it will be inserted as a resource. This ensures that the resource only
     /// exists when the assets are ready.
     fn load_resource<T: Resource + Asset + Clone + FromWorld>(&mut self) -> &mut Self;
+
+    /// Like [`LoadResource::load_resource`], but only keeps the [`Resource`] and its assets
+    /// resident while `screen` is active. It's loaded behind the loading screen and released
+    /// when leaving `screen`. Entering `screen` some other way (the demo, the replay viewer)
+    /// loads it then, so it turns up a little late rather than not at all.
+    fn load_screen_resource<T: Resource + Asset + Clone + FromWorld>(
+        &mut self,
+        screen: impl States,
+    ) -> &mut Self;
 }
 
 impl LoadResource for App {
     fn load_resource<T: Resource + Asset + Clone + FromWorld>(&mut self) -> &mut Self {
         self.init_asset::<T>();
-        let world = self.world_mut();
-        let value = T::from_world(world);
-        let assets = world.resource::<AssetServer>();
-        let handle = assets.add(value);
-        let mut handles = world.resource_mut::<ResourceHandles>();
-        handles
-            .waiting
-            .push_back((handle.untyped(), |world, handle| {
-                let assets = world.resource::<Assets<T>>();
-                if let Some(value) = assets.get(handle.id().typed::<T>()) {
-                    world.insert_resource(value.clone());
-                }
-            }));
+        queue_resource::<T>(self.world_mut());
+        self
+    }
+
+    fn load_screen_resource<T: Resource + Asset + Clone + FromWorld>(
+        &mut self,
+        screen: impl States,
+    ) -> &mut Self {
+        self.init_asset::<T>();
+        self.add_systems(OnEnter(Screen::Loading), queue_resource::<T>);
+        self.add_systems(OnEnter(screen.clone()), queue_resource::<T>);
+        self.add_systems(OnExit(screen), release_resource::<T>);
         self
     }
 }
 
+/// Start loading `T` and insert it as a resource once it's ready, unless it's
+/// already loaded or on its way.
+fn queue_resource<T: Resource + Asset + Clone + FromWorld>(world: &mut World) {
+    let type_id = std::any::TypeId::of::<T>();
+    if world.contains_resource::<T>()
+        || world
+            .resource::<ResourceHandles>()
+            .waiting
+            .iter()
+            .any(|(handle, _)| handle.type_id() == type_id)
+    {
+        return;
+    }
+    let value = T::from_world(world);
+    let assets = world.resource::<AssetServer>();
+    let handle = assets.add(value);
+    let mut handles = world.resource_mut::<ResourceHandles>();
+    handles
+        .waiting
+        .push_back((handle.untyped(), |world, handle| {
+            let assets = world.resource::<Assets<T>>();
+            if let Some(value) = assets.get(handle.id().typed::<T>()) {
+                world.insert_resource(value.clone());
+            }
+        }));
+}
+
+/// Drop `T` and our handle to it, so its assets unload once nothing else uses them.
+fn release_resource<T: Resource + Asset>(world: &mut World) {
+    world.remove_resource::<T>();
+    let mut handles = world.resource_mut::<ResourceHandles>();
+    let type_id = std::any::TypeId::of::<T>();
+    handles
+        .waiting
+        .retain(|(handle, _)| handle.type_id() != type_id);
+    handles
+        .finished
+        .retain(|handle| handle.type_id() != type_id);
+}
+
 /// A function that inserts a loaded resource.
 type InsertLoadedResource = fn(&mut World, &UntypedHandle);
 
@@ -52,6 +102,12 @@ impl ResourceHandles {
     pub fn is_all_done(&self) -> bool {
         self.waiting.is_empty()
     }
+
+    /// How many requested resources are loaded, out of how many in total.
+    pub fn progress(&self) -> (usize, usize) {
+        let done = self.finished.len();
+        (done, done + self.waiting.len())
+    }
 }
 
 fn load_resource_assets(world: &mut World) {
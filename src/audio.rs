@@ -1,12 +1,27 @@
-use bevy::prelude::*;
+use bevy::{
+    diagnostic::{Diagnostic, DiagnosticPath, Diagnostics, RegisterDiagnostic},
+    prelude::*,
+};
 
 pub(super) fn plugin(app: &mut App) {
+    app.register_diagnostic(Diagnostic::new(AUDIO_MEMORY).with_suffix(" KiB"));
+    app.register_diagnostic(Diagnostic::new(AUDIO_SOURCES));
+
     app.add_systems(
         Update,
-        apply_global_volume.run_if(resource_changed::<GlobalVolume>),
+        (
+            apply_global_volume.run_if(resource_changed::<GlobalVolume>),
+            measure_audio_memory,
+        ),
     );
 }
 
+/// Memory held by loaded audio sources, in KiB.
+pub const AUDIO_MEMORY: DiagnosticPath = DiagnosticPath::const_new("audio/memory");
+
+/// Number of loaded audio sources.
+pub const AUDIO_SOURCES: DiagnosticPath = DiagnosticPath::const_new("audio/sources");
+
 /// An organizational marker component that should be added to a spawned [`AudioPlayer`] if it's in the
 /// general "music" category (e.g. global background music, soundtrack).
 ///
@@ -16,6 +31,10 @@ pub(super) fn plugin(app: &mut App) {
 pub struct Music;
 
 /// A music audio instance.
+///
+/// Sources stay compressed in memory and are decoded as they play, so a long
+/// track costs its file size rather than its decoded length. Load music where
+/// it plays instead of in an always-resident bank.
 #[allow(dead_code)]
 pub fn music(handle: Handle<AudioSource>) -> impl Bundle {
     (AudioPlayer(handle), PlaybackSettings::LOOP, Music)
@@ -58,3 +77,10 @@ fn apply_global_volume(
         sink.set_volume(global_volume.volume * playback.volume);
     }
 }
+
+/// Sample how much audio is resident, for the diagnostics overlay.
+fn measure_audio_memory(mut diagnostics: Diagnostics, sources: Res<Assets<AudioSource>>) {
+    let bytes: usize = sources.iter().map(|(_, source)| source.bytes.len()).sum();
+    diagnostics.add_measurement(&AUDIO_MEMORY, || bytes as f64 / 1024.0);
+    diagnostics.add_measurement(&AUDIO_SOURCES, || sources.len() as f64);
+}
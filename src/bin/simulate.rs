@@ -0,0 +1,198 @@
+//! Headless balance tuning harness.
+//!
+//! Plays thousands of games with bot policies and prints the distribution of
+//! level reached, score, and loss causes for each config:
+//!
+//! ```text
+//! cargo run --bin simulate -- --games 2000 --policy greedy,random --colors 4,5,6 --shots 6,8
+//! ```
+//!
+//! Each config runs on its own task pool thread. Games run on the headless
+//! core in `src/sim`, which doesn't model aiming
+//! and ignores power-ups. The policies bracket human play: `random` is far
+//! worse than a player, `greedy` and `drops` (with perfect aim) far better.
+
+use bevy::tasks::TaskPool;
+use rand::{SeedableRng, rngs::StdRng};
+
+use snord::sim::{GameResult, Outcome, SimConfig, play_game, policy_by_name};
+
+/// Safety cap so a stalemate can't loop forever.
+const MAX_SHOTS_PER_GAME: u32 = 5_000;
+
+/// Value at the given percentile of a sorted slice.
+fn percentile(sorted: &[u32], p: f32) -> u32 {
+    if sorted.is_empty() {
+        return 0;
+    }
+    let index = ((sorted.len() - 1) as f32 * p).round() as usize;
+    sorted[index]
+}
+
+fn report(policy: &str, config: SimConfig, results: &[GameResult]) {
+    let mut levels: Vec<u32> = results.iter().map(|r| r.level).collect();
+    let mut scores: Vec<u32> = results.iter().map(|r| r.score).collect();
+    levels.sort_unstable();
+    scores.sort_unstable();
+    let games = results.len() as f32;
+
+    println!(
+        "policy={} colors={} shots={} games={}",
+        policy,
+        config.colors,
+        config.shots_per_descent,
+        results.len()
+    );
+    println!(
+        "  level  p10={} p50={} p90={} max={} mean={:.1}",
+        percentile(&levels, 0.1),
+        percentile(&levels, 0.5),
+        percentile(&levels, 0.9),
+        levels.last().copied().unwrap_or(0),
+        levels.iter().sum::<u32>() as f32 / games
+    );
+    println!(
+        "  score  p10={} p50={} p90={} max={} mean={:.0}",
+        percentile(&scores, 0.1),
+        percentile(&scores, 0.5),
+        percentile(&scores, 0.9),
+        scores.last().copied().unwrap_or(0),
+        scores.iter().sum::<u32>() as f32 / games
+    );
+
+    let mut causes = String::new();
+    for (outcome, label) in [
+        (Outcome::Won, "won"),
+        (Outcome::GridReachedDanger, "grid reached danger"),
+        (Outcome::DangerLanding, "danger landing"),
+        (Outcome::ShotLimit, "shot limit"),
+    ] {
+        let count = results.iter().filter(|r| r.outcome == outcome).count();
+        if count > 0 {
+            causes.push_str(&format!(
+                " {label}={count} ({:.0}%)",
+                100.0 * count as f32 / games
+            ));
+        }
+    }
+    println!("  ends  {}", causes.trim_start());
+
+    // Level histogram in up to 10 buckets, at least 5 levels wide
+    let max_level = levels.last().copied().unwrap_or(0);
+    let width = max_level.div_ceil(10).max(5);
+    for bucket in 0..=max_level / width {
+        let low = bucket * width;
+        let count = levels.iter().filter(|&&l| l / width == bucket).count();
+        let bar = "#".repeat((60.0 * count as f32 / games).ceil() as usize);
+        println!("  L{:>4}-{:<4} {:>6} {}", low, low + width - 1, count, bar);
+    }
+    println!();
+}
+
+struct Args {
+    games: u32,
+    seed: u64,
+    policies: Vec<String>,
+    colors: Vec<u8>,
+    shots: Vec<u32>,
+}
+
+const USAGE: &str = "usage: simulate [--games N] [--seed N] [--policy random,greedy,drops] \
+[--colors 4,5,6] [--shots 6,8]";
+
+fn parse_list<T: std::str::FromStr>(value: &str) -> Option<Vec<T>> {
+    value.split(',').map(|v| v.trim().parse().ok()).collect()
+}
+
+/// Parse the command line. Returns `None` if `--help` was asked for.
+fn parse_args() -> Result<Option<Args>, String> {
+    let mut args = Args {
+        games: 1000,
+        seed: 0,
+        policies: vec!["greedy".to_string()],
+        colors: vec![6],
+        shots: vec![8],
+    };
+
+    let mut iter = std::env::args().skip(1);
+    while let Some(flag) = iter.next() {
+        if flag == "--help" || flag == "-h" {
+            return Ok(None);
+        }
+        let value = iter
+            .next()
+            .ok_or_else(|| format!("missing value for {flag}\n{USAGE}"))?;
+        let invalid = || format!("invalid value for {flag}: {value}\n{USAGE}");
+        match flag.as_str() {
+            "--games" => args.games = value.parse().map_err(|_| invalid())?,
+            "--seed" => args.seed = value.parse().map_err(|_| invalid())?,
+            "--policy" => {
+                args.policies = value.split(',').map(str::to_string).collect();
+                if !args.policies.iter().all(|p| policy_by_name(p).is_some()) {
+                    return Err(invalid());
+                }
+            }
+            "--colors" => {
+                args.colors = parse_list(&value)
+                    .filter(|c: &Vec<u8>| c.iter().all(|&n| (1..=6).contains(&n)))
+                    .ok_or_else(invalid)?
+            }
+            "--shots" => {
+                args.shots = parse_list(&value)
+                    .filter(|s: &Vec<u32>| s.iter().all(|&n| n > 0))
+                    .ok_or_else(invalid)?
+            }
+            _ => return Err(format!("unknown flag {flag}\n{USAGE}")),
+        }
+    }
+
+    Ok(Some(args))
+}
+
+fn main() {
+    let args = match parse_args() {
+        Ok(Some(args)) => args,
+        Ok(None) => {
+            println!("{USAGE}");
+            return;
+        }
+        Err(message) => {
+            eprintln!("{message}");
+            std::process::exit(2);
+        }
+    };
+
+    let mut configs = Vec::new();
+    for name in &args.policies {
+        for &colors in &args.colors {
+            for &shots_per_descent in &args.shots {
+                let config = SimConfig {
+                    colors,
+                    shots_per_descent,
+                    max_shots: MAX_SHOTS_PER_GAME,
+                };
+                configs.push((name.clone(), config));
+            }
+        }
+    }
+
+    // Configs play in parallel on a task pool, and report in order
+    let pool = TaskPool::new();
+    let reports = pool.scope(|scope| {
+        for (name, config) in configs {
+            let (games, seed) = (args.games, args.seed);
+            scope.spawn(async move {
+                let mut policy = policy_by_name(&name)?;
+                // Same seed per config so configs are compared on equal footing
+                let mut rng = StdRng::seed_from_u64(seed);
+                let results: Vec<GameResult> = (0..games)
+                    .map(|_| play_game(config, policy.as_mut(), &mut rng))
+                    .collect();
+                Some((policy.name(), config, results))
+            });
+        }
+    });
+    for (name, config, results) in reports.into_iter().flatten() {
+        report(name, config, &results);
+    }
+}
@@ -0,0 +1,75 @@
+//! Background compute - heavy work kept off the frame.
+//!
+//! [`SpawnCompute::spawn_compute`] runs a closure on Bevy's
+//! [`AsyncComputeTaskPool`] and sends what it returns as a message once it's
+//! done, so no system waits on it. Every job belongs to a state: leaving that
+//! state despawns the job, which cancels it, so a result never turns up for
+//! a screen that's already gone.
+//!
+//! Result messages are registered with [`AddComputeMessage`]. They're sent in
+//! `PreUpdate`, and pausable systems can miss a message while paused, so read
+//! them from a system that keeps running and store what you need.
+
+use bevy::{
+    prelude::*,
+    tasks::{AsyncComputeTaskPool, Task, futures::check_ready},
+};
+
+pub trait AddComputeMessage {
+    /// Register `M` as the result of background jobs: the message, and a
+    /// system that sends it as jobs finish.
+    fn add_compute_message<M: Message>(&mut self) -> &mut Self;
+}
+
+impl AddComputeMessage for App {
+    fn add_compute_message<M: Message>(&mut self) -> &mut Self {
+        self.add_message::<M>();
+        self.add_systems(PreUpdate, finish_compute_jobs::<M>);
+        self
+    }
+}
+
+pub trait SpawnCompute {
+    /// Run `job` in the background while `scope` is active, and send its
+    /// result (if any) as a message.
+    fn spawn_compute<M: Message, S: States>(
+        &mut self,
+        scope: S,
+        job: impl FnOnce() -> Option<M> + Send + 'static,
+    );
+}
+
+impl SpawnCompute for Commands<'_, '_> {
+    fn spawn_compute<M: Message, S: States>(
+        &mut self,
+        scope: S,
+        job: impl FnOnce() -> Option<M> + Send + 'static,
+    ) {
+        let task = AsyncComputeTaskPool::get().spawn(async move { job() });
+        self.spawn((
+            Name::new("Compute Job"),
+            ComputeJob(task),
+            DespawnOnExit(scope),
+        ));
+    }
+}
+
+/// A background job. Dropping it cancels the task.
+#[derive(Component)]
+struct ComputeJob<M: Message>(Task<Option<M>>);
+
+fn finish_compute_jobs<M: Message>(
+    mut commands: Commands,
+    mut jobs: Query<(Entity, &mut ComputeJob<M>)>,
+    mut results: MessageWriter<M>,
+) {
+    for (entity, mut job) in &mut jobs {
+        let Some(result) = check_ready(&mut job.0) else {
+            continue;
+        };
+        commands.entity(entity).despawn();
+        if let Some(result) = result {
+            results.write(result);
+        }
+    }
+}
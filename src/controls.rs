@@ -0,0 +1,292 @@
+//! Key bindings: which keys and gamepad buttons fire, swap, pause, and toggle
+//! the debug overlay.
+//!
+//! Keys can be rebound in the controls menu, and are saved to `controls.json`
+//! next to the other save files. Keys and buttons are saved by their variant
+//! names (`"Space"`, `"South"`), the same way input recordings keep them.
+//! The mouse, touch, and Escape work on top of whatever's bound, as do the
+//! fixed aim and debug keys below, so none of those can be bound.
+
+use bevy::{prelude::*, reflect::Enum};
+use serde::{Deserialize, Deserializer, Serialize, Serializer, de::Error as _, ser::Error as _};
+
+use crate::{
+    input_replay::{from_variant_name, variant_name},
+    save::{SaveFile, save_on_change},
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<InputBindings>();
+    app.register_type::<InputBindings>();
+
+    app.add_systems(Startup, load_input_bindings);
+    app.add_systems(
+        Update,
+        save_on_change::<InputBindings>.run_if(resource_changed::<InputBindings>),
+    );
+}
+
+/// Keys that turn the aim left while the mouse rests.
+pub const AIM_LEFT_KEYS: [KeyCode; 2] = [KeyCode::ArrowLeft, KeyCode::KeyA];
+/// Keys that turn the aim right while the mouse rests.
+pub const AIM_RIGHT_KEYS: [KeyCode; 2] = [KeyCode::ArrowRight, KeyCode::KeyD];
+/// Toggles the frame time overlay.
+pub const DIAGNOSTICS_KEY: KeyCode = KeyCode::F3;
+/// Saves a snapshot of the board.
+pub const QUICKSAVE_KEY: KeyCode = KeyCode::F5;
+/// Restores the last snapshot.
+pub const QUICKLOAD_KEY: KeyCode = KeyCode::F9;
+/// Fills the board for a stress test.
+pub const STRESS_TEST_KEY: KeyCode = KeyCode::F8;
+/// Shows or hides the gameplay message panel.
+pub const MESSAGE_PANEL_KEY: KeyCode = KeyCode::KeyM;
+/// Shows or hides the last few shots' paths.
+pub const SHOT_TRACE_KEY: KeyCode = KeyCode::KeyT;
+/// Clears the landing heatmap, while the debug overlay is up.
+pub const HEATMAP_RESET_KEY: KeyCode = KeyCode::Backspace;
+/// Exports the landing heatmap, while the debug overlay is up.
+pub const HEATMAP_EXPORT_KEY: KeyCode = KeyCode::KeyE;
+
+/// Whether `key` already does something fixed, and so can't be bound.
+pub fn is_reserved(key: KeyCode) -> bool {
+    key == KeyCode::Escape
+        || AIM_LEFT_KEYS.contains(&key)
+        || AIM_RIGHT_KEYS.contains(&key)
+        || [
+            DIAGNOSTICS_KEY,
+            QUICKSAVE_KEY,
+            QUICKLOAD_KEY,
+            STRESS_TEST_KEY,
+            MESSAGE_PANEL_KEY,
+            SHOT_TRACE_KEY,
+            HEATMAP_RESET_KEY,
+            HEATMAP_EXPORT_KEY,
+        ]
+        .contains(&key)
+}
+
+/// Something the player can do with a key or a button.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+pub enum InputAction {
+    Fire,
+    /// Trade the loaded snord for the next one.
+    Swap,
+    Pause,
+    DebugToggle,
+}
+
+impl InputAction {
+    /// Every action, in the order the controls menu lists them.
+    pub const ALL: [InputAction; 4] = [
+        InputAction::Fire,
+        InputAction::Swap,
+        InputAction::Pause,
+        InputAction::DebugToggle,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            InputAction::Fire => "Fire",
+            InputAction::Swap => "Swap",
+            InputAction::Pause => "Pause",
+            InputAction::DebugToggle => "Debug Overlay",
+        }
+    }
+}
+
+/// The key and gamepad button for one action.
+#[derive(Debug, Clone, Copy, PartialEq, Reflect, Serialize, Deserialize)]
+pub struct Binding {
+    #[serde(with = "by_variant_name")]
+    pub key: KeyCode,
+    #[serde(with = "by_variant_name")]
+    pub button: GamepadButton,
+}
+
+impl Binding {
+    const fn new(key: KeyCode, button: GamepadButton) -> Self {
+        Self { key, button }
+    }
+}
+
+/// Every action's binding, saved between sessions.
+#[derive(Resource, Debug, Clone, PartialEq, Reflect, Serialize, Deserialize)]
+#[reflect(Resource)]
+#[serde(default)]
+pub struct InputBindings {
+    pub fire: Binding,
+    pub swap: Binding,
+    pub pause: Binding,
+    pub debug_toggle: Binding,
+}
+
+impl Default for InputBindings {
+    fn default() -> Self {
+        Self {
+            fire: Binding::new(KeyCode::Space, GamepadButton::South),
+            swap: Binding::new(KeyCode::KeyS, GamepadButton::West),
+            pause: Binding::new(KeyCode::KeyP, GamepadButton::Start),
+            debug_toggle: Binding::new(DIAGNOSTICS_KEY, GamepadButton::Select),
+        }
+    }
+}
+
+impl SaveFile for InputBindings {
+    const FILE_NAME: &'static str = "controls.json";
+    const DESCRIPTION: &'static str = "key bindings";
+}
+
+impl InputBindings {
+    pub fn get(&self, action: InputAction) -> Binding {
+        match action {
+            InputAction::Fire => self.fire,
+            InputAction::Swap => self.swap,
+            InputAction::Pause => self.pause,
+            InputAction::DebugToggle => self.debug_toggle,
+        }
+    }
+
+    fn get_mut(&mut self, action: InputAction) -> &mut Binding {
+        match action {
+            InputAction::Fire => &mut self.fire,
+            InputAction::Swap => &mut self.swap,
+            InputAction::Pause => &mut self.pause,
+            InputAction::DebugToggle => &mut self.debug_toggle,
+        }
+    }
+
+    /// Bind `key` to `action`. An action that already had `key` takes
+    /// `action`'s old key, so no key ever does two things.
+    ///
+    /// Returns `false`, changing nothing, if that would bind a reserved key
+    /// (see [`is_reserved`]) to an action it isn't the default for.
+    pub fn rebind_key(&mut self, action: InputAction, key: KeyCode) -> bool {
+        let allowed = |action: InputAction, key: KeyCode| {
+            !is_reserved(key) || InputBindings::default().get(action).key == key
+        };
+        let old = self.get(action).key;
+        let other = InputAction::ALL
+            .into_iter()
+            .find(|&other| other != action && self.get(other).key == key);
+        if !allowed(action, key) || other.is_some_and(|other| !allowed(other, old)) {
+            return false;
+        }
+
+        if let Some(other) = other {
+            self.get_mut(other).key = old;
+        }
+        self.get_mut(action).key = key;
+        true
+    }
+
+    /// Whether `action`'s key or any gamepad's button for it was just pressed.
+    pub fn just_pressed(
+        &self,
+        action: InputAction,
+        keyboard: &ButtonInput<KeyCode>,
+        gamepads: &Query<&Gamepad>,
+    ) -> bool {
+        let binding = self.get(action);
+        keyboard.just_pressed(binding.key)
+            || gamepads
+                .iter()
+                .any(|gamepad| gamepad.just_pressed(binding.button))
+    }
+}
+
+/// Run condition: `action`'s key or button was just pressed.
+pub fn action_just_pressed(
+    action: InputAction,
+) -> impl FnMut(Res<InputBindings>, Res<ButtonInput<KeyCode>>, Query<&Gamepad>) -> bool + Clone {
+    move |bindings: Res<InputBindings>,
+          keyboard: Res<ButtonInput<KeyCode>>,
+          gamepads: Query<&Gamepad>| bindings.just_pressed(action, &keyboard, &gamepads)
+}
+
+/// The name of a key as the controls menu shows it, e.g. `"P"` for
+/// `KeyCode::KeyP`.
+pub fn key_label(key: KeyCode) -> String {
+    let name = variant_name(&key).unwrap_or_else(|| "?".to_string());
+    match name
+        .strip_prefix("Key")
+        .or_else(|| name.strip_prefix("Digit"))
+    {
+        Some(short) => short.to_string(),
+        None => name,
+    }
+}
+
+/// Saves unit enum variants (like keys) by name.
+mod by_variant_name {
+    use super::*;
+
+    pub fn serialize<T: Enum, S: Serializer>(value: &T, serializer: S) -> Result<S::Ok, S::Error> {
+        variant_name(value)
+            .ok_or_else(|| S::Error::custom("only unit variants can be saved"))?
+            .serialize(serializer)
+    }
+
+    pub fn deserialize<'de, T: FromReflect, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<T, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        from_variant_name(&name).ok_or_else(|| D::Error::custom(format!("unknown input {name:?}")))
+    }
+}
+
+fn load_input_bindings(mut bindings: ResMut<InputBindings>) {
+    *bindings = InputBindings::load();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::save::{decode, encode};
+
+    #[test]
+    fn bindings_round_trip_by_name() {
+        let mut bindings = InputBindings::default();
+        assert!(bindings.rebind_key(InputAction::Fire, KeyCode::Enter));
+        let json = encode(&bindings).unwrap();
+        assert!(json.contains("\"Enter\""));
+        assert_eq!(decode::<InputBindings>(&json), Ok(bindings));
+    }
+
+    #[test]
+    fn rebinding_a_taken_key_swaps_the_two() {
+        let mut bindings = InputBindings::default();
+        assert!(bindings.rebind_key(InputAction::Swap, KeyCode::Space));
+        assert_eq!(bindings.swap.key, KeyCode::Space);
+        assert_eq!(bindings.fire.key, KeyCode::KeyS);
+    }
+
+    #[test]
+    fn reserved_keys_cannot_be_bound() {
+        let mut bindings = InputBindings::default();
+        for key in [
+            KeyCode::Escape,
+            KeyCode::ArrowLeft,
+            KeyCode::KeyD,
+            QUICKSAVE_KEY,
+            DIAGNOSTICS_KEY,
+            MESSAGE_PANEL_KEY,
+        ] {
+            assert!(!bindings.rebind_key(InputAction::Fire, key), "{key:?}");
+        }
+        assert_eq!(bindings, InputBindings::default());
+
+        // The debug toggle can go back to its default, but not by handing
+        // that key to the action it swaps with
+        assert!(bindings.rebind_key(InputAction::DebugToggle, KeyCode::KeyG));
+        assert!(bindings.rebind_key(InputAction::DebugToggle, DIAGNOSTICS_KEY));
+        assert!(!bindings.rebind_key(InputAction::DebugToggle, KeyCode::Space));
+        assert_eq!(bindings, InputBindings::default());
+    }
+
+    #[test]
+    fn keys_are_labeled_without_prefixes() {
+        assert_eq!(key_label(KeyCode::KeyP), "P");
+        assert_eq!(key_label(KeyCode::Digit4), "4");
+        assert_eq!(key_label(KeyCode::Space), "Space");
+    }
+}
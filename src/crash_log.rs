@@ -0,0 +1,241 @@
+//! Error reporting - a panic hook and a log of recent warnings and errors.
+//!
+//! Warnings and errors are kept in a small in-memory ring buffer by a custom
+//! tracing layer. If the game panics, the panic message and the buffer are
+//! written to `crash.log` in the user's data directory. On the next launch the
+//! crash log is kept as `last_crash.log` and bundled into problem reports.
+
+use std::{collections::VecDeque, fs, path::PathBuf, sync::Mutex};
+
+use bevy::{
+    log::{
+        BoxedLayer, Level,
+        tracing::{
+            Event, Subscriber,
+            field::{Field, Visit},
+        },
+        tracing_subscriber::{Layer, layer::Context},
+    },
+    prelude::*,
+};
+
+use crate::{save, theme::toast::ShowToast};
+
+pub(super) fn plugin(app: &mut App) {
+    install_panic_hook();
+    app.add_systems(Startup, check_last_session_crash);
+}
+
+/// Maximum number of warning/error lines kept in memory.
+const MAX_RECENT_LOGS: usize = 200;
+
+/// Recent warning and error lines, oldest first.
+static RECENT_LOGS: Mutex<VecDeque<String>> = Mutex::new(VecDeque::new());
+
+/// Returns a tracing layer that records warnings and errors for crash logs.
+/// Passed to [`LogPlugin::custom_layer`](bevy::log::LogPlugin::custom_layer).
+pub fn log_layer(_app: &mut App) -> Option<BoxedLayer> {
+    Some(Box::new(RecentLogLayer))
+}
+
+/// Tracing layer that copies warnings and errors into [`RECENT_LOGS`].
+struct RecentLogLayer;
+
+impl<S: Subscriber> Layer<S> for RecentLogLayer {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let metadata = event.metadata();
+        // More verbose levels compare greater than WARN
+        if *metadata.level() > Level::WARN {
+            return;
+        }
+
+        let mut visitor = MessageVisitor(String::new());
+        event.record(&mut visitor);
+        push_recent_log(format!(
+            "{} {}: {}",
+            metadata.level(),
+            metadata.target(),
+            visitor.0
+        ));
+    }
+}
+
+/// Extracts the `message` field of a tracing event.
+struct MessageVisitor(String);
+
+impl Visit for MessageVisitor {
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        if field.name() == "message" {
+            self.0 = format!("{value:?}");
+        }
+    }
+}
+
+fn push_recent_log(line: String) {
+    let Ok(mut logs) = RECENT_LOGS.lock() else {
+        return;
+    };
+    if logs.len() >= MAX_RECENT_LOGS {
+        logs.pop_front();
+    }
+    logs.push_back(line);
+}
+
+/// Snapshot of the recent warning and error lines.
+pub fn recent_logs() -> Vec<String> {
+    RECENT_LOGS
+        .lock()
+        .map(|logs| logs.iter().cloned().collect())
+        .unwrap_or_default()
+}
+
+/// Get the directory for crash logs and reports.
+fn log_dir() -> Option<PathBuf> {
+    save::data_dir()
+}
+
+/// Chain a panic hook that writes `crash.log` before the default handler runs.
+fn install_panic_hook() {
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        write_crash_log(&info.to_string());
+        previous(info);
+    }));
+}
+
+fn write_crash_log(panic_message: &str) {
+    let Some(dir) = log_dir() else {
+        return;
+    };
+    let mut contents = format!(
+        "snord {} crashed\n\n{}\n\nRecent warnings and errors:\n",
+        env!("CARGO_PKG_VERSION"),
+        panic_message
+    );
+    for line in recent_logs() {
+        contents.push_str(&line);
+        contents.push('\n');
+    }
+    // Best effort: we're already panicking, so there's nobody to report failures to
+    let _ = fs::create_dir_all(&dir);
+    let _ = fs::write(dir.join("crash.log"), contents);
+}
+
+/// If the last session crashed, keep its log for problem reports and let the player know.
+fn check_last_session_crash(mut toasts: MessageWriter<ShowToast>) {
+    let Some(dir) = log_dir() else {
+        return;
+    };
+    let crash_log = dir.join("crash.log");
+    if !crash_log.exists() {
+        return;
+    }
+
+    match fs::rename(&crash_log, dir.join("last_crash.log")) {
+        Ok(()) => {
+            warn!("Previous session crashed, log kept at {:?}", dir);
+            toasts.write(ShowToast::warning(
+                "Snord crashed last time. Use Report a Problem to send us the log.",
+            ));
+        }
+        Err(e) => warn!("Failed to keep previous crash log: {}", e),
+    }
+}
+
+/// File name problem reports are written (or downloaded) as.
+const REPORT_FILE: &str = "problem_report.txt";
+
+/// Where a problem report ended up.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReportSaved {
+    /// Written to this file.
+    File(PathBuf),
+    /// Handed to the browser as a download.
+    #[cfg(target_arch = "wasm32")]
+    Download,
+}
+
+/// Bundle recent logs, the last crash log, and the given context (settings,
+/// seed, etc.) into a problem report.
+///
+/// Returns where the report was saved (downloaded, on the web build), or None
+/// if it could only be printed to the log.
+pub fn write_problem_report(context: &[(&str, String)]) -> Option<ReportSaved> {
+    let mut report = format!(
+        "snord {} problem report\nos: {}\n\n",
+        env!("CARGO_PKG_VERSION"),
+        std::env::consts::OS
+    );
+    for (key, value) in context {
+        report.push_str(&format!("{key}: {value}\n"));
+    }
+
+    report.push_str("\nRecent warnings and errors:\n");
+    for line in recent_logs() {
+        report.push_str(&line);
+        report.push('\n');
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    match download_report(&report) {
+        Ok(()) => {
+            info!("Downloaded problem report as {}", REPORT_FILE);
+            return Some(ReportSaved::Download);
+        }
+        Err(e) => warn!("Failed to download problem report: {}", e),
+    }
+
+    let Some(dir) = log_dir() else {
+        info!("Problem report:\n{}", report);
+        return None;
+    };
+
+    if let Ok(last_crash) = fs::read_to_string(dir.join("last_crash.log")) {
+        report.push_str("\nLast crash:\n");
+        report.push_str(&last_crash);
+    }
+
+    if let Err(e) = fs::create_dir_all(&dir) {
+        warn!("Failed to create report directory: {}", e);
+        return None;
+    }
+    let path = dir.join(REPORT_FILE);
+    match fs::write(&path, report) {
+        Ok(()) => {
+            info!("Wrote problem report to {:?}", path);
+            Some(ReportSaved::File(path))
+        }
+        Err(e) => {
+            warn!("Failed to write problem report: {}", e);
+            None
+        }
+    }
+}
+
+/// Have the browser download `report` as [`REPORT_FILE`], through a Blob URL
+/// on a throwaway link.
+#[cfg(target_arch = "wasm32")]
+fn download_report(report: &str) -> Result<(), String> {
+    use wasm_bindgen::{JsCast, JsValue};
+    use web_sys::{Blob, BlobPropertyBag, HtmlAnchorElement, Url};
+
+    let document = web_sys::window()
+        .and_then(|window| window.document())
+        .ok_or("no document")?;
+    let options = BlobPropertyBag::new();
+    options.set_type("text/plain");
+    let parts = js_sys::Array::of1(&JsValue::from_str(report));
+    let blob =
+        Blob::new_with_str_sequence_and_options(&parts, &options).map_err(|e| format!("{e:?}"))?;
+    let url = Url::create_object_url_with_blob(&blob).map_err(|e| format!("{e:?}"))?;
+
+    let link: HtmlAnchorElement = document
+        .create_element("a")
+        .map_err(|e| format!("{e:?}"))?
+        .dyn_into()
+        .map_err(|_| "not a link")?;
+    link.set_href(&url);
+    link.set_download(REPORT_FILE);
+    link.click();
+    Url::revoke_object_url(&url).map_err(|e| format!("{e:?}"))
+}
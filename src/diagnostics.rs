@@ -0,0 +1,82 @@
+//! A performance overlay, toggled with F3.
+//!
+//! Shows frame rate and how much audio and texture data is resident, to keep
+//! an eye on memory on the web build.
+
+use bevy::{
+    diagnostic::{DiagnosticsStore, FrameTimeDiagnosticsPlugin},
+    input::common_conditions::input_just_pressed,
+    prelude::*,
+};
+
+use crate::{
+    audio::{AUDIO_MEMORY, AUDIO_SOURCES},
+    controls::DIAGNOSTICS_KEY,
+    textures::{TEXTURE_COUNT, TEXTURE_MEMORY},
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_plugins(FrameTimeDiagnosticsPlugin::default());
+
+    app.add_systems(
+        Update,
+        (
+            toggle_overlay.run_if(input_just_pressed(DIAGNOSTICS_KEY)),
+            update_overlay,
+        )
+            .chain(),
+    );
+}
+
+/// Marker for the overlay text.
+#[derive(Component)]
+struct DiagnosticsOverlay;
+
+fn toggle_overlay(mut commands: Commands, overlay_query: Query<Entity, With<DiagnosticsOverlay>>) {
+    if let Ok(entity) = overlay_query.single() {
+        commands.entity(entity).despawn();
+        return;
+    }
+
+    commands.spawn((
+        Name::new("Diagnostics Overlay"),
+        DiagnosticsOverlay,
+        Text::default(),
+        TextFont::from_font_size(14.0),
+        TextColor(Color::WHITE),
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(8.0),
+            left: Val::Px(8.0),
+            padding: UiRect::all(Val::Px(4.0)),
+            ..default()
+        },
+        BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.6)),
+        GlobalZIndex(10),
+        Pickable::IGNORE,
+    ));
+}
+
+fn update_overlay(
+    diagnostics: Res<DiagnosticsStore>,
+    mut overlay_query: Query<&mut Text, With<DiagnosticsOverlay>>,
+) {
+    let Ok(mut text) = overlay_query.single_mut() else {
+        return;
+    };
+
+    let value = |path| {
+        diagnostics
+            .get(path)
+            .and_then(|diagnostic| diagnostic.smoothed())
+            .unwrap_or_default()
+    };
+    text.0 = format!(
+        "FPS: {:.0}\nAudio: {:.0} KiB in {:.0} sources\nTextures: {:.0} KiB in {:.0} images",
+        value(&FrameTimeDiagnosticsPlugin::FPS),
+        value(&AUDIO_MEMORY),
+        value(&AUDIO_SOURCES),
+        value(&TEXTURE_MEMORY),
+        value(&TEXTURE_COUNT),
+    );
+}
@@ -0,0 +1,377 @@
+//! Display settings: vsync, an optional frame rate cap, graphics quality, and
+//! seasonal content (see `seasons`).
+//!
+//! Vsync is applied through the primary window's present mode. The frame cap
+//! is a frame pacing system that sleeps off whatever is left of each frame's
+//! budget, so it only works on native builds; browsers already pace frames
+//! to the display. All of them are changed in the settings menu and saved to
+//! `display.json` next to the other save files.
+//!
+//! The quality preset scales back effects that cost fill rate on weak GPUs:
+//! particles, trails, idle animations, and the backdrop. Left on auto, it's
+//! picked from the render backend, so a browser stuck on WebGL2 starts on
+//! the low preset.
+//!
+//! Power saver is for long sessions on a laptop: it caps the frame rate at
+//! 30 and holds quality at the low preset, whatever the other two are set
+//! to. Native builds on Linux check the battery now and then and suggest it
+//! once a session when running unplugged; other platforms don't report
+//! their power source, so it's never suggested there.
+
+use bevy::{
+    prelude::*,
+    render::{render_resource::WgpuAdapterInfo, renderer::RenderAdapterInfo},
+    window::{PresentMode, PrimaryWindow},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    save::{SaveFile, save_on_change},
+    seasons::SeasonalContent,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<DisplaySettings>();
+    app.register_type::<DisplaySettings>();
+    app.init_resource::<GraphicsQuality>();
+    app.register_type::<GraphicsQuality>();
+
+    app.add_systems(Startup, load_display_settings);
+    app.add_systems(
+        Update,
+        (save_on_change::<DisplaySettings>, apply_present_mode)
+            .run_if(resource_changed::<DisplaySettings>),
+    );
+    app.add_systems(
+        Update,
+        resolve_quality
+            .run_if(resource_changed::<DisplaySettings>.or(resource_added::<RenderAdapterInfo>)),
+    );
+
+    #[cfg(not(target_arch = "wasm32"))]
+    app.add_systems(
+        Last,
+        pace_frames.run_if(any_with_component::<PrimaryWindow>.and(frame_limited)),
+    );
+    #[cfg(not(target_arch = "wasm32"))]
+    app.add_systems(
+        Update,
+        suggest_power_saver.run_if(bevy::time::common_conditions::on_real_timer(
+            BATTERY_CHECK_INTERVAL,
+        )),
+    );
+}
+
+/// Frame rate cap while power saver is on.
+const POWER_SAVER_FPS: u32 = 30;
+
+/// How often to check whether the machine is running on battery.
+#[cfg(not(target_arch = "wasm32"))]
+const BATTERY_CHECK_INTERVAL: std::time::Duration = std::time::Duration::from_secs(60);
+
+/// Frame rate caps offered in the settings menu.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Reflect, Serialize, Deserialize)]
+pub enum FrameLimit {
+    Fps30,
+    Fps60,
+    Fps120,
+    #[default]
+    Uncapped,
+}
+
+impl FrameLimit {
+    /// The next option, wrapping around (for a cycling settings button).
+    pub fn next(self) -> Self {
+        match self {
+            FrameLimit::Fps30 => FrameLimit::Fps60,
+            FrameLimit::Fps60 => FrameLimit::Fps120,
+            FrameLimit::Fps120 => FrameLimit::Uncapped,
+            FrameLimit::Uncapped => FrameLimit::Fps30,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            FrameLimit::Fps30 => "30",
+            FrameLimit::Fps60 => "60",
+            FrameLimit::Fps120 => "120",
+            FrameLimit::Uncapped => "Uncapped",
+        }
+    }
+
+    /// Frames per second, or None if uncapped.
+    #[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+    pub fn fps(self) -> Option<u32> {
+        match self {
+            FrameLimit::Fps30 => Some(30),
+            FrameLimit::Fps60 => Some(60),
+            FrameLimit::Fps120 => Some(120),
+            FrameLimit::Uncapped => None,
+        }
+    }
+}
+
+/// Graphics quality presets offered in the settings menu.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Reflect, Serialize, Deserialize)]
+pub enum QualityPreset {
+    Low,
+    Medium,
+    #[default]
+    High,
+}
+
+impl QualityPreset {
+    pub fn label(self) -> &'static str {
+        match self {
+            QualityPreset::Low => "Low",
+            QualityPreset::Medium => "Medium",
+            QualityPreset::High => "High",
+        }
+    }
+
+    /// Share of the usual particles (confetti, snord rain) to spawn.
+    pub fn particles(self) -> f32 {
+        match self {
+            QualityPreset::Low => 0.0,
+            QualityPreset::Medium => 0.5,
+            QualityPreset::High => 1.0,
+        }
+    }
+
+    /// Whether shot trails are drawn.
+    pub fn trails(self) -> bool {
+        self != QualityPreset::Low
+    }
+
+    /// Whether purely decorative loops (mood wiggles, portal swirls) run.
+    pub fn idle_animations(self) -> bool {
+        self == QualityPreset::High
+    }
+
+    /// Share of the background doodles to spawn.
+    pub fn backdrop(self) -> f32 {
+        match self {
+            QualityPreset::Low => 0.0,
+            QualityPreset::Medium => 0.4,
+            QualityPreset::High => 1.0,
+        }
+    }
+
+    /// The preset to start on for this render adapter. Browsers on WebGL2
+    /// are usually on integrated GPUs with little to spare.
+    fn detect(adapter: Option<&WgpuAdapterInfo>) -> Self {
+        let Some(adapter) = adapter else {
+            // Headless, nothing is drawn anyway
+            return QualityPreset::High;
+        };
+        match adapter.backend.to_str() {
+            "gl" if cfg!(target_arch = "wasm32") => QualityPreset::Low,
+            "gl" | "webgpu" => QualityPreset::Medium,
+            _ => QualityPreset::High,
+        }
+    }
+}
+
+/// The quality preset in use: the one picked in settings, or the detected one
+/// on auto.
+#[derive(Resource, Debug, Clone, Copy, Default, PartialEq, Eq, Reflect)]
+#[reflect(Resource)]
+pub struct GraphicsQuality(pub QualityPreset);
+
+/// Run condition: particles are spawned at all.
+pub fn particles_enabled(quality: Res<GraphicsQuality>) -> bool {
+    quality.0.particles() > 0.0
+}
+
+/// Run condition: shot trails are drawn.
+pub fn trails_enabled(quality: Res<GraphicsQuality>) -> bool {
+    quality.0.trails()
+}
+
+/// Run condition: idle animations are played.
+pub fn idle_animations_enabled(quality: Res<GraphicsQuality>) -> bool {
+    quality.0.idle_animations()
+}
+
+/// Display settings, saved between sessions.
+#[derive(Resource, Debug, Clone, PartialEq, Reflect, Serialize, Deserialize)]
+#[reflect(Resource)]
+pub struct DisplaySettings {
+    pub vsync: bool,
+    #[serde(default)]
+    pub frame_limit: FrameLimit,
+    /// The chosen quality preset, or None to pick one from the render backend.
+    #[serde(default)]
+    pub quality: Option<QualityPreset>,
+    #[serde(default)]
+    pub seasonal: SeasonalContent,
+    /// Caps the frame rate and holds quality at low, to save battery.
+    #[serde(default)]
+    pub power_saver: bool,
+}
+
+impl Default for DisplaySettings {
+    fn default() -> Self {
+        Self {
+            vsync: true,
+            frame_limit: FrameLimit::default(),
+            quality: None,
+            seasonal: SeasonalContent::default(),
+            power_saver: false,
+        }
+    }
+}
+
+impl SaveFile for DisplaySettings {
+    const FILE_NAME: &'static str = "display.json";
+    const DESCRIPTION: &'static str = "display settings";
+}
+
+impl DisplaySettings {
+    /// Step to the next quality option: auto, then each preset, wrapping
+    /// around (for a cycling settings button).
+    pub fn cycle_quality(&mut self) {
+        self.quality = match self.quality {
+            None => Some(QualityPreset::Low),
+            Some(QualityPreset::Low) => Some(QualityPreset::Medium),
+            Some(QualityPreset::Medium) => Some(QualityPreset::High),
+            Some(QualityPreset::High) => None,
+        };
+    }
+
+    /// The frame rate cap in effect, with power saver's on top of the
+    /// chosen one.
+    #[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+    pub fn frame_cap(&self) -> Option<u32> {
+        let fps = self.frame_limit.fps();
+        if self.power_saver {
+            Some(fps.map_or(POWER_SAVER_FPS, |fps| fps.min(POWER_SAVER_FPS)))
+        } else {
+            fps
+        }
+    }
+
+    pub fn quality_label(&self) -> &'static str {
+        self.quality.map_or("Auto", QualityPreset::label)
+    }
+
+    fn present_mode(&self) -> PresentMode {
+        if self.vsync {
+            PresentMode::AutoVsync
+        } else {
+            PresentMode::AutoNoVsync
+        }
+    }
+}
+
+fn load_display_settings(mut settings: ResMut<DisplaySettings>) {
+    *settings = DisplaySettings::load();
+}
+
+fn apply_present_mode(
+    settings: Res<DisplaySettings>,
+    mut window: Query<&mut Window, With<PrimaryWindow>>,
+) {
+    let Ok(mut window) = window.single_mut() else {
+        return;
+    };
+    let present_mode = settings.present_mode();
+    if window.present_mode != present_mode {
+        info!("Present mode: {:?}", present_mode);
+        window.present_mode = present_mode;
+    }
+}
+
+fn resolve_quality(
+    settings: Res<DisplaySettings>,
+    adapter: Option<Res<RenderAdapterInfo>>,
+    mut quality: ResMut<GraphicsQuality>,
+) {
+    let preset = if settings.power_saver {
+        QualityPreset::Low
+    } else {
+        settings
+            .quality
+            .unwrap_or_else(|| QualityPreset::detect(adapter.as_deref().map(|info| &***info)))
+    };
+    if quality.0 != preset {
+        info!("Graphics quality: {}", preset.label());
+        quality.0 = preset;
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn frame_limited(settings: Res<DisplaySettings>) -> bool {
+    settings.frame_cap().is_some()
+}
+
+/// Sleep until this frame has taken its share of a second at the frame cap.
+#[cfg(not(target_arch = "wasm32"))]
+fn pace_frames(settings: Res<DisplaySettings>, mut frame_start: Local<Option<std::time::Instant>>) {
+    use std::time::{Duration, Instant};
+
+    let Some(fps) = settings.frame_cap() else {
+        return;
+    };
+    let budget = Duration::from_secs_f64(1.0 / fps as f64);
+    if let Some(start) = *frame_start {
+        let spent = start.elapsed();
+        if spent < budget {
+            std::thread::sleep(budget - spent);
+        }
+    }
+    *frame_start = Some(Instant::now());
+}
+
+/// Suggest power saver, once a session, when running on battery.
+#[cfg(not(target_arch = "wasm32"))]
+fn suggest_power_saver(
+    settings: Res<DisplaySettings>,
+    mut suggested: Local<bool>,
+    mut toasts: MessageWriter<crate::theme::toast::ShowToast>,
+) {
+    if *suggested || settings.power_saver || !on_battery() {
+        return;
+    }
+    *suggested = true;
+    info!("Running on battery, suggesting power saver");
+    toasts.write(crate::theme::toast::ShowToast::info(
+        "On battery? Power Saver in the settings menu makes it last longer",
+    ));
+}
+
+/// Whether a battery is discharging, from the kernel's power supply list.
+#[cfg(target_os = "linux")]
+fn on_battery() -> bool {
+    let Ok(supplies) = std::fs::read_dir("/sys/class/power_supply") else {
+        return false;
+    };
+    supplies.flatten().any(|supply| {
+        let read = |name: &str| std::fs::read_to_string(supply.path().join(name));
+        read("type").is_ok_and(|kind| kind.trim() == "Battery")
+            && read("status").is_ok_and(|status| status.trim() == "Discharging")
+    })
+}
+
+/// Other platforms don't report their power source.
+#[cfg(all(not(target_arch = "wasm32"), not(target_os = "linux")))]
+fn on_battery() -> bool {
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn power_saver_caps_the_frame_rate_at_30() {
+        let mut settings = DisplaySettings::default();
+        assert_eq!(settings.frame_cap(), None);
+        settings.power_saver = true;
+        assert_eq!(settings.frame_cap(), Some(30));
+        settings.frame_limit = FrameLimit::Fps120;
+        assert_eq!(settings.frame_cap(), Some(30));
+        settings.power_saver = false;
+        assert_eq!(settings.frame_cap(), Some(120));
+    }
+}
@@ -0,0 +1,108 @@
+//! Feedback from playtesters, written in the settings menu.
+//!
+//! Each note (a subject and a message) is stamped with the game version and
+//! the seed of the run in play or last played, then appended to a local JSON
+//! file. If an endpoint is configured in that file, the note is also posted
+//! there, the same way telemetry uploads run summaries.
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{save::SaveFile, upload};
+
+/// Longest subject, in characters.
+pub const MAX_SUBJECT_CHARS: usize = 80;
+
+/// Longest message, in characters.
+pub const MAX_MESSAGE_CHARS: usize = 1000;
+
+/// Maximum number of notes kept in the local feedback file.
+const MAX_FEEDBACK_RECORDS: usize = 100;
+
+/// A note from a player.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Feedback {
+    pub game_version: String,
+    /// Seed of the run the player was in, or last played.
+    pub seed: Option<u64>,
+    pub subject: String,
+    pub message: String,
+}
+
+impl Feedback {
+    pub fn new(subject: &str, message: &str, seed: Option<u64>) -> Self {
+        Self {
+            game_version: env!("CARGO_PKG_VERSION").to_string(),
+            seed,
+            subject: subject.trim().to_string(),
+            message: message.trim().to_string(),
+        }
+    }
+
+    /// Whether there's nothing written.
+    pub fn is_empty(&self) -> bool {
+        self.subject.is_empty() && self.message.is_empty()
+    }
+}
+
+/// Contents of the local feedback file.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct FeedbackLog {
+    /// Optional URL that notes are posted to as JSON.
+    #[serde(default)]
+    pub endpoint: Option<String>,
+    #[serde(default)]
+    pub notes: Vec<Feedback>,
+}
+
+impl SaveFile for FeedbackLog {
+    const FILE_NAME: &'static str = "feedback.json";
+    const DESCRIPTION: &'static str = "feedback";
+}
+
+impl FeedbackLog {
+    /// Add a note, dropping the oldest once the log is full.
+    fn push(&mut self, feedback: Feedback) {
+        self.notes.push(feedback);
+        if self.notes.len() > MAX_FEEDBACK_RECORDS {
+            let excess = self.notes.len() - MAX_FEEDBACK_RECORDS;
+            self.notes.drain(..excess);
+        }
+    }
+}
+
+/// Keep a note in the feedback file, and start posting it if an endpoint is
+/// set.
+///
+/// Returns whether an upload was started. It may still fail; that's only
+/// logged.
+pub fn send(feedback: Feedback) -> bool {
+    let mut log = FeedbackLog::load();
+    let sending = log
+        .endpoint
+        .clone()
+        .is_some_and(|endpoint| upload::post_json(endpoint, &feedback, "feedback"));
+    log.push(feedback);
+    log.save();
+    sending
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn notes_are_trimmed_and_the_log_keeps_the_latest() {
+        let feedback = Feedback::new("  Stuck shot ", "It hung on the wall.\n", Some(7));
+        assert_eq!(feedback.subject, "Stuck shot");
+        assert_eq!(feedback.message, "It hung on the wall.");
+        assert!(Feedback::new(" ", "\n", None).is_empty());
+
+        let mut log = FeedbackLog::default();
+        for i in 0..MAX_FEEDBACK_RECORDS + 3 {
+            log.push(Feedback::new(&i.to_string(), "", None));
+        }
+        assert_eq!(log.notes.len(), MAX_FEEDBACK_RECORDS);
+        assert_eq!(log.notes[0].subject, "3");
+    }
+}
@@ -0,0 +1,142 @@
+//! Autosave of the run in progress, so a crash never costs more than a level.
+//!
+//! Every descent writes a [`GameSnapshot`] of the run, along with its
+//! [`GameMode`], to `autosave.json`. Ending or leaving the run clears the
+//! file, so one is only left behind by a game that closed mid-run. The
+//! title screen offers to resume it: the mode is put back, the board goes
+//! through loading as usual, and the snapshot replaces it once gameplay
+//! starts.
+//!
+//! Campaign levels and drills aren't autosaved, since the snapshot doesn't
+//! carry their goals. Nor are weekly challenge runs, so each one is played
+//! in one sitting.
+//!
+//! Closing the window mid-run asks first (see `menus/quit.rs`), and can save
+//! the run as it is right then with [`save_run`].
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    campaign::campaign_active,
+    drills::drill_active,
+    handicap::{Handicap, RunHandicap},
+    mode::GameMode,
+    replay::ReplayLog,
+    snapshot::GameSnapshot,
+    state::{GameEnded, TriggerDescent},
+    weekly::challenge_active,
+};
+use crate::{save::SaveFile, screens::Screen};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<Autosave>();
+    app.init_resource::<PendingResume>();
+
+    app.add_systems(Startup, load_autosave);
+    app.add_systems(OnExit(Screen::Gameplay), clear_autosave);
+    app.add_systems(
+        Update,
+        (
+            resume_run.run_if(resume_pending),
+            clear_autosave.run_if(on_message::<GameEnded>),
+        )
+            .run_if(in_state(Screen::Gameplay)),
+    );
+    // After the descent's new row has been spawned
+    app.add_systems(
+        PostUpdate,
+        save_run.run_if(
+            in_state(Screen::Gameplay)
+                .and(on_message::<TriggerDescent>)
+                .and(not(campaign_active))
+                .and(not(drill_active))
+                .and(not(challenge_active)),
+        ),
+    );
+}
+
+/// The last unfinished run, mirrored in `autosave.json`.
+#[derive(Resource, Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Autosave {
+    pub run: Option<SavedRun>,
+}
+
+impl SaveFile for Autosave {
+    const FILE_NAME: &'static str = "autosave.json";
+    const DESCRIPTION: &'static str = "autosave";
+}
+
+impl Autosave {
+    /// Forget the saved run, if there is one.
+    pub fn clear(&mut self) {
+        if self.run.take().is_some() {
+            self.save();
+        }
+    }
+}
+
+/// A run as it was at its last descent.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SavedRun {
+    pub mode: GameMode,
+    #[serde(default)]
+    pub handicap: Handicap,
+    pub snapshot: GameSnapshot,
+}
+
+/// A run waiting for gameplay to start so its snapshot can replace the new
+/// board.
+#[derive(Resource, Debug, Default)]
+pub struct PendingResume(pub Option<SavedRun>);
+
+impl PendingResume {
+    /// Queue `run` to be resumed, and put its mode back for the board that
+    /// loads first.
+    pub fn resume(&mut self, run: &SavedRun, mode: &mut GameMode) {
+        *mode = run.mode;
+        self.0 = Some(run.clone());
+    }
+}
+
+fn load_autosave(mut autosave: ResMut<Autosave>) {
+    *autosave = Autosave::load();
+}
+
+fn resume_pending(pending: Res<PendingResume>) -> bool {
+    pending.0.is_some()
+}
+
+/// Replace the new board with the pending snapshot, and keep it autosaved
+/// until the run's next descent.
+fn resume_run(world: &mut World) {
+    let Some(run) = world.resource_mut::<PendingResume>().0.take() else {
+        return;
+    };
+    info!(
+        "Resuming autosaved run at level {}",
+        run.snapshot.level.level
+    );
+    run.snapshot.restore(world);
+    // The board no longer comes from the run seed, so it can't be replayed
+    world.resource_mut::<ReplayLog>().seed = None;
+    let mut autosave = world.resource_mut::<Autosave>();
+    autosave.run = Some(run);
+    autosave.save();
+}
+
+/// Save the run in play to `autosave.json`.
+pub fn save_run(world: &mut World) {
+    let run = SavedRun {
+        mode: *world.resource::<GameMode>(),
+        handicap: world.resource::<RunHandicap>().handicap,
+        snapshot: GameSnapshot::capture(world),
+    };
+    let mut autosave = world.resource_mut::<Autosave>();
+    autosave.run = Some(run);
+    autosave.save();
+}
+
+fn clear_autosave(mut autosave: ResMut<Autosave>) {
+    autosave.clear();
+}
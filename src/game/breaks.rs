@@ -0,0 +1,94 @@
+//! Break reminders for long sessions.
+//!
+//! [`PlayTime`] counts unpaused gameplay time across the whole session, runs
+//! and restarts included. Once it passes the interval picked in the settings
+//! menu ([`BreakReminderSettings`]), a toast suggests a break and the count
+//! starts over.
+
+use bevy::prelude::*;
+
+use crate::{PausableSystems, screens::Screen, theme::toast::ShowToast};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<BreakReminderSettings>();
+    app.init_resource::<PlayTime>();
+    app.register_type::<BreakReminderSettings>();
+
+    app.add_systems(
+        Update,
+        remind_to_take_breaks
+            .in_set(PausableSystems)
+            .run_if(in_state(Screen::Gameplay)),
+    );
+}
+
+/// How much play between break reminders.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Reflect)]
+pub enum BreakInterval {
+    #[default]
+    Off,
+    Minutes30,
+    Minutes60,
+    Minutes90,
+}
+
+impl BreakInterval {
+    pub fn label(self) -> &'static str {
+        match self {
+            BreakInterval::Off => "Off",
+            BreakInterval::Minutes30 => "30 min",
+            BreakInterval::Minutes60 => "60 min",
+            BreakInterval::Minutes90 => "90 min",
+        }
+    }
+
+    /// The next setting, for the settings menu button.
+    pub fn next(self) -> Self {
+        match self {
+            BreakInterval::Off => BreakInterval::Minutes30,
+            BreakInterval::Minutes30 => BreakInterval::Minutes60,
+            BreakInterval::Minutes60 => BreakInterval::Minutes90,
+            BreakInterval::Minutes90 => BreakInterval::Off,
+        }
+    }
+
+    /// Seconds of play before a reminder, or `None` when off.
+    pub fn secs(self) -> Option<f32> {
+        match self {
+            BreakInterval::Off => None,
+            BreakInterval::Minutes30 => Some(30.0 * 60.0),
+            BreakInterval::Minutes60 => Some(60.0 * 60.0),
+            BreakInterval::Minutes90 => Some(90.0 * 60.0),
+        }
+    }
+}
+
+/// Break reminder settings, changed in the settings menu.
+#[derive(Resource, Debug, Clone, Default, Reflect)]
+#[reflect(Resource)]
+pub struct BreakReminderSettings {
+    pub interval: BreakInterval,
+}
+
+/// Unpaused gameplay seconds since the last reminder.
+#[derive(Resource, Debug, Default)]
+pub struct PlayTime(pub f32);
+
+fn remind_to_take_breaks(
+    time: Res<Time<Real>>,
+    settings: Res<BreakReminderSettings>,
+    mut played: ResMut<PlayTime>,
+    mut toasts: MessageWriter<ShowToast>,
+) {
+    played.0 += time.delta_secs();
+    let Some(interval) = settings.interval.secs() else {
+        return;
+    };
+    if played.0 >= interval {
+        played.0 = 0.0;
+        toasts.write(ShowToast::info(format!(
+            "You've been playing for {} minutes. Time for a break?",
+            (interval / 60.0) as u32
+        )));
+    }
+}
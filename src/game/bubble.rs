@@ -2,15 +2,31 @@
 //!
 //! Bubbles are placed on the hex grid and have different colors.
 //! When 3+ of the same color are connected, they pop!
+//! Bombs and stones are special kinds of bubble that match no color. Landing
+//! a shot next to a bomb blows up everything within two cells (see
+//! `cluster.rs`); stones can't be popped at all, and only fall once they're
+//! cut off from the top.
+
+use std::collections::HashSet;
 
 use bevy::prelude::*;
-use rand::Rng;
+use rand::{Rng, SeedableRng, rngs::StdRng};
+use serde::{Deserialize, Serialize};
 
 use super::{
+    bubble_material::{BubbleMaterial, BubbleShading},
+    gameplay_entities::GameplayEntity,
     grid::HexGrid,
+    handicap::{RunHandicap, apply_handicap},
     hex::{GridOffset, HEX_SIZE, HexCoord},
+    history::{RunSeed, pick_run_seed},
+    mode::GameMode,
+    patterns::color_symbol,
+    rng::{GameRng, seed_game_rng},
+    snapshot::GridCell,
+    weekly::ActiveChallenge,
 };
-use crate::screens::Screen;
+use crate::{display::GraphicsQuality, screens::InGame, textures::SpriteLoader};
 
 /// Holds game asset handles for bubble rendering.
 #[derive(Resource)]
@@ -21,62 +37,98 @@ pub struct GameAssets {
     pub angry_image: Handle<Image>,
     pub happy_image: Handle<Image>,
     pub enamored_image: Handle<Image>,
+    pub bomb_image: Handle<Image>,
+    pub stone_image: Handle<Image>,
     pub shooter_image: Handle<Image>,
     pub guide_line_image: Handle<Image>,
     pub doodle_images: Vec<Handle<Image>>,
 }
 
+impl GameAssets {
+    /// The snord sprite drawn for a bubble color.
+    pub fn sprite_for(&self, color: BubbleColor) -> Handle<Image> {
+        match color {
+            BubbleColor::Blue => self.derpy_image.clone(),
+            BubbleColor::Purple => self.scared_image.clone(),
+            BubbleColor::Yellow => self.sad_image.clone(),
+            BubbleColor::Red => self.angry_image.clone(),
+            BubbleColor::Green => self.happy_image.clone(),
+            BubbleColor::Orange => self.enamored_image.clone(),
+        }
+    }
+}
+
 /// Scale factor for snord sprites (64px -> ~40px to match HEX_SIZE diameter).
 pub const SNORD_SPRITE_SCALE: f32 = 0.625;
 
+/// Snord sprite scale for bubbles of the given hex size.
+pub fn sprite_scale(hex_size: f32) -> f32 {
+    SNORD_SPRITE_SCALE * hex_size / HEX_SIZE
+}
+
 pub(super) fn plugin(app: &mut App) {
     app.register_type::<Bubble>();
     app.register_type::<BubbleColor>();
+    app.register_type::<BubbleKind>();
+    app.register_type::<BubbleAge>();
+    app.init_resource::<StartingBoard>();
+    app.init_resource::<StartingStones>();
 
     // Load game assets before spawning bubbles
     app.add_systems(
-        OnEnter(Screen::Gameplay),
+        OnEnter(InGame),
         load_game_assets.before(spawn_initial_bubbles),
     );
 
-    // Spawn initial bubbles when entering gameplay
-    app.add_systems(OnEnter(Screen::Gameplay), spawn_initial_bubbles);
+    // Spawn initial bubbles when entering gameplay, from this run's seed
+    app.add_systems(
+        OnEnter(InGame),
+        spawn_initial_bubbles
+            .after(pick_run_seed)
+            .after(apply_handicap),
+    );
 
     // Spawn background doodles after assets are loaded
     app.add_systems(
-        OnEnter(Screen::Gameplay),
-        spawn_background_doodles.after(load_game_assets),
+        OnEnter(InGame),
+        spawn_background_doodles
+            .after(load_game_assets)
+            .after(seed_game_rng),
     );
 
     // Cleanup bubbles when leaving gameplay
-    app.add_systems(OnExit(Screen::Gameplay), cleanup_bubbles);
+    app.add_systems(OnExit(InGame), cleanup_bubbles);
 }
 
 /// Load game assets - must run before any systems that use GameAssets.
-pub fn load_game_assets(mut commands: Commands, asset_server: Res<AssetServer>) {
+pub fn load_game_assets(mut commands: Commands, sprites: SpriteLoader) {
     commands.insert_resource(GameAssets {
-        derpy_image: asset_server.load("images/derpy.png"),
-        scared_image: asset_server.load("images/scared.png"),
-        sad_image: asset_server.load("images/sad.png"),
-        angry_image: asset_server.load("images/angry.png"),
-        happy_image: asset_server.load("images/happy.png"),
-        enamored_image: asset_server.load("images/enamored.png"),
-        shooter_image: asset_server.load("images/shooter.png"),
-        guide_line_image: asset_server.load("images/guide_line.png"),
+        derpy_image: sprites.load("images/derpy.png"),
+        scared_image: sprites.load("images/scared.png"),
+        sad_image: sprites.load("images/sad.png"),
+        angry_image: sprites.load("images/angry.png"),
+        happy_image: sprites.load("images/happy.png"),
+        enamored_image: sprites.load("images/enamored.png"),
+        bomb_image: sprites.load("images/bomb.png"),
+        stone_image: sprites.load("images/stone.png"),
+        shooter_image: sprites.load("images/shooter.png"),
+        guide_line_image: sprites.load("images/guide_line.png"),
         doodle_images: vec![
-            asset_server.load("images/doodle_1.png"),
-            asset_server.load("images/doodle_2.png"),
-            asset_server.load("images/doodle_3.png"),
-            asset_server.load("images/doodle_4.png"),
-            asset_server.load("images/doodle_5.png"),
+            sprites.load("images/doodle_1.png"),
+            sprites.load("images/doodle_2.png"),
+            sprites.load("images/doodle_3.png"),
+            sprites.load("images/doodle_4.png"),
+            sprites.load("images/doodle_5.png"),
         ],
     });
 }
 
 /// The different bubble colors.
 /// Using 6 colors like classic Snood.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Component, Reflect, Default)]
-#[reflect(Component)]
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, Component, Reflect, Default, Serialize, Deserialize,
+)]
+#[reflect(Component, Serialize, Deserialize)]
 pub enum BubbleColor {
     #[default]
     Red,
@@ -100,39 +152,42 @@ impl BubbleColor {
         }
     }
 
-    /// Get a random bubble color.
-    pub fn random() -> Self {
-        let mut rng = rand::rng();
-        match rng.random_range(0..6) {
-            0 => BubbleColor::Red,
-            1 => BubbleColor::Blue,
-            2 => BubbleColor::Green,
-            3 => BubbleColor::Yellow,
-            4 => BubbleColor::Purple,
-            _ => BubbleColor::Orange,
+    /// Letter shown on bubbles of this color in colorblind letters mode.
+    pub fn letter(self) -> &'static str {
+        match self {
+            BubbleColor::Red => "R",
+            BubbleColor::Blue => "B",
+            BubbleColor::Green => "G",
+            BubbleColor::Yellow => "Y",
+            BubbleColor::Purple => "P",
+            BubbleColor::Orange => "O",
         }
     }
 
+    /// Get a random color from a palette (e.g. [`GameMode::palette`]) using
+    /// the given RNG.
+    pub fn random_with(palette: &[BubbleColor], rng: &mut impl Rng) -> Self {
+        palette[rng.random_range(0..palette.len())]
+    }
+
     /// Get a random color weighted toward colors that exist on the grid.
     /// With Lucky Snord, there's a 70% chance to pick from existing grid colors.
-    pub fn random_weighted(grid_colors: &[BubbleColor]) -> Self {
-        if grid_colors.is_empty() {
-            return Self::random();
-        }
-
-        let mut rng = rand::rng();
+    pub fn random_weighted(
+        grid_colors: &[BubbleColor],
+        palette: &[BubbleColor],
+        rng: &mut impl Rng,
+    ) -> Self {
         // 70% chance to pick from existing grid colors
-        if rng.random_bool(0.7) {
+        if !grid_colors.is_empty() && rng.random_bool(0.7) {
             // Pick a random color from the grid
             let idx = rng.random_range(0..grid_colors.len());
             grid_colors[idx]
         } else {
-            Self::random()
+            Self::random_with(palette, rng)
         }
     }
 
     /// Get all possible bubble colors.
-    #[allow(dead_code)]
     pub const ALL: [BubbleColor; 6] = [
         BubbleColor::Red,
         BubbleColor::Blue,
@@ -143,68 +198,290 @@ impl BubbleColor {
     ];
 }
 
+/// What a bubble does besides sitting on the grid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Reflect, Default, Serialize, Deserialize)]
+#[reflect(Serialize, Deserialize)]
+pub enum BubbleKind {
+    /// An ordinary bubble that pops with its color.
+    #[default]
+    Normal,
+    /// Matches no color, and clears everything within [`BOMB_RADIUS`] when a
+    /// shot lands next to it.
+    Bomb,
+    /// Matches no color and survives blasts. Only falls when cut off.
+    Stone,
+}
+
+/// How far a bomb's blast reaches, in cells.
+pub const BOMB_RADIUS: i32 = 2;
+
 /// Marker component for bubble entities.
 #[derive(Component, Debug, Clone, Reflect)]
 #[reflect(Component)]
 pub struct Bubble {
-    /// The bubble's color (also stored as a separate component for easy querying)
+    /// The bubble's color (also stored as a separate component for easy
+    /// querying). Bombs and stones don't have one and keep the default.
     pub color: BubbleColor,
     /// The hex coordinate where this bubble is placed
     pub coord: HexCoord,
+    pub kind: BubbleKind,
 }
 
+impl Bubble {
+    /// The color this bubble matches, or None for a bomb or stone.
+    pub fn match_color(&self) -> Option<BubbleColor> {
+        (self.kind == BubbleKind::Normal).then_some(self.color)
+    }
+}
+
+/// How many descents a bubble has survived on the board.
+/// Purely cosmetic: old bubbles get grumpier (see `polish.rs`).
+#[derive(Component, Debug, Clone, Copy, Default, PartialEq, Eq, Reflect)]
+#[reflect(Component)]
+pub struct BubbleAge(pub u32);
+
 /// Number of rows to fill at the start of the game.
-const INITIAL_ROWS: i32 = 5;
+pub const INITIAL_ROWS: i32 = 5;
+
+/// Chance that a cell of a random classic board holds a bomb.
+const BOMB_CHANCE: f64 = 0.02;
+
+/// Mixed into the run seed for bomb placement, so the colors of a seeded
+/// board stay the same as before bombs existed.
+const BOMB_SEED_SALT: u64 = 0xB0B;
+
+/// A fixed layout to start with instead of random rows (used by drills).
+#[derive(Resource, Debug, Default)]
+pub struct StartingBoard(pub Option<Vec<GridCell>>);
+
+/// Cells to put stones in at the start, in place of any bubble there (set by
+/// campaign levels).
+#[derive(Resource, Debug, Default)]
+pub struct StartingStones(pub Vec<HexCoord>);
 
 /// Spawn the initial bubbles at the top of the grid.
-fn spawn_initial_bubbles(
+pub(super) fn spawn_initial_bubbles(
     mut commands: Commands,
     mut grid: ResMut<HexGrid>,
     mut meshes: ResMut<Assets<Mesh>>,
-    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut materials: ResMut<Assets<BubbleMaterial>>,
     grid_offset: Res<GridOffset>,
     game_assets: Res<GameAssets>,
+    starting_board: Res<StartingBoard>,
+    stones: Res<StartingStones>,
+    mode: Res<GameMode>,
+    seed: Res<RunSeed>,
+    handicap: Res<RunHandicap>,
+    challenge: Res<ActiveChallenge>,
 ) {
     info!("Spawning initial bubbles...");
 
     let bounds = grid.bounds;
+    let random = starting_board.0.is_none();
+    let cells = starting_board.0.clone().unwrap_or_else(|| {
+        // Fill the top INITIAL_ROWS rows with random bubbles, and more for a
+        // handicap or challenge
+        let mut rng = StdRng::seed_from_u64(seed.0.unwrap_or_default());
+        (0..INITIAL_ROWS + handicap.handicap.extra_rows() + challenge.extra_rows())
+            .flat_map(|r| (bounds.min_q..=bounds.max_q).map(move |q| HexCoord::new(q, r)))
+            .map(|coord| GridCell {
+                coord,
+                color: BubbleColor::random_with(mode.palette(), &mut rng),
+            })
+            .collect()
+    });
+    let bombs = if random && mode.has_bombs() {
+        bomb_cells(seed.0.unwrap_or_default(), &cells)
+    } else {
+        HashSet::new()
+    };
     let mut count = 0;
 
-    // Fill the top INITIAL_ROWS rows with random bubbles
-    for r in 0..INITIAL_ROWS {
-        for q in bounds.min_q..=bounds.max_q {
-            let coord = HexCoord::new(q, r);
-            let color = BubbleColor::random();
-
-            let entity = spawn_bubble(
+    for GridCell { coord, color } in cells {
+        if !bounds.contains(coord) {
+            warn!("Skipping starting bubble outside the grid at {}", coord);
+            continue;
+        }
+        if stones.0.contains(&coord) {
+            continue;
+        }
+        if bombs.contains(&coord) {
+            let entity = spawn_bomb(
                 &mut commands,
                 &mut meshes,
                 &mut materials,
                 coord,
-                color,
+                grid.hex_size,
                 grid_offset.y,
                 Some(&game_assets),
             );
             grid.insert(coord, entity);
             count += 1;
+            continue;
         }
+        let entity = spawn_bubble(
+            &mut commands,
+            &mut meshes,
+            &mut materials,
+            coord,
+            color,
+            grid.hex_size,
+            grid_offset.y,
+            Some(&game_assets),
+        );
+        grid.insert(coord, entity);
+        count += 1;
     }
+    for &coord in &stones.0 {
+        if !bounds.contains(coord) {
+            warn!("Skipping starting stone outside the grid at {}", coord);
+            continue;
+        }
+        let entity = spawn_stone(
+            &mut commands,
+            &mut meshes,
+            &mut materials,
+            coord,
+            grid.hex_size,
+            grid_offset.y,
+            Some(&game_assets),
+        );
+        grid.insert(coord, entity);
+    }
+
+    info!(
+        "Spawned {} initial bubbles ({} bombs, {} stones)",
+        count,
+        bombs.len(),
+        stones.0.len()
+    );
+}
+
+/// Cells of a random board to turn into bombs, picked from the run seed.
+fn bomb_cells(seed: u64, cells: &[GridCell]) -> HashSet<HexCoord> {
+    let mut rng = StdRng::seed_from_u64(seed ^ BOMB_SEED_SALT);
+    cells
+        .iter()
+        .filter(|_| rng.random_bool(BOMB_CHANCE))
+        .map(|cell| cell.coord)
+        .collect()
+}
+
+/// Spawn a bomb at the given hex coordinate. Bombs have no
+/// [`BubbleColor`] component, so color matching passes them by.
+pub fn spawn_bomb(
+    commands: &mut Commands,
+    meshes: &mut Assets<Mesh>,
+    materials: &mut Assets<BubbleMaterial>,
+    coord: HexCoord,
+    hex_size: f32,
+    grid_origin_y: f32,
+    game_assets: Option<&GameAssets>,
+) -> Entity {
+    spawn_special(
+        commands,
+        meshes,
+        materials,
+        coord,
+        BubbleKind::Bomb,
+        hex_size,
+        grid_origin_y,
+        game_assets.map(|assets| assets.bomb_image.clone()),
+    )
+}
 
-    info!("Spawned {} initial bubbles", count);
+/// Spawn a stone at the given hex coordinate. Like bombs, stones have no
+/// [`BubbleColor`] component.
+pub fn spawn_stone(
+    commands: &mut Commands,
+    meshes: &mut Assets<Mesh>,
+    materials: &mut Assets<BubbleMaterial>,
+    coord: HexCoord,
+    hex_size: f32,
+    grid_origin_y: f32,
+    game_assets: Option<&GameAssets>,
+) -> Entity {
+    spawn_special(
+        commands,
+        meshes,
+        materials,
+        coord,
+        BubbleKind::Stone,
+        hex_size,
+        grid_origin_y,
+        game_assets.map(|assets| assets.stone_image.clone()),
+    )
 }
 
+/// Spawn a bubble of a kind that matches no color, drawn with `image` or a
+/// plain mesh without one.
+fn spawn_special(
+    commands: &mut Commands,
+    meshes: &mut Assets<Mesh>,
+    materials: &mut Assets<BubbleMaterial>,
+    coord: HexCoord,
+    kind: BubbleKind,
+    hex_size: f32,
+    grid_origin_y: f32,
+    image: Option<Handle<Image>>,
+) -> Entity {
+    let world_pos = coord.to_pixel_with_offset(hex_size, grid_origin_y);
+    let bubble = (
+        Name::new(format!("{:?} at {}", kind, coord)),
+        Bubble {
+            color: BubbleColor::default(),
+            coord,
+            kind,
+        },
+    );
+    match image {
+        Some(image) => commands
+            .spawn((
+                bubble,
+                Transform::from_translation(world_pos.extend(0.0))
+                    .with_scale(Vec3::splat(sprite_scale(hex_size))),
+                Sprite::from_image(image),
+                DespawnOnExit(InGame),
+            ))
+            .id(),
+        None => {
+            let color = match kind {
+                BubbleKind::Stone => STONE_COLOR,
+                _ => BOMB_COLOR,
+            };
+            commands
+                .spawn((
+                    bubble,
+                    Transform::from_translation(world_pos.extend(0.0)),
+                    Mesh2d(meshes.add(RegularPolygon::new(hex_size, 6))),
+                    MeshMaterial2d(materials.add(BubbleMaterial::new(color))),
+                    BubbleShading::default(),
+                    DespawnOnExit(InGame),
+                ))
+                .id()
+        }
+    }
+}
+
+/// Bombs drawn without their sprite.
+const BOMB_COLOR: Color = Color::srgb(0.18, 0.18, 0.24);
+
+/// Stones drawn without their sprite.
+const STONE_COLOR: Color = Color::srgb(0.5, 0.49, 0.46);
+
 /// Spawn a single bubble at the given hex coordinate with the given color.
 /// If game_assets is provided and color is Blue, uses derpy sprite instead of mesh.
 pub fn spawn_bubble(
     commands: &mut Commands,
     meshes: &mut Assets<Mesh>,
-    materials: &mut Assets<ColorMaterial>,
+    materials: &mut Assets<BubbleMaterial>,
     coord: HexCoord,
     color: BubbleColor,
+    hex_size: f32,
     grid_origin_y: f32,
     game_assets: Option<&GameAssets>,
 ) -> Entity {
-    let world_pos = coord.to_pixel_with_offset(HEX_SIZE, grid_origin_y);
+    let world_pos = coord.to_pixel_with_offset(hex_size, grid_origin_y);
 
     // For certain colors, use sprite images instead of colored meshes
     if let Some(assets) = game_assets {
@@ -221,30 +498,44 @@ pub fn spawn_bubble(
             return commands
                 .spawn((
                     Name::new(format!("Bubble {:?} at {}", color, coord)),
-                    Bubble { color, coord },
+                    Bubble {
+                        color,
+                        coord,
+                        kind: BubbleKind::Normal,
+                    },
                     color,
+                    BubbleAge::default(),
                     Transform::from_translation(world_pos.extend(0.0))
-                        .with_scale(Vec3::splat(SNORD_SPRITE_SCALE)),
+                        .with_scale(Vec3::splat(sprite_scale(hex_size))),
                     Sprite::from_image(image),
-                    DespawnOnExit(Screen::Gameplay),
+                    // The grid owns bubbles, so run resets leave them be
+                    DespawnOnExit(InGame),
+                    children![color_symbol(color, hex_size, sprite_scale(hex_size))],
                 ))
                 .id();
         }
     }
 
     // Default: Create a hexagon mesh for the bubble
-    // RegularPolygon::new(circumradius, sides) - circumradius = HEX_SIZE
+    // RegularPolygon::new(circumradius, sides) - circumradius = hex_size
     commands
         .spawn((
             Name::new(format!("Bubble {:?} at {}", color, coord)),
-            Bubble { color, coord },
+            Bubble {
+                color,
+                coord,
+                kind: BubbleKind::Normal,
+            },
             color,
+            BubbleAge::default(),
             Transform::from_translation(world_pos.extend(0.0)),
             // Hexagon mesh
-            Mesh2d(meshes.add(RegularPolygon::new(HEX_SIZE, 6))),
-            MeshMaterial2d(materials.add(ColorMaterial::from_color(color.to_color()))),
+            Mesh2d(meshes.add(RegularPolygon::new(hex_size, 6))),
+            MeshMaterial2d(materials.add(BubbleMaterial::new(color.to_color()))),
+            BubbleShading::default(),
             // Mark for cleanup when leaving gameplay
-            DespawnOnExit(Screen::Gameplay),
+            DespawnOnExit(InGame),
+            children![color_symbol(color, hex_size, 1.0)],
         ))
         .id()
 }
@@ -255,9 +546,19 @@ fn cleanup_bubbles(mut grid: ResMut<HexGrid>) {
     info!("Cleared bubble grid");
 }
 
+/// A decorative doodle in the margins beside the board.
+#[derive(Component, Debug)]
+pub struct BackgroundDoodle;
+
 /// Spawn decorative doodles in the background on left/right sides of the game area.
-fn spawn_background_doodles(mut commands: Commands, game_assets: Res<GameAssets>) {
-    let mut rng = rand::rng();
+fn spawn_background_doodles(
+    mut commands: Commands,
+    game_assets: Res<GameAssets>,
+    quality: Res<GraphicsQuality>,
+    mut game_rng: ResMut<GameRng>,
+) {
+    let rng = &mut game_rng.effects;
+    let share = quality.0.backdrop() as f64;
 
     // Game bounds are -245 to +245, window is -400 to +400
     // Left margin: -400 to -260 (keeping buffer from game)
@@ -292,6 +593,11 @@ fn spawn_background_doodles(mut commands: Commands, game_assets: Res<GameAssets>
                 let base_x = min_x + (col as f32 + 0.5) * CELL_SIZE;
                 let base_y = Y_MIN + (row as f32 + 0.5) * CELL_SIZE;
 
+                // Lower quality presets leave gaps
+                if !rng.random_bool(share) {
+                    continue;
+                }
+
                 let x = base_x + rng.random_range(-JITTER..JITTER);
                 let y = base_y + rng.random_range(-JITTER..JITTER);
 
@@ -307,11 +613,12 @@ fn spawn_background_doodles(mut commands: Commands, game_assets: Res<GameAssets>
 
                 commands.spawn((
                     Name::new(format!("Background Doodle {}", doodle_idx + 1)),
+                    BackgroundDoodle,
                     Transform::from_translation(Vec3::new(x, y, -1.0))
                         .with_rotation(Quat::from_rotation_z(rotation))
                         .with_scale(Vec3::splat(scale)),
                     Sprite::from_image(image),
-                    DespawnOnExit(Screen::Gameplay),
+                    GameplayEntity,
                 ));
                 count += 1;
             }
@@ -0,0 +1,173 @@
+//! Bubble material - shading for mesh bubbles.
+//!
+//! Colors without a snord sprite are drawn as hexagon meshes. Instead of a
+//! flat fill they get a small material: a radial highlight and a darker rim
+//! so they read as round like the sprites, a vertex wobble, and a white hit
+//! flash. The shader lives in `assets/shaders/bubble.wgsl`.
+//!
+//! The wobble is a gentle idle sway (with idle animations on) plus a nudge
+//! that kicks in when a shot lands next to the bubble and settles back down.
+//! The bubble a shot lands as flashes.
+
+use bevy::{
+    prelude::*,
+    render::render_resource::{AsBindGroup, ShaderType},
+    shader::ShaderRef,
+    sprite_render::{Material2d, Material2dPlugin},
+};
+
+use super::{
+    clock::GameClock,
+    grid::HexGrid,
+    projectile::{BubbleLanded, ProjectileSystems},
+};
+use crate::{PausableSystems, display::GraphicsQuality, screens::InGame};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_plugins(Material2dPlugin::<BubbleMaterial>::default());
+
+    app.add_systems(
+        Update,
+        (shake_landing_neighbors, update_bubble_shading)
+            .chain()
+            .after(ProjectileSystems)
+            .in_set(PausableSystems)
+            .run_if(in_state(InGame)),
+    );
+}
+
+const SHADER_PATH: &str = "shaders/bubble.wgsl";
+
+/// Wobble while nothing is happening, as a share of the bubble's radius.
+const IDLE_WOBBLE: f32 = 0.02;
+
+/// Extra wobble right after a nudge.
+const NUDGE_WOBBLE: f32 = 0.08;
+
+/// Seconds a nudge takes to settle.
+const NUDGE_SECONDS: f32 = 0.5;
+
+/// Seconds a hit flash takes to fade.
+const FLASH_SECONDS: f32 = 0.15;
+
+/// Shading for a hexagon bubble mesh.
+#[derive(Asset, TypePath, AsBindGroup, Debug, Clone)]
+pub struct BubbleMaterial {
+    #[uniform(0)]
+    pub params: BubbleParams,
+}
+
+/// The material's uniform, mirrored by `BubbleParams` in the shader.
+#[derive(ShaderType, Debug, Clone, Copy)]
+pub struct BubbleParams {
+    pub color: LinearRgba,
+    /// Strength of the radial highlight toward the top left.
+    pub highlight: f32,
+    /// How much the rim darkens.
+    pub rim: f32,
+    /// How far the outline sways, as a share of the radius.
+    pub wobble: f32,
+    /// How far toward white the bubble is, for hit flashes.
+    pub flash: f32,
+}
+
+impl BubbleMaterial {
+    pub fn new(color: Color) -> Self {
+        Self {
+            params: BubbleParams {
+                color: color.into(),
+                highlight: 0.35,
+                rim: 0.3,
+                wobble: 0.0,
+                flash: 0.0,
+            },
+        }
+    }
+}
+
+impl Material2d for BubbleMaterial {
+    fn vertex_shader() -> ShaderRef {
+        SHADER_PATH.into()
+    }
+
+    fn fragment_shader() -> ShaderRef {
+        SHADER_PATH.into()
+    }
+}
+
+/// Nudge and flash state for a mesh bubble, written to its material.
+#[derive(Component, Debug, Default)]
+pub struct BubbleShading {
+    /// Seconds left on the current nudge.
+    nudge: f32,
+    /// Seconds left on the current flash.
+    flash: f32,
+}
+
+impl BubbleShading {
+    pub fn nudge(&mut self) {
+        self.nudge = NUDGE_SECONDS;
+    }
+
+    pub fn flash(&mut self) {
+        self.flash = FLASH_SECONDS;
+    }
+
+    fn settled(&self) -> bool {
+        self.nudge <= 0.0 && self.flash <= 0.0
+    }
+}
+
+/// Flash the bubble a shot landed as and nudge the ones around it.
+fn shake_landing_neighbors(
+    mut landed: MessageReader<BubbleLanded>,
+    grid: Res<HexGrid>,
+    mut shading: Query<&mut BubbleShading>,
+) {
+    for event in landed.read() {
+        if let Some(mut landed) = grid
+            .get(event.coord)
+            .and_then(|entity| shading.get_mut(entity).ok())
+        {
+            landed.flash();
+        }
+        for neighbor in event.coord.neighbors() {
+            if let Some(mut shading) = grid
+                .get(neighbor)
+                .and_then(|entity| shading.get_mut(entity).ok())
+            {
+                shading.nudge();
+            }
+        }
+    }
+}
+
+/// Count down nudges and flashes and write them to the materials. Settled
+/// bubbles are left alone unless they're new or the quality preset changed,
+/// so their materials aren't uploaded again every frame.
+fn update_bubble_shading(
+    time: Res<Time<GameClock>>,
+    quality: Res<GraphicsQuality>,
+    mut materials: ResMut<Assets<BubbleMaterial>>,
+    mut bubbles: Query<(&mut BubbleShading, &MeshMaterial2d<BubbleMaterial>)>,
+) {
+    let idle = if quality.0.idle_animations() {
+        IDLE_WOBBLE
+    } else {
+        0.0
+    };
+    let dt = time.delta_secs();
+    for (mut shading, material) in &mut bubbles {
+        if shading.settled() && !shading.is_added() && !quality.is_changed() {
+            continue;
+        }
+        shading.nudge = (shading.nudge - dt).max(0.0);
+        shading.flash = (shading.flash - dt).max(0.0);
+
+        let Some(material) = materials.get_mut(&material.0) else {
+            continue;
+        };
+        material.params.wobble = idle + NUDGE_WOBBLE * shading.nudge / NUDGE_SECONDS;
+        material.params.flash = shading.flash / FLASH_SECONDS;
+    }
+}
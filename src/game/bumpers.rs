@@ -0,0 +1,215 @@
+//! Bumpers - round pegs in the playfield that knock shots away, pinball
+//! style.
+//!
+//! A shot that touches a bumper reflects off it with a little extra speed,
+//! and the bumper scores a few points and flashes. The reflection math is in
+//! [`Bumpers::deflect`], shared by the projectile and every trajectory
+//! preview so they agree on where a shot goes.
+//!
+//! Bumpers come from campaign level data; other runs have none.
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::{clock::GameClock, messages::AddGameMessage};
+use crate::{PausableSystems, accessibility::full_motion, screens::InGame};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<Bumpers>();
+    app.init_resource::<Bumpers>();
+    app.add_game_message::<BumperHit>("A projectile bounced off a bumper");
+
+    app.add_systems(OnEnter(InGame), spawn_bumpers);
+    app.add_systems(
+        Update,
+        (flash_hit_bumpers, animate_bumper_flash.run_if(full_motion))
+            .chain()
+            .in_set(PausableSystems)
+            .run_if(in_state(InGame)),
+    );
+}
+
+/// Points scored each time a shot hits a bumper.
+pub const BUMPER_POINTS: u32 = 5;
+
+/// Speed multiplier for each hit.
+const BUMPER_BOOST: f32 = 1.1;
+
+/// Boosts stop adding speed past this, in pixels per second.
+const MAX_BUMPER_SPEED: f32 = 900.0;
+
+/// How long a hit bumper stays swollen, in seconds.
+const FLASH_SECONDS: f32 = 0.2;
+
+/// Extra scale at the start of a flash.
+const FLASH_SCALE: f32 = 0.3;
+
+const BUMPER_RIM: Color = Color::srgb(0.85, 0.3, 0.35);
+const BUMPER_CAP: Color = Color::srgb(1.0, 0.75, 0.55);
+
+/// Message sent when a projectile bounces off a bumper.
+#[derive(Message, Debug, Clone)]
+pub struct BumperHit {
+    /// Index of the bumper in [`Bumpers`].
+    pub index: usize,
+}
+
+fn default_radius() -> f32 {
+    16.0
+}
+
+/// A round peg in the playfield.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Reflect)]
+pub struct Bumper {
+    pub x: f32,
+    pub y: f32,
+    #[serde(default = "default_radius")]
+    pub radius: f32,
+}
+
+impl Bumper {
+    pub fn center(self) -> Vec2 {
+        Vec2::new(self.x, self.y)
+    }
+}
+
+/// The bumpers in the playfield this run.
+#[derive(Resource, Debug, Clone, Default, PartialEq, Reflect)]
+#[reflect(Resource)]
+pub struct Bumpers(pub Vec<Bumper>);
+
+impl Bumpers {
+    /// Reflect a shot of `radius` at `pos` moving at `velocity` off the
+    /// first bumper it's touching and heading into: which bumper, the shot
+    /// pushed back out to its edge, and the reflected velocity. Returns None
+    /// if the shot is clear of every bumper.
+    ///
+    /// The velocity isn't boosted here; see [`boosted`] for that.
+    pub fn deflect(&self, pos: Vec2, velocity: Vec2, radius: f32) -> Option<(usize, Vec2, Vec2)> {
+        self.0.iter().enumerate().find_map(|(index, bumper)| {
+            let offset = pos - bumper.center();
+            let reach = bumper.radius + radius;
+            // Already heading away means it bounced last frame
+            if offset.length_squared() >= reach * reach || velocity.dot(offset) >= 0.0 {
+                return None;
+            }
+            let normal = offset.normalize_or(Vec2::Y);
+            let reflected = velocity - 2.0 * velocity.dot(normal) * normal;
+            Some((index, bumper.center() + normal * reach, reflected))
+        })
+    }
+}
+
+/// `velocity` with a bumper's speed boost.
+pub fn boosted(velocity: Vec2) -> Vec2 {
+    let speed = velocity.length();
+    let boosted = (speed * BUMPER_BOOST).min(MAX_BUMPER_SPEED.max(speed));
+    velocity.normalize_or_zero() * boosted
+}
+
+/// A bumper's sprite.
+#[derive(Component, Debug, Clone, Copy)]
+struct BumperVisual(usize);
+
+/// Seconds left on a bumper's hit flash.
+#[derive(Component, Debug, Clone, Copy, Default)]
+struct BumperFlash(f32);
+
+fn spawn_bumpers(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    bumpers: Res<Bumpers>,
+) {
+    let rim = materials.add(ColorMaterial::from_color(BUMPER_RIM));
+    let cap = materials.add(ColorMaterial::from_color(BUMPER_CAP));
+    for (index, bumper) in bumpers.0.iter().enumerate() {
+        commands.spawn((
+            Name::new("Bumper"),
+            BumperVisual(index),
+            BumperFlash::default(),
+            Mesh2d(meshes.add(Circle::new(bumper.radius))),
+            MeshMaterial2d(rim.clone()),
+            Transform::from_translation(bumper.center().extend(0.6)),
+            DespawnOnExit(InGame),
+            children![(
+                Mesh2d(meshes.add(Circle::new(bumper.radius * 0.6))),
+                MeshMaterial2d(cap.clone()),
+                Transform::from_xyz(0.0, 0.0, 0.1),
+            )],
+        ));
+    }
+}
+
+fn flash_hit_bumpers(
+    mut hits: MessageReader<BumperHit>,
+    mut visuals: Query<(&BumperVisual, &mut BumperFlash)>,
+) {
+    for hit in hits.read() {
+        for (visual, mut flash) in &mut visuals {
+            if visual.0 == hit.index {
+                flash.0 = FLASH_SECONDS;
+            }
+        }
+    }
+}
+
+fn animate_bumper_flash(
+    time: Res<Time<GameClock>>,
+    mut visuals: Query<(&mut BumperFlash, &mut Transform)>,
+) {
+    for (mut flash, mut transform) in &mut visuals {
+        flash.0 = (flash.0 - time.delta_secs()).max(0.0);
+        let swell = 1.0 + FLASH_SCALE * flash.0 / FLASH_SECONDS;
+        transform.scale = Vec3::new(swell, swell, 1.0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn one_bumper() -> Bumpers {
+        Bumpers(vec![Bumper {
+            x: 0.0,
+            y: 0.0,
+            radius: 10.0,
+        }])
+    }
+
+    #[test]
+    fn head_on_shots_come_straight_back() {
+        let (index, pos, velocity) = one_bumper()
+            .deflect(Vec2::new(0.0, -15.0), Vec2::new(0.0, 600.0), 10.0)
+            .unwrap();
+        assert_eq!(index, 0);
+        assert_eq!(pos, Vec2::new(0.0, -20.0));
+        assert_eq!(velocity, Vec2::new(0.0, -600.0));
+    }
+
+    #[test]
+    fn glancing_shots_keep_their_speed_and_turn_away() {
+        let velocity = Vec2::new(0.0, 600.0);
+        let (_, _, reflected) = one_bumper()
+            .deflect(Vec2::new(10.0, -15.0), velocity, 10.0)
+            .unwrap();
+        assert!((reflected.length() - velocity.length()).abs() < 0.01);
+        assert!(reflected.x > 0.0);
+
+        // Moving away from a bumper it's still touching doesn't bounce again
+        assert!(
+            one_bumper()
+                .deflect(Vec2::new(10.0, -15.0), reflected, 10.0)
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn boosts_stop_at_the_cap() {
+        let speed = |velocity: Vec2| boosted(velocity).length();
+        assert!((speed(Vec2::new(0.0, 600.0)) - 660.0).abs() < 0.01);
+        assert!((speed(Vec2::new(0.0, 880.0)) - 900.0).abs() < 0.01);
+        // Already faster than the cap (unlikely) isn't slowed down
+        assert!((speed(Vec2::new(0.0, 1000.0)) - 1000.0).abs() < 0.01);
+    }
+}
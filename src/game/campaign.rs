@@ -0,0 +1,519 @@
+//! The campaign - hand-made levels played in order.
+//!
+//! Levels are defined in `assets/data/campaign.json`, with boards in the same
+//! format as drills. The side walls close in while a level is played,
+//! narrowing the playfield until the board is cleared or they reach the
+//! level's minimum width, and can have portals or sticky zones on them.
+//! Levels can also place bumpers in the playfield, and hang the board from
+//! anchor cells of their own instead of the top row, and put stones on the
+//! board that can't be popped. Score zones along the
+//! bottom multiply the bonus for bubbles dropped into them.
+//! The walls are the pressure instead of descent.
+//! A level can limit the colors in play, and ask for a score or a number of
+//! popped bubbles instead of a cleared board, and have a script of its own.
+//! Boards with nothing else laid out around them are mirrored or turned
+//! upside down at random (see [`layout`](super::layout)), so a replay plays
+//! a little differently.
+//! Winning a level records it in the [`Profile`] and shows its grade on the
+//! results screen, which goes on to the next one; the next campaign run
+//! starts from there too.
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    bubble::{BubbleColor, StartingBoard, StartingStones},
+    bumpers::{Bumper, Bumpers},
+    clock::GameClock,
+    cluster::LevelAnchors,
+    drills::parse_board,
+    grid::{GridBounds, HexGrid},
+    hex::{GridOffset, HexCoord},
+    layout::random_variant,
+    mode::{GameMode, LevelColors, SelectedMode},
+    portals::{Portal, Portals},
+    projectile::{TOP_WALL, Walls},
+    rng::GameRng,
+    score_zones::{ScoreZone, ScoreZones},
+    shooter::SHOOTER_Y,
+    snapshot::GridCell,
+    state::{GameEnded, GameScore},
+    sticky_walls::{StickyWalls, StickyZone},
+    telemetry::RunOutcome,
+};
+use crate::{
+    PausableSystems,
+    mods::{ModList, parse_data},
+    profile::Profile,
+    save::SaveFile,
+    screens::{InGame, Screen},
+    theme::toast::ShowToast,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    let levels = CampaignLevels::load(app.world().get_resource::<ModList>());
+    app.insert_resource(levels);
+    app.init_resource::<ActiveLevel>();
+    app.init_resource::<LevelGoal>();
+
+    app.add_systems(OnEnter(Screen::Title), clear_active_level);
+    app.add_systems(
+        Update,
+        (
+            select_campaign_level
+                .run_if(resource_changed::<SelectedMode>.and(not(resource_added::<SelectedMode>))),
+            apply_active_level.run_if(resource_changed::<ActiveLevel>),
+        )
+            .chain(),
+    );
+
+    app.add_systems(
+        OnEnter(InGame),
+        (spawn_wall_sprites, spawn_anchor_pins).run_if(campaign_active),
+    );
+    app.add_systems(
+        Update,
+        (close_in_walls, sync_wall_sprites)
+            .chain()
+            .in_set(PausableSystems)
+            .run_if(in_state(InGame).and(campaign_active)),
+    );
+    app.add_systems(Update, record_cleared_level.run_if(campaign_active));
+}
+
+/// Levels are compiled in, like the drills.
+const CAMPAIGN_FILE: &str = include_str!("../../assets/data/campaign.json");
+
+/// Thickness of the drawn walls.
+const WALL_THICKNESS: f32 = 8.0;
+
+const WALL_COLOR: Color = Color::srgb(0.45, 0.36, 0.28);
+
+/// Ring drawn behind anchor cells, so it shows around their bubbles.
+const ANCHOR_PIN_COLOR: Color = Color::srgb(0.3, 0.25, 0.2);
+
+/// One campaign level.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LevelDefinition {
+    pub name: String,
+    pub description: String,
+    /// Rows from the top, one letter per column starting at the left wall.
+    pub board: Vec<String>,
+    /// Colors the shooter and new rows use, or empty for all of them.
+    #[serde(default)]
+    pub colors: Vec<BubbleColor>,
+    /// What it takes to win the level.
+    #[serde(default)]
+    pub goal: WinCondition,
+    /// How fast each wall moves inward, in pixels per second.
+    #[serde(default)]
+    pub wall_speed: f32,
+    /// The walls stop once they're this far apart.
+    #[serde(default = "full_width")]
+    pub min_width: f32,
+    /// Portal pairs on the walls.
+    #[serde(default)]
+    pub portals: Vec<[Portal; 2]>,
+    /// Bumpers in the playfield.
+    #[serde(default)]
+    pub bumpers: Vec<Bumper>,
+    /// Stretches of the walls that shots stick to.
+    #[serde(default)]
+    pub sticky: Vec<StickyZone>,
+    /// Cells that hold the board up instead of the top row, as
+    /// `[column, row]` in the same layout as `board`.
+    #[serde(default)]
+    pub anchors: Vec<[i32; 2]>,
+    /// Buckets along the bottom that multiply dropped bubbles.
+    #[serde(default)]
+    pub score_zones: Vec<ScoreZone>,
+    /// Lines of the level's script (see [`scripting`](super::scripting)).
+    #[serde(default)]
+    pub script: Vec<String>,
+    /// Cells that start with a stone, as `[column, row]` in the same layout
+    /// as `board`. A stone that hangs from the top row never falls, so keep
+    /// them lower down on boards that need clearing.
+    #[serde(default)]
+    pub stones: Vec<[i32; 2]>,
+    /// Dialogue scene played as the level starts (see
+    /// [`dialogue`](super::dialogue)).
+    #[serde(default)]
+    pub intro: Option<String>,
+    /// Shots the level should take, for its grade (see
+    /// [`grading`](super::grading)). Defaults to a third of the board.
+    #[serde(default)]
+    pub par: Option<u32>,
+}
+
+/// What a level asks for to be won.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WinCondition {
+    /// Clear every bubble off the board.
+    #[default]
+    ClearBoard,
+    /// Reach this score.
+    Score(u32),
+    /// Pop this many bubbles.
+    PopBubbles(u32),
+}
+
+impl WinCondition {
+    /// Whether the goal is met with this board and score.
+    pub fn met(self, board_cleared: bool, score: &GameScore) -> bool {
+        match self {
+            WinCondition::ClearBoard => board_cleared,
+            WinCondition::Score(points) => board_cleared || score.score >= points,
+            WinCondition::PopBubbles(count) => board_cleared || score.bubbles_popped >= count,
+        }
+    }
+}
+
+/// The current level's goal. Runs outside the campaign clear the board.
+#[derive(Resource, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct LevelGoal(pub WinCondition);
+
+fn full_width() -> f32 {
+    Walls::default().width()
+}
+
+/// Grid cells for `[column, row]` pairs in the board layout.
+fn board_cells(cells: &[[i32; 2]]) -> Vec<HexCoord> {
+    let min_q = GridBounds::default().min_q;
+    cells
+        .iter()
+        .map(|&[column, row]| HexCoord::new(min_q + column, row))
+        .collect()
+}
+
+impl LevelDefinition {
+    /// The starting bubbles, or an error naming the first bad letter.
+    pub fn cells(&self) -> Result<Vec<GridCell>, String> {
+        parse_board(&self.board)
+    }
+
+    /// Shots the level is graded against.
+    pub fn par(&self) -> u32 {
+        self.par.unwrap_or_else(|| {
+            self.cells()
+                .map_or(1, |cells| cells.len().div_ceil(3) as u32)
+        })
+    }
+
+    /// The level's anchor cells, or None to anchor to the top row.
+    pub fn anchor_cells(&self) -> Option<Vec<HexCoord>> {
+        (!self.anchors.is_empty()).then(|| board_cells(&self.anchors))
+    }
+
+    /// The cells that start with a stone.
+    pub fn stone_cells(&self) -> Vec<HexCoord> {
+        board_cells(&self.stones)
+    }
+
+    /// Whether the board can be mirrored or turned: nothing else in the
+    /// level (anchors, stones, wall and playfield features, a script) was
+    /// placed to fit it.
+    pub fn can_vary(&self) -> bool {
+        self.anchors.is_empty()
+            && self.stones.is_empty()
+            && self.portals.is_empty()
+            && self.bumpers.is_empty()
+            && self.sticky.is_empty()
+            && self.score_zones.is_empty()
+            && self.script.is_empty()
+    }
+
+    /// `walls` moved `secs` worth of closing in, stopping at the minimum
+    /// width. The walls close in evenly from both sides.
+    pub fn close_in(&self, walls: Walls, secs: f32) -> Walls {
+        let step = self.wall_speed * secs;
+        let center = (walls.left + walls.right) / 2.0;
+        let half_min = self.min_width / 2.0;
+        Walls {
+            left: (walls.left + step).min(center - half_min).max(walls.left),
+            right: (walls.right - step).max(center + half_min).min(walls.right),
+        }
+    }
+}
+
+/// All campaign levels, in play order.
+#[derive(Resource, Debug, Default)]
+pub struct CampaignLevels(pub Vec<LevelDefinition>);
+
+impl CampaignLevels {
+    fn load(mods: Option<&ModList>) -> Self {
+        match parse_data::<Vec<LevelDefinition>>(mods, "data/campaign.json", CAMPAIGN_FILE) {
+            Ok(levels) => Self(levels),
+            Err(e) => {
+                warn!("Failed to parse campaign levels: {}", e);
+                Self::default()
+            }
+        }
+    }
+
+    /// Whether another level follows the one at `index`.
+    pub fn has_next(&self, index: usize) -> bool {
+        index + 1 < self.0.len()
+    }
+
+    /// The level to play next, given how many have been cleared. Once every
+    /// level is cleared, the last one is replayed.
+    pub fn next_index(&self, cleared: u32) -> Option<usize> {
+        let last = self.0.len().checked_sub(1)?;
+        Some((cleared as usize).min(last))
+    }
+}
+
+/// Index of the campaign level being played, if any. Kept across restarts
+/// from the game over menu and cleared on returning to the title screen.
+#[derive(Resource, Debug, Default, PartialEq)]
+pub struct ActiveLevel(pub Option<usize>);
+
+/// Run condition: a campaign level is being played.
+pub fn campaign_active(active: Res<ActiveLevel>) -> bool {
+    active.0.is_some()
+}
+
+/// Marker for a drawn side wall.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+enum WallSprite {
+    Left,
+    Right,
+}
+
+fn clear_active_level(mut active: ResMut<ActiveLevel>) {
+    active.set_if_neq(ActiveLevel(None));
+}
+
+/// Pick the next uncleared level when Campaign is chosen on the mode select
+/// screen.
+fn select_campaign_level(
+    selected: Res<SelectedMode>,
+    levels: Res<CampaignLevels>,
+    profile: Res<Profile>,
+    mut active: ResMut<ActiveLevel>,
+) {
+    let level = match *selected {
+        SelectedMode::Campaign => levels.next_index(profile.campaign_cleared),
+        _ => None,
+    };
+    active.set_if_neq(ActiveLevel(level));
+}
+
+/// Point the starting board, colors, goal, and wall and playfield features at
+/// the selected level, or back to a random board with none.
+fn apply_active_level(
+    active: Res<ActiveLevel>,
+    levels: Res<CampaignLevels>,
+    mut board: ResMut<StartingBoard>,
+    mut stones: ResMut<StartingStones>,
+    mut colors: ResMut<LevelColors>,
+    mut goal: ResMut<LevelGoal>,
+    mut portals: ResMut<Portals>,
+    mut bumpers: ResMut<Bumpers>,
+    mut sticky: ResMut<StickyWalls>,
+    mut anchors: ResMut<LevelAnchors>,
+    mut zones: ResMut<ScoreZones>,
+    mut rng: ResMut<GameRng>,
+) {
+    let Some(level) = active.0.and_then(|index| levels.0.get(index)) else {
+        board.0 = None;
+        stones.0.clear();
+        colors.0 = None;
+        goal.0 = WinCondition::default();
+        portals.0.clear();
+        bumpers.0.clear();
+        sticky.0.clear();
+        anchors.0 = None;
+        zones.0.clear();
+        return;
+    };
+    colors.0 = (!level.colors.is_empty()).then(|| level.colors.clone());
+    goal.0 = level.goal;
+    portals.0 = level.portals.clone();
+    bumpers.0 = level.bumpers.clone();
+    sticky.0 = level.sticky.clone();
+    anchors.0 = level.anchor_cells();
+    stones.0 = level.stone_cells();
+    zones.0 = level.score_zones.clone();
+
+    match level.cells() {
+        Ok(cells) => {
+            info!("Campaign level selected: {}", level.name);
+            board.0 = Some(if level.can_vary() {
+                random_variant(&cells, GameMode::Campaign.grid_bounds(), &mut rng.gameplay)
+            } else {
+                cells
+            });
+        }
+        Err(e) => warn!("Campaign level '{}' is invalid: {}", level.name, e),
+    }
+}
+
+fn spawn_wall_sprites(mut commands: Commands, walls: Res<Walls>) {
+    let bottom = SHOOTER_Y - 50.0;
+    let size = Vec2::new(WALL_THICKNESS, TOP_WALL - bottom);
+    let y = (TOP_WALL + bottom) / 2.0;
+    for (side, x) in [
+        (WallSprite::Left, walls.left - WALL_THICKNESS / 2.0),
+        (WallSprite::Right, walls.right + WALL_THICKNESS / 2.0),
+    ] {
+        commands.spawn((
+            Name::new("Campaign Wall"),
+            side,
+            Sprite::from_color(WALL_COLOR, size),
+            Transform::from_xyz(x, y, 0.5),
+            DespawnOnExit(InGame),
+        ));
+    }
+}
+
+fn spawn_anchor_pins(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    anchors: Res<LevelAnchors>,
+    grid: Res<HexGrid>,
+    grid_offset: Res<GridOffset>,
+) {
+    let Some(cells) = &anchors.0 else {
+        return;
+    };
+    let pin = meshes.add(Circle::new(grid.hex_size * 1.1));
+    let color = materials.add(ColorMaterial::from_color(ANCHOR_PIN_COLOR));
+    for coord in cells {
+        let pos = coord.to_pixel_with_offset(grid.hex_size, grid_offset.y);
+        commands.spawn((
+            Name::new("Anchor Pin"),
+            Mesh2d(pin.clone()),
+            MeshMaterial2d(color.clone()),
+            Transform::from_translation(pos.extend(-0.5)),
+            DespawnOnExit(InGame),
+        ));
+    }
+}
+
+fn close_in_walls(
+    time: Res<Time<GameClock>>,
+    active: Res<ActiveLevel>,
+    levels: Res<CampaignLevels>,
+    mut walls: ResMut<Walls>,
+) {
+    let Some(level) = active.0.and_then(|index| levels.0.get(index)) else {
+        return;
+    };
+    walls.set_if_neq(level.close_in(*walls, time.delta_secs()));
+}
+
+fn sync_wall_sprites(walls: Res<Walls>, mut sprites: Query<(&WallSprite, &mut Transform)>) {
+    if !walls.is_changed() {
+        return;
+    }
+    for (side, mut transform) in &mut sprites {
+        transform.translation.x = match side {
+            WallSprite::Left => walls.left - WALL_THICKNESS / 2.0,
+            WallSprite::Right => walls.right + WALL_THICKNESS / 2.0,
+        };
+    }
+}
+
+/// Record a won level. The results screen moves on to the next one.
+fn record_cleared_level(
+    mut ended_events: MessageReader<GameEnded>,
+    levels: Res<CampaignLevels>,
+    active: Res<ActiveLevel>,
+    mut profile: ResMut<Profile>,
+    mut toasts: MessageWriter<ShowToast>,
+) {
+    if !ended_events
+        .read()
+        .any(|event| event.outcome == RunOutcome::Won)
+    {
+        return;
+    }
+    let Some(index) = active.0 else {
+        return;
+    };
+
+    let name = levels.0.get(index).map_or("", |level| level.name.as_str());
+    info!("Campaign level '{}' cleared", name);
+    profile.campaign_cleared = profile.campaign_cleared.max(index as u32 + 1);
+    profile.save();
+
+    if levels.has_next(index) {
+        toasts.write(ShowToast::success(format!("Level cleared: {name}")));
+    } else {
+        toasts.write(ShowToast::success("Campaign complete!"));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{SeedableRng, rngs::StdRng};
+
+    use super::*;
+
+    #[test]
+    fn campaign_levels_parse() {
+        let levels = CampaignLevels::load(None);
+        assert!(!levels.0.is_empty());
+        for level in &levels.0 {
+            let cells = level
+                .cells()
+                .unwrap_or_else(|e| panic!("{}: {e}", level.name));
+            if !level.colors.is_empty() {
+                assert!(
+                    cells.iter().all(|cell| level.colors.contains(&cell.color)),
+                    "{} has bubbles outside its colors",
+                    level.name
+                );
+            }
+            for anchor in level.anchor_cells().unwrap_or_default() {
+                assert!(
+                    cells.iter().any(|cell| cell.coord == anchor),
+                    "{} has no bubble on anchor {anchor:?}",
+                    level.name
+                );
+            }
+            assert!(
+                level.min_width <= full_width(),
+                "{} is too wide",
+                level.name
+            );
+            if level.can_vary() {
+                let mut rng = StdRng::seed_from_u64(0);
+                let variant = random_variant(&cells, GameMode::Campaign.grid_bounds(), &mut rng);
+                assert_eq!(variant.len(), cells.len(), "{} lost bubbles", level.name);
+            }
+        }
+    }
+
+    #[test]
+    fn walls_stop_at_the_minimum_width() {
+        let level = LevelDefinition {
+            name: String::new(),
+            description: String::new(),
+            board: Vec::new(),
+            colors: Vec::new(),
+            goal: WinCondition::default(),
+            wall_speed: 10.0,
+            min_width: 300.0,
+            portals: Vec::new(),
+            bumpers: Vec::new(),
+            sticky: Vec::new(),
+            anchors: Vec::new(),
+            score_zones: Vec::new(),
+            script: Vec::new(),
+            stones: Vec::new(),
+            intro: None,
+            par: None,
+        };
+
+        let walls = level.close_in(Walls::default(), 1.0);
+        assert_eq!(walls.left, Walls::default().left + 10.0);
+        assert_eq!(walls.right, Walls::default().right - 10.0);
+
+        let walls = level.close_in(walls, 100.0);
+        assert_eq!(walls.width(), 300.0);
+        assert_eq!(walls.left, -150.0);
+    }
+}
@@ -0,0 +1,50 @@
+//! The game clock - gameplay time that stops while the game is paused.
+//!
+//! Gameplay timers (projectiles, animations, the shot clock, drills) read
+//! `Time<GameClock>` instead of the default `Time`, so they all freeze
+//! together when the game is paused or the run phase freezes the board,
+//! whether or not their system is in
+//! [`PausableSystems`](crate::PausableSystems). UI
+//! animations that should keep moving read `Time<Real>`.
+
+use std::time::Duration;
+
+use bevy::{prelude::*, time::TimeSystems};
+
+use crate::{Pause, screens::RunPhase};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<Time<GameClock>>();
+    app.add_systems(First, advance_game_clock.after(TimeSystems));
+}
+
+/// Context for the gameplay [`Time`]. Use it as `Res<Time<GameClock>>`.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct GameClock;
+
+/// Follow virtual time while unpaused; stand still while paused or frozen.
+fn advance_game_clock(
+    virtual_time: Res<Time<Virtual>>,
+    pause: Res<State<Pause>>,
+    phase: Option<Res<State<RunPhase>>>,
+    mut clock: ResMut<Time<GameClock>>,
+) {
+    let frozen = phase.is_some_and(|phase| phase.is_frozen());
+    let delta = if pause.get().0 || frozen {
+        Duration::ZERO
+    } else {
+        virtual_time.delta()
+    };
+    clock.advance_by(delta);
+}
+
+/// Run condition that's true once every `duration` of game time, like
+/// [`on_timer`](bevy::time::common_conditions::on_timer) but paused with
+/// the game.
+pub fn on_game_timer(duration: Duration) -> impl FnMut(Res<Time<GameClock>>) -> bool + Clone {
+    let mut timer = Timer::new(duration, TimerMode::Repeating);
+    move |clock: Res<Time<GameClock>>| {
+        timer.tick(clock.delta());
+        timer.just_finished()
+    }
+}
@@ -1,7 +1,10 @@
 //! Cluster detection - finding and popping matching bubbles.
 //!
-//! Uses flood fill (BFS) to find connected groups of same-colored bubbles.
+//! Uses flood fill (BFS) to find connected groups of same-colored bubbles,
+//! following the board's [`BoardLayout`] from cell to cell.
 //! When a cluster of 3+ is found, they pop!
+//! A landing next to a bomb pops everything within [`BOMB_RADIUS`] of it
+//! instead, setting off any other bombs caught in the blast.
 
 use bevy::prelude::*;
 use rand::Rng;
@@ -10,13 +13,20 @@ use std::collections::{HashSet, VecDeque};
 use crate::{asset_tracking::LoadResource, audio::sound_effect_with_settings};
 
 use super::{
-    bubble::{Bubble, BubbleColor},
-    grid::HexGrid,
-    hex::HexCoord,
+    bubble::{BOMB_RADIUS, BubbleColor, BubbleKind},
+    forecast::LandingForecasts,
+    gameplay_entities::GameplayEntity,
+    grid::{GridCommands, HexGrid},
+    hex::{BoardLayout, HexCoord},
+    messages::AddGameMessage,
     polish::PopAnimation,
-    projectile::BubbleLanded,
+    projectile::{BubbleLanded, Walls},
+    rng::GameRng,
+    score_zones::{FallingBubble, ScoreZones},
+    sticky_walls::StickyWalls,
+    wildfire::Wildfire,
 };
-use crate::{PausableSystems, screens::Screen};
+use crate::{PausableSystems, screens::InGame};
 
 /// Audio assets for game sound effects.
 #[derive(Resource, Asset, Clone, Reflect)]
@@ -32,6 +42,8 @@ pub struct GameAudioAssets {
     pub hmp: Handle<AudioSource>,
     #[dependency]
     pub my_little_snords: Handle<AudioSource>,
+    #[dependency]
+    pub snord: Handle<AudioSource>,
 }
 
 impl FromWorld for GameAudioAssets {
@@ -43,6 +55,7 @@ impl FromWorld for GameAudioAssets {
             ow: assets.load("audio/sound_effects/ow.ogg"),
             hmp: assets.load("audio/sound_effects/hmp.ogg"),
             my_little_snords: assets.load("audio/sound_effects/my_little_snords.ogg"),
+            snord: assets.load("audio/sound_effects/snord.ogg"),
         }
     }
 }
@@ -51,9 +64,14 @@ impl FromWorld for GameAudioAssets {
 const COMBO_SOUND_THRESHOLD: usize = 5;
 
 pub(super) fn plugin(app: &mut App) {
-    app.load_resource::<GameAudioAssets>();
-    app.add_message::<ClusterPopped>();
-    app.add_message::<FloatingBubblesRemoved>();
+    app.load_screen_resource::<GameAudioAssets>(InGame);
+    app.add_game_message::<ClusterPopped>("A matching cluster of 3+ bubbles popped");
+    app.add_game_message::<FloatingBubblesRemoved>("Bubbles cut off from the top fell");
+    app.init_resource::<FloatingScan>();
+    app.init_resource::<PopQueue>();
+    app.init_resource::<LevelAnchors>();
+
+    app.add_systems(OnEnter(InGame), reset_cluster_processing);
 
     // Configure system sets for proper ordering with command application between them
     app.configure_sets(
@@ -67,16 +85,16 @@ pub(super) fn plugin(app: &mut App) {
         ApplyDeferred
             .after(super::projectile::ProjectileSystems)
             .before(ClusterSystems)
-            .run_if(in_state(Screen::Gameplay)),
+            .run_if(in_state(InGame)),
     );
 
     app.add_systems(
         Update,
-        (detect_clusters, detect_floating_bubbles)
+        (detect_clusters, detect_floating_bubbles, start_queued_pops)
             .chain()
             .in_set(PausableSystems)
             .in_set(ClusterSystems)
-            .run_if(in_state(Screen::Gameplay)),
+            .run_if(in_state(InGame)),
     );
 }
 
@@ -85,7 +103,16 @@ pub(super) fn plugin(app: &mut App) {
 pub struct ClusterSystems;
 
 /// Minimum cluster size to pop (match-3).
-const MIN_CLUSTER_SIZE: usize = 3;
+pub const MIN_CLUSTER_SIZE: usize = 3;
+
+/// Cells the floating check may visit per frame. A full standard board is
+/// under 200 cells, so this only spreads the work out around pops on
+/// oversized boards.
+const FLOOD_FILL_BUDGET: usize = 512;
+
+/// Pop animations started per frame. Bigger drops cascade over a few frames
+/// instead of hitching one.
+const POPS_PER_FRAME: usize = 64;
 
 /// Message sent when a cluster is popped.
 #[derive(Message, Debug, Clone)]
@@ -103,90 +130,301 @@ pub struct FloatingBubblesRemoved {
     pub count: usize,
 }
 
-/// Detect and pop clusters when a bubble lands.
+/// In-progress search for bubbles cut off from every anchor: the top row (or
+/// the level's [`LevelAnchors`]) and the sticky walls.
+///
+/// Only bubbles next to a pop can lose their hold, so the search starts from
+/// them. It follows each one's connected group until it reaches an anchor or
+/// a group already known to hang (the group stays) or runs out of bubbles
+/// (the group falls), so the work is proportional to the groups around the
+/// pop rather than the whole board. That relies on everything else hanging
+/// already, so a new board, or one that lost an anchor or a bubble some other
+/// way than popping, gets a search from every bubble. The search is resumable so a
+/// huge group can be followed over several frames.
+#[derive(Resource, Debug, Default)]
+struct FloatingScan {
+    active: bool,
+    /// Everything hung from the anchors when the last search finished.
+    settled: bool,
+    /// The grid's [`HexGrid::removed`] count the search has accounted for.
+    removed: usize,
+    anchors: HashSet<HexCoord>,
+    /// Cells next to the pops, kept so the search can restart.
+    seeds: Vec<HexCoord>,
+    /// Seeds not checked yet.
+    pending: Vec<HexCoord>,
+    /// Cells known to still hang from an anchor.
+    anchored: HashSet<HexCoord>,
+    /// Cells known to be cut off.
+    floating: HashSet<HexCoord>,
+    /// The group being followed, and the cells in it not visited yet.
+    group: HashSet<HexCoord>,
+    frontier: VecDeque<HexCoord>,
+}
+
+impl FloatingScan {
+    /// Start a fresh search from the `seeds`, for groups that reach none of
+    /// the `anchors`.
+    fn start(&mut self, anchors: HashSet<HexCoord>, seeds: Vec<HexCoord>) {
+        self.active = true;
+        self.anchors = anchors;
+        self.pending = seeds.clone();
+        self.seeds = seeds;
+        self.anchored.clear();
+        self.floating.clear();
+        self.group.clear();
+        self.frontier.clear();
+    }
+
+    /// Visit up to `budget` cells. Returns true once the search is complete.
+    fn advance(&mut self, grid: &HexGrid, budget: usize) -> bool {
+        let mut visited = 0;
+        while visited < budget {
+            let Some(coord) = self.frontier.pop_front() else {
+                // The group ran out without reaching an anchor
+                self.floating.extend(self.group.drain());
+                if !self.next_group(grid) {
+                    return true;
+                }
+                continue;
+            };
+            visited += 1;
+            for neighbor in grid.layout.neighbors(coord) {
+                if !grid.is_occupied(neighbor) || self.group.contains(&neighbor) {
+                    continue;
+                }
+                if self.anchors.contains(&neighbor) || self.anchored.contains(&neighbor) {
+                    self.anchored.extend(self.group.drain());
+                    self.frontier.clear();
+                    break;
+                }
+                self.group.insert(neighbor);
+                self.frontier.push_back(neighbor);
+            }
+        }
+        false
+    }
+
+    /// Start following the next seed that isn't settled yet. Returns false
+    /// once every seed is.
+    fn next_group(&mut self, grid: &HexGrid) -> bool {
+        while let Some(seed) = self.pending.pop() {
+            if !grid.is_occupied(seed)
+                || self.anchored.contains(&seed)
+                || self.floating.contains(&seed)
+            {
+                continue;
+            }
+            if self.anchors.contains(&seed) {
+                self.anchored.insert(seed);
+                continue;
+            }
+            self.group.insert(seed);
+            self.frontier.push_back(seed);
+            return true;
+        }
+        false
+    }
+}
+
+/// Occupied cells next to the `popped` ones, where a floating search starts.
+fn pop_neighbors(grid: &HexGrid, popped: &[HexCoord]) -> Vec<HexCoord> {
+    let mut seen = HashSet::new();
+    popped
+        .iter()
+        .flat_map(|&coord| grid.layout.neighbors(coord))
+        .filter(|&coord| grid.is_occupied(coord) && seen.insert(coord))
+        .collect()
+}
+
+/// Every bubble connected to one of the `anchors`, checking the whole board
+/// in one go.
+pub fn find_anchored_bubbles(grid: &HexGrid, anchors: Vec<HexCoord>) -> HashSet<HexCoord> {
+    let mut scan = FloatingScan::default();
+    scan.start(anchors.into_iter().collect(), grid.coords().collect());
+    scan.advance(grid, usize::MAX);
+    scan.anchored
+}
+
+/// Bubbles left connected to none of the `anchors` once the `popped` cells
+/// are gone, checked in one go rather than spread over frames like
+/// [`detect_floating_bubbles`].
+pub fn find_floating_bubbles(
+    grid: &HexGrid,
+    anchors: Vec<HexCoord>,
+    popped: &[HexCoord],
+) -> HashSet<HexCoord> {
+    let mut scan = FloatingScan::default();
+    scan.start(anchors.into_iter().collect(), pop_neighbors(grid, popped));
+    scan.advance(grid, usize::MAX);
+    scan.floating
+}
+
+/// Cells a level holds its bubbles up from, in place of the top row. `None`
+/// (the default) anchors to the top row.
+///
+/// Only occupied cells anchor anything, so popping the bubble on an anchor
+/// cell lets whatever hung from it fall.
+#[derive(Resource, Debug, Clone, Default, PartialEq)]
+pub struct LevelAnchors(pub Option<Vec<HexCoord>>);
+
+impl LevelAnchors {
+    /// The occupied cells the floating check starts from.
+    pub fn roots(&self, grid: &HexGrid) -> Vec<HexCoord> {
+        match &self.0 {
+            Some(cells) => cells
+                .iter()
+                .copied()
+                .filter(|&coord| grid.is_occupied(coord))
+                .collect(),
+            None => grid.top_row_coords(),
+        }
+    }
+}
+
+/// Bubbles already taken off the grid, waiting to start their pop animation.
+#[derive(Resource, Debug, Default)]
+struct PopQueue(VecDeque<Entity>);
+
+/// Drop any half-finished work from the previous run.
+fn reset_cluster_processing(mut scan: ResMut<FloatingScan>, mut pop_queue: ResMut<PopQueue>) {
+    *scan = FloatingScan::default();
+    pop_queue.0.clear();
+}
+
+/// Detect and pop clusters when bubbles land.
+///
+/// All landings in a frame are resolved together (e.g. multi-ball), so a
+/// cluster touched by several of them pops once and is scored once.
 fn detect_clusters(
     mut commands: Commands,
-    mut grid: ResMut<HexGrid>,
-    bubble_query: Query<&Bubble>,
-    transform_query: Query<&Transform>,
+    mut grid: GridCommands,
+    mut pop_queue: ResMut<PopQueue>,
     mut landed_events: MessageReader<BubbleLanded>,
     mut popped_events: MessageWriter<ClusterPopped>,
+    mut forecasts: ResMut<LandingForecasts>,
     audio_assets: Option<Res<GameAudioAssets>>,
+    mut game_rng: ResMut<GameRng>,
+    wildfire: Res<Wildfire>,
 ) {
-    for event in landed_events.read() {
-        // Find the cluster starting from the landed bubble
-        let cluster = find_cluster(&grid, &bubble_query, event.coord, event.color);
-
-        if cluster.len() >= MIN_CLUSTER_SIZE {
-            info!(
-                "Found cluster of {} {:?} bubbles at {:?}",
-                cluster.len(),
-                event.color,
-                event.coord
-            );
+    let landed: Vec<BubbleLanded> = landed_events.read().cloned().collect();
+    let mut forecast = forecasts.take(&landed, &grid);
+    let mut resolved = HashSet::new();
+    let mut largest_cluster = 0;
+    let mut clusters_popped = 0;
+
+    for event in &landed {
+        // Already popped as part of an earlier landing's cluster this frame
+        if resolved.contains(&event.coord) {
+            continue;
+        }
 
-            // Remove all bubbles in the cluster (with pop animation)
-            for &coord in &cluster {
-                if let Some(entity) = grid.remove(coord) {
-                    // Get current scale for animation
-                    let current_scale = transform_query
-                        .get(entity)
-                        .map(|t| t.scale)
-                        .unwrap_or(Vec3::ONE);
-
-                    // Add pop animation instead of instant despawn
-                    commands
-                        .entity(entity)
-                        .insert(PopAnimation::new(current_scale));
-                }
+        // Bombs next to the landing go off, taking the landed bubble with
+        // them, but not stones. Forecasts don't know about bombs.
+        let blast = find_blast(
+            &grid.layout,
+            event.coord,
+            |coord| {
+                grid.kind(coord)
+                    .is_some_and(|kind| kind != BubbleKind::Stone)
+                    && !resolved.contains(&coord)
+            },
+            |coord| grid.kind(coord) == Some(BubbleKind::Bomb),
+        );
+        let cluster = if !blast.is_empty() {
+            info!("Bomb went off, taking {} bubbles", blast.len());
+            forecast = None;
+            blast
+        } else {
+            // Find the cluster starting from the landed bubble, unless it was
+            // worked out while the shot flew
+            match &forecast {
+                Some(forecast) => forecast.cluster.clone(),
+                None => find_cluster(
+                    &grid.layout,
+                    event.coord,
+                    wildfire.resolve(event.color),
+                    |coord| grid.color(coord).map(|color| wildfire.resolve(color)),
+                ),
             }
+        };
+        if cluster.len() < MIN_CLUSTER_SIZE {
+            continue;
+        }
 
-            // Play one death scream per cluster popped
-            if let Some(ref assets) = audio_assets {
-                let mut rng = rand::rng();
-                // Random scream selection
-                let scream = if rng.random_bool(0.5) {
-                    assets.death_scream_1.clone()
-                } else {
-                    assets.death_scream_2.clone()
-                };
-                // Random pitch (0.9 to 1.1) for subtle variety
-                let pitch = rng.random_range(0.9..1.1);
-                commands.spawn(sound_effect_with_settings(scream, pitch, 1.0));
-
-                // Play "my_little_snords" combo sound for big clusters (5+)
-                if cluster.len() >= COMBO_SOUND_THRESHOLD {
-                    let combo_pitch = rng.random_range(0.6..0.8);
-                    commands.spawn(sound_effect_with_settings(
-                        assets.my_little_snords.clone(),
-                        combo_pitch,
-                        1.0,
-                    ));
-                    info!(
-                        "Combo sound! Cluster of {} triggered my_little_snords",
-                        cluster.len()
-                    );
-                }
+        info!(
+            "Found cluster of {} {:?} bubbles at {:?}",
+            cluster.len(),
+            event.color,
+            event.coord
+        );
+
+        // Remove all bubbles in the cluster (pop animations start from the queue)
+        for &coord in &cluster {
+            resolved.insert(coord);
+            if let Some(entity) = grid.remove(coord) {
+                pop_queue.0.push_back(entity);
             }
+        }
+
+        largest_cluster = largest_cluster.max(cluster.len());
+        clusters_popped += 1;
+        popped_events.write(ClusterPopped {
+            color: event.color,
+            count: cluster.len(),
+            coords: cluster,
+        });
+    }
 
-            popped_events.write(ClusterPopped {
-                coords: cluster.clone(),
-                color: event.color,
-                count: cluster.len(),
-            });
+    if landed.is_empty() {
+        return;
+    }
+    forecasts.applied = forecast.filter(|forecast| !forecast.cluster.is_empty());
+    if clusters_popped > 1 {
+        info!(
+            "{} clusters popped together from {} landings",
+            clusters_popped,
+            landed.len()
+        );
+    }
+
+    let Some(assets) = audio_assets else {
+        return;
+    };
+    let rng = &mut game_rng.effects;
+    if clusters_popped > 0 {
+        // One death scream per batch, so simultaneous pops don't stack
+        let scream = if rng.random_bool(0.5) {
+            assets.death_scream_1.clone()
         } else {
-            // No match - play random "ow" or "hmp" sound at random pitch
-            if let Some(ref assets) = audio_assets {
-                let mut rng = rand::rng();
-                let pitch = rng.random_range(0.7..1.3);
-                let sound = if rng.random_bool(0.5) {
-                    assets.ow.clone()
-                } else {
-                    assets.hmp.clone()
-                };
-                commands.spawn(sound_effect_with_settings(sound, pitch, 1.0));
-            }
+            assets.death_scream_2.clone()
+        };
+        // Random pitch (0.9 to 1.1) for subtle variety
+        let pitch = rng.random_range(0.9..1.1);
+        commands.spawn(sound_effect_with_settings(scream, pitch, 1.0));
+
+        // Play "my_little_snords" combo sound for big clusters (5+)
+        if largest_cluster >= COMBO_SOUND_THRESHOLD {
+            let combo_pitch = rng.random_range(0.6..0.8);
+            commands.spawn(sound_effect_with_settings(
+                assets.my_little_snords.clone(),
+                combo_pitch,
+                1.0,
+            ));
+            info!(
+                "Combo sound! Cluster of {} triggered my_little_snords",
+                largest_cluster
+            );
         }
+    } else {
+        // No match - play random "ow" or "hmp" sound at random pitch
+        let pitch = rng.random_range(0.7..1.3);
+        let sound = if rng.random_bool(0.5) {
+            assets.ow.clone()
+        } else {
+            assets.hmp.clone()
+        };
+        commands.spawn(sound_effect_with_settings(sound, pitch, 1.0));
     }
 }
 
@@ -195,12 +433,15 @@ fn detect_clusters(
 /// Note: The start coordinate is always included in the cluster because we know
 /// its color from the BubbleLanded event. This bypasses Bevy's deferred commands
 /// timing issue where the newly spawned bubble's Bubble component may not exist
-/// yet when we query it.
-fn find_cluster(
-    grid: &HexGrid,
-    bubble_query: &Query<&Bubble>,
+/// yet when we query it. It also lets a shot be previewed before it lands.
+///
+/// `color_at` looks up the bubble color at a cell, if there is one. Bombs
+/// and stones have none, so clusters go around them.
+pub fn find_cluster(
+    layout: &impl BoardLayout,
     start: HexCoord,
     target_color: BubbleColor,
+    color_at: impl Fn(HexCoord) -> Option<BubbleColor>,
 ) -> Vec<HexCoord> {
     let mut cluster = Vec::new();
     let mut visited = HashSet::new();
@@ -212,7 +453,7 @@ fn find_cluster(
     visited.insert(start);
 
     // Start exploring from the starting position's neighbors
-    for neighbor in start.neighbors() {
+    for neighbor in layout.neighbors(start) {
         if !visited.contains(&neighbor) {
             visited.insert(neighbor);
             queue.push_back(neighbor);
@@ -222,14 +463,11 @@ fn find_cluster(
     // Continue BFS for neighbors
     while let Some(coord) = queue.pop_front() {
         // Check if this cell has a bubble of the right color
-        if let Some(entity) = grid.get(coord)
-            && let Ok(bubble) = bubble_query.get(entity)
-            && bubble.color == target_color
-        {
+        if color_at(coord) == Some(target_color) {
             cluster.push(coord);
 
             // Add unvisited neighbors to the queue
-            for neighbor in coord.neighbors() {
+            for neighbor in layout.neighbors(coord) {
                 if !visited.contains(&neighbor) {
                     visited.insert(neighbor);
                     queue.push_back(neighbor);
@@ -241,51 +479,171 @@ fn find_cluster(
     cluster
 }
 
-/// Detect and remove floating bubbles (not connected to top row).
+/// The color a wildcard landing at `start` matches: whichever neighboring
+/// color makes the biggest cluster with it, or None if it has no colored
+/// neighbors. Since [`find_cluster`] always counts the start, the wildcard
+/// then clusters as that color.
+pub fn wildcard_color(
+    layout: &impl BoardLayout,
+    start: HexCoord,
+    color_at: impl Fn(HexCoord) -> Option<BubbleColor>,
+) -> Option<BubbleColor> {
+    let mut best: Option<(usize, BubbleColor)> = None;
+    for color in layout.neighbors(start).filter_map(&color_at) {
+        let size = find_cluster(layout, start, color, &color_at).len();
+        if best.is_none_or(|(best_size, _)| size > best_size) {
+            best = Some((size, color));
+        }
+    }
+    best.map(|(_, color)| color)
+}
+
+/// Everything a landing at `start` blows up: nothing unless a bomb is next
+/// to it, otherwise `start` and every occupied cell within [`BOMB_RADIUS`]
+/// of a bomb that goes off. Bombs caught in a blast go off too.
+///
+/// `occupied` says whether a cell can be blown up, and `is_bomb` whether it
+/// holds a bomb.
+pub fn find_blast(
+    layout: &impl BoardLayout,
+    start: HexCoord,
+    occupied: impl Fn(HexCoord) -> bool,
+    is_bomb: impl Fn(HexCoord) -> bool,
+) -> Vec<HexCoord> {
+    let mut bombs: VecDeque<HexCoord> = layout
+        .neighbors(start)
+        .filter(|&coord| occupied(coord) && is_bomb(coord))
+        .collect();
+    if bombs.is_empty() {
+        return Vec::new();
+    }
+
+    let mut blast = vec![start];
+    let mut caught: HashSet<HexCoord> = HashSet::from([start]);
+    for &bomb in &bombs {
+        caught.insert(bomb);
+        blast.push(bomb);
+    }
+    while let Some(bomb) = bombs.pop_front() {
+        for coord in cells_within(layout, bomb, BOMB_RADIUS) {
+            if !occupied(coord) || !caught.insert(coord) {
+                continue;
+            }
+            blast.push(coord);
+            if is_bomb(coord) {
+                bombs.push_back(coord);
+            }
+        }
+    }
+    blast
+}
+
+/// Every cell within `radius` steps of `center`, including it.
+fn cells_within(layout: &impl BoardLayout, center: HexCoord, radius: i32) -> HashSet<HexCoord> {
+    let mut cells = HashSet::from([center]);
+    let mut ring = vec![center];
+    for _ in 0..radius {
+        ring = ring
+            .iter()
+            .flat_map(|&coord| layout.neighbors(coord))
+            .filter(|&coord| cells.insert(coord))
+            .collect();
+    }
+    cells
+}
+
+/// Detect and remove floating bubbles (not connected to an anchor).
+///
+/// The search runs under [`FLOOD_FILL_BUDGET`] and picks up where it left off
+/// next frame. It restarts if the grid changes before it finishes. A pop
+/// that came from a [`LandingForecast`](super::forecast::LandingForecast)
+/// skips the search and drops what the forecast found.
 fn detect_floating_bubbles(
     mut commands: Commands,
-    mut grid: ResMut<HexGrid>,
-    transform_query: Query<&Transform>,
+    mut grid: GridCommands,
+    mut scan: ResMut<FloatingScan>,
+    mut pop_queue: ResMut<PopQueue>,
     mut popped_events: MessageReader<ClusterPopped>,
     mut floating_events: MessageWriter<FloatingBubblesRemoved>,
+    level_anchors: Res<LevelAnchors>,
+    sticky: Res<StickyWalls>,
+    walls: Res<Walls>,
+    zones: Res<ScoreZones>,
+    mut forecasts: ResMut<LandingForecasts>,
+    mut game_rng: ResMut<GameRng>,
 ) {
     // Only run after a cluster is popped
-    let mut should_check = false;
-    for _ in popped_events.read() {
-        should_check = true;
-    }
-
-    if !should_check {
-        return;
+    let popped: Vec<HexCoord> = popped_events
+        .read()
+        .flat_map(|event| event.coords.iter().copied())
+        .collect();
+    let mut forecast_floating = None;
+    if !popped.is_empty() || (scan.active && grid.is_changed()) {
+        let mut anchors: HashSet<HexCoord> = level_anchors.roots(&grid).into_iter().collect();
+        anchors.extend(sticky.anchors(&grid, grid.origin_y(), *walls));
+        let lost_anchor = scan
+            .anchors
+            .iter()
+            .any(|coord| !anchors.contains(coord) && !popped.contains(coord));
+        let lost_bubble = grid.removed() != scan.removed + popped.len();
+        scan.removed = grid.removed();
+
+        match forecasts.applied.take() {
+            // Worked out while the shot flew, on this same board
+            Some(forecast)
+                if !scan.active && forecast.cluster == popped && forecast.anchors == anchors =>
+            {
+                scan.anchors = anchors;
+                forecast_floating = Some(forecast.floating);
+            }
+            _ => {
+                let seeds = if !scan.settled || lost_anchor || lost_bubble {
+                    scan.settled = false;
+                    grid.coords().collect()
+                } else {
+                    // Keep the seeds of a search that hadn't finished
+                    let mut seeds = if scan.active {
+                        std::mem::take(&mut scan.seeds)
+                    } else {
+                        Vec::new()
+                    };
+                    seeds.extend(pop_neighbors(&grid, &popped));
+                    seeds
+                };
+                scan.start(anchors, seeds);
+            }
+        }
     }
 
-    // Find all bubbles connected to the top row
-    let anchored = find_anchored_bubbles(&grid);
-
-    // Find floating bubbles (in grid but not anchored)
-    let mut floating = Vec::new();
-    for coord in grid.coords().collect::<Vec<_>>() {
-        if !anchored.contains(&coord) {
-            floating.push(coord);
+    let mut floating: Vec<HexCoord> = match forecast_floating {
+        Some(floating) => floating,
+        None => {
+            if !scan.active || !scan.advance(&grid, FLOOD_FILL_BUDGET) {
+                return;
+            }
+            scan.active = false;
+            scan.floating.drain().collect()
         }
-    }
+    };
+    scan.settled = true;
 
     if !floating.is_empty() {
         info!("Found {} floating bubbles to remove", floating.len());
 
-        // Remove floating bubbles (with pop animation)
+        // Remove floating bubbles. They fall into the score zones if the
+        // level has them, or pop in place (animations start from the queue).
+        // In board order, so a seed always gives each bubble the same drift
+        floating.sort_by_key(|coord| (coord.r, coord.q));
         for &coord in &floating {
             if let Some(entity) = grid.remove(coord) {
-                // Get current scale for animation
-                let current_scale = transform_query
-                    .get(entity)
-                    .map(|t| t.scale)
-                    .unwrap_or(Vec3::ONE);
-
-                // Add pop animation instead of instant despawn
-                commands
-                    .entity(entity)
-                    .insert(PopAnimation::new(current_scale));
+                if zones.0.is_empty() {
+                    pop_queue.0.push_back(entity);
+                } else {
+                    commands.entity(entity).try_insert((
+                        FallingBubble::with_random_drift(&mut game_rng.gameplay),
+                        GameplayEntity,
+                    ));
+                }
             }
         }
 
@@ -294,28 +652,138 @@ fn detect_floating_bubbles(
             count: floating.len(),
         });
     }
+    scan.removed = grid.removed();
 }
 
-/// Find all bubbles connected to the top row using BFS.
-fn find_anchored_bubbles(grid: &HexGrid) -> HashSet<HexCoord> {
-    let mut anchored = HashSet::new();
-    let mut queue = VecDeque::new();
-
-    // Start from all bubbles in the top row (r = 0)
-    for coord in grid.top_row_coords() {
-        queue.push_back(coord);
-        anchored.insert(coord);
+/// Start pop animations for removed bubbles, up to [`POPS_PER_FRAME`] at a time.
+fn start_queued_pops(
+    mut commands: Commands,
+    mut pop_queue: ResMut<PopQueue>,
+    transform_query: Query<&Transform>,
+) {
+    let count = pop_queue.0.len().min(POPS_PER_FRAME);
+    for entity in pop_queue.0.drain(..count) {
+        // Get current scale for animation
+        let current_scale = transform_query
+            .get(entity)
+            .map(|t| t.scale)
+            .unwrap_or(Vec3::ONE);
+
+        // Add pop animation instead of instant despawn
+        commands
+            .entity(entity)
+            .try_insert(PopAnimation::new(current_scale));
     }
+}
 
-    // BFS to find all connected bubbles
-    while let Some(coord) = queue.pop_front() {
-        for neighbor in coord.neighbors() {
-            if !anchored.contains(&neighbor) && grid.is_occupied(neighbor) {
-                anchored.insert(neighbor);
-                queue.push_back(neighbor);
-            }
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+    use crate::game::hex::OddRPointy;
+
+    #[test]
+    fn floating_search_matches_a_full_scan() {
+        // Two columns hanging from the top row, joined by a bridge at row 2,
+        // and a bubble under the right column
+        let cells = [
+            (0, 0),
+            (0, 1),
+            (0, 2),
+            (1, 2),
+            (2, 2),
+            (3, 2),
+            (3, 1),
+            (3, 0),
+            (3, 3),
+            (3, 4),
+        ];
+        let mut grid = HexGrid::default();
+        for (q, r) in cells {
+            grid.insert(HexCoord::new(q, r), Entity::PLACEHOLDER);
         }
+
+        // Cutting the left column leaves everything hanging from the right
+        let popped = [HexCoord::new(0, 1)];
+        grid.remove(popped[0]);
+        let anchors = grid.top_row_coords();
+        assert!(find_floating_bubbles(&grid, anchors.clone(), &popped).is_empty());
+
+        // Cutting the right column too drops the bridge and what hangs off it
+        let popped = [HexCoord::new(3, 1)];
+        grid.remove(popped[0]);
+        let anchors = grid.top_row_coords();
+        let floating = find_floating_bubbles(&grid, anchors.clone(), &popped);
+        let anchored = find_anchored_bubbles(&grid, anchors);
+        let expected: HashSet<HexCoord> = grid
+            .coords()
+            .filter(|coord| !anchored.contains(coord))
+            .collect();
+        assert_eq!(floating, expected);
+        assert_eq!(floating.len(), 6);
     }
 
-    anchored
+    #[test]
+    fn wildcards_take_the_color_of_the_biggest_cluster() {
+        let board: HashMap<HexCoord, BubbleColor> = HashMap::from([
+            (HexCoord::new(0, 0), BubbleColor::Green),
+            (HexCoord::new(1, 0), BubbleColor::Red),
+            (HexCoord::new(2, 0), BubbleColor::Red),
+        ]);
+        let color_at = |coord: HexCoord| board.get(&coord).copied();
+
+        // Next to one green and a row of two reds
+        assert_eq!(
+            wildcard_color(&OddRPointy, HexCoord::new(0, 1), color_at),
+            Some(BubbleColor::Red)
+        );
+        // Only green is in reach
+        assert_eq!(
+            wildcard_color(&OddRPointy, HexCoord::new(-1, 1), color_at),
+            Some(BubbleColor::Green)
+        );
+        assert_eq!(
+            wildcard_color(&OddRPointy, HexCoord::new(5, 5), color_at),
+            None
+        );
+    }
+
+    #[test]
+    fn bombs_clear_everything_within_two_cells() {
+        // A full board with a bomb at (0, 2), and another just inside its
+        // blast at (2, 2)
+        let board: HashSet<HexCoord> = (-6..=6)
+            .flat_map(|q| (0..8).map(move |r| HexCoord::new(q, r)))
+            .collect();
+        let occupied = |coord: HexCoord| board.contains(&coord);
+
+        // Nowhere near a bomb: nothing happens
+        let single = [HexCoord::new(0, 2)];
+        let is_bomb = |coord: HexCoord| single.contains(&coord);
+        assert!(find_blast(&OddRPointy, HexCoord::new(4, 6), occupied, is_bomb).is_empty());
+
+        // One bomb clears the 19 cells within two steps of it
+        let blast: HashSet<HexCoord> =
+            find_blast(&OddRPointy, HexCoord::new(0, 3), occupied, is_bomb)
+                .into_iter()
+                .collect();
+        assert_eq!(blast.len(), 19);
+        assert!(blast.contains(&HexCoord::new(-2, 2)));
+        assert!(blast.contains(&HexCoord::new(2, 2)));
+        assert!(!blast.contains(&HexCoord::new(3, 2)));
+        assert!(blast.contains(&HexCoord::new(1, 4)));
+        assert!(!blast.contains(&HexCoord::new(2, 4)));
+
+        // A second bomb in the blast goes off too
+        let chained = [HexCoord::new(0, 2), HexCoord::new(2, 2)];
+        let is_bomb = |coord: HexCoord| chained.contains(&coord);
+        let blast = find_blast(&OddRPointy, HexCoord::new(0, 3), occupied, is_bomb);
+        assert!(blast.contains(&HexCoord::new(4, 2)));
+        assert_eq!(
+            blast.len(),
+            blast.iter().collect::<HashSet<_>>().len(),
+            "no cell is blown up twice"
+        );
+    }
 }
@@ -0,0 +1,202 @@
+//! Color clears - popping the last bubble of a color.
+//!
+//! [`ColorCounts`] tracks how many bubbles of each color are on the grid and
+//! is recounted whenever the grid changes. When a pop takes a color from some
+//! to none, a [`ColorCleared`] message is sent: the score adds a bonus, a
+//! "COLOR CLEAR!" banner appears, and that color's snords rain down behind
+//! the board.
+
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+use rand::Rng;
+
+use super::{
+    bubble::{Bubble, BubbleColor, GameAssets, sprite_scale},
+    clock::GameClock,
+    cluster::{ClusterPopped, ClusterSystems, FloatingBubblesRemoved},
+    grid::HexGrid,
+    messages::AddGameMessage,
+    polish::{ComboText, combo_text},
+    rng::GameRng,
+};
+use crate::{
+    PausableSystems,
+    accessibility::full_motion,
+    display::{GraphicsQuality, particles_enabled},
+    screens::InGame,
+    theme::GameFont,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<ColorCounts>();
+    app.add_game_message::<ColorCleared>("The last bubble of a color was popped");
+
+    app.add_systems(OnEnter(InGame), reset_color_counts);
+    app.add_systems(
+        Update,
+        (
+            count_colors.run_if(resource_changed::<HexGrid>),
+            (
+                spawn_color_clear_banner,
+                spawn_snord_rain.run_if(full_motion.and(particles_enabled)),
+            ),
+        )
+            .chain()
+            .after(ClusterSystems)
+            .in_set(PausableSystems)
+            .run_if(in_state(InGame)),
+    );
+    app.add_systems(
+        Update,
+        animate_snord_rain
+            .in_set(PausableSystems)
+            .run_if(in_state(InGame)),
+    );
+}
+
+/// Snords in the rain for each color clear.
+const RAIN_COUNT: usize = 30;
+
+/// Rain falls from above the window to below it.
+const RAIN_TOP: f32 = 340.0;
+const RAIN_BOTTOM: f32 = -340.0;
+
+/// Horizontal spread of the rain (the window is 800px wide).
+const RAIN_HALF_WIDTH: f32 = 380.0;
+
+/// Message sent when the last bubble of a color leaves the grid by popping
+/// or falling.
+#[derive(Message, Debug, Clone)]
+pub struct ColorCleared {
+    pub color: BubbleColor,
+}
+
+/// How many bubbles of each color are on the grid.
+#[derive(Resource, Debug, Clone, Default, PartialEq)]
+pub struct ColorCounts(pub HashMap<BubbleColor, usize>);
+
+impl ColorCounts {
+    /// Bubbles of `color` on the grid.
+    pub fn get(&self, color: BubbleColor) -> usize {
+        self.0.get(&color).copied().unwrap_or(0)
+    }
+}
+
+/// A snord falling behind the board after a color clear.
+#[derive(Component)]
+struct SnordRain {
+    velocity: Vec2,
+    spin: f32,
+}
+
+fn reset_color_counts(mut counts: ResMut<ColorCounts>) {
+    *counts = ColorCounts::default();
+}
+
+/// Recount colors on the grid and report any color that popping just cleared.
+///
+/// Runs after cluster detection, so the bubble that landed this frame has
+/// been spawned. Colors that disappear any other way (new board, continue,
+/// restored snapshot) only update the counts.
+fn count_colors(
+    grid: Res<HexGrid>,
+    bubbles: Query<&Bubble>,
+    mut counts: ResMut<ColorCounts>,
+    mut cluster_events: MessageReader<ClusterPopped>,
+    mut floating_events: MessageReader<FloatingBubblesRemoved>,
+    mut cleared_events: MessageWriter<ColorCleared>,
+) {
+    let mut new_counts = ColorCounts::default();
+    for (_, &entity) in grid.iter() {
+        if let Some(color) = bubbles.get(entity).ok().and_then(Bubble::match_color) {
+            *new_counts.0.entry(color).or_default() += 1;
+        }
+    }
+
+    let popped = cluster_events.read().count() + floating_events.read().count() > 0;
+    if popped {
+        for color in BubbleColor::ALL {
+            if counts.get(color) > 0 && new_counts.get(color) == 0 {
+                info!("Color clear! No {:?} bubbles left", color);
+                cleared_events.write(ColorCleared { color });
+            }
+        }
+    }
+    *counts = new_counts;
+}
+
+/// Show a banner for each cleared color.
+fn spawn_color_clear_banner(
+    mut commands: Commands,
+    mut cleared_events: MessageReader<ColorCleared>,
+    game_font: Res<GameFont>,
+) {
+    for (i, event) in cleared_events.read().enumerate() {
+        // Stack banners if several colors clear at once
+        let anchor = Vec2::new(0.0, 60.0 - i as f32 * 50.0);
+        commands.spawn(combo_text(
+            "Color Clear Text",
+            "COLOR CLEAR!",
+            ComboText::new(anchor, 1.8, 40.0, event.color.to_color()),
+            game_font.0.clone(),
+            40.0,
+        ));
+    }
+}
+
+/// Rain the cleared color's snords down behind the board.
+fn spawn_snord_rain(
+    mut commands: Commands,
+    mut cleared_events: MessageReader<ColorCleared>,
+    game_assets: Res<GameAssets>,
+    grid: Res<HexGrid>,
+    quality: Res<GraphicsQuality>,
+    mut game_rng: ResMut<GameRng>,
+) {
+    let rng = &mut game_rng.effects;
+    let count = (RAIN_COUNT as f32 * quality.0.particles()).round() as usize;
+    for event in cleared_events.read() {
+        let image = game_assets.sprite_for(event.color);
+        for _ in 0..count {
+            let x = rng.random_range(-RAIN_HALF_WIDTH..RAIN_HALF_WIDTH);
+            // Stagger the start so they don't fall as one line
+            let y = RAIN_TOP + rng.random_range(0.0..400.0);
+            let scale = sprite_scale(grid.hex_size) * rng.random_range(0.6..1.0);
+            commands.spawn((
+                Name::new("Snord Rain"),
+                SnordRain {
+                    velocity: Vec2::new(
+                        rng.random_range(-30.0..30.0),
+                        -rng.random_range(250.0..450.0),
+                    ),
+                    spin: rng.random_range(-4.0..4.0),
+                },
+                Sprite {
+                    image: image.clone(),
+                    color: Color::WHITE.with_alpha(0.7),
+                    ..default()
+                },
+                // In front of the game panel, behind the bubbles
+                Transform::from_xyz(x, y, -0.5).with_scale(Vec3::splat(scale)),
+                DespawnOnExit(InGame),
+            ));
+        }
+    }
+}
+
+/// Move falling snords and despawn them once they leave the screen.
+fn animate_snord_rain(
+    mut commands: Commands,
+    time: Res<Time<GameClock>>,
+    mut query: Query<(Entity, &mut Transform, &SnordRain)>,
+) {
+    let dt = time.delta_secs();
+    for (entity, mut transform, rain) in &mut query {
+        transform.translation += rain.velocity.extend(0.0) * dt;
+        transform.rotate_z(rain.spin * dt);
+        if transform.translation.y < RAIN_BOTTOM {
+            commands.entity(entity).despawn();
+        }
+    }
+}
@@ -0,0 +1,219 @@
+//! Companion snords: picked before a run from the companions menu, unlocked
+//! as the player's [`Profile`] grows.
+//!
+//! Each one grants a small passive for the whole run:
+//! - Pip starts the run with a random tier-1 power-up
+//! - Goldie adds 5% to every point scored
+//! - Boing adds a grace bounce on top of the usual one
+//!
+//! The effects hook into the run's own setup (power-up, grace, and score
+//! resets), reading the choice straight from the profile. The companion
+//! itself sits beside the shooter, hopping when clusters pop and shivering
+//! when the board gets close to the danger line.
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    bubble::{GameAssets, load_game_assets},
+    clock::GameClock,
+    cluster::ClusterPopped,
+    mood::SnordMood,
+    shooter::SHOOTER_Y,
+};
+use crate::{PausableSystems, display::idle_animations_enabled, profile::Profile, screens::InGame};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(OnEnter(InGame), spawn_companion.after(load_game_assets));
+    app.add_systems(
+        Update,
+        (
+            cheer_on_pops,
+            animate_companion.run_if(idle_animations_enabled),
+        )
+            .chain()
+            .in_set(PausableSystems)
+            .run_if(in_state(InGame)),
+    );
+}
+
+/// Share of every point scored Goldie adds on top, in percent.
+const SCORE_BONUS_PERCENT: u32 = 5;
+
+/// Where the companion sits, left of the shooter.
+const COMPANION_POSITION: Vec3 = Vec3::new(-150.0, SHOOTER_Y - 8.0, 1.0);
+
+/// Size of the companion sprite.
+const COMPANION_SIZE: f32 = 44.0;
+
+/// How long a cheer hop lasts, in seconds.
+const CHEER_SECS: f32 = 0.5;
+
+/// A snord that comes along for the run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Companion {
+    Pip,
+    Goldie,
+    Boing,
+}
+
+impl Companion {
+    pub const ALL: [Companion; 3] = [Companion::Pip, Companion::Goldie, Companion::Boing];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Companion::Pip => "Pip",
+            Companion::Goldie => "Goldie",
+            Companion::Boing => "Boing",
+        }
+    }
+
+    pub fn description(self) -> &'static str {
+        match self {
+            Companion::Pip => "Starts each run with a random tier 1 power-up.",
+            Companion::Goldie => "Adds 5% to every point scored.",
+            Companion::Boing => "One extra grace bounce per run.",
+        }
+    }
+
+    /// What the player has to do to unlock this companion.
+    pub fn unlock_hint(self) -> &'static str {
+        match self {
+            Companion::Pip => "Finish a run",
+            Companion::Goldie => "Score 5000 in one run",
+            Companion::Boing => "Clear 3 campaign levels",
+        }
+    }
+
+    /// Whether `profile` has earned this companion.
+    pub fn is_unlocked(self, profile: &Profile) -> bool {
+        match self {
+            Companion::Pip => !profile.recent_runs.is_empty(),
+            Companion::Goldie => profile.best_scores.values().any(|&best| best >= 5000),
+            Companion::Boing => profile.campaign_cleared >= 3,
+        }
+    }
+
+    /// The face the companion wears.
+    fn image(self, assets: &GameAssets) -> Handle<Image> {
+        match self {
+            Companion::Pip => assets.happy_image.clone(),
+            Companion::Goldie => assets.enamored_image.clone(),
+            Companion::Boing => assets.derpy_image.clone(),
+        }
+    }
+}
+
+/// Points Goldie adds on top of `earned`, if she's along for the run.
+pub fn bonus_points(companion: Option<Companion>, earned: u32) -> u32 {
+    match companion {
+        Some(Companion::Goldie) => earned * SCORE_BONUS_PERCENT / 100,
+        _ => 0,
+    }
+}
+
+/// Grace bounces the companion adds to the run's usual ones.
+pub fn extra_grace(companion: Option<Companion>) -> u32 {
+    match companion {
+        Some(Companion::Boing) => 1,
+        _ => 0,
+    }
+}
+
+/// The companion's sprite beside the shooter.
+#[derive(Component, Debug, Default)]
+struct CompanionSprite {
+    /// Time left on the current cheer, in seconds.
+    cheer: f32,
+    /// How high the current cheer hops.
+    height: f32,
+}
+
+fn spawn_companion(mut commands: Commands, profile: Res<Profile>, assets: Res<GameAssets>) {
+    let Some(companion) = profile.companion else {
+        return;
+    };
+    commands.spawn((
+        Name::new(format!("Companion {}", companion.label())),
+        CompanionSprite::default(),
+        Sprite {
+            image: companion.image(&assets),
+            custom_size: Some(Vec2::splat(COMPANION_SIZE)),
+            ..default()
+        },
+        Transform::from_translation(COMPANION_POSITION),
+        DespawnOnExit(InGame),
+    ));
+}
+
+/// Start a hop for each popped cluster, higher for bigger ones.
+fn cheer_on_pops(
+    mut popped: MessageReader<ClusterPopped>,
+    mut companions: Query<&mut CompanionSprite>,
+) {
+    let Some(biggest) = popped.read().map(|event| event.count).max() else {
+        return;
+    };
+    for mut companion in &mut companions {
+        companion.cheer = CHEER_SECS;
+        companion.height = 8.0 + biggest.min(10) as f32 * 2.0;
+    }
+}
+
+/// Hop while cheering, and shiver along with a terrified loaded snord.
+fn animate_companion(
+    time: Res<Time<GameClock>>,
+    mood: Res<SnordMood>,
+    mut companions: Query<(&mut Transform, &mut CompanionSprite)>,
+) {
+    let t = time.elapsed_secs();
+    for (mut transform, mut companion) in &mut companions {
+        companion.cheer = (companion.cheer - time.delta_secs()).max(0.0);
+        *transform = Transform::from_translation(COMPANION_POSITION);
+
+        if companion.cheer > 0.0 {
+            let progress = 1.0 - companion.cheer / CHEER_SECS;
+            transform.translation.y += (progress * std::f32::consts::PI).sin() * companion.height;
+        } else if *mood == SnordMood::Terrified {
+            transform.translation.x += (t * 50.0).sin() * 1.5;
+            transform.rotate_z((t * 37.0).sin() * 0.05);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        game::{mode::GameMode, telemetry::RunOutcome},
+        profile::RecentRun,
+    };
+
+    #[test]
+    fn companions_unlock_as_the_profile_grows() {
+        let mut profile = Profile::default();
+        assert!(Companion::ALL.iter().all(|c| !c.is_unlocked(&profile)));
+
+        profile.push_run(RecentRun {
+            mode: GameMode::Classic,
+            score: 6000,
+            level: 3,
+            duration_secs: 90.0,
+            outcome: RunOutcome::GridReachedDanger,
+            seed: None,
+        });
+        assert!(Companion::Pip.is_unlocked(&profile));
+        assert!(Companion::Goldie.is_unlocked(&profile));
+        assert!(!Companion::Boing.is_unlocked(&profile));
+
+        profile.campaign_cleared = 3;
+        assert!(Companion::Boing.is_unlocked(&profile));
+    }
+
+    #[test]
+    fn only_goldie_adds_score() {
+        assert_eq!(bonus_points(Some(Companion::Goldie), 200), 10);
+        assert_eq!(bonus_points(Some(Companion::Pip), 200), 0);
+        assert_eq!(bonus_points(None, 200), 0);
+    }
+}
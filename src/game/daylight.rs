@@ -0,0 +1,146 @@
+//! Day and night - the backdrop slowly changes over a long run.
+//!
+//! Every [`LEVELS_PER_DAY`] levels the backdrop goes from day to dusk to night
+//! and back to day, easing along with each shot rather than jumping at
+//! descents. The palettes are in `theme::palette`; this tints the clear color,
+//! the game panel, and the background doodles to match.
+
+use bevy::prelude::*;
+
+use super::{GamePanel, bubble::BackgroundDoodle, state::GameLevel};
+use crate::{
+    screens::InGame,
+    theme::palette::{DAY, DUSK, DaylightPalette, NIGHT},
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<Daylight>();
+
+    app.add_systems(OnExit(InGame), restore_backdrop);
+    app.add_systems(
+        Update,
+        (
+            update_daylight.run_if(resource_changed::<GameLevel>),
+            (
+                apply_daylight.run_if(resource_changed::<Daylight>),
+                tint_new_doodles,
+            ),
+        )
+            .chain()
+            .run_if(in_state(InGame)),
+    );
+}
+
+/// Levels in one full day, from morning through the night and back.
+pub const LEVELS_PER_DAY: f32 = 10.0;
+
+/// The colors for the current point in the day.
+#[derive(Resource, Debug, Clone, Copy, PartialEq)]
+pub struct Daylight(pub DaylightPalette);
+
+impl Default for Daylight {
+    fn default() -> Self {
+        Self(DAY)
+    }
+}
+
+/// How far through the day a run is, from 0 (morning) up to 1, counting the
+/// shots toward the next descent as part of a level.
+pub fn time_of_day(level: &GameLevel) -> f32 {
+    let round = if level.shots_until_descent == 0 {
+        0.0
+    } else {
+        (level.shots_this_round as f32 / level.shots_until_descent as f32).min(1.0)
+    };
+    let levels = level.level.saturating_sub(1) as f32 + round;
+    (levels / LEVELS_PER_DAY).fract()
+}
+
+/// The palette at `time` through the day, blending between day, dusk, and
+/// night.
+pub fn palette_at(time: f32) -> DaylightPalette {
+    const STOPS: [DaylightPalette; 4] = [DAY, DUSK, NIGHT, DAY];
+    let position = time.rem_euclid(1.0) * (STOPS.len() - 1) as f32;
+    let index = (position as usize).min(STOPS.len() - 2);
+    let t = position - index as f32;
+    let (from, to) = (STOPS[index], STOPS[index + 1]);
+    DaylightPalette {
+        backdrop: blend(from.backdrop, to.backdrop, t),
+        light: blend(from.light, to.light, t),
+        doodle: blend(from.doodle, to.doodle, t),
+    }
+}
+
+fn blend(from: Color, to: Color, t: f32) -> Color {
+    Oklaba::from(from).mix(&Oklaba::from(to), t).into()
+}
+
+fn update_daylight(level: Res<GameLevel>, mut daylight: ResMut<Daylight>) {
+    daylight.set_if_neq(Daylight(palette_at(time_of_day(&level))));
+}
+
+fn apply_daylight(
+    daylight: Res<Daylight>,
+    mut clear_color: ResMut<ClearColor>,
+    mut panels: Query<&mut Sprite, (With<GamePanel>, Without<BackgroundDoodle>)>,
+    mut doodles: Query<&mut Sprite, With<BackgroundDoodle>>,
+) {
+    clear_color.0 = daylight.0.backdrop;
+    for mut sprite in &mut panels {
+        sprite.color = daylight.0.light;
+    }
+    for mut sprite in &mut doodles {
+        sprite.color = daylight.0.doodle;
+    }
+}
+
+/// Doodles are spawned after the first tint, so catch them up.
+fn tint_new_doodles(
+    daylight: Res<Daylight>,
+    mut doodles: Query<&mut Sprite, Added<BackgroundDoodle>>,
+) {
+    for mut sprite in &mut doodles {
+        sprite.color = daylight.0.doodle;
+    }
+}
+
+/// Menus are always in daylight.
+fn restore_backdrop(mut clear_color: ResMut<ClearColor>, mut daylight: ResMut<Daylight>) {
+    clear_color.0 = DAY.backdrop;
+    *daylight = Daylight::default();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::state::STARTING_SHOTS_PER_DESCENT;
+
+    fn level(level: u32, shots_this_round: u32) -> GameLevel {
+        GameLevel {
+            level,
+            shots_until_descent: STARTING_SHOTS_PER_DESCENT,
+            shots_this_round,
+            wasted_shots_this_round: 0,
+        }
+    }
+
+    fn close(a: Color, b: Color) -> bool {
+        let (a, b) = (a.to_srgba(), b.to_srgba());
+        (a.red - b.red).abs() < 1e-3
+            && (a.green - b.green).abs() < 1e-3
+            && (a.blue - b.blue).abs() < 1e-3
+    }
+
+    #[test]
+    fn a_day_passes_every_ten_levels() {
+        assert_eq!(time_of_day(&level(1, 0)), 0.0);
+        assert_eq!(time_of_day(&level(1, 4)), 0.05);
+        assert_eq!(time_of_day(&level(6, 0)), 0.5);
+        assert_eq!(time_of_day(&level(11, 0)), 0.0);
+
+        assert!(close(palette_at(0.0).backdrop, DAY.backdrop));
+        assert!(close(palette_at(1.0 / 3.0).doodle, DUSK.doodle));
+        assert!(close(palette_at(2.0 / 3.0).light, NIGHT.light));
+        assert!(close(palette_at(0.9999).backdrop, DAY.backdrop));
+    }
+}
@@ -1,32 +1,53 @@
 //! Debug visualization for the hexagonal grid.
 //!
-//! Toggle with the 'D' key during gameplay.
+//! Toggle with the debug key (F3 unless rebound) during gameplay.
 //! Shows:
 //! - Hex cell outlines for all valid positions
 //! - Occupied cells highlighted
 //! - Coordinate labels (when zoomed in)
+//! - A heatmap of where shots have landed this session, to spot snapping bias
+//!   in `closest_empty_cell` (Backspace resets it, 'E' exports it as CSV)
+//! - The board before and after the last descent (see
+//!   [`descent_diff`](super::descent_diff))
+
+use std::collections::HashMap;
+#[cfg(not(target_arch = "wasm32"))]
+use std::fs;
 
 use bevy::{color::palettes::css, input::common_conditions::input_just_pressed, prelude::*};
 
-use super::{
-    grid::HexGrid,
-    hex::{HEX_SIZE, HexCoord},
+use super::{grid::HexGrid, hex::HexCoord, projectile::BubbleLanded};
+use crate::{
+    controls::{HEATMAP_EXPORT_KEY, HEATMAP_RESET_KEY, InputAction, action_just_pressed},
+    screens::InGame,
 };
-use crate::screens::Screen;
 
 pub(super) fn plugin(app: &mut App) {
     app.init_resource::<DebugGridVisible>();
+    app.init_resource::<LandingHeatmap>();
 
-    // Toggle debug with 'D' key
+    // Count landings all session, so the heatmap is ready when debug is opened
+    app.add_systems(Update, count_landings.run_if(in_state(InGame)));
     app.add_systems(
         Update,
-        toggle_debug.run_if(in_state(Screen::Gameplay).and(input_just_pressed(KeyCode::KeyD))),
+        (
+            draw_landing_heatmap,
+            reset_landing_heatmap.run_if(input_just_pressed(HEATMAP_RESET_KEY)),
+            export_landing_heatmap.run_if(input_just_pressed(HEATMAP_EXPORT_KEY)),
+        )
+            .run_if(in_state(InGame).and(debug_visible)),
+    );
+
+    // Toggle debug with its bound key (A and D aim)
+    app.add_systems(
+        Update,
+        toggle_debug.run_if(in_state(InGame).and(action_just_pressed(InputAction::DebugToggle))),
     );
 
     // Draw debug grid when visible
     app.add_systems(
         Update,
-        draw_debug_grid.run_if(in_state(Screen::Gameplay).and(debug_visible)),
+        draw_debug_grid.run_if(in_state(InGame).and(debug_visible)),
     );
 }
 
@@ -34,7 +55,7 @@ pub(super) fn plugin(app: &mut App) {
 #[derive(Resource, Default)]
 pub struct DebugGridVisible(pub bool);
 
-fn debug_visible(debug: Res<DebugGridVisible>) -> bool {
+pub(super) fn debug_visible(debug: Res<DebugGridVisible>) -> bool {
     debug.0
 }
 
@@ -68,12 +89,87 @@ fn draw_debug_grid(mut gizmos: Gizmos, grid: Res<HexGrid>) {
                 css::WHITE.with_alpha(0.15)
             };
 
-            draw_hex_outline(&mut gizmos, coord, HEX_SIZE, color);
+            draw_hex_outline(&mut gizmos, coord, grid.hex_size, color);
         }
     }
 
     // Draw grid bounds outline
-    draw_bounds_outline(&mut gizmos, bounds, HEX_SIZE);
+    draw_bounds_outline(&mut gizmos, bounds, grid.hex_size);
+}
+
+/// How many shots have landed in each cell this session.
+#[derive(Resource, Debug, Default)]
+pub struct LandingHeatmap {
+    pub counts: HashMap<HexCoord, u32>,
+}
+
+impl LandingHeatmap {
+    /// The counts as CSV, one `q,r,count` row per cell, top row first.
+    pub fn to_csv(&self) -> String {
+        let mut cells: Vec<(&HexCoord, &u32)> = self.counts.iter().collect();
+        cells.sort_by_key(|(coord, _)| (coord.r, coord.q));
+        let mut csv = String::from("q,r,count\n");
+        for (coord, count) in cells {
+            csv.push_str(&format!("{},{},{}\n", coord.q, coord.r, count));
+        }
+        csv
+    }
+}
+
+fn count_landings(
+    mut landed_events: MessageReader<BubbleLanded>,
+    mut heatmap: ResMut<LandingHeatmap>,
+) {
+    for event in landed_events.read() {
+        *heatmap.counts.entry(event.coord).or_default() += 1;
+    }
+}
+
+/// Tint each cell by how often shots landed there, from yellow to red.
+fn draw_landing_heatmap(mut gizmos: Gizmos, heatmap: Res<LandingHeatmap>, grid: Res<HexGrid>) {
+    let Some(&max) = heatmap.counts.values().max() else {
+        return;
+    };
+    for (&coord, &count) in &heatmap.counts {
+        let heat = count as f32 / max as f32;
+        let color = css::YELLOW
+            .mix(&css::RED, heat)
+            .with_alpha(0.25 + 0.5 * heat);
+        // Gizmos can't fill shapes, so nest outlines to shade the cell
+        for ring in 1..=4 {
+            draw_hex_outline(&mut gizmos, coord, grid.hex_size * ring as f32 / 5.0, color);
+        }
+    }
+}
+
+fn reset_landing_heatmap(mut heatmap: ResMut<LandingHeatmap>) {
+    heatmap.counts.clear();
+    info!("Landing heatmap reset");
+}
+
+/// Write the heatmap next to the high scores, or to the log on the web.
+fn export_landing_heatmap(heatmap: Res<LandingHeatmap>) {
+    let csv = heatmap.to_csv();
+
+    #[cfg(target_arch = "wasm32")]
+    info!("Landing heatmap:\n{}", csv);
+
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        let Some(dir) = dirs::data_local_dir().map(|dir| dir.join("snord")) else {
+            info!("Landing heatmap:\n{}", csv);
+            return;
+        };
+        if let Err(e) = fs::create_dir_all(&dir) {
+            warn!("Failed to create heatmap directory: {}", e);
+            return;
+        }
+        let path = dir.join("landing_heatmap.csv");
+        match fs::write(&path, csv) {
+            Ok(()) => info!("Exported landing heatmap to {:?}", path),
+            Err(e) => warn!("Failed to write landing heatmap: {}", e),
+        }
+    }
 }
 
 /// Draw a hexagon outline at the given coordinates.
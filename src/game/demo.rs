@@ -0,0 +1,249 @@
+//! The attract-mode bot.
+//!
+//! While [`Screen::Demo`] is active, a generated board from a fixed seed is
+//! played by the simulator's greedy policy. The bot turns the shooter toward
+//! the chosen cell, then fires straight at it. Bank shots aren't modeled, so
+//! now and then a shot sticks short of its target. Queue colors come from
+//! a fresh `GameRng` seed each run, so runs match in layout but not shot for shot.
+//!
+//! The board is generated in the background while the title screen sits
+//! idle, and the bot picks each target in the background too, so neither
+//! holds up a frame.
+
+use bevy::prelude::*;
+use rand::{Rng, SeedableRng, rngs::StdRng};
+
+use super::{
+    bubble::{BubbleColor, StartingBoard},
+    clock::GameClock,
+    generator::{GeneratorParams, generate},
+    grid::HexGrid,
+    hex::{GridOffset, HexCoord},
+    mode::GameMode,
+    projectile::{FireProjectile, Projectile},
+    shooter::{AimDirection, LoadedBubble, LoadedWildcard, MAX_AIM_ANGLE, Shooter, ShooterState},
+    snapshot::GridCell,
+    state::GameLevel,
+};
+use crate::{
+    PausableSystems,
+    compute::{AddComputeMessage, SpawnCompute},
+    screens::Screen,
+    sim::{
+        board::Board,
+        policy::{GreedyPolicy, ShotPolicy},
+    },
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<DemoBot>();
+    app.init_resource::<DemoBoard>();
+    app.add_compute_message::<DemoBoardGenerated>();
+    app.add_compute_message::<DemoTargetChosen>();
+
+    app.add_systems(OnEnter(Screen::Title), prepare_demo_board);
+    app.add_systems(OnEnter(Screen::Demo), start_demo);
+    app.add_systems(OnExit(Screen::Demo), clear_demo_board);
+    app.add_systems(
+        Update,
+        (
+            store_demo_board,
+            (store_demo_target, play_demo_shot.in_set(PausableSystems))
+                .chain()
+                .run_if(in_state(Screen::Demo)),
+        ),
+    );
+}
+
+/// Seed for the demo board and the bot's tie-breaks.
+const DEMO_SEED: u64 = 0x5_40_4D;
+
+/// Generator level for the demo board: a few colors, some overhangs.
+const DEMO_LEVEL: u32 = 4;
+
+/// Seconds the bot spends turning toward its target before firing.
+const AIM_SECONDS: f32 = 0.6;
+
+/// The bot's state between shots.
+#[derive(Resource)]
+struct DemoBot {
+    rng: StdRng,
+    /// Where the bot is aiming, once it has picked a cell.
+    target: Option<Vec2>,
+    /// A target is being picked in the background.
+    searching: bool,
+    aim_timer: f32,
+}
+
+impl Default for DemoBot {
+    fn default() -> Self {
+        Self {
+            rng: StdRng::seed_from_u64(DEMO_SEED),
+            target: None,
+            searching: false,
+            aim_timer: 0.0,
+        }
+    }
+}
+
+/// The seeded demo board, once it has been generated.
+#[derive(Resource, Debug, Default)]
+struct DemoBoard(Option<Vec<GridCell>>);
+
+/// A demo board finished generating in the background.
+#[derive(Message, Debug, Clone)]
+struct DemoBoardGenerated(Vec<GridCell>);
+
+/// The bot finished picking a cell in the background, or found none.
+#[derive(Message, Debug, Clone)]
+struct DemoTargetChosen(Option<HexCoord>);
+
+/// The board from [`DEMO_SEED`]. Every demo plays the same one.
+fn generate_demo_board() -> Vec<GridCell> {
+    let mut rng = StdRng::seed_from_u64(DEMO_SEED);
+    let params = GeneratorParams::for_level(DEMO_LEVEL);
+    generate(&params, GameMode::Classic.grid_bounds(), &mut rng)
+}
+
+/// Generate the demo board while the title screen waits for input.
+fn prepare_demo_board(mut commands: Commands, board: Res<DemoBoard>) {
+    if board.0.is_none() {
+        commands.spawn_compute(Screen::Title, || {
+            Some(DemoBoardGenerated(generate_demo_board()))
+        });
+    }
+}
+
+fn store_demo_board(
+    mut board: ResMut<DemoBoard>,
+    mut generated: MessageReader<DemoBoardGenerated>,
+) {
+    if let Some(DemoBoardGenerated(cells)) = generated.read().last() {
+        board.0 = Some(cells.clone());
+    }
+}
+
+/// Lay out the seeded board before the bubbles spawn, generating it here if
+/// the title screen didn't get to it.
+///
+/// `OnEnter(Screen::Demo)` runs before the board's `OnEnter(InGame)` systems.
+fn start_demo(
+    mut bot: ResMut<DemoBot>,
+    mut demo_board: ResMut<DemoBoard>,
+    mut board: ResMut<StartingBoard>,
+) {
+    *bot = DemoBot::default();
+    let cells = demo_board.0.get_or_insert_with(generate_demo_board);
+    board.0 = Some(cells.clone());
+    info!("Demo started");
+}
+
+fn clear_demo_board(mut board: ResMut<StartingBoard>) {
+    board.0 = None;
+}
+
+/// Aim at the cell the bot picked, once it has one.
+fn store_demo_target(
+    mut bot: ResMut<DemoBot>,
+    mut chosen: MessageReader<DemoTargetChosen>,
+    grid: Res<HexGrid>,
+    grid_offset: Res<GridOffset>,
+) {
+    let Some(DemoTargetChosen(cell)) = chosen.read().last() else {
+        return;
+    };
+    bot.searching = false;
+    bot.target = cell.map(|cell| cell.to_pixel_with_offset(grid.hex_size, grid_offset.y));
+    bot.aim_timer = 0.0;
+}
+
+/// Pick a target when the shooter is ready, swing toward it, then fire.
+fn play_demo_shot(
+    mut commands: Commands,
+    time: Res<Time<GameClock>>,
+    mut bot: ResMut<DemoBot>,
+    grid: Res<HexGrid>,
+    grid_offset: Res<GridOffset>,
+    colors: Query<&BubbleColor>,
+    mut shooter_query: Query<
+        (
+            &Transform,
+            &mut AimDirection,
+            &mut ShooterState,
+            &LoadedBubble,
+            &LoadedWildcard,
+        ),
+        With<Shooter>,
+    >,
+    projectiles: Query<(), With<Projectile>>,
+    mut fire_events: MessageWriter<FireProjectile>,
+    mut level: ResMut<GameLevel>,
+) {
+    let Ok((transform, mut aim, mut state, loaded, wildcard)) = shooter_query.single_mut() else {
+        return;
+    };
+    if *state != ShooterState::Ready || !projectiles.is_empty() {
+        return;
+    }
+    let shooter_pos = transform.translation.truncate();
+
+    let bot = bot.as_mut();
+    let Some(target) = bot.target else {
+        if !bot.searching {
+            let board = sim_board(&grid, &grid_offset, &colors);
+            let color = loaded.0;
+            let mut rng = StdRng::seed_from_u64(bot.rng.random());
+            commands.spawn_compute(Screen::Demo, move || {
+                Some(DemoTargetChosen(
+                    GreedyPolicy.choose_target(&board, color, &mut rng),
+                ))
+            });
+            bot.searching = true;
+        }
+        return;
+    };
+
+    bot.aim_timer += time.delta_secs();
+    let goal = clamp_aim(target - shooter_pos);
+    let t = (bot.aim_timer / AIM_SECONDS).min(1.0);
+    aim.0 = aim.0.lerp(goal, t).normalize_or(goal);
+    if t < 1.0 {
+        return;
+    }
+
+    aim.0 = goal;
+    fire_events.write(FireProjectile {
+        position: shooter_pos,
+        direction: goal,
+        color: loaded.0,
+        wildcard: wildcard.0,
+    });
+    *state = ShooterState::Reloading;
+    level.shots_this_round += 1;
+    bot.target = None;
+}
+
+/// The grid as the simulator sees it. Stones and bombs stay in the grid
+/// without a color, so they block shots without joining clusters.
+pub(super) fn sim_board(
+    grid: &HexGrid,
+    grid_offset: &GridOffset,
+    colors: &Query<&BubbleColor>,
+) -> Board {
+    let colors = grid
+        .iter()
+        .filter_map(|(&coord, &entity)| Some((coord, *colors.get(entity).ok()?)))
+        .collect();
+    Board {
+        grid: grid.clone(),
+        colors,
+        top_r: grid_offset.ceiling_row(grid.hex_size),
+    }
+}
+
+/// Point upward within the shooter's aim limits, like the player's aim.
+fn clamp_aim(direction: Vec2) -> Vec2 {
+    let angle = direction.x.atan2(direction.y.max(0.1));
+    let clamped = angle.clamp(-MAX_AIM_ANGLE, MAX_AIM_ANGLE);
+    Vec2::new(clamped.sin(), clamped.cos())
+}
@@ -0,0 +1,366 @@
+//! Descent diffs - what the last descent did to the board, for debugging.
+//!
+//! Each descent captures the board just before and just after
+//! [`handle_descent`] runs: every bubble's cell, where it's drawn, and where
+//! its cell says it should be drawn. With the debug overlay on, the two
+//! are drawn side by side in the margins, before on the left and after on the
+//! right, with each bubble marked as added, moved, or removed.
+//!
+//! Bubbles keep their cells through a descent, so a bubble is "moved" if its
+//! cell changed or it isn't drawn where [`BoardLayout::to_pixel`] puts its
+//! cell. Either one points at an offset or row parity bug.
+//!
+//! The after capture is also audited for neighbors that aren't drawn one hex
+//! apart, which is how a row with the wrong parity shows up next to the rows
+//! around it. Those are logged and linked in red on the after panel.
+
+use std::collections::HashMap;
+
+use bevy::{color::palettes::css, prelude::*};
+
+use super::{
+    bubble::Bubble,
+    campaign::campaign_active,
+    debug::debug_visible,
+    grid::HexGrid,
+    hex::{BoardLayout, GridOffset, HexCoord, SQRT_3},
+    mode::pressure_mode,
+    projectile::TOP_WALL,
+    state::{TriggerDescent, handle_descent},
+};
+use crate::{PausableSystems, screens::InGame};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<DescentDiff>();
+
+    app.add_systems(OnEnter(InGame), clear_descent_diff);
+    app.add_systems(
+        Update,
+        (
+            capture_before_descent.before(handle_descent),
+            capture_after_descent.after(handle_descent),
+        )
+            .in_set(PausableSystems)
+            .run_if(
+                in_state(InGame)
+                    .and(pressure_mode)
+                    .and(not(campaign_active)),
+            ),
+    );
+    app.add_systems(
+        Update,
+        draw_descent_diff.run_if(in_state(InGame).and(debug_visible)),
+    );
+}
+
+/// How far off its cell a bubble can be drawn before it counts as moved.
+const POSITION_TOLERANCE: f32 = 0.5;
+
+/// World units to panel units.
+const PANEL_SCALE: f32 = 0.3;
+
+/// Centers of the before and after panels, in the margins beside the walls.
+const BEFORE_PANEL_X: f32 = -322.0;
+const AFTER_PANEL_X: f32 = 322.0;
+
+/// A bubble as it was captured.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DiffCell {
+    pub entity: Entity,
+    pub coord: HexCoord,
+    /// Where the bubble was drawn.
+    pub position: Vec2,
+    /// Where its cell should be drawn.
+    pub expected: Vec2,
+}
+
+impl DiffCell {
+    /// Whether the bubble is drawn away from its cell.
+    fn misplaced(&self) -> bool {
+        self.position.distance(self.expected) > POSITION_TOLERANCE
+    }
+}
+
+/// What the descent did to one bubble.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CellChange {
+    Unchanged,
+    /// Spawned by the descent.
+    Added,
+    /// Changed cells, or was drawn away from its cell.
+    Moved,
+    /// Gone after the descent.
+    Removed,
+}
+
+impl CellChange {
+    fn color(self) -> Color {
+        match self {
+            CellChange::Unchanged => css::LIGHT_GRAY.with_alpha(0.6).into(),
+            CellChange::Added => css::LIMEGREEN.into(),
+            CellChange::Moved => css::ORANGE.into(),
+            CellChange::Removed => css::RED.into(),
+        }
+    }
+}
+
+/// The board before and after the last descent.
+#[derive(Resource, Debug, Clone, Default)]
+pub struct DescentDiff {
+    pub before: Vec<DiffCell>,
+    pub after: Vec<DiffCell>,
+    pub hex_size: f32,
+    /// Neighbors after the descent that aren't drawn one hex apart.
+    pub broken: Vec<(HexCoord, HexCoord)>,
+}
+
+impl DescentDiff {
+    /// Each bubble before the descent, and what became of it.
+    pub fn before_changes(&self) -> Vec<(DiffCell, CellChange)> {
+        let after: HashMap<Entity, &DiffCell> =
+            self.after.iter().map(|cell| (cell.entity, cell)).collect();
+        self.before
+            .iter()
+            .map(|cell| (*cell, change(Some(cell), after.get(&cell.entity).copied())))
+            .collect()
+    }
+
+    /// Each bubble after the descent, and how it got there.
+    pub fn after_changes(&self) -> Vec<(DiffCell, CellChange)> {
+        let before: HashMap<Entity, &DiffCell> =
+            self.before.iter().map(|cell| (cell.entity, cell)).collect();
+        self.after
+            .iter()
+            .map(|cell| (*cell, change(before.get(&cell.entity).copied(), Some(cell))))
+            .collect()
+    }
+
+    /// How many bubbles had `kind` of change.
+    pub fn count(&self, kind: CellChange) -> usize {
+        let changes = match kind {
+            CellChange::Removed => self.before_changes(),
+            _ => self.after_changes(),
+        };
+        changes.iter().filter(|(_, change)| *change == kind).count()
+    }
+}
+
+/// What happened to a bubble, from where it was and is. Only where it ends up
+/// drawn matters: a bubble drawn off its cell before the descent and put back
+/// by it is fine.
+fn change(before: Option<&DiffCell>, after: Option<&DiffCell>) -> CellChange {
+    match (before, after) {
+        (_, None) => CellChange::Removed,
+        (_, Some(after)) if after.misplaced() => CellChange::Moved,
+        (None, Some(_)) => CellChange::Added,
+        (Some(before), Some(after)) if before.coord != after.coord => CellChange::Moved,
+        (Some(_), Some(_)) => CellChange::Unchanged,
+    }
+}
+
+/// Neighboring cells whose bubbles aren't drawn one hex apart, each pair once.
+pub fn broken_neighbors(
+    layout: &impl BoardLayout,
+    hex_size: f32,
+    cells: &[DiffCell],
+) -> Vec<(HexCoord, HexCoord)> {
+    let positions: HashMap<HexCoord, Vec2> = cells
+        .iter()
+        .map(|cell| (cell.coord, cell.position))
+        .collect();
+    let spacing = hex_size * SQRT_3;
+    let mut broken = Vec::new();
+    for cell in cells {
+        for neighbor in layout.neighbors(cell.coord) {
+            let Some(position) = positions.get(&neighbor) else {
+                continue;
+            };
+            if (neighbor.r, neighbor.q) > (cell.coord.r, cell.coord.q)
+                && (cell.position.distance(*position) - spacing).abs() > POSITION_TOLERANCE
+            {
+                broken.push((cell.coord, neighbor));
+            }
+        }
+    }
+    broken
+}
+
+fn clear_descent_diff(mut diff: ResMut<DescentDiff>) {
+    *diff = DescentDiff::default();
+}
+
+/// Every bubble on the grid, where it's drawn, and where it should be.
+fn capture(
+    grid: &HexGrid,
+    grid_offset: &GridOffset,
+    bubbles: &Query<&Transform, With<Bubble>>,
+) -> Vec<DiffCell> {
+    let mut cells: Vec<DiffCell> = grid
+        .iter()
+        .filter_map(|(&coord, &entity)| {
+            Some(DiffCell {
+                entity,
+                coord,
+                position: bubbles.get(entity).ok()?.translation.truncate(),
+                expected: grid.layout.to_pixel(coord, grid.hex_size, grid_offset.y),
+            })
+        })
+        .collect();
+    cells.sort_by_key(|cell| (cell.coord.r, cell.coord.q));
+    cells
+}
+
+fn capture_before_descent(
+    mut descent_events: MessageReader<TriggerDescent>,
+    grid: Res<HexGrid>,
+    grid_offset: Res<GridOffset>,
+    bubbles: Query<&Transform, With<Bubble>>,
+    mut diff: ResMut<DescentDiff>,
+) {
+    if descent_events.read().count() == 0 {
+        return;
+    }
+    diff.before = capture(&grid, &grid_offset, &bubbles);
+    diff.hex_size = grid.hex_size;
+}
+
+fn capture_after_descent(
+    mut descent_events: MessageReader<TriggerDescent>,
+    grid: Res<HexGrid>,
+    grid_offset: Res<GridOffset>,
+    bubbles: Query<&Transform, With<Bubble>>,
+    mut diff: ResMut<DescentDiff>,
+) {
+    if descent_events.read().count() == 0 {
+        return;
+    }
+    diff.after = capture(&grid, &grid_offset, &bubbles);
+    diff.broken = broken_neighbors(&grid.layout, grid.hex_size, &diff.after);
+    for (cell, neighbor) in &diff.broken {
+        warn!("Descent diff: {cell} and {neighbor} aren't drawn one hex apart");
+    }
+    info!(
+        "Descent diff: {} added, {} moved, {} removed",
+        diff.count(CellChange::Added),
+        diff.count(CellChange::Moved),
+        diff.count(CellChange::Removed)
+    );
+}
+
+/// Where a world position goes on a panel centered at `panel_x`, with the
+/// top wall along the panel's top.
+fn to_panel(position: Vec2, panel_x: f32) -> Vec2 {
+    Vec2::new(
+        panel_x + position.x * PANEL_SCALE,
+        TOP_WALL + (position.y - TOP_WALL) * PANEL_SCALE,
+    )
+}
+
+/// Draw the board before and after the last descent in the margins.
+fn draw_descent_diff(mut gizmos: Gizmos, diff: Res<DescentDiff>) {
+    if diff.after.is_empty() {
+        return;
+    }
+    let radius = diff.hex_size * PANEL_SCALE * 0.9;
+    for (panel_x, changes) in [
+        (BEFORE_PANEL_X, diff.before_changes()),
+        (AFTER_PANEL_X, diff.after_changes()),
+    ] {
+        for (cell, change) in changes {
+            let center = to_panel(cell.position, panel_x);
+            gizmos.circle_2d(center, radius, change.color());
+            // Show where a moved bubble should have been
+            if change == CellChange::Moved {
+                gizmos.arrow_2d(to_panel(cell.expected, panel_x), center, change.color());
+            }
+        }
+    }
+
+    let after: HashMap<HexCoord, Vec2> = diff
+        .after
+        .iter()
+        .map(|cell| (cell.coord, to_panel(cell.position, AFTER_PANEL_X)))
+        .collect();
+    for (cell, neighbor) in &diff.broken {
+        if let (Some(&start), Some(&end)) = (after.get(cell), after.get(neighbor)) {
+            gizmos.line_2d(start, end, CellChange::Removed.color());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::hex::OddRPointy;
+
+    fn cell(index: u32, q: i32, r: i32, position: Vec2, expected: Vec2) -> DiffCell {
+        DiffCell {
+            entity: Entity::from_raw_u32(index).unwrap(),
+            coord: HexCoord::new(q, r),
+            position,
+            expected,
+        }
+    }
+
+    #[test]
+    fn sorts_bubbles_into_added_moved_and_removed() {
+        let down = Vec2::new(0.0, -30.0);
+        let diff = DescentDiff {
+            before: vec![
+                cell(1, 0, 0, Vec2::ZERO, Vec2::ZERO),
+                cell(2, 1, 0, Vec2::X, Vec2::X),
+                cell(3, 2, 0, Vec2::Y, Vec2::Y),
+                cell(4, 3, 0, Vec2::ONE, Vec2::ONE),
+            ],
+            after: vec![
+                // Kept its cell and moved down with the board
+                cell(1, 0, 0, down, down),
+                // Drawn half a row off its cell
+                cell(2, 1, 0, Vec2::X, Vec2::X + down),
+                // Changed cells
+                cell(3, 2, 1, Vec2::Y + down, Vec2::Y + down),
+                // The new row
+                cell(5, 0, -1, Vec2::ZERO, Vec2::ZERO),
+            ],
+            hex_size: 20.0,
+            broken: Vec::new(),
+        };
+
+        let before: Vec<CellChange> = diff.before_changes().iter().map(|(_, c)| *c).collect();
+        assert_eq!(
+            before,
+            [
+                CellChange::Unchanged,
+                CellChange::Moved,
+                CellChange::Moved,
+                CellChange::Removed
+            ]
+        );
+        assert_eq!(diff.count(CellChange::Added), 1);
+        assert_eq!(diff.count(CellChange::Moved), 2);
+        assert_eq!(diff.count(CellChange::Removed), 1);
+        assert_eq!(diff.count(CellChange::Unchanged), 1);
+    }
+
+    #[test]
+    fn finds_neighbors_drawn_off_their_spacing() {
+        let size = 20.0;
+        let at = |q, r| HexCoord::new(q, r).to_pixel(size);
+        let mut cells = vec![
+            cell(1, 0, 0, at(0, 0), at(0, 0)),
+            cell(2, 1, 0, at(1, 0), at(1, 0)),
+            cell(3, 0, -1, at(0, -1), at(0, -1)),
+        ];
+        assert!(broken_neighbors(&OddRPointy, size, &cells).is_empty());
+
+        // A row drawn as if it had the other parity
+        cells[2].position.x -= size * SQRT_3 / 2.0;
+        assert_eq!(
+            broken_neighbors(&OddRPointy, size, &cells),
+            [
+                (HexCoord::new(0, -1), HexCoord::new(0, 0)),
+                (HexCoord::new(0, -1), HexCoord::new(1, 0)),
+            ]
+        );
+    }
+}
@@ -0,0 +1,115 @@
+//! Descent preview - a ghost of the board one row lower.
+//!
+//! With the assist turned on in the settings, every bubble gets a faint ring
+//! where it will sit after the next descent, from the last shot before one
+//! until the board moves. The rings are one mesh drawn with the highlight
+//! [`GlowMaterial`], so the ghost board is a single draw call however full
+//! the board is.
+
+use bevy::prelude::*;
+
+use super::{
+    bubble::{Bubble, BubbleColor},
+    grid::HexGrid,
+    hex::GridOffset,
+    highlight::{GlowMaterial, GlowParams, Highlight, glow_mesh},
+    mode::pressure_mode,
+    powerups::UnlockedPowerUps,
+    state::GameLevel,
+};
+use crate::{accessibility::AccessibilitySettings, screens::InGame};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(
+        Update,
+        update_descent_preview
+            .run_if(in_state(InGame).and(pressure_mode))
+            .run_if(
+                resource_changed::<AccessibilitySettings>
+                    .or(resource_changed::<GameLevel>)
+                    .or(resource_changed::<HexGrid>)
+                    .or(resource_changed::<GridOffset>),
+            ),
+    );
+}
+
+/// How bright the ghost rings are, from 0 to 1.
+const GHOST_INTENSITY: f32 = 0.45;
+
+/// Ring color for bombs and stones, which have no color of their own.
+const COLORLESS_GHOST: Color = Color::srgb(0.85, 0.85, 0.85);
+
+/// The entity that draws the ghost board.
+#[derive(Component, Debug)]
+struct DescentPreview;
+
+/// Whether the next shot is the last before the board descends, or already
+/// on its way.
+fn descent_is_next(level: &GameLevel, powerups: &UnlockedPowerUps) -> bool {
+    level.shots_this_round + 1 >= level.descent_threshold(powerups)
+}
+
+/// Ghost every bubble one row lower while a descent is one shot away.
+fn update_descent_preview(
+    mut commands: Commands,
+    settings: Res<AccessibilitySettings>,
+    level: Res<GameLevel>,
+    powerups: Res<UnlockedPowerUps>,
+    grid: Res<HexGrid>,
+    grid_offset: Res<GridOffset>,
+    bubbles: Query<&Bubble>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<GlowMaterial>>,
+    mut preview: Query<(&Mesh2d, &mut Visibility), With<DescentPreview>>,
+) {
+    let ghosts: Vec<Highlight> = if settings.descent_preview && descent_is_next(&level, &powerups) {
+        grid.iter()
+            .map(|(&coord, &entity)| Highlight {
+                coord,
+                color: bubbles
+                    .get(entity)
+                    .ok()
+                    .and_then(Bubble::match_color)
+                    .map_or(COLORLESS_GHOST, BubbleColor::to_color),
+                intensity: GHOST_INTENSITY,
+            })
+            .collect()
+    } else {
+        Vec::new()
+    };
+    // A descent moves the whole board down a row
+    let ghost_origin_y = grid_offset.y - grid.hex_size * 1.5;
+
+    let Ok((mesh, mut visibility)) = preview.single_mut() else {
+        if !ghosts.is_empty() {
+            commands.spawn((
+                Name::new("Descent Preview"),
+                DescentPreview,
+                Mesh2d(meshes.add(glow_mesh(&ghosts, grid.hex_size, ghost_origin_y))),
+                MeshMaterial2d(materials.add(GlowMaterial {
+                    params: GlowParams {
+                        // Slower and softer than a highlight, so it reads
+                        // as a ghost rather than a match
+                        pulse: 2.0,
+                        pulse_depth: 0.2,
+                        ..GlowMaterial::default().params
+                    },
+                })),
+                // Over the bubbles, under the highlights
+                Transform::from_xyz(0.0, 0.0, 0.9),
+                DespawnOnExit(InGame),
+            ));
+        }
+        return;
+    };
+
+    // An empty mesh has nothing to draw, so keep the old one and hide it
+    if ghosts.is_empty() {
+        *visibility = Visibility::Hidden;
+        return;
+    }
+    *visibility = Visibility::Inherited;
+    if let Some(mesh) = meshes.get_mut(&mesh.0) {
+        *mesh = glow_mesh(&ghosts, grid.hex_size, ghost_origin_y);
+    }
+}
@@ -0,0 +1,391 @@
+//! Dialogue - a snord's portrait and typewritten lines over the board,
+//! played when a campaign level starts and when a level's script calls
+//! `talk(scene)`, like a boss showing up.
+//!
+//! Scenes are in `assets/data/dialogue.json` (mods can replace it), keyed by
+//! id:
+//!
+//! ```json
+//! { "grump": [{ "speaker": "The Big Grump", "portrait": "angry", "text": "Hmph." }] }
+//! ```
+//!
+//! `portrait` is a snord sprite in `assets/images/`. Clicking, Space, or
+//! Enter finishes the line being typed, or moves on to the next one; Skip
+//! ends the scene. The board is frozen while a scene plays.
+
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use super::campaign::{ActiveLevel, CampaignLevels};
+use crate::{
+    Pause,
+    mods::{ModList, parse_data},
+    screens::RunPhase,
+    textures::SpriteLoader,
+    theme::{GameFont, widget},
+};
+
+pub(super) fn plugin(app: &mut App) {
+    let scenes = DialogueScenes::load(app.world().get_resource::<ModList>());
+    app.insert_resource(scenes);
+    app.init_resource::<ActiveDialogue>();
+    app.add_message::<StartDialogue>();
+
+    app.add_systems(
+        OnTransition {
+            exited: RunPhase::Setup,
+            entered: RunPhase::Playing,
+        },
+        play_level_intro,
+    );
+    app.add_systems(Update, start_dialogue.run_if(in_state(RunPhase::Playing)));
+    app.add_systems(OnEnter(RunPhase::Dialogue), spawn_dialogue_box);
+    app.add_systems(
+        Update,
+        (advance_on_key, type_dialogue)
+            .chain()
+            .run_if(in_state(RunPhase::Dialogue).and(in_state(Pause(false)))),
+    );
+}
+
+/// Scenes are compiled in, like the campaign levels.
+const DIALOGUE_FILE: &str = include_str!("../../assets/data/dialogue.json");
+
+/// Characters typed per second.
+const TYPE_SPEED: f32 = 40.0;
+
+/// Size of the portrait next to the text.
+const PORTRAIT_SIZE: f32 = 96.0;
+
+const BOX_COLOR: Color = Color::srgba(0.96, 0.92, 0.84, 0.95);
+const SPEAKER_COLOR: Color = Color::srgb(0.275, 0.400, 0.750);
+const LINE_COLOR: Color = Color::srgb(0.1, 0.1, 0.1);
+
+/// One line of a scene.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct DialogueLine {
+    pub speaker: String,
+    /// Snord sprite shown next to the line, like `"derpy"`.
+    pub portrait: String,
+    pub text: String,
+}
+
+/// Every scene, by id.
+#[derive(Resource, Debug, Default)]
+pub struct DialogueScenes(pub HashMap<String, Vec<DialogueLine>>);
+
+impl DialogueScenes {
+    fn load(mods: Option<&ModList>) -> Self {
+        match parse_data(mods, "data/dialogue.json", DIALOGUE_FILE) {
+            Ok(scenes) => Self(scenes),
+            Err(e) => {
+                warn!("Failed to parse dialogue: {}", e);
+                Self::default()
+            }
+        }
+    }
+}
+
+/// Message to play a scene by id. Only heeded while the board is in play.
+#[derive(Message, Debug, Clone)]
+pub struct StartDialogue(pub String);
+
+/// The scene playing, and how far into it the player is.
+#[derive(Resource, Debug, Default)]
+pub struct ActiveDialogue {
+    lines: Vec<DialogueLine>,
+    /// Index of the line on screen.
+    line: usize,
+    /// Seconds spent typing the line on screen.
+    typed: f32,
+}
+
+impl ActiveDialogue {
+    fn new(lines: Vec<DialogueLine>) -> Self {
+        Self {
+            lines,
+            line: 0,
+            typed: 0.0,
+        }
+    }
+
+    /// The line on screen, or None once the scene is over.
+    pub fn current(&self) -> Option<&DialogueLine> {
+        self.lines.get(self.line)
+    }
+
+    /// How much of the current line has been typed out so far.
+    pub fn shown_text(&self) -> &str {
+        let Some(line) = self.current() else {
+            return "";
+        };
+        let chars = (self.typed * TYPE_SPEED) as usize;
+        match line.text.char_indices().nth(chars) {
+            Some((end, _)) => &line.text[..end],
+            None => &line.text,
+        }
+    }
+
+    fn line_typed(&self) -> bool {
+        self.current()
+            .is_none_or(|line| self.shown_text().len() == line.text.len())
+    }
+
+    /// Finish typing the current line, or move on to the next. Returns
+    /// false once there are no lines left.
+    pub fn advance(&mut self) -> bool {
+        if self.line_typed() {
+            self.line += 1;
+            self.typed = 0.0;
+        } else {
+            self.typed = f32::INFINITY;
+        }
+        self.current().is_some()
+    }
+}
+
+/// Marker for the dialogue box's portrait.
+#[derive(Component)]
+struct DialoguePortrait;
+
+/// Marker for the speaker's name.
+#[derive(Component)]
+struct DialogueSpeaker;
+
+/// Marker for the typed line.
+#[derive(Component)]
+struct DialogueText;
+
+/// Play the level's intro scene as the board comes into play.
+fn play_level_intro(
+    active: Res<ActiveLevel>,
+    levels: Res<CampaignLevels>,
+    mut dialogue_events: MessageWriter<StartDialogue>,
+) {
+    let level = active.0.and_then(|index| levels.0.get(index));
+    if let Some(intro) = level.and_then(|level| level.intro.clone()) {
+        dialogue_events.write(StartDialogue(intro));
+    }
+}
+
+fn start_dialogue(
+    mut dialogue_events: MessageReader<StartDialogue>,
+    scenes: Res<DialogueScenes>,
+    mut dialogue: ResMut<ActiveDialogue>,
+    mut next_phase: ResMut<NextState<RunPhase>>,
+) {
+    // One scene at a time; a second asked for on the same frame is dropped
+    let Some(StartDialogue(id)) = dialogue_events.read().last() else {
+        return;
+    };
+    match scenes.0.get(id).filter(|lines| !lines.is_empty()) {
+        Some(lines) => {
+            *dialogue = ActiveDialogue::new(lines.clone());
+            next_phase.set(RunPhase::Dialogue);
+        }
+        None => warn!("No dialogue scene '{}'", id),
+    }
+}
+
+fn spawn_dialogue_box(mut commands: Commands, game_font: Option<Res<GameFont>>) {
+    let font = game_font.map(|f| f.0.clone()).unwrap_or_default();
+    commands
+        .spawn((
+            Name::new("Dialogue"),
+            Node {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                align_items: AlignItems::End,
+                justify_content: JustifyContent::Center,
+                padding: UiRect::bottom(Val::Px(40.0)),
+                ..default()
+            },
+            GlobalZIndex(2),
+            DespawnOnExit(RunPhase::Dialogue),
+            children![(
+                Name::new("Dialogue Box"),
+                Node {
+                    width: Val::Percent(90.0),
+                    max_width: Val::Px(560.0),
+                    padding: UiRect::all(Val::Px(14.0)),
+                    column_gap: Val::Px(14.0),
+                    align_items: AlignItems::Start,
+                    ..default()
+                },
+                BackgroundColor(BOX_COLOR),
+                BorderRadius::all(Val::Px(12.0)),
+                children![
+                    (
+                        Name::new("Dialogue Portrait"),
+                        DialoguePortrait,
+                        ImageNode::default(),
+                        Node {
+                            width: Val::Px(PORTRAIT_SIZE),
+                            height: Val::Px(PORTRAIT_SIZE),
+                            flex_shrink: 0.0,
+                            ..default()
+                        },
+                        Pickable::IGNORE,
+                    ),
+                    (
+                        Node {
+                            flex_direction: FlexDirection::Column,
+                            flex_grow: 1.0,
+                            row_gap: Val::Px(6.0),
+                            ..default()
+                        },
+                        Pickable::IGNORE,
+                        children![
+                            (
+                                Name::new("Dialogue Speaker"),
+                                DialogueSpeaker,
+                                Text::default(),
+                                TextFont {
+                                    font: font.clone(),
+                                    font_size: 24.0,
+                                    ..default()
+                                },
+                                TextColor(SPEAKER_COLOR),
+                                Pickable::IGNORE,
+                            ),
+                            (
+                                Name::new("Dialogue Text"),
+                                DialogueText,
+                                Text::default(),
+                                TextFont {
+                                    font: font.clone(),
+                                    font_size: 18.0,
+                                    ..default()
+                                },
+                                TextColor(LINE_COLOR),
+                                Pickable::IGNORE,
+                            ),
+                            (
+                                Text::new("Click to continue"),
+                                TextFont {
+                                    font,
+                                    font_size: 14.0,
+                                    ..default()
+                                },
+                                TextColor(Color::srgb(0.45, 0.45, 0.45)),
+                                Pickable::IGNORE,
+                            ),
+                        ],
+                    ),
+                    widget::button_medium("Skip", skip_dialogue),
+                ],
+            )],
+        ))
+        // Anywhere on screen, so a click can't reach the board underneath
+        .observe(advance_on_click);
+}
+
+fn advance_on_click(
+    _: On<Pointer<Click>>,
+    pause: Res<State<Pause>>,
+    mut dialogue: ResMut<ActiveDialogue>,
+    mut next_phase: ResMut<NextState<RunPhase>>,
+) {
+    if !pause.0 && !dialogue.advance() {
+        next_phase.set(RunPhase::Playing);
+    }
+}
+
+fn advance_on_key(
+    input: Res<ButtonInput<KeyCode>>,
+    mut dialogue: ResMut<ActiveDialogue>,
+    mut next_phase: ResMut<NextState<RunPhase>>,
+) {
+    if input.any_just_pressed([KeyCode::Space, KeyCode::Enter]) && !dialogue.advance() {
+        next_phase.set(RunPhase::Playing);
+    }
+}
+
+fn skip_dialogue(mut click: On<Pointer<Click>>, mut next_phase: ResMut<NextState<RunPhase>>) {
+    // Don't let the click reach the box and advance the scene too
+    click.propagate(false);
+    next_phase.set(RunPhase::Playing);
+}
+
+/// Type out the current line and show who's saying it.
+fn type_dialogue(
+    time: Res<Time<Real>>,
+    sprites: SpriteLoader,
+    mut dialogue: ResMut<ActiveDialogue>,
+    mut portrait_query: Query<&mut ImageNode, With<DialoguePortrait>>,
+    mut speaker_query: Query<&mut Text, (With<DialogueSpeaker>, Without<DialogueText>)>,
+    mut text_query: Query<&mut Text, (With<DialogueText>, Without<DialogueSpeaker>)>,
+) {
+    dialogue.typed += time.delta_secs();
+    let Some(line) = dialogue.current() else {
+        return;
+    };
+
+    for mut portrait in &mut portrait_query {
+        let image = sprites.load(&format!("images/{}.png", line.portrait));
+        if portrait.image != image {
+            portrait.image = image;
+        }
+    }
+    for mut speaker in &mut speaker_query {
+        if speaker.0 != line.speaker {
+            speaker.0.clone_from(&line.speaker);
+        }
+    }
+    let shown = dialogue.shown_text();
+    for mut text in &mut text_query {
+        if text.0 != shown {
+            text.0 = shown.to_string();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::campaign::LevelDefinition;
+
+    fn line(text: &str) -> DialogueLine {
+        DialogueLine {
+            speaker: "Derpy".to_string(),
+            portrait: "derpy".to_string(),
+            text: text.to_string(),
+        }
+    }
+
+    #[test]
+    fn lines_type_out_and_advance_a_click_at_a_time() {
+        let mut dialogue = ActiveDialogue::new(vec![line("Héllo there"), line("Bye")]);
+        assert_eq!(dialogue.shown_text(), "");
+
+        dialogue.typed = 2.0 / TYPE_SPEED;
+        assert_eq!(dialogue.shown_text(), "Hé");
+
+        // The first click finishes the line, the second moves on
+        assert!(dialogue.advance());
+        assert_eq!(dialogue.shown_text(), "Héllo there");
+        assert!(dialogue.advance());
+        assert_eq!(dialogue.current(), Some(&line("Bye")));
+        assert_eq!(dialogue.shown_text(), "");
+
+        dialogue.typed = 10.0;
+        assert!(!dialogue.advance());
+        assert!(dialogue.current().is_none());
+    }
+
+    #[test]
+    fn campaign_scenes_exist() {
+        let scenes = DialogueScenes::load(None);
+        let levels: Vec<LevelDefinition> =
+            serde_json::from_str(include_str!("../../assets/data/campaign.json")).unwrap();
+        for level in &levels {
+            if let Some(intro) = &level.intro {
+                assert!(scenes.0.contains_key(intro), "{} has no scene", intro);
+            }
+        }
+        assert!(scenes.0.values().all(|lines| !lines.is_empty()));
+    }
+}
@@ -0,0 +1,350 @@
+//! Practice drills - short scripted scenarios with a goal and a shot limit.
+//!
+//! Each drill starts from a fixed board and bubble queue, defined in
+//! `assets/data/drills.json`. Board rows are strings with one letter per
+//! column (`R`ed, `B`lue, `G`reen, `Y`ellow, `P`urple, `O`range, `.` empty).
+//! Clearing a drill records the time taken if it beats the previous best,
+//! and opens the results screen with its grade.
+
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    bubble::{BubbleColor, StartingBoard},
+    clock::GameClock,
+    cluster::FloatingBubblesRemoved,
+    grid::{GridBounds, HexGrid},
+    hex::HexCoord,
+    projectile::{FireProjectile, Projectile},
+    shooter::ScriptedQueue,
+    snapshot::GridCell,
+};
+use crate::{
+    PausableSystems,
+    menus::Menu,
+    mods::{ModList, parse_data},
+    save::SaveFile,
+    screens::{RunPhase, Screen},
+    theme::toast::ShowToast,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    let drills = Drills::load(app.world().get_resource::<ModList>());
+    app.insert_resource(drills);
+    app.init_resource::<ActiveDrill>();
+    app.init_resource::<DrillRun>();
+    app.init_resource::<DrillBests>();
+
+    app.add_systems(Startup, load_drill_bests);
+    app.add_systems(OnEnter(Screen::Title), clear_active_drill);
+    app.add_systems(
+        Update,
+        apply_active_drill.run_if(resource_changed::<ActiveDrill>),
+    );
+
+    app.add_systems(OnEnter(Screen::Gameplay), reset_drill_run);
+    app.add_systems(
+        Update,
+        (track_drill_progress, evaluate_drill)
+            .chain()
+            .in_set(PausableSystems)
+            .run_if(in_state(Screen::Gameplay).and(drill_active)),
+    );
+}
+
+/// Drills are compiled in, like the loading screen tips.
+const DRILLS_FILE: &str = include_str!("../../assets/data/drills.json");
+
+/// Seconds to wait after the last shot for pops and drops to finish before
+/// the drill counts as failed.
+const SETTLE_SECONDS: f32 = 1.0;
+
+/// What a drill asks the player to do.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DrillGoal {
+    /// Pop or drop every bubble.
+    ClearBoard,
+    /// Get rid of every bubble of one color.
+    PopColor(BubbleColor),
+    /// Drop at least this many floating bubbles.
+    DropBubbles(u32),
+}
+
+impl DrillGoal {
+    /// Whether the goal has been met.
+    fn is_met(self, grid: &HexGrid, colors: &Query<&BubbleColor>, dropped: u32) -> bool {
+        match self {
+            DrillGoal::ClearBoard => grid.is_empty(),
+            DrillGoal::PopColor(color) => !grid
+                .iter()
+                .any(|(_, &entity)| colors.get(entity).is_ok_and(|&c| c == color)),
+            DrillGoal::DropBubbles(count) => dropped >= count,
+        }
+    }
+}
+
+/// A scripted scenario.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Drill {
+    pub name: String,
+    pub description: String,
+    pub goal: DrillGoal,
+    /// Shots allowed before the drill is failed.
+    pub shots: u32,
+    /// Bubble colors loaded into the shooter, in order.
+    pub queue: String,
+    /// Rows from the top, one letter per column starting at the left wall.
+    pub board: Vec<String>,
+    /// Shots the drill should take, for its grade. Defaults to `shots`.
+    #[serde(default)]
+    pub par: Option<u32>,
+}
+
+impl Drill {
+    /// Shots the drill is graded against.
+    pub fn par(&self) -> u32 {
+        self.par.unwrap_or(self.shots)
+    }
+
+    /// The starting bubbles, or an error naming the first bad letter.
+    pub fn cells(&self) -> Result<Vec<GridCell>, String> {
+        parse_board(&self.board)
+    }
+
+    /// The shooter queue, or an error naming the first bad letter.
+    pub fn queue_colors(&self) -> Result<Vec<BubbleColor>, String> {
+        self.queue
+            .chars()
+            .map(|letter| {
+                color_for_letter(letter)
+                    .ok_or_else(|| format!("unknown bubble '{letter}' in queue"))
+            })
+            .collect()
+    }
+}
+
+/// Bubbles from board rows in the drill format, or an error naming the
+/// first bad letter. Campaign levels use the same format.
+pub fn parse_board(rows: &[String]) -> Result<Vec<GridCell>, String> {
+    let min_q = GridBounds::default().min_q;
+    let mut cells = Vec::new();
+    for (r, row) in rows.iter().enumerate() {
+        for (i, letter) in row.chars().enumerate() {
+            if letter == '.' {
+                continue;
+            }
+            let color = color_for_letter(letter)
+                .ok_or_else(|| format!("unknown bubble '{letter}' in row {r}"))?;
+            cells.push(GridCell {
+                coord: HexCoord::new(min_q + i as i32, r as i32),
+                color,
+            });
+        }
+    }
+    Ok(cells)
+}
+
+/// Board rows in the drill format for these bubbles, one row per grid row
+/// from the top down to the lowest bubble, each the full width of the grid.
+pub fn board_rows(cells: &[GridCell]) -> Vec<String> {
+    let bounds = GridBounds::default();
+    let width = (bounds.max_q - bounds.min_q + 1) as usize;
+    let rows = cells.iter().map(|cell| cell.coord.r + 1).max().unwrap_or(0);
+    let mut board = vec![vec!['.'; width]; rows.max(0) as usize];
+    for cell in cells {
+        let column = (cell.coord.q - bounds.min_q) as usize;
+        if cell.coord.r >= 0 && column < width {
+            board[cell.coord.r as usize][column] = letter_for_color(cell.color);
+        }
+    }
+    board.into_iter().map(String::from_iter).collect()
+}
+
+pub(super) fn letter_for_color(color: BubbleColor) -> char {
+    match color {
+        BubbleColor::Red => 'R',
+        BubbleColor::Blue => 'B',
+        BubbleColor::Green => 'G',
+        BubbleColor::Yellow => 'Y',
+        BubbleColor::Purple => 'P',
+        BubbleColor::Orange => 'O',
+    }
+}
+
+pub(super) fn color_for_letter(letter: char) -> Option<BubbleColor> {
+    match letter.to_ascii_uppercase() {
+        'R' => Some(BubbleColor::Red),
+        'B' => Some(BubbleColor::Blue),
+        'G' => Some(BubbleColor::Green),
+        'Y' => Some(BubbleColor::Yellow),
+        'P' => Some(BubbleColor::Purple),
+        'O' => Some(BubbleColor::Orange),
+        _ => None,
+    }
+}
+
+/// All available drills, in menu order.
+#[derive(Resource, Debug, Default)]
+pub struct Drills(pub Vec<Drill>);
+
+impl Drills {
+    fn load(mods: Option<&ModList>) -> Self {
+        match parse_data::<Vec<Drill>>(mods, "data/drills.json", DRILLS_FILE) {
+            Ok(drills) => Self(drills),
+            Err(e) => {
+                warn!("Failed to parse drills: {}", e);
+                Self::default()
+            }
+        }
+    }
+}
+
+/// Index of the drill being played, if any. Kept across restarts from the
+/// game over menu and cleared on returning to the title screen.
+#[derive(Resource, Debug, Default, PartialEq)]
+pub struct ActiveDrill(pub Option<usize>);
+
+/// Run condition: a drill is being played.
+pub fn drill_active(active: Res<ActiveDrill>) -> bool {
+    active.0.is_some()
+}
+
+/// Progress through the current drill attempt.
+#[derive(Resource, Debug, Default)]
+pub struct DrillRun {
+    pub shots: u32,
+    pub dropped: u32,
+    /// Unpaused seconds since the attempt started.
+    pub elapsed: f32,
+    /// Seconds since the last allowed shot was used up.
+    out_of_shots_for: f32,
+    pub finished: bool,
+}
+
+/// Best clear time per drill name, in seconds.
+#[derive(Resource, Debug, Default, Serialize, Deserialize)]
+pub struct DrillBests {
+    pub times: HashMap<String, f32>,
+}
+
+impl SaveFile for DrillBests {
+    const FILE_NAME: &'static str = "drill_times.json";
+    const DESCRIPTION: &'static str = "drill times";
+}
+
+impl DrillBests {
+    /// Record a clear time. Returns true if it's a new best.
+    pub fn record(&mut self, name: &str, secs: f32) -> bool {
+        match self.times.get(name) {
+            Some(&best) if best <= secs => false,
+            _ => {
+                self.times.insert(name.to_string(), secs);
+                true
+            }
+        }
+    }
+}
+
+fn load_drill_bests(mut bests: ResMut<DrillBests>) {
+    *bests = DrillBests::load();
+}
+
+fn clear_active_drill(mut active: ResMut<ActiveDrill>) {
+    active.set_if_neq(ActiveDrill(None));
+}
+
+/// Point the starting board and shooter queue at the selected drill, or back
+/// to random ones.
+fn apply_active_drill(
+    active: Res<ActiveDrill>,
+    drills: Res<Drills>,
+    mut board: ResMut<StartingBoard>,
+    mut queue: ResMut<ScriptedQueue>,
+) {
+    let Some(drill) = active.0.and_then(|index| drills.0.get(index)) else {
+        board.0 = None;
+        *queue = ScriptedQueue::default();
+        return;
+    };
+
+    match (drill.cells(), drill.queue_colors()) {
+        (Ok(cells), Ok(colors)) => {
+            info!("Drill selected: {}", drill.name);
+            board.0 = Some(cells);
+            *queue = ScriptedQueue::new(colors);
+        }
+        (Err(e), _) | (_, Err(e)) => warn!("Drill '{}' is invalid: {}", drill.name, e),
+    }
+}
+
+fn reset_drill_run(mut run: ResMut<DrillRun>) {
+    *run = DrillRun::default();
+}
+
+fn track_drill_progress(
+    time: Res<Time<GameClock>>,
+    mut run: ResMut<DrillRun>,
+    mut fire_events: MessageReader<FireProjectile>,
+    mut floating_events: MessageReader<FloatingBubblesRemoved>,
+) {
+    run.elapsed += time.delta_secs();
+    run.shots += fire_events.read().count() as u32;
+    run.dropped += floating_events
+        .read()
+        .map(|event| event.count as u32)
+        .sum::<u32>();
+}
+
+/// Pass the drill once its goal is met, or fail it once the shots run out.
+fn evaluate_drill(
+    time: Res<Time<GameClock>>,
+    active: Res<ActiveDrill>,
+    drills: Res<Drills>,
+    mut run: ResMut<DrillRun>,
+    mut bests: ResMut<DrillBests>,
+    grid: Res<HexGrid>,
+    colors: Query<&BubbleColor>,
+    projectiles: Query<(), With<Projectile>>,
+    mut toasts: MessageWriter<ShowToast>,
+    mut next_menu: ResMut<NextState<Menu>>,
+    mut next_phase: ResMut<NextState<RunPhase>>,
+) {
+    let Some(drill) = active.0.and_then(|index| drills.0.get(index)) else {
+        return;
+    };
+    if run.finished {
+        return;
+    }
+
+    // A pass is graded on the results screen; a fail goes back to the list
+    let next = if drill.goal.is_met(&grid, &colors, run.dropped) {
+        let secs = run.elapsed;
+        info!("Drill '{}' passed in {:.1}s", drill.name, secs);
+        if bests.record(&drill.name, secs) {
+            bests.save();
+            toasts.write(ShowToast::success(format!(
+                "Drill passed in {secs:.1}s - new best!"
+            )));
+        } else {
+            toasts.write(ShowToast::success(format!("Drill passed in {secs:.1}s")));
+        }
+        Menu::LevelResults
+    } else if run.shots >= drill.shots && projectiles.is_empty() {
+        // Give pops and drops from the last shot time to land
+        run.out_of_shots_for += time.delta_secs();
+        if run.out_of_shots_for < SETTLE_SECONDS {
+            return;
+        }
+        info!("Drill '{}' failed: out of shots", drill.name);
+        toasts.write(ShowToast::warning("Drill failed - out of shots"));
+        Menu::Drills
+    } else {
+        return;
+    };
+
+    run.finished = true;
+    next_phase.set(RunPhase::GameEnding);
+    next_menu.set(next);
+}
@@ -0,0 +1,508 @@
+//! The level editor - laying out boards by hand.
+//!
+//! Click a cell to place a bubble of the chosen color; right-click, or click
+//! with the eraser, to take one away. Levels are saved as RON files under
+//! `assets/levels/`, with the board in the drill format and the number of
+//! shots between descents, and the Load button steps through the saved ones
+//! so they can be tweaked. Saving needs a filesystem, so the main menu only
+//! offers the editor on native builds.
+
+use std::path::PathBuf;
+
+use bevy::{prelude::*, window::PrimaryWindow};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    GamePanel,
+    bubble::{BubbleColor, GameAssets, load_game_assets, spawn_bubble},
+    bubble_material::BubbleMaterial,
+    drills::{board_rows, parse_board},
+    grid::HexGrid,
+    hex::{GridOffset, HexCoord},
+    snapshot::GridCell,
+};
+use crate::{
+    screens::Screen,
+    theme::{palette::LABEL_TEXT, toast::ShowToast, widget},
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<Editor>();
+
+    app.add_systems(
+        OnEnter(Screen::Editor),
+        (load_game_assets, reset_editor, spawn_editor_ui),
+    );
+    app.add_systems(OnExit(Screen::Editor), clear_editor_board);
+    app.add_systems(
+        Update,
+        (
+            edit_cell,
+            leave_on_escape,
+            update_editor_labels.run_if(resource_changed::<Editor>),
+        )
+            .run_if(in_state(Screen::Editor)),
+    );
+}
+
+/// Where saved levels go, relative to the asset folder.
+const LEVELS_DIR: &str = "levels";
+
+/// Shots between descents for a new level, matching a fresh run.
+const DEFAULT_SHOTS_UNTIL_DESCENT: u32 = 8;
+
+/// The fewest and most shots between descents a level can have.
+const SHOTS_UNTIL_DESCENT_RANGE: std::ops::RangeInclusive<u32> = 3..=12;
+
+/// A level as saved by the editor.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EditorLevel {
+    /// Shots fired before the board comes down a row.
+    pub shots_until_descent: u32,
+    /// Rows from the top, in the drill format.
+    pub board: Vec<String>,
+}
+
+impl EditorLevel {
+    pub fn from_ron(text: &str) -> Result<Self, String> {
+        ron::from_str(text).map_err(|e| e.to_string())
+    }
+
+    pub fn to_ron(&self) -> String {
+        ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+            .expect("editor levels always serialize")
+    }
+}
+
+/// What clicking a cell does.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EditorTool {
+    Paint(BubbleColor),
+    Erase,
+}
+
+/// The editor's settings for the level being edited.
+#[derive(Resource, Debug, Clone, PartialEq)]
+pub struct Editor {
+    pub tool: EditorTool,
+    pub shots_until_descent: u32,
+    /// The file the level was loaded from or last saved to, if any.
+    pub file: Option<String>,
+}
+
+impl Default for Editor {
+    fn default() -> Self {
+        Self {
+            tool: EditorTool::Paint(BubbleColor::Red),
+            shots_until_descent: DEFAULT_SHOTS_UNTIL_DESCENT,
+            file: None,
+        }
+    }
+}
+
+/// The folder levels are saved to, inside the asset folder.
+fn levels_dir() -> PathBuf {
+    #[cfg(not(target_arch = "wasm32"))]
+    let assets = bevy::asset::io::file::FileAssetReader::get_base_path().join("assets");
+    #[cfg(target_arch = "wasm32")]
+    let assets = PathBuf::from("assets");
+    assets.join(LEVELS_DIR)
+}
+
+/// Saved level files, sorted by name.
+fn level_files() -> Vec<String> {
+    let Ok(entries) = std::fs::read_dir(levels_dir()) else {
+        return Vec::new();
+    };
+    let mut files: Vec<String> = entries
+        .filter_map(|entry| entry.ok()?.file_name().into_string().ok())
+        .filter(|name| name.ends_with(".ron"))
+        .collect();
+    files.sort();
+    files
+}
+
+/// The first `level_N.ron` that isn't taken.
+fn new_level_file(taken: &[String]) -> String {
+    (1..)
+        .map(|n| format!("level_{n}.ron"))
+        .find(|name| !taken.contains(name))
+        .expect("some level number is free")
+}
+
+fn reset_editor(
+    mut editor: ResMut<Editor>,
+    mut grid: ResMut<HexGrid>,
+    mut grid_offset: ResMut<GridOffset>,
+) {
+    *editor = Editor::default();
+    // Levels are laid out on the classic grid
+    *grid = HexGrid::default();
+    *grid_offset = GridOffset::default();
+}
+
+fn clear_editor_board(mut commands: Commands, mut grid: ResMut<HexGrid>) {
+    for (_, &entity) in grid.iter() {
+        commands.entity(entity).despawn();
+    }
+    grid.clear();
+}
+
+/// Marks the text showing the editor's settings.
+#[derive(Component, Debug)]
+struct EditorLabel;
+
+/// Marks a tool button, to outline the selected one.
+#[derive(Component, Debug)]
+struct ToolButton(EditorTool);
+
+/// Outline of the selected tool's button.
+const SELECTED_OUTLINE: Color = Color::srgb(0.1, 0.1, 0.1);
+
+fn spawn_editor_ui(mut commands: Commands, sprites: crate::textures::SpriteLoader) {
+    commands.spawn((
+        Name::new("Editor Panel"),
+        GamePanel,
+        Sprite::from_image(sprites.load("images/game_bounds.png")),
+        Transform::from_xyz(0.0, 15.0, -1.0),
+        DespawnOnExit(Screen::Editor),
+    ));
+
+    let tools = BubbleColor::ALL
+        .into_iter()
+        .map(EditorTool::Paint)
+        .chain([EditorTool::Erase]);
+    commands
+        .spawn((
+            Name::new("Editor Toolbar"),
+            Node {
+                position_type: PositionType::Absolute,
+                width: percent(100),
+                bottom: px(8),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                row_gap: px(6),
+                ..default()
+            },
+            DespawnOnExit(Screen::Editor),
+        ))
+        .with_children(|toolbar| {
+            toolbar
+                .spawn((
+                    Name::new("Tool Row"),
+                    Node {
+                        column_gap: px(8),
+                        align_items: AlignItems::Center,
+                        ..default()
+                    },
+                ))
+                .with_children(|row| {
+                    for tool in tools {
+                        row.spawn(tool_button(tool)).observe(
+                            move |_: On<Pointer<Click>>, mut editor: ResMut<Editor>| {
+                                editor.tool = tool;
+                            },
+                        );
+                    }
+                    row.spawn(widget::button_small("-", fewer_shots));
+                    row.spawn((
+                        Name::new("Editor Label"),
+                        EditorLabel,
+                        Text::default(),
+                        TextFont::from_font_size(20.0),
+                        TextColor(LABEL_TEXT),
+                    ));
+                    row.spawn(widget::button_small("+", more_shots));
+                });
+            toolbar
+                .spawn((
+                    Name::new("File Row"),
+                    Node {
+                        column_gap: px(10),
+                        ..default()
+                    },
+                ))
+                .with_children(|row| {
+                    row.spawn(widget::button_medium("Load", load_next_level));
+                    row.spawn(widget::button_medium("Save", save_level));
+                    row.spawn(widget::button_medium("Back", leave_editor));
+                });
+        });
+}
+
+fn tool_button(tool: EditorTool) -> impl Bundle {
+    let (name, color) = match tool {
+        EditorTool::Paint(color) => (format!("{color:?} Tool"), color.to_color()),
+        EditorTool::Erase => ("Eraser".to_string(), Color::WHITE),
+    };
+    (
+        Name::new(name),
+        Button,
+        ToolButton(tool),
+        Node {
+            width: px(32),
+            height: px(32),
+            border: UiRect::all(px(3)),
+            ..default()
+        },
+        BackgroundColor(color),
+        BorderColor::all(Color::NONE),
+        BorderRadius::MAX,
+    )
+}
+
+fn update_editor_labels(
+    editor: Res<Editor>,
+    mut labels: Query<&mut Text, With<EditorLabel>>,
+    mut tools: Query<(&ToolButton, &mut BorderColor)>,
+) {
+    let file = editor.file.as_deref().unwrap_or("unsaved");
+    for mut text in &mut labels {
+        text.0 = format!("{} shots per row - {}", editor.shots_until_descent, file);
+    }
+    for (button, mut border) in &mut tools {
+        let color = if button.0 == editor.tool {
+            SELECTED_OUTLINE
+        } else {
+            Color::NONE
+        };
+        *border = BorderColor::all(color);
+    }
+}
+
+fn fewer_shots(_: On<Pointer<Click>>, mut editor: ResMut<Editor>) {
+    editor.shots_until_descent =
+        (editor.shots_until_descent - 1).max(*SHOTS_UNTIL_DESCENT_RANGE.start());
+}
+
+fn more_shots(_: On<Pointer<Click>>, mut editor: ResMut<Editor>) {
+    editor.shots_until_descent =
+        (editor.shots_until_descent + 1).min(*SHOTS_UNTIL_DESCENT_RANGE.end());
+}
+
+fn leave_editor(_: On<Pointer<Click>>, mut next_screen: ResMut<NextState<Screen>>) {
+    next_screen.set(Screen::Title);
+}
+
+fn leave_on_escape(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut next_screen: ResMut<NextState<Screen>>,
+) {
+    if keyboard.just_pressed(KeyCode::Escape) {
+        next_screen.set(Screen::Title);
+    }
+}
+
+/// Paint or erase the cell under the cursor.
+fn edit_cell(
+    mut commands: Commands,
+    mouse: Res<ButtonInput<MouseButton>>,
+    editor: Res<Editor>,
+    mut grid: ResMut<HexGrid>,
+    grid_offset: Res<GridOffset>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<BubbleMaterial>>,
+    game_assets: Option<Res<GameAssets>>,
+    colors: Query<&BubbleColor>,
+    buttons: Query<&Interaction, With<Button>>,
+    window: Query<&Window, With<PrimaryWindow>>,
+    camera: Query<(&Camera, &GlobalTransform)>,
+) {
+    let tool = if mouse.just_pressed(MouseButton::Left) {
+        editor.tool
+    } else if mouse.just_pressed(MouseButton::Right) {
+        EditorTool::Erase
+    } else {
+        return;
+    };
+    // Clicks on the toolbar aren't meant for the board
+    if buttons
+        .iter()
+        .any(|interaction| *interaction != Interaction::None)
+    {
+        return;
+    }
+    let Some(cursor) = window.single().ok().and_then(Window::cursor_position) else {
+        return;
+    };
+    let Ok((camera, camera_transform)) = camera.single() else {
+        return;
+    };
+    let Ok(position) = camera.viewport_to_world_2d(camera_transform, cursor) else {
+        return;
+    };
+    let coord = HexCoord::from_pixel_with_offset(position, grid.hex_size, grid_offset.y);
+    if !grid.bounds.contains(coord) {
+        return;
+    }
+
+    let existing = grid.get(coord);
+    if let (EditorTool::Paint(color), Some(entity)) = (tool, existing)
+        && colors.get(entity).ok() == Some(&color)
+    {
+        return;
+    }
+    if let Some(entity) = existing {
+        commands.entity(entity).despawn();
+        grid.remove(coord);
+    }
+    if let EditorTool::Paint(color) = tool {
+        let entity = spawn_bubble(
+            &mut commands,
+            &mut meshes,
+            &mut materials,
+            coord,
+            color,
+            grid.hex_size,
+            grid_offset.y,
+            game_assets.as_deref(),
+        );
+        grid.insert(coord, entity);
+    }
+}
+
+/// The level on the editor's board.
+fn current_level(editor: &Editor, grid: &HexGrid, colors: &Query<&BubbleColor>) -> EditorLevel {
+    let cells: Vec<GridCell> = grid
+        .iter()
+        .filter_map(|(&coord, &entity)| {
+            Some(GridCell {
+                coord,
+                color: *colors.get(entity).ok()?,
+            })
+        })
+        .collect();
+    EditorLevel {
+        shots_until_descent: editor.shots_until_descent,
+        board: board_rows(&cells),
+    }
+}
+
+fn save_level(
+    _: On<Pointer<Click>>,
+    mut editor: ResMut<Editor>,
+    grid: Res<HexGrid>,
+    colors: Query<&BubbleColor>,
+    mut toasts: MessageWriter<ShowToast>,
+) {
+    let level = current_level(&editor, &grid, &colors);
+    let file = editor
+        .file
+        .clone()
+        .unwrap_or_else(|| new_level_file(&level_files()));
+    let dir = levels_dir();
+    let result = std::fs::create_dir_all(&dir)
+        .and_then(|()| std::fs::write(dir.join(&file), level.to_ron()));
+    match result {
+        Ok(()) => {
+            info!("Saved level to {:?}", dir.join(&file));
+            toasts.write(ShowToast::success(format!("Saved {file}")));
+            editor.file = Some(file);
+        }
+        Err(e) => {
+            warn!("Failed to save level: {}", e);
+            toasts.write(ShowToast::warning("Couldn't save the level"));
+        }
+    }
+}
+
+/// Load the saved level after the current one, wrapping around.
+fn load_next_level(
+    _: On<Pointer<Click>>,
+    mut commands: Commands,
+    mut editor: ResMut<Editor>,
+    mut grid: ResMut<HexGrid>,
+    grid_offset: Res<GridOffset>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<BubbleMaterial>>,
+    game_assets: Option<Res<GameAssets>>,
+    mut toasts: MessageWriter<ShowToast>,
+) {
+    let files = level_files();
+    let next = editor
+        .file
+        .as_ref()
+        .and_then(|file| files.iter().position(|name| name == file))
+        .map_or(0, |index| (index + 1) % files.len().max(1));
+    let Some(file) = files.get(next) else {
+        toasts.write(ShowToast::info("No saved levels yet"));
+        return;
+    };
+
+    let level = std::fs::read_to_string(levels_dir().join(file))
+        .map_err(|e| e.to_string())
+        .and_then(|text| EditorLevel::from_ron(&text));
+    let cells = match level
+        .as_ref()
+        .map_err(String::clone)
+        .and_then(|level| parse_board(&level.board))
+    {
+        Ok(cells) => cells,
+        Err(e) => {
+            warn!("Failed to load level {}: {}", file, e);
+            toasts.write(ShowToast::warning(format!("Couldn't load {file}")));
+            return;
+        }
+    };
+
+    for (_, &entity) in grid.iter() {
+        commands.entity(entity).despawn();
+    }
+    grid.clear();
+    for GridCell { coord, color } in cells {
+        if !grid.bounds.contains(coord) {
+            continue;
+        }
+        let entity = spawn_bubble(
+            &mut commands,
+            &mut meshes,
+            &mut materials,
+            coord,
+            color,
+            grid.hex_size,
+            grid_offset.y,
+            game_assets.as_deref(),
+        );
+        grid.insert(coord, entity);
+    }
+    if let Ok(level) = level {
+        editor.shots_until_descent = level.shots_until_descent.clamp(
+            *SHOTS_UNTIL_DESCENT_RANGE.start(),
+            *SHOTS_UNTIL_DESCENT_RANGE.end(),
+        );
+    }
+    editor.file = Some(file.clone());
+    toasts.write(ShowToast::info(format!("Loaded {file}")));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn levels_survive_a_round_trip_through_ron() {
+        let cells = vec![
+            GridCell {
+                coord: HexCoord::new(-5, 0),
+                color: BubbleColor::Red,
+            },
+            GridCell {
+                coord: HexCoord::new(0, 2),
+                color: BubbleColor::Purple,
+            },
+        ];
+        let level = EditorLevel {
+            shots_until_descent: 6,
+            board: board_rows(&cells),
+        };
+        assert_eq!(level.board.len(), 3);
+
+        let loaded = EditorLevel::from_ron(&level.to_ron()).unwrap();
+        assert_eq!(loaded, level);
+        let mut parsed = parse_board(&loaded.board).unwrap();
+        parsed.sort_by_key(|cell| cell.coord.r);
+        assert_eq!(parsed, cells);
+
+        assert_eq!(new_level_file(&["level_1.ron".into()]), "level_2.ron");
+    }
+}
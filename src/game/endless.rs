@@ -0,0 +1,130 @@
+//! Endless clears - clearing the board doesn't end an Endless run.
+//!
+//! In modes that [continue after a clear](GameMode::continues_after_clear),
+//! the win check scores [`BOARD_CLEAR_BONUS`] and sends [`BoardCleared`]
+//! instead of ending the run. The board then moves back up to the ceiling and
+//! refills with a fresh board, a row deeper for every clear so far (up to
+//! [`MAX_EXTRA_ROWS`]). The level, and with it the descent pace, carries on.
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    bubble::{BubbleColor, GameAssets, INITIAL_ROWS},
+    bubble_material::BubbleMaterial,
+    grid::GridCommands,
+    hex::{GRID_ORIGIN_Y, HexCoord},
+    mode::{GameMode, LevelColors, pressure_mode},
+    polish::{ComboText, combo_text},
+    rng::GameRng,
+    state::{BOARD_CLEAR_BONUS, BoardCleared, check_win_condition},
+};
+use crate::{PausableSystems, screens::InGame, theme::GameFont};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<BoardClears>();
+    app.init_resource::<BoardClears>();
+
+    app.add_systems(OnEnter(InGame), reset_board_clears);
+    app.add_systems(
+        Update,
+        refill_cleared_board
+            .after(check_win_condition)
+            .in_set(PausableSystems)
+            .run_if(in_state(InGame).and(pressure_mode)),
+    );
+}
+
+/// Most rows a refilled board has beyond a new run's.
+const MAX_EXTRA_ROWS: u32 = 4;
+
+/// Boards cleared so far this run.
+#[derive(Resource, Debug, Clone, Copy, Default, PartialEq, Reflect, Serialize, Deserialize)]
+#[reflect(Resource, Serialize, Deserialize)]
+pub struct BoardClears(pub u32);
+
+impl BoardClears {
+    /// Rows in the next fresh board.
+    pub fn rows(self) -> i32 {
+        INITIAL_ROWS + self.0.min(MAX_EXTRA_ROWS) as i32
+    }
+}
+
+fn reset_board_clears(mut clears: ResMut<BoardClears>) {
+    *clears = BoardClears::default();
+}
+
+/// Put a fresh, deeper board under the ceiling after a clear.
+fn refill_cleared_board(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<BubbleMaterial>>,
+    mut cleared_events: MessageReader<BoardCleared>,
+    mut grid: GridCommands,
+    mut clears: ResMut<BoardClears>,
+    mut rng: ResMut<GameRng>,
+    mode: Res<GameMode>,
+    level_colors: Res<LevelColors>,
+    game_assets: Res<GameAssets>,
+    game_font: Res<GameFont>,
+) {
+    if cleared_events.read().count() == 0 {
+        return;
+    }
+    clears.0 += 1;
+
+    // The board is empty, so this only moves the origin back to the top
+    let rise = grid.origin_y() - GRID_ORIGIN_Y;
+    grid.shift_down(rise);
+
+    let bounds = grid.bounds;
+    for r in 0..clears.rows() {
+        for q in bounds.min_q..=bounds.max_q {
+            let color = BubbleColor::random_with(level_colors.palette(*mode), &mut rng.gameplay);
+            grid.spawn(
+                &mut commands,
+                &mut meshes,
+                &mut materials,
+                HexCoord::new(q, r),
+                color,
+                Some(&game_assets),
+            );
+        }
+    }
+    info!(
+        "Board cleared ({} so far): +{} and a fresh {}-row board",
+        clears.0,
+        BOARD_CLEAR_BONUS,
+        clears.rows()
+    );
+
+    commands.spawn(combo_text(
+        "Board Cleared Text",
+        "BOARD CLEARED!",
+        ComboText::new(Vec2::new(0.0, 60.0), 2.2, 40.0, Color::srgb(0.3, 0.85, 0.4)),
+        game_font.0.clone(),
+        40.0,
+    ));
+    commands.spawn(combo_text(
+        "Board Cleared Bonus Text",
+        format!("+{BOARD_CLEAR_BONUS}"),
+        ComboText::new(Vec2::new(0.0, 15.0), 2.2, 40.0, Color::WHITE),
+        game_font.0.clone(),
+        28.0,
+    ));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fresh_boards_stop_deepening_after_max_extra_rows() {
+        assert_eq!(BoardClears(0).rows(), INITIAL_ROWS);
+        assert_eq!(BoardClears(2).rows(), INITIAL_ROWS + 2);
+        assert_eq!(
+            BoardClears(MAX_EXTRA_ROWS + 3).rows(),
+            INITIAL_ROWS + MAX_EXTRA_ROWS as i32
+        );
+    }
+}
@@ -0,0 +1,198 @@
+//! Event feed - a small scrolling log of notable events beside the playfield.
+//!
+//! Entries are fed by the existing gameplay messages ("Popped 7 greens!",
+//! "Dropped 5 floaters", "Descent!", power-up picks) and fade out after a
+//! few seconds. The feed can be turned off in the settings menu.
+
+use bevy::prelude::*;
+
+use super::{
+    cluster::{ClusterPopped, FloatingBubblesRemoved},
+    powerups::{PowerUpCatalog, UnlockedPowerUps},
+    projectile::GraceBounceUsed,
+    state::TriggerDescent,
+};
+use crate::{PausableSystems, screens::InGame, theme::GameFont};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<EventFeedSettings>();
+    app.register_type::<EventFeedSettings>();
+
+    app.add_systems(OnEnter(InGame), spawn_event_feed);
+
+    app.add_systems(
+        Update,
+        (push_feed_entries, fade_feed_entries)
+            .chain()
+            .in_set(PausableSystems)
+            .run_if(in_state(InGame)),
+    );
+    app.add_systems(
+        Update,
+        apply_feed_visibility.run_if(in_state(InGame).and(resource_changed::<EventFeedSettings>)),
+    );
+}
+
+/// Settings for the event feed.
+#[derive(Resource, Debug, Clone, Reflect)]
+#[reflect(Resource)]
+pub struct EventFeedSettings {
+    pub enabled: bool,
+}
+
+impl Default for EventFeedSettings {
+    fn default() -> Self {
+        Self { enabled: true }
+    }
+}
+
+/// Marker for the feed's root UI node.
+#[derive(Component)]
+struct EventFeed;
+
+/// A single feed entry with its age in seconds.
+#[derive(Component)]
+struct FeedEntry {
+    age: f32,
+}
+
+/// Maximum number of entries shown at once (oldest are dropped first).
+const MAX_FEED_ENTRIES: usize = 6;
+
+/// Seconds an entry stays fully visible before fading.
+const FEED_ENTRY_HOLD: f32 = 3.0;
+
+/// Seconds an entry takes to fade out.
+const FEED_ENTRY_FADE: f32 = 1.0;
+
+/// Color of feed text (dark, for the light background).
+const FEED_TEXT_COLOR: Color = Color::srgb(0.15, 0.15, 0.15);
+
+/// Spawn the (empty) feed panel on the left side of the playfield.
+fn spawn_event_feed(mut commands: Commands, settings: Res<EventFeedSettings>) {
+    commands.spawn((
+        Name::new("Event Feed"),
+        EventFeed,
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(60.0),
+            left: Val::Px(8.0),
+            width: Val::Px(140.0),
+            flex_direction: FlexDirection::Column,
+            row_gap: Val::Px(4.0),
+            ..default()
+        },
+        if settings.enabled {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        },
+        Pickable::IGNORE,
+        DespawnOnExit(InGame),
+    ));
+}
+
+/// Turn gameplay messages into feed entries.
+fn push_feed_entries(
+    mut commands: Commands,
+    settings: Res<EventFeedSettings>,
+    game_font: Res<GameFont>,
+    feed_query: Query<(Entity, Option<&Children>), With<EventFeed>>,
+    mut cluster_events: MessageReader<ClusterPopped>,
+    mut floating_events: MessageReader<FloatingBubblesRemoved>,
+    mut descent_events: MessageReader<TriggerDescent>,
+    mut grace_events: MessageReader<GraceBounceUsed>,
+    (powerups, catalog): (Res<UnlockedPowerUps>, Res<PowerUpCatalog>),
+    mut known_powerups: Local<usize>,
+) {
+    let mut lines = Vec::new();
+
+    for event in cluster_events.read() {
+        let color = format!("{:?}s", event.color).to_lowercase();
+        lines.push(format!("Popped {} {}!", event.count, color));
+    }
+    for event in floating_events.read() {
+        lines.push(format!("Dropped {} floaters", event.count));
+    }
+    for _ in descent_events.read() {
+        lines.push("Descent!".to_string());
+    }
+    for _ in grace_events.read() {
+        lines.push("Last chance used!".to_string());
+    }
+
+    // Power-ups have no message; watch the unlocked list grow instead
+    if powerups.powers.len() < *known_powerups {
+        *known_powerups = 0;
+    }
+    for power in &powerups.powers[*known_powerups..] {
+        lines.push(format!("Power-up: {}", catalog.name(*power)));
+    }
+    *known_powerups = powerups.powers.len();
+
+    if !settings.enabled || lines.is_empty() {
+        return;
+    }
+    let Ok((feed, children)) = feed_query.single() else {
+        return;
+    };
+
+    // Drop the oldest entries to make room
+    let existing = children.map(|c| c.len()).unwrap_or(0);
+    let overflow = (existing + lines.len()).saturating_sub(MAX_FEED_ENTRIES);
+    if let Some(children) = children {
+        for child in children.iter().take(overflow) {
+            commands.entity(child).despawn();
+        }
+    }
+
+    for line in lines.into_iter().rev().take(MAX_FEED_ENTRIES).rev() {
+        let entry = commands
+            .spawn((
+                Name::new("Feed Entry"),
+                FeedEntry { age: 0.0 },
+                Text::new(line),
+                TextFont {
+                    font: game_font.0.clone(),
+                    font_size: 12.0,
+                    ..default()
+                },
+                TextColor(FEED_TEXT_COLOR),
+                Pickable::IGNORE,
+            ))
+            .id();
+        commands.entity(feed).add_child(entry);
+    }
+}
+
+/// Age feed entries, fade them out, and despawn them when done.
+fn fade_feed_entries(
+    mut commands: Commands,
+    time: Res<Time<Real>>,
+    mut query: Query<(Entity, &mut FeedEntry, &mut TextColor)>,
+) {
+    for (entity, mut entry, mut color) in &mut query {
+        entry.age += time.delta_secs();
+
+        let fade = ((entry.age - FEED_ENTRY_HOLD) / FEED_ENTRY_FADE).clamp(0.0, 1.0);
+        color.0 = FEED_TEXT_COLOR.with_alpha(1.0 - fade);
+
+        if fade >= 1.0 {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+/// Show or hide the feed when the setting changes.
+fn apply_feed_visibility(
+    settings: Res<EventFeedSettings>,
+    mut feed_query: Query<&mut Visibility, With<EventFeed>>,
+) {
+    for mut visibility in &mut feed_query {
+        *visibility = if settings.enabled {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+    }
+}
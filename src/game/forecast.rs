@@ -0,0 +1,298 @@
+//! Landing forecasts - working out a shot's outcome while it flies.
+//!
+//! When a projectile is fired, a background task follows its path with
+//! [`predict_landing`] and resolves the landing on a copy of the board: the
+//! cluster that pops and the bubbles that fall with it. If the shot lands
+//! where predicted on a board that hasn't changed since, the cluster systems
+//! use that instead of doing the work in the landing frame. Anything else (a
+//! wall that moved, another shot landing first, a forecast still running)
+//! falls back to the live checks.
+
+use std::collections::{HashMap, HashSet};
+
+use bevy::prelude::*;
+
+use super::{
+    bubble::BubbleColor,
+    bumpers::Bumpers,
+    cluster::{
+        LevelAnchors, MIN_CLUSTER_SIZE, find_anchored_bubbles, find_cluster, wildcard_color,
+    },
+    grid::HexGrid,
+    hex::{GridOffset, HexCoord},
+    mode::GameMode,
+    portals::Portals,
+    powerups::UnlockedPowerUps,
+    projectile::{
+        BubbleLanded, Projectile, ProjectileSystems, Walls, collision_distance, predict_landing,
+    },
+    sticky_walls::StickyWalls,
+    wildfire::Wildfire,
+};
+use crate::{
+    PausableSystems,
+    compute::{AddComputeMessage, SpawnCompute},
+    screens::InGame,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<LandingForecasts>();
+    app.add_compute_message::<LandingForecast>();
+
+    app.add_systems(OnEnter(InGame), clear_forecasts);
+    app.add_systems(
+        Update,
+        (
+            start_forecasts.in_set(PausableSystems),
+            // Not pausable, so a forecast finishing during a pause isn't lost
+            collect_forecasts,
+        )
+            .before(ProjectileSystems)
+            .run_if(in_state(InGame)),
+    );
+}
+
+/// A shot's outcome, worked out while it flew.
+#[derive(Message, Debug, Clone, PartialEq)]
+pub struct LandingForecast {
+    /// Where the shot is expected to land.
+    pub coord: HexCoord,
+    pub color: BubbleColor,
+    /// The cluster that pops, or empty if the landing doesn't make a match.
+    pub cluster: Vec<HexCoord>,
+    /// Bubbles cut off once the cluster pops.
+    pub floating: Vec<HexCoord>,
+    /// What the rest of the board hangs from once the cluster pops.
+    pub anchors: HashSet<HexCoord>,
+    /// The grid's size and [`HexGrid::removed`] count when the shot was
+    /// fired, to tell whether the board changed during the flight.
+    board: (usize, usize),
+}
+
+/// Finished forecasts for shots still in flight.
+#[derive(Resource, Debug, Default)]
+pub struct LandingForecasts {
+    ready: Vec<LandingForecast>,
+    /// The forecast the latest pop came from, for the floating check.
+    pub applied: Option<LandingForecast>,
+}
+
+impl LandingForecasts {
+    /// The forecast for this frame's `landed` shot, if it was made on the
+    /// board as it is now apart from the landed bubble.
+    ///
+    /// A landing changes the board every other forecast was made on, so they
+    /// are all dropped. Several landings in one frame never use a forecast.
+    pub fn take(&mut self, landed: &[BubbleLanded], grid: &HexGrid) -> Option<LandingForecast> {
+        if landed.is_empty() {
+            return None;
+        }
+        let mut ready = std::mem::take(&mut self.ready);
+        let [event] = landed else {
+            return None;
+        };
+        let index = ready
+            .iter()
+            .position(|forecast| forecast.coord == event.coord && forecast.color == event.color)?;
+        let forecast = ready.swap_remove(index);
+        (forecast.board == (grid.len() - 1, grid.removed())).then_some(forecast)
+    }
+}
+
+/// Everything a forecast needs, copied so it can be worked out off the main
+/// thread.
+struct ForecastInput {
+    grid: HexGrid,
+    colors: HashMap<HexCoord, BubbleColor>,
+    origin_y: f32,
+    walls: Walls,
+    portals: Portals,
+    sticky: StickyWalls,
+    bumpers: Bumpers,
+    level_anchors: LevelAnchors,
+    start: Vec2,
+    direction: Vec2,
+    color: BubbleColor,
+    wildcard: bool,
+    wildfire: Wildfire,
+    collision_distance: f32,
+}
+
+impl ForecastInput {
+    /// Follow the shot and resolve its landing. None if it never lands on the
+    /// grid, or lands in the danger zone.
+    fn resolve(mut self) -> Option<LandingForecast> {
+        let board = (self.grid.len(), self.grid.removed());
+        let landing = predict_landing(
+            &self.grid,
+            self.origin_y,
+            self.walls,
+            &self.portals,
+            &self.sticky,
+            &self.bumpers,
+            self.start,
+            self.direction,
+            self.collision_distance,
+        )?;
+        if landing.in_danger {
+            return None;
+        }
+
+        let coord = landing.coord;
+        let color = if self.wildcard {
+            wildcard_color(&self.grid.layout, coord, |coord| {
+                self.colors.get(&coord).copied()
+            })
+            .unwrap_or(self.color)
+        } else {
+            self.color
+        };
+        self.grid.insert(coord, Entity::PLACEHOLDER);
+        self.colors.insert(coord, color);
+        let wildfire = self.wildfire;
+        let mut cluster =
+            find_cluster(&self.grid.layout, coord, wildfire.resolve(color), |coord| {
+                self.colors
+                    .get(&coord)
+                    .map(|&color| wildfire.resolve(color))
+            });
+        if cluster.len() < MIN_CLUSTER_SIZE {
+            cluster.clear();
+        }
+        for &coord in &cluster {
+            self.grid.remove(coord);
+        }
+
+        let mut anchors: HashSet<HexCoord> =
+            self.level_anchors.roots(&self.grid).into_iter().collect();
+        anchors.extend(self.sticky.anchors(&self.grid, self.origin_y, self.walls));
+        // Off the main thread there's time to check the whole board, so the
+        // result doesn't rely on everything having hung before the pop
+        let floating = if cluster.is_empty() {
+            Vec::new()
+        } else {
+            let anchored = find_anchored_bubbles(&self.grid, anchors.iter().copied().collect());
+            self.grid
+                .coords()
+                .filter(|coord| !anchored.contains(coord))
+                .collect()
+        };
+
+        Some(LandingForecast {
+            coord,
+            color,
+            cluster,
+            floating,
+            anchors,
+            board,
+        })
+    }
+}
+
+fn clear_forecasts(mut forecasts: ResMut<LandingForecasts>) {
+    *forecasts = LandingForecasts::default();
+}
+
+/// Start a forecast for each new projectile.
+fn start_forecasts(
+    mut commands: Commands,
+    projectiles: Query<(&Transform, &Projectile), Added<Projectile>>,
+    bubbles: Query<&BubbleColor>,
+    grid: Res<HexGrid>,
+    grid_offset: Res<GridOffset>,
+    walls: Res<Walls>,
+    portals: Res<Portals>,
+    sticky: Res<StickyWalls>,
+    bumpers: Res<Bumpers>,
+    level_anchors: Res<LevelAnchors>,
+    powerups: Res<UnlockedPowerUps>,
+    mode: Res<GameMode>,
+    wildfire: Res<Wildfire>,
+) {
+    if projectiles.is_empty() {
+        return;
+    }
+    let colors: HashMap<HexCoord, BubbleColor> = grid
+        .iter()
+        .filter_map(|(&coord, &entity)| Some((coord, *bubbles.get(entity).ok()?)))
+        .collect();
+
+    for (transform, projectile) in &projectiles {
+        let input = ForecastInput {
+            grid: grid.clone(),
+            colors: colors.clone(),
+            origin_y: grid_offset.y,
+            walls: *walls,
+            portals: portals.clone(),
+            sticky: sticky.clone(),
+            bumpers: bumpers.clone(),
+            level_anchors: level_anchors.clone(),
+            start: transform.translation.truncate(),
+            direction: projectile.velocity,
+            color: projectile.color,
+            wildcard: projectile.wildcard,
+            wildfire: *wildfire,
+            collision_distance: collision_distance(&grid, &powerups, *mode),
+        };
+        commands.spawn_compute(InGame, move || input.resolve());
+    }
+}
+
+/// Keep finished forecasts until their shots land.
+fn collect_forecasts(
+    mut forecasts: ResMut<LandingForecasts>,
+    mut finished: MessageReader<LandingForecast>,
+) {
+    forecasts.ready.extend(finished.read().cloned());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::{hex::GRID_ORIGIN_Y, shooter::SHOOTER_Y};
+
+    #[test]
+    fn forecast_pops_the_cluster_and_drops_what_hung_from_it() {
+        // Three reds along the top, a blue hanging off the right one, and a
+        // green holding the top row in place
+        let layout = [
+            (-1, 0, BubbleColor::Red),
+            (0, 0, BubbleColor::Red),
+            (1, 0, BubbleColor::Red),
+            (1, 1, BubbleColor::Blue),
+            (5, 0, BubbleColor::Green),
+        ];
+        let mut grid = HexGrid::default();
+        let mut colors = HashMap::new();
+        for (q, r, color) in layout {
+            grid.insert(HexCoord::new(q, r), Entity::PLACEHOLDER);
+            colors.insert(HexCoord::new(q, r), color);
+        }
+        let collision_distance = grid.hex_size * GameMode::default().collision_reach();
+
+        // A red shot straight up into the middle
+        let forecast = ForecastInput {
+            grid,
+            colors,
+            origin_y: GRID_ORIGIN_Y,
+            walls: Walls::default(),
+            portals: Portals::default(),
+            sticky: StickyWalls::default(),
+            bumpers: Bumpers::default(),
+            level_anchors: LevelAnchors::default(),
+            start: Vec2::new(0.0, SHOOTER_Y),
+            direction: Vec2::Y,
+            color: BubbleColor::Red,
+            wildcard: false,
+            wildfire: Wildfire::default(),
+            collision_distance,
+        }
+        .resolve()
+        .expect("the shot lands");
+
+        assert_eq!(forecast.cluster.len(), 4);
+        assert!(forecast.cluster.contains(&forecast.coord));
+        assert_eq!(forecast.floating, vec![HexCoord::new(1, 1)]);
+        assert_eq!(forecast.anchors, HashSet::from([HexCoord::new(5, 0)]));
+    }
+}
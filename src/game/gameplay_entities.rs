@@ -0,0 +1,65 @@
+//! Per-run gameplay entities.
+//!
+//! Effects and decorations that belong to one run (trajectory segments, combo
+//! text, falling bubbles, confetti, doodles) are tagged [`GameplayEntity`]
+//! rather than despawned with the screen. World-space ones are parented under
+//! the [`GameRoot`] as they're tagged, so a run's hierarchy stays in one
+//! place; screen-space text can't join a 2D hierarchy, so it stays loose and
+//! is found by its tag.
+//!
+//! Writing [`ClearGameplayEntities`] despawns them all, so a run can be reset
+//! without cycling through the screens. They're also cleared when the run
+//! ends.
+
+use bevy::prelude::*;
+
+use super::messages::AddGameMessage;
+use crate::screens::InGame;
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_game_message::<ClearGameplayEntities>("Despawn everything spawned for this run");
+
+    app.add_observer(adopt_gameplay_entity);
+    app.add_systems(OnExit(InGame), despawn_gameplay_entities);
+    app.add_systems(
+        Update,
+        despawn_gameplay_entities.run_if(in_state(InGame).and(on_message::<ClearGameplayEntities>)),
+    );
+}
+
+/// The entity a run's world-space entities hang off.
+#[derive(Component, Debug, Clone, Copy)]
+pub struct GameRoot;
+
+/// An entity that only lives for one run.
+#[derive(Component, Debug, Clone, Copy, Default)]
+pub struct GameplayEntity;
+
+/// Message to despawn every [`GameplayEntity`].
+#[derive(Message, Debug, Clone)]
+pub struct ClearGameplayEntities;
+
+/// Parent newly tagged world-space entities under the root, if it's there.
+fn adopt_gameplay_entity(
+    add: On<Add, GameplayEntity>,
+    mut commands: Commands,
+    roots: Query<Entity, With<GameRoot>>,
+    loose: Query<(), (Without<ChildOf>, Without<Node>)>,
+) {
+    let Ok(root) = roots.single() else {
+        return;
+    };
+    if loose.contains(add.entity) {
+        commands.entity(root).add_child(add.entity);
+    }
+}
+
+fn despawn_gameplay_entities(
+    mut commands: Commands,
+    entities: Query<Entity, With<GameplayEntity>>,
+) {
+    // Children of the root may already be going with it
+    for entity in &entities {
+        commands.entity(entity).try_despawn();
+    }
+}
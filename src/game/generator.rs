@@ -0,0 +1,285 @@
+//! Procedural board generator for endless, campaign-style levels.
+//!
+//! Boards grow down from a full top row, so every bubble hangs from the
+//! ceiling. Parameters control how full the board is, how many colors it
+//! uses, how much colors clump together, how many bubbles hang below the main
+//! body, and whether the two halves mirror each other.
+
+use std::collections::HashMap;
+
+use rand::Rng;
+
+use super::{
+    bubble::BubbleColor, grid::GridBounds, hex::HexCoord, layout::is_anchored, snapshot::GridCell,
+};
+
+/// Fewest bubbles of a color on a generated board. Rarer colors are merged
+/// into their neighbors so they don't leave lone bubbles behind.
+const MIN_BUBBLES_PER_COLOR: usize = 3;
+
+/// Knobs for a generated board.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GeneratorParams {
+    /// Rows in the main body, counting the full top row.
+    pub rows: i32,
+    /// Chance each cell below the top row is filled (0.0 to 1.0).
+    pub density: f32,
+    /// Number of colors, taken from the start of [`BubbleColor::ALL`].
+    pub colors: usize,
+    /// Chance a bubble copies the color of the bubble it hangs from, which
+    /// seeds ready-made clusters (0.0 to 1.0).
+    pub clustering: f32,
+    /// Chains of bubbles hanging below the main body. Doubled on symmetric
+    /// boards, where each side gets its own.
+    pub overhangs: u32,
+    /// Mirror the left half onto the right.
+    pub symmetric: bool,
+}
+
+impl GeneratorParams {
+    /// Parameters that ramp up with the level number (starting at 1).
+    pub fn for_level(level: u32) -> Self {
+        let step = level.saturating_sub(1);
+        Self {
+            rows: (4 + step as i32 / 2).min(8),
+            density: (0.6 + 0.04 * step as f32).min(0.95),
+            colors: (3 + step as usize / 3).min(BubbleColor::ALL.len()),
+            clustering: (0.6 - 0.05 * step as f32).max(0.2),
+            overhangs: (step / 2).min(4),
+            symmetric: level.is_multiple_of(2),
+        }
+    }
+}
+
+/// Generate a board that fits `bounds`.
+pub fn generate(params: &GeneratorParams, bounds: GridBounds, rng: &mut impl Rng) -> Vec<GridCell> {
+    let palette = &BubbleColor::ALL[..params.colors.clamp(1, BubbleColor::ALL.len())];
+    let last_row = (bounds.min_r + params.rows - 1).min(bounds.max_r);
+    // Symmetric boards are generated on the left half (x <= 0) and mirrored
+    let in_half = |coord: HexCoord| {
+        bounds.contains(coord) && (!params.symmetric || 2 * coord.q + coord.r.rem_euclid(2) <= 0)
+    };
+
+    // Grow the body row by row; a cell can only fill if it hangs from one above
+    let mut colors: HashMap<HexCoord, BubbleColor> = HashMap::new();
+    for r in bounds.min_r..=last_row {
+        for q in bounds.min_q..=bounds.max_q {
+            let coord = HexCoord::new(q, r);
+            if !in_half(coord) {
+                continue;
+            }
+            let parents = parents_of(coord, &colors);
+            let color = if r == bounds.min_r {
+                pick_color(None, params.clustering, palette, rng)
+            } else if !parents.is_empty() && rng.random_bool(params.density.clamp(0.0, 1.0) as f64)
+            {
+                let parent = parents[rng.random_range(0..parents.len())];
+                pick_color(Some(parent), params.clustering, palette, rng)
+            } else {
+                continue;
+            };
+            colors.insert(coord, color);
+        }
+    }
+
+    // Hang chains off the bottom of the body
+    for _ in 0..params.overhangs {
+        let mut bottom: Vec<HexCoord> = colors
+            .keys()
+            .copied()
+            .filter(|coord| coord.r == last_row)
+            .collect();
+        // Sorted so the same rng gives the same board
+        bottom.sort_by_key(|coord| coord.q);
+        if bottom.is_empty() {
+            break;
+        }
+        let mut coord = bottom[rng.random_range(0..bottom.len())];
+        for _ in 0..rng.random_range(2..=3) {
+            // Southwest or southeast
+            let below = coord.neighbors()[rng.random_range(4..6)];
+            if !in_half(below) || colors.contains_key(&below) {
+                break;
+            }
+            let color = pick_color(colors.get(&coord).copied(), params.clustering, palette, rng);
+            colors.insert(below, color);
+            coord = below;
+        }
+    }
+
+    // Merge before mirroring so both halves get the same colors
+    merge_rare_colors(&mut colors);
+
+    if params.symmetric {
+        let mirrored: Vec<(HexCoord, BubbleColor)> = colors
+            .iter()
+            .map(|(&coord, &color)| (coord.mirrored(), color))
+            .filter(|(coord, _)| bounds.contains(*coord))
+            .collect();
+        colors.extend(mirrored);
+    }
+
+    let mut cells: Vec<GridCell> = colors
+        .into_iter()
+        .map(|(coord, color)| GridCell { coord, color })
+        .collect();
+    cells.sort_by_key(|cell| (cell.coord.r, cell.coord.q));
+    debug_assert!(looks_solvable(&cells, bounds), "generated {cells:?}");
+    cells
+}
+
+/// Quick checks that a board is fair to start a run with: something to
+/// shoot at, nothing floating, and no color too rare to pop.
+pub fn looks_solvable(cells: &[GridCell], bounds: GridBounds) -> bool {
+    let mut counts: HashMap<BubbleColor, usize> = HashMap::new();
+    for cell in cells {
+        *counts.entry(cell.color).or_default() += 1;
+    }
+    !cells.is_empty()
+        && cells.iter().all(|cell| bounds.contains(cell.coord))
+        && is_anchored(cells, bounds.min_r)
+        && counts.values().all(|&count| count >= MIN_BUBBLES_PER_COLOR)
+}
+
+/// Colors of the filled cells in the row above that `coord` touches.
+fn parents_of(coord: HexCoord, colors: &HashMap<HexCoord, BubbleColor>) -> Vec<BubbleColor> {
+    // Northeast and northwest
+    coord.neighbors()[1..3]
+        .iter()
+        .filter_map(|parent| colors.get(parent).copied())
+        .collect()
+}
+
+fn pick_color(
+    parent: Option<BubbleColor>,
+    clustering: f32,
+    palette: &[BubbleColor],
+    rng: &mut impl Rng,
+) -> BubbleColor {
+    match parent {
+        Some(color) if rng.random_bool(clustering.clamp(0.0, 1.0) as f64) => color,
+        _ => palette[rng.random_range(0..palette.len())],
+    }
+}
+
+/// Recolor bubbles of any color that's too rare to pop to match a neighbor,
+/// falling back to the most common color.
+fn merge_rare_colors(colors: &mut HashMap<HexCoord, BubbleColor>) {
+    loop {
+        let mut counts: HashMap<BubbleColor, usize> = HashMap::new();
+        for &color in colors.values() {
+            *counts.entry(color).or_default() += 1;
+        }
+        let Some(rare) = counts
+            .iter()
+            .find(|&(_, &count)| count < MIN_BUBBLES_PER_COLOR)
+            .map(|(&color, _)| color)
+        else {
+            return;
+        };
+        let Some(common) = counts
+            .iter()
+            .filter(|&(&color, _)| color != rare)
+            .max_by_key(|&(_, &count)| count)
+            .map(|(&color, _)| color)
+        else {
+            // A tiny board with a single color; nothing to merge into
+            return;
+        };
+
+        let mut rare_cells: Vec<HexCoord> = colors
+            .iter()
+            .filter(|&(_, &color)| color == rare)
+            .map(|(&coord, _)| coord)
+            .collect();
+        rare_cells.sort_by_key(|coord| (coord.r, coord.q));
+        for coord in rare_cells {
+            let neighbor = coord
+                .neighbors()
+                .iter()
+                .filter_map(|neighbor| colors.get(neighbor).copied())
+                .find(|&color| color != rare);
+            colors.insert(coord, neighbor.unwrap_or(common));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use rand::{SeedableRng, rngs::StdRng};
+
+    use super::*;
+
+    #[test]
+    fn generated_boards_look_solvable() {
+        let bounds = GridBounds::default();
+        for level in 1..=12 {
+            let params = GeneratorParams::for_level(level);
+            for seed in 0..20 {
+                let mut rng = StdRng::seed_from_u64(seed);
+                let cells = generate(&params, bounds, &mut rng);
+                assert!(
+                    looks_solvable(&cells, bounds),
+                    "level {level} seed {seed} isn't solvable: {cells:?}"
+                );
+                let colors: HashSet<BubbleColor> = cells.iter().map(|cell| cell.color).collect();
+                assert!(colors.len() <= params.colors);
+            }
+        }
+    }
+
+    #[test]
+    fn symmetric_boards_mirror() {
+        let params = GeneratorParams {
+            symmetric: true,
+            overhangs: 2,
+            ..GeneratorParams::for_level(5)
+        };
+        let mut rng = StdRng::seed_from_u64(7);
+        let cells = generate(&params, GridBounds::default(), &mut rng);
+        let occupied: HashMap<HexCoord, BubbleColor> =
+            cells.iter().map(|cell| (cell.coord, cell.color)).collect();
+        for cell in &cells {
+            let mirrored = cell.coord.mirrored();
+            if GridBounds::default().contains(mirrored) {
+                assert_eq!(occupied.get(&mirrored), Some(&cell.color), "{}", cell.coord);
+            }
+        }
+    }
+
+    #[test]
+    fn density_and_overhangs_shape_the_board() {
+        let bounds = GridBounds::default();
+        let sparse = GeneratorParams {
+            rows: 6,
+            density: 0.0,
+            colors: 3,
+            clustering: 0.5,
+            overhangs: 0,
+            symmetric: false,
+        };
+        let mut rng = StdRng::seed_from_u64(1);
+        let cells = generate(&sparse, bounds, &mut rng);
+        assert!(cells.iter().all(|cell| cell.coord.r == 0));
+
+        let full = GeneratorParams {
+            density: 1.0,
+            overhangs: 3,
+            ..sparse
+        };
+        let cells = generate(&full, bounds, &mut rng);
+        assert!(cells.iter().any(|cell| cell.coord.r > full.rows - 1));
+    }
+
+    #[test]
+    fn rare_colors_are_merged() {
+        let mut colors: HashMap<HexCoord, BubbleColor> = (-2..=2)
+            .map(|q| (HexCoord::new(q, 0), BubbleColor::Red))
+            .collect();
+        colors.insert(HexCoord::new(0, 1), BubbleColor::Blue);
+        merge_rare_colors(&mut colors);
+        assert!(colors.values().all(|&color| color == BubbleColor::Red));
+    }
+}
@@ -0,0 +1,295 @@
+//! End-of-level grades for campaign levels and puzzle drills.
+//!
+//! While a level is played, [`LevelStats`] counts the shots fired, the
+//! unpaused time taken, and the best chain (the most bubbles popped and
+//! dropped by one shot). Winning the level opens the results screen, which
+//! grades the stats against the level's par:
+//! - Shots: at most par earns full marks, up to half again earns half
+//! - Time: par is [`SECS_PER_PAR_SHOT`] per par shot, with the same steps
+//! - Chain: a chain of [`GREAT_CHAIN`] earns full marks, [`GOOD_CHAIN`] half
+//!
+//! Full marks everywhere is an S, and each half mark missed drops a step.
+//! The best rank per level is saved to `level_ranks.json` and shown as a
+//! badge in the drills menu.
+
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    campaign::{ActiveLevel, CampaignLevels},
+    clock::GameClock,
+    cluster::{ClusterPopped, FloatingBubblesRemoved},
+    drills::{ActiveDrill, Drills},
+    projectile::FireProjectile,
+};
+use crate::{PausableSystems, menus::Menu, save::SaveFile, screens::InGame};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<LevelStats>();
+    app.init_resource::<LevelRanks>();
+    app.init_resource::<LevelGrade>();
+
+    app.add_systems(Startup, load_level_ranks);
+    app.add_systems(OnEnter(InGame), reset_level_stats);
+    app.add_systems(
+        Update,
+        track_level_stats
+            .in_set(PausableSystems)
+            .run_if(in_state(InGame)),
+    );
+    app.add_systems(OnEnter(Menu::LevelResults), grade_level);
+}
+
+/// Seconds allowed per par shot before the time mark drops.
+pub const SECS_PER_PAR_SHOT: f32 = 5.0;
+
+/// Bubbles one shot has to clear for full chain marks.
+pub const GREAT_CHAIN: u32 = 8;
+
+/// Bubbles one shot has to clear for half chain marks.
+pub const GOOD_CHAIN: u32 = 5;
+
+/// A level's grade, worst first so better ranks compare greater.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Rank {
+    C,
+    B,
+    A,
+    S,
+}
+
+impl Rank {
+    pub fn label(self) -> &'static str {
+        match self {
+            Rank::C => "C",
+            Rank::B => "B",
+            Rank::A => "A",
+            Rank::S => "S",
+        }
+    }
+
+    pub fn color(self) -> Color {
+        match self {
+            Rank::S => Color::srgb(0.95, 0.7, 0.1),
+            Rank::A => Color::srgb(0.85, 0.25, 0.2),
+            Rank::B => Color::srgb(0.25, 0.45, 0.8),
+            Rank::C => Color::srgb(0.45, 0.45, 0.45),
+        }
+    }
+}
+
+/// How the current level is being played.
+#[derive(Resource, Debug, Clone, Default, PartialEq)]
+pub struct LevelStats {
+    pub shots: u32,
+    /// Unpaused seconds since the level started.
+    pub secs: f32,
+    /// Most bubbles popped and dropped in one frame, which is one shot's
+    /// worth unless two land together.
+    pub best_chain: u32,
+}
+
+impl LevelStats {
+    /// The rank these stats earn against `par` shots.
+    pub fn rank(&self, par: u32) -> Rank {
+        let par = par.max(1);
+        let par_secs = par as f32 * SECS_PER_PAR_SHOT;
+        let shots = marks(self.shots as f32, par as f32);
+        let time = marks(self.secs, par_secs);
+        let chain = match self.best_chain {
+            GREAT_CHAIN.. => 2,
+            GOOD_CHAIN.. => 1,
+            _ => 0,
+        };
+        match shots + time + chain {
+            6 => Rank::S,
+            4..=5 => Rank::A,
+            2..=3 => Rank::B,
+            _ => Rank::C,
+        }
+    }
+}
+
+/// Full marks for reaching `par`, half for up to half again.
+fn marks(used: f32, par: f32) -> u32 {
+    if used <= par {
+        2
+    } else if used <= par * 1.5 {
+        1
+    } else {
+        0
+    }
+}
+
+/// Which level a grade is for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GradedLevel {
+    Campaign(String),
+    Drill(String),
+}
+
+impl GradedLevel {
+    pub fn name(&self) -> &str {
+        match self {
+            GradedLevel::Campaign(name) | GradedLevel::Drill(name) => name,
+        }
+    }
+}
+
+/// The grade shown on the results screen, set as it opens.
+#[derive(Resource, Debug, Clone, Default)]
+pub struct LevelGrade(pub Option<Grade>);
+
+#[derive(Debug, Clone)]
+pub struct Grade {
+    pub level: GradedLevel,
+    pub rank: Rank,
+    pub par: u32,
+    pub stats: LevelStats,
+    /// Whether this beat the level's saved rank.
+    pub new_best: bool,
+}
+
+/// Best rank per level name, kept apart for campaign levels and drills.
+#[derive(Resource, Debug, Default, Serialize, Deserialize)]
+pub struct LevelRanks {
+    #[serde(default)]
+    pub campaign: HashMap<String, Rank>,
+    #[serde(default)]
+    pub drills: HashMap<String, Rank>,
+}
+
+impl SaveFile for LevelRanks {
+    const FILE_NAME: &'static str = "level_ranks.json";
+    const DESCRIPTION: &'static str = "level ranks";
+}
+
+impl LevelRanks {
+    pub fn best(&self, level: &GradedLevel) -> Option<Rank> {
+        self.table(level).get(level.name()).copied()
+    }
+
+    /// Record a rank. Returns true if it's a new best.
+    pub fn record(&mut self, level: &GradedLevel, rank: Rank) -> bool {
+        if self.best(level).is_some_and(|best| best >= rank) {
+            return false;
+        }
+        let name = level.name().to_string();
+        match level {
+            GradedLevel::Campaign(_) => self.campaign.insert(name, rank),
+            GradedLevel::Drill(_) => self.drills.insert(name, rank),
+        };
+        true
+    }
+
+    fn table(&self, level: &GradedLevel) -> &HashMap<String, Rank> {
+        match level {
+            GradedLevel::Campaign(_) => &self.campaign,
+            GradedLevel::Drill(_) => &self.drills,
+        }
+    }
+}
+
+fn load_level_ranks(mut ranks: ResMut<LevelRanks>) {
+    *ranks = LevelRanks::load();
+}
+
+fn reset_level_stats(mut stats: ResMut<LevelStats>) {
+    *stats = LevelStats::default();
+}
+
+fn track_level_stats(
+    time: Res<Time<GameClock>>,
+    mut stats: ResMut<LevelStats>,
+    mut fire_events: MessageReader<FireProjectile>,
+    mut cluster_events: MessageReader<ClusterPopped>,
+    mut floating_events: MessageReader<FloatingBubblesRemoved>,
+) {
+    stats.secs += time.delta_secs();
+    stats.shots += fire_events.read().count() as u32;
+    let cleared = cluster_events
+        .read()
+        .map(|event| event.count)
+        .sum::<usize>()
+        + floating_events
+            .read()
+            .map(|event| event.count)
+            .sum::<usize>();
+    stats.best_chain = stats.best_chain.max(cleared as u32);
+}
+
+/// Grade the level that was just won and save it if it's a new best.
+pub fn grade_level(
+    active_level: Res<ActiveLevel>,
+    levels: Res<CampaignLevels>,
+    active_drill: Res<ActiveDrill>,
+    drills: Res<Drills>,
+    stats: Res<LevelStats>,
+    mut ranks: ResMut<LevelRanks>,
+    mut grade: ResMut<LevelGrade>,
+) {
+    let drill = active_drill.0.and_then(|index| drills.0.get(index));
+    let graded = match drill {
+        Some(drill) => Some((GradedLevel::Drill(drill.name.clone()), drill.par())),
+        None => active_level
+            .0
+            .and_then(|index| levels.0.get(index))
+            .map(|level| (GradedLevel::Campaign(level.name.clone()), level.par())),
+    };
+    let Some((level, par)) = graded else {
+        grade.0 = None;
+        return;
+    };
+
+    let rank = stats.rank(par);
+    let new_best = ranks.record(&level, rank);
+    if new_best {
+        ranks.save();
+    }
+    info!("'{}' graded {}", level.name(), rank.label());
+    grade.0 = Some(Grade {
+        level,
+        rank,
+        par,
+        stats: stats.clone(),
+        new_best,
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stats(shots: u32, secs: f32, best_chain: u32) -> LevelStats {
+        LevelStats {
+            shots,
+            secs,
+            best_chain,
+        }
+    }
+
+    #[test]
+    fn ranks_drop_a_step_per_half_mark() {
+        assert_eq!(stats(10, 50.0, GREAT_CHAIN).rank(10), Rank::S);
+        assert_eq!(stats(12, 50.0, GREAT_CHAIN).rank(10), Rank::A);
+        assert_eq!(stats(12, 60.0, GOOD_CHAIN).rank(10), Rank::B);
+        assert_eq!(stats(20, 200.0, 3).rank(10), Rank::C);
+    }
+
+    #[test]
+    fn only_better_ranks_are_recorded() {
+        let mut ranks = LevelRanks::default();
+        let level = GradedLevel::Drill("Bank Shot".to_string());
+
+        assert!(ranks.record(&level, Rank::B));
+        assert!(!ranks.record(&level, Rank::C));
+        assert!(ranks.record(&level, Rank::S));
+        assert_eq!(ranks.best(&level), Some(Rank::S));
+        assert_eq!(
+            ranks.best(&GradedLevel::Campaign("Bank Shot".to_string())),
+            None
+        );
+    }
+}
@@ -4,10 +4,15 @@
 //! This is more flexible than a 2D array and handles the hex coordinate
 //! system naturally.
 
-use bevy::prelude::*;
-use std::collections::HashMap;
+use bevy::{ecs::system::SystemParam, prelude::*};
+use std::{collections::HashMap, ops::Deref};
 
-use super::hex::{HEX_SIZE, HexCoord};
+use super::{
+    bubble::{Bubble, BubbleColor, BubbleKind, GameAssets, spawn_bubble},
+    bubble_material::BubbleMaterial,
+    hex::{BoardLayout, GRID_ORIGIN_Y, GridOffset, HEX_SIZE, HexCoord, OddRPointy, SQRT_3},
+    projectile::{LEFT_WALL, RIGHT_WALL},
+};
 
 pub(super) fn plugin(app: &mut App) {
     app.init_resource::<HexGrid>();
@@ -18,7 +23,7 @@ pub(super) fn plugin(app: &mut App) {
 /// The bounds of the playable grid area.
 ///
 /// Defines which hex coordinates are valid for the game.
-#[derive(Debug, Clone, Copy, Reflect)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Reflect)]
 pub struct GridBounds {
     /// Minimum q coordinate (left edge).
     pub min_q: i32,
@@ -58,6 +63,18 @@ impl GridBounds {
             && coord.r <= self.max_r
     }
 
+    /// These bounds with as many columns as fit between the walls for hexes
+    /// of `hex_size`. Odd rows sit half a hex right, so they decide the right
+    /// edge, and even rows the left.
+    pub fn fit_columns(self, hex_size: f32) -> Self {
+        let width = hex_size * SQRT_3;
+        Self {
+            min_q: (LEFT_WALL / width + 0.5).ceil() as i32,
+            max_q: (RIGHT_WALL / width - 1.0).floor() as i32,
+            ..self
+        }
+    }
+
     /// Iterate over all valid hex coordinates in the grid.
     #[allow(dead_code)]
     pub fn iter(&self) -> impl Iterator<Item = HexCoord> {
@@ -73,7 +90,6 @@ impl GridBounds {
     }
 
     /// Get the number of columns for a given row.
-    #[allow(dead_code)]
     pub fn columns_in_row(&self, _r: i32) -> i32 {
         self.max_q - self.min_q + 1
     }
@@ -88,15 +104,40 @@ impl GridBounds {
 }
 
 /// The main grid resource holding all bubbles.
-#[derive(Resource, Debug, Default, Reflect)]
+#[derive(Resource, Debug, Clone, Reflect)]
 #[reflect(Resource)]
 pub struct HexGrid {
     /// Map from hex coordinates to bubble entities.
-    #[reflect(ignore)]
+    ///
+    /// Entities only make sense within this session; saved games use the
+    /// colors by coordinate in a [`GameSnapshot`](super::snapshot::GameSnapshot).
     bubbles: HashMap<HexCoord, Entity>,
 
     /// The playable area bounds.
     pub bounds: GridBounds,
+
+    /// The size (outer radius) of each hexagon in pixels. Set per game mode,
+    /// along with the bounds.
+    pub hex_size: f32,
+
+    /// How the cells are arranged.
+    pub layout: OddRPointy,
+
+    /// Bubbles taken off the grid so far, so the floating check can tell
+    /// when something other than a pop removed one.
+    removed: usize,
+}
+
+impl Default for HexGrid {
+    fn default() -> Self {
+        Self {
+            bubbles: HashMap::default(),
+            bounds: GridBounds::default(),
+            hex_size: HEX_SIZE,
+            layout: OddRPointy,
+            removed: 0,
+        }
+    }
 }
 
 impl HexGrid {
@@ -113,7 +154,7 @@ impl HexGrid {
 
     /// Check if a coordinate is adjacent to any occupied cell.
     fn is_adjacent_to_bubble(&self, coord: HexCoord) -> bool {
-        coord.neighbors().iter().any(|n| self.is_occupied(*n))
+        self.layout.neighbors(coord).any(|n| self.is_occupied(n))
     }
 
     /// Get the bubble entity at a position, if any.
@@ -132,16 +173,23 @@ impl HexGrid {
     ///
     /// Returns the entity that was removed, if any.
     pub fn remove(&mut self, coord: HexCoord) -> Option<Entity> {
-        self.bubbles.remove(&coord)
+        let entity = self.bubbles.remove(&coord);
+        self.removed += usize::from(entity.is_some());
+        entity
     }
 
     /// Clear all bubbles from the grid.
     pub fn clear(&mut self) {
+        self.removed += self.bubbles.len();
         self.bubbles.clear();
     }
 
+    /// How many bubbles have been taken off the grid so far.
+    pub fn removed(&self) -> usize {
+        self.removed
+    }
+
     /// Get the number of bubbles in the grid.
-    #[allow(dead_code)]
     pub fn len(&self) -> usize {
         self.bubbles.len()
     }
@@ -166,9 +214,8 @@ impl HexGrid {
     /// Useful for finding where a projectile can snap to.
     #[allow(dead_code)]
     pub fn empty_neighbors(&self, coord: HexCoord) -> Vec<HexCoord> {
-        coord
-            .neighbors()
-            .into_iter()
+        self.layout
+            .neighbors(coord)
             .filter(|n| self.bounds.contains(*n) && !self.is_occupied(*n))
             .collect()
     }
@@ -179,7 +226,7 @@ impl HexGrid {
     /// It first converts the position to hex coordinates, then finds
     /// the nearest valid empty cell.
     pub fn closest_empty_cell(&self, world_pos: Vec2, grid_origin_y: f32) -> Option<HexCoord> {
-        let target = HexCoord::from_pixel_with_offset(world_pos, HEX_SIZE, grid_origin_y);
+        let target = self.layout.cell_at(world_pos, self.hex_size, grid_origin_y);
 
         // If the target cell is valid and empty, use it
         // Allow cells within bounds OR adjacent to existing bubbles (for descended rows)
@@ -210,7 +257,7 @@ impl HexGrid {
                 }
 
                 // Add neighbors for next iteration
-                for neighbor in coord.neighbors() {
+                for neighbor in self.layout.neighbors(coord) {
                     if !checked.contains(&neighbor) {
                         next_ring.push(neighbor);
                     }
@@ -230,11 +277,26 @@ impl HexGrid {
 
     /// Get the lowest row (highest r value) that has bubbles.
     /// Used for checking game over condition.
-    #[allow(dead_code)]
     pub fn lowest_row(&self) -> Option<i32> {
         self.bubbles.keys().map(|c| c.r).max()
     }
 
+    /// Remove every bubble in the lowest `rows` occupied rows.
+    ///
+    /// Returns the removed entities so the caller can despawn or animate them.
+    pub fn trim_bottom_rows(&mut self, rows: i32) -> Vec<Entity> {
+        let Some(lowest) = self.lowest_row() else {
+            return Vec::new();
+        };
+        let cutoff = lowest - rows + 1;
+
+        let trimmed: Vec<HexCoord> = self.coords().filter(|c| c.r >= cutoff).collect();
+        trimmed
+            .into_iter()
+            .filter_map(|coord| self.remove(coord))
+            .collect()
+    }
+
     /// Get all bubbles in the top row (smallest r value).
     /// Used as starting point for floating bubble detection.
     pub fn top_row_coords(&self) -> Vec<HexCoord> {
@@ -250,3 +312,144 @@ impl HexGrid {
             .collect()
     }
 }
+
+/// The way systems change what's on the grid.
+///
+/// [`HexGrid`] keys, [`Bubble::coord`], and bubble transforms are updated
+/// together here, so they can't drift apart. Reading goes through the grid
+/// itself (this derefs to [`HexGrid`]).
+#[derive(SystemParam)]
+pub struct GridCommands<'w, 's> {
+    grid: ResMut<'w, HexGrid>,
+    offset: ResMut<'w, GridOffset>,
+    bubbles: Query<'w, 's, (&'static mut Bubble, &'static mut Transform)>,
+}
+
+impl Deref for GridCommands<'_, '_> {
+    type Target = HexGrid;
+
+    fn deref(&self) -> &HexGrid {
+        &self.grid
+    }
+}
+
+impl GridCommands<'_, '_> {
+    /// Whether the grid changed since the system last ran.
+    pub fn is_changed(&self) -> bool {
+        self.grid.is_changed()
+    }
+
+    /// The grid's current Y origin.
+    pub fn origin_y(&self) -> f32 {
+        self.offset.y
+    }
+
+    /// The row along the ceiling (see [`GridOffset::ceiling_row`]).
+    pub fn ceiling_row(&self) -> i32 {
+        self.offset.ceiling_row(self.grid.hex_size)
+    }
+
+    /// World position of a cell.
+    pub fn position(&self, coord: HexCoord) -> Vec2 {
+        self.grid
+            .layout
+            .to_pixel(coord, self.grid.hex_size, self.offset.y)
+    }
+
+    /// Color of the bubble at a cell, if there is one. Bombs and stones have
+    /// none.
+    ///
+    /// Bubbles spawned this frame aren't visible until their commands apply.
+    pub fn color(&self, coord: HexCoord) -> Option<BubbleColor> {
+        let entity = self.grid.get(coord)?;
+        self.bubbles
+            .get(entity)
+            .ok()
+            .and_then(|(bubble, _)| bubble.match_color())
+    }
+
+    /// Kind of the bubble at a cell, if there is one.
+    pub fn kind(&self, coord: HexCoord) -> Option<BubbleKind> {
+        let entity = self.grid.get(coord)?;
+        self.bubbles.get(entity).ok().map(|(bubble, _)| bubble.kind)
+    }
+
+    /// Spawn a new bubble at a cell and add it to the grid.
+    pub fn spawn(
+        &mut self,
+        commands: &mut Commands,
+        meshes: &mut Assets<Mesh>,
+        materials: &mut Assets<BubbleMaterial>,
+        coord: HexCoord,
+        color: BubbleColor,
+        game_assets: Option<&GameAssets>,
+    ) -> Entity {
+        let entity = spawn_bubble(
+            commands,
+            meshes,
+            materials,
+            coord,
+            color,
+            self.grid.hex_size,
+            self.offset.y,
+            game_assets,
+        );
+        self.insert(coord, entity);
+        entity
+    }
+
+    /// Put an existing bubble at a cell, moving its component and transform
+    /// to match.
+    ///
+    /// Returns the previous entity if the cell was occupied.
+    pub fn insert(&mut self, coord: HexCoord, entity: Entity) -> Option<Entity> {
+        let previous = self.grid.insert(coord, entity);
+        self.sync(coord, entity);
+        previous
+    }
+
+    /// Take the bubble at a cell off the grid.
+    ///
+    /// The entity is left alone so the caller can animate or despawn it.
+    pub fn remove(&mut self, coord: HexCoord) -> Option<Entity> {
+        self.grid.remove(coord)
+    }
+
+    /// Lower the whole grid by `distance` pixels. Bubbles keep their
+    /// coordinates; only the origin and their transforms move.
+    pub fn shift_down(&mut self, distance: f32) {
+        self.offset.y -= distance;
+        let cells: Vec<(HexCoord, Entity)> = self.grid.iter().map(|(&c, &e)| (c, e)).collect();
+        for (coord, entity) in cells {
+            self.sync(coord, entity);
+        }
+    }
+
+    /// Change the hex size, fitting columns to it. Bubbles keep their cells
+    /// and scale with them, and the ceiling stays put, so the board shrinks
+    /// up toward it.
+    pub fn resize(&mut self, hex_size: f32) {
+        let ratio = hex_size / self.grid.hex_size;
+        self.offset.y = GRID_ORIGIN_Y - (GRID_ORIGIN_Y - self.offset.y) * ratio;
+        self.grid.hex_size = hex_size;
+        self.grid.bounds = self.grid.bounds.fit_columns(hex_size);
+        let cells: Vec<(HexCoord, Entity)> = self.grid.iter().map(|(&c, &e)| (c, e)).collect();
+        for (coord, entity) in cells {
+            self.sync(coord, entity);
+            if let Ok((_, mut transform)) = self.bubbles.get_mut(entity) {
+                transform.scale *= ratio;
+            }
+        }
+    }
+
+    /// Make a bubble's component and transform agree with its grid key.
+    fn sync(&mut self, coord: HexCoord, entity: Entity) {
+        let position = self.position(coord);
+        // Bubbles spawned this frame already start in the right place
+        if let Ok((mut bubble, mut transform)) = self.bubbles.get_mut(entity) {
+            bubble.coord = coord;
+            transform.translation.x = position.x;
+            transform.translation.y = position.y;
+        }
+    }
+}
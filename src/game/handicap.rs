@@ -0,0 +1,208 @@
+//! Starting handicaps for experienced players.
+//!
+//! A [`Handicap`] picked on the mode select screen starts scored runs further
+//! along: at level 5, 10, or 15, with the descent ramp that far along and an
+//! extra starting row for every five levels skipped. Each power-up milestone
+//! skipped is offered before the first shot instead, and points are
+//! multiplied to make up for the harder start.
+//!
+//! Campaign levels and drills have their own starts, zen doesn't score, the
+//! demo always starts fresh, and weekly challenge runs are all played from
+//! the same start, so none of them take a handicap. A resumed
+//! autosave keeps the one it was played with, without offering its picks
+//! again.
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    autosave::PendingResume,
+    campaign::ActiveLevel,
+    drills::ActiveDrill,
+    mode::GameMode,
+    powerups::{PowerUpCatalog, PowerUpChoices, UnlockedPowerUps},
+    rng::GameRng,
+    weekly::{ActiveChallenge, start_challenge},
+};
+use crate::{
+    PausableSystems,
+    menus::Menu,
+    screens::{InGame, RunPhase, Screen},
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<Handicap>();
+    app.init_resource::<RunHandicap>();
+    app.register_type::<Handicap>();
+
+    app.add_systems(OnEnter(InGame), apply_handicap.after(start_challenge));
+    app.add_systems(
+        Update,
+        offer_handicap_pick
+            .in_set(PausableSystems)
+            .run_if(in_state(RunPhase::Playing).and(handicap_picks_left)),
+    );
+}
+
+/// Levels between power-up milestones, and between handicaps.
+const LEVELS_PER_MILESTONE: u32 = 5;
+
+/// Where scored runs start, picked on the mode select screen.
+#[derive(Resource, Debug, Clone, Copy, Default, PartialEq, Eq, Reflect, Serialize, Deserialize)]
+#[reflect(Resource)]
+pub enum Handicap {
+    #[default]
+    Off,
+    Level5,
+    Level10,
+    Level15,
+}
+
+impl Handicap {
+    /// The level runs start at.
+    pub fn start_level(self) -> u32 {
+        match self {
+            Handicap::Off => 1,
+            Handicap::Level5 => 5,
+            Handicap::Level10 => 10,
+            Handicap::Level15 => 15,
+        }
+    }
+
+    /// Power-up milestones skipped, each offered before the first shot.
+    pub fn picks(self) -> u32 {
+        self.start_level() / LEVELS_PER_MILESTONE
+    }
+
+    /// Rows added to the usual starting board.
+    pub fn extra_rows(self) -> i32 {
+        self.picks() as i32
+    }
+
+    /// Points scored, in percent of the usual.
+    pub fn score_percent(self) -> u32 {
+        match self {
+            Handicap::Off => 100,
+            Handicap::Level5 => 125,
+            Handicap::Level10 => 150,
+            Handicap::Level15 => 200,
+        }
+    }
+
+    /// Points added on top of `earned`.
+    pub fn bonus_points(self, earned: u32) -> u32 {
+        earned * (self.score_percent() - 100) / 100
+    }
+
+    pub fn label(self) -> String {
+        match self {
+            Handicap::Off => "Level 1".to_string(),
+            _ => format!(
+                "Level {} (x{})",
+                self.start_level(),
+                self.score_percent() as f32 / 100.0
+            ),
+        }
+    }
+
+    /// The next option, wrapping around (for a cycling menu button).
+    pub fn next(self) -> Self {
+        match self {
+            Handicap::Off => Handicap::Level5,
+            Handicap::Level5 => Handicap::Level10,
+            Handicap::Level10 => Handicap::Level15,
+            Handicap::Level15 => Handicap::Off,
+        }
+    }
+}
+
+/// The handicap the run in play started with.
+#[derive(Resource, Debug, Default)]
+pub struct RunHandicap {
+    pub handicap: Handicap,
+    /// Skipped milestones still to be offered.
+    picks_left: u32,
+}
+
+/// Settle this run's handicap before the level and board are set up.
+pub(super) fn apply_handicap(
+    chosen: Res<Handicap>,
+    mode: Res<GameMode>,
+    active_level: Res<ActiveLevel>,
+    active_drill: Res<ActiveDrill>,
+    challenge: Res<ActiveChallenge>,
+    pending: Res<PendingResume>,
+    screen: Res<State<Screen>>,
+    mut run: ResMut<RunHandicap>,
+) {
+    *run = if let Some(saved) = &pending.0 {
+        RunHandicap {
+            handicap: saved.handicap,
+            picks_left: 0,
+        }
+    } else if mode.has_pressure()
+        && active_level.0.is_none()
+        && active_drill.0.is_none()
+        && challenge.0.is_none()
+        && *screen.get() != Screen::Demo
+    {
+        RunHandicap {
+            handicap: *chosen,
+            picks_left: chosen.picks(),
+        }
+    } else {
+        RunHandicap::default()
+    };
+    if run.handicap != Handicap::Off {
+        info!("Starting with handicap: {}", run.handicap.label());
+    }
+}
+
+impl RunHandicap {
+    /// Forget the picks still to be offered, for a run picked up partway
+    /// through.
+    pub fn skip_picks(&mut self) {
+        self.picks_left = 0;
+    }
+}
+
+fn handicap_picks_left(run: Res<RunHandicap>) -> bool {
+    run.picks_left > 0
+}
+
+/// Offer the next skipped milestone's power-ups.
+fn offer_handicap_pick(
+    catalog: Res<PowerUpCatalog>,
+    unlocked: Res<UnlockedPowerUps>,
+    mut run: ResMut<RunHandicap>,
+    mut rng: ResMut<GameRng>,
+    mut choices: ResMut<PowerUpChoices>,
+    mut next_menu: ResMut<NextState<Menu>>,
+    mut next_phase: ResMut<NextState<RunPhase>>,
+) {
+    let level = (run.handicap.picks() - run.picks_left + 1) * LEVELS_PER_MILESTONE;
+    run.picks_left -= 1;
+    let offered = catalog.random_choices(level, &unlocked.powers, &mut rng.gameplay);
+    if offered.is_empty() {
+        return;
+    }
+    info!("Handicap power-up selection for level {}", level);
+    choices.choices = offered;
+    choices.level = level;
+    next_phase.set(RunPhase::PowerUpChoice);
+    next_menu.set(Menu::PowerUpSelect);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn harder_starts_score_more() {
+        assert_eq!(Handicap::Off.bonus_points(100), 0);
+        assert_eq!(Handicap::Level10.bonus_points(100), 50);
+        assert_eq!(Handicap::Level15.picks(), 3);
+        assert_eq!(Handicap::Level15.label(), "Level 15 (x2)");
+        assert_eq!(Handicap::Level15.next(), Handicap::Off);
+    }
+}
@@ -6,21 +6,28 @@
 //! We use "pointy-top" orientation with "odd-r" offset coordinates.
 //! This creates a rectangular grid where odd rows are shifted right by half a hex.
 //! This is the classic bubble shooter layout.
+//!
+//! Cluster matching and anchoring don't depend on that: they ask a
+//! [`BoardLayout`] which cells touch and where cells sit, so a mode can
+//! arrange its cells differently (flat-top hexes, a square grid) without
+//! them changing. [`OddRPointy`] is the only layout so far, and what
+//! [`HexCoord`]'s own methods use.
 
 use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
 
-use crate::screens::Screen;
+use crate::screens::InGame;
 
 pub(super) fn plugin(app: &mut App) {
     app.register_type::<HexCoord>();
     app.register_type::<GridOffset>();
     app.init_resource::<GridOffset>();
-    app.add_systems(OnEnter(Screen::Gameplay), reset_grid_offset);
+    app.add_systems(OnEnter(InGame), reset_grid_offset);
 }
 
 /// Resource tracking the grid's Y origin (decreases on descent).
-#[derive(Resource, Debug, Clone, Reflect)]
-#[reflect(Resource)]
+#[derive(Resource, Debug, Clone, PartialEq, Reflect, Serialize, Deserialize)]
+#[reflect(Resource, Serialize, Deserialize)]
 pub struct GridOffset {
     pub y: f32,
 }
@@ -31,6 +38,14 @@ impl Default for GridOffset {
     }
 }
 
+impl GridOffset {
+    /// The row along the ceiling, for hexes of `hex_size`: row 0 at the
+    /// start, and one row up with every descent.
+    pub fn ceiling_row(&self, hex_size: f32) -> i32 {
+        -((GRID_ORIGIN_Y - self.y) / (hex_size * 1.5)).round() as i32
+    }
+}
+
 /// Reset grid offset when starting a new game.
 fn reset_grid_offset(mut grid_offset: ResMut<GridOffset>) {
     grid_offset.y = GRID_ORIGIN_Y;
@@ -48,6 +63,38 @@ pub const HEX_SIZE: f32 = 20.0;
 /// Row 0 will be at this Y position.
 pub const GRID_ORIGIN_Y: f32 = 250.0;
 
+/// How a board's cells are arranged: which cells touch, and where each one
+/// sits in the world.
+pub trait BoardLayout {
+    /// Cells touching `coord`.
+    fn neighbors(&self, coord: HexCoord) -> impl Iterator<Item = HexCoord>;
+
+    /// World position of `coord`'s center, for cells `size` from center to
+    /// corner, with row 0 at `origin_y`.
+    fn to_pixel(&self, coord: HexCoord, size: f32, origin_y: f32) -> Vec2;
+
+    /// The cell whose center is nearest `pos`.
+    fn cell_at(&self, pos: Vec2, size: f32, origin_y: f32) -> HexCoord;
+}
+
+/// Pointy-top hexes, with odd rows shifted right by half a hex.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Reflect)]
+pub struct OddRPointy;
+
+impl BoardLayout for OddRPointy {
+    fn neighbors(&self, coord: HexCoord) -> impl Iterator<Item = HexCoord> {
+        coord.neighbors().into_iter()
+    }
+
+    fn to_pixel(&self, coord: HexCoord, size: f32, origin_y: f32) -> Vec2 {
+        coord.to_pixel_with_offset(size, origin_y)
+    }
+
+    fn cell_at(&self, pos: Vec2, size: f32, origin_y: f32) -> HexCoord {
+        HexCoord::from_pixel_with_offset(pos, size, origin_y)
+    }
+}
+
 /// Offset hex coordinate (odd-r system).
 ///
 /// In offset coordinates:
@@ -56,8 +103,8 @@ pub const GRID_ORIGIN_Y: f32 = 250.0;
 /// - Odd rows are shifted right by half a hex width
 ///
 /// This creates a rectangular grid appearance, perfect for bubble shooters.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Component, Reflect)]
-#[reflect(Component)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Component, Reflect, Serialize, Deserialize)]
+#[reflect(Component, Serialize, Deserialize)]
 pub struct HexCoord {
     /// Column (x-axis)
     pub q: i32,
@@ -114,6 +161,27 @@ impl HexCoord {
         }
     }
 
+    /// Mirror across the vertical line through the grid center (x = 0).
+    ///
+    /// Odd rows are shifted right by half a hex, so their mirror image lands
+    /// one column further left than on even rows.
+    pub fn mirrored(self) -> Self {
+        Self::new(-self.q - self.r.rem_euclid(2), self.r)
+    }
+
+    /// Rotate 180° so row `top` swaps with row `bottom`.
+    ///
+    /// When `top + bottom` is odd every row changes parity, which moves it
+    /// half a hex sideways. The center of rotation then sits a quarter hex
+    /// left of x = 0 so that every cell still lands on a cell.
+    pub fn rotated_180(self, top: i32, bottom: i32) -> Self {
+        let span = top + bottom;
+        let r = span - self.r;
+        // Work in half-hex columns, where x = 2q + parity
+        let x = -(2 * self.q + self.r.rem_euclid(2)) - span.rem_euclid(2);
+        Self::new((x - r.rem_euclid(2)) / 2, r)
+    }
+
     /// Calculate the hex distance between two coordinates.
     ///
     /// In cube coordinates, this is: max(|dq|, |dr|, |ds|)
@@ -229,6 +297,55 @@ mod tests {
         assert_eq!(hex.neighbors().len(), 6);
     }
 
+    /// What any layout has to get right for clusters and anchoring to work.
+    fn check_layout(layout: &impl BoardLayout) {
+        for q in -3..=3 {
+            for r in -3..=3 {
+                let coord = HexCoord::new(q, r);
+                let pixel = layout.to_pixel(coord, HEX_SIZE, GRID_ORIGIN_Y);
+                assert_eq!(layout.cell_at(pixel, HEX_SIZE, GRID_ORIGIN_Y), coord);
+                for neighbor in layout.neighbors(coord) {
+                    assert_ne!(neighbor, coord);
+                    assert!(
+                        layout.neighbors(neighbor).any(|back| back == coord),
+                        "{neighbor} doesn't touch {coord} back"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_odd_r_pointy_layout() {
+        check_layout(&OddRPointy);
+    }
+
+    #[test]
+    fn test_neighbors_one_hex_apart_above_row_zero() {
+        // Descents add rows at negative r, with the origin moving down
+        for descents in 0..=40 {
+            let offset = GridOffset {
+                y: GRID_ORIGIN_Y - descents as f32 * HEX_SIZE * 1.5,
+            };
+            assert_eq!(offset.ceiling_row(HEX_SIZE), -descents);
+            let ceiling = HexCoord::new(0, -descents).to_pixel_with_offset(HEX_SIZE, offset.y);
+            assert_eq!(ceiling.y, GRID_ORIGIN_Y);
+
+            for r in -descents..=-descents + 2 {
+                let coord = HexCoord::new(1, r);
+                let pixel = coord.to_pixel_with_offset(HEX_SIZE, offset.y);
+                for neighbor in coord.neighbors() {
+                    let distance =
+                        pixel.distance(neighbor.to_pixel_with_offset(HEX_SIZE, offset.y));
+                    assert!(
+                        (distance - HEX_SIZE * SQRT_3).abs() < 0.01,
+                        "{coord} and {neighbor} are {distance} apart"
+                    );
+                }
+            }
+        }
+    }
+
     #[test]
     fn test_pixel_roundtrip_even_row() {
         let original = HexCoord::new(5, 2);
@@ -244,4 +361,59 @@ mod tests {
         let back = HexCoord::from_pixel(pixel, HEX_SIZE);
         assert_eq!(original, back);
     }
+
+    #[test]
+    fn test_mirror_flips_pixel_x() {
+        for coord in [
+            HexCoord::new(4, 2),
+            HexCoord::new(4, 3),
+            HexCoord::new(-2, -1),
+        ] {
+            let pixel = coord.to_pixel(HEX_SIZE);
+            let mirrored = coord.mirrored().to_pixel(HEX_SIZE);
+            assert!(
+                (mirrored.x + pixel.x).abs() < 0.01,
+                "{coord} mirrored off-axis"
+            );
+            assert_eq!(mirrored.y, pixel.y);
+            assert_eq!(coord.mirrored().mirrored(), coord);
+        }
+    }
+
+    #[test]
+    fn test_mirror_keeps_odd_row_neighbors() {
+        let coord = HexCoord::new(2, 3);
+        for neighbor in coord.neighbors() {
+            assert!(coord.mirrored().neighbors().contains(&neighbor.mirrored()));
+        }
+    }
+
+    #[test]
+    fn test_rotate_180_same_parity_span() {
+        // Rows 0..=4 keep their parity, so the rotation is about x = 0
+        assert_eq!(HexCoord::new(3, 0).rotated_180(0, 4), HexCoord::new(-3, 4));
+        assert_eq!(HexCoord::new(3, 1).rotated_180(0, 4), HexCoord::new(-4, 3));
+        assert_eq!(HexCoord::new(0, 2).rotated_180(0, 4), HexCoord::new(0, 2));
+    }
+
+    #[test]
+    fn test_rotate_180_flipped_parity_span() {
+        // Rows 0..=3 swap parity, so every cell shifts by half a hex
+        assert_eq!(HexCoord::new(3, 0).rotated_180(0, 3), HexCoord::new(-4, 3));
+        assert_eq!(HexCoord::new(3, 1).rotated_180(0, 3), HexCoord::new(-4, 2));
+        for q in -3..=3 {
+            for r in 0..=3 {
+                let coord = HexCoord::new(q, r);
+                assert_eq!(coord.rotated_180(0, 3).rotated_180(0, 3), coord);
+                for neighbor in coord.neighbors() {
+                    assert!(
+                        coord
+                            .rotated_180(0, 3)
+                            .neighbors()
+                            .contains(&neighbor.rotated_180(0, 3))
+                    );
+                }
+            }
+        }
+    }
 }
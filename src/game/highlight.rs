@@ -0,0 +1,294 @@
+//! Cell highlights - a soft glow ring around grid cells.
+//!
+//! Systems ask for highlights through the [`Highlights`] resource, each on its
+//! own [`HighlightLayer`] so they don't clear each other's cells. Every
+//! highlighted cell is a quad in one shared mesh, drawn with [`GlowMaterial`]
+//! (`assets/shaders/glow.wgsl`), so highlighting fifty bubbles is still one
+//! draw call. Color and intensity ride along as vertex colors.
+//!
+//! Used by:
+//! - The aimed match: bubbles the current aim would pop with
+//! - The debug grid's hovered cell
+//!
+//! The descent preview draws its ghost board with the same mesh and material.
+
+use std::collections::BTreeMap;
+
+use bevy::{
+    asset::RenderAssetUsages,
+    mesh::{Indices, PrimitiveTopology},
+    prelude::*,
+    render::render_resource::{AsBindGroup, ShaderType},
+    shader::ShaderRef,
+    sprite_render::{AlphaMode2d, Material2d, Material2dPlugin},
+    window::PrimaryWindow,
+};
+
+use super::{
+    cluster::MIN_CLUSTER_SIZE,
+    debug::DebugGridVisible,
+    grid::HexGrid,
+    hex::{GridOffset, HexCoord},
+    mood::AimedCluster,
+};
+use crate::screens::InGame;
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_plugins(Material2dPlugin::<GlowMaterial>::default());
+    app.init_resource::<Highlights>();
+
+    app.add_systems(OnEnter(InGame), clear_highlights);
+    app.add_systems(
+        Update,
+        (
+            (
+                highlight_aimed_match.run_if(resource_changed::<AimedCluster>),
+                highlight_hovered_cell,
+            ),
+            rebuild_glow_mesh
+                .run_if(resource_changed::<Highlights>.or(resource_changed::<GridOffset>)),
+        )
+            .chain()
+            .run_if(in_state(InGame)),
+    );
+}
+
+const SHADER_PATH: &str = "shaders/glow.wgsl";
+
+/// Half the width of a highlight quad, in hex sizes. Leaves room outside the
+/// cell for the glow to fade.
+const GLOW_EXTENT: f32 = 1.5;
+
+/// Glow around bubbles the aim would pop with.
+const MATCH_GLOW: Color = Color::srgb(1.0, 0.95, 0.6);
+
+/// Glow around the cell under the cursor in the debug grid.
+const INSPECTOR_GLOW: Color = Color::srgb(0.3, 0.9, 1.0);
+
+/// Who asked for a highlight. Each layer is set and cleared on its own.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum HighlightLayer {
+    AimedMatch,
+    Inspector,
+}
+
+/// A glowing cell.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Highlight {
+    pub coord: HexCoord,
+    pub color: Color,
+    /// How bright the glow is, from 0 (off) to 1.
+    pub intensity: f32,
+}
+
+/// The highlighted cells, by layer.
+#[derive(Resource, Debug, Default)]
+pub struct Highlights {
+    layers: BTreeMap<HighlightLayer, Vec<Highlight>>,
+}
+
+impl Highlights {
+    /// Replace a layer's highlights.
+    pub fn set(&mut self, layer: HighlightLayer, highlights: Vec<Highlight>) {
+        if highlights.is_empty() {
+            self.layers.remove(&layer);
+        } else {
+            self.layers.insert(layer, highlights);
+        }
+    }
+
+    pub fn get(&self, layer: HighlightLayer) -> &[Highlight] {
+        self.layers.get(&layer).map_or(&[], Vec::as_slice)
+    }
+
+    /// Every highlight on every layer, in layer order.
+    pub fn iter(&self) -> impl Iterator<Item = &Highlight> {
+        self.layers.values().flatten()
+    }
+}
+
+/// Glow ring shading for the highlight mesh.
+#[derive(Asset, TypePath, AsBindGroup, Debug, Clone)]
+pub struct GlowMaterial {
+    #[uniform(0)]
+    pub params: GlowParams,
+}
+
+/// The material's uniform, mirrored by `GlowParams` in the shader.
+#[derive(ShaderType, Debug, Clone, Copy)]
+pub struct GlowParams {
+    /// Where the ring is brightest, from the quad's center (0) to its edge (1).
+    pub ring: f32,
+    /// How far the ring spreads to each side, in the same units.
+    pub width: f32,
+    /// Breathing speed, in radians per second.
+    pub pulse: f32,
+    /// How much the glow dims at the low point of each breath.
+    pub pulse_depth: f32,
+}
+
+impl Default for GlowMaterial {
+    fn default() -> Self {
+        Self {
+            params: GlowParams {
+                // The cell's corners sit at one hex size from its center
+                ring: 1.0 / GLOW_EXTENT,
+                width: 0.2,
+                pulse: 4.0,
+                pulse_depth: 0.3,
+            },
+        }
+    }
+}
+
+impl Material2d for GlowMaterial {
+    fn fragment_shader() -> ShaderRef {
+        SHADER_PATH.into()
+    }
+
+    fn alpha_mode(&self) -> AlphaMode2d {
+        AlphaMode2d::Blend
+    }
+}
+
+/// The entity that draws every highlight.
+#[derive(Component, Debug)]
+struct GlowLayer;
+
+fn clear_highlights(mut highlights: ResMut<Highlights>) {
+    *highlights = Highlights::default();
+}
+
+/// Put every highlight into the one glow mesh, spawning it the first time
+/// there's something to show.
+fn rebuild_glow_mesh(
+    mut commands: Commands,
+    highlights: Res<Highlights>,
+    grid: Res<HexGrid>,
+    grid_offset: Res<GridOffset>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<GlowMaterial>>,
+    mut layer: Query<(&Mesh2d, &mut Visibility), With<GlowLayer>>,
+) {
+    let highlights: Vec<Highlight> = highlights.iter().copied().collect();
+    let Ok((mesh, mut visibility)) = layer.single_mut() else {
+        if !highlights.is_empty() {
+            commands.spawn((
+                Name::new("Highlight Glow"),
+                GlowLayer,
+                Mesh2d(meshes.add(glow_mesh(&highlights, grid.hex_size, grid_offset.y))),
+                MeshMaterial2d(materials.add(GlowMaterial::default())),
+                // Over the bubbles, under the projectile
+                Transform::from_xyz(0.0, 0.0, 1.0),
+                DespawnOnExit(InGame),
+            ));
+        }
+        return;
+    };
+
+    // An empty mesh has nothing to draw, so keep the old one and hide it
+    if highlights.is_empty() {
+        *visibility = Visibility::Hidden;
+        return;
+    }
+    *visibility = Visibility::Inherited;
+    if let Some(mesh) = meshes.get_mut(&mesh.0) {
+        *mesh = glow_mesh(&highlights, grid.hex_size, grid_offset.y);
+    }
+}
+
+/// A quad per highlight, centered on its cell and tinted with its color.
+pub(super) fn glow_mesh(highlights: &[Highlight], hex_size: f32, grid_origin_y: f32) -> Mesh {
+    let half = hex_size * GLOW_EXTENT;
+    let mut positions = Vec::with_capacity(highlights.len() * 4);
+    let mut uvs = Vec::with_capacity(highlights.len() * 4);
+    let mut colors = Vec::with_capacity(highlights.len() * 4);
+    let mut indices = Vec::with_capacity(highlights.len() * 6);
+
+    for highlight in highlights {
+        let center = highlight
+            .coord
+            .to_pixel_with_offset(hex_size, grid_origin_y);
+        let color =
+            LinearRgba::from(highlight.color).with_alpha(highlight.intensity.clamp(0.0, 1.0));
+        let first = positions.len() as u32;
+        for (corner, uv) in [
+            (Vec2::new(-1.0, -1.0), [0.0, 1.0]),
+            (Vec2::new(1.0, -1.0), [1.0, 1.0]),
+            (Vec2::new(1.0, 1.0), [1.0, 0.0]),
+            (Vec2::new(-1.0, 1.0), [0.0, 0.0]),
+        ] {
+            positions.push((center + corner * half).extend(0.0).to_array());
+            uvs.push(uv);
+            colors.push(color.to_f32_array());
+        }
+        indices.extend([first, first + 1, first + 2, first, first + 2, first + 3]);
+    }
+
+    Mesh::new(
+        PrimitiveTopology::TriangleList,
+        RenderAssetUsages::default(),
+    )
+    .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, positions)
+    .with_inserted_attribute(Mesh::ATTRIBUTE_UV_0, uvs)
+    .with_inserted_attribute(Mesh::ATTRIBUTE_COLOR, colors)
+    .with_inserted_indices(Indices::U32(indices))
+}
+
+/// Glow around the bubbles the current aim would pop with.
+fn highlight_aimed_match(aimed: Res<AimedCluster>, mut highlights: ResMut<Highlights>) {
+    let matches = if aimed.cluster.len() >= MIN_CLUSTER_SIZE {
+        aimed
+            .cluster
+            .iter()
+            // The landing cell is still empty
+            .filter(|&&coord| Some(coord) != aimed.landing)
+            .map(|&coord| Highlight {
+                coord,
+                color: MATCH_GLOW,
+                intensity: 0.6,
+            })
+            .collect()
+    } else {
+        Vec::new()
+    };
+    highlights.set(HighlightLayer::AimedMatch, matches);
+}
+
+/// In the debug grid, glow around the cell under the cursor.
+fn highlight_hovered_cell(
+    debug: Res<DebugGridVisible>,
+    grid: Res<HexGrid>,
+    grid_offset: Res<GridOffset>,
+    window: Query<&Window, With<PrimaryWindow>>,
+    camera: Query<(&Camera, &GlobalTransform)>,
+    mut highlights: ResMut<Highlights>,
+) {
+    let hovered = debug
+        .0
+        .then(|| {
+            let cursor = window.single().ok()?.cursor_position()?;
+            let (camera, camera_transform) = camera.single().ok()?;
+            let position = camera.viewport_to_world_2d(camera_transform, cursor).ok()?;
+            let coord = HexCoord::from_pixel_with_offset(position, grid.hex_size, grid_offset.y);
+            grid.bounds.contains(coord).then_some(coord)
+        })
+        .flatten();
+
+    let current = highlights
+        .get(HighlightLayer::Inspector)
+        .first()
+        .map(|h| h.coord);
+    if current == hovered {
+        return;
+    }
+    let cells = hovered
+        .map(|coord| Highlight {
+            coord,
+            color: INSPECTOR_GLOW,
+            intensity: 1.0,
+        })
+        .into_iter()
+        .collect();
+    highlights.set(HighlightLayer::Inspector, cells);
+}
@@ -1,14 +1,23 @@
 //! High score persistence with Top 10 leaderboard.
 //!
 //! Scores are saved to a local JSON file in the user's data directory.
+//! Abandoned runs are kept off the leaderboard unless the player opts in.
+//!
+//! Each entry is signed with an HMAC keyed per build, covering every field and
+//! its place in the list, so editing, copying, or reordering entries by hand
+//! shows up: entries whose signature doesn't match (including ones saved
+//! before signing existed) are listed as unverified. The key ships inside the
+//! binary, so this stops casual edits, not a determined cheater.
 
 use bevy::prelude::*;
 use serde::{Deserialize, Serialize};
-use std::fs;
-use std::path::PathBuf;
+
+use crate::save::{self, SaveFile};
 
 pub(super) fn plugin(app: &mut App) {
     app.init_resource::<HighScores>();
+    app.init_resource::<LeaderboardSettings>();
+    app.register_type::<LeaderboardSettings>();
 
     // Load high scores on startup
     app.add_systems(Startup, load_high_scores);
@@ -22,6 +31,12 @@ const MAX_HIGH_SCORES: usize = 10;
 pub struct ScoreEntry {
     pub score: u32,
     pub bubbles_popped: u32,
+    /// The player ended the run early from the pause menu.
+    #[serde(default)]
+    pub abandoned: bool,
+    /// Hex-encoded HMAC-SHA256 of the fields above and the entry's rank.
+    #[serde(default)]
+    pub signature: String,
 }
 
 impl ScoreEntry {
@@ -29,8 +44,37 @@ impl ScoreEntry {
         Self {
             score,
             bubbles_popped,
+            abandoned: false,
+            signature: String::new(),
         }
     }
+
+    /// The signature this entry should have at `index` in the list.
+    fn expected_signature(&self, index: usize) -> String {
+        let unsigned = Self {
+            signature: String::new(),
+            ..self.clone()
+        };
+        let fields = serde_json::to_string(&unsigned).unwrap_or_default();
+        save::sign(&format!("{index}:{fields}"))
+    }
+
+    fn sign(&mut self, index: usize) {
+        self.signature = self.expected_signature(index);
+    }
+
+    /// Whether the entry is unchanged since this game saved it at `index`.
+    pub fn is_verified(&self, index: usize) -> bool {
+        self.signature == self.expected_signature(index)
+    }
+}
+
+/// Settings for which runs can make the leaderboard.
+#[derive(Resource, Debug, Clone, Default, Reflect)]
+#[reflect(Resource)]
+pub struct LeaderboardSettings {
+    /// Let abandoned runs into the top 10.
+    pub include_abandoned: bool,
 }
 
 /// Resource holding the top 10 high scores.
@@ -55,8 +99,11 @@ impl HighScores {
             .unwrap_or(true)
     }
 
-    /// Add a new score to the leaderboard (if it qualifies).
+    /// Sign and add a new score to the leaderboard (if it qualifies).
     /// Returns true if the score was added.
+    ///
+    /// Entries below it move down a rank and are signed again, unless they
+    /// were already unverified.
     pub fn add_score(&mut self, entry: ScoreEntry) -> bool {
         if entry.score == 0 {
             return false;
@@ -73,7 +120,19 @@ impl HighScores {
             return false;
         }
 
+        let verified: Vec<bool> = self
+            .entries
+            .iter()
+            .enumerate()
+            .map(|(index, entry)| entry.is_verified(index))
+            .collect();
         self.entries.insert(pos, entry);
+        self.entries[pos].sign(pos);
+        for index in pos + 1..self.entries.len() {
+            if verified[index - 1] {
+                self.entries[index].sign(index);
+            }
+        }
 
         // Trim to max size
         if self.entries.len() > MAX_HIGH_SCORES {
@@ -82,73 +141,67 @@ impl HighScores {
 
         true
     }
+}
 
-    /// Get the file path for storing high scores.
-    /// Returns None on WASM targets where filesystem access is not available.
-    fn file_path() -> Option<PathBuf> {
-        #[cfg(target_arch = "wasm32")]
-        return None;
+impl SaveFile for HighScores {
+    const FILE_NAME: &'static str = "highscores.json";
+    const DESCRIPTION: &'static str = "high scores";
+}
 
-        #[cfg(not(target_arch = "wasm32"))]
-        dirs::data_local_dir().map(|dir| dir.join("snord").join("highscores.json"))
+/// Load high scores on startup.
+fn load_high_scores(mut high_scores: ResMut<HighScores>) {
+    *high_scores = HighScores::load();
+    let unverified = high_scores
+        .entries
+        .iter()
+        .enumerate()
+        .filter(|(index, entry)| !entry.is_verified(*index))
+        .count();
+    if unverified > 0 {
+        warn!("{} high score(s) failed signature checks", unverified);
     }
+}
 
-    /// Load high scores from disk.
-    pub fn load() -> Self {
-        let Some(path) = Self::file_path() else {
-            warn!("Could not determine data directory for high scores");
-            return Self::default();
-        };
-
-        if !path.exists() {
-            info!("No high scores file found at {:?}, starting fresh", path);
-            return Self::default();
-        }
-
-        match fs::read_to_string(&path) {
-            Ok(contents) => match serde_json::from_str(&contents) {
-                Ok(scores) => {
-                    info!("Loaded high scores from {:?}", path);
-                    scores
-                }
-                Err(e) => {
-                    warn!("Failed to parse high scores: {}", e);
-                    Self::default()
-                }
-            },
-            Err(e) => {
-                warn!("Failed to read high scores file: {}", e);
-                Self::default()
-            }
-        }
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn edited_entries_fail_verification() {
+        let mut scores = HighScores::default();
+        assert!(scores.add_score(ScoreEntry::new(1200, 40)));
+        assert!(scores.entries[0].is_verified(0));
+
+        let mut edited = scores.entries[0].clone();
+        edited.score = 999_999;
+        assert!(!edited.is_verified(0));
+        edited = scores.entries[0].clone();
+        edited.abandoned = true;
+        assert!(!edited.is_verified(0));
+
+        // Saved before signing existed
+        let legacy: ScoreEntry =
+            serde_json::from_str(r#"{ "score": 500, "bubbles_popped": 12 }"#).unwrap();
+        assert!(!legacy.is_verified(0));
     }
 
-    /// Save high scores to disk.
-    pub fn save(&self) {
-        let Some(path) = Self::file_path() else {
-            warn!("Could not determine data directory for saving high scores");
-            return;
-        };
-
-        // Create parent directory if needed
-        if let Some(parent) = path.parent()
-            && let Err(e) = fs::create_dir_all(parent)
-        {
-            warn!("Failed to create high scores directory: {}", e);
-            return;
-        }
-
-        match serde_json::to_string_pretty(self) {
-            Ok(json) => match fs::write(&path, json) {
-                Ok(()) => info!("Saved high scores to {:?}", path),
-                Err(e) => warn!("Failed to write high scores: {}", e),
-            },
-            Err(e) => warn!("Failed to serialize high scores: {}", e),
-        }
+    #[test]
+    fn entries_are_signed_for_their_rank() {
+        let mut scores = HighScores::default();
+        assert!(scores.add_score(ScoreEntry::new(500, 10)));
+        assert!(scores.add_score(ScoreEntry::new(300, 8)));
+        // Moves both entries down a rank
+        assert!(scores.add_score(ScoreEntry::new(900, 20)));
+        assert!(
+            scores
+                .entries
+                .iter()
+                .enumerate()
+                .all(|(index, entry)| entry.is_verified(index))
+        );
+
+        // Copied or moved to another rank by hand
+        assert!(!scores.entries[2].is_verified(1));
+        assert!(!scores.entries[0].is_verified(1));
     }
 }
-
-/// Load high scores on startup.
-fn load_high_scores(mut high_scores: ResMut<HighScores>) {
-    *high_scores = HighScores::load();
-}
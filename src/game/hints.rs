@@ -0,0 +1,510 @@
+//! Hint nudges for players who are stuck.
+//!
+//! [`MissStreak`] counts landings in a row that didn't pop anything. After
+//! [`MISSES_BEFORE_HINT`] of them a small "Need a hint?" button appears in
+//! the corner. It goes away on its own, or with the next shot; accepting it
+//! has the demo bot's greedy policy pick a target in the background, and the
+//! path of a shot that lands there (bounces and all, traced like the aim
+//! guide) is drawn until the next shot is fired.
+//!
+//! How often the button may come back, or whether it appears at all, is set
+//! in the settings menu through [`HintSettings`].
+
+use bevy::prelude::*;
+use rand::{Rng, SeedableRng, rngs::StdRng};
+
+use super::{
+    bubble::{BubbleColor, GameAssets},
+    bumpers::Bumpers,
+    cluster::{ClusterPopped, ClusterSystems},
+    demo::sim_board,
+    gameplay_entities::GameplayEntity,
+    grid::HexGrid,
+    hex::{GridOffset, HexCoord},
+    messages::AddGameMessage,
+    mode::GameMode,
+    portals::Portals,
+    powerups::UnlockedPowerUps,
+    projectile::{
+        BubbleLanded, FireProjectile, MAX_PREDICTION_DISTANCE, Walls, collision_distance,
+        predict_landing, trace_shot,
+    },
+    rng::GameRng,
+    shooter::{LoadedBubble, MAX_AIM_ANGLE, Shooter},
+    sticky_walls::StickyWalls,
+};
+use crate::{
+    PausableSystems,
+    compute::{AddComputeMessage, SpawnCompute},
+    screens::{InGame, Screen},
+    sim::{
+        board::Board,
+        policy::{GreedyPolicy, ShotPolicy},
+    },
+    theme::{
+        GameFont,
+        interaction::InteractionPalette,
+        palette::{
+            BUTTON_BACKGROUND, BUTTON_HOVERED_BACKGROUND, BUTTON_PRESSED_BACKGROUND, BUTTON_TEXT,
+        },
+    },
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<HintSettings>();
+    app.init_resource::<MissStreak>();
+    app.register_type::<HintSettings>();
+    app.add_game_message::<HintRequested>("The player accepted a hint");
+    app.add_compute_message::<HintShotTraced>();
+
+    app.add_systems(OnEnter(InGame), reset_miss_streak);
+    app.add_systems(
+        Update,
+        (
+            track_misses.after(ClusterSystems),
+            offer_hint,
+            expire_hint_prompt,
+            request_hint_target,
+            draw_hint_line,
+            clear_hint_line,
+        )
+            .chain()
+            .in_set(PausableSystems)
+            .run_if(in_state(Screen::Gameplay)),
+    );
+}
+
+/// Missed shots in a row before a hint is offered.
+pub const MISSES_BEFORE_HINT: u32 = 5;
+
+/// Seconds the hint button stays up if it's ignored.
+const PROMPT_SECS: f32 = 8.0;
+
+/// Color of the hint line.
+const HINT_LINE_COLOR: Color = Color::srgba(1.0, 0.85, 0.2, 0.9);
+
+/// How often hints are offered.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Reflect)]
+pub enum HintFrequency {
+    Off,
+    /// At most once every [`HintFrequency::cooldown_shots`] shots.
+    #[default]
+    Sometimes,
+    /// Every time the miss streak gets long enough.
+    Often,
+}
+
+impl HintFrequency {
+    pub fn label(self) -> &'static str {
+        match self {
+            HintFrequency::Off => "Off",
+            HintFrequency::Sometimes => "Sometimes",
+            HintFrequency::Often => "Often",
+        }
+    }
+
+    /// The next setting, for the settings menu button.
+    pub fn next(self) -> Self {
+        match self {
+            HintFrequency::Off => HintFrequency::Sometimes,
+            HintFrequency::Sometimes => HintFrequency::Often,
+            HintFrequency::Often => HintFrequency::Off,
+        }
+    }
+
+    /// Shots that have to land after one offer before the next.
+    fn cooldown_shots(self) -> u32 {
+        match self {
+            HintFrequency::Off | HintFrequency::Often => 0,
+            HintFrequency::Sometimes => 20,
+        }
+    }
+}
+
+/// Hint settings, changed in the settings menu.
+#[derive(Resource, Debug, Clone, Default, Reflect)]
+#[reflect(Resource)]
+pub struct HintSettings {
+    pub frequency: HintFrequency,
+}
+
+/// Missed shots this run.
+#[derive(Resource, Debug, Default)]
+pub struct MissStreak {
+    /// Landings in a row that didn't pop a cluster.
+    pub misses: u32,
+    /// Landings since the last hint was offered, or `None` if one hasn't been.
+    pub since_offer: Option<u32>,
+}
+
+impl MissStreak {
+    /// Whether a hint should be offered now.
+    fn wants_hint(&self, frequency: HintFrequency) -> bool {
+        frequency != HintFrequency::Off
+            && self.misses >= MISSES_BEFORE_HINT
+            && self
+                .since_offer
+                .is_none_or(|shots| shots >= frequency.cooldown_shots())
+    }
+}
+
+/// Message sent when the player accepts a hint.
+#[derive(Message, Debug, Clone)]
+pub struct HintRequested;
+
+/// The hinted shot's path was traced in the background, or there was
+/// nothing worth hinting.
+#[derive(Message, Debug, Clone)]
+struct HintShotTraced(Option<Vec<(Vec2, Vec2)>>);
+
+/// The "Need a hint?" button, with how long it's been up.
+#[derive(Component, Debug, Default)]
+pub struct HintPrompt {
+    age: f32,
+}
+
+/// A stretch of the hinted shot's path, from the shooter to the hinted cell.
+#[derive(Component, Debug)]
+pub struct HintLine;
+
+fn reset_miss_streak(mut streak: ResMut<MissStreak>) {
+    *streak = MissStreak::default();
+}
+
+/// Count landings that didn't pop anything, and reset on one that did.
+fn track_misses(
+    mut streak: ResMut<MissStreak>,
+    mut landed_events: MessageReader<BubbleLanded>,
+    mut cluster_events: MessageReader<ClusterPopped>,
+) {
+    let popped: Vec<HexCoord> = cluster_events
+        .read()
+        .flat_map(|event| event.coords.iter().copied())
+        .collect();
+    for event in landed_events.read() {
+        if let Some(shots) = &mut streak.since_offer {
+            *shots += 1;
+        }
+        if popped.contains(&event.coord) {
+            streak.misses = 0;
+        } else {
+            streak.misses += 1;
+        }
+    }
+}
+
+fn offer_hint(
+    mut commands: Commands,
+    mut streak: ResMut<MissStreak>,
+    settings: Res<HintSettings>,
+    game_font: Res<GameFont>,
+    prompts: Query<(), With<HintPrompt>>,
+) {
+    if !prompts.is_empty() || !streak.wants_hint(settings.frequency) {
+        return;
+    }
+    streak.misses = 0;
+    streak.since_offer = Some(0);
+
+    commands
+        .spawn((
+            Name::new("Hint Prompt"),
+            HintPrompt::default(),
+            Button,
+            BackgroundColor(BUTTON_BACKGROUND),
+            InteractionPalette {
+                none: BUTTON_BACKGROUND,
+                hovered: BUTTON_HOVERED_BACKGROUND,
+                pressed: BUTTON_PRESSED_BACKGROUND,
+            },
+            Node {
+                position_type: PositionType::Absolute,
+                left: Val::Px(16.0),
+                bottom: Val::Px(16.0),
+                padding: UiRect::axes(Val::Px(12.0), Val::Px(6.0)),
+                ..default()
+            },
+            BorderRadius::all(Val::Px(8.0)),
+            GameplayEntity,
+            children![(
+                Name::new("Hint Prompt Text"),
+                Text::new("Need a hint?"),
+                TextFont {
+                    font: game_font.0.clone(),
+                    font_size: 18.0,
+                    ..default()
+                },
+                TextColor(BUTTON_TEXT),
+                Pickable::IGNORE,
+            )],
+        ))
+        .observe(accept_hint);
+}
+
+fn accept_hint(
+    click: On<Pointer<Click>>,
+    mut commands: Commands,
+    mut requests: MessageWriter<HintRequested>,
+) {
+    commands.entity(click.entity).despawn();
+    requests.write(HintRequested);
+}
+
+/// Take the button down once it's been ignored for a while, or a shot is
+/// fired without it.
+fn expire_hint_prompt(
+    mut commands: Commands,
+    time: Res<Time<Real>>,
+    mut fire_events: MessageReader<FireProjectile>,
+    mut prompts: Query<(Entity, &mut HintPrompt)>,
+) {
+    let fired = fire_events.read().count() > 0;
+    for (entity, mut prompt) in &mut prompts {
+        prompt.age += time.delta_secs();
+        if fired || prompt.age >= PROMPT_SECS {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+/// Aim directions tried, across the shooter's whole range, when looking for
+/// the shot that lands on the hinted cell.
+const HINT_AIM_SAMPLES: usize = 180;
+
+/// Everything a hint needs, copied so it can be worked out off the main
+/// thread.
+struct HintInput {
+    board: Board,
+    color: BubbleColor,
+    origin_y: f32,
+    walls: Walls,
+    portals: Portals,
+    sticky: StickyWalls,
+    bumpers: Bumpers,
+    start: Vec2,
+    collision_distance: f32,
+    rng: StdRng,
+}
+
+impl HintInput {
+    /// Have the greedy policy pick a cell, then find the aim that lands there
+    /// (or the nearest it can get) and trace that shot. None if there's
+    /// nothing worth hinting.
+    fn resolve(mut self) -> Option<Vec<(Vec2, Vec2)>> {
+        let target = GreedyPolicy.choose_target(&self.board, self.color, &mut self.rng)?;
+        let grid = &self.board.grid;
+        let goal = target.to_pixel_with_offset(grid.hex_size, self.origin_y);
+        let offset = goal - self.start;
+        let direct = offset.x.atan2(offset.y);
+
+        // Closest to the straight line first, so a direct shot beats a bank
+        // shot that lands in the same cell
+        let mut angles: Vec<f32> = (0..=HINT_AIM_SAMPLES)
+            .map(|i| -MAX_AIM_ANGLE + 2.0 * MAX_AIM_ANGLE * i as f32 / HINT_AIM_SAMPLES as f32)
+            .collect();
+        angles.sort_by(|a, b| (a - direct).abs().total_cmp(&(b - direct).abs()));
+
+        let direction = angles
+            .into_iter()
+            .map(|angle| Vec2::new(angle.sin(), angle.cos()))
+            .filter_map(|direction| {
+                let landing = predict_landing(
+                    grid,
+                    self.origin_y,
+                    self.walls,
+                    &self.portals,
+                    &self.sticky,
+                    &self.bumpers,
+                    self.start,
+                    direction,
+                    self.collision_distance,
+                )?;
+                let miss = landing
+                    .coord
+                    .to_pixel_with_offset(grid.hex_size, self.origin_y)
+                    .distance(goal);
+                (!landing.in_danger).then_some((direction, miss))
+            })
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))?
+            .0;
+
+        let path = trace_shot(
+            grid,
+            self.origin_y,
+            self.walls,
+            &self.portals,
+            &self.sticky,
+            &self.bumpers,
+            self.start,
+            direction,
+            self.collision_distance,
+            MAX_PREDICTION_DISTANCE,
+        );
+        Some(path.segments)
+    }
+}
+
+/// Have the greedy policy pick a cell for the loaded bubble, and trace the
+/// shot there.
+fn request_hint_target(
+    mut commands: Commands,
+    mut requests: MessageReader<HintRequested>,
+    grid: Res<HexGrid>,
+    grid_offset: Res<GridOffset>,
+    colors: Query<&BubbleColor>,
+    shooter: Single<(&Transform, &LoadedBubble), With<Shooter>>,
+    (walls, portals, sticky, bumpers): (Res<Walls>, Res<Portals>, Res<StickyWalls>, Res<Bumpers>),
+    powerups: Res<UnlockedPowerUps>,
+    mode: Res<GameMode>,
+    mut game_rng: ResMut<GameRng>,
+) {
+    if requests.read().count() == 0 {
+        return;
+    }
+    let (transform, loaded) = *shooter;
+    let input = HintInput {
+        board: sim_board(&grid, &grid_offset, &colors),
+        color: loaded.0,
+        origin_y: grid_offset.y,
+        walls: *walls,
+        portals: portals.clone(),
+        sticky: sticky.clone(),
+        bumpers: bumpers.clone(),
+        start: transform.translation.truncate(),
+        collision_distance: collision_distance(&grid, &powerups, *mode),
+        // The hint only shows a shot, so its tie-breaks come from the effects
+        // stream
+        rng: StdRng::seed_from_u64(game_rng.effects.random()),
+    };
+    commands.spawn_compute(InGame, move || Some(HintShotTraced(input.resolve())));
+}
+
+/// Draw the hinted shot's path, bounces and all.
+fn draw_hint_line(
+    mut commands: Commands,
+    mut traced: MessageReader<HintShotTraced>,
+    game_assets: Res<GameAssets>,
+    lines: Query<Entity, With<HintLine>>,
+) {
+    let Some(HintShotTraced(Some(segments))) = traced.read().last() else {
+        return;
+    };
+    for entity in &lines {
+        commands.entity(entity).despawn();
+    }
+
+    // The guide line image is 300px wide, anchored at its left end
+    const GUIDE_LINE_WIDTH: f32 = 300.0;
+    for &(start, end) in segments {
+        let offset = end - start;
+        if offset.length() <= 0.0 {
+            continue;
+        }
+        commands.spawn((
+            Name::new("Hint Line"),
+            HintLine,
+            Sprite {
+                image: game_assets.guide_line_image.clone(),
+                color: HINT_LINE_COLOR,
+                ..default()
+            },
+            bevy::sprite::Anchor::CENTER_LEFT,
+            Transform {
+                translation: start.extend(1.6),
+                rotation: Quat::from_rotation_z(offset.y.atan2(offset.x)),
+                scale: Vec3::new(offset.length() / GUIDE_LINE_WIDTH, 0.6, 1.0),
+            },
+            GameplayEntity,
+        ));
+    }
+}
+
+/// The hint is good for one shot.
+fn clear_hint_line(
+    mut commands: Commands,
+    mut fire_events: MessageReader<FireProjectile>,
+    lines: Query<Entity, With<HintLine>>,
+) {
+    if fire_events.read().count() == 0 {
+        return;
+    }
+    for entity in &lines {
+        commands.entity(entity).despawn();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::{
+        hex::{BoardLayout, GRID_ORIGIN_Y},
+        shooter::SHOOTER_Y,
+    };
+
+    #[test]
+    fn hints_wait_out_the_cooldown() {
+        let mut streak = MissStreak {
+            misses: MISSES_BEFORE_HINT,
+            since_offer: None,
+        };
+        assert!(streak.wants_hint(HintFrequency::Sometimes));
+        assert!(!streak.wants_hint(HintFrequency::Off));
+
+        streak.since_offer = Some(5);
+        assert!(!streak.wants_hint(HintFrequency::Sometimes));
+        assert!(streak.wants_hint(HintFrequency::Often));
+
+        streak.misses = MISSES_BEFORE_HINT - 1;
+        assert!(!streak.wants_hint(HintFrequency::Often));
+    }
+
+    #[test]
+    fn hints_aim_past_stones_within_the_mode_bounds() {
+        let mode = GameMode::Kids;
+        let mut board = Board::default();
+        board.grid.bounds = mode.grid_bounds();
+        board.grid.hex_size = mode.hex_size();
+        // A shelf of stones, with a blue pair hanging off the middle
+        for q in -4..=4 {
+            board.insert(HexCoord::new(q, 0), None);
+        }
+        board.insert(HexCoord::new(0, 1), Some(BubbleColor::Blue));
+        board.insert(HexCoord::new(1, 1), Some(BubbleColor::Blue));
+
+        let cells = board.reachable_cells();
+        assert!(!cells.is_empty());
+        assert!(
+            cells
+                .iter()
+                .all(|&c| (-4..=4).contains(&c.q) && !board.grid.is_occupied(c))
+        );
+
+        let grid = board.grid.clone();
+        let start = Vec2::new(0.0, SHOOTER_Y);
+        let input = HintInput {
+            board,
+            color: BubbleColor::Blue,
+            origin_y: GRID_ORIGIN_Y,
+            walls: Walls::default(),
+            portals: Portals::default(),
+            sticky: StickyWalls::default(),
+            bumpers: Bumpers::default(),
+            start,
+            collision_distance: grid.hex_size * mode.collision_reach(),
+            rng: StdRng::seed_from_u64(1),
+        };
+        let segments = input.resolve().expect("a pop should be hinted");
+        assert_eq!(segments[0].0, start);
+
+        // The hinted shot lands beside the pair, so it pops
+        let (_, end) = *segments.last().unwrap();
+        let landing = grid
+            .closest_empty_cell(end, GRID_ORIGIN_Y)
+            .expect("the shot should land on the grid");
+        assert!(
+            grid.layout
+                .neighbors(landing)
+                .any(|n| n == HexCoord::new(0, 1) || n == HexCoord::new(1, 1))
+        );
+    }
+}
@@ -0,0 +1,119 @@
+//! Run seeds and the recent games history.
+//!
+//! Every run that starts from a random board gets a seed, and the starting
+//! rows are generated from it. A seed set in [`RetrySeed`] (by `--seed` or a
+//! "Retry seed" button in the history) is used for the next run instead of a
+//! fresh one, so the same starting board comes back. Later rows and the
+//! shooter queue are drawn from it too (see `GameRng`), so the same shots
+//! get the same bubbles.
+//!
+//! When a run leaves gameplay after ending (won, lost, or abandoned), it's
+//! added to the [`Profile`]'s recent runs.
+
+use bevy::prelude::*;
+use rand::Rng;
+
+use super::{
+    bubble::StartingBoard,
+    clock::GameClock,
+    drills::drill_active,
+    mode::GameMode,
+    state::{ContinueRun, GameEnded, GameLevel, GameScore},
+    telemetry::RunOutcome,
+};
+use crate::{
+    profile::{Profile, RecentRun},
+    save::SaveFile,
+    screens::{InGame, Screen},
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<RunSeed>();
+    app.init_resource::<RetrySeed>();
+    app.init_resource::<RunInProgress>();
+
+    app.add_systems(OnEnter(InGame), pick_run_seed);
+    app.add_systems(OnEnter(Screen::Gameplay), start_run);
+    app.add_systems(Update, note_run_end.run_if(in_state(Screen::Gameplay)));
+    app.add_systems(
+        OnExit(Screen::Gameplay),
+        record_recent_run.run_if(not(drill_active)),
+    );
+}
+
+/// Seed the current run's starting board was generated from, or `None` if
+/// it started from a fixed layout (drills, the demo).
+#[derive(Resource, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RunSeed(pub Option<u64>);
+
+/// Seed to use for the next run instead of a random one.
+#[derive(Resource, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RetrySeed(pub Option<u64>);
+
+/// When the current run started on the game clock, and how it ended.
+#[derive(Resource, Debug, Default)]
+struct RunInProgress {
+    started_at: f32,
+    outcome: Option<RunOutcome>,
+}
+
+/// Pick this run's seed before the starting board is spawned.
+pub(super) fn pick_run_seed(
+    starting_board: Res<StartingBoard>,
+    mut retry: ResMut<RetrySeed>,
+    mut seed: ResMut<RunSeed>,
+) {
+    if starting_board.0.is_some() {
+        seed.0 = None;
+        return;
+    }
+    let picked = retry.0.take().unwrap_or_else(|| rand::rng().random());
+    info!("Run seed: {}", picked);
+    seed.0 = Some(picked);
+}
+
+fn start_run(time: Res<Time<GameClock>>, mut run: ResMut<RunInProgress>) {
+    *run = RunInProgress {
+        started_at: time.elapsed_secs(),
+        outcome: None,
+    };
+}
+
+fn note_run_end(
+    mut ended_events: MessageReader<GameEnded>,
+    mut continue_events: MessageReader<ContinueRun>,
+    mut run: ResMut<RunInProgress>,
+) {
+    if let Some(event) = ended_events.read().last() {
+        run.outcome = Some(event.outcome);
+    }
+    // A continued run isn't over yet
+    if continue_events.read().count() > 0 {
+        run.outcome = None;
+    }
+}
+
+/// Add the run to the history if it ended, rather than being quit midway.
+fn record_recent_run(
+    time: Res<Time<GameClock>>,
+    mut run: ResMut<RunInProgress>,
+    mode: Res<GameMode>,
+    score: Res<GameScore>,
+    level: Res<GameLevel>,
+    seed: Res<RunSeed>,
+    mut profile: ResMut<Profile>,
+) {
+    let Some(outcome) = run.outcome.take() else {
+        return;
+    };
+    profile.push_run(RecentRun {
+        mode: *mode,
+        score: score.score,
+        level: level.level,
+        duration_secs: time.elapsed_secs() - run.started_at,
+        outcome,
+        seed: seed.0,
+    });
+    profile.save();
+    info!("Recorded {:?} run in the history", outcome);
+}
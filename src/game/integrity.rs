@@ -0,0 +1,223 @@
+//! Grid integrity checks.
+//!
+//! [`HexGrid`] and the [`Bubble`] components are updated by several systems,
+//! and nothing notices if they drift apart. Every few seconds this checks that:
+//! - Every grid entity still exists as a bubble
+//! - Every `Bubble.coord` matches its grid key
+//! - No entity is stored under two coordinates
+//! - No bubble sits outside the bounds without a path to the top row
+//!
+//! Violations are logged, and repaired unless repair is turned off. The grid
+//! is treated as the source of truth. Checks run in debug builds by default,
+//! and in release builds with `--validate-grid`.
+
+use std::{
+    collections::{HashMap, HashSet},
+    time::Duration,
+};
+
+use bevy::prelude::*;
+
+use super::{
+    bubble::Bubble,
+    clock::on_game_timer,
+    grid::HexGrid,
+    hex::{GridOffset, HexCoord},
+};
+use crate::{PausableSystems, launch::LaunchOptions, screens::InGame};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<GridValidation>();
+
+    app.add_systems(Startup, enable_from_launch_options);
+    app.add_systems(
+        Update,
+        validate_grid
+            .run_if(
+                in_state(InGame)
+                    .and(validation_enabled)
+                    .and(on_game_timer(Duration::from_secs_f32(CHECK_INTERVAL_SECS))),
+            )
+            .in_set(PausableSystems),
+    );
+}
+
+/// Seconds between checks.
+const CHECK_INTERVAL_SECS: f32 = 3.0;
+
+/// Whether grid checks run, and whether they fix what they find.
+#[derive(Resource, Debug, Clone)]
+pub struct GridValidation {
+    pub enabled: bool,
+    pub repair: bool,
+}
+
+impl Default for GridValidation {
+    fn default() -> Self {
+        Self {
+            enabled: cfg!(debug_assertions),
+            repair: true,
+        }
+    }
+}
+
+fn validation_enabled(validation: Res<GridValidation>) -> bool {
+    validation.enabled
+}
+
+fn enable_from_launch_options(launch: Res<LaunchOptions>, mut validation: ResMut<GridValidation>) {
+    if launch.validate_grid {
+        validation.enabled = true;
+    }
+}
+
+/// A way the grid and the bubble components disagree.
+#[derive(Debug, Clone, PartialEq)]
+pub enum GridViolation {
+    /// The grid points at an entity that isn't a bubble anymore.
+    MissingEntity { key: HexCoord, entity: Entity },
+    /// The bubble thinks it's somewhere other than its grid key.
+    CoordMismatch {
+        key: HexCoord,
+        entity: Entity,
+        coord: HexCoord,
+    },
+    /// The same entity is stored under several coordinates.
+    SharedEntity { entity: Entity, keys: Vec<HexCoord> },
+    /// Outside the bounds and not connected to the top row.
+    Unanchored { key: HexCoord, entity: Entity },
+}
+
+impl std::fmt::Display for GridViolation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GridViolation::MissingEntity { key, entity } => {
+                write!(f, "{key} points at missing bubble {entity}")
+            }
+            GridViolation::CoordMismatch { key, entity, coord } => {
+                write!(f, "bubble {entity} at {key} thinks it's at {coord}")
+            }
+            GridViolation::SharedEntity { entity, keys } => {
+                write!(f, "bubble {entity} is stored at {keys:?}")
+            }
+            GridViolation::Unanchored { key, entity } => {
+                write!(
+                    f,
+                    "bubble {entity} at {key} is out of bounds and unanchored"
+                )
+            }
+        }
+    }
+}
+
+/// Compare the grid against the bubble components.
+pub fn find_violations(grid: &HexGrid, bubbles: &Query<&Bubble>) -> Vec<GridViolation> {
+    let mut violations = Vec::new();
+
+    let mut keys_by_entity: HashMap<Entity, Vec<HexCoord>> = HashMap::new();
+    for (&key, &entity) in grid.iter() {
+        keys_by_entity.entry(entity).or_default().push(key);
+    }
+
+    for (entity, mut keys) in keys_by_entity {
+        keys.sort_by_key(|key| (key.r, key.q));
+        let Ok(bubble) = bubbles.get(entity) else {
+            violations.extend(
+                keys.into_iter()
+                    .map(|key| GridViolation::MissingEntity { key, entity }),
+            );
+            continue;
+        };
+        if keys.len() > 1 {
+            violations.push(GridViolation::SharedEntity { entity, keys });
+        } else if bubble.coord != keys[0] {
+            violations.push(GridViolation::CoordMismatch {
+                key: keys[0],
+                entity,
+                coord: bubble.coord,
+            });
+        }
+    }
+
+    let anchored = anchored_coords(grid);
+    for (&key, &entity) in grid.iter() {
+        if !grid.bounds.contains(key) && !anchored.contains(&key) {
+            violations.push(GridViolation::Unanchored { key, entity });
+        }
+    }
+
+    violations
+}
+
+/// Coordinates connected to the top occupied row through other bubbles.
+fn anchored_coords(grid: &HexGrid) -> HashSet<HexCoord> {
+    let Some(top) = grid.coords().map(|coord| coord.r).min() else {
+        return HashSet::new();
+    };
+    let mut anchored: HashSet<HexCoord> = grid.coords().filter(|coord| coord.r == top).collect();
+    let mut frontier: Vec<HexCoord> = anchored.iter().copied().collect();
+    while let Some(coord) = frontier.pop() {
+        for neighbor in coord.neighbors() {
+            if grid.is_occupied(neighbor) && anchored.insert(neighbor) {
+                frontier.push(neighbor);
+            }
+        }
+    }
+    anchored
+}
+
+fn validate_grid(
+    mut commands: Commands,
+    validation: Res<GridValidation>,
+    mut grid: ResMut<HexGrid>,
+    grid_offset: Res<GridOffset>,
+    mut bubbles: ParamSet<(Query<&Bubble>, Query<(&mut Bubble, &mut Transform)>)>,
+) {
+    let violations = find_violations(&grid, &bubbles.p0());
+    if violations.is_empty() {
+        return;
+    }
+
+    for violation in &violations {
+        warn!("Grid integrity: {}", violation);
+    }
+    if !validation.repair {
+        return;
+    }
+
+    for violation in violations {
+        match violation {
+            GridViolation::MissingEntity { key, .. } => {
+                grid.remove(key);
+            }
+            GridViolation::CoordMismatch { key, entity, .. } => {
+                if let Ok((mut bubble, mut transform)) = bubbles.p1().get_mut(entity) {
+                    bubble.coord = key;
+                    let position = key.to_pixel_with_offset(grid.hex_size, grid_offset.y);
+                    transform.translation.x = position.x;
+                    transform.translation.y = position.y;
+                }
+            }
+            GridViolation::SharedEntity { entity, keys } => {
+                // Keep the key the bubble agrees with, or else the first one
+                let coord = bubbles.p0().get(entity).map(|bubble| bubble.coord).ok();
+                let keep = coord
+                    .filter(|coord| keys.contains(coord))
+                    .unwrap_or(keys[0]);
+                for key in keys.into_iter().filter(|&key| key != keep) {
+                    grid.remove(key);
+                }
+                if coord != Some(keep)
+                    && let Ok((mut bubble, _)) = bubbles.p1().get_mut(entity)
+                {
+                    bubble.coord = keep;
+                }
+            }
+            GridViolation::Unanchored { key, entity } => {
+                grid.remove(key);
+                commands.entity(entity).despawn();
+            }
+        }
+    }
+    info!("Grid integrity: repaired");
+}
@@ -0,0 +1,129 @@
+//! Kids mode celebrations.
+//!
+//! Every cluster pop gets a cheer and a burst of confetti, not just the big
+//! combos. The bigger bubbles, smaller palette, slower shots, and wider
+//! collisions come from [`GameMode`] itself.
+
+use bevy::prelude::*;
+use rand::Rng;
+
+use super::{
+    bubble::BubbleColor,
+    clock::GameClock,
+    cluster::ClusterPopped,
+    gameplay_entities::GameplayEntity,
+    grid::HexGrid,
+    hex::GridOffset,
+    mode::GameMode,
+    polish::{ComboText, combo_text},
+    rng::GameRng,
+};
+use crate::{PausableSystems, display::GraphicsQuality, screens::InGame, theme::GameFont};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(
+        Update,
+        (celebrate_pops.run_if(kids_mode), animate_confetti)
+            .in_set(PausableSystems)
+            .run_if(in_state(InGame)),
+    );
+}
+
+/// Cheers picked at random for each pop.
+const CHEERS: [&str; 5] = ["Yay!", "Wow!", "Great!", "Super!", "Hooray!"];
+
+/// Confetti pieces per popped bubble.
+const CONFETTI_PER_BUBBLE: usize = 4;
+
+/// Downward acceleration on confetti, in pixels per second squared.
+const CONFETTI_GRAVITY: f32 = 420.0;
+
+fn kids_mode(mode: Res<GameMode>) -> bool {
+    *mode == GameMode::Kids
+}
+
+/// A piece of confetti that flies out, falls, and fades.
+#[derive(Component, Debug)]
+pub(super) struct Confetti {
+    velocity: Vec2,
+    spin: f32,
+    timer: f32,
+    duration: f32,
+}
+
+/// Cheer and throw confetti whenever a cluster pops.
+fn celebrate_pops(
+    mut commands: Commands,
+    mut cluster_events: MessageReader<ClusterPopped>,
+    grid: Res<HexGrid>,
+    grid_offset: Res<GridOffset>,
+    game_font: Res<GameFont>,
+    quality: Res<GraphicsQuality>,
+    mut game_rng: ResMut<GameRng>,
+) {
+    let rng = &mut game_rng.effects;
+    let confetti = (CONFETTI_PER_BUBBLE as f32 * quality.0.particles()).round() as usize;
+    for event in cluster_events.read() {
+        if event.coords.is_empty() {
+            continue;
+        }
+        let positions: Vec<Vec2> = event
+            .coords
+            .iter()
+            .map(|coord| coord.to_pixel_with_offset(grid.hex_size, grid_offset.y))
+            .collect();
+        let center = positions.iter().sum::<Vec2>() / positions.len() as f32;
+
+        let cheer = CHEERS[rng.random_range(0..CHEERS.len())];
+        let color = Color::srgb(1.0, 0.45, 0.75);
+        commands.spawn(combo_text(
+            "Kids Cheer",
+            cheer,
+            ComboText::new(center + Vec2::Y * grid.hex_size, 1.0, 60.0, color),
+            game_font.0.clone(),
+            36.0,
+        ));
+
+        for position in positions {
+            for _ in 0..confetti {
+                let angle = rng.random_range(0.2..std::f32::consts::PI - 0.2);
+                let speed = rng.random_range(120.0..280.0);
+                let tint = BubbleColor::ALL[rng.random_range(0..BubbleColor::ALL.len())];
+                commands.spawn((
+                    Name::new("Confetti"),
+                    Confetti {
+                        velocity: Vec2::from_angle(angle) * speed,
+                        spin: rng.random_range(-10.0..10.0),
+                        timer: 0.0,
+                        duration: rng.random_range(0.8..1.3),
+                    },
+                    Sprite::from_color(tint.to_color(), Vec2::new(6.0, 10.0)),
+                    Transform::from_translation(position.extend(9.0)),
+                    GameplayEntity,
+                ));
+            }
+        }
+    }
+}
+
+/// Move, spin, and fade confetti, despawning it when done.
+fn animate_confetti(
+    mut commands: Commands,
+    time: Res<Time<GameClock>>,
+    mut query: Query<(Entity, &mut Confetti, &mut Transform, &mut Sprite)>,
+) {
+    let dt = time.delta_secs();
+    for (entity, mut confetti, mut transform, mut sprite) in &mut query {
+        confetti.timer += dt;
+        let progress = (confetti.timer / confetti.duration).min(1.0);
+
+        confetti.velocity.y -= CONFETTI_GRAVITY * dt;
+        transform.translation += confetti.velocity.extend(0.0) * dt;
+        transform.rotate_z(confetti.spin * dt);
+        sprite.color = sprite.color.with_alpha(1.0 - progress);
+
+        if progress >= 1.0 {
+            commands.entity(entity).despawn();
+        }
+    }
+}
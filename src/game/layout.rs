@@ -0,0 +1,144 @@
+//! Board layout transforms for level variety.
+//!
+//! Authored layouts can be mirrored or rotated so one definition plays as
+//! several boards. The transforms are pure functions over a list of cells;
+//! the odd-r parity handling lives in [`HexCoord::mirrored`] and
+//! [`HexCoord::rotated_180`].
+
+use std::collections::HashSet;
+
+use rand::Rng;
+
+use super::{grid::GridBounds, hex::HexCoord, snapshot::GridCell};
+
+/// A way to rearrange a layout without changing its shape.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LayoutTransform {
+    /// Flip left to right.
+    Mirror,
+    /// Turn upside down, keeping the same rows.
+    Rotate180,
+}
+
+impl LayoutTransform {
+    pub const ALL: [LayoutTransform; 2] = [LayoutTransform::Mirror, LayoutTransform::Rotate180];
+
+    /// Apply to a layout.
+    ///
+    /// Returns None if a cell would leave the grid, or if any bubble would be
+    /// left hanging without a path to the top row.
+    pub fn apply(self, cells: &[GridCell], bounds: GridBounds) -> Option<Vec<GridCell>> {
+        let top = cells.iter().map(|cell| cell.coord.r).min()?;
+        let bottom = cells.iter().map(|cell| cell.coord.r).max()?;
+        let transformed: Vec<GridCell> = cells
+            .iter()
+            .map(|cell| GridCell {
+                coord: match self {
+                    LayoutTransform::Mirror => cell.coord.mirrored(),
+                    LayoutTransform::Rotate180 => cell.coord.rotated_180(top, bottom),
+                },
+                color: cell.color,
+            })
+            .collect();
+
+        (transformed.iter().all(|cell| bounds.contains(cell.coord))
+            && is_anchored(&transformed, bounds.min_r))
+        .then_some(transformed)
+    }
+}
+
+/// Whether every cell connects to the top row through other cells.
+pub(super) fn is_anchored(cells: &[GridCell], top_row: i32) -> bool {
+    let occupied: HashSet<HexCoord> = cells.iter().map(|cell| cell.coord).collect();
+    let mut reached: HashSet<HexCoord> = occupied
+        .iter()
+        .copied()
+        .filter(|coord| coord.r == top_row)
+        .collect();
+    let mut frontier: Vec<HexCoord> = reached.iter().copied().collect();
+    while let Some(coord) = frontier.pop() {
+        for neighbor in coord.neighbors() {
+            if occupied.contains(&neighbor) && reached.insert(neighbor) {
+                frontier.push(neighbor);
+            }
+        }
+    }
+    reached.len() == occupied.len()
+}
+
+/// Pick a random variant of a layout: as-is, mirrored, rotated, or both.
+/// Transforms that don't fit the grid are skipped.
+pub fn random_variant(cells: &[GridCell], bounds: GridBounds, rng: &mut impl Rng) -> Vec<GridCell> {
+    let mut variant = cells.to_vec();
+    for transform in LayoutTransform::ALL {
+        if rng.random_bool(0.5)
+            && let Some(transformed) = transform.apply(&variant, bounds)
+        {
+            variant = transformed;
+        }
+    }
+    variant
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::bubble::BubbleColor;
+
+    fn row(r: i32, qs: std::ops::RangeInclusive<i32>) -> Vec<GridCell> {
+        qs.map(|q| GridCell {
+            coord: HexCoord::new(q, r),
+            color: BubbleColor::Red,
+        })
+        .collect()
+    }
+
+    #[test]
+    fn mirror_rejects_odd_row_past_left_wall() {
+        // The rightmost odd-row cell mirrors to one column past the left edge
+        let cells = [row(0, -6..=6), row(1, 5..=6)].concat();
+        assert!(
+            LayoutTransform::Mirror
+                .apply(&cells, GridBounds::default())
+                .is_none()
+        );
+
+        let cells = [row(0, -6..=6), row(1, -6..=5)].concat();
+        let mirrored = LayoutTransform::Mirror
+            .apply(&cells, GridBounds::default())
+            .expect("layout should fit when mirrored");
+        assert!(
+            mirrored
+                .iter()
+                .any(|cell| cell.coord == HexCoord::new(-6, 1))
+        );
+        assert!(
+            !mirrored
+                .iter()
+                .any(|cell| cell.coord == HexCoord::new(6, 1))
+        );
+    }
+
+    #[test]
+    fn rotate_rejects_hanging_bubbles() {
+        // Two pillars of different lengths: the short one ends up hanging
+        let cells = [row(0, -5..=-4), row(0, 4..=5), row(1, -5..=-4)].concat();
+        assert!(
+            LayoutTransform::Rotate180
+                .apply(&cells, GridBounds::default())
+                .is_none()
+        );
+
+        // A full block stays anchored, with its parity shifted
+        let cells = [row(0, -5..=5), row(1, -5..=4)].concat();
+        let rotated = LayoutTransform::Rotate180
+            .apply(&cells, GridBounds::default())
+            .expect("full block should rotate");
+        assert_eq!(rotated.len(), cells.len());
+        assert!(
+            rotated
+                .iter()
+                .any(|cell| cell.coord == HexCoord::new(-6, 1))
+        );
+    }
+}
@@ -0,0 +1,252 @@
+//! Registry of gameplay messages, for debugging the event-driven systems.
+//!
+//! Gameplay messages are registered with [`AddGameMessage::add_game_message`]
+//! instead of `add_message`, which also records a description, a running
+//! count, and the most recent messages. Logging can be toggled per message
+//! type from a debug panel (toggle with the 'M' key during gameplay).
+
+use std::{collections::VecDeque, fmt::Debug};
+
+use bevy::{input::common_conditions::input_just_pressed, prelude::*};
+
+use crate::{controls::MESSAGE_PANEL_KEY, screens::Screen, theme::GameFont};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<MessageRegistry>();
+    app.init_resource::<MessagePanelVisible>();
+
+    app.add_systems(
+        Update,
+        (
+            toggle_message_panel
+                .run_if(in_state(Screen::Gameplay).and(input_just_pressed(MESSAGE_PANEL_KEY))),
+            update_message_panel
+                .run_if(in_state(Screen::Gameplay).and(resource_changed::<MessageRegistry>)),
+        )
+            .chain(),
+    );
+}
+
+/// Maximum number of recent messages kept for the debug panel.
+const MAX_RECENT_MESSAGES: usize = 12;
+
+/// Maximum length of a recent message line before it's cut off.
+const MAX_RECENT_LINE: usize = 48;
+
+pub trait AddGameMessage {
+    /// Register a gameplay message like `add_message`, and also list it in the
+    /// [`MessageRegistry`] with a short description of what it's for.
+    fn add_game_message<M: Message + Debug>(&mut self, description: &'static str) -> &mut Self;
+}
+
+impl AddGameMessage for App {
+    fn add_game_message<M: Message + Debug>(&mut self, description: &'static str) -> &mut Self {
+        self.add_message::<M>();
+        self.init_resource::<MessageRegistry>();
+
+        let mut registry = self.world_mut().resource_mut::<MessageRegistry>();
+        let index = registry.entries.len();
+        registry.entries.push(MessageEntry {
+            name: short_type_name::<M>(),
+            description,
+            log: false,
+            count: 0,
+        });
+
+        // PostUpdate sees messages written anywhere in Update
+        self.add_systems(
+            PostUpdate,
+            move |mut messages: MessageReader<M>, mut registry: ResMut<MessageRegistry>| {
+                if messages.is_empty() {
+                    return;
+                }
+                for message in messages.read() {
+                    registry.record(index, message);
+                }
+            },
+        );
+        self
+    }
+}
+
+/// A registered gameplay message type.
+#[derive(Debug)]
+pub struct MessageEntry {
+    pub name: &'static str,
+    pub description: &'static str,
+    /// Whether each message of this type is logged.
+    pub log: bool,
+    /// Messages seen since startup.
+    pub count: u64,
+}
+
+/// All registered gameplay messages and the most recent ones seen.
+#[derive(Resource, Debug, Default)]
+pub struct MessageRegistry {
+    pub entries: Vec<MessageEntry>,
+    pub recent: VecDeque<String>,
+}
+
+impl MessageRegistry {
+    fn record(&mut self, index: usize, message: &impl Debug) {
+        let entry = &mut self.entries[index];
+        entry.count += 1;
+
+        let mut line = format!("{message:?}");
+        if entry.log {
+            info!("[{}] {}", entry.description, line);
+        }
+        if line.len() > MAX_RECENT_LINE {
+            let cut = (0..=MAX_RECENT_LINE)
+                .rev()
+                .find(|&i| line.is_char_boundary(i))
+                .unwrap_or(0);
+            line.truncate(cut);
+            line.push_str("...");
+        }
+
+        if self.recent.len() >= MAX_RECENT_MESSAGES {
+            self.recent.pop_front();
+        }
+        self.recent.push_back(line);
+    }
+}
+
+/// `game::cluster::ClusterPopped` -> `ClusterPopped`
+fn short_type_name<M>() -> &'static str {
+    let name = std::any::type_name::<M>();
+    name.rsplit("::").next().unwrap_or(name)
+}
+
+/// Resource to track if the message panel is visible.
+#[derive(Resource, Default)]
+pub struct MessagePanelVisible(pub bool);
+
+/// Marker for the message panel root.
+#[derive(Component)]
+struct MessagePanel;
+
+/// Button toggling logging for the registry entry at this index.
+#[derive(Component)]
+struct MessageLogToggle(usize);
+
+/// Text showing the registry entry at this index.
+#[derive(Component)]
+struct MessageEntryText(usize);
+
+/// Text listing the most recent messages.
+#[derive(Component)]
+struct RecentMessagesText;
+
+/// Text color for the panel (light, for the dark background).
+const PANEL_TEXT_COLOR: Color = Color::srgb(0.9, 0.9, 0.9);
+
+fn toggle_message_panel(
+    mut commands: Commands,
+    mut visible: ResMut<MessagePanelVisible>,
+    mut registry: ResMut<MessageRegistry>,
+    panel_query: Query<Entity, With<MessagePanel>>,
+    game_font: Res<GameFont>,
+) {
+    visible.0 = !visible.0;
+    let state = if visible.0 { "ON" } else { "OFF" };
+    info!("Message panel: {}", state);
+
+    for entity in &panel_query {
+        commands.entity(entity).despawn();
+    }
+    if !visible.0 {
+        return;
+    }
+
+    let font = game_font.0.clone();
+    let text_font = TextFont {
+        font,
+        font_size: 11.0,
+        ..default()
+    };
+    let count = registry.entries.len();
+    commands
+        .spawn((
+            Name::new("Message Panel"),
+            MessagePanel,
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Px(60.0),
+                right: Val::Px(8.0),
+                width: Val::Px(280.0),
+                padding: UiRect::all(Val::Px(6.0)),
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(2.0),
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.05, 0.05, 0.1, 0.85)),
+            GlobalZIndex(5),
+            DespawnOnExit(Screen::Gameplay),
+        ))
+        .with_children(|panel| {
+            for index in 0..count {
+                panel
+                    .spawn((
+                        Name::new("Message Log Toggle"),
+                        Button,
+                        MessageLogToggle(index),
+                        Node::default(),
+                    ))
+                    .with_children(|row| {
+                        row.spawn((
+                            Text::default(),
+                            text_font.clone(),
+                            TextColor(PANEL_TEXT_COLOR),
+                            MessageEntryText(index),
+                            Pickable::IGNORE,
+                        ));
+                    })
+                    .observe(toggle_message_logging);
+            }
+            panel.spawn((
+                Text::default(),
+                text_font.clone(),
+                TextColor(PANEL_TEXT_COLOR.with_alpha(0.7)),
+                RecentMessagesText,
+                Node {
+                    margin: UiRect::top(Val::Px(6.0)),
+                    ..default()
+                },
+            ));
+        });
+
+    // Fill in the new panel's text
+    registry.set_changed();
+}
+
+fn toggle_message_logging(
+    click: On<Pointer<Click>>,
+    toggle_query: Query<&MessageLogToggle>,
+    mut registry: ResMut<MessageRegistry>,
+) {
+    if let Ok(toggle) = toggle_query.get(click.entity)
+        && let Some(entry) = registry.entries.get_mut(toggle.0)
+    {
+        entry.log = !entry.log;
+    }
+}
+
+fn update_message_panel(
+    registry: Res<MessageRegistry>,
+    mut entry_texts: Query<(&mut Text, &MessageEntryText), Without<RecentMessagesText>>,
+    mut recent_text: Query<&mut Text, With<RecentMessagesText>>,
+) {
+    for (mut text, entry_text) in &mut entry_texts {
+        let Some(entry) = registry.entries.get(entry_text.0) else {
+            continue;
+        };
+        let log = if entry.log { "[log]" } else { "[   ]" };
+        text.0 = format!("{log} {} ({})", entry.name, entry.count);
+    }
+
+    for mut text in &mut recent_text {
+        let lines: Vec<&str> = registry.recent.iter().rev().map(String::as_str).collect();
+        text.0 = format!("Recent:\n{}", lines.join("\n"));
+    }
+}
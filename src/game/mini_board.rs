@@ -0,0 +1,151 @@
+//! A miniature, non-interactive copy of the board for the results screen.
+//!
+//! The grid is captured as [`GridCell`]s (the same representation snapshots
+//! use) when the game over menu opens, into [`FinalBoard`]. Any UI node with
+//! a [`MiniBoard`] component is filled with a scaled-down render of it: the
+//! walls, the danger line, and every bubble, so the player can see exactly
+//! what ended the run.
+
+use bevy::prelude::*;
+
+use super::{
+    bubble::{Bubble, GameAssets},
+    grid::HexGrid,
+    hex::GridOffset,
+    projectile::{DANGER_LINE_Y, TOP_WALL},
+    shooter::SHOOTER_Y,
+    snapshot::GridCell,
+};
+use crate::menus::Menu;
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<FinalBoard>();
+    app.add_systems(OnEnter(Menu::GameOver), capture_final_board);
+    app.add_observer(fill_mini_board);
+}
+
+/// World units to UI pixels.
+const MINI_SCALE: f32 = 0.35;
+
+/// Half the playfield width, out to the walls.
+const PLAYFIELD_HALF_WIDTH: f32 = 245.0;
+
+/// The board as it was when the run ended.
+#[derive(Resource, Debug, Clone, Default)]
+pub struct FinalBoard {
+    /// Bubbles sorted by row, then column.
+    pub cells: Vec<GridCell>,
+    pub grid_origin_y: f32,
+    pub hex_size: f32,
+}
+
+/// A UI node that shows [`FinalBoard`] in miniature.
+#[derive(Component, Debug, Clone, Copy, Default)]
+pub struct MiniBoard;
+
+fn capture_final_board(
+    grid: Res<HexGrid>,
+    grid_offset: Res<GridOffset>,
+    bubbles: Query<&Bubble>,
+    mut board: ResMut<FinalBoard>,
+) {
+    let mut cells: Vec<GridCell> = grid
+        .iter()
+        .filter_map(|(&coord, &entity)| {
+            // Bombs and stones are left out
+            let color = bubbles.get(entity).ok()?.match_color()?;
+            Some(GridCell { coord, color })
+        })
+        .collect();
+    cells.sort_by_key(|cell| (cell.coord.r, cell.coord.q));
+
+    *board = FinalBoard {
+        cells,
+        grid_origin_y: grid_offset.y,
+        hex_size: grid.hex_size,
+    };
+    info!("Captured final board with {} bubbles", board.cells.len());
+}
+
+/// Map a world position onto the miniature (origin at its top left).
+fn to_mini(world: Vec2) -> Vec2 {
+    Vec2::new(
+        (world.x + PLAYFIELD_HALF_WIDTH) * MINI_SCALE,
+        (TOP_WALL - world.y) * MINI_SCALE,
+    )
+}
+
+fn fill_mini_board(
+    add: On<Add, MiniBoard>,
+    mut commands: Commands,
+    board: Res<FinalBoard>,
+    game_assets: Option<Res<GameAssets>>,
+) {
+    let size = to_mini(Vec2::new(PLAYFIELD_HALF_WIDTH, SHOOTER_Y));
+    let danger_y = to_mini(Vec2::new(0.0, DANGER_LINE_Y)).y;
+    let bubble_size = board.hex_size * 2.0 * MINI_SCALE;
+
+    let mut entity = commands.entity(add.entity);
+    entity.insert((
+        Node {
+            width: Val::Px(size.x),
+            height: Val::Px(size.y),
+            border: UiRect::all(Val::Px(2.0)),
+            overflow: Overflow::clip(),
+            ..default()
+        },
+        BackgroundColor(Color::srgb(0.99, 0.97, 0.92)),
+        BorderColor::all(Color::srgb(0.3, 0.3, 0.3)),
+        Pickable::IGNORE,
+    ));
+    entity.with_children(|parent| {
+        parent.spawn((
+            Name::new("Mini Danger Line"),
+            Node {
+                position_type: PositionType::Absolute,
+                left: Val::Px(0.0),
+                top: Val::Px(danger_y),
+                width: Val::Percent(100.0),
+                height: Val::Px(2.0),
+                ..default()
+            },
+            BackgroundColor(Color::srgb(0.85, 0.15, 0.15)),
+            Pickable::IGNORE,
+        ));
+
+        for cell in &board.cells {
+            let world = cell
+                .coord
+                .to_pixel_with_offset(board.hex_size, board.grid_origin_y);
+            let center = to_mini(world);
+            let node = Node {
+                position_type: PositionType::Absolute,
+                left: Val::Px(center.x - bubble_size / 2.0),
+                top: Val::Px(center.y - bubble_size / 2.0),
+                width: Val::Px(bubble_size),
+                height: Val::Px(bubble_size),
+                ..default()
+            };
+            match &game_assets {
+                Some(assets) => {
+                    parent.spawn((
+                        Name::new("Mini Bubble"),
+                        ImageNode::new(assets.sprite_for(cell.color)),
+                        node,
+                        Pickable::IGNORE,
+                    ));
+                }
+                // Plain dots if the sprites aren't around
+                None => {
+                    parent.spawn((
+                        Name::new("Mini Bubble"),
+                        BackgroundColor(cell.color.to_color()),
+                        BorderRadius::MAX,
+                        node,
+                        Pickable::IGNORE,
+                    ));
+                }
+            }
+        }
+    });
+}
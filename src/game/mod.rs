@@ -3,26 +3,152 @@
 //! This module contains all the gameplay logic including:
 //! - Hexagonal grid system (axial coordinates)
 //! - Bubble entities and colors
+//! - Shading for mesh bubbles
 //! - Shooter/launcher mechanics
 //! - Projectile physics
 //! - Cluster detection and popping
 //! - Game state management
+//! - Optional shot clock
+//! - Shot history overlay
+//! - Glow highlights on grid cells
+//! - Day and night over long runs
+//! - Opt-in telemetry
+//! - Gameplay message registry
+//! - Serializable game state snapshots
+//! - Practice drills
+//! - Game modes (classic, zen, and kids)
+//! - Board layout transforms and procedural boards
+//! - Grid integrity checks
+//! - A game clock that pauses with the game
+//! - The attract-mode demo bot
+//! - Colorblind shapes and letters
+//! - Loaded snord mood reactions
+//! - Color clear celebrations
+//! - Miniature final board for the results screen
+//! - Run seeds and the recent games history
+//! - Campaign levels with closing walls
+//! - Wall portals
+//! - Bumper pegs
+//! - Sticky wall zones
+//! - Score zones for dropped bubbles
+//! - An entity-count watchdog for despawn leaks
+//! - Per-run gameplay entities under the game root
+//! - Landing forecasts worked out while a shot flies
+//! - A level editor
+//! - Campaign level scripts
+//! - Dialogue scenes with snord portraits
+//! - Companion snords with run-long passives
+//! - End-of-level grades
+//! - Hint nudges after a run of missed shots
+//! - Autosave of the run in progress
+//! - Break reminders for long sessions
+//! - Run recording and the replay viewer
+//! - The seeded run RNG
+//! - Starting handicaps for experienced players
+//! - The rotating weekly challenge
+//! - Bubbles that shrink deep into a run
+//! - Wildfires that merge two colors for a few shots
+//! - Endless runs that go on past a cleared board
+//! - An assist that ghosts the board one row lower before a descent
 
+pub mod autosave;
+pub mod breaks;
 mod bubble;
+mod bubble_material;
+mod bumpers;
+pub mod campaign;
+mod clock;
 mod cluster;
+mod color_clear;
+pub mod companion;
+mod daylight;
 mod debug;
+mod demo;
+mod descent_diff;
+mod descent_preview;
+mod dialogue;
+pub mod drills;
+mod editor;
+mod endless;
+pub mod event_feed;
+mod forecast;
+mod gameplay_entities;
+mod generator;
+pub mod grading;
 mod grid;
+pub mod handicap;
 mod hex;
-mod highscore;
+mod highlight;
+pub mod highscore;
+pub mod hints;
+pub mod history;
+mod integrity;
+mod kids;
+mod layout;
+mod messages;
+pub mod mini_board;
+pub mod mode;
+mod mood;
+mod patterns;
 mod polish;
+mod portals;
 pub mod powerups;
 mod projectile;
-mod shooter;
-mod state;
+pub mod replay;
+pub mod rng;
+mod score_zones;
+mod scripting;
+pub mod shooter;
+pub mod shot_clock;
+mod shot_trace;
+mod shrink;
+pub mod snapshot;
+pub mod state;
+mod sticky_walls;
+pub mod telemetry;
+#[cfg(test)]
+mod tests;
+mod watchdog;
+pub mod weekly;
+mod wildfire;
+mod zen;
 
 use bevy::prelude::*;
 
-use crate::screens::Screen;
+use crate::{screens::InGame, textures::SpriteLoader};
+use gameplay_entities::GameRoot;
+use mode::GameMode;
+
+/// Hot paths, exposed for the benchmarks in `benches/` (with the `bench`
+/// feature, so normal builds keep them private).
+#[cfg(feature = "bench")]
+pub mod bench {
+    pub use super::{
+        bubble::BubbleColor,
+        bumpers::Bumpers,
+        cluster::{find_anchored_bubbles, find_cluster, find_floating_bubbles},
+        grid::HexGrid,
+        hex::{GRID_ORIGIN_Y, HexCoord},
+        mode::GameMode,
+        portals::Portals,
+        projectile::{Walls, predict_landing},
+        shooter::SHOOTER_Y,
+        sticky_walls::StickyWalls,
+    };
+}
+
+/// The rules the headless simulator in `src/sim` plays by, so the bots
+/// and the game can't drift apart.
+pub(crate) mod rules {
+    pub use super::{
+        bubble::{BubbleColor, INITIAL_ROWS},
+        cluster::{MIN_CLUSTER_SIZE, find_cluster, find_floating_bubbles},
+        grid::HexGrid,
+        hex::{BoardLayout, HexCoord},
+        projectile::{GRACE_BOUNCES_PER_RUN, danger_depth},
+        state::{FLOATING_BONUS_MULTIPLIER, POINTS_PER_BUBBLE, shots_per_descent},
+    };
+}
 
 pub(super) fn plugin(app: &mut App) {
     app.add_plugins((
@@ -30,6 +156,7 @@ pub(super) fn plugin(app: &mut App) {
         grid::plugin,
         bubble::plugin,
         shooter::plugin,
+        shot_clock::plugin,
         projectile::plugin,
         cluster::plugin,
         state::plugin,
@@ -37,36 +164,99 @@ pub(super) fn plugin(app: &mut App) {
         powerups::plugin,
         polish::plugin,
         debug::plugin,
+        event_feed::plugin,
+        telemetry::plugin,
+        messages::plugin,
+    ));
+    app.add_plugins((
+        snapshot::plugin,
+        drills::plugin,
+        mode::plugin,
+        zen::plugin,
+        kids::plugin,
+        shot_trace::plugin,
+        integrity::plugin,
+        clock::plugin,
+        demo::plugin,
+        patterns::plugin,
+        mood::plugin,
+        color_clear::plugin,
+        mini_board::plugin,
+        history::plugin,
+    ));
+    app.add_plugins((
+        campaign::plugin,
+        portals::plugin,
+        bumpers::plugin,
+        sticky_walls::plugin,
+        score_zones::plugin,
+        watchdog::plugin,
+        gameplay_entities::plugin,
+        forecast::plugin,
+        bubble_material::plugin,
+        highlight::plugin,
+        daylight::plugin,
+        editor::plugin,
+        scripting::plugin,
+        dialogue::plugin,
+        companion::plugin,
+    ));
+    app.add_plugins((
+        grading::plugin,
+        hints::plugin,
+        autosave::plugin,
+        breaks::plugin,
+        replay::plugin,
+        rng::plugin,
+        handicap::plugin,
+        weekly::plugin,
+        shrink::plugin,
+        wildfire::plugin,
+        descent_diff::plugin,
+        endless::plugin,
+        descent_preview::plugin,
     ));
 }
 
+/// The panel behind the board.
+#[derive(Component, Debug)]
+pub struct GamePanel;
+
 /// System to spawn the game level when entering gameplay.
-/// Called from `screens/gameplay.rs` on `OnEnter(Screen::Gameplay)`.
-pub fn spawn_game(mut commands: Commands, asset_server: Res<AssetServer>) {
+/// Called from `screens/gameplay.rs` on `OnEnter(InGame)`, for both gameplay
+/// and the demo.
+pub fn spawn_game(mut commands: Commands, sprites: SpriteLoader, mode: Res<GameMode>) {
     commands.spawn((
         Name::new("Game"),
+        GameRoot,
         Transform::default(),
         Visibility::default(),
-        DespawnOnExit(Screen::Gameplay),
+        DespawnOnExit(InGame),
     ));
 
     // Spawn game panel background (centered on playfield)
     // Playfield: TOP_WALL=280, SHOOTER_Y=-250, so center Y = (280 + (-250)) / 2 = 15
-    let panel_image = asset_server.load("images/game_bounds.png");
+    let panel_image = sprites.load("images/game_bounds.png");
     commands.spawn((
         Name::new("Game Panel"),
+        GamePanel,
         Sprite::from_image(panel_image),
         Transform::from_xyz(0.0, 15.0, -1.0), // Z=-1 to be behind bubbles
-        DespawnOnExit(Screen::Gameplay),
+        DespawnOnExit(InGame),
     ));
 
     // Spawn danger line indicator (Y=-170, overlays game panel)
-    let danger_line_image = asset_server.load("images/danger_line.png");
+    // Zen mode has no danger line
+    if !mode.has_pressure() {
+        info!("Game spawned - zen mode");
+        return;
+    }
+    let danger_line_image = sprites.load("images/danger_line.png");
     commands.spawn((
         Name::new("Danger Line"),
         Sprite::from_image(danger_line_image),
         Transform::from_xyz(0.0, -170.0, 0.0), // Z=0 to overlay game panel
-        DespawnOnExit(Screen::Gameplay),
+        DespawnOnExit(InGame),
     ));
 
     info!("Game spawned - bubble shooter ready!");
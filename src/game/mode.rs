@@ -0,0 +1,289 @@
+//! Game modes, which switch parts of the rules on or off.
+//!
+//! The mode is picked before a run starts (from the mode select screen or
+//! `--mode`) and stays the same through restarts from the game over menu. The
+//! mode select screen sets [`SelectedMode`], which is turned into the
+//! [`GameMode`] for the next run. Modes can also
+//! change the grid layout, which is applied to [`HexGrid`] as soon as the mode
+//! changes so it's in place before the board is spawned.
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    bubble::BubbleColor,
+    grid::{GridBounds, HexGrid},
+    hex::HEX_SIZE,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<GameMode>();
+    app.register_type::<GameMode>();
+    app.init_resource::<SelectedMode>();
+    app.init_resource::<LevelColors>();
+
+    app.add_systems(
+        Update,
+        (
+            // Not on startup, so `--mode` and tests can set the mode directly
+            apply_selected_mode
+                .run_if(resource_changed::<SelectedMode>.and(not(resource_added::<SelectedMode>))),
+            apply_grid_layout.run_if(resource_changed::<GameMode>),
+        )
+            .chain(),
+    );
+}
+
+/// The rules the current run is played with.
+#[derive(
+    Resource, Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Reflect, Serialize, Deserialize,
+)]
+#[reflect(Resource)]
+pub enum GameMode {
+    /// Descent, danger line, score, and power-ups.
+    #[default]
+    Classic,
+    /// No descent, no danger line, no score; the board refills when sparse.
+    Zen,
+    /// Classic rules with bigger bubbles, fewer colors, slower shots, and
+    /// more forgiving collisions.
+    Kids,
+    /// Classic rules on hand-made levels, played in order. The level comes
+    /// from the profile's progress (see `campaign`).
+    Campaign,
+    /// Classic rules against a countdown that pops and drops add time to
+    /// (see `state`).
+    TimeAttack,
+}
+
+impl GameMode {
+    pub const ALL: [GameMode; 5] = [
+        GameMode::Classic,
+        GameMode::Zen,
+        GameMode::Kids,
+        GameMode::Campaign,
+        GameMode::TimeAttack,
+    ];
+
+    /// Name used on the command line.
+    pub fn name(self) -> &'static str {
+        match self {
+            GameMode::Classic => "classic",
+            GameMode::Zen => "zen",
+            GameMode::Kids => "kids",
+            GameMode::Campaign => "campaign",
+            GameMode::TimeAttack => "time-attack",
+        }
+    }
+
+    /// Name shown in menus, matching the mode select screen.
+    pub fn label(self) -> &'static str {
+        match self {
+            GameMode::Classic => "Endless",
+            GameMode::Zen => "Zen",
+            GameMode::Kids => "Kids",
+            GameMode::Campaign => "Campaign",
+            GameMode::TimeAttack => "Time Attack",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|mode| mode.name().eq_ignore_ascii_case(name))
+    }
+
+    /// Whether the grid descends, the run can be lost, and score matters.
+    pub fn has_pressure(self) -> bool {
+        self != GameMode::Zen
+    }
+
+    /// Whether random boards get a few bombs.
+    pub fn has_bombs(self) -> bool {
+        self == GameMode::Classic
+    }
+
+    /// Whether clearing the board refills it and the run goes on, instead
+    /// of winning (see `endless`).
+    pub fn continues_after_clear(self) -> bool {
+        self == GameMode::Classic
+    }
+
+    /// Whether bubbles shrink deep into a run (see `shrink`).
+    pub fn shrinks(self) -> bool {
+        matches!(self, GameMode::Classic | GameMode::TimeAttack)
+    }
+
+    /// The size (outer radius) of each hexagon in pixels, before any
+    /// shrinking.
+    pub fn hex_size(self) -> f32 {
+        match self {
+            GameMode::Kids => 26.0,
+            _ => HEX_SIZE,
+        }
+    }
+
+    /// The playable area for this mode's hex size.
+    pub fn grid_bounds(self) -> GridBounds {
+        match self {
+            // Hex width = 26 * sqrt(3) ≈ 45px, so 9 columns fit between the
+            // walls (odd rows reach ±225px), and 11 rows reach the danger line
+            GameMode::Kids => GridBounds {
+                min_q: -4,
+                max_q: 4,
+                min_r: 0,
+                max_r: 10,
+            },
+            _ => GridBounds::default(),
+        }
+    }
+
+    /// Colors bubbles are drawn from.
+    pub fn palette(self) -> &'static [BubbleColor] {
+        match self {
+            GameMode::Kids => &BubbleColor::ALL[..4],
+            _ => &BubbleColor::ALL,
+        }
+    }
+
+    /// Multiplier on projectile speed.
+    pub fn projectile_speed_scale(self) -> f32 {
+        match self {
+            GameMode::Kids => 0.7,
+            _ => 1.0,
+        }
+    }
+
+    /// How close a projectile gets to a bubble before it sticks, in hex sizes.
+    pub fn collision_reach(self) -> f32 {
+        match self {
+            GameMode::Kids => 2.0,
+            // Slightly less than 2 radii
+            _ => 1.8,
+        }
+    }
+}
+
+/// An entry on the mode select screen.
+///
+/// Only some entries have rules of their own yet; the rest are listed so the
+/// screen shows what's coming, and stay locked until they're playable.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum SelectedMode {
+    #[default]
+    Endless,
+    Campaign,
+    TimeAttack,
+    /// The practice drills.
+    Puzzle,
+    Zen,
+    Versus,
+    Daily,
+    /// This week's challenge, played with Classic rules.
+    Weekly,
+    Kids,
+}
+
+impl SelectedMode {
+    pub const ALL: [SelectedMode; 9] = [
+        SelectedMode::Endless,
+        SelectedMode::Campaign,
+        SelectedMode::TimeAttack,
+        SelectedMode::Puzzle,
+        SelectedMode::Zen,
+        SelectedMode::Versus,
+        SelectedMode::Daily,
+        SelectedMode::Weekly,
+        SelectedMode::Kids,
+    ];
+
+    /// Entries unlocked in a new profile.
+    pub const STARTER: [SelectedMode; 7] = [
+        SelectedMode::Endless,
+        SelectedMode::Campaign,
+        SelectedMode::TimeAttack,
+        SelectedMode::Puzzle,
+        SelectedMode::Zen,
+        SelectedMode::Weekly,
+        SelectedMode::Kids,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            SelectedMode::Endless => "Endless",
+            SelectedMode::Campaign => "Campaign",
+            SelectedMode::TimeAttack => "Time Attack",
+            SelectedMode::Puzzle => "Puzzle",
+            SelectedMode::Zen => "Zen",
+            SelectedMode::Versus => "Versus",
+            SelectedMode::Daily => "Daily",
+            SelectedMode::Weekly => "Weekly",
+            SelectedMode::Kids => "Kids",
+        }
+    }
+
+    pub fn description(self) -> &'static str {
+        match self {
+            SelectedMode::Endless => "The classic game. Survive as long as you can.",
+            SelectedMode::Campaign => "Hand-made levels where the walls close in.",
+            SelectedMode::TimeAttack => "Score as much as you can against the clock.",
+            SelectedMode::Puzzle => "Fixed boards with a set number of shots.",
+            SelectedMode::Zen => "No descent, no danger, no score. Just pop.",
+            SelectedMode::Versus => "Race a friend to clear the board.",
+            SelectedMode::Daily => "One shared board a day, one try.",
+            SelectedMode::Weekly => "This week's board, with a twist.",
+            SelectedMode::Kids => "Big bubbles, few colors, slow shots.",
+        }
+    }
+
+    /// The rules this entry is played with, or `None` if it isn't playable
+    /// yet. Puzzle boards are picked from the drills menu.
+    pub fn game_mode(self) -> Option<GameMode> {
+        match self {
+            SelectedMode::Endless | SelectedMode::Puzzle | SelectedMode::Weekly => {
+                Some(GameMode::Classic)
+            }
+            SelectedMode::Campaign => Some(GameMode::Campaign),
+            SelectedMode::Zen => Some(GameMode::Zen),
+            SelectedMode::Kids => Some(GameMode::Kids),
+            SelectedMode::TimeAttack => Some(GameMode::TimeAttack),
+            _ => None,
+        }
+    }
+}
+
+/// Colors the current level limits its bubbles to, instead of the mode's
+/// palette. Set by campaign levels.
+#[derive(Resource, Debug, Clone, Default, PartialEq)]
+pub struct LevelColors(pub Option<Vec<BubbleColor>>);
+
+impl LevelColors {
+    /// Colors bubbles are drawn from: the level's, or else `mode`'s.
+    pub fn palette(&self, mode: GameMode) -> &[BubbleColor] {
+        self.0.as_deref().unwrap_or(mode.palette())
+    }
+}
+
+/// Run condition: the current mode has descent, losing, and scoring.
+pub fn pressure_mode(mode: Res<GameMode>) -> bool {
+    mode.has_pressure()
+}
+
+/// Run condition: the run is against the time attack clock.
+pub fn time_attack_mode(mode: Res<GameMode>) -> bool {
+    *mode == GameMode::TimeAttack
+}
+
+/// Use the selected entry's rules for the next run.
+fn apply_selected_mode(selected: Res<SelectedMode>, mut mode: ResMut<GameMode>) {
+    if let Some(game_mode) = selected.game_mode() {
+        info!("Selected {} mode", selected.label());
+        mode.set_if_neq(game_mode);
+    }
+}
+
+/// Size the grid for the current mode.
+fn apply_grid_layout(mode: Res<GameMode>, mut grid: ResMut<HexGrid>) {
+    grid.bounds = mode.grid_bounds();
+    grid.hex_size = mode.hex_size();
+}
@@ -0,0 +1,180 @@
+//! The loaded snord reacts to the shot it's about to be.
+//!
+//! Each frame the current aim is run through [`predict_landing`] and the
+//! cluster it would make is counted:
+//! - Terrified: the shot would stop past the danger line, or the board is
+//!   about to reach it
+//! - Worried: the shot wouldn't pop anything
+//! - Excited: the shot would pop a big cluster
+//!
+//! Snord faces double as their color, so the sprite stays the same and the
+//! mood shows in how the loaded snord moves: it trembles, bounces, or shivers.
+//!
+//! The predicted cluster is kept in [`AimedCluster`] for the match highlight.
+
+use bevy::prelude::*;
+
+use super::{
+    bubble::BubbleColor,
+    bumpers::Bumpers,
+    clock::GameClock,
+    cluster::find_cluster,
+    grid::HexGrid,
+    hex::{GridOffset, HexCoord},
+    mode::GameMode,
+    portals::Portals,
+    powerups::UnlockedPowerUps,
+    projectile::{DANGER_LINE_Y, Walls, collision_distance, predict_landing},
+    shooter::{AimDirection, LoadedBubble, LoadedBubbleVisual, Shooter},
+    sticky_walls::StickyWalls,
+    wildfire::Wildfire,
+};
+use crate::{PausableSystems, display::idle_animations_enabled, screens::InGame};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<SnordMood>();
+    app.init_resource::<AimedCluster>();
+    app.add_systems(OnEnter(InGame), reset_mood);
+    app.add_systems(
+        Update,
+        (update_mood, animate_mood.run_if(idle_animations_enabled))
+            .chain()
+            .in_set(PausableSystems)
+            .run_if(in_state(InGame)),
+    );
+}
+
+/// Predicted clusters at least this big get the loaded snord excited.
+const EXCITED_CLUSTER: usize = 5;
+
+/// Rows above the danger line at which the snord starts to panic.
+const TERRIFIED_ROWS: f32 = 2.0;
+
+/// How the loaded snord feels about the current aim.
+#[derive(Resource, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SnordMood {
+    #[default]
+    Calm,
+    Worried,
+    Excited,
+    Terrified,
+}
+
+/// Where the current aim would land and the cluster it would make there.
+#[derive(Resource, Debug, Clone, Default, PartialEq, Eq)]
+pub struct AimedCluster {
+    pub landing: Option<HexCoord>,
+    /// The landing cell and the matching bubbles joined to it.
+    pub cluster: Vec<HexCoord>,
+}
+
+/// The loaded visual's transform before any mood animation.
+#[derive(Component)]
+struct MoodBase(Transform);
+
+fn reset_mood(mut mood: ResMut<SnordMood>, mut aimed: ResMut<AimedCluster>) {
+    *mood = SnordMood::default();
+    *aimed = AimedCluster::default();
+}
+
+fn update_mood(
+    mut mood: ResMut<SnordMood>,
+    mut aimed: ResMut<AimedCluster>,
+    grid: Res<HexGrid>,
+    grid_offset: Res<GridOffset>,
+    colors: Query<&BubbleColor>,
+    shooter: Query<(&Transform, &AimDirection, &LoadedBubble), With<Shooter>>,
+    powerups: Res<UnlockedPowerUps>,
+    mode: Res<GameMode>,
+    walls: Res<Walls>,
+    portals: Res<Portals>,
+    sticky: Res<StickyWalls>,
+    bumpers: Res<Bumpers>,
+    wildfire: Res<Wildfire>,
+) {
+    let Ok((transform, aim, loaded)) = shooter.single() else {
+        return;
+    };
+
+    let row_height = grid.hex_size * 1.5;
+    let near_danger = grid.coords().any(|coord| {
+        let y = coord.to_pixel_with_offset(grid.hex_size, grid_offset.y).y;
+        y < DANGER_LINE_Y + TERRIFIED_ROWS * row_height
+    });
+    let landing = predict_landing(
+        &grid,
+        grid_offset.y,
+        *walls,
+        &portals,
+        &sticky,
+        &bumpers,
+        transform.translation.truncate(),
+        aim.0,
+        collision_distance(&grid, &powerups, *mode),
+    );
+
+    let new_aimed = match landing {
+        Some(landing) if !landing.in_danger => AimedCluster {
+            landing: Some(landing.coord),
+            cluster: find_cluster(
+                &grid.layout,
+                landing.coord,
+                wildfire.resolve(loaded.0),
+                |coord| {
+                    grid.get(coord)
+                        .and_then(|entity| colors.get(entity).ok())
+                        .map(|&color| wildfire.resolve(color))
+                },
+            ),
+        },
+        _ => AimedCluster::default(),
+    };
+
+    let new_mood = match landing {
+        _ if near_danger => SnordMood::Terrified,
+        Some(landing) if landing.in_danger => SnordMood::Terrified,
+        Some(_) => match new_aimed.cluster.len() {
+            0..3 => SnordMood::Worried,
+            EXCITED_CLUSTER.. => SnordMood::Excited,
+            _ => SnordMood::Calm,
+        },
+        None => SnordMood::Calm,
+    };
+    mood.set_if_neq(new_mood);
+    aimed.set_if_neq(new_aimed);
+}
+
+/// Tremble, bounce, or shiver the loaded snord to match its mood.
+fn animate_mood(
+    mut commands: Commands,
+    time: Res<Time<GameClock>>,
+    mood: Res<SnordMood>,
+    new_visuals: Query<(Entity, &Transform), (With<LoadedBubbleVisual>, Without<MoodBase>)>,
+    mut visuals: Query<(&mut Transform, &MoodBase), With<LoadedBubbleVisual>>,
+) {
+    for (entity, transform) in &new_visuals {
+        commands.entity(entity).insert(MoodBase(*transform));
+    }
+
+    let t = time.elapsed_secs();
+    for (mut transform, base) in &mut visuals {
+        *transform = base.0;
+        match *mood {
+            SnordMood::Calm => {}
+            SnordMood::Worried => {
+                // Slow, nervous rocking
+                transform.rotate_z((t * 5.0).sin() * 0.12);
+            }
+            SnordMood::Excited => {
+                // Hop in place
+                transform.translation.y += (t * 12.0).sin().abs() * 4.0;
+                transform.scale *= 1.0 + (t * 12.0).sin().abs() * 0.06;
+            }
+            SnordMood::Terrified => {
+                // Fast shiver
+                transform.translation.x += (t * 50.0).sin() * 1.5;
+                transform.rotate_z((t * 37.0).sin() * 0.05);
+            }
+        }
+    }
+}
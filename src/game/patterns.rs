@@ -0,0 +1,174 @@
+//! Colorblind symbols - a shape or letter over every bubble.
+//!
+//! In [`ColorblindMode::Shapes`] each color gets its own outline (circle,
+//! square, triangle, ...), drawn with gizmos over the grid, the projectile,
+//! and the shooter's queue. In [`ColorblindMode::Letters`] each bubble
+//! carries a [`ColorSymbol`] child with its color's letter, spawned along
+//! with the bubble and shown only in that mode.
+
+use bevy::prelude::*;
+
+use super::{
+    bubble::{BubbleColor, sprite_scale},
+    grid::HexGrid,
+    projectile::Projectile,
+};
+use crate::{
+    accessibility::{ColorblindMode, shapes_enabled},
+    screens::InGame,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(
+        Update,
+        (
+            draw_patterns.run_if(shapes_enabled),
+            show_color_symbols.run_if(
+                resource_changed::<ColorblindMode>.or(any_match_filter::<Added<ColorSymbol>>),
+            ),
+            sync_color_symbols,
+        )
+            .run_if(in_state(InGame)),
+    );
+}
+
+/// Letter size as a fraction of the hex size.
+const LETTER_SIZE: f32 = 0.8;
+
+/// Where the letter sits on its bubble, in hex sizes from the center. Off to
+/// the side, so the snord's face still shows.
+const LETTER_OFFSET: Vec2 = Vec2::new(0.45, -0.4);
+
+/// A bubble's letter, shown in [`ColorblindMode::Letters`].
+#[derive(Component, Debug)]
+pub struct ColorSymbol;
+
+/// The letter for `color`, as a child of a bubble of `hex_size` whose own
+/// scale is `parent_scale` (sprites are scaled, meshes aren't).
+pub fn color_symbol(color: BubbleColor, hex_size: f32, parent_scale: f32) -> impl Bundle {
+    (
+        Name::new("Color Symbol"),
+        ColorSymbol,
+        Text2d::new(color.letter()),
+        TextFont {
+            font_size: LETTER_SIZE * hex_size,
+            ..default()
+        },
+        TextColor(PATTERN_COLOR),
+        Transform::from_translation((LETTER_OFFSET * hex_size / parent_scale).extend(0.1))
+            .with_scale(Vec3::splat(1.0 / parent_scale)),
+        Visibility::Hidden,
+        Pickable::IGNORE,
+    )
+}
+
+/// Pattern size as a fraction of the hex size.
+const PATTERN_SIZE: f32 = 0.4;
+
+/// Dark outline so the shapes show on every bubble color.
+const PATTERN_COLOR: Color = Color::srgba(0.1, 0.1, 0.1, 0.85);
+
+fn draw_patterns(
+    mut gizmos: Gizmos,
+    grid: Res<HexGrid>,
+    bubbles: Query<(
+        &GlobalTransform,
+        &BubbleColor,
+        &InheritedVisibility,
+        Has<Sprite>,
+    )>,
+    projectiles: Query<(&GlobalTransform, &Projectile, Has<Sprite>)>,
+) {
+    let shapes = bubbles
+        .iter()
+        .filter(|(_, _, visibility, _)| visibility.get())
+        .map(|(transform, color, _, sprite)| (transform, *color, sprite))
+        .chain(
+            projectiles
+                .iter()
+                .map(|(transform, projectile, sprite)| (transform, projectile.color, sprite)),
+        );
+    for (transform, color, sprite) in shapes {
+        let center = transform.translation().truncate();
+        // Sprites are scaled to the hex size and meshes are built at it, so
+        // whatever scale is left over (the shooter's queue) sizes the pattern
+        let base_scale = if sprite {
+            sprite_scale(grid.hex_size)
+        } else {
+            1.0
+        };
+        let size = PATTERN_SIZE * grid.hex_size * transform.scale().x / base_scale;
+        draw_pattern(&mut gizmos, color, center, size);
+    }
+}
+
+fn draw_pattern(gizmos: &mut Gizmos, color: BubbleColor, center: Vec2, size: f32) {
+    match color {
+        BubbleColor::Red => {
+            gizmos.circle_2d(center, size, PATTERN_COLOR);
+        }
+        BubbleColor::Blue => {
+            gizmos.rect_2d(center, Vec2::splat(size * 1.6), PATTERN_COLOR);
+        }
+        BubbleColor::Green => {
+            let points = [90.0_f32, 210.0, 330.0, 90.0]
+                .map(|degrees| center + Vec2::from_angle(degrees.to_radians()) * size);
+            gizmos.linestrip_2d(points, PATTERN_COLOR);
+        }
+        BubbleColor::Yellow => {
+            let d = size * 0.7;
+            gizmos.line_2d(
+                center + Vec2::new(-d, -d),
+                center + Vec2::new(d, d),
+                PATTERN_COLOR,
+            );
+            gizmos.line_2d(
+                center + Vec2::new(-d, d),
+                center + Vec2::new(d, -d),
+                PATTERN_COLOR,
+            );
+        }
+        BubbleColor::Purple => {
+            let points = [0.0_f32, 90.0, 180.0, 270.0, 0.0]
+                .map(|degrees| center + Vec2::from_angle(degrees.to_radians()) * size);
+            gizmos.linestrip_2d(points, PATTERN_COLOR);
+        }
+        BubbleColor::Orange => {
+            for y in [-size * 0.5, 0.0, size * 0.5] {
+                gizmos.line_2d(
+                    center + Vec2::new(-size, y),
+                    center + Vec2::new(size, y),
+                    PATTERN_COLOR,
+                );
+            }
+        }
+    }
+}
+
+fn show_color_symbols(
+    colorblind: Res<ColorblindMode>,
+    mut symbols: Query<&mut Visibility, With<ColorSymbol>>,
+) {
+    let visibility = if *colorblind == ColorblindMode::Letters {
+        Visibility::Inherited
+    } else {
+        Visibility::Hidden
+    };
+    for mut symbol in &mut symbols {
+        symbol.set_if_neq(visibility);
+    }
+}
+
+/// Keep letters in step with bubbles whose color is swapped, like the
+/// shooter's queue.
+fn sync_color_symbols(
+    bubbles: Query<(&BubbleColor, &Children), Changed<BubbleColor>>,
+    mut symbols: Query<&mut Text2d, With<ColorSymbol>>,
+) {
+    for (color, children) in &bubbles {
+        let mut symbols = symbols.iter_many_mut(children);
+        while let Some(mut text) = symbols.fetch_next() {
+            text.0 = color.letter().to_string();
+        }
+    }
+}
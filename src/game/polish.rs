@@ -4,38 +4,68 @@ use bevy::prelude::*;
 use rand::Rng;
 
 use super::{
-    bubble::Bubble,
-    cluster::{ClusterPopped, FloatingBubblesRemoved},
+    bubble::BubbleAge,
+    clock::GameClock,
+    cluster::{ClusterPopped, FloatingBubblesRemoved, GameAudioAssets},
+    gameplay_entities::GameplayEntity,
+    grid::HexGrid,
     hex::{GridOffset, HEX_SIZE},
-    projectile::BubbleInDangerZone,
+    projectile::{BubbleInDangerZone, GraceBounceUsed, REJECTED_BUBBLE_DURATION, RejectedBubble},
+    rng::GameRng,
+    state::PerfectClear,
+};
+use crate::{
+    PausableSystems, accessibility::full_motion, audio::sound_effect_with_settings,
+    screens::InGame, theme::GameFont,
 };
-use crate::{PausableSystems, screens::Screen, theme::GameFont};
 
 pub(super) fn plugin(app: &mut App) {
     // Screen shake
     app.init_resource::<ScreenShake>();
     app.add_systems(
         Update,
-        (trigger_shake_on_events, apply_screen_shake)
+        (
+            trigger_shake_on_events.run_if(full_motion),
+            apply_screen_shake,
+        )
             .chain()
             .in_set(PausableSystems)
-            .run_if(in_state(Screen::Gameplay)),
+            .run_if(in_state(InGame)),
     );
 
     // Pop animation
     app.add_systems(
         Update,
-        animate_pop
-            .in_set(PausableSystems)
-            .run_if(in_state(Screen::Gameplay)),
+        animate_pop.in_set(PausableSystems).run_if(in_state(InGame)),
     );
 
     // Combo text
     app.add_systems(
         Update,
-        (spawn_combo_text, animate_combo_text)
+        (spawn_combo_text, animate_combo_text, position_combo_text)
+            .chain()
+            .after(apply_screen_shake)
+            .in_set(PausableSystems)
+            .run_if(in_state(InGame)),
+    );
+
+    // Old bubbles get grumpier
+    app.add_systems(Update, tint_aging_bubbles.run_if(in_state(InGame)));
+
+    // Perfect clear fanfare
+    app.add_systems(
+        Update,
+        celebrate_perfect_clear
+            .in_set(PausableSystems)
+            .run_if(in_state(InGame)),
+    );
+
+    // Last chance warning
+    app.add_systems(
+        Update,
+        (spawn_last_chance_warning, animate_rejected_bubble)
             .in_set(PausableSystems)
-            .run_if(in_state(Screen::Gameplay)),
+            .run_if(in_state(InGame)),
     );
 }
 
@@ -63,6 +93,7 @@ fn trigger_shake_on_events(
     mut cluster_events: MessageReader<ClusterPopped>,
     mut danger_events: MessageReader<BubbleInDangerZone>,
     mut floating_events: MessageReader<FloatingBubblesRemoved>,
+    mut grace_events: MessageReader<GraceBounceUsed>,
 ) {
     // Cluster popped - shake scales with size
     for event in cluster_events.read() {
@@ -90,20 +121,26 @@ fn trigger_shake_on_events(
         let intensity = (event.count as f32 * 0.15).min(0.6);
         shake.trauma = (shake.trauma + intensity).min(1.0);
     }
+
+    // Grace bounce used - almost as strong as game over
+    for _ in grace_events.read() {
+        shake.trauma = (shake.trauma + 0.8).min(1.0);
+    }
 }
 
 /// Apply screen shake to camera.
 fn apply_screen_shake(
-    time: Res<Time>,
+    time: Res<Time<GameClock>>,
     mut shake: ResMut<ScreenShake>,
     mut camera_query: Query<&mut Transform, With<Camera2d>>,
+    mut game_rng: ResMut<GameRng>,
 ) {
     let Ok(mut camera_transform) = camera_query.single_mut() else {
         return;
     };
 
     if shake.trauma > 0.0 {
-        let mut rng = rand::rng();
+        let rng = &mut game_rng.effects;
 
         // Shake amount = trauma^2 (makes it feel more natural)
         let shake_amount = shake.trauma * shake.trauma;
@@ -156,7 +193,7 @@ impl PopAnimation {
 /// Animate popping bubbles and despawn when done.
 fn animate_pop(
     mut commands: Commands,
-    time: Res<Time>,
+    time: Res<Time<GameClock>>,
     mut query: Query<(Entity, &mut Transform, &mut PopAnimation)>,
 ) {
     for (entity, mut transform, mut pop) in &mut query {
@@ -183,21 +220,105 @@ fn animate_pop(
     }
 }
 
+// =============================================================================
+// BUBBLE AGING
+// =============================================================================
+
+/// Sprite tint by descents survived: `(minimum age, tint)`, oldest last.
+/// Snords that hang around go from their normal selves to flushed and grumpy.
+const AGE_TINTS: [(u32, Color); 4] = [
+    (0, Color::WHITE),
+    (3, Color::srgb(1.0, 0.9, 0.85)),
+    (6, Color::srgb(1.0, 0.78, 0.7)),
+    (10, Color::srgb(0.95, 0.62, 0.55)),
+];
+
+/// The tint for a bubble that has survived `age` descents.
+pub fn age_tint(age: u32) -> Color {
+    AGE_TINTS
+        .iter()
+        .rev()
+        .find(|(min_age, _)| age >= *min_age)
+        .map_or(Color::WHITE, |(_, tint)| *tint)
+}
+
+/// Retint bubble sprites when they survive a descent.
+fn tint_aging_bubbles(mut bubbles: Query<(&BubbleAge, &mut Sprite), Changed<BubbleAge>>) {
+    for (age, mut sprite) in &mut bubbles {
+        // Keep any fade that's in progress
+        let alpha = sprite.color.alpha();
+        sprite.color = age_tint(age.0).with_alpha(alpha);
+    }
+}
+
 // =============================================================================
 // COMBO TEXT
 // =============================================================================
 
 /// Component for floating combo text.
+///
+/// The text is screen-space UI anchored to a point in the world. Its position
+/// is projected through the camera every frame, so it stays on its cluster
+/// through screen shake and zoom, and stays the same size on screen.
 #[derive(Component)]
 pub struct ComboText {
     /// Time elapsed.
     pub timer: f32,
     /// Total duration.
     pub duration: f32,
-    /// Starting position.
-    pub start_y: f32,
-    /// Float distance.
+    /// World position the text starts from.
+    pub anchor: Vec2,
+    /// How far the text floats up, in screen pixels.
     pub float_distance: f32,
+    /// Text color (alpha is animated).
+    pub color: Color,
+}
+
+impl ComboText {
+    pub fn new(anchor: Vec2, duration: f32, float_distance: f32, color: Color) -> Self {
+        Self {
+            timer: 0.0,
+            duration,
+            anchor,
+            float_distance,
+            color,
+        }
+    }
+}
+
+/// Floating text bundle. Starts hidden until it's first placed on screen.
+pub fn combo_text(
+    name: &'static str,
+    text: impl Into<String>,
+    combo: ComboText,
+    font: Handle<Font>,
+    font_size: f32,
+) -> impl Bundle {
+    let color = combo.color;
+    (
+        Name::new(name),
+        combo,
+        Text::new(text),
+        TextFont {
+            font,
+            font_size,
+            ..default()
+        },
+        TextColor(color),
+        Node {
+            position_type: PositionType::Absolute,
+            ..default()
+        },
+        // Center the text on its anchor
+        UiTransform {
+            translation: Val2::percent(-50.0, -50.0),
+            scale: Vec2::splat(0.5),
+            ..default()
+        },
+        Visibility::Hidden,
+        Pickable::IGNORE,
+        GameplayEntity,
+    )
 }
 
 /// Spawn combo text when clusters pop.
@@ -205,7 +326,7 @@ fn spawn_combo_text(
     mut commands: Commands,
     mut cluster_events: MessageReader<ClusterPopped>,
     grid_offset: Res<GridOffset>,
-    _bubble_query: Query<&Transform, With<Bubble>>,
+    grid: Res<HexGrid>,
     game_font: Res<GameFont>,
 ) {
     for event in cluster_events.read() {
@@ -219,7 +340,7 @@ fn spawn_combo_text(
             let sum: Vec2 = event
                 .coords
                 .iter()
-                .map(|coord| coord.to_pixel_with_offset(HEX_SIZE, grid_offset.y))
+                .map(|coord| coord.to_pixel_with_offset(grid.hex_size, grid_offset.y))
                 .fold(Vec2::ZERO, |acc, pos| acc + pos);
             sum / event.coords.len() as f32
         } else {
@@ -235,32 +356,21 @@ fn spawn_combo_text(
             format!("+{}!", event.count)
         };
 
-        commands.spawn((
-            Name::new("Combo Text"),
-            ComboText {
-                timer: 0.0,
-                duration: 0.8,
-                start_y: center_pos.y,
-                float_distance: 50.0,
-            },
-            Text2d::new(text),
-            TextFont {
-                font: game_font.0.clone(),
-                font_size: 32.0,
-                ..default()
-            },
-            TextColor(Color::srgb(1.0, 1.0, 0.2)),
-            Transform::from_translation(center_pos.extend(10.0)).with_scale(Vec3::splat(0.5)),
-            DespawnOnExit(Screen::Gameplay),
+        commands.spawn(combo_text(
+            "Combo Text",
+            text,
+            ComboText::new(center_pos, 0.8, 50.0, Color::srgb(1.0, 1.0, 0.2)),
+            game_font.0.clone(),
+            32.0,
         ));
     }
 }
 
-/// Animate combo text (float up and fade out).
+/// Animate combo text (grow, fade out, and despawn).
 fn animate_combo_text(
     mut commands: Commands,
-    time: Res<Time>,
-    mut query: Query<(Entity, &mut Transform, &mut ComboText, &mut TextColor)>,
+    time: Res<Time<GameClock>>,
+    mut query: Query<(Entity, &mut UiTransform, &mut ComboText, &mut TextColor)>,
 ) {
     for (entity, mut transform, mut combo, mut color) in &mut query {
         combo.timer += time.delta_secs();
@@ -273,10 +383,7 @@ fn animate_combo_text(
         } else {
             1.5
         };
-        transform.scale = Vec3::splat(scale);
-
-        // Float upward
-        transform.translation.y = combo.start_y + combo.float_distance * progress;
+        transform.scale = Vec2::splat(scale);
 
         // Fade out in last 30%
         let alpha = if progress > 0.7 {
@@ -284,7 +391,7 @@ fn animate_combo_text(
         } else {
             1.0
         };
-        color.0 = Color::srgba(1.0, 1.0, 0.2, alpha);
+        color.0 = combo.color.with_alpha(alpha);
 
         // Despawn when done
         if progress >= 1.0 {
@@ -292,3 +399,128 @@ fn animate_combo_text(
         }
     }
 }
+
+/// Place combo text on screen over its anchor, floating upward.
+///
+/// Runs after the shake so the text moves with this frame's camera.
+fn position_combo_text(
+    camera_query: Query<(&Camera, &Transform), With<Camera2d>>,
+    mut query: Query<(&ComboText, &mut Node, &mut Visibility)>,
+) {
+    let Ok((camera, camera_transform)) = camera_query.single() else {
+        return;
+    };
+    // The camera has no parent, so its transform is its global transform
+    let camera_transform = GlobalTransform::from(*camera_transform);
+
+    for (combo, mut node, mut visibility) in &mut query {
+        let Ok(screen_pos) = camera.world_to_viewport(&camera_transform, combo.anchor.extend(0.0))
+        else {
+            // No viewport yet (or anchor behind the camera)
+            *visibility = Visibility::Hidden;
+            continue;
+        };
+        let progress = (combo.timer / combo.duration).min(1.0);
+        node.left = Val::Px(screen_pos.x);
+        node.top = Val::Px(screen_pos.y - combo.float_distance * progress);
+        *visibility = Visibility::Inherited;
+    }
+}
+
+// =============================================================================
+// PERFECT CLEAR
+// =============================================================================
+
+/// Fanfare pitches for a perfect clear: the snord call stacked in a rising
+/// chord over a fast "my little snords".
+const FANFARE_PITCHES: [f32; 3] = [1.0, 1.26, 1.5];
+
+/// Banner and fanfare for clearing the board without a wasted shot.
+fn celebrate_perfect_clear(
+    mut commands: Commands,
+    mut perfect_events: MessageReader<PerfectClear>,
+    game_font: Res<GameFont>,
+    audio_assets: Option<Res<GameAudioAssets>>,
+) {
+    if perfect_events.read().next().is_none() {
+        return;
+    }
+
+    commands.spawn(combo_text(
+        "Perfect Clear Text",
+        "PERFECT CLEAR!",
+        ComboText::new(
+            Vec2::new(0.0, 120.0),
+            2.5,
+            40.0,
+            Color::srgb(1.0, 0.84, 0.0),
+        ),
+        game_font.0.clone(),
+        48.0,
+    ));
+
+    let Some(assets) = audio_assets else {
+        return;
+    };
+    commands.spawn(sound_effect_with_settings(
+        assets.my_little_snords.clone(),
+        1.3,
+        1.0,
+    ));
+    for pitch in FANFARE_PITCHES {
+        commands.spawn(sound_effect_with_settings(assets.snord.clone(), pitch, 0.7));
+    }
+}
+
+// =============================================================================
+// LAST CHANCE WARNING
+// =============================================================================
+
+/// Show a dramatic warning when the grace bounce saves the run.
+fn spawn_last_chance_warning(
+    mut commands: Commands,
+    mut grace_events: MessageReader<GraceBounceUsed>,
+    game_font: Res<GameFont>,
+) {
+    for event in grace_events.read() {
+        // Show the warning just above where the bubble was rejected
+        let start_y = event.position.y + HEX_SIZE * 2.0;
+        commands.spawn(combo_text(
+            "Last Chance Text",
+            "LAST CHANCE!",
+            ComboText::new(
+                Vec2::new(0.0, start_y),
+                1.6,
+                30.0,
+                Color::srgb(0.95, 0.15, 0.15),
+            ),
+            game_font.0.clone(),
+            40.0,
+        ));
+    }
+}
+
+/// Gravity applied to rejected bubbles in pixels per second squared.
+const REJECTED_BUBBLE_GRAVITY: f32 = 900.0;
+
+/// Animate rejected bubbles (fall away and shrink) and despawn when done.
+fn animate_rejected_bubble(
+    mut commands: Commands,
+    time: Res<Time<GameClock>>,
+    mut query: Query<(Entity, &mut Transform, &mut RejectedBubble)>,
+) {
+    for (entity, mut transform, mut rejected) in &mut query {
+        let dt = time.delta_secs();
+        rejected.timer += dt;
+        rejected.velocity.y -= REJECTED_BUBBLE_GRAVITY * dt;
+        transform.translation += rejected.velocity.extend(0.0) * dt;
+        transform.rotate_z(8.0 * dt);
+
+        let progress = (rejected.timer / REJECTED_BUBBLE_DURATION).min(1.0);
+        transform.scale = transform.scale.lerp(Vec3::ZERO, progress * 0.2);
+
+        if progress >= 1.0 {
+            commands.entity(entity).despawn();
+        }
+    }
+}
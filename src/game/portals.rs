@@ -0,0 +1,263 @@
+//! Portals - paired openings on the side walls.
+//!
+//! A shot that reaches a wall inside a portal comes out of the portal it's
+//! paired with instead of bouncing, at the same height within the portal and
+//! still heading the same way. If both ends are on the same wall, it comes
+//! back out of that wall. Portals sit on the [`Walls`], so they move with
+//! them, and [`Portals::teleport`] is shared by the projectile and every
+//! trajectory preview.
+//!
+//! Portals come from campaign level data; other runs have none.
+
+use std::f32::consts::TAU;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    clock::GameClock,
+    projectile::{WallSide, Walls},
+};
+use crate::{
+    PausableSystems, accessibility::full_motion, display::idle_animations_enabled, screens::InGame,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<Portals>();
+    app.init_resource::<Portals>();
+
+    app.add_systems(OnEnter(InGame), spawn_portal_swirls);
+    app.add_systems(
+        Update,
+        (
+            place_portal_swirls,
+            spin_portal_swirls
+                .in_set(PausableSystems)
+                .run_if(full_motion.and(idle_animations_enabled)),
+        )
+            .run_if(in_state(InGame)),
+    );
+}
+
+/// Half the height of a portal opening.
+pub const PORTAL_HALF_HEIGHT: f32 = 32.0;
+
+/// Radians per second the swirls turn.
+const SWIRL_SPEED: f32 = 3.0;
+
+/// Dots orbiting in each swirl.
+const SWIRL_DOTS: usize = 6;
+
+/// Colors for each pair's swirls, cycled if a level has more pairs.
+const PAIR_COLORS: [Color; 3] = [
+    Color::srgb(0.55, 0.3, 0.85),
+    Color::srgb(0.2, 0.7, 0.75),
+    Color::srgb(0.9, 0.5, 0.2),
+];
+
+/// One end of a portal pair.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Reflect)]
+pub struct Portal {
+    pub side: WallSide,
+    /// Height of the portal's center.
+    pub y: f32,
+}
+
+impl Portal {
+    fn contains(self, side: WallSide, y: f32) -> bool {
+        self.side == side && (y - self.y).abs() <= PORTAL_HALF_HEIGHT
+    }
+}
+
+/// The portals on the walls this run, in pairs.
+#[derive(Resource, Debug, Clone, Default, PartialEq, Reflect)]
+#[reflect(Resource)]
+pub struct Portals(pub Vec<[Portal; 2]>);
+
+impl Portals {
+    /// Where a shot touching the `side` wall at `pos` comes out, if it's in
+    /// a portal: the new position, `inset` in from the exit wall, and the
+    /// new direction. Returns None if the shot should bounce as usual.
+    pub fn teleport(
+        &self,
+        side: WallSide,
+        pos: Vec2,
+        direction: Vec2,
+        walls: Walls,
+        inset: f32,
+    ) -> Option<(Vec2, Vec2)> {
+        let (entry, exit) = self.0.iter().find_map(|&[a, b]| {
+            if a.contains(side, pos.y) {
+                Some((a, b))
+            } else if b.contains(side, pos.y) {
+                Some((b, a))
+            } else {
+                None
+            }
+        })?;
+
+        let exit_pos = Vec2::new(
+            exit.side.x(walls) + exit.side.inward() * inset,
+            exit.y + (pos.y - entry.y),
+        );
+        // Keep heading the same way, unless that's back into the exit wall
+        let mut exit_direction = direction;
+        if exit_direction.x * exit.side.inward() < 0.0 {
+            exit_direction.x = -exit_direction.x;
+        }
+        Some((exit_pos, exit_direction))
+    }
+}
+
+/// A portal drawn on the wall. Squashed toward the wall, so its spinning
+/// [`SwirlSpin`] child reads as an opening rather than a wheel.
+#[derive(Component, Debug, Clone, Copy)]
+struct PortalSwirl(Portal);
+
+/// The turning part of a swirl.
+#[derive(Component, Debug, Clone, Copy)]
+struct SwirlSpin(WallSide);
+
+/// Horizontal squash of the swirls.
+const SWIRL_SQUASH: f32 = 0.45;
+
+fn spawn_portal_swirls(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    portals: Res<Portals>,
+    walls: Res<Walls>,
+) {
+    let dot = meshes.add(Circle::new(3.5));
+    for (pair, ends) in portals.0.iter().enumerate() {
+        let color = PAIR_COLORS[pair % PAIR_COLORS.len()];
+        for portal in ends {
+            let swirl = commands
+                .spawn((
+                    Name::new("Portal Swirl"),
+                    PortalSwirl(*portal),
+                    Transform::from_xyz(portal.side.x(*walls), portal.y, 0.6)
+                        .with_scale(Vec3::new(SWIRL_SQUASH, 1.0, 1.0)),
+                    Visibility::default(),
+                    DespawnOnExit(InGame),
+                ))
+                .id();
+            let spin = commands
+                .spawn((
+                    SwirlSpin(portal.side),
+                    Transform::default(),
+                    Visibility::default(),
+                    ChildOf(swirl),
+                ))
+                .id();
+            for i in 0..SWIRL_DOTS {
+                // Dots spiral in toward the center, fading as they go
+                let t = i as f32 / SWIRL_DOTS as f32;
+                let angle = t * TAU;
+                let distance = PORTAL_HALF_HEIGHT * (1.0 - 0.6 * t);
+                commands.spawn((
+                    Mesh2d(dot.clone()),
+                    MeshMaterial2d(
+                        materials.add(ColorMaterial::from_color(color.with_alpha(1.0 - 0.6 * t))),
+                    ),
+                    Transform::from_xyz(angle.cos() * distance, angle.sin() * distance, 0.0),
+                    ChildOf(spin),
+                ));
+            }
+        }
+    }
+}
+
+/// Keep swirls on their walls as the walls move.
+fn place_portal_swirls(walls: Res<Walls>, mut swirls: Query<(&PortalSwirl, &mut Transform)>) {
+    if !walls.is_changed() {
+        return;
+    }
+    for (swirl, mut transform) in &mut swirls {
+        transform.translation.x = swirl.0.side.x(*walls);
+    }
+}
+
+fn spin_portal_swirls(time: Res<Time<GameClock>>, mut spins: Query<(&SwirlSpin, &mut Transform)>) {
+    for (spin, mut transform) in &mut spins {
+        // Both ends of a pair turn the same way as seen from inside
+        transform.rotate_z(SWIRL_SPEED * spin.0.inward() * time.delta_secs());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pair(a: Portal, b: Portal) -> Portals {
+        Portals(vec![[a, b]])
+    }
+
+    #[test]
+    fn shots_keep_their_heading_through_opposite_walls() {
+        let portals = pair(
+            Portal {
+                side: WallSide::Left,
+                y: 100.0,
+            },
+            Portal {
+                side: WallSide::Right,
+                y: -50.0,
+            },
+        );
+        let walls = Walls::default();
+        let direction = Vec2::new(-0.6, 0.8);
+
+        let (pos, dir) = portals
+            .teleport(
+                WallSide::Left,
+                Vec2::new(walls.left, 110.0),
+                direction,
+                walls,
+                20.0,
+            )
+            .unwrap();
+        assert_eq!(pos, Vec2::new(walls.right - 20.0, -40.0));
+        assert_eq!(dir, direction);
+
+        // Outside the opening it's a plain wall
+        assert!(
+            portals
+                .teleport(
+                    WallSide::Left,
+                    Vec2::new(walls.left, 0.0),
+                    direction,
+                    walls,
+                    20.0
+                )
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn same_wall_portals_send_shots_back_out() {
+        let portals = pair(
+            Portal {
+                side: WallSide::Right,
+                y: 0.0,
+            },
+            Portal {
+                side: WallSide::Right,
+                y: 150.0,
+            },
+        );
+        let walls = Walls::default();
+
+        let (pos, dir) = portals
+            .teleport(
+                WallSide::Right,
+                Vec2::new(walls.right, 0.0),
+                Vec2::new(0.6, 0.8),
+                walls,
+                0.0,
+            )
+            .unwrap();
+        assert_eq!(pos, Vec2::new(walls.right, 150.0));
+        assert_eq!(dir, Vec2::new(-0.6, 0.8));
+    }
+}
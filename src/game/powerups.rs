@@ -2,18 +2,31 @@
 //!
 //! Power-ups are selected from a random choice of 3 at each milestone.
 //! They reset each game (roguelike-style progression).
+//! Names, descriptions, and tiers are defined in `assets/data/powerups.json`.
 
 use bevy::prelude::*;
-use rand::seq::SliceRandom;
+use rand::{
+    Rng,
+    seq::{IndexedRandom, SliceRandom},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::mods::{ModList, parse_data};
 
 pub(super) fn plugin(app: &mut App) {
+    let catalog = PowerUpCatalog::load(app.world().get_resource::<ModList>());
+    app.insert_resource(catalog);
     app.init_resource::<UnlockedPowerUps>();
     app.init_resource::<PowerUpChoices>();
     app.register_type::<UnlockedPowerUps>();
 }
 
+/// Power-ups are compiled in, like the campaign levels.
+const POWERUPS_FILE: &str = include_str!("../../assets/data/powerups.json");
+
 /// All available power-ups.
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Reflect)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Reflect, Serialize, Deserialize)]
+#[reflect(Serialize, Deserialize)]
 pub enum PowerUp {
     // Tier 1 (Levels 5, 10)
     SpeedySnord,
@@ -25,25 +38,11 @@ pub enum PowerUp {
     FortuneSnord,
     ComboSnord,
     Sharpshooter,
+    RainbowSnord,
 }
 
 impl PowerUp {
-    /// Get the tier of this power-up (1 or 2).
-    #[allow(dead_code)]
-    pub fn tier(&self) -> u32 {
-        match self {
-            PowerUp::SpeedySnord
-            | PowerUp::EagleEye
-            | PowerUp::LuckySnord
-            | PowerUp::BouncySnord => 1,
-            PowerUp::Procrastisnord
-            | PowerUp::FortuneSnord
-            | PowerUp::ComboSnord
-            | PowerUp::Sharpshooter => 2,
-        }
-    }
-
-    /// Get the display name.
+    /// The built-in name, used in logs. Menus use the catalog's name.
     pub fn name(&self) -> &'static str {
         match self {
             PowerUp::SpeedySnord => "Speedy Snord",
@@ -54,74 +53,95 @@ impl PowerUp {
             PowerUp::FortuneSnord => "Fortune Snord",
             PowerUp::ComboSnord => "Combo Snord",
             PowerUp::Sharpshooter => "Sharpshooter",
+            PowerUp::RainbowSnord => "Rainbow Snord",
         }
     }
 
-    /// Get the description.
-    pub fn description(&self) -> &'static str {
-        match self {
-            PowerUp::SpeedySnord => "25% faster projectiles",
-            PowerUp::EagleEye => "2x longer aim line",
-            PowerUp::LuckySnord => "Better color matching",
-            PowerUp::BouncySnord => "Shows bounce trajectory",
-            PowerUp::Procrastisnord => "+2 shots before descent",
-            PowerUp::FortuneSnord => "See 3 upcoming snords",
-            PowerUp::ComboSnord => "+50% score for big combos",
-            PowerUp::Sharpshooter => "More precise shots",
-        }
+    /// Get the tier for a given level.
+    pub fn tier_for_level(level: u32) -> u32 {
+        if level < 15 { 1 } else { 2 }
     }
+}
+
+/// How a power-up is offered: its menu text and tier (1 or 2).
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct PowerUpDefinition {
+    pub power: PowerUp,
+    pub name: String,
+    pub description: String,
+    pub tier: u32,
+}
 
-    /// Get all power-ups for a given tier.
-    pub fn for_tier(tier: u32) -> Vec<PowerUp> {
-        match tier {
-            1 => vec![
-                PowerUp::SpeedySnord,
-                PowerUp::EagleEye,
-                PowerUp::LuckySnord,
-                PowerUp::BouncySnord,
-            ],
-            _ => vec![
-                PowerUp::Procrastisnord,
-                PowerUp::FortuneSnord,
-                PowerUp::ComboSnord,
-                PowerUp::Sharpshooter,
-            ],
+/// The power-ups on offer, from `assets/data/powerups.json`. Power-ups
+/// missing from it are never offered.
+#[derive(Resource, Debug, Default)]
+pub struct PowerUpCatalog(pub Vec<PowerUpDefinition>);
+
+impl PowerUpCatalog {
+    fn load(mods: Option<&ModList>) -> Self {
+        match parse_data::<Vec<PowerUpDefinition>>(mods, "data/powerups.json", POWERUPS_FILE) {
+            Ok(powers) => Self(powers),
+            Err(e) => {
+                warn!("Failed to parse power-ups: {}", e);
+                Self::default()
+            }
         }
     }
 
-    /// Get the tier for a given level.
-    pub fn tier_for_level(level: u32) -> u32 {
-        if level < 15 { 1 } else { 2 }
+    pub fn get(&self, power: PowerUp) -> Option<&PowerUpDefinition> {
+        self.0.iter().find(|def| def.power == power)
+    }
+
+    /// The name shown for a power-up.
+    pub fn name(&self, power: PowerUp) -> &str {
+        self.get(power)
+            .map_or(power.name(), |def| def.name.as_str())
+    }
+
+    pub fn description(&self, power: PowerUp) -> &str {
+        self.get(power).map_or("", |def| def.description.as_str())
+    }
+
+    /// Power-ups in a tier.
+    fn tier(&self, tier: u32) -> impl Iterator<Item = PowerUp> + '_ {
+        self.0
+            .iter()
+            .filter(move |def| def.tier == tier)
+            .map(|def| def.power)
     }
 
     /// Get 3 random power-ups for selection, excluding already unlocked ones.
-    pub fn random_choices(level: u32, unlocked: &[PowerUp]) -> Vec<PowerUp> {
-        let tier = Self::tier_for_level(level);
-        let mut available: Vec<PowerUp> = Self::for_tier(tier)
-            .into_iter()
-            .filter(|p| !unlocked.contains(p))
-            .collect();
+    pub fn random_choices(
+        &self,
+        level: u32,
+        unlocked: &[PowerUp],
+        rng: &mut impl Rng,
+    ) -> Vec<PowerUp> {
+        let tier = PowerUp::tier_for_level(level);
+        let mut available: Vec<PowerUp> =
+            self.tier(tier).filter(|p| !unlocked.contains(p)).collect();
 
         // If not enough in current tier, add from other tier
         if available.len() < 3 {
             let other_tier = if tier == 1 { 2 } else { 1 };
-            let other: Vec<PowerUp> = Self::for_tier(other_tier)
-                .into_iter()
-                .filter(|p| !unlocked.contains(p))
-                .collect();
-            available.extend(other);
+            available.extend(self.tier(other_tier).filter(|p| !unlocked.contains(p)));
         }
 
         // Shuffle and take 3
-        let mut rng = rand::rng();
-        available.shuffle(&mut rng);
+        available.shuffle(rng);
         available.into_iter().take(3).collect()
     }
+
+    /// A random power-up from `tier`, if it has any.
+    pub fn random_from_tier(&self, tier: u32, rng: &mut impl Rng) -> Option<PowerUp> {
+        let powers: Vec<PowerUp> = self.tier(tier).collect();
+        powers.choose(rng).copied()
+    }
 }
 
 /// Resource tracking player's unlocked power-ups (reset each game).
-#[derive(Resource, Default, Reflect)]
-#[reflect(Resource)]
+#[derive(Resource, Debug, Clone, Default, PartialEq, Reflect, Serialize, Deserialize)]
+#[reflect(Resource, Serialize, Deserialize)]
 pub struct UnlockedPowerUps {
     pub powers: Vec<PowerUp>,
 }
@@ -153,3 +173,36 @@ pub struct PowerUpChoices {
     pub choices: Vec<PowerUp>,
     pub level: u32,
 }
+
+#[cfg(test)]
+mod tests {
+    use rand::{SeedableRng, rngs::StdRng};
+
+    use super::*;
+
+    #[test]
+    fn choices_come_from_the_catalog() {
+        let catalog = PowerUpCatalog::load(None);
+        assert_eq!(catalog.0.len(), 9);
+        assert_eq!(catalog.name(PowerUp::EagleEye), "Eagle Eye");
+        assert_eq!(catalog.description(PowerUp::EagleEye), "2x longer aim line");
+
+        let mut rng = StdRng::seed_from_u64(7);
+        let choices = catalog.random_choices(5, &[PowerUp::SpeedySnord], &mut rng);
+        assert_eq!(choices.len(), 3);
+        assert!(choices.iter().all(|p| catalog.get(*p).unwrap().tier == 1));
+        assert!(!choices.contains(&PowerUp::SpeedySnord));
+
+        // Only what's in the catalog is offered
+        let small = PowerUpCatalog(vec![catalog.get(PowerUp::ComboSnord).unwrap().clone()]);
+        assert_eq!(
+            small.random_choices(20, &[], &mut rng),
+            [PowerUp::ComboSnord]
+        );
+        assert!(
+            small
+                .random_choices(20, &[PowerUp::ComboSnord], &mut rng)
+                .is_empty()
+        );
+    }
+}
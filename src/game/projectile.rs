@@ -1,37 +1,61 @@
 //! Projectile - the bubble being shot.
 //!
 //! The projectile travels in a straight line, bouncing off walls,
-//! until it hits another bubble or the top of the grid.
+//! until it hits another bubble or the top of the grid. The side walls are
+//! the [`Walls`] resource rather than constants, since some levels move them.
+//! A wildcard shot (from Rainbow Snord) lands as whichever neighboring color
+//! it makes the biggest cluster with.
 
 use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
 
 use super::{
-    bubble::{BubbleColor, GameAssets, SNORD_SPRITE_SCALE, spawn_bubble},
-    grid::HexGrid,
-    hex::{GridOffset, HEX_SIZE, HexCoord},
+    bubble::{Bubble, BubbleColor, GameAssets, sprite_scale},
+    bubble_material::BubbleMaterial,
+    bumpers::{BumperHit, Bumpers, boosted},
+    clock::GameClock,
+    cluster::wildcard_color,
+    companion::extra_grace,
+    grid::{GridCommands, HexGrid},
+    hex::{GRID_ORIGIN_Y, HexCoord},
+    messages::AddGameMessage,
+    mode::GameMode,
+    patterns::color_symbol,
+    portals::Portals,
     powerups::{PowerUp, UnlockedPowerUps},
     shooter::SHOOTER_Y,
+    sticky_walls::StickyWalls,
+    weekly::ActiveChallenge,
 };
 
-use crate::{PausableSystems, audio::sound_effect, screens::Screen};
+use crate::{PausableSystems, audio::sound_effect, profile::Profile, screens::InGame};
 
 pub(super) fn plugin(app: &mut App) {
     app.register_type::<Projectile>();
-    app.add_message::<FireProjectile>();
-    app.add_message::<BubbleLanded>();
-    app.add_message::<BubbleInDangerZone>();
+    app.register_type::<DangerGrace>();
+    app.register_type::<Walls>();
+    app.init_resource::<DangerGrace>();
+    app.init_resource::<Walls>();
+    app.add_game_message::<FireProjectile>("The shooter fired a bubble");
+    app.add_game_message::<BubbleLanded>("A projectile snapped onto the grid");
+    app.add_game_message::<BubbleInDangerZone>("A bubble landed in the danger zone");
+    app.add_game_message::<GraceBounceUsed>("A danger zone landing was forgiven");
+
+    app.add_systems(OnEnter(InGame), (reset_danger_grace, reset_walls));
 
     app.add_systems(
         Update,
         (
             spawn_projectile,
             move_projectile,
-            check_wall_collision,
-            check_bubble_collision,
+            check_bumper_collision,
+            // Chained so a shot the walls landed is gone before it can land
+            // on a bubble too
+            (check_wall_collision, check_bubble_collision).chain(),
         )
             .in_set(PausableSystems)
             .in_set(ProjectileSystems)
-            .run_if(in_state(Screen::Gameplay)),
+            .run_if(in_state(InGame)),
     );
 }
 
@@ -39,6 +63,58 @@ pub(super) fn plugin(app: &mut App) {
 #[derive(Message, Debug, Clone)]
 pub struct BubbleInDangerZone;
 
+/// Message sent when a danger zone landing was forgiven by the grace bounce.
+#[derive(Message, Debug, Clone)]
+pub struct GraceBounceUsed {
+    /// Where the rejected bubble was when it bounced off.
+    pub position: Vec2,
+}
+
+/// Number of "last chance" bounces granted per run.
+pub const GRACE_BOUNCES_PER_RUN: u32 = 1;
+
+/// Resource tracking how many danger zone landings can still be forgiven this run.
+///
+/// The first violation rejects the offending bubble instead of ending the game;
+/// the next one is fatal.
+#[derive(Resource, Debug, Clone, PartialEq, Reflect, Serialize, Deserialize)]
+#[reflect(Resource, Serialize, Deserialize)]
+pub struct DangerGrace {
+    pub remaining: u32,
+}
+
+impl Default for DangerGrace {
+    fn default() -> Self {
+        Self {
+            remaining: GRACE_BOUNCES_PER_RUN,
+        }
+    }
+}
+
+impl DangerGrace {
+    /// Consume a grace bounce if one is left. Returns true if the landing was forgiven.
+    pub fn try_consume(&mut self) -> bool {
+        if self.remaining > 0 {
+            self.remaining -= 1;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+/// Component for a rejected projectile bouncing back down before it despawns.
+#[derive(Component, Debug, Clone)]
+pub struct RejectedBubble {
+    /// Current velocity (falls away from the grid).
+    pub velocity: Vec2,
+    /// Time elapsed since rejection.
+    pub timer: f32,
+}
+
+/// How long a rejected bubble stays visible before despawning.
+pub const REJECTED_BUBBLE_DURATION: f32 = 0.6;
+
 /// System set for projectile systems.
 #[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
 pub struct ProjectileSystems;
@@ -49,6 +125,8 @@ pub struct FireProjectile {
     pub position: Vec2,
     pub direction: Vec2,
     pub color: BubbleColor,
+    /// Matches any color (see [`PowerUp::RainbowSnord`]).
+    pub wildcard: bool,
 }
 
 /// Message sent when a bubble lands on the grid.
@@ -69,6 +147,8 @@ pub struct Projectile {
     pub velocity: Vec2,
     /// The bubble color
     pub color: BubbleColor,
+    /// Lands as whichever neighboring color it matches best.
+    pub wildcard: bool,
 }
 
 /// Speed of the projectile in pixels per second.
@@ -82,21 +162,125 @@ pub const LEFT_WALL: f32 = -245.0;
 /// For q=-6 to 6, odd rows extend to ~242px, walls at ±245 for margin.
 pub const RIGHT_WALL: f32 = 245.0;
 
+/// Where the side walls are right now. Starts at [`LEFT_WALL`] and
+/// [`RIGHT_WALL`] each run; campaign levels can move them inward.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Reflect)]
+#[reflect(Resource)]
+pub struct Walls {
+    pub left: f32,
+    pub right: f32,
+}
+
+impl Default for Walls {
+    fn default() -> Self {
+        Self {
+            left: LEFT_WALL,
+            right: RIGHT_WALL,
+        }
+    }
+}
+
+impl Walls {
+    /// Distance between the walls.
+    pub fn width(&self) -> f32 {
+        self.right - self.left
+    }
+}
+
+/// Which side wall something is on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Reflect)]
+pub enum WallSide {
+    Left,
+    Right,
+}
+
+impl WallSide {
+    /// Where this wall is now.
+    pub fn x(self, walls: Walls) -> f32 {
+        match self {
+            WallSide::Left => walls.left,
+            WallSide::Right => walls.right,
+        }
+    }
+
+    /// Which way is away from this wall, into the playfield.
+    pub fn inward(self) -> f32 {
+        match self {
+            WallSide::Left => 1.0,
+            WallSide::Right => -1.0,
+        }
+    }
+}
+
 /// Top wall Y position (where projectiles stop).
 pub const TOP_WALL: f32 = 280.0;
 
 /// Danger line Y position - bubbles landing below this trigger game over.
 pub const DANGER_LINE_Y: f32 = SHOOTER_Y + 40.0;
 
+/// Rows below the ceiling of the first row past the [`DANGER_LINE_Y`], for
+/// hexes of `hex_size`. A bubble that deep ends the run.
+pub fn danger_depth(hex_size: f32) -> i32 {
+    ((GRID_ORIGIN_Y - DANGER_LINE_Y) / (hex_size * 1.5)).floor() as i32 + 1
+}
+
+/// Reset the grace bounce counter when starting a new game, plus any the
+/// companion brings.
+pub(super) fn reset_danger_grace(mut grace: ResMut<DangerGrace>, profile: Res<Profile>) {
+    *grace = DangerGrace::default();
+    grace.remaining += extra_grace(profile.companion);
+}
+
+fn reset_walls(mut walls: ResMut<Walls>) {
+    *walls = Walls::default();
+}
+
+/// Handle a projectile that would land in the danger zone.
+///
+/// Uses up the run's grace bounce if available (the bubble bounces off and
+/// despawns), otherwise triggers game over.
+fn handle_danger_landing(
+    commands: &mut Commands,
+    grace: &mut DangerGrace,
+    danger_events: &mut MessageWriter<BubbleInDangerZone>,
+    grace_events: &mut MessageWriter<GraceBounceUsed>,
+    projectile_entity: Entity,
+    position: Vec2,
+    velocity: Vec2,
+) {
+    if grace.try_consume() {
+        info!(
+            "Last chance! Rejected bubble at y={} ({} grace bounces left)",
+            position.y, grace.remaining
+        );
+        // Bounce back down and away from the grid
+        let bounce_velocity = Vec2::new(velocity.x * 0.5, -velocity.y.abs() * 0.5);
+        commands
+            .entity(projectile_entity)
+            .remove::<Projectile>()
+            .insert(RejectedBubble {
+                velocity: bounce_velocity,
+                timer: 0.0,
+            });
+        grace_events.write(GraceBounceUsed { position });
+    } else {
+        danger_events.write(BubbleInDangerZone);
+        commands.entity(projectile_entity).despawn();
+    }
+}
+
 /// Spawn a projectile when the fire message is received.
 fn spawn_projectile(
     mut commands: Commands,
     mut meshes: ResMut<Assets<Mesh>>,
-    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut materials: ResMut<Assets<BubbleMaterial>>,
     mut fire_events: MessageReader<FireProjectile>,
     powerups: Res<UnlockedPowerUps>,
     game_assets: Res<GameAssets>,
     asset_server: Res<AssetServer>,
+    grid: Res<HexGrid>,
+    mode: Res<GameMode>,
+    challenge: Res<ActiveChallenge>,
 ) {
     for event in fire_events.read() {
         // Play launch sound
@@ -107,7 +291,8 @@ fn spawn_projectile(
             PROJECTILE_SPEED * 1.25
         } else {
             PROJECTILE_SPEED
-        };
+        } * mode.projectile_speed_scale()
+            * challenge.shot_speed_scale();
         let velocity = event.direction.normalize() * speed;
 
         // Check if this color uses a sprite
@@ -126,11 +311,17 @@ fn spawn_projectile(
                 Projectile {
                     velocity,
                     color: event.color,
+                    wildcard: event.wildcard,
                 },
                 Transform::from_translation(event.position.extend(5.0))
-                    .with_scale(Vec3::splat(SNORD_SPRITE_SCALE)),
+                    .with_scale(Vec3::splat(sprite_scale(grid.hex_size))),
                 Sprite::from_image(image),
-                DespawnOnExit(Screen::Gameplay),
+                DespawnOnExit(InGame),
+                children![color_symbol(
+                    event.color,
+                    grid.hex_size,
+                    sprite_scale(grid.hex_size)
+                )],
             ));
         } else {
             commands.spawn((
@@ -138,11 +329,13 @@ fn spawn_projectile(
                 Projectile {
                     velocity,
                     color: event.color,
+                    wildcard: event.wildcard,
                 },
                 Transform::from_translation(event.position.extend(5.0)),
-                Mesh2d(meshes.add(RegularPolygon::new(HEX_SIZE, 6))),
-                MeshMaterial2d(materials.add(ColorMaterial::from_color(event.color.to_color()))),
-                DespawnOnExit(Screen::Gameplay),
+                Mesh2d(meshes.add(RegularPolygon::new(grid.hex_size, 6))),
+                MeshMaterial2d(materials.add(BubbleMaterial::new(event.color.to_color()))),
+                DespawnOnExit(InGame),
+                children![color_symbol(event.color, grid.hex_size, 1.0)],
             ));
         }
 
@@ -154,54 +347,95 @@ fn spawn_projectile(
 }
 
 /// Move the projectile based on its velocity.
-fn move_projectile(time: Res<Time>, mut query: Query<(&mut Transform, &Projectile)>) {
+fn move_projectile(time: Res<Time<GameClock>>, mut query: Query<(&mut Transform, &Projectile)>) {
     for (mut transform, projectile) in &mut query {
         transform.translation += projectile.velocity.extend(0.0) * time.delta_secs();
     }
 }
 
+/// Knock projectiles off any bumper they touch.
+fn check_bumper_collision(
+    grid: Res<HexGrid>,
+    bumpers: Res<Bumpers>,
+    mut query: Query<(&mut Transform, &mut Projectile)>,
+    mut hit_events: MessageWriter<BumperHit>,
+) {
+    let radius = grid.hex_size * 0.9;
+    for (mut transform, mut projectile) in &mut query {
+        if let Some((index, pos, velocity)) = bumpers.deflect(
+            transform.translation.truncate(),
+            projectile.velocity,
+            radius,
+        ) {
+            transform.translation = pos.extend(transform.translation.z);
+            projectile.velocity = boosted(velocity);
+            hit_events.write(BumperHit { index });
+        }
+    }
+}
+
 /// Check for wall collisions and bounce.
 fn check_wall_collision(
     mut commands: Commands,
-    mut grid: ResMut<HexGrid>,
+    mut grid: GridCommands,
     mut meshes: ResMut<Assets<Mesh>>,
-    mut materials: ResMut<Assets<ColorMaterial>>,
-    mut query: Query<(Entity, &mut Transform, &mut Projectile)>,
+    mut materials: ResMut<Assets<BubbleMaterial>>,
+    mut query: Query<(Entity, &mut Transform, &mut Projectile), Without<Bubble>>,
     mut landed_events: MessageWriter<BubbleLanded>,
     mut danger_events: MessageWriter<BubbleInDangerZone>,
-    grid_offset: Res<GridOffset>,
+    mut grace_events: MessageWriter<GraceBounceUsed>,
+    mut grace: ResMut<DangerGrace>,
     game_assets: Res<GameAssets>,
+    walls: Res<Walls>,
+    portals: Res<Portals>,
+    sticky: Res<StickyWalls>,
 ) {
     for (entity, mut transform, mut projectile) in &mut query {
         let pos = transform.translation;
-        let radius = HEX_SIZE * 0.9;
-
-        // Left wall bounce
-        if pos.x - radius < LEFT_WALL {
-            transform.translation.x = LEFT_WALL + radius;
-            projectile.velocity.x = projectile.velocity.x.abs();
-        }
-
-        // Right wall bounce
-        if pos.x + radius > RIGHT_WALL {
-            transform.translation.x = RIGHT_WALL - radius;
-            projectile.velocity.x = -projectile.velocity.x.abs();
+        let radius = grid.hex_size * 0.9;
+
+        // Side walls: through a portal, stick, or bounce
+        let mut stuck = false;
+        match side_wall_contact(
+            pos.truncate(),
+            projectile.velocity,
+            radius,
+            *walls,
+            &portals,
+            &sticky,
+        ) {
+            WallContact::Clear => {}
+            WallContact::Portal(exit, velocity) => {
+                transform.translation = exit.extend(pos.z);
+                projectile.velocity = velocity;
+                continue;
+            }
+            WallContact::Stuck => stuck = true,
+            WallContact::Bounced(bounced, velocity) => {
+                transform.translation = bounced.extend(pos.z);
+                projectile.velocity = velocity;
+            }
         }
 
-        // Top wall - snap to grid
-        if pos.y + radius > TOP_WALL {
+        // Top wall or a sticky wall - snap to grid
+        if stuck || pos.y + radius > TOP_WALL {
             let world_pos = pos.truncate();
-            if let Some(coord) = grid.closest_empty_cell(world_pos, grid_offset.y) {
+            if let Some(coord) = grid.closest_empty_cell(world_pos, grid.origin_y()) {
                 // Check if landing position is in danger zone
-                let landing_y = coord.to_pixel_with_offset(HEX_SIZE, grid_offset.y).y;
+                let landing_y = grid.position(coord).y;
                 if landing_y < DANGER_LINE_Y {
-                    info!(
-                        "Bubble would land in danger zone at y={}, triggering game over",
-                        landing_y
+                    info!("Bubble would land in danger zone at y={}", landing_y);
+                    handle_danger_landing(
+                        &mut commands,
+                        &mut grace,
+                        &mut danger_events,
+                        &mut grace_events,
+                        entity,
+                        world_pos,
+                        projectile.velocity,
                     );
-                    danger_events.write(BubbleInDangerZone);
-                    commands.entity(entity).despawn();
                 } else {
+                    let color = landing_color(projectile.color, projectile.wildcard, coord, &grid);
                     let new_entity = land_projectile(
                         &mut commands,
                         &mut meshes,
@@ -209,13 +443,12 @@ fn check_wall_collision(
                         &mut grid,
                         entity,
                         coord,
-                        projectile.color,
-                        grid_offset.y,
+                        color,
                         &game_assets,
                     );
                     landed_events.write(BubbleLanded {
                         coord,
-                        color: projectile.color,
+                        color,
                         entity: new_entity,
                     });
                 }
@@ -236,41 +469,39 @@ fn check_wall_collision(
 /// Check for collision with existing bubbles on the grid.
 fn check_bubble_collision(
     mut commands: Commands,
-    mut grid: ResMut<HexGrid>,
+    mut grid: GridCommands,
     mut meshes: ResMut<Assets<Mesh>>,
-    mut materials: ResMut<Assets<ColorMaterial>>,
-    projectile_query: Query<(Entity, &Transform, &Projectile)>,
-    bubble_query: Query<&Transform, Without<Projectile>>,
+    mut materials: ResMut<Assets<BubbleMaterial>>,
+    projectile_query: Query<(Entity, &Transform, &Projectile), Without<Bubble>>,
     mut landed_events: MessageWriter<BubbleLanded>,
     mut danger_events: MessageWriter<BubbleInDangerZone>,
-    grid_offset: Res<GridOffset>,
+    mut grace_events: MessageWriter<GraceBounceUsed>,
+    mut grace: ResMut<DangerGrace>,
     powerups: Res<UnlockedPowerUps>,
     game_assets: Res<GameAssets>,
+    mode: Res<GameMode>,
 ) {
-    // Sharpshooter reduces collision distance for more precise shots
-    let collision_distance = if powerups.has(PowerUp::Sharpshooter) {
-        HEX_SIZE * 1.5 // Tighter hitbox
-    } else {
-        HEX_SIZE * 1.8 // Default: slightly less than 2 radii
-    };
+    let collision_distance = collision_distance(&grid, &powerups, *mode);
 
     // First pass: find collisions (without borrowing grid mutably)
-    let mut collision: Option<(Entity, Vec2, BubbleColor)> = None;
+    let mut collision: Option<(Entity, Vec2, Vec2, BubbleColor, bool)> = None;
 
     for (proj_entity, proj_transform, projectile) in &projectile_query {
         let proj_pos = proj_transform.translation.truncate();
 
         // Check against all grid bubbles
-        for (_coord, &bubble_entity) in grid.iter() {
-            let Ok(bubble_transform) = bubble_query.get(bubble_entity) else {
-                continue;
-            };
-
-            let bubble_pos = bubble_transform.translation.truncate();
+        for coord in grid.coords() {
+            let bubble_pos = grid.position(coord);
             let distance = proj_pos.distance(bubble_pos);
 
             if distance < collision_distance {
-                collision = Some((proj_entity, proj_pos, projectile.color));
+                collision = Some((
+                    proj_entity,
+                    proj_pos,
+                    projectile.velocity,
+                    projectile.color,
+                    projectile.wildcard,
+                ));
                 break;
             }
         }
@@ -281,20 +512,25 @@ fn check_bubble_collision(
     }
 
     // Second pass: handle the collision (now we can borrow grid mutably)
-    if let Some((proj_entity, proj_pos, color)) = collision {
+    if let Some((proj_entity, proj_pos, velocity, color, wildcard)) = collision {
         // Check if projectile position at collision time is in danger zone
         // This must happen BEFORE pathfinding, since pathfinding can find cells above
         if proj_pos.y < DANGER_LINE_Y {
-            info!(
-                "Projectile collided in danger zone at y={}, triggering game over",
-                proj_pos.y
+            info!("Projectile collided in danger zone at y={}", proj_pos.y);
+            handle_danger_landing(
+                &mut commands,
+                &mut grace,
+                &mut danger_events,
+                &mut grace_events,
+                proj_entity,
+                proj_pos,
+                velocity,
             );
-            danger_events.write(BubbleInDangerZone);
-            commands.entity(proj_entity).despawn();
             return;
         }
 
-        if let Some(snap_coord) = grid.closest_empty_cell(proj_pos, grid_offset.y) {
+        if let Some(snap_coord) = grid.closest_empty_cell(proj_pos, grid.origin_y()) {
+            let color = landing_color(color, wildcard, snap_coord, &grid);
             let new_entity = land_projectile(
                 &mut commands,
                 &mut meshes,
@@ -303,7 +539,6 @@ fn check_bubble_collision(
                 proj_entity,
                 snap_coord,
                 color,
-                grid_offset.y,
                 &game_assets,
             );
             landed_events.write(BubbleLanded {
@@ -318,34 +553,325 @@ fn check_bubble_collision(
     }
 }
 
+/// How close a projectile gets to a grid bubble before it sticks.
+pub fn collision_distance(grid: &HexGrid, powerups: &UnlockedPowerUps, mode: GameMode) -> f32 {
+    // Sharpshooter reduces collision distance for more precise shots
+    if powerups.has(PowerUp::Sharpshooter) {
+        grid.hex_size * 1.5 // Tighter hitbox
+    } else {
+        grid.hex_size * mode.collision_reach()
+    }
+}
+
+/// Where a shot would end up, from [`predict_landing`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PredictedLanding {
+    /// The cell the bubble would snap to.
+    pub coord: HexCoord,
+    /// The shot would stop below the danger line.
+    pub in_danger: bool,
+}
+
+/// Distance the prediction moves per step, as a fraction of the hex size.
+const PREDICTION_STEP: f32 = 0.25;
+
+/// Distance a prediction follows a shot before giving up, for shots a bumper
+/// sends bouncing between the walls.
+pub const MAX_PREDICTION_DISTANCE: f32 = 4000.0;
+
+/// What the side walls do to a shot touching one.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum WallContact {
+    /// Clear of both walls.
+    Clear,
+    /// Through a portal: where the shot comes out, and its new velocity.
+    Portal(Vec2, Vec2),
+    /// Held by a sticky wall.
+    Stuck,
+    /// Pushed back in off the wall, with its velocity mirrored.
+    Bounced(Vec2, Vec2),
+}
+
+/// What the side walls do to a shot of `radius` at `pos`. Shared by the
+/// projectile and everything that follows a shot ahead of time.
+pub fn side_wall_contact(
+    pos: Vec2,
+    velocity: Vec2,
+    radius: f32,
+    walls: Walls,
+    portals: &Portals,
+    sticky: &StickyWalls,
+) -> WallContact {
+    let side = if pos.x - radius < walls.left {
+        WallSide::Left
+    } else if pos.x + radius > walls.right {
+        WallSide::Right
+    } else {
+        return WallContact::Clear;
+    };
+    if let Some((exit, velocity)) = portals.teleport(side, pos, velocity, walls, radius) {
+        return WallContact::Portal(exit, velocity);
+    }
+    if sticky.holds(side, pos.y) {
+        return WallContact::Stuck;
+    }
+    let x = side.x(walls) + side.inward() * radius;
+    let vx = velocity.x.abs() * side.inward();
+    WallContact::Bounced(Vec2::new(x, pos.y), Vec2::new(vx, velocity.y))
+}
+
+/// Why a traced shot stopped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShotStop {
+    /// Touching a grid bubble.
+    Bubble,
+    /// At the top wall, or held by a sticky wall.
+    Wall,
+    /// Knocked back down past the shooter, or still going at the distance
+    /// limit.
+    Lost,
+}
+
+/// A shot's path, from [`trace_shot`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ShotPath {
+    /// Straight runs, start to end. A new one starts at every bounce and
+    /// portal.
+    pub segments: Vec<(Vec2, Vec2)>,
+    /// Where the shot stopped.
+    pub end: Vec2,
+    pub stop: ShotStop,
+}
+
+/// Follow a shot from `start` along `direction` for up to `max_distance`,
+/// bouncing off the side walls and bumpers and going through portals like a
+/// projectile, until it touches a bubble, the top wall, or a sticky wall.
+pub fn trace_shot(
+    grid: &HexGrid,
+    grid_origin_y: f32,
+    walls: Walls,
+    portals: &Portals,
+    sticky: &StickyWalls,
+    bumpers: &Bumpers,
+    start: Vec2,
+    direction: Vec2,
+    collision_distance: f32,
+    max_distance: f32,
+) -> ShotPath {
+    let radius = grid.hex_size * 0.9;
+    let step = grid.hex_size * PREDICTION_STEP;
+    let mut direction = direction.normalize_or(Vec2::Y);
+    let mut pos = start;
+    let mut segments = Vec::new();
+    let mut segment_start = start;
+    let mut stop = ShotStop::Lost;
+
+    for _ in 0..(max_distance / step) as usize {
+        pos += direction * step;
+        if let Some((_, bumped, reflected)) = bumpers.deflect(pos, direction, radius) {
+            segments.push((segment_start, bumped));
+            segment_start = bumped;
+            pos = bumped;
+            direction = reflected;
+        }
+        // Knocked back down past the shooter, where the projectile despawns
+        if pos.y < SHOOTER_Y - 50.0 {
+            break;
+        }
+        match side_wall_contact(pos, direction, radius, walls, portals, sticky) {
+            WallContact::Clear => {}
+            WallContact::Portal(exit, exit_direction) => {
+                segments.push((segment_start, pos));
+                segment_start = exit;
+                pos = exit;
+                direction = exit_direction;
+                continue;
+            }
+            WallContact::Stuck => {
+                stop = ShotStop::Wall;
+                break;
+            }
+            WallContact::Bounced(bounced, bounced_direction) => {
+                segments.push((segment_start, bounced));
+                segment_start = bounced;
+                pos = bounced;
+                direction = bounced_direction;
+            }
+        }
+
+        let hit_bubble = grid.coords().any(|coord| {
+            let bubble_pos = coord.to_pixel_with_offset(grid.hex_size, grid_origin_y);
+            pos.distance(bubble_pos) < collision_distance
+        });
+        if hit_bubble {
+            stop = ShotStop::Bubble;
+            break;
+        }
+        if pos.y + radius > TOP_WALL {
+            stop = ShotStop::Wall;
+            break;
+        }
+    }
+    segments.push((segment_start, pos));
+
+    ShotPath {
+        segments,
+        end: pos,
+        stop,
+    }
+}
+
+/// Where a shot from `start` along `direction` would land, following it with
+/// [`trace_shot`].
+///
+/// Mirrors the collision systems, so the landing cell matches where the real
+/// shot would stick. Returns None if there's no free cell to snap to, or the
+/// shot never lands.
+pub fn predict_landing(
+    grid: &HexGrid,
+    grid_origin_y: f32,
+    walls: Walls,
+    portals: &Portals,
+    sticky: &StickyWalls,
+    bumpers: &Bumpers,
+    start: Vec2,
+    direction: Vec2,
+    collision_distance: f32,
+) -> Option<PredictedLanding> {
+    if direction.normalize_or(Vec2::Y).y <= 0.0 {
+        return None;
+    }
+    let path = trace_shot(
+        grid,
+        grid_origin_y,
+        walls,
+        portals,
+        sticky,
+        bumpers,
+        start,
+        direction,
+        collision_distance,
+        MAX_PREDICTION_DISTANCE,
+    );
+    if path.stop == ShotStop::Lost {
+        return None;
+    }
+
+    let coord = grid.closest_empty_cell(path.end, grid_origin_y)?;
+    let in_danger = if path.stop == ShotStop::Bubble {
+        path.end.y < DANGER_LINE_Y
+    } else {
+        coord.to_pixel_with_offset(grid.hex_size, grid_origin_y).y < DANGER_LINE_Y
+    };
+    Some(PredictedLanding { coord, in_danger })
+}
+
 /// Convert a projectile into a grid bubble.
+/// The color a shot lands as at `coord`: its own, or for a wildcard, the
+/// neighboring color it makes the biggest cluster with.
+fn landing_color(
+    color: BubbleColor,
+    wildcard: bool,
+    coord: HexCoord,
+    grid: &GridCommands,
+) -> BubbleColor {
+    if !wildcard {
+        return color;
+    }
+    wildcard_color(&grid.layout, coord, |coord| grid.color(coord)).unwrap_or(color)
+}
+
 fn land_projectile(
     commands: &mut Commands,
     meshes: &mut Assets<Mesh>,
-    materials: &mut Assets<ColorMaterial>,
-    grid: &mut ResMut<HexGrid>,
+    materials: &mut Assets<BubbleMaterial>,
+    grid: &mut GridCommands,
     projectile_entity: Entity,
     coord: HexCoord,
     color: BubbleColor,
-    grid_origin_y: f32,
     game_assets: &GameAssets,
 ) -> Entity {
     // Despawn the projectile
     commands.entity(projectile_entity).despawn();
 
     // Spawn a new bubble at the grid position
-    let new_entity = spawn_bubble(
-        commands,
-        meshes,
-        materials,
-        coord,
-        color,
-        grid_origin_y,
-        Some(game_assets),
-    );
-    grid.insert(coord, new_entity);
+    let new_entity = grid.spawn(commands, meshes, materials, coord, color, Some(game_assets));
 
     info!("Bubble landed at {} with color {:?}", coord, color);
 
     new_entity
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::{hex::GRID_ORIGIN_Y, sticky_walls::StickyZone};
+
+    fn trace_empty_board(direction: Vec2, max_distance: f32) -> ShotPath {
+        let grid = HexGrid::new();
+        trace_shot(
+            &grid,
+            GRID_ORIGIN_Y,
+            Walls::default(),
+            &Portals::default(),
+            &StickyWalls::default(),
+            &Bumpers::default(),
+            Vec2::new(0.0, SHOOTER_Y),
+            direction,
+            grid.hex_size * 2.0,
+            max_distance,
+        )
+    }
+
+    #[test]
+    fn traced_shots_bounce_off_the_walls_up_to_the_top() {
+        let path = trace_empty_board(Vec2::new(1.0, 1.0), MAX_PREDICTION_DISTANCE);
+        assert_eq!(path.stop, ShotStop::Wall);
+        assert!(path.segments.len() > 1, "{path:?}");
+        // The first run ends pushed back in off the right wall
+        let radius = HexGrid::new().hex_size * 0.9;
+        assert!((path.segments[0].1.x - (RIGHT_WALL - radius)).abs() < 1e-3);
+        assert!(path.end.y + radius > TOP_WALL);
+    }
+
+    #[test]
+    fn traced_shots_stop_at_the_distance_limit() {
+        let path = trace_empty_board(Vec2::Y, 100.0);
+        assert_eq!(path.stop, ShotStop::Lost);
+        assert_eq!(path.segments.len(), 1);
+        assert!(path.end.distance(Vec2::new(0.0, SHOOTER_Y)) <= 100.0);
+    }
+
+    #[test]
+    fn sticky_walls_hold_instead_of_bouncing() {
+        let pos = Vec2::new(LEFT_WALL, 100.0);
+        let velocity = Vec2::new(-1.0, 1.0);
+        let bounced = side_wall_contact(
+            pos,
+            velocity,
+            10.0,
+            Walls::default(),
+            &Portals::default(),
+            &StickyWalls::default(),
+        );
+        assert_eq!(
+            bounced,
+            WallContact::Bounced(Vec2::new(LEFT_WALL + 10.0, 100.0), Vec2::new(1.0, 1.0))
+        );
+
+        let sticky = StickyWalls(vec![StickyZone {
+            side: WallSide::Left,
+            top: 150.0,
+            bottom: 50.0,
+        }]);
+        let held = side_wall_contact(
+            pos,
+            velocity,
+            10.0,
+            Walls::default(),
+            &Portals::default(),
+            &sticky,
+        );
+        assert_eq!(held, WallContact::Stuck);
+    }
+}
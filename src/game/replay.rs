@@ -0,0 +1,576 @@
+//! Recording runs and watching them back.
+//!
+//! Every run from a seeded board is recorded into a [`ReplayLog`]: the seed,
+//! the mode, companion, handicap, and weekly challenge it was played with,
+//! and each [`FireProjectile`] with the time it was fired. Power-up picks
+//! are kept too, since no one is there to make them when it plays back. When the run leaves gameplay the
+//! log is written to `replay.json` as the [`LastReplay`].
+//!
+//! [`Screen::ReplayViewer`] plays the last replay back. The seed brings back
+//! the starting board, and through `GameRng` the same queue and new rows,
+//! so firing the same shots in the same order plays out the same run. Each
+//! shot waits for the shooter to be ready and for its recorded time, so the
+//! replay keeps the run's pace. It stops where the run first ended, so
+//! continues aren't played back, and runs resumed from an autosave aren't
+//! recorded since their board didn't come from the seed.
+//!
+//! The game over menu can also play back just the run's [`FINAL_SHOTS`]
+//! shots, slowed down. As each shot is fired, a [`GameSnapshot`] of the run
+//! and its `GameRng` are kept in [`FinalShots`]; the viewer restores the
+//! oldest one instead of starting from the seed, and plays the shots from
+//! there at half speed, with the danger line lit up and the bubble that
+//! ended the run circled once it does.
+
+use std::collections::VecDeque;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    bubble::BubbleColor,
+    clock::GameClock,
+    companion::Companion,
+    grid::HexGrid,
+    handicap::{Handicap, RunHandicap},
+    hex::GridOffset,
+    history::{RetrySeed, RunSeed},
+    messages::AddGameMessage,
+    mode::GameMode,
+    powerups::{PowerUp, UnlockedPowerUps},
+    projectile::{DANGER_LINE_Y, FireProjectile, LEFT_WALL, Projectile, RIGHT_WALL},
+    rng::GameRng,
+    shooter::{AimDirection, Shooter, ShooterState},
+    snapshot::GameSnapshot,
+    state::GameLevel,
+    weekly::{ActiveChallenge, WeeklyChallenge},
+};
+use crate::{
+    PausableSystems,
+    asset_tracking::ResourceHandles,
+    menus::Menu,
+    profile::Profile,
+    save::SaveFile,
+    screens::{RunPhase, Screen},
+    theme::toast::ShowToast,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<ReplayLog>();
+    app.init_resource::<LastReplay>();
+    app.init_resource::<ReplayPlayback>();
+    app.init_resource::<RunStart>();
+    app.init_resource::<FinalShots>();
+    app.add_game_message::<WatchReplay>("The player asked to watch the last replay");
+
+    app.add_systems(Startup, load_last_replay);
+    app.add_systems(OnEnter(RunPhase::Setup), mark_run_start);
+
+    // Recording
+    app.add_systems(
+        OnEnter(RunPhase::Setup),
+        start_recording
+            .after(mark_run_start)
+            .run_if(in_state(Screen::Gameplay)),
+    );
+    app.add_systems(
+        Update,
+        (
+            record_shots,
+            record_picks.run_if(resource_changed::<UnlockedPowerUps>),
+            checkpoint_shot
+                .after(record_shots)
+                .run_if(on_message::<FireProjectile>),
+        )
+            .run_if(in_state(Screen::Gameplay)),
+    );
+    app.add_systems(OnExit(Screen::Gameplay), keep_last_replay);
+
+    // Playback
+    app.add_systems(
+        Update,
+        start_watching.run_if(in_state(Screen::Title).and(on_message::<WatchReplay>)),
+    );
+    app.add_systems(
+        OnEnter(RunPhase::Setup),
+        (start_playback, rewind_playback.run_if(rewound))
+            .chain()
+            .after(mark_run_start)
+            .run_if(in_state(Screen::ReplayViewer)),
+    );
+    app.add_systems(
+        OnEnter(Screen::ReplayViewer),
+        spawn_danger_highlight.run_if(rewound),
+    );
+    app.add_systems(
+        Update,
+        (
+            play_replay_shot.in_set(PausableSystems),
+            (answer_replay_menus, finish_replay)
+                .chain()
+                .after(PausableSystems),
+            flag_fatal_bubble.run_if(rewound),
+        )
+            .run_if(in_state(Screen::ReplayViewer)),
+    );
+    app.add_systems(OnExit(Screen::ReplayViewer), restore_player_choices);
+}
+
+/// Game clock seconds the viewer lingers after the last shot before closing,
+/// if the run didn't end on its own.
+const LINGER_SECS: f32 = 3.0;
+
+/// Shots the game over menu plays back.
+pub const FINAL_SHOTS: usize = 10;
+
+/// How fast the final shots play, relative to the run.
+const FINAL_SHOTS_SPEED: f32 = 0.5;
+
+/// Game clock seconds the rewound board sits still before its first shot.
+const REWIND_LEAD_SECS: f32 = 1.0;
+
+/// Color of the danger line while the final shots play.
+const DANGER_HIGHLIGHT: Color = Color::srgba(0.9, 0.15, 0.1, 0.55);
+
+/// Color of the circle around the bubble that ended the run.
+const FATAL_FLAG: Color = Color::srgb(0.9, 0.1, 0.1);
+
+/// A recorded run.
+#[derive(Resource, Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ReplayLog {
+    /// Seed the run was played from. Runs without one aren't recorded.
+    pub seed: Option<u64>,
+    pub mode: GameMode,
+    pub companion: Option<Companion>,
+    #[serde(default)]
+    pub handicap: Handicap,
+    /// The weekly challenge the run was part of.
+    #[serde(default)]
+    pub challenge: Option<WeeklyChallenge>,
+    /// Power-ups the run started with (a companion can bring one).
+    pub starting_powers: Vec<PowerUp>,
+    pub shots: Vec<ReplayShot>,
+    /// Power-ups picked at milestones, in order.
+    pub picks: Vec<PowerUp>,
+}
+
+/// One shot of a recorded run.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReplayShot {
+    /// Game clock seconds since the run started.
+    pub secs: f32,
+    pub position: [f32; 2],
+    pub direction: [f32; 2],
+    pub color: BubbleColor,
+    #[serde(default)]
+    pub wildcard: bool,
+}
+
+impl From<&ReplayShot> for FireProjectile {
+    fn from(shot: &ReplayShot) -> Self {
+        FireProjectile {
+            position: Vec2::from_array(shot.position),
+            direction: Vec2::from_array(shot.direction),
+            color: shot.color,
+            wildcard: shot.wildcard,
+        }
+    }
+}
+
+/// The last recorded run, mirrored in `replay.json`.
+#[derive(Resource, Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LastReplay {
+    pub log: Option<ReplayLog>,
+}
+
+impl SaveFile for LastReplay {
+    const FILE_NAME: &'static str = "replay.json";
+    const DESCRIPTION: &'static str = "replay";
+}
+
+/// The run just before one of its shots, to play back from.
+#[derive(Debug, Clone)]
+pub struct ShotCheckpoint {
+    /// Index of the shot in the run's [`ReplayLog`].
+    shot: usize,
+    snapshot: GameSnapshot,
+    rng: GameRng,
+}
+
+/// Checkpoints before the last [`FINAL_SHOTS`] shots of the run in play,
+/// oldest first.
+#[derive(Resource, Debug, Default)]
+pub struct FinalShots {
+    checkpoints: VecDeque<ShotCheckpoint>,
+    /// Whether the next [`WatchReplay`] is for these shots.
+    requested: bool,
+}
+
+impl FinalShots {
+    /// Whether there are shots to play back.
+    pub fn is_empty(&self) -> bool {
+        self.checkpoints.is_empty()
+    }
+
+    /// Play back these shots instead of the whole run on the next
+    /// [`WatchReplay`].
+    pub fn request(&mut self) {
+        self.requested = true;
+    }
+}
+
+/// Message asking to watch the [`LastReplay`] from the title screen.
+#[derive(Message, Debug, Clone)]
+pub struct WatchReplay;
+
+/// Where the viewer is in the replay it's playing.
+#[derive(Resource, Debug, Default)]
+pub struct ReplayPlayback {
+    pub log: ReplayLog,
+    pub next_shot: usize,
+    next_pick: usize,
+    /// The player's own companion and handicap, put back when the viewer
+    /// closes.
+    player_companion: Option<Companion>,
+    player_handicap: Handicap,
+    /// Where to pick the run up, when only its final shots are played.
+    rewind: Option<ShotCheckpoint>,
+    /// Real seconds the run ended at, when it's lingering on its end.
+    ended_at: Option<f32>,
+}
+
+impl ReplayPlayback {
+    /// Whether every shot has been fired.
+    pub fn is_done(&self) -> bool {
+        self.next_shot >= self.log.shots.len()
+    }
+
+    /// Whether only the run's final shots are playing.
+    pub fn is_rewound(&self) -> bool {
+        self.rewind.is_some()
+    }
+}
+
+fn rewound(playback: Res<ReplayPlayback>) -> bool {
+    playback.is_rewound()
+}
+
+/// Game clock time the run in play started at, which shot times count from.
+#[derive(Resource, Debug, Default)]
+struct RunStart(f32);
+
+impl RunStart {
+    /// Game clock seconds since the run started.
+    fn secs(&self, time: &Time<GameClock>) -> f32 {
+        time.elapsed_secs() - self.0
+    }
+}
+
+fn load_last_replay(mut last: ResMut<LastReplay>) {
+    *last = LastReplay::load();
+}
+
+fn mark_run_start(time: Res<Time<GameClock>>, mut start: ResMut<RunStart>) {
+    start.0 = time.elapsed_secs();
+}
+
+fn start_recording(
+    seed: Res<RunSeed>,
+    mode: Res<GameMode>,
+    profile: Res<Profile>,
+    powerups: Res<UnlockedPowerUps>,
+    handicap: Res<RunHandicap>,
+    challenge: Res<ActiveChallenge>,
+    mut log: ResMut<ReplayLog>,
+    mut final_shots: ResMut<FinalShots>,
+) {
+    *final_shots = FinalShots::default();
+    *log = ReplayLog {
+        seed: seed.0,
+        mode: *mode,
+        companion: profile.companion,
+        handicap: handicap.handicap,
+        challenge: challenge.0,
+        starting_powers: powerups.powers.clone(),
+        shots: Vec::new(),
+        picks: Vec::new(),
+    };
+}
+
+fn record_shots(
+    time: Res<Time<GameClock>>,
+    start: Res<RunStart>,
+    mut log: ResMut<ReplayLog>,
+    mut fire_events: MessageReader<FireProjectile>,
+) {
+    let secs = start.secs(&time);
+    for shot in fire_events.read() {
+        log.shots.push(ReplayShot {
+            secs,
+            position: shot.position.to_array(),
+            direction: shot.direction.to_array(),
+            color: shot.color,
+            wildcard: shot.wildcard,
+        });
+    }
+}
+
+/// Keep the run as it was when the shot just recorded was fired.
+fn checkpoint_shot(world: &mut World) {
+    let log = world.resource::<ReplayLog>();
+    if log.seed.is_none() {
+        return;
+    }
+    let shot = log.shots.len().saturating_sub(1);
+    let mut snapshot = GameSnapshot::capture(world);
+    // Firing already counted the shot, and playing it back counts it again
+    snapshot.level.shots_this_round = snapshot.level.shots_this_round.saturating_sub(1);
+    let rng = world.resource::<GameRng>().clone();
+
+    let checkpoints = &mut world.resource_mut::<FinalShots>().checkpoints;
+    checkpoints.push_back(ShotCheckpoint {
+        shot,
+        snapshot,
+        rng,
+    });
+    while checkpoints.len() > FINAL_SHOTS {
+        checkpoints.pop_front();
+    }
+}
+
+/// Note power-ups added since the run started.
+fn record_picks(powerups: Res<UnlockedPowerUps>, mut log: ResMut<ReplayLog>) {
+    let known = log.starting_powers.len() + log.picks.len();
+    if let Some(new) = powerups.powers.get(known..) {
+        log.picks.extend_from_slice(new);
+    }
+}
+
+fn keep_last_replay(log: Res<ReplayLog>, mut last: ResMut<LastReplay>) {
+    if log.seed.is_none() || log.shots.is_empty() {
+        return;
+    }
+    last.log = Some(log.clone());
+    last.save();
+}
+
+/// Put back the recorded run's seed, mode, companion, and handicap, then
+/// open the viewer.
+fn start_watching(
+    last: Res<LastReplay>,
+    resource_handles: Res<ResourceHandles>,
+    mut final_shots: ResMut<FinalShots>,
+    mut playback: ResMut<ReplayPlayback>,
+    mut retry: ResMut<RetrySeed>,
+    mut mode: ResMut<GameMode>,
+    mut profile: ResMut<Profile>,
+    mut handicap: ResMut<Handicap>,
+    mut toasts: MessageWriter<ShowToast>,
+    mut next_screen: ResMut<NextState<Screen>>,
+) {
+    let Some(log) = last.log.clone() else {
+        return;
+    };
+    // The viewer plays without a loading screen, like the demo
+    if !resource_handles.is_all_done() {
+        toasts.write(ShowToast::info("Still loading, try again in a moment"));
+        return;
+    }
+    let rewind = if std::mem::take(&mut final_shots.requested) {
+        final_shots.checkpoints.front().cloned()
+    } else {
+        None
+    };
+    match &rewind {
+        Some(checkpoint) => info!(
+            "Watching the final {} shots of {}",
+            log.shots.len() - checkpoint.shot,
+            log.shots.len()
+        ),
+        None => info!("Watching replay of {} shots", log.shots.len()),
+    }
+    retry.0 = log.seed;
+    *mode = log.mode;
+    *playback = ReplayPlayback {
+        player_companion: std::mem::replace(&mut profile.companion, log.companion),
+        player_handicap: std::mem::replace(&mut *handicap, log.handicap),
+        log,
+        rewind,
+        ..default()
+    };
+    next_screen.set(Screen::ReplayViewer);
+}
+
+fn start_playback(mut playback: ResMut<ReplayPlayback>, mut powerups: ResMut<UnlockedPowerUps>) {
+    playback.next_shot = 0;
+    playback.next_pick = 0;
+    powerups.powers = playback.log.starting_powers.clone();
+}
+
+/// Swap the seeded board for the checkpoint the final shots play from, and
+/// slow the game down.
+fn rewind_playback(world: &mut World) {
+    let Some(checkpoint) = world.resource::<ReplayPlayback>().rewind.clone() else {
+        return;
+    };
+    checkpoint.snapshot.restore(world);
+    world.insert_resource(checkpoint.rng);
+    // Picks already made are in the snapshot's power-ups
+    world.resource_mut::<RunHandicap>().skip_picks();
+
+    let mut playback = world.resource_mut::<ReplayPlayback>();
+    playback.next_shot = checkpoint.shot;
+    playback.next_pick = checkpoint
+        .snapshot
+        .powerups
+        .powers
+        .len()
+        .saturating_sub(playback.log.starting_powers.len());
+    let first_secs = playback
+        .log
+        .shots
+        .get(checkpoint.shot)
+        .map_or(0.0, |shot| shot.secs);
+
+    // Count shot times as if the run had got to the checkpoint just now
+    world.resource_mut::<RunStart>().0 -= first_secs - REWIND_LEAD_SECS;
+    world
+        .resource_mut::<Time<Virtual>>()
+        .set_relative_speed(FINAL_SHOTS_SPEED);
+}
+
+fn spawn_danger_highlight(mut commands: Commands) {
+    commands.spawn((
+        Name::new("Danger Highlight"),
+        Sprite::from_color(DANGER_HIGHLIGHT, Vec2::new(RIGHT_WALL - LEFT_WALL, 6.0)),
+        Transform::from_xyz((LEFT_WALL + RIGHT_WALL) / 2.0, DANGER_LINE_Y, 0.5),
+        DespawnOnExit(Screen::ReplayViewer),
+    ));
+}
+
+/// Circle the bubble that ended the run: the lowest one past the danger
+/// line, or else the last shot, which tried to land there.
+fn flag_fatal_bubble(
+    mut gizmos: Gizmos,
+    playback: Res<ReplayPlayback>,
+    grid: Res<HexGrid>,
+    grid_offset: Res<GridOffset>,
+    projectiles: Query<&Transform, With<Projectile>>,
+    mut last_shot_at: Local<Option<Vec2>>,
+) {
+    if let Some(transform) = projectiles.iter().next() {
+        *last_shot_at = Some(transform.translation.truncate());
+    }
+    if playback.ended_at.is_none() {
+        return;
+    }
+    let lowest = grid
+        .coords()
+        .map(|coord| coord.to_pixel_with_offset(grid.hex_size, grid_offset.y))
+        .filter(|position| position.y < DANGER_LINE_Y)
+        .min_by(|a, b| a.y.total_cmp(&b.y));
+    let Some(fatal) = lowest.or(*last_shot_at) else {
+        return;
+    };
+    gizmos.circle_2d(fatal, grid.hex_size * 1.4, FATAL_FLAG);
+    gizmos.circle_2d(fatal, grid.hex_size * 1.6, FATAL_FLAG);
+}
+
+/// Fire the next shot once the shooter is ready and its time has come.
+fn play_replay_shot(
+    time: Res<Time<GameClock>>,
+    start: Res<RunStart>,
+    mut playback: ResMut<ReplayPlayback>,
+    mut shooter_query: Query<(&mut AimDirection, &mut ShooterState), With<Shooter>>,
+    projectiles: Query<(), With<Projectile>>,
+    mut fire_events: MessageWriter<FireProjectile>,
+    mut level: ResMut<GameLevel>,
+) {
+    let Some(shot) = playback.log.shots.get(playback.next_shot) else {
+        return;
+    };
+    let Ok((mut aim, mut state)) = shooter_query.single_mut() else {
+        return;
+    };
+    if *state != ShooterState::Ready || !projectiles.is_empty() || start.secs(&time) < shot.secs {
+        return;
+    }
+
+    let shot = FireProjectile::from(shot);
+    aim.0 = shot.direction;
+    fire_events.write(shot);
+    *state = ShooterState::Reloading;
+    level.shots_this_round += 1;
+    playback.next_shot += 1;
+}
+
+/// Make the recorded power-up pick when the run offers one.
+fn answer_replay_menus(
+    mut playback: ResMut<ReplayPlayback>,
+    mut powerups: ResMut<UnlockedPowerUps>,
+    mut next_menu: ResMut<NextState<Menu>>,
+    mut next_phase: ResMut<NextState<RunPhase>>,
+) {
+    if !matches!(*next_menu, NextState::Pending(Menu::PowerUpSelect)) {
+        return;
+    }
+    let Some(&pick) = playback.log.picks.get(playback.next_pick) else {
+        // Left to the finish below
+        return;
+    };
+    playback.next_pick += 1;
+    powerups.add(pick);
+    next_menu.reset();
+    next_phase.set(RunPhase::Playing);
+}
+
+/// Close the viewer once the run ends, or a little after the last shot if it
+/// never did. The final shots linger on the end first, so the bubble that
+/// ended the run can be seen.
+fn finish_replay(
+    time: Res<Time<GameClock>>,
+    real_time: Res<Time<Real>>,
+    start: Res<RunStart>,
+    mut playback: ResMut<ReplayPlayback>,
+    projectiles: Query<(), With<Projectile>>,
+    mut next_menu: ResMut<NextState<Menu>>,
+    mut next_screen: ResMut<NextState<Screen>>,
+    mut toasts: MessageWriter<ShowToast>,
+) {
+    let mut ended = matches!(*next_menu, NextState::Pending(menu) if menu != Menu::None);
+    if playback.is_rewound() {
+        let now = real_time.elapsed_secs();
+        if ended {
+            // Keep the board showing instead of the run's menu
+            next_menu.reset();
+            playback.ended_at.get_or_insert(now);
+        }
+        ended = playback
+            .ended_at
+            .is_some_and(|ended_at| now > ended_at + LINGER_SECS);
+    }
+    let lingered = playback.is_done()
+        && projectiles.is_empty()
+        && playback
+            .log
+            .shots
+            .last()
+            .is_none_or(|shot| start.secs(&time) > shot.secs + LINGER_SECS);
+    if !ended && !lingered {
+        return;
+    }
+    info!("Replay finished");
+    toasts.write(ShowToast::info("Replay finished"));
+    next_menu.reset();
+    next_screen.set(Screen::Title);
+}
+
+fn restore_player_choices(
+    playback: Res<ReplayPlayback>,
+    mut profile: ResMut<Profile>,
+    mut handicap: ResMut<Handicap>,
+    mut virtual_time: ResMut<Time<Virtual>>,
+) {
+    profile.companion = playback.player_companion;
+    *handicap = playback.player_handicap;
+    virtual_time.set_relative_speed(1.0);
+}
@@ -0,0 +1,87 @@
+//! The run's random numbers.
+//!
+//! [`GameRng`] is reseeded at the start of every run from the run seed (see
+//! `history`), or from a fresh random seed when the run starts from a fixed
+//! layout. Anything random during a run draws from it instead of the thread
+//! RNG, so a seed is enough to reproduce a run for a replay, a shared
+//! challenge, or a bug report. The seed is shown in the pause menu.
+//!
+//! Draws are split into two streams. [`GameRng::gameplay`] deals everything
+//! that changes how the run plays (bubble colors, power-up offers), and
+//! [`GameRng::effects`] everything that's only for show (sounds, shake,
+//! doodles). Effects draw per frame, so keeping them apart means a different
+//! frame rate never shifts the bubbles a seed deals.
+
+use bevy::prelude::*;
+use rand::{Rng, SeedableRng, rngs::StdRng};
+
+use super::history::{RunSeed, pick_run_seed};
+use crate::screens::InGame;
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<GameRng>();
+    app.add_systems(OnEnter(InGame), seed_game_rng.after(pick_run_seed));
+}
+
+/// Mixed into the seed for the gameplay stream, so it doesn't repeat the
+/// starting board's draws.
+const GAMEPLAY_SEED_SALT: u64 = 0x5EED;
+
+/// Mixed into the seed for the effects stream.
+const EFFECTS_SEED_SALT: u64 = 0xEFFEC7;
+
+/// Random numbers for the run in play.
+#[derive(Resource, Debug, Clone)]
+pub struct GameRng {
+    seed: u64,
+    /// Draws that change how the run plays.
+    pub gameplay: StdRng,
+    /// Draws that are only for show.
+    pub effects: StdRng,
+}
+
+impl Default for GameRng {
+    fn default() -> Self {
+        Self::from_seed(rand::rng().random())
+    }
+}
+
+impl GameRng {
+    pub fn from_seed(seed: u64) -> Self {
+        Self {
+            seed,
+            gameplay: StdRng::seed_from_u64(seed ^ GAMEPLAY_SEED_SALT),
+            effects: StdRng::seed_from_u64(seed ^ EFFECTS_SEED_SALT),
+        }
+    }
+
+    /// The seed both streams were started from.
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+}
+
+/// Reseed from the run seed, or at random for fixed layouts.
+pub(super) fn seed_game_rng(seed: Res<RunSeed>, mut rng: ResMut<GameRng>) {
+    *rng = seed.0.map(GameRng::from_seed).unwrap_or_default();
+    info!("Game RNG seed: {}", rng.seed());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_seed_deals_the_same_gameplay_however_effects_draw() {
+        let mut a = GameRng::from_seed(42);
+        let mut b = GameRng::from_seed(42);
+        for _ in 0..10 {
+            b.effects.random::<u32>();
+        }
+        let deal = |rng: &mut GameRng| -> Vec<u32> {
+            (0..5).map(|_| rng.gameplay.random_range(0..6)).collect()
+        };
+        assert_eq!(deal(&mut a), deal(&mut b));
+        assert_eq!(a.seed(), 42);
+    }
+}
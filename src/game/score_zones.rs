@@ -0,0 +1,180 @@
+//! Score zones - buckets along the bottom that multiply dropped bubbles.
+//!
+//! When a level has score zones, bubbles cut off from the board fall instead
+//! of popping in place, and each one scores its drop bonus again for every
+//! step of multiplier on the zone it lands in (a 5x zone pays the bonus five
+//! times in all). Bubbles that miss every zone just get the usual bonus.
+//!
+//! Score zones come from campaign level data; other runs have none.
+
+use bevy::prelude::*;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use super::{clock::GameClock, messages::AddGameMessage, polish::PopAnimation};
+use crate::{PausableSystems, screens::InGame, theme::GameFont};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<ScoreZones>();
+    app.init_resource::<ScoreZones>();
+    app.add_game_message::<BubbleDropped>("A falling bubble reached the score zones");
+
+    app.add_systems(OnEnter(InGame), spawn_score_zones);
+    app.add_systems(
+        Update,
+        fall_into_zones
+            .in_set(PausableSystems)
+            .run_if(in_state(InGame)),
+    );
+}
+
+/// Height of the top of the zones. Falling bubbles score when they cross it.
+pub const ZONE_TOP: f32 = -255.0;
+
+/// Height of the zones' band, down to the bottom of the window.
+const ZONE_HEIGHT: f32 = 45.0;
+
+/// Downward acceleration of falling bubbles, in pixels per second squared.
+const GRAVITY: f32 = 900.0;
+
+/// Largest sideways speed a bubble starts falling with.
+const MAX_DRIFT: f32 = 80.0;
+
+/// Zone color by multiplier, hottest last.
+const ZONE_COLORS: [(u32, Color); 3] = [
+    (1, Color::srgb(0.75, 0.7, 0.62)),
+    (2, Color::srgb(0.9, 0.72, 0.35)),
+    (5, Color::srgb(0.9, 0.4, 0.3)),
+];
+
+/// Message sent when a falling bubble crosses into the score zones.
+#[derive(Message, Debug, Clone)]
+pub struct BubbleDropped {
+    /// Multiplier of the zone it fell into, 1 if it missed them all.
+    pub multiplier: u32,
+}
+
+/// A bucket along the bottom of the playfield.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Reflect)]
+pub struct ScoreZone {
+    pub left: f32,
+    pub right: f32,
+    pub multiplier: u32,
+}
+
+/// The score zones this run. Empty means dropped bubbles pop in place.
+#[derive(Resource, Debug, Clone, Default, PartialEq, Reflect)]
+#[reflect(Resource)]
+pub struct ScoreZones(pub Vec<ScoreZone>);
+
+impl ScoreZones {
+    /// The multiplier for a bubble landing at `x`.
+    pub fn multiplier_at(&self, x: f32) -> u32 {
+        self.0
+            .iter()
+            .find(|zone| (zone.left..zone.right).contains(&x))
+            .map_or(1, |zone| zone.multiplier)
+    }
+}
+
+/// A bubble cut off from the board, falling toward the score zones.
+#[derive(Component, Debug, Clone)]
+pub struct FallingBubble {
+    pub velocity: Vec2,
+}
+
+impl FallingBubble {
+    /// Start falling with a little random sideways drift, so a big drop
+    /// spreads over the zones. The drift picks the zone, so it's drawn from
+    /// the run's gameplay stream.
+    pub fn with_random_drift(rng: &mut impl Rng) -> Self {
+        Self {
+            velocity: Vec2::new(rng.random_range(-MAX_DRIFT..MAX_DRIFT), 0.0),
+        }
+    }
+}
+
+fn zone_color(multiplier: u32) -> Color {
+    ZONE_COLORS
+        .iter()
+        .rev()
+        .find(|(min, _)| multiplier >= *min)
+        .map_or(ZONE_COLORS[0].1, |(_, color)| *color)
+}
+
+fn spawn_score_zones(mut commands: Commands, zones: Res<ScoreZones>, game_font: Res<GameFont>) {
+    for zone in &zones.0 {
+        let width = zone.right - zone.left;
+        let center = Vec2::new((zone.left + zone.right) / 2.0, ZONE_TOP - ZONE_HEIGHT / 2.0);
+        commands.spawn((
+            Name::new("Score Zone"),
+            Sprite::from_color(
+                zone_color(zone.multiplier),
+                Vec2::new(width - 4.0, ZONE_HEIGHT),
+            ),
+            Transform::from_translation(center.extend(0.2)),
+            DespawnOnExit(InGame),
+            children![(
+                Text2d::new(format!("x{}", zone.multiplier)),
+                TextFont {
+                    font: game_font.0.clone(),
+                    font_size: 22.0,
+                    ..default()
+                },
+                TextColor(Color::WHITE),
+                Transform::from_xyz(0.0, 0.0, 0.1),
+            )],
+        ));
+    }
+}
+
+/// Move falling bubbles, and pop them as they reach the zones.
+fn fall_into_zones(
+    mut commands: Commands,
+    time: Res<Time<GameClock>>,
+    zones: Res<ScoreZones>,
+    mut falling: Query<(Entity, &mut Transform, &mut FallingBubble)>,
+    mut dropped_events: MessageWriter<BubbleDropped>,
+) {
+    let dt = time.delta_secs();
+    for (entity, mut transform, mut bubble) in &mut falling {
+        bubble.velocity.y -= GRAVITY * dt;
+        transform.translation += bubble.velocity.extend(0.0) * dt;
+        if transform.translation.y > ZONE_TOP {
+            continue;
+        }
+
+        dropped_events.write(BubbleDropped {
+            multiplier: zones.multiplier_at(transform.translation.x),
+        });
+        commands
+            .entity(entity)
+            .remove::<FallingBubble>()
+            .insert(PopAnimation::new(transform.scale));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bubbles_outside_every_zone_score_once() {
+        let zones = ScoreZones(vec![
+            ScoreZone {
+                left: -100.0,
+                right: 0.0,
+                multiplier: 2,
+            },
+            ScoreZone {
+                left: 0.0,
+                right: 50.0,
+                multiplier: 5,
+            },
+        ]);
+        assert_eq!(zones.multiplier_at(-50.0), 2);
+        assert_eq!(zones.multiplier_at(0.0), 5);
+        assert_eq!(zones.multiplier_at(50.0), 1);
+        assert_eq!(zones.multiplier_at(-200.0), 1);
+    }
+}
@@ -0,0 +1,452 @@
+//! Level scripts - small [Rhai](https://rhai.rs) scripts that give campaign
+//! levels their own behavior without recompiling.
+//!
+//! A level's `script` lines (in `assets/data/campaign.json`) can define any
+//! of these hooks:
+//!
+//! - `on_start()` once the board is set up
+//! - `on_second(time)` every second of play
+//! - `on_shot(shots)` whenever a shot is fired
+//!
+//! Scripts can't touch the world directly. They read the run through
+//! `score()`, `shots()`, `time()`, `bubbles()`, and `bubble_at(column, row)`
+//! (a board letter, or `"."`), and ask for changes with `place(column, row,
+//! letter)`, `fill_row(row, letters)`, `descend()`, `say(text)`, and
+//! `talk(scene)` (plays a [dialogue](super::dialogue) scene). Columns
+//! and rows count from the top left, and letters are the board letters
+//! drills use. Requests are applied after the hook returns; bubbles only go
+//! into empty cells. Every call is capped at a fixed number of operations,
+//! so a runaway loop ends the hook instead of the game.
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex, MutexGuard},
+};
+
+use bevy::prelude::*;
+use rhai::{AST, CallFnOptions, Dynamic, Engine, EvalAltResult, FuncArgs, Scope};
+
+use super::{
+    bubble::{BubbleColor, GameAssets},
+    bubble_material::BubbleMaterial,
+    campaign::{ActiveLevel, CampaignLevels},
+    clock::GameClock,
+    dialogue::StartDialogue,
+    drills::{color_for_letter, letter_for_color},
+    grid::{GridBounds, GridCommands},
+    hex::HexCoord,
+    projectile::FireProjectile,
+    state::{GameScore, TriggerDescent},
+};
+use crate::{PausableSystems, screens::InGame, theme::toast::ShowToast};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<LevelScript>();
+    app.init_resource::<ScriptRun>();
+
+    app.add_systems(
+        Update,
+        load_level_script.run_if(resource_changed::<ActiveLevel>),
+    );
+    app.add_systems(OnEnter(InGame), reset_script_run);
+    app.add_systems(
+        Update,
+        run_level_script
+            .in_set(PausableSystems)
+            .run_if(in_state(InGame).and(script_loaded)),
+    );
+}
+
+/// Operations one hook call may take before it's stopped.
+const MAX_OPERATIONS: u64 = 50_000;
+
+/// A change a script asked for.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ScriptAction {
+    /// Put a bubble in a cell, if it's empty.
+    Place { coord: HexCoord, color: BubbleColor },
+    /// Lower the board a row.
+    Descend,
+    /// Show a line of dialogue.
+    Say(String),
+    /// Play a dialogue scene by id.
+    Talk(String),
+}
+
+/// What a script can see of the run, and what it's asked for so far.
+#[derive(Debug, Default)]
+struct ScriptView {
+    board: HashMap<HexCoord, BubbleColor>,
+    score: u32,
+    shots: u32,
+    time: u32,
+    actions: Vec<ScriptAction>,
+}
+
+/// The run as a script sees it.
+#[derive(Debug, Default, Clone)]
+pub struct RunView {
+    pub board: HashMap<HexCoord, BubbleColor>,
+    pub score: u32,
+    pub shots: u32,
+    pub time: u32,
+}
+
+/// The current level's compiled script, if it has one.
+#[derive(Resource)]
+pub struct LevelScript {
+    engine: Engine,
+    view: Arc<Mutex<ScriptView>>,
+    ast: Option<AST>,
+}
+
+impl Default for LevelScript {
+    fn default() -> Self {
+        let view = Arc::default();
+        Self {
+            engine: sandbox(&view),
+            view,
+            ast: None,
+        }
+    }
+}
+
+impl LevelScript {
+    /// Compile a level's script lines, replacing the current script.
+    pub fn load(&mut self, lines: &[String]) -> Result<(), String> {
+        self.ast = None;
+        let ast = self
+            .engine
+            .compile(lines.join("\n"))
+            .map_err(|e| e.to_string())?;
+        self.ast = Some(ast);
+        Ok(())
+    }
+
+    pub fn is_loaded(&self) -> bool {
+        self.ast.is_some()
+    }
+
+    /// Call a hook if the script defines it, returning what it asked for.
+    /// A hook that fails keeps whatever it asked for before failing.
+    pub fn call(&self, hook: &str, args: impl FuncArgs, run: RunView) -> Vec<ScriptAction> {
+        let Some(ast) = &self.ast else {
+            return Vec::new();
+        };
+        let mut arg_values = Vec::new();
+        args.parse(&mut arg_values);
+        let defined = ast
+            .iter_functions()
+            .any(|f| f.name == hook && f.params.len() == arg_values.len());
+        if !defined {
+            return Vec::new();
+        }
+
+        *lock(&self.view) = ScriptView {
+            board: run.board,
+            score: run.score,
+            shots: run.shots,
+            time: run.time,
+            actions: Vec::new(),
+        };
+        let options = CallFnOptions::new().eval_ast(false);
+        if let Err(e) = self.engine.call_fn_with_options::<Dynamic>(
+            options,
+            &mut Scope::new(),
+            ast,
+            hook,
+            arg_values,
+        ) {
+            warn!("Level script {} failed: {}", hook, e);
+        }
+        std::mem::take(&mut lock(&self.view).actions)
+    }
+}
+
+fn lock(view: &Mutex<ScriptView>) -> MutexGuard<'_, ScriptView> {
+    view.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// An engine with only the level API, and limits on what a script can use.
+fn sandbox(view: &Arc<Mutex<ScriptView>>) -> Engine {
+    let mut engine = Engine::new();
+    engine.set_max_operations(MAX_OPERATIONS);
+    engine.set_max_call_levels(16);
+    engine.set_max_expr_depths(64, 32);
+    engine.set_max_string_size(1024);
+    engine.set_max_array_size(256);
+    engine.set_max_map_size(64);
+    engine.disable_symbol("eval");
+    engine.on_print(|text| info!("Level script: {}", text));
+    engine.on_debug(|text, _, _| debug!("Level script: {}", text));
+
+    let v = view.clone();
+    engine.register_fn("score", move || lock(&v).score as i64);
+    let v = view.clone();
+    engine.register_fn("shots", move || lock(&v).shots as i64);
+    let v = view.clone();
+    engine.register_fn("time", move || lock(&v).time as i64);
+    let v = view.clone();
+    engine.register_fn("bubbles", move || lock(&v).board.len() as i64);
+    let v = view.clone();
+    engine.register_fn("bubble_at", move |column: i64, row: i64| {
+        lock(&v)
+            .board
+            .get(&cell(column, row))
+            .map_or(".".to_string(), |&color| {
+                letter_for_color(color).to_string()
+            })
+    });
+
+    let v = view.clone();
+    engine.register_fn(
+        "place",
+        move |column: i64, row: i64, letter: &str| -> Result<(), Box<EvalAltResult>> {
+            let color = parse_letter(letter)?;
+            lock(&v).actions.push(ScriptAction::Place {
+                coord: cell(column, row),
+                color,
+            });
+            Ok(())
+        },
+    );
+    let v = view.clone();
+    engine.register_fn(
+        "fill_row",
+        move |row: i64, letters: &str| -> Result<(), Box<EvalAltResult>> {
+            let mut view = lock(&v);
+            for (column, letter) in letters.chars().enumerate() {
+                if letter == '.' {
+                    continue;
+                }
+                let color = parse_letter(&letter.to_string())?;
+                view.actions.push(ScriptAction::Place {
+                    coord: cell(column as i64, row),
+                    color,
+                });
+            }
+            Ok(())
+        },
+    );
+    let v = view.clone();
+    engine.register_fn("descend", move || {
+        lock(&v).actions.push(ScriptAction::Descend);
+    });
+    let v = view.clone();
+    engine.register_fn("say", move |text: &str| {
+        lock(&v).actions.push(ScriptAction::Say(text.to_string()));
+    });
+    let v = view.clone();
+    engine.register_fn("talk", move |scene: &str| {
+        lock(&v).actions.push(ScriptAction::Talk(scene.to_string()));
+    });
+
+    engine
+}
+
+/// The grid cell at a script's column and row.
+fn cell(column: i64, row: i64) -> HexCoord {
+    let min_q = GridBounds::default().min_q;
+    HexCoord::new(min_q + column as i32, row as i32)
+}
+
+fn parse_letter(letter: &str) -> Result<BubbleColor, Box<EvalAltResult>> {
+    let mut chars = letter.chars();
+    match (chars.next().and_then(color_for_letter), chars.next()) {
+        (Some(color), None) => Ok(color),
+        _ => Err(format!("unknown bubble '{letter}'").into()),
+    }
+}
+
+/// Where the script is in the current run.
+#[derive(Resource, Debug, Default)]
+struct ScriptRun {
+    started: bool,
+    /// Game time since the run started, in seconds.
+    elapsed: f32,
+    /// Whole seconds already passed to `on_second`.
+    seconds: u32,
+    shots: u32,
+}
+
+/// Run condition: the current level has a script.
+fn script_loaded(script: Res<LevelScript>) -> bool {
+    script.is_loaded()
+}
+
+fn load_level_script(
+    active: Res<ActiveLevel>,
+    levels: Res<CampaignLevels>,
+    mut script: ResMut<LevelScript>,
+) {
+    let level = active.0.and_then(|index| levels.0.get(index));
+    let Some(level) = level.filter(|level| !level.script.is_empty()) else {
+        if script.is_loaded() {
+            *script = LevelScript::default();
+        }
+        return;
+    };
+    if let Err(e) = script.load(&level.script) {
+        warn!("Script for level '{}' didn't compile: {}", level.name, e);
+    }
+}
+
+fn reset_script_run(mut run: ResMut<ScriptRun>) {
+    *run = ScriptRun::default();
+}
+
+/// Call the script's hooks and carry out what they ask for.
+fn run_level_script(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<BubbleMaterial>>,
+    mut grid: GridCommands,
+    game_assets: Res<GameAssets>,
+    clock: Res<Time<GameClock>>,
+    score: Res<GameScore>,
+    script: Res<LevelScript>,
+    mut run: ResMut<ScriptRun>,
+    mut shots: MessageReader<FireProjectile>,
+    mut descent_events: MessageWriter<TriggerDescent>,
+    mut toasts: MessageWriter<ShowToast>,
+    mut dialogue_events: MessageWriter<StartDialogue>,
+) {
+    let view = |run: &ScriptRun| RunView {
+        board: grid
+            .coords()
+            .filter_map(|coord| Some((coord, grid.color(coord)?)))
+            .collect(),
+        score: score.score,
+        shots: run.shots,
+        time: run.seconds,
+    };
+
+    let mut actions = Vec::new();
+    if !run.started {
+        run.started = true;
+        actions.extend(script.call("on_start", (), view(&run)));
+    }
+    run.elapsed += clock.delta_secs();
+    while run.elapsed >= (run.seconds + 1) as f32 {
+        run.seconds += 1;
+        let time = run.seconds as i64;
+        actions.extend(script.call("on_second", (time,), view(&run)));
+    }
+    for _ in shots.read() {
+        run.shots += 1;
+        let count = run.shots as i64;
+        actions.extend(script.call("on_shot", (count,), view(&run)));
+    }
+
+    for action in actions {
+        match action {
+            ScriptAction::Place { coord, color } => {
+                if grid.bounds.contains(coord) && !grid.is_occupied(coord) {
+                    grid.spawn(
+                        &mut commands,
+                        &mut meshes,
+                        &mut materials,
+                        coord,
+                        color,
+                        Some(&game_assets),
+                    );
+                }
+            }
+            ScriptAction::Descend => {
+                descent_events.write(TriggerDescent);
+            }
+            ScriptAction::Say(text) => {
+                toasts.write(ShowToast::info(text));
+            }
+            ScriptAction::Talk(scene) => {
+                dialogue_events.write(StartDialogue(scene));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn compile(source: &str) -> LevelScript {
+        let mut script = LevelScript::default();
+        let lines: Vec<String> = source.lines().map(String::from).collect();
+        script.load(&lines).unwrap();
+        script
+    }
+
+    #[test]
+    fn hooks_ask_for_changes_through_the_level_api() {
+        let script = compile(
+            r#"
+            fn on_start() { say("Incoming!"); fill_row(1, "R.B"); }
+            fn on_second(t) { if t % 10 == 0 && bubble_at(0, 0) == "G" { descend(); } }
+            fn on_shot(n) { place(n, 2, "y"); }
+            "#,
+        );
+        let min_q = GridBounds::default().min_q;
+
+        assert_eq!(
+            script.call("on_start", (), RunView::default()),
+            [
+                ScriptAction::Say("Incoming!".to_string()),
+                ScriptAction::Place {
+                    coord: HexCoord::new(min_q, 1),
+                    color: BubbleColor::Red
+                },
+                ScriptAction::Place {
+                    coord: HexCoord::new(min_q + 2, 1),
+                    color: BubbleColor::Blue
+                },
+            ]
+        );
+
+        let run = RunView {
+            board: HashMap::from([(HexCoord::new(min_q, 0), BubbleColor::Green)]),
+            ..default()
+        };
+        assert_eq!(
+            script.call("on_second", (10_i64,), run.clone()),
+            [ScriptAction::Descend]
+        );
+        assert!(script.call("on_second", (9_i64,), run).is_empty());
+        assert!(
+            script
+                .call("on_second", (10_i64,), RunView::default())
+                .is_empty()
+        );
+
+        assert_eq!(
+            script.call("on_shot", (3_i64,), RunView::default()),
+            [ScriptAction::Place {
+                coord: HexCoord::new(min_q + 3, 2),
+                color: BubbleColor::Yellow
+            }]
+        );
+    }
+
+    #[test]
+    fn scripts_stay_inside_the_sandbox() {
+        // Runaway loops are stopped, keeping what was asked for first
+        let script = compile(r#"fn on_start() { say("hi"); loop { } }"#);
+        assert_eq!(
+            script.call("on_start", (), RunView::default()),
+            [ScriptAction::Say("hi".to_string())]
+        );
+
+        // Missing hooks and bad letters do nothing
+        let script = compile(r#"fn on_shot(n) { place(0, 0, "X"); }"#);
+        assert!(script.call("on_start", (), RunView::default()).is_empty());
+        assert!(
+            script
+                .call("on_shot", (1_i64,), RunView::default())
+                .is_empty()
+        );
+
+        let mut bad = LevelScript::default();
+        assert!(bad.load(&["fn on_start( {".to_string()]).is_err());
+        assert!(!bad.is_loaded());
+        assert!(bad.load(&["eval(\"1\")".to_string()]).is_err());
+    }
+}
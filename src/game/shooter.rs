@@ -1,50 +1,91 @@
 //! The shooter/launcher at the bottom of the screen.
 //!
-//! The player aims with the mouse and fires bubbles upward.
-//! The shooter always has a "loaded" bubble ready to fire and
-//! a "next" bubble preview.
-
-use bevy::{input::touch::Touches, prelude::*, window::PrimaryWindow};
+//! The player aims with the mouse, a finger, the keys, or a gamepad stick
+//! and fires bubbles upward, with a short dotted guide along the shot's path.
+//! The shooter always has a "loaded" bubble ready to fire and a "next"
+//! bubble preview, which the swap key trades places. With Rainbow Snord, the
+//! loaded bubble is now and then a wildcard that matches any color, tinted
+//! through the rainbow.
+
+use bevy::{
+    input::touch::Touches,
+    prelude::*,
+    window::{CursorMoved, PrimaryWindow},
+};
+use rand::Rng;
 
 use super::{
-    bubble::{Bubble, BubbleColor, GameAssets, SNORD_SPRITE_SCALE, load_game_assets},
+    bubble::{Bubble, BubbleColor, GameAssets, load_game_assets, sprite_scale},
+    bubble_material::BubbleMaterial,
+    bumpers::Bumpers,
+    gameplay_entities::GameplayEntity,
     grid::HexGrid,
-    hex::HEX_SIZE,
+    hex::{GridOffset, HEX_SIZE},
+    mode::{GameMode, LevelColors},
+    patterns::color_symbol,
+    portals::Portals,
     powerups::{PowerUp, UnlockedPowerUps},
-    projectile::{FireProjectile, LEFT_WALL, Projectile, RIGHT_WALL, TOP_WALL},
+    projectile::{
+        FireProjectile, MAX_PREDICTION_DISTANCE, Projectile, Walls, collision_distance, trace_shot,
+    },
+    rng::{GameRng, seed_game_rng},
     state::{GameLevel, TriggerDescent},
+    sticky_walls::StickyWalls,
+    wildfire::Wildfire,
+};
+use crate::{
+    PausableSystems,
+    controls::{AIM_LEFT_KEYS, AIM_RIGHT_KEYS, InputAction, InputBindings},
+    screens::{InGame, RunPhase, Screen},
 };
-use crate::{PausableSystems, screens::Screen};
 
 pub(super) fn plugin(app: &mut App) {
     app.register_type::<Shooter>();
     app.register_type::<ShooterState>();
     app.register_type::<AimDirection>();
+    app.register_type::<KeyboardAim>();
+    app.register_type::<AimGuideSettings>();
+    app.register_type::<LoadedBubble>();
+    app.register_type::<LoadedWildcard>();
     app.register_type::<NextBubble>();
+    app.register_type::<SecondNextBubble>();
+    app.register_type::<ThirdNextBubble>();
 
     // Initialize touch state resource
     app.init_resource::<TouchAimState>();
+    app.init_resource::<KeyboardAim>();
+    app.init_resource::<ScriptedQueue>();
+    app.init_resource::<AimGuideSettings>();
 
     // Spawn shooter when entering gameplay (after assets are loaded)
     app.add_systems(
-        OnEnter(Screen::Gameplay),
-        spawn_shooter.after(load_game_assets),
+        OnEnter(InGame),
+        spawn_shooter.after(load_game_assets).after(seed_game_rng),
     );
 
     // Update systems that run while playing
     app.add_systems(
         Update,
         (
-            update_aim_direction,
-            handle_touch_input,
+            // Player controls (the demo bot aims and fires on its own)
+            // Touch first, so a finger on the screen wins over the cursor
+            (
+                handle_touch_input,
+                update_aim_direction,
+                swap_loaded_bubble,
+                handle_fire_input,
+            )
+                .chain()
+                .run_if(in_state(Screen::Gameplay).and(in_state(RunPhase::Playing))),
             update_shooter_visuals,
-            handle_fire_input,
-            reload_shooter,
+            // Straight after, so fresh previews never show a merged color
+            (reload_shooter, sync_queue_visuals).chain(),
             update_fortune_snord_visibility,
-            draw_bounce_trajectory,
+            draw_aim_guide,
+            tint_rainbow_snords,
         )
             .in_set(PausableSystems)
-            .run_if(in_state(Screen::Gameplay)),
+            .run_if(in_state(InGame)),
     );
 }
 
@@ -52,7 +93,7 @@ pub(super) fn plugin(app: &mut App) {
 pub const SHOOTER_Y: f32 = -210.0;
 
 /// Maximum angle from vertical (in radians) - prevents shooting too horizontally.
-const MAX_AIM_ANGLE: f32 = 1.3; // About 75 degrees
+pub(super) const MAX_AIM_ANGLE: f32 = 1.3; // About 75 degrees
 
 /// Marker component for the shooter entity.
 #[derive(Component, Debug, Clone, Reflect)]
@@ -86,6 +127,17 @@ impl Default for AimDirection {
 #[reflect(Component)]
 pub struct LoadedBubble(pub BubbleColor);
 
+/// Whether the loaded bubble is a wildcard (see [`PowerUp::RainbowSnord`]).
+#[derive(Component, Debug, Clone, Default, Reflect)]
+#[reflect(Component)]
+pub struct LoadedWildcard(pub bool);
+
+/// Chance that a reload brings up a wildcard, with Rainbow Snord.
+const RAINBOW_CHANCE: f64 = 0.12;
+
+/// Seconds for a wildcard's tint to go once around the rainbow.
+const RAINBOW_CYCLE_SECS: f32 = 1.5;
+
 /// The next bubble color (preview).
 #[derive(Component, Debug, Clone, Reflect)]
 #[reflect(Component)]
@@ -103,7 +155,7 @@ pub struct ThirdNextBubble(pub BubbleColor);
 
 /// Marker for the loaded bubble visual entity.
 #[derive(Component)]
-struct LoadedBubbleVisual;
+pub(super) struct LoadedBubbleVisual;
 
 /// Marker for the next bubble visual entity.
 #[derive(Component)]
@@ -121,16 +173,98 @@ struct ThirdNextBubbleVisual;
 #[derive(Component)]
 struct ShooterArrowVisual;
 
-/// Marker for trajectory segment visuals (used by Bouncy Snord).
+/// Marker for the aim guide's segment visuals.
 /// The index indicates which segment (0 = first, 1 = after first bounce, etc.)
 #[derive(Component)]
-struct TrajectorySegment(usize);
+pub(super) struct TrajectorySegment(usize);
 
 /// Maximum number of trajectory segments to show (initial + bounces).
 const MAX_TRAJECTORY_SEGMENTS: usize = 4;
 
+/// Length of the aim guide without Bouncy Snord.
+const AIM_GUIDE_LENGTH: f32 = 120.0;
+
+/// Whether everyone gets the short aim guide, changed in the settings menu.
+/// Bouncy Snord's full guide shows either way.
+#[derive(Resource, Debug, Clone, Reflect)]
+#[reflect(Resource)]
+pub struct AimGuideSettings {
+    pub enabled: bool,
+}
+
+impl Default for AimGuideSettings {
+    fn default() -> Self {
+        Self { enabled: true }
+    }
+}
+
+/// Colors to load in order instead of random ones (used by drills).
+/// Random colors take over once it runs out.
+#[derive(Resource, Debug, Default)]
+pub struct ScriptedQueue {
+    pub colors: Vec<BubbleColor>,
+    /// Index of the next color to hand out, reset when the shooter spawns.
+    cursor: usize,
+}
+
+impl ScriptedQueue {
+    pub fn new(colors: Vec<BubbleColor>) -> Self {
+        Self { colors, cursor: 0 }
+    }
+
+    /// Take the next scripted color, if any are left.
+    fn take(&mut self) -> Option<BubbleColor> {
+        let color = self.colors.get(self.cursor).copied();
+        self.cursor += 1;
+        color
+    }
+}
+
+/// Keyboard aiming: left/right (or A/D) turn the aim, with Shift held for
+/// fine adjustment.
+///
+/// The mouse takes over again as soon as it moves, and the keys only turn
+/// the aim once the mouse has been still for [`MOUSE_IDLE_SECS`], so a
+/// resting hand on the keyboard doesn't fight the cursor.
+#[derive(Resource, Debug, Clone, Reflect)]
+#[reflect(Resource)]
+pub struct KeyboardAim {
+    /// How fast the keys turn the aim, in radians per second.
+    pub speed: f32,
+    /// Fraction of [`Self::speed`] while Shift is held.
+    pub fine_scale: f32,
+    /// Seconds since the mouse last moved.
+    mouse_idle: f32,
+    /// Whether the keys or a gamepad stick turned the aim since the mouse
+    /// last moved.
+    active: bool,
+}
+
+impl Default for KeyboardAim {
+    fn default() -> Self {
+        Self {
+            speed: 1.2,
+            fine_scale: 0.25,
+            // The mouse hasn't moved at all yet
+            mouse_idle: MOUSE_IDLE_SECS,
+            active: false,
+        }
+    }
+}
+
+/// How long the mouse has to be still before the keys can aim.
+const MOUSE_IDLE_SECS: f32 = 0.5;
+
+/// How far a gamepad stick has to be pushed before it aims.
+const STICK_DEADZONE: f32 = 0.3;
+
+/// How close to the shooter a finger has to be for letting go to cancel the
+/// shot instead of firing it.
+const TOUCH_CANCEL_RADIUS: f32 = 50.0;
+
 /// Resource tracking touch input state for mobile controls.
-/// Implements drag-to-aim, release-to-fire control scheme.
+/// Implements drag-to-aim, release-to-fire control scheme. Dragging back
+/// onto the shooter and letting go there cancels the shot.
 #[derive(Resource, Default)]
 pub struct TouchAimState {
     /// Whether we're currently tracking a touch for aiming.
@@ -139,21 +273,35 @@ pub struct TouchAimState {
     pub touch_id: Option<u64>,
     /// Whether a fire should be triggered this frame (set on touch release).
     pub should_fire: bool,
+    /// Whether the finger is within [`TOUCH_CANCEL_RADIUS`] of the shooter.
+    pub in_cancel_zone: bool,
 }
 
 /// Spawn the shooter at the bottom of the screen.
-fn spawn_shooter(
+pub(super) fn spawn_shooter(
     mut commands: Commands,
     mut meshes: ResMut<Assets<Mesh>>,
-    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut materials: ResMut<Assets<BubbleMaterial>>,
     game_assets: Res<GameAssets>,
+    mut scripted: ResMut<ScriptedQueue>,
+    mut rng: ResMut<GameRng>,
+    mode: Res<GameMode>,
+    level_colors: Res<LevelColors>,
+    grid: Res<HexGrid>,
 ) {
     info!("Spawning shooter at y={}", SHOOTER_Y);
 
-    let loaded_color = BubbleColor::random();
-    let next_color = BubbleColor::random();
-    let second_next_color = BubbleColor::random();
-    let third_next_color = BubbleColor::random();
+    scripted.cursor = 0;
+    let palette = level_colors.palette(*mode);
+    let mut color = || {
+        scripted
+            .take()
+            .unwrap_or_else(|| BubbleColor::random_with(palette, &mut rng.gameplay))
+    };
+    let loaded_color = color();
+    let next_color = color();
+    let second_next_color = color();
+    let third_next_color = color();
 
     // Main shooter entity
     let shooter_entity = commands
@@ -163,12 +311,13 @@ fn spawn_shooter(
             ShooterState::Ready,
             AimDirection::default(),
             LoadedBubble(loaded_color),
+            LoadedWildcard::default(),
             NextBubble(next_color),
             SecondNextBubble(second_next_color),
             ThirdNextBubble(third_next_color),
             Transform::from_xyz(0.0, SHOOTER_Y, 1.0),
             Visibility::default(),
-            DespawnOnExit(Screen::Gameplay),
+            DespawnOnExit(InGame),
         ))
         .id();
 
@@ -190,7 +339,7 @@ fn spawn_shooter(
         .id();
     commands.entity(shooter_entity).add_child(arrow);
 
-    // Spawn trajectory segment sprites (for Bouncy Snord powerup)
+    // Spawn the aim guide's segment sprites
     // These are positioned in world space since they need to follow bounce paths
     // The guide_line image is 300px wide (horizontal), we rotate it to be vertical
     for i in 0..MAX_TRAJECTORY_SEGMENTS {
@@ -201,7 +350,7 @@ fn spawn_shooter(
             Sprite::from_image(game_assets.guide_line_image.clone()),
             bevy::sprite::Anchor::CENTER_LEFT,
             Visibility::Hidden,
-            DespawnOnExit(Screen::Gameplay),
+            GameplayEntity,
         ));
     }
 
@@ -213,6 +362,7 @@ fn spawn_shooter(
         &game_assets,
         shooter_entity,
         loaded_color,
+        grid.hex_size,
         Vec3::ZERO,
         1.5,
         LoadedBubbleVisual,
@@ -226,6 +376,7 @@ fn spawn_shooter(
         &game_assets,
         shooter_entity,
         next_color,
+        grid.hex_size,
         Vec3::new(HEX_SIZE * 3.5, 0.0, 0.0),
         1.0,
         NextBubbleVisual,
@@ -239,6 +390,7 @@ fn spawn_shooter(
         &game_assets,
         shooter_entity,
         second_next_color,
+        grid.hex_size,
         Vec3::new(HEX_SIZE * 5.5, 0.0, 0.0),
         0.8,
         SecondNextBubbleVisual,
@@ -252,6 +404,7 @@ fn spawn_shooter(
         &game_assets,
         shooter_entity,
         third_next_color,
+        grid.hex_size,
         Vec3::new(HEX_SIZE * 7.3, 0.0, 0.0),
         0.65,
         ThirdNextBubbleVisual,
@@ -264,14 +417,16 @@ fn spawn_shooter(
     );
 }
 
-/// Spawn a bubble visual (sprite for blue, mesh for others) as a child of the given parent.
+/// Spawn a bubble visual (sprite for blue, mesh for others) as a child of the given parent,
+/// sized for bubbles of `hex_size`.
 fn spawn_bubble_visual<M: Component>(
     commands: &mut Commands,
     meshes: &mut Assets<Mesh>,
-    materials: &mut Assets<ColorMaterial>,
+    materials: &mut Assets<BubbleMaterial>,
     game_assets: &GameAssets,
     parent: Entity,
     color: BubbleColor,
+    hex_size: f32,
     position: Vec3,
     scale: f32,
     marker: M,
@@ -291,11 +446,17 @@ fn spawn_bubble_visual<M: Component>(
         let child = commands
             .spawn((
                 Name::new("Bubble Visual (Sprite)"),
+                color,
                 marker,
                 Transform::from_translation(position)
-                    .with_scale(Vec3::splat(SNORD_SPRITE_SCALE * scale)),
+                    .with_scale(Vec3::splat(sprite_scale(hex_size) * scale)),
                 Sprite::from_image(image),
                 visibility,
+                children![color_symbol(
+                    color,
+                    hex_size * scale,
+                    sprite_scale(hex_size) * scale
+                )],
             ))
             .id();
         commands.entity(parent).add_child(child);
@@ -304,30 +465,100 @@ fn spawn_bubble_visual<M: Component>(
         let child = commands
             .spawn((
                 Name::new("Bubble Visual (Mesh)"),
+                color,
                 marker,
-                Transform::from_translation(position),
-                Mesh2d(meshes.add(RegularPolygon::new(HEX_SIZE * scale, 6))),
-                MeshMaterial2d(materials.add(ColorMaterial::from_color(color.to_color()))),
+                Transform::from_translation(position).with_scale(Vec3::splat(scale)),
+                Mesh2d(meshes.add(RegularPolygon::new(hex_size, 6))),
+                MeshMaterial2d(materials.add(BubbleMaterial::new(color.to_color()))),
                 visibility,
+                children![color_symbol(color, hex_size * scale, scale)],
             ))
             .id();
         commands.entity(parent).add_child(child);
     }
 }
 
+/// Tint wildcard snords through the rainbow, in the shooter and in flight.
+fn tint_rainbow_snords(
+    time: Res<Time<Real>>,
+    shooter_query: Query<&LoadedWildcard, With<Shooter>>,
+    mut loaded_query: Query<&mut Sprite, With<LoadedBubbleVisual>>,
+    mut projectile_query: Query<(&Projectile, &mut Sprite), Without<LoadedBubbleVisual>>,
+) {
+    let hue = (time.elapsed_secs() / RAINBOW_CYCLE_SECS).fract() * 360.0;
+    let rainbow = Color::hsl(hue, 0.9, 0.75);
+    let tint = |wildcard: bool| if wildcard { rainbow } else { Color::WHITE };
+
+    let wildcard = shooter_query.iter().any(|wildcard| wildcard.0);
+    for mut sprite in &mut loaded_query {
+        let color = tint(wildcard);
+        if sprite.color != color {
+            sprite.color = color;
+        }
+    }
+    for (projectile, mut sprite) in &mut projectile_query {
+        if projectile.wildcard {
+            sprite.color = rainbow;
+        }
+    }
+}
+
 /// Update the aim direction based on mouse position.
 fn update_aim_direction(
     window_query: Query<&Window, With<PrimaryWindow>>,
     camera_query: Query<(&Camera, &GlobalTransform)>,
     mut shooter_query: Query<(&Transform, &mut AimDirection), With<Shooter>>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut cursor_moved: MessageReader<CursorMoved>,
+    mut keyboard_aim: ResMut<KeyboardAim>,
+    time: Res<Time>,
+    gamepads: Query<&Gamepad>,
+    touch_state: Res<TouchAimState>,
 ) {
-    let Ok(window) = window_query.single() else {
+    // A finger on the screen aims on its own
+    if touch_state.is_aiming {
+        return;
+    }
+    let Ok((shooter_transform, mut aim)) = shooter_query.single_mut() else {
         return;
     };
-    let Ok((camera, camera_transform)) = camera_query.single() else {
+
+    if cursor_moved.read().count() > 0 {
+        keyboard_aim.mouse_idle = 0.0;
+        keyboard_aim.active = false;
+    } else {
+        keyboard_aim.mouse_idle += time.delta_secs();
+    }
+
+    // Turn with the keys while the mouse is resting
+    let pressed = |keys: [KeyCode; 2]| keyboard_input.any_pressed(keys) as i8 as f32;
+    let turn = pressed(AIM_RIGHT_KEYS) - pressed(AIM_LEFT_KEYS);
+    if turn != 0.0 && keyboard_aim.mouse_idle >= MOUSE_IDLE_SECS {
+        keyboard_aim.active = true;
+        let fine = keyboard_input.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+        let speed = keyboard_aim.speed * if fine { keyboard_aim.fine_scale } else { 1.0 };
+        let angle = aim.0.x.atan2(aim.0.y) + turn * speed * time.delta_secs();
+        let clamped_angle = angle.clamp(-MAX_AIM_ANGLE, MAX_AIM_ANGLE);
+        aim.0 = Vec2::new(clamped_angle.sin(), clamped_angle.cos());
+    }
+    // A gamepad stick points the aim where it's pushed
+    if let Some(stick) = gamepads
+        .iter()
+        .map(Gamepad::left_stick)
+        .find(|stick| stick.length() > STICK_DEADZONE)
+    {
+        keyboard_aim.active = true;
+        aim.0 = clamp_aim(stick.normalize());
+    }
+    // The keys and stick keep the aim until the mouse moves again
+    if keyboard_aim.active {
+        return;
+    }
+
+    let Ok(window) = window_query.single() else {
         return;
     };
-    let Ok((shooter_transform, mut aim)) = shooter_query.single_mut() else {
+    let Ok((camera, camera_transform)) = camera_query.single() else {
         return;
     };
 
@@ -341,8 +572,11 @@ fn update_aim_direction(
 
     // Calculate direction from shooter to cursor
     let shooter_pos = shooter_transform.translation.truncate();
-    let mut direction = (cursor_pos - shooter_pos).normalize_or_zero();
+    aim.0 = clamp_aim((cursor_pos - shooter_pos).normalize_or_zero());
+}
 
+/// Keep an aim direction pointing upward and short of [`MAX_AIM_ANGLE`].
+fn clamp_aim(mut direction: Vec2) -> Vec2 {
     // Ensure we're aiming upward (not down)
     if direction.y < 0.1 {
         direction.y = 0.1;
@@ -353,10 +587,11 @@ fn update_aim_direction(
     let angle = direction.x.atan2(direction.y);
     let clamped_angle = angle.clamp(-MAX_AIM_ANGLE, MAX_AIM_ANGLE);
 
-    aim.0 = Vec2::new(clamped_angle.sin(), clamped_angle.cos());
+    Vec2::new(clamped_angle.sin(), clamped_angle.cos())
 }
 
-/// Handle touch input for mobile controls (drag-to-aim, release-to-fire).
+/// Handle touch input for mobile controls (drag-to-aim, release-to-fire,
+/// release on the shooter to cancel).
 fn handle_touch_input(
     touches: Res<Touches>,
     camera_query: Query<(&Camera, &GlobalTransform)>,
@@ -379,12 +614,17 @@ fn handle_touch_input(
         touch_state.touch_id = Some(touch.id());
     }
 
-    // Handle touch release (fire!)
-    for touch in touches.iter_just_released() {
+    // Handle touch release (fire, unless let go on the shooter)
+    for touch in touches
+        .iter_just_released()
+        .chain(touches.iter_just_canceled())
+    {
         if touch_state.is_aiming && touch_state.touch_id == Some(touch.id()) {
-            touch_state.should_fire = true;
+            touch_state.should_fire =
+                !touch_state.in_cancel_zone && touches.just_released(touch.id());
             touch_state.is_aiming = false;
             touch_state.touch_id = None;
+            touch_state.in_cancel_zone = false;
         }
     }
 
@@ -397,21 +637,14 @@ fn handle_touch_input(
         let touch_screen_pos = touch.position();
         if let Ok(touch_world_pos) = camera.viewport_to_world_2d(camera_transform, touch_screen_pos)
         {
-            // Calculate direction from shooter to touch position
+            // Calculate direction from shooter to touch position, keeping
+            // the last aim while the finger rests on the shooter
             let shooter_pos = shooter_transform.translation.truncate();
-            let mut direction = (touch_world_pos - shooter_pos).normalize_or_zero();
-
-            // Ensure we're aiming upward (not down)
-            if direction.y < 0.1 {
-                direction.y = 0.1;
-                direction = direction.normalize();
+            touch_state.in_cancel_zone =
+                touch_world_pos.distance(shooter_pos) < TOUCH_CANCEL_RADIUS;
+            if !touch_state.in_cancel_zone {
+                aim.0 = clamp_aim((touch_world_pos - shooter_pos).normalize_or_zero());
             }
-
-            // Clamp angle to prevent too-horizontal shots
-            let angle = direction.x.atan2(direction.y);
-            let clamped_angle = angle.clamp(-MAX_AIM_ANGLE, MAX_AIM_ANGLE);
-
-            aim.0 = Vec2::new(clamped_angle.sin(), clamped_angle.cos());
         }
     }
 }
@@ -421,6 +654,7 @@ fn update_shooter_visuals(
     shooter_query: Query<&AimDirection, With<Shooter>>,
     mut arrow_query: Query<(&mut Transform, &mut Visibility), With<ShooterArrowVisual>>,
     powerups: Res<UnlockedPowerUps>,
+    touch_state: Res<TouchAimState>,
 ) {
     let Ok(aim) = shooter_query.single() else {
         return;
@@ -434,8 +668,9 @@ fn update_shooter_visuals(
     if let Ok((mut arrow_transform, mut arrow_visibility)) = arrow_query.single_mut() {
         arrow_transform.rotation = Quat::from_rotation_z(aim_angle);
 
-        // Hide arrow when Bouncy Snord is active (trajectory segments replace it)
-        if powerups.has(PowerUp::BouncySnord) {
+        // Hide arrow when Bouncy Snord is active (trajectory segments replace
+        // it), or while letting go would cancel the shot
+        if powerups.has(PowerUp::BouncySnord) || touch_state.in_cancel_zone {
             *arrow_visibility = Visibility::Hidden;
         } else {
             *arrow_visibility = Visibility::Inherited;
@@ -452,29 +687,58 @@ fn update_shooter_visuals(
     }
 }
 
-/// Handle fire input (mouse click, spacebar, or touch release).
+/// Trade the loaded snord for the next one on the swap key or button.
+fn swap_loaded_bubble(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    gamepads: Query<&Gamepad>,
+    bindings: Res<InputBindings>,
+    mut shooter_query: Query<(&ShooterState, &mut LoadedBubble, &mut NextBubble), With<Shooter>>,
+) {
+    if !bindings.just_pressed(InputAction::Swap, &keyboard_input, &gamepads) {
+        return;
+    }
+    let Ok((state, mut loaded, mut next)) = shooter_query.single_mut() else {
+        return;
+    };
+    if *state != ShooterState::Ready {
+        return;
+    }
+    // Changing the loaded snord brings the previews up to date
+    std::mem::swap(&mut loaded.0, &mut next.0);
+    info!("Swapped to {:?}, next is {:?}", loaded.0, next.0);
+}
+
+/// Handle fire input (mouse click, fire key, gamepad, or touch release).
 fn handle_fire_input(
     mouse_input: Res<ButtonInput<MouseButton>>,
     keyboard_input: Res<ButtonInput<KeyCode>>,
     touch_state: Res<TouchAimState>,
+    gamepads: Query<&Gamepad>,
+    bindings: Res<InputBindings>,
     mut shooter_query: Query<
-        (&Transform, &AimDirection, &mut ShooterState, &LoadedBubble),
+        (
+            &Transform,
+            &AimDirection,
+            &mut ShooterState,
+            &LoadedBubble,
+            &LoadedWildcard,
+        ),
         With<Shooter>,
     >,
     projectile_query: Query<&Projectile>,
     mut fire_events: MessageWriter<FireProjectile>,
     mut level: ResMut<GameLevel>,
 ) {
-    // Check for fire input (mouse click, spacebar, or touch release)
+    // Check for fire input (mouse click, fire key or button, or touch release)
     let fire_pressed = mouse_input.just_pressed(MouseButton::Left)
-        || keyboard_input.just_pressed(KeyCode::Space)
+        || bindings.just_pressed(InputAction::Fire, &keyboard_input, &gamepads)
         || touch_state.should_fire;
 
     if !fire_pressed {
         return;
     }
 
-    let Ok((transform, aim, mut state, loaded)) = shooter_query.single_mut() else {
+    let Ok((transform, aim, mut state, loaded, wildcard)) = shooter_query.single_mut() else {
         return;
     };
 
@@ -495,6 +759,7 @@ fn handle_fire_input(
         position: spawn_pos,
         direction: aim.0,
         color: loaded.0,
+        wildcard: wildcard.0,
     });
 
     *state = ShooterState::Reloading;
@@ -511,7 +776,7 @@ fn handle_fire_input(
 fn reload_shooter(
     mut commands: Commands,
     mut meshes: ResMut<Assets<Mesh>>,
-    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut materials: ResMut<Assets<BubbleMaterial>>,
     mut shooter_query: Query<
         (
             Entity,
@@ -520,6 +785,7 @@ fn reload_shooter(
             &mut NextBubble,
             &mut SecondNextBubble,
             &mut ThirdNextBubble,
+            &mut LoadedWildcard,
         ),
         With<Shooter>,
     >,
@@ -530,13 +796,26 @@ fn reload_shooter(
     projectile_query: Query<&Projectile>,
     level: Res<GameLevel>,
     mut descent_events: MessageWriter<TriggerDescent>,
-    powerups: Res<UnlockedPowerUps>,
+    (powerups, mode, level_colors, mut rng): (
+        Res<UnlockedPowerUps>,
+        Res<GameMode>,
+        Res<LevelColors>,
+        ResMut<GameRng>,
+    ),
     grid: Res<HexGrid>,
     bubble_query: Query<&Bubble>,
     game_assets: Res<GameAssets>,
+    mut scripted: ResMut<ScriptedQueue>,
 ) {
-    let Ok((shooter_entity, mut state, mut loaded, mut next, mut second_next, mut third_next)) =
-        shooter_query.single_mut()
+    let Ok((
+        shooter_entity,
+        mut state,
+        mut loaded,
+        mut next,
+        mut second_next,
+        mut third_next,
+        mut wildcard,
+    )) = shooter_query.single_mut()
     else {
         return;
     };
@@ -555,18 +834,25 @@ fn reload_shooter(
     loaded.0 = next.0;
     next.0 = second_next.0;
     second_next.0 = third_next.0;
+    // Rainbow Snord: now and then the loaded snord matches anything
+    wildcard.0 = powerups.has(PowerUp::RainbowSnord) && rng.gameplay.random_bool(RAINBOW_CHANCE);
 
     // Generate new third preview color
-    // Lucky Snord: Weight color selection toward colors on the grid
-    if powerups.has(PowerUp::LuckySnord) {
+    if let Some(color) = scripted.take() {
+        third_next.0 = color;
+    } else if powerups.has(PowerUp::LuckySnord) {
+        // Lucky Snord: Weight color selection toward colors on the grid
         let grid_colors: Vec<BubbleColor> = grid
             .iter()
-            .filter_map(|(_, &entity)| bubble_query.get(entity).ok())
-            .map(|b| b.color)
+            .filter_map(|(_, &entity)| bubble_query.get(entity).ok()?.match_color())
             .collect();
-        third_next.0 = BubbleColor::random_weighted(&grid_colors);
+        third_next.0 = BubbleColor::random_weighted(
+            &grid_colors,
+            level_colors.palette(*mode),
+            &mut rng.gameplay,
+        );
     } else {
-        third_next.0 = BubbleColor::random();
+        third_next.0 = BubbleColor::random_with(level_colors.palette(*mode), &mut rng.gameplay);
     }
 
     // Despawn old visuals and spawn new ones with correct rendering
@@ -580,6 +866,7 @@ fn reload_shooter(
         &game_assets,
         shooter_entity,
         loaded.0,
+        grid.hex_size,
         Vec3::ZERO,
         1.5,
         LoadedBubbleVisual,
@@ -596,6 +883,7 @@ fn reload_shooter(
         &game_assets,
         shooter_entity,
         next.0,
+        grid.hex_size,
         Vec3::new(HEX_SIZE * 3.5, 0.0, 0.0),
         1.0,
         NextBubbleVisual,
@@ -612,6 +900,7 @@ fn reload_shooter(
         &game_assets,
         shooter_entity,
         second_next.0,
+        grid.hex_size,
         Vec3::new(HEX_SIZE * 5.5, 0.0, 0.0),
         0.8,
         SecondNextBubbleVisual,
@@ -628,6 +917,7 @@ fn reload_shooter(
         &game_assets,
         shooter_entity,
         third_next.0,
+        grid.hex_size,
         Vec3::new(HEX_SIZE * 7.3, 0.0, 0.0),
         0.65,
         ThirdNextBubbleVisual,
@@ -638,12 +928,7 @@ fn reload_shooter(
     info!("Reloaded with {:?}, next is {:?}", loaded.0, next.0);
 
     // Check if it's time for descent
-    // Procrastisnord: +2 extra shots before descent
-    let shots_threshold = if powerups.has(PowerUp::Procrastisnord) {
-        level.shots_until_descent + 2
-    } else {
-        level.shots_until_descent
-    };
+    let shots_threshold = level.descent_threshold(&powerups);
 
     if level.shots_this_round >= shots_threshold {
         info!(
@@ -654,6 +939,53 @@ fn reload_shooter(
     }
 }
 
+/// Keep the preview sprites in step with the queue when it's replaced outright,
+/// e.g. by restoring a [`GameSnapshot`](super::snapshot::GameSnapshot), and
+/// show each snord as the color a [`Wildfire`] has it match as.
+fn sync_queue_visuals(
+    shooter_query: Query<
+        (
+            Ref<LoadedBubble>,
+            &NextBubble,
+            &SecondNextBubble,
+            &ThirdNextBubble,
+        ),
+        With<Shooter>,
+    >,
+    mut visual_query: Query<(
+        &mut Sprite,
+        &mut BubbleColor,
+        Has<LoadedBubbleVisual>,
+        Has<NextBubbleVisual>,
+        Has<SecondNextBubbleVisual>,
+        Has<ThirdNextBubbleVisual>,
+    )>,
+    game_assets: Res<GameAssets>,
+    wildfire: Res<Wildfire>,
+) {
+    let Ok((loaded, next, second_next, third_next)) = shooter_query.single() else {
+        return;
+    };
+    if !loaded.is_changed() && !wildfire.is_changed() {
+        return;
+    }
+
+    for (mut sprite, mut visual_color, is_loaded, is_next, is_second, is_third) in &mut visual_query
+    {
+        let color = match (is_loaded, is_next, is_second, is_third) {
+            (true, ..) => loaded.0,
+            (_, true, ..) => next.0,
+            (_, _, true, _) => second_next.0,
+            (.., true) => third_next.0,
+            _ => continue,
+        };
+        let color = wildfire.resolve(color);
+        sprite.image = game_assets.sprite_for(color);
+        // Keeps colorblind patterns in step
+        *visual_color = color;
+    }
+}
+
 /// Update visibility of extra preview bubbles based on Fortune Snord power-up.
 fn update_fortune_snord_visibility(
     mut second_query: Query<&mut Visibility, With<SecondNextBubbleVisual>>,
@@ -682,14 +1014,21 @@ fn update_fortune_snord_visibility(
     }
 }
 
-/// Update trajectory segment sprites when Bouncy Snord powerup is active.
-fn draw_bounce_trajectory(
+/// Lay the aim guide along the shot's path: a short stretch for everyone
+/// (twice as long with Eagle Eye) if the guide is on, or every bounce up to
+/// the landing with Bouncy Snord.
+fn draw_aim_guide(
     shooter_query: Query<(&Transform, &AimDirection, &ShooterState), With<Shooter>>,
     mut segment_query: Query<
         (&TrajectorySegment, &mut Transform, &mut Visibility),
         Without<Shooter>,
     >,
+    settings: Res<AimGuideSettings>,
     powerups: Res<UnlockedPowerUps>,
+    (walls, portals, sticky, bumpers): (Res<Walls>, Res<Portals>, Res<StickyWalls>, Res<Bumpers>),
+    grid: Res<HexGrid>,
+    grid_offset: Res<GridOffset>,
+    mode: Res<GameMode>,
 ) {
     let has_bouncy = powerups.has(PowerUp::BouncySnord);
 
@@ -701,73 +1040,47 @@ fn draw_bounce_trajectory(
         return;
     };
 
-    // Hide all segments if Bouncy Snord not active or reloading
-    if !has_bouncy || *state == ShooterState::Reloading {
+    // Hide all segments if there's no guide to show or while reloading
+    if !(has_bouncy || settings.enabled) || *state == ShooterState::Reloading {
         for (_, _, mut vis) in &mut segment_query {
             *vis = Visibility::Hidden;
         }
         return;
     }
 
-    // Calculate trajectory segments
-    let mut segments: Vec<(Vec2, Vec2, f32)> = Vec::new(); // (start, end, length)
-    let mut pos = shooter_transform.translation.truncate();
-    let mut dir = aim.0;
-    let max_distance = 800.0;
-    let mut remaining_distance = max_distance;
-
-    // Simulate trajectory with bounces
-    while remaining_distance > 0.0 && segments.len() < MAX_TRAJECTORY_SEGMENTS {
-        // Calculate how far we can travel before hitting a wall or top
-        let mut t_min = remaining_distance;
-        let mut hit_wall = false;
-
-        // Check left wall
-        if dir.x < 0.0 {
-            let t = (LEFT_WALL - pos.x) / dir.x;
-            if t > 0.0 && t < t_min {
-                t_min = t;
-                hit_wall = true;
-            }
-        }
-
-        // Check right wall
-        if dir.x > 0.0 {
-            let t = (RIGHT_WALL - pos.x) / dir.x;
-            if t > 0.0 && t < t_min {
-                t_min = t;
-                hit_wall = true;
-            }
-        }
-
-        // Check top wall
-        if dir.y > 0.0 {
-            let t = (TOP_WALL - pos.y) / dir.y;
-            if t > 0.0 && t < t_min {
-                t_min = t;
-                hit_wall = false; // Stop at top, don't bounce
-            }
-        }
-
-        let end_pos = pos + dir * t_min;
-        segments.push((pos, end_pos, t_min));
-
-        // Update position and remaining distance
-        pos = end_pos;
-        remaining_distance -= t_min;
-
-        // If we hit top wall, stop
-        if pos.y >= TOP_WALL - 1.0 {
-            break;
-        }
-
-        // Bounce off side walls
-        if hit_wall {
-            dir.x = -dir.x;
-        } else {
-            break;
-        }
-    }
+    // Follow the shot the way the projectile will fly
+    let max_distance = if has_bouncy {
+        MAX_PREDICTION_DISTANCE
+    } else if powerups.has(PowerUp::EagleEye) {
+        AIM_GUIDE_LENGTH * 2.0
+    } else {
+        AIM_GUIDE_LENGTH
+    };
+    let path = trace_shot(
+        &grid,
+        grid_offset.y,
+        *walls,
+        &portals,
+        &sticky,
+        &bumpers,
+        shooter_transform.translation.truncate(),
+        aim.0,
+        collision_distance(&grid, &powerups, *mode),
+        max_distance,
+    );
+    // Without Bouncy Snord the guide stops at the first bounce
+    let shown = if has_bouncy {
+        MAX_TRAJECTORY_SEGMENTS
+    } else {
+        1
+    };
+    let segments: Vec<(Vec2, Vec2, f32)> = path
+        .segments
+        .into_iter()
+        .map(|(start, end)| (start, end, start.distance(end)))
+        .filter(|&(.., length)| length > 0.0)
+        .take(shown)
+        .collect();
 
     // Update trajectory segment sprites
     // Guide line image is 300px wide (horizontal), anchored at CENTER_LEFT
@@ -0,0 +1,319 @@
+//! Optional shot clock for arcade-style pressure.
+//!
+//! When enabled for the mode in play, the player has a fixed number of
+//! seconds to fire each shot. A radial timer around the shooter shows the
+//! time left. On timeout the shooter either auto-fires straight up or
+//! discards the loaded bubble, which still counts toward the next descent.
+//!
+//! Each mode has its own clock, set up from the settings menu and saved to
+//! `shot_clock.json`.
+
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    clock::GameClock,
+    grid::HexGrid,
+    mode::{GameMode, pressure_mode},
+    projectile::FireProjectile,
+    shooter::{LoadedBubble, LoadedWildcard, Shooter, ShooterState},
+    state::GameLevel,
+};
+use crate::{
+    PausableSystems,
+    save::{SaveFile, save_on_change},
+    screens::Screen,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<ShotClockConfig>();
+    app.init_resource::<ShotClock>();
+    app.register_type::<ShotClockConfig>();
+    app.register_type::<ShotClock>();
+
+    app.add_systems(Startup, load_shot_clock_config);
+    app.add_systems(
+        Update,
+        save_on_change::<ShotClockConfig>.run_if(resource_changed::<ShotClockConfig>),
+    );
+    app.add_systems(OnEnter(Screen::Gameplay), reset_shot_clock);
+
+    app.add_systems(
+        Update,
+        (tick_shot_clock, draw_shot_clock)
+            .chain()
+            .in_set(PausableSystems)
+            .run_if(
+                in_state(Screen::Gameplay)
+                    .and(shot_clock_enabled)
+                    .and(pressure_mode),
+            ),
+    );
+}
+
+/// What happens when the shot clock runs out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Reflect, Serialize, Deserialize)]
+pub enum ShotClockTimeout {
+    /// Fire the loaded bubble straight up.
+    #[default]
+    AutoFire,
+    /// Discard the loaded bubble; the shot still counts toward descent.
+    WastedShot,
+}
+
+impl ShotClockTimeout {
+    pub fn label(self) -> &'static str {
+        match self {
+            ShotClockTimeout::AutoFire => "Auto-fire",
+            ShotClockTimeout::WastedShot => "Wasted Shot",
+        }
+    }
+
+    /// The other behavior, for switching with a button.
+    pub fn next(self) -> Self {
+        match self {
+            ShotClockTimeout::AutoFire => ShotClockTimeout::WastedShot,
+            ShotClockTimeout::WastedShot => ShotClockTimeout::AutoFire,
+        }
+    }
+}
+
+/// Lengths the settings menu cycles through, in seconds.
+pub const SHOT_CLOCK_LENGTHS: [f32; 4] = [5.0, 7.0, 10.0, 15.0];
+
+/// One mode's shot clock (off by default).
+#[derive(Debug, Clone, Copy, PartialEq, Reflect, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ShotClockRules {
+    pub enabled: bool,
+    /// Seconds allowed per shot.
+    pub seconds: f32,
+    pub on_timeout: ShotClockTimeout,
+}
+
+impl Default for ShotClockRules {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            seconds: 7.0,
+            on_timeout: ShotClockTimeout::AutoFire,
+        }
+    }
+}
+
+impl ShotClockRules {
+    /// `"Off"`, or the length, e.g. `"7s"`.
+    pub fn length_label(&self) -> String {
+        if self.enabled {
+            format!("{}s", self.seconds)
+        } else {
+            "Off".to_string()
+        }
+    }
+
+    /// Step to the next of [`SHOT_CLOCK_LENGTHS`], turning the clock off
+    /// after the longest and back on at the shortest.
+    pub fn cycle_length(&mut self) {
+        let next = SHOT_CLOCK_LENGTHS
+            .into_iter()
+            .find(|&length| length > self.seconds);
+        match (self.enabled, next) {
+            (false, _) => {
+                self.enabled = true;
+                self.seconds = SHOT_CLOCK_LENGTHS[0];
+            }
+            (true, Some(length)) => self.seconds = length,
+            (true, None) => self.enabled = false,
+        }
+    }
+}
+
+/// Every mode's shot clock, saved between sessions. Modes without an entry
+/// use the default.
+#[derive(Resource, Debug, Clone, Default, PartialEq, Reflect, Serialize, Deserialize)]
+#[reflect(Resource)]
+#[serde(default)]
+pub struct ShotClockConfig {
+    pub modes: HashMap<GameMode, ShotClockRules>,
+}
+
+impl SaveFile for ShotClockConfig {
+    const FILE_NAME: &'static str = "shot_clock.json";
+    const DESCRIPTION: &'static str = "shot clock settings";
+}
+
+impl ShotClockConfig {
+    pub fn get(&self, mode: GameMode) -> ShotClockRules {
+        self.modes.get(&mode).copied().unwrap_or_default()
+    }
+
+    pub fn get_mut(&mut self, mode: GameMode) -> &mut ShotClockRules {
+        self.modes.entry(mode).or_default()
+    }
+
+    /// Modes with the clock on and their lengths, e.g. `"classic 7s"`, for
+    /// problem reports.
+    pub fn summary(&self) -> String {
+        let enabled: Vec<String> = GameMode::ALL
+            .into_iter()
+            .filter(|&mode| self.get(mode).enabled)
+            .map(|mode| format!("{} {}", mode.name(), self.get(mode).length_label()))
+            .collect();
+        if enabled.is_empty() {
+            "off".to_string()
+        } else {
+            enabled.join(", ")
+        }
+    }
+}
+
+/// Resource tracking the time left for the current shot.
+#[derive(Resource, Debug, Default, Reflect)]
+#[reflect(Resource)]
+pub struct ShotClock {
+    pub remaining: f32,
+}
+
+/// Radius of the radial timer drawn around the shooter, in hex sizes.
+const SHOT_CLOCK_RADIUS: f32 = 2.2;
+
+fn shot_clock_enabled(config: Res<ShotClockConfig>, mode: Res<GameMode>) -> bool {
+    config.get(*mode).enabled
+}
+
+fn load_shot_clock_config(mut config: ResMut<ShotClockConfig>) {
+    *config = ShotClockConfig::load();
+}
+
+/// Reset the shot clock when starting a new game.
+fn reset_shot_clock(
+    config: Res<ShotClockConfig>,
+    mode: Res<GameMode>,
+    mut clock: ResMut<ShotClock>,
+) {
+    clock.remaining = config.get(*mode).seconds;
+}
+
+/// Count down while the shooter is ready and handle timeouts.
+fn tick_shot_clock(
+    time: Res<Time<GameClock>>,
+    config: Res<ShotClockConfig>,
+    mode: Res<GameMode>,
+    mut clock: ResMut<ShotClock>,
+    mut shooter_query: Query<
+        (
+            &Transform,
+            &mut ShooterState,
+            &LoadedBubble,
+            &LoadedWildcard,
+        ),
+        With<Shooter>,
+    >,
+    mut fire_events: MessageWriter<FireProjectile>,
+    mut level: ResMut<GameLevel>,
+) {
+    let Ok((transform, mut state, loaded, wildcard)) = shooter_query.single_mut() else {
+        return;
+    };
+    let rules = config.get(*mode);
+
+    // The clock restarts every time the shooter reloads
+    if *state != ShooterState::Ready {
+        clock.remaining = rules.seconds;
+        return;
+    }
+
+    clock.remaining -= time.delta_secs();
+    if clock.remaining > 0.0 {
+        return;
+    }
+
+    match rules.on_timeout {
+        ShotClockTimeout::AutoFire => {
+            fire_events.write(FireProjectile {
+                position: transform.translation.truncate(),
+                direction: Vec2::Y,
+                color: loaded.0,
+                wildcard: wildcard.0,
+            });
+            info!(
+                "Shot clock expired - auto-firing {:?} straight up",
+                loaded.0
+            );
+        }
+        ShotClockTimeout::WastedShot => {
+            // Reloading without a projectile cycles the queue and checks descent
+            info!("Shot clock expired - {:?} bubble wasted", loaded.0);
+        }
+    }
+
+    *state = ShooterState::Reloading;
+    level.shots_this_round += 1;
+    clock.remaining = rules.seconds;
+}
+
+/// Draw the radial timer around the shooter.
+fn draw_shot_clock(
+    mut gizmos: Gizmos,
+    config: Res<ShotClockConfig>,
+    mode: Res<GameMode>,
+    clock: Res<ShotClock>,
+    grid: Res<HexGrid>,
+    shooter_query: Query<(&Transform, &ShooterState), With<Shooter>>,
+) {
+    let Ok((transform, state)) = shooter_query.single() else {
+        return;
+    };
+    let seconds = config.get(*mode).seconds;
+    if *state != ShooterState::Ready || seconds <= 0.0 {
+        return;
+    }
+
+    let fraction = (clock.remaining / seconds).clamp(0.0, 1.0);
+    // Green when plenty of time is left, red when about to expire
+    let color = Color::srgb(1.0 - fraction, 0.2 + fraction * 0.6, 0.2);
+    let center = transform.translation.truncate();
+
+    gizmos
+        .arc_2d(
+            Isometry2d::from_translation(center),
+            std::f32::consts::TAU * fraction,
+            SHOT_CLOCK_RADIUS * grid.hex_size,
+            color,
+        )
+        .resolution(48);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::save::{decode, encode};
+
+    #[test]
+    fn lengths_cycle_through_off() {
+        let mut rules = ShotClockRules::default();
+        let mut labels = vec![rules.length_label()];
+        for _ in 0..SHOT_CLOCK_LENGTHS.len() + 1 {
+            rules.cycle_length();
+            labels.push(rules.length_label());
+        }
+        assert_eq!(labels, ["Off", "5s", "7s", "10s", "15s", "Off"]);
+    }
+
+    #[test]
+    fn each_mode_keeps_its_own_clock() {
+        let mut config = ShotClockConfig::default();
+        *config.get_mut(GameMode::Kids) = ShotClockRules {
+            enabled: true,
+            seconds: 10.0,
+            on_timeout: ShotClockTimeout::WastedShot,
+        };
+        assert!(!config.get(GameMode::Classic).enabled);
+        assert_eq!(config.summary(), "kids 10s");
+
+        let json = encode(&config).unwrap();
+        assert_eq!(decode::<ShotClockConfig>(&json), Ok(config));
+    }
+}
@@ -0,0 +1,129 @@
+//! Shot history overlay - faint lines along the last few shots.
+//!
+//! Each shot's actual path (launch, every bounce, and where it stopped) is
+//! recorded while it flies, so players can see which bank angles worked.
+//! Press 'T' during gameplay to show or hide the overlay.
+
+use std::collections::{HashMap, VecDeque};
+
+use bevy::{input::common_conditions::input_just_pressed, prelude::*};
+
+use super::projectile::{Projectile, ProjectileSystems};
+use crate::{PausableSystems, controls::SHOT_TRACE_KEY, display::trails_enabled, screens::InGame};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<ShotTraceVisible>();
+    app.init_resource::<ShotHistory>();
+
+    app.add_systems(OnEnter(InGame), clear_shot_history);
+    app.add_systems(
+        Update,
+        (
+            toggle_shot_trace.run_if(input_just_pressed(SHOT_TRACE_KEY)),
+            record_shot_paths
+                .after(ProjectileSystems)
+                .in_set(PausableSystems),
+            draw_shot_history.run_if(shot_trace_visible.and(trails_enabled)),
+        )
+            .run_if(in_state(InGame)),
+    );
+}
+
+/// Number of past shots kept and drawn.
+const SHOT_HISTORY_LEN: usize = 5;
+
+/// Trace color (dark, for the light background).
+const TRACE_COLOR: Color = Color::srgb(0.15, 0.15, 0.15);
+
+/// Alpha of the newest trace; older ones fade toward zero.
+const TRACE_ALPHA: f32 = 0.35;
+
+/// Resource to track if the shot history overlay is visible.
+#[derive(Resource, Default)]
+pub struct ShotTraceVisible(pub bool);
+
+fn shot_trace_visible(visible: Res<ShotTraceVisible>) -> bool {
+    visible.0
+}
+
+/// Recent shot paths, oldest first, plus the shots still in flight.
+#[derive(Resource, Debug, Default)]
+pub struct ShotHistory {
+    pub paths: VecDeque<Vec<Vec2>>,
+    in_flight: HashMap<Entity, InFlight>,
+}
+
+impl ShotHistory {
+    /// Store a finished path, dropping the oldest past the limit.
+    fn push(&mut self, path: Vec<Vec2>) {
+        if path.len() < 2 {
+            return;
+        }
+        self.paths.push_back(path);
+        while self.paths.len() > SHOT_HISTORY_LEN {
+            self.paths.pop_front();
+        }
+    }
+}
+
+/// A shot's path so far.
+#[derive(Debug)]
+struct InFlight {
+    points: Vec<Vec2>,
+    /// Latest position, added as the end point once the shot is gone.
+    last: Vec2,
+    velocity: Vec2,
+}
+
+fn clear_shot_history(mut history: ResMut<ShotHistory>) {
+    *history = ShotHistory::default();
+}
+
+fn toggle_shot_trace(mut visible: ResMut<ShotTraceVisible>) {
+    visible.0 = !visible.0;
+    let state = if visible.0 { "ON" } else { "OFF" };
+    info!("Shot trace: {}", state);
+}
+
+/// Follow each projectile, adding a point whenever its direction changes.
+fn record_shot_paths(
+    mut history: ResMut<ShotHistory>,
+    projectiles: Query<(Entity, &Transform, &Projectile)>,
+) {
+    for (entity, transform, projectile) in &projectiles {
+        let position = transform.translation.truncate();
+        let shot = history.in_flight.entry(entity).or_insert_with(|| InFlight {
+            points: vec![position],
+            last: position,
+            velocity: projectile.velocity,
+        });
+        if projectile.velocity != shot.velocity {
+            // Bounced off a wall
+            shot.points.push(position);
+            shot.velocity = projectile.velocity;
+        }
+        shot.last = position;
+    }
+
+    // Shots that landed (or were rejected) since last frame
+    let finished: Vec<Entity> = history
+        .in_flight
+        .keys()
+        .copied()
+        .filter(|&entity| !projectiles.contains(entity))
+        .collect();
+    for entity in finished {
+        if let Some(mut shot) = history.in_flight.remove(&entity) {
+            shot.points.push(shot.last);
+            history.push(shot.points);
+        }
+    }
+}
+
+fn draw_shot_history(mut gizmos: Gizmos, history: Res<ShotHistory>) {
+    let count = history.paths.len();
+    for (age, path) in history.paths.iter().rev().enumerate() {
+        let alpha = TRACE_ALPHA * (count - age) as f32 / count as f32;
+        gizmos.linestrip_2d(path.iter().copied(), TRACE_COLOR.with_alpha(alpha));
+    }
+}
@@ -0,0 +1,108 @@
+//! Shrinking snords: bubbles get smaller deep into a run.
+//!
+//! Every [`LEVELS_PER_SHRINK`] levels in classic and time attack, the hex
+//! size steps down and more columns fit between the same walls. Bubbles
+//! already on the board shrink in their cells, up toward the ceiling, and the
+//! extra columns come in with the rows that descend after that.
+//!
+//! The size follows the level, so a snapshot is restored at the size it was
+//! taken at (see [`fit_to_level`]).
+
+use bevy::prelude::*;
+
+use super::{
+    bubble::spawn_initial_bubbles,
+    grid::{GridCommands, HexGrid},
+    mode::GameMode,
+    shooter::spawn_shooter,
+    state::{GameLevel, handle_descent, reset_level},
+};
+use crate::screens::InGame;
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(
+        OnEnter(InGame),
+        size_board
+            .after(reset_level)
+            .before(spawn_initial_bubbles)
+            .before(spawn_shooter),
+    );
+    app.add_systems(
+        Update,
+        // After the descent, so the row it spawns is resized with the rest
+        shrink_board
+            .after(handle_descent)
+            .run_if(in_state(InGame).and(resource_changed::<GameLevel>)),
+    );
+}
+
+/// Levels between each step down in size.
+pub const LEVELS_PER_SHRINK: u32 = 20;
+
+/// Hex size at each step, as a fraction of the mode's. Runs past the last
+/// step stay there.
+const SIZE_STEPS: [f32; 4] = [1.0, 0.875, 0.775, 0.7];
+
+/// The hex size for `level` in `mode`.
+pub fn hex_size_at(mode: GameMode, level: u32) -> f32 {
+    if !mode.shrinks() {
+        return mode.hex_size();
+    }
+    let step = ((level / LEVELS_PER_SHRINK) as usize).min(SIZE_STEPS.len() - 1);
+    mode.hex_size() * SIZE_STEPS[step]
+}
+
+/// Size an empty grid for `level` in `mode`, with the columns that fit.
+pub fn fit_to_level(grid: &mut HexGrid, mode: GameMode, level: u32) {
+    grid.hex_size = hex_size_at(mode, level);
+    grid.bounds = mode.grid_bounds().fit_columns(grid.hex_size);
+}
+
+/// Size the board for the level the run starts at, before it's filled.
+fn size_board(mut grid: ResMut<HexGrid>, mode: Res<GameMode>, level: Res<GameLevel>) {
+    fit_to_level(&mut grid, *mode, level.level);
+}
+
+/// Shrink the board in place once the level calls for it.
+fn shrink_board(mut grid: GridCommands, mode: Res<GameMode>, level: Res<GameLevel>) {
+    let hex_size = hex_size_at(*mode, level.level);
+    if hex_size == grid.hex_size {
+        return;
+    }
+    grid.resize(hex_size);
+    info!(
+        "Level {}: snords shrink to {}px, {} columns",
+        level.level,
+        hex_size,
+        grid.bounds.columns_in_row(0)
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::grid::GridBounds;
+
+    #[test]
+    fn every_twenty_levels_fits_more_columns() {
+        let columns = |level| {
+            let mut grid = HexGrid::default();
+            fit_to_level(&mut grid, GameMode::Classic, level);
+            grid.bounds.columns_in_row(0)
+        };
+        assert_eq!(columns(1), 13);
+        assert_eq!(columns(19), 13);
+        assert_eq!(columns(20), 15);
+        assert_eq!(columns(40), 17);
+        assert_eq!(columns(60), 19);
+        assert_eq!(columns(200), 19);
+
+        // Each mode's own bounds already fit its walls
+        let mut grid = HexGrid::default();
+        fit_to_level(&mut grid, GameMode::Classic, 1);
+        assert_eq!(grid.bounds, GridBounds::default());
+        fit_to_level(&mut grid, GameMode::Kids, 60);
+        assert_eq!(grid.hex_size, GameMode::Kids.hex_size());
+        assert_eq!(grid.bounds, GameMode::Kids.grid_bounds());
+    }
+}
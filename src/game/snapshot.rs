@@ -0,0 +1,399 @@
+//! Serializable snapshot of a run in progress.
+//!
+//! Bubble entities only live for one session, so the snapshot stores the grid
+//! as colors by coordinate (and where the bombs and stones are) alongside the resources that make up a run. Save
+//! games, replays, and networked sync can all share this one representation,
+//! and it's reflected so it shows up in the inspector.
+//!
+//! For debugging, F5 quicksaves a snapshot during gameplay and F9 restores it.
+//! F8 runs a stress test: the current run goes through JSON a thousand times
+//! and back into the world, and the first field that comes back different is
+//! logged.
+
+use bevy::{ecs::system::RunSystemOnce, input::common_conditions::input_just_pressed, prelude::*};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::{
+    bubble::{Bubble, BubbleColor, BubbleKind, GameAssets, spawn_bomb, spawn_bubble, spawn_stone},
+    bubble_material::BubbleMaterial,
+    endless::BoardClears,
+    grid::HexGrid,
+    hex::{GridOffset, HexCoord},
+    mode::GameMode,
+    powerups::UnlockedPowerUps,
+    projectile::DangerGrace,
+    shooter::{
+        LoadedBubble, LoadedWildcard, NextBubble, SecondNextBubble, Shooter, ThirdNextBubble,
+    },
+    shrink::fit_to_level,
+    state::{ContinueState, GameLevel, GameScore},
+    wildfire::Wildfire,
+};
+use crate::{
+    controls::{QUICKLOAD_KEY, QUICKSAVE_KEY, STRESS_TEST_KEY},
+    screens::Screen,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<GridCell>();
+    app.register_type::<ShooterQueue>();
+    app.register_type::<GameSnapshot>();
+    app.init_resource::<QuickSave>();
+
+    app.add_systems(
+        Update,
+        (
+            quicksave.run_if(input_just_pressed(QUICKSAVE_KEY)),
+            quickload.run_if(input_just_pressed(QUICKLOAD_KEY)),
+            run_stress_test.run_if(input_just_pressed(STRESS_TEST_KEY)),
+        )
+            .run_if(in_state(Screen::Gameplay)),
+    );
+}
+
+/// A bubble on the grid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Reflect, Serialize, Deserialize)]
+#[reflect(Serialize, Deserialize)]
+pub struct GridCell {
+    pub coord: HexCoord,
+    pub color: BubbleColor,
+}
+
+/// The loaded bubble and the three previews after it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Reflect, Serialize, Deserialize)]
+#[reflect(Serialize, Deserialize)]
+pub struct ShooterQueue {
+    pub loaded: BubbleColor,
+    pub next: BubbleColor,
+    pub second_next: BubbleColor,
+    pub third_next: BubbleColor,
+    /// Whether the loaded bubble is a wildcard.
+    #[serde(default)]
+    pub loaded_wildcard: bool,
+}
+
+/// Everything needed to resume a run.
+#[derive(Debug, Clone, PartialEq, Reflect, Serialize, Deserialize)]
+#[reflect(Serialize, Deserialize)]
+pub struct GameSnapshot {
+    /// Bubbles sorted by row, then column.
+    pub grid: Vec<GridCell>,
+    /// Cells holding bombs, in the same order.
+    #[serde(default)]
+    pub bombs: Vec<HexCoord>,
+    /// Cells holding stones, in the same order.
+    #[serde(default)]
+    pub stones: Vec<HexCoord>,
+    pub grid_offset: GridOffset,
+    /// `None` if the shooter hasn't been spawned yet.
+    pub shooter_queue: Option<ShooterQueue>,
+    pub powerups: UnlockedPowerUps,
+    pub score: GameScore,
+    pub level: GameLevel,
+    pub continue_state: ContinueState,
+    pub danger_grace: DangerGrace,
+    /// Colors merged when the snapshot was taken.
+    #[serde(default)]
+    pub wildfire: Wildfire,
+    /// Boards cleared so far, in modes that go on after a clear.
+    #[serde(default)]
+    pub board_clears: BoardClears,
+}
+
+impl GameSnapshot {
+    /// Capture the current run.
+    pub fn capture(world: &mut World) -> Self {
+        let bubbles: Vec<Bubble> = world
+            .query::<&Bubble>()
+            .iter(world)
+            .filter(|bubble| world.resource::<HexGrid>().get(bubble.coord).is_some())
+            .cloned()
+            .collect();
+        let mut grid: Vec<GridCell> = bubbles
+            .iter()
+            .filter_map(|bubble| {
+                Some(GridCell {
+                    coord: bubble.coord,
+                    color: bubble.match_color()?,
+                })
+            })
+            .collect();
+        grid.sort_by_key(|cell| (cell.coord.r, cell.coord.q));
+        let cells_of = |kind: BubbleKind| {
+            let mut cells: Vec<HexCoord> = bubbles
+                .iter()
+                .filter(|bubble| bubble.kind == kind)
+                .map(|bubble| bubble.coord)
+                .collect();
+            cells.sort_by_key(|coord| (coord.r, coord.q));
+            cells
+        };
+        let bombs = cells_of(BubbleKind::Bomb);
+        let stones = cells_of(BubbleKind::Stone);
+
+        let shooter_queue = world
+            .query_filtered::<(
+                &LoadedBubble,
+                &NextBubble,
+                &SecondNextBubble,
+                &ThirdNextBubble,
+                &LoadedWildcard,
+            ), With<Shooter>>()
+            .iter(world)
+            .next()
+            .map(
+                |(loaded, next, second_next, third_next, wildcard)| ShooterQueue {
+                    loaded: loaded.0,
+                    next: next.0,
+                    second_next: second_next.0,
+                    third_next: third_next.0,
+                    loaded_wildcard: wildcard.0,
+                },
+            );
+
+        Self {
+            grid,
+            bombs,
+            stones,
+            grid_offset: world.resource::<GridOffset>().clone(),
+            shooter_queue,
+            powerups: world.resource::<UnlockedPowerUps>().clone(),
+            score: world.resource::<GameScore>().clone(),
+            level: world.resource::<GameLevel>().clone(),
+            continue_state: world.resource::<ContinueState>().clone(),
+            danger_grace: world.resource::<DangerGrace>().clone(),
+            wildfire: *world.resource::<Wildfire>(),
+            board_clears: *world.resource::<BoardClears>(),
+        }
+    }
+
+    /// Replace the current run with this snapshot.
+    ///
+    /// Only valid during gameplay, once the game assets are loaded.
+    pub fn restore(&self, world: &mut World) {
+        world.insert_resource(self.grid_offset.clone());
+        world.insert_resource(self.powerups.clone());
+        world.insert_resource(self.score.clone());
+        world.insert_resource(self.level.clone());
+        world.insert_resource(self.continue_state.clone());
+        world.insert_resource(self.danger_grace.clone());
+        world.insert_resource(self.wildfire);
+        world.insert_resource(self.board_clears);
+
+        if let Some(queue) = self.shooter_queue {
+            let mut shooters = world.query_filtered::<(
+                &mut LoadedBubble,
+                &mut NextBubble,
+                &mut SecondNextBubble,
+                &mut ThirdNextBubble,
+                &mut LoadedWildcard,
+            ), With<Shooter>>();
+            for (mut loaded, mut next, mut second_next, mut third_next, mut wildcard) in
+                shooters.iter_mut(world)
+            {
+                loaded.0 = queue.loaded;
+                next.0 = queue.next;
+                second_next.0 = queue.second_next;
+                third_next.0 = queue.third_next;
+                wildcard.0 = queue.loaded_wildcard;
+            }
+        }
+
+        let grid = self.grid.clone();
+        let bombs = self.bombs.clone();
+        let stones = self.stones.clone();
+        let level = self.level.level;
+        let result = world.run_system_once(
+            move |mut commands: Commands,
+                  mut hex_grid: ResMut<HexGrid>,
+                  mut meshes: ResMut<Assets<Mesh>>,
+                  mut materials: ResMut<Assets<BubbleMaterial>>,
+                  bubbles: Query<Entity, With<Bubble>>,
+                  grid_offset: Res<GridOffset>,
+                  mode: Res<GameMode>,
+                  game_assets: Option<Res<GameAssets>>| {
+                for entity in &bubbles {
+                    commands.entity(entity).despawn();
+                }
+                hex_grid.clear();
+                // The offset was saved at the level's size
+                fit_to_level(&mut hex_grid, *mode, level);
+
+                for cell in &grid {
+                    let entity = spawn_bubble(
+                        &mut commands,
+                        &mut meshes,
+                        &mut materials,
+                        cell.coord,
+                        cell.color,
+                        hex_grid.hex_size,
+                        grid_offset.y,
+                        game_assets.as_deref(),
+                    );
+                    hex_grid.insert(cell.coord, entity);
+                }
+                for &coord in &bombs {
+                    let entity = spawn_bomb(
+                        &mut commands,
+                        &mut meshes,
+                        &mut materials,
+                        coord,
+                        hex_grid.hex_size,
+                        grid_offset.y,
+                        game_assets.as_deref(),
+                    );
+                    hex_grid.insert(coord, entity);
+                }
+                for &coord in &stones {
+                    let entity = spawn_stone(
+                        &mut commands,
+                        &mut meshes,
+                        &mut materials,
+                        coord,
+                        hex_grid.hex_size,
+                        grid_offset.y,
+                        game_assets.as_deref(),
+                    );
+                    hex_grid.insert(coord, entity);
+                }
+            },
+        );
+        match result {
+            Ok(()) => info!(
+                "Restored snapshot with {} bubbles, {} bombs, and {} stones",
+                self.grid.len(),
+                self.bombs.len(),
+                self.stones.len()
+            ),
+            Err(err) => warn!("Failed to restore snapshot grid: {}", err),
+        }
+    }
+}
+
+/// Round trips made by the F8 stress test.
+pub const STRESS_TEST_ROUNDS: usize = 1000;
+
+/// Send the current run through JSON `rounds` times, feeding each copy into
+/// the next, then restore the last copy and capture it again. Every copy is
+/// compared with the original; on a mismatch, returns a description of the
+/// first field that differs.
+pub fn stress_test(world: &mut World, rounds: usize) -> Result<(), String> {
+    let original = GameSnapshot::capture(world);
+    let expected = to_json(&original)?;
+
+    let mut copy = original.clone();
+    for round in 1..=rounds {
+        let json = serde_json::to_string(&copy).map_err(|err| format!("round {round}: {err}"))?;
+        copy = serde_json::from_str(&json).map_err(|err| format!("round {round}: {err}"))?;
+        if copy != original {
+            let diff = first_difference("snapshot", &expected, &to_json(&copy)?);
+            return Err(format!(
+                "round {round}: {}",
+                diff.unwrap_or_else(|| "equal as JSON, unequal as a snapshot".to_string())
+            ));
+        }
+    }
+
+    copy.restore(world);
+    let restored = GameSnapshot::capture(world);
+    if restored != original {
+        let diff = first_difference("snapshot", &expected, &to_json(&restored)?);
+        return Err(format!(
+            "after restoring: {}",
+            diff.unwrap_or_else(|| "equal as JSON, unequal as a snapshot".to_string())
+        ));
+    }
+    Ok(())
+}
+
+fn to_json(snapshot: &GameSnapshot) -> Result<Value, String> {
+    serde_json::to_value(snapshot).map_err(|err| err.to_string())
+}
+
+/// The path to the first place `a` and `b` differ, with both values there.
+fn first_difference(path: &str, a: &Value, b: &Value) -> Option<String> {
+    match (a, b) {
+        (Value::Object(a), Value::Object(b)) => {
+            for (key, a_value) in a {
+                let path = format!("{path}.{key}");
+                let Some(b_value) = b.get(key) else {
+                    return Some(format!("{path}: {a_value} vs missing"));
+                };
+                if let Some(diff) = first_difference(&path, a_value, b_value) {
+                    return Some(diff);
+                }
+            }
+            b.iter()
+                .find(|(key, _)| !a.contains_key(*key))
+                .map(|(key, b_value)| format!("{path}.{key}: missing vs {b_value}"))
+        }
+        (Value::Array(a), Value::Array(b)) => {
+            for (index, (a_value, b_value)) in a.iter().zip(b).enumerate() {
+                if let Some(diff) = first_difference(&format!("{path}[{index}]"), a_value, b_value)
+                {
+                    return Some(diff);
+                }
+            }
+            (a.len() != b.len()).then(|| format!("{path}: {} items vs {}", a.len(), b.len()))
+        }
+        _ => (a != b).then(|| format!("{path}: {a} vs {b}")),
+    }
+}
+
+fn run_stress_test(world: &mut World) {
+    match stress_test(world, STRESS_TEST_ROUNDS) {
+        Ok(()) => info!("Snapshot survived {} round trips", STRESS_TEST_ROUNDS),
+        Err(diff) => warn!("Snapshot stress test failed at {}", diff),
+    }
+}
+
+/// The snapshot taken with F5, if any.
+#[derive(Resource, Default)]
+pub struct QuickSave(pub Option<GameSnapshot>);
+
+fn quicksave(world: &mut World) {
+    let snapshot = GameSnapshot::capture(world);
+    match serde_json::to_string(&snapshot) {
+        Ok(json) => info!(
+            "Quicksaved {} bubbles ({} bytes as JSON)",
+            snapshot.grid.len(),
+            json.len()
+        ),
+        Err(err) => warn!("Failed to serialize snapshot: {}", err),
+    }
+    world.resource_mut::<QuickSave>().0 = Some(snapshot);
+}
+
+fn quickload(world: &mut World) {
+    let Some(snapshot) = world.resource::<QuickSave>().0.clone() else {
+        info!("No quicksave to load");
+        return;
+    };
+    snapshot.restore(world);
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn differences_name_the_first_divergent_field() {
+        let a = json!({ "score": { "score": 10 }, "grid": [1, 2, 3] });
+        assert_eq!(first_difference("snapshot", &a, &a), None);
+
+        let b = json!({ "score": { "score": 12 }, "grid": [1, 5] });
+        assert_eq!(
+            first_difference("snapshot", &a, &b).as_deref(),
+            Some("snapshot.grid[1]: 2 vs 5")
+        );
+
+        let c = json!({ "score": { "score": 12 }, "grid": [1, 2] });
+        assert_eq!(
+            first_difference("snapshot", &a, &c).as_deref(),
+            Some("snapshot.grid: 3 items vs 2")
+        );
+    }
+}
@@ -1,35 +1,91 @@
 //! Game state management - score, win/lose conditions, level progression.
 //!
-//! Win: Clear all bubbles from the grid.
+//! Win: Clear all bubbles from the grid, or meet the campaign level's goal.
+//! In Endless a clear scores a bonus and the run goes on (see `endless`).
 //! Lose: Bubbles reach the danger zone (bottom of grid).
 //!
 //! Level system: After X shots, all bubbles descend and a new row spawns.
+//!
+//! Time attack: the run also ends when [`TimeAttackClock`] runs out. Popped
+//! and dropped bubbles add time, and every stretch survived scores a bonus.
 
 use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
 
 use super::{
-    bubble::{Bubble, BubbleColor, GameAssets, spawn_bubble},
-    cluster::{ClusterPopped, FloatingBubblesRemoved},
-    grid::HexGrid,
-    hex::{GridOffset, HEX_SIZE, HexCoord},
-    highscore::{HighScores, ScoreEntry},
-    powerups::{PowerUp, PowerUpChoices, UnlockedPowerUps},
-    projectile::BubbleInDangerZone,
-    shooter::SHOOTER_Y,
+    bubble::{Bubble, BubbleAge, BubbleColor, GameAssets},
+    bubble_material::BubbleMaterial,
+    bumpers::{BUMPER_POINTS, BumperHit},
+    campaign::{ActiveLevel, LevelGoal, campaign_active},
+    clock::GameClock,
+    cluster::{ClusterPopped, ClusterSystems, FloatingBubblesRemoved},
+    color_clear::ColorCleared,
+    companion::{Companion, bonus_points},
+    drills::drill_active,
+    grid::{GridCommands, HexGrid},
+    handicap::{RunHandicap, apply_handicap},
+    hex::HexCoord,
+    highscore::{HighScores, LeaderboardSettings, ScoreEntry},
+    messages::AddGameMessage,
+    mode::{GameMode, LevelColors, pressure_mode, time_attack_mode},
+    polish::PopAnimation,
+    powerups::{PowerUp, PowerUpCatalog, PowerUpChoices, UnlockedPowerUps},
+    projectile::{BubbleInDangerZone, BubbleLanded, DANGER_LINE_Y},
+    rng::{GameRng, seed_game_rng},
+    score_zones::{BubbleDropped, FallingBubble},
+    telemetry::RunOutcome,
+    weekly::{ActiveChallenge, challenge_active},
+};
+use crate::{
+    PausableSystems,
+    launch::LaunchOptions,
+    menus::Menu,
+    profile::Profile,
+    save::SaveFile,
+    screens::{InGame, RunPhase, Screen},
+    theme::toast::ShowToast,
 };
-use crate::{PausableSystems, Pause, menus::Menu, screens::Screen};
 
 pub(super) fn plugin(app: &mut App) {
     app.init_resource::<GameScore>();
     app.init_resource::<GameLevel>();
+    app.init_resource::<ContinueState>();
+    app.init_resource::<FinalOutcome>();
+    app.init_resource::<TimeAttackClock>();
     app.register_type::<GameScore>();
     app.register_type::<GameLevel>();
+    app.register_type::<ContinueState>();
+    app.register_type::<TimeAttackClock>();
+
+    app.add_game_message::<TriggerDescent>("The grid should descend a row");
+    app.add_game_message::<ContinueRun>("The player continued from game over");
+    app.add_game_message::<GameEnded>("A run ended (won, lost, or abandoned)");
+    app.add_game_message::<PerfectClear>("The board was cleared without a wasted shot");
+    app.add_game_message::<BoardCleared>("The board was cleared and the run goes on");
 
-    app.add_message::<TriggerDescent>();
+    app.add_systems(
+        OnEnter(InGame),
+        (
+            reset_score,
+            reset_level.after(apply_handicap),
+            reset_powerups.after(seed_game_rng),
+            reset_continue,
+            reset_time_attack_clock,
+            // No score pressure in zen mode
+            spawn_score_ui.run_if(pressure_mode),
+        ),
+    );
 
+    // Continue runs while the game over menu has the board frozen, and note
+    // how runs end (abandoned in the pause menu too)
     app.add_systems(
-        OnEnter(Screen::Gameplay),
-        (reset_score, reset_level, reset_powerups, spawn_score_ui),
+        Update,
+        (handle_continue, note_final_outcome).run_if(in_state(Screen::Gameplay)),
+    );
+    // Weekly challenge runs have their own leaderboard
+    app.add_systems(
+        OnExit(Screen::Gameplay),
+        record_final_score.run_if(not(challenge_active)),
     );
 
     app.add_systems(
@@ -37,13 +93,23 @@ pub(super) fn plugin(app: &mut App) {
         (
             update_score,
             update_score_ui,
-            handle_descent,
-            check_win_condition,
-            check_lose_condition,
-            check_danger_zone_game_over,
+            count_wasted_shots.after(ClusterSystems),
+            (
+                // Campaign walls close in instead
+                handle_descent.run_if(not(campaign_active)),
+                // Drills have their own goals
+                check_win_condition.run_if(not(drill_active)),
+                check_lose_condition,
+                check_danger_zone_game_over,
+            )
+                .run_if(pressure_mode),
+            (tick_time_attack_clock, check_time_up)
+                .chain()
+                .after(ClusterSystems)
+                .run_if(time_attack_mode),
         )
             .in_set(PausableSystems)
-            .run_if(in_state(Screen::Gameplay)),
+            .run_if(in_state(InGame)),
     );
 }
 
@@ -55,9 +121,94 @@ struct ScoreText;
 #[derive(Message, Debug, Clone)]
 pub struct TriggerDescent;
 
-/// Resource tracking the current level and descent timing.
-#[derive(Resource, Debug, Reflect)]
+/// Message sent when a run ends, however it ended. The final score is
+/// recorded from here so every ending goes through the same path.
+#[derive(Message, Debug, Clone)]
+pub struct GameEnded {
+    pub outcome: RunOutcome,
+}
+
+/// Message sent when the board is cleared without a wasted shot since the
+/// last descent.
+#[derive(Message, Debug, Clone)]
+pub struct PerfectClear;
+
+/// Message sent when the board is cleared in a mode that keeps going after a
+/// clear, for a fresh board to be put up.
+#[derive(Message, Debug, Clone)]
+pub struct BoardCleared;
+
+/// Message requesting the run be revived from the game over menu.
+#[derive(Message, Debug, Clone)]
+pub struct ContinueRun;
+
+/// Number of bottom rows removed when continuing after game over.
+const CONTINUE_TRIM_ROWS: i32 = 3;
+
+/// Resource tracking whether the once-per-game continue has been used.
+#[derive(Resource, Debug, Clone, Default, PartialEq, Reflect, Serialize, Deserialize)]
+#[reflect(Resource, Serialize, Deserialize)]
+pub struct ContinueState {
+    pub used: bool,
+}
+
+/// How the run in play ended, once it has. Cleared again if it's continued.
+#[derive(Resource, Debug, Default)]
+struct FinalOutcome(Option<RunOutcome>);
+
+/// Seconds on the clock when a time attack run starts.
+pub const TIME_ATTACK_SECONDS: f32 = 120.0;
+
+/// Seconds added for each bubble popped or dropped in time attack.
+pub const TIME_PER_BUBBLE: f32 = 0.5;
+
+/// Seconds survived per time attack survival bonus.
+pub const SURVIVAL_BONUS_SECONDS: f32 = 10.0;
+
+/// Points for each [`SURVIVAL_BONUS_SECONDS`] survived in time attack.
+pub const SURVIVAL_BONUS_POINTS: u32 = 50;
+
+/// Seconds a continue puts back on an empty time attack clock.
+const CONTINUE_SECONDS: f32 = 30.0;
+
+/// The time attack countdown. Unused in other modes.
+#[derive(Resource, Debug, Clone, PartialEq, Reflect)]
 #[reflect(Resource)]
+pub struct TimeAttackClock {
+    /// Seconds left before the run ends.
+    pub remaining: f32,
+    /// Unpaused seconds survived this run.
+    pub elapsed: f32,
+}
+
+impl Default for TimeAttackClock {
+    fn default() -> Self {
+        Self {
+            remaining: TIME_ATTACK_SECONDS,
+            elapsed: 0.0,
+        }
+    }
+}
+
+impl TimeAttackClock {
+    /// Run the clock for `delta` seconds, adding time for `cleared` bubbles.
+    /// Returns the survival bonus earned along the way.
+    pub fn tick(&mut self, delta: f32, cleared: usize) -> u32 {
+        let stretches_before = (self.elapsed / SURVIVAL_BONUS_SECONDS) as u32;
+        self.elapsed += delta;
+        self.remaining = (self.remaining - delta + cleared as f32 * TIME_PER_BUBBLE).max(0.0);
+        let stretches = (self.elapsed / SURVIVAL_BONUS_SECONDS) as u32;
+        (stretches - stretches_before) * SURVIVAL_BONUS_POINTS
+    }
+
+    pub fn is_up(&self) -> bool {
+        self.remaining <= 0.0
+    }
+}
+
+/// Resource tracking the current level and descent timing.
+#[derive(Resource, Debug, Clone, PartialEq, Reflect, Serialize, Deserialize)]
+#[reflect(Resource, Serialize, Deserialize)]
 pub struct GameLevel {
     /// Current level number (starts at 1).
     pub level: u32,
@@ -65,14 +216,33 @@ pub struct GameLevel {
     pub shots_until_descent: u32,
     /// Shots fired since last descent.
     pub shots_this_round: u32,
+    /// Shots since last descent that landed without popping anything.
+    #[serde(default)]
+    pub wasted_shots_this_round: u32,
+}
+
+/// Shots between descents at the start of a run.
+pub const STARTING_SHOTS_PER_DESCENT: u32 = 8;
+
+/// Fewest shots between descents, however high the level.
+pub const MIN_SHOTS_PER_DESCENT: u32 = 5;
+
+/// Shots between descents at `level`, for a run that starts with `starting`:
+/// one fewer every 10 levels, down to [`MIN_SHOTS_PER_DESCENT`] (or
+/// `starting`, if that's already fewer).
+pub fn shots_per_descent(starting: u32, level: u32) -> u32 {
+    starting
+        .saturating_sub(level / 10)
+        .max(MIN_SHOTS_PER_DESCENT.min(starting))
 }
 
 impl Default for GameLevel {
     fn default() -> Self {
         Self {
             level: 1,
-            shots_until_descent: 8,
+            shots_until_descent: STARTING_SHOTS_PER_DESCENT,
             shots_this_round: 0,
+            wasted_shots_this_round: 0,
         }
     }
 }
@@ -80,16 +250,28 @@ impl Default for GameLevel {
 impl GameLevel {
     pub fn reset(&mut self) {
         self.level = 1;
-        self.shots_until_descent = 8;
+        self.shots_until_descent = STARTING_SHOTS_PER_DESCENT;
         self.shots_this_round = 0;
+        self.wasted_shots_this_round = 0;
     }
 
     /// Called after each descent to advance the level.
     pub fn advance_level(&mut self) {
         self.level += 1;
         self.shots_this_round = 0;
+        self.wasted_shots_this_round = 0;
         // Ramp down every 10 levels: 8 -> 7 -> 6 -> 5 (minimum)
-        self.shots_until_descent = 8u32.saturating_sub(self.level / 10).max(5);
+        self.shots_until_descent = shots_per_descent(STARTING_SHOTS_PER_DESCENT, self.level);
+    }
+
+    /// Shots in a round before the board descends, with Procrastisnord's
+    /// extra two.
+    pub fn descent_threshold(&self, powerups: &UnlockedPowerUps) -> u32 {
+        if powerups.has(PowerUp::Procrastisnord) {
+            self.shots_until_descent + 2
+        } else {
+            self.shots_until_descent
+        }
     }
 
     /// Returns shots remaining until next descent.
@@ -100,17 +282,23 @@ impl GameLevel {
 }
 
 /// Points awarded per bubble popped in a cluster.
-const POINTS_PER_BUBBLE: u32 = 10;
+pub const POINTS_PER_BUBBLE: u32 = 10;
 
 /// Bonus multiplier for floating bubbles.
-const FLOATING_BONUS_MULTIPLIER: u32 = 2;
+pub const FLOATING_BONUS_MULTIPLIER: u32 = 2;
 
-/// The Y position below which bubbles trigger game over.
-const DANGER_LINE_Y: f32 = SHOOTER_Y + 40.0;
+/// Bonus for popping the last bubble of a color.
+pub const COLOR_CLEAR_BONUS: u32 = 250;
+
+/// Bonus for clearing the board without a wasted shot since the last descent.
+pub const PERFECT_CLEAR_BONUS: u32 = 2000;
+
+/// Bonus for clearing the board in a mode that keeps going after a clear.
+pub const BOARD_CLEAR_BONUS: u32 = 5000;
 
 /// Resource tracking the current game score.
-#[derive(Resource, Debug, Default, Reflect)]
-#[reflect(Resource)]
+#[derive(Resource, Debug, Clone, Default, PartialEq, Reflect, Serialize, Deserialize)]
+#[reflect(Resource, Serialize, Deserialize)]
 pub struct GameScore {
     pub score: u32,
     pub bubbles_popped: u32,
@@ -132,33 +320,110 @@ fn reset_score(mut score: ResMut<GameScore>) {
 }
 
 /// Reset level when starting a new game.
-fn reset_level(mut level: ResMut<GameLevel>) {
+pub(super) fn reset_level(
+    mut level: ResMut<GameLevel>,
+    launch: Res<LaunchOptions>,
+    handicap: Res<RunHandicap>,
+) {
     level.reset();
-    info!("Level reset to 1");
+    // `--level` and handicaps start the run further along the descent ramp
+    let start = launch
+        .level
+        .unwrap_or(1)
+        .max(handicap.handicap.start_level());
+    while level.level < start {
+        level.advance_level();
+    }
+    info!("Level reset to {}", level.level);
 }
 
-/// Reset power-ups when starting a new game.
-fn reset_powerups(mut powerups: ResMut<UnlockedPowerUps>) {
+/// Reset power-ups when starting a new game. Pip brings a tier 1 one along.
+pub(super) fn reset_powerups(
+    mut powerups: ResMut<UnlockedPowerUps>,
+    profile: Res<Profile>,
+    catalog: Res<PowerUpCatalog>,
+    mut rng: ResMut<GameRng>,
+) {
     powerups.reset();
+    if profile.companion == Some(Companion::Pip)
+        && let Some(power) = catalog.random_from_tier(1, &mut rng.gameplay)
+    {
+        powerups.add(power);
+    }
+}
+
+/// Reset the continue option when starting a new game.
+fn reset_continue(mut continue_state: ResMut<ContinueState>) {
+    continue_state.used = false;
+}
+
+fn reset_time_attack_clock(mut clock: ResMut<TimeAttackClock>) {
+    *clock = TimeAttackClock::default();
+}
+
+/// Revive the run: trim the bottom rows, halve the score, and resume play.
+/// A time attack run that ran out gets some time back too.
+fn handle_continue(
+    mut commands: Commands,
+    mut continue_events: MessageReader<ContinueRun>,
+    mut continue_state: ResMut<ContinueState>,
+    mut grid: ResMut<HexGrid>,
+    mut score: ResMut<GameScore>,
+    mut clock: ResMut<TimeAttackClock>,
+    transform_query: Query<&Transform>,
+    mut next_menu: ResMut<NextState<Menu>>,
+    mut next_phase: ResMut<NextState<RunPhase>>,
+) {
+    if continue_events.read().next().is_none() || continue_state.used {
+        return;
+    }
+    continue_state.used = true;
+
+    let trimmed = grid.trim_bottom_rows(CONTINUE_TRIM_ROWS);
+    for entity in &trimmed {
+        let current_scale = transform_query
+            .get(*entity)
+            .map(|t| t.scale)
+            .unwrap_or(Vec3::ONE);
+        commands
+            .entity(*entity)
+            .insert(PopAnimation::new(current_scale));
+    }
+
+    score.score /= 2;
+    clock.remaining = clock.remaining.max(CONTINUE_SECONDS);
+    info!(
+        "Continuing run: removed {} bubbles, score halved to {}",
+        trimmed.len(),
+        score.score
+    );
+
+    next_menu.set(Menu::None);
+    next_phase.set(RunPhase::Playing);
 }
 
 /// Handle bubble descent when triggered.
-fn handle_descent(
+pub(super) fn handle_descent(
     mut commands: Commands,
     mut meshes: ResMut<Assets<Mesh>>,
-    mut materials: ResMut<Assets<ColorMaterial>>,
-    mut grid: ResMut<HexGrid>,
+    mut materials: ResMut<Assets<BubbleMaterial>>,
+    mut grid: GridCommands,
     mut level: ResMut<GameLevel>,
-    mut grid_offset: ResMut<GridOffset>,
-    mut bubble_query: Query<(&Bubble, &mut Transform)>,
     mut descent_events: MessageReader<TriggerDescent>,
     mut danger_events: MessageWriter<BubbleInDangerZone>,
+    mut ages: Query<&mut BubbleAge>,
     // Power-up system
     unlocked_powerups: Res<UnlockedPowerUps>,
+    catalog: Res<PowerUpCatalog>,
     mut powerup_choices: ResMut<PowerUpChoices>,
     mut next_menu: ResMut<NextState<Menu>>,
-    mut next_pause: ResMut<NextState<Pause>>,
-    game_assets: Res<GameAssets>,
+    mut next_phase: ResMut<NextState<RunPhase>>,
+    (game_assets, mode, level_colors, mut rng): (
+        Res<GameAssets>,
+        Res<GameMode>,
+        Res<LevelColors>,
+        ResMut<GameRng>,
+    ),
 ) {
     // Only process if we received a descent trigger
     if descent_events.read().next().is_none() {
@@ -168,67 +433,73 @@ fn handle_descent(
     info!("Descent triggered! Moving grid down...");
 
     // Move grid down by one row height (bubbles keep their coordinates)
-    grid_offset.y -= HEX_SIZE * 1.5;
+    let row_height = grid.hex_size * 1.5;
+    grid.shift_down(row_height);
 
-    // Update all bubble transforms with new offset (coords stay the same)
-    for (_coord, &entity) in grid.iter() {
-        if let Ok((bubble, mut transform)) = bubble_query.get_mut(entity) {
-            let new_pos = bubble.coord.to_pixel_with_offset(HEX_SIZE, grid_offset.y);
-            transform.translation.x = new_pos.x;
-            transform.translation.y = new_pos.y;
+    // Everyone already on the board survived another descent
+    for (_, &entity) in grid.iter() {
+        if let Ok(mut age) = ages.get_mut(entity) {
+            age.0 += 1;
         }
     }
 
-    // Find the current minimum row to spawn new row above it
-    let min_r = grid.iter().map(|(coord, _)| coord.r).min().unwrap_or(0);
-    let new_row_r = min_r - 1;
+    // Spawn the new row above the current top row. Its r is one less, so its
+    // parity alternates with the row below and positions come from the cell.
+    // An empty board starts again along the ceiling.
+    let new_row_r = grid
+        .iter()
+        .map(|(coord, _)| coord.r - 1)
+        .min()
+        .unwrap_or_else(|| grid.ceiling_row());
 
     // Spawn new row at top
     let bounds = grid.bounds;
     for q in bounds.min_q..=bounds.max_q {
         let coord = HexCoord::new(q, new_row_r);
-        let color = BubbleColor::random();
-        let entity = spawn_bubble(
+        let color = BubbleColor::random_with(level_colors.palette(*mode), &mut rng.gameplay);
+        grid.spawn(
             &mut commands,
             &mut meshes,
             &mut materials,
             coord,
             color,
-            grid_offset.y,
             Some(&game_assets),
         );
-        grid.insert(coord, entity);
     }
 
     // Check for game over (any bubble below danger line after descent)
-    for (_coord, &entity) in grid.iter() {
-        if let Ok((_, transform)) = bubble_query.get(entity)
-            && transform.translation.y < DANGER_LINE_Y
-        {
+    for coord in grid.coords() {
+        let y = grid.position(coord).y;
+        if y < DANGER_LINE_Y {
             info!(
                 "GAME OVER! Descent pushed bubble into danger zone at y={}",
-                transform.translation.y
+                y
             );
             danger_events.write(BubbleInDangerZone);
             return;
         }
     }
 
+    next_phase.set(RunPhase::DescentAnimation);
+
     // Advance level
     level.advance_level();
     info!(
         "Level {} - next descent in {} shots (grid_offset.y = {})",
-        level.level, level.shots_until_descent, grid_offset.y
+        level.level,
+        level.shots_until_descent,
+        grid.origin_y()
     );
 
     // Check for power-up milestone (every 5 levels)
     if level.level > 0 && level.level.is_multiple_of(5) {
-        let choices = PowerUp::random_choices(level.level, &unlocked_powerups.powers);
+        let choices =
+            catalog.random_choices(level.level, &unlocked_powerups.powers, &mut rng.gameplay);
         if !choices.is_empty() {
             info!("Power-up selection at level {}!", level.level);
             powerup_choices.choices = choices;
             powerup_choices.level = level.level;
-            next_pause.set(Pause(true));
+            next_phase.set(RunPhase::PowerUpChoice);
             next_menu.set(Menu::PowerUpSelect);
         }
     }
@@ -255,17 +526,22 @@ fn spawn_score_ui(mut commands: Commands, game_font: Res<crate::theme::GameFont>
             justify_content: JustifyContent::Center,
             ..default()
         },
-        DespawnOnExit(Screen::Gameplay),
+        DespawnOnExit(InGame),
     ));
 }
 
-/// Update the score text when score or level changes.
+/// Update the score text when score or level changes, with the countdown
+/// in time attack.
 fn update_score_ui(
     score: Res<GameScore>,
     level: Res<GameLevel>,
+    mode: Res<GameMode>,
+    clock: Res<TimeAttackClock>,
     mut query: Query<&mut Text, With<ScoreText>>,
 ) {
-    if !score.is_changed() && !level.is_changed() {
+    let timed = *mode == GameMode::TimeAttack;
+    let clock_changed = timed && clock.is_changed();
+    if !score.is_changed() && !level.is_changed() && !clock_changed {
         return;
     }
     for mut text in &mut query {
@@ -275,16 +551,76 @@ fn update_score_ui(
             level.level,
             level.shots_remaining()
         );
+        if timed {
+            text.push_str(&format!("     Time: {}", countdown_label(clock.remaining)));
+        }
+    }
+}
+
+/// `m:ss`, rounded up so the clock reads 0:00 only once time is up.
+fn countdown_label(remaining: f32) -> String {
+    let secs = remaining.ceil() as u32;
+    format!("{}:{:02}", secs / 60, secs % 60)
+}
+
+/// Run the time attack clock, adding time for cleared bubbles and scoring
+/// the survival bonus.
+fn tick_time_attack_clock(
+    time: Res<Time<GameClock>>,
+    mut clock: ResMut<TimeAttackClock>,
+    mut score: ResMut<GameScore>,
+    mut cluster_events: MessageReader<ClusterPopped>,
+    mut floating_events: MessageReader<FloatingBubblesRemoved>,
+) {
+    let cleared = cluster_events
+        .read()
+        .map(|event| event.count)
+        .sum::<usize>()
+        + floating_events
+            .read()
+            .map(|event| event.count)
+            .sum::<usize>();
+    let bonus = clock.tick(time.delta_secs(), cleared);
+    if bonus > 0 {
+        score.score += bonus;
+        info!("Survival bonus! +{} points", bonus);
     }
 }
 
+/// End the run when the time attack clock runs out.
+fn check_time_up(
+    clock: Res<TimeAttackClock>,
+    mut next_menu: ResMut<NextState<Menu>>,
+    mut next_phase: ResMut<NextState<RunPhase>>,
+    score: Res<GameScore>,
+    mut ended_events: MessageWriter<GameEnded>,
+) {
+    if !clock.is_up() {
+        return;
+    }
+    info!("TIME UP! Final score: {}", score.score);
+    ended_events.write(GameEnded {
+        outcome: RunOutcome::TimeUp,
+    });
+    next_phase.set(RunPhase::GameEnding);
+    next_menu.set(Menu::GameOver);
+}
+
 /// Update score when clusters/floating bubbles are removed.
 fn update_score(
     mut score: ResMut<GameScore>,
     mut cluster_events: MessageReader<ClusterPopped>,
     mut floating_events: MessageReader<FloatingBubblesRemoved>,
+    mut cleared_events: MessageReader<ColorCleared>,
+    mut bumper_events: MessageReader<BumperHit>,
+    mut dropped_events: MessageReader<BubbleDropped>,
     powerups: Res<UnlockedPowerUps>,
+    profile: Res<Profile>,
+    handicap: Res<RunHandicap>,
+    challenge: Res<ActiveChallenge>,
 ) {
+    let before = score.score;
+
     for event in cluster_events.read() {
         let mut points = event.count as u32 * POINTS_PER_BUBBLE;
 
@@ -297,6 +633,7 @@ fn update_score(
                 bonus, event.count
             );
         }
+        let points = challenge.cluster_points(event.count, points);
 
         score.score += points;
         score.bubbles_popped += event.count as u32;
@@ -309,7 +646,8 @@ fn update_score(
     }
 
     for event in floating_events.read() {
-        let points = event.count as u32 * POINTS_PER_BUBBLE * FLOATING_BONUS_MULTIPLIER;
+        let points = challenge
+            .drop_points(event.count as u32 * POINTS_PER_BUBBLE * FLOATING_BONUS_MULTIPLIER);
         score.score += points;
         score.bubbles_popped += event.count as u32;
 
@@ -318,29 +656,151 @@ fn update_score(
             event.count, points, score.score
         );
     }
+
+    for event in cleared_events.read() {
+        let bonus = challenge.color_clear_bonus(COLOR_CLEAR_BONUS);
+        score.score += bonus;
+        info!(
+            "Cleared every {:?} bubble, +{} bonus points (total: {})",
+            event.color, bonus, score.score
+        );
+    }
+
+    for event in dropped_events.read() {
+        // The usual drop bonus was scored when it came loose
+        let points = challenge.drop_points(
+            POINTS_PER_BUBBLE * FLOATING_BONUS_MULTIPLIER * (event.multiplier.max(1) - 1),
+        );
+        score.score += points;
+        if points > 0 {
+            info!(
+                "Bubble dropped into a x{} zone, +{} points (total: {})",
+                event.multiplier, points, score.score
+            );
+        }
+    }
+
+    score.score += bumper_events.read().count() as u32 * BUMPER_POINTS;
+
+    // Goldie's cut and the handicap's of everything scored this frame
+    let earned = score.score - before;
+    score.score += bonus_points(profile.companion, earned) + handicap.handicap.bonus_points(earned);
 }
 
-/// Check if the player has won (all bubbles cleared).
-fn check_win_condition(
-    grid: Res<HexGrid>,
-    mut next_menu: ResMut<NextState<Menu>>,
+/// Count landings that didn't pop a cluster towards this round's wasted shots.
+///
+/// Runs after cluster detection so this frame's pops are known.
+fn count_wasted_shots(
+    mut level: ResMut<GameLevel>,
+    mut landed_events: MessageReader<BubbleLanded>,
+    mut cluster_events: MessageReader<ClusterPopped>,
+) {
+    let popped: Vec<HexCoord> = cluster_events
+        .read()
+        .flat_map(|event| event.coords.iter().copied())
+        .collect();
+    let wasted = landed_events
+        .read()
+        .filter(|event| !popped.contains(&event.coord))
+        .count() as u32;
+    if wasted > 0 {
+        level.wasted_shots_this_round += wasted;
+    }
+}
+
+fn note_final_outcome(
+    mut ended_events: MessageReader<GameEnded>,
+    mut continue_events: MessageReader<ContinueRun>,
+    mut outcome: ResMut<FinalOutcome>,
+) {
+    if let Some(event) = ended_events.read().last() {
+        outcome.0 = Some(event.outcome);
+    }
+    // A continued run isn't over yet
+    if continue_events.read().count() > 0 {
+        outcome.0 = None;
+    }
+}
+
+/// Save the final score to the leaderboard if it qualifies, once the run is
+/// left. Recording at game over would count a continued run twice, and miss
+/// the halving.
+fn record_final_score(
+    mut outcome: ResMut<FinalOutcome>,
     score: Res<GameScore>,
+    settings: Res<LeaderboardSettings>,
     mut high_scores: ResMut<HighScores>,
+    mut toasts: MessageWriter<ShowToast>,
+) {
+    let Some(outcome) = outcome.0.take() else {
+        return;
+    };
+    let abandoned = outcome == RunOutcome::Abandoned;
+    if abandoned && !settings.include_abandoned {
+        info!(
+            "Run abandoned with score {}, not eligible for top 10",
+            score.score
+        );
+        return;
+    }
+
+    let entry = ScoreEntry {
+        abandoned,
+        ..ScoreEntry::new(score.score, score.bubbles_popped)
+    };
+    if high_scores.add_score(entry) {
+        info!("New high score!");
+        high_scores.save();
+        toasts.write(ShowToast::success("New high score!"));
+    }
+}
+
+/// Check if the player has won: all bubbles cleared, or the campaign level's
+/// goal met. A won campaign level shows its grade before the next one, and a
+/// cleared board in Endless is refilled instead.
+pub(super) fn check_win_condition(
+    grid: Res<HexGrid>,
+    level: Res<GameLevel>,
+    mode: Res<GameMode>,
+    goal: Res<LevelGoal>,
+    active: Res<ActiveLevel>,
+    mut next_menu: ResMut<NextState<Menu>>,
+    mut next_phase: ResMut<NextState<RunPhase>>,
+    mut score: ResMut<GameScore>,
+    mut ended_events: MessageWriter<GameEnded>,
+    mut perfect_events: MessageWriter<PerfectClear>,
+    mut cleared_events: MessageWriter<BoardCleared>,
+    falling: Query<(), With<FallingBubble>>,
 ) {
     // Need to have popped at least one cluster to win
-    // (prevents winning on empty grid at start)
-    if score.clusters_popped > 0 && grid.is_empty() {
-        info!("WIN! All bubbles cleared! Final score: {}", score.score);
-
-        // Save high score if it qualifies
-        let entry = ScoreEntry::new(score.score, score.bubbles_popped);
-        if high_scores.add_score(entry) {
-            info!("New high score!");
-            high_scores.save();
+    // (prevents winning on empty grid at start), and let the last drops
+    // reach the score zones
+    if score.clusters_popped > 0 && goal.0.met(grid.is_empty(), &score) && falling.is_empty() {
+        // Scored before the run ends so the bonus reaches the leaderboard
+        if grid.is_empty() && level.wasted_shots_this_round == 0 {
+            score.score += PERFECT_CLEAR_BONUS;
+            info!("PERFECT CLEAR! +{} bonus points", PERFECT_CLEAR_BONUS);
+            perfect_events.write(PerfectClear);
+        }
+        if grid.is_empty() && mode.continues_after_clear() && active.0.is_none() {
+            score.score += BOARD_CLEAR_BONUS;
+            info!("BOARD CLEARED! +{} bonus points", BOARD_CLEAR_BONUS);
+            cleared_events.write(BoardCleared);
+            return;
+        }
+        info!("WIN! Final score: {}", score.score);
+        ended_events.write(GameEnded {
+            outcome: RunOutcome::Won,
+        });
+
+        next_phase.set(RunPhase::GameEnding);
+        if active.0.is_some() {
+            // Graded first; the results screen moves on to the next level
+            next_menu.set(Menu::LevelResults);
+        } else {
+            // Show win screen (using credits menu as placeholder)
+            next_menu.set(Menu::Credits);
         }
-
-        // Show win screen (using credits menu as placeholder)
-        next_menu.set(Menu::Credits);
     }
 }
 
@@ -349,8 +809,9 @@ fn check_lose_condition(
     grid: Res<HexGrid>,
     bubble_query: Query<&Transform, With<Bubble>>,
     mut next_menu: ResMut<NextState<Menu>>,
+    mut next_phase: ResMut<NextState<RunPhase>>,
     score: Res<GameScore>,
-    mut high_scores: ResMut<HighScores>,
+    mut ended_events: MessageWriter<GameEnded>,
 ) {
     // Check if any bubble is below the danger line
     for (_coord, &entity) in grid.iter() {
@@ -361,15 +822,12 @@ fn check_lose_condition(
                 "GAME OVER! Bubble reached danger zone. Final score: {}",
                 score.score
             );
-
-            // Save high score if it qualifies
-            let entry = ScoreEntry::new(score.score, score.bubbles_popped);
-            if high_scores.add_score(entry) {
-                info!("New high score!");
-                high_scores.save();
-            }
+            ended_events.write(GameEnded {
+                outcome: RunOutcome::GridReachedDanger,
+            });
 
             // Show game over screen
+            next_phase.set(RunPhase::GameEnding);
             next_menu.set(Menu::GameOver);
             return;
         }
@@ -380,23 +838,42 @@ fn check_lose_condition(
 fn check_danger_zone_game_over(
     mut danger_events: MessageReader<BubbleInDangerZone>,
     mut next_menu: ResMut<NextState<Menu>>,
+    mut next_phase: ResMut<NextState<RunPhase>>,
     score: Res<GameScore>,
-    mut high_scores: ResMut<HighScores>,
+    mut ended_events: MessageWriter<GameEnded>,
 ) {
     for _ in danger_events.read() {
         info!(
             "GAME OVER! Projectile tried to land in danger zone. Final score: {}",
             score.score
         );
-
-        // Save high score if it qualifies
-        let entry = ScoreEntry::new(score.score, score.bubbles_popped);
-        if high_scores.add_score(entry) {
-            info!("New high score!");
-            high_scores.save();
-        }
+        ended_events.write(GameEnded {
+            outcome: RunOutcome::DangerLanding,
+        });
 
         // Show game over screen
+        next_phase.set(RunPhase::GameEnding);
         next_menu.set(Menu::GameOver);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn time_attack_clock_runs_down_and_pays_for_surviving() {
+        let mut clock = TimeAttackClock::default();
+        assert_eq!(clock.tick(9.0, 0), 0);
+        assert_eq!(clock.tick(2.0, 4), SURVIVAL_BONUS_POINTS);
+        assert_eq!(
+            clock.remaining,
+            TIME_ATTACK_SECONDS - 11.0 + 4.0 * TIME_PER_BUBBLE
+        );
+
+        clock.tick(TIME_ATTACK_SECONDS, 0);
+        assert!(clock.is_up());
+        assert_eq!(clock.remaining, 0.0);
+        assert_eq!(countdown_label(65.2), "1:06");
+    }
+}
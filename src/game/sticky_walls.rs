@@ -0,0 +1,140 @@
+//! Sticky walls - stretches of the side walls that shots stick to.
+//!
+//! A shot that reaches a wall inside a sticky zone snaps onto the grid there
+//! instead of bouncing. Bubbles next to a sticky zone count as anchored, like
+//! the top row, so a column hanging off the wall doesn't fall when it's cut
+//! off from the ceiling. That lets levels build puzzles around the sides.
+//!
+//! Sticky zones come from campaign level data; other runs have none.
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    grid::HexGrid,
+    hex::HexCoord,
+    projectile::{WallSide, Walls},
+};
+use crate::screens::InGame;
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<StickyWalls>();
+    app.init_resource::<StickyWalls>();
+
+    app.add_systems(OnEnter(InGame), spawn_sticky_zone_sprites);
+    app.add_systems(
+        Update,
+        place_sticky_zone_sprites.run_if(in_state(InGame).and(resource_changed::<Walls>)),
+    );
+}
+
+/// How far from a sticky wall a bubble's center can be and still be held by
+/// it, in hex sizes. Edge cells on odd and even rows sit at different
+/// distances from the wall; this covers both.
+const HOLD_REACH: f32 = 2.0;
+
+/// Thickness of the drawn zones.
+const ZONE_THICKNESS: f32 = 6.0;
+
+const ZONE_COLOR: Color = Color::srgb(0.45, 0.75, 0.3);
+
+/// A stretch of one side wall that shots stick to.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Reflect)]
+pub struct StickyZone {
+    pub side: WallSide,
+    /// Height of the top of the zone.
+    pub top: f32,
+    /// Height of the bottom of the zone.
+    pub bottom: f32,
+}
+
+impl StickyZone {
+    fn contains(self, side: WallSide, y: f32) -> bool {
+        self.side == side && (self.bottom..=self.top).contains(&y)
+    }
+}
+
+/// The sticky zones on the walls this run.
+#[derive(Resource, Debug, Clone, Default, PartialEq, Reflect)]
+#[reflect(Resource)]
+pub struct StickyWalls(pub Vec<StickyZone>);
+
+impl StickyWalls {
+    /// Whether a shot touching the `side` wall at height `y` sticks.
+    pub fn holds(&self, side: WallSide, y: f32) -> bool {
+        self.0.iter().any(|zone| zone.contains(side, y))
+    }
+
+    /// Occupied cells held up by a sticky zone.
+    pub fn anchors(&self, grid: &HexGrid, grid_origin_y: f32, walls: Walls) -> Vec<HexCoord> {
+        if self.0.is_empty() {
+            return Vec::new();
+        }
+        let reach = grid.hex_size * HOLD_REACH;
+        grid.coords()
+            .filter(|&coord| {
+                let pos = coord.to_pixel_with_offset(grid.hex_size, grid_origin_y);
+                [WallSide::Left, WallSide::Right].into_iter().any(|side| {
+                    (pos.x - side.x(walls)) * side.inward() <= reach && self.holds(side, pos.y)
+                })
+            })
+            .collect()
+    }
+}
+
+/// A sticky zone drawn on its wall.
+#[derive(Component, Debug, Clone, Copy)]
+struct StickyZoneSprite(WallSide);
+
+fn spawn_sticky_zone_sprites(mut commands: Commands, sticky: Res<StickyWalls>, walls: Res<Walls>) {
+    for zone in &sticky.0 {
+        let x = zone.side.x(*walls) - zone.side.inward() * ZONE_THICKNESS / 2.0;
+        commands.spawn((
+            Name::new("Sticky Zone"),
+            StickyZoneSprite(zone.side),
+            Sprite::from_color(
+                ZONE_COLOR,
+                Vec2::new(ZONE_THICKNESS, zone.top - zone.bottom),
+            ),
+            Transform::from_xyz(x, (zone.top + zone.bottom) / 2.0, 0.6),
+            DespawnOnExit(InGame),
+        ));
+    }
+}
+
+/// Keep zones on their walls as the walls move.
+fn place_sticky_zone_sprites(
+    walls: Res<Walls>,
+    mut sprites: Query<(&StickyZoneSprite, &mut Transform)>,
+) {
+    for (sprite, mut transform) in &mut sprites {
+        transform.translation.x = sprite.0.x(*walls) - sprite.0.inward() * ZONE_THICKNESS / 2.0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_cells_beside_a_zone_are_anchors() {
+        let sticky = StickyWalls(vec![StickyZone {
+            side: WallSide::Left,
+            top: 100.0,
+            bottom: -100.0,
+        }]);
+        let mut grid = HexGrid::default();
+        let walls = Walls::default();
+        let origin_y = 0.0;
+
+        let edge = HexCoord::new(grid.bounds.min_q, 0);
+        let middle = HexCoord::new(0, 0);
+        grid.insert(edge, Entity::PLACEHOLDER);
+        grid.insert(middle, Entity::PLACEHOLDER);
+
+        assert_eq!(sticky.anchors(&grid, origin_y, walls), vec![edge]);
+        assert!(sticky.holds(WallSide::Left, 0.0));
+        assert!(!sticky.holds(WallSide::Right, 0.0));
+        assert!(!sticky.holds(WallSide::Left, 150.0));
+    }
+}
@@ -0,0 +1,208 @@
+//! Opt-in gameplay telemetry for balance tuning.
+//!
+//! When enabled in the settings menu, each run is summarized (session length,
+//! level reached, power-ups offered and picked, how it ended) and appended to a
+//! local JSON file. If an endpoint is configured in that file, each summary is
+//! also posted there. Records contain no player identifiers.
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    powerups::{PowerUpChoices, UnlockedPowerUps},
+    state::{ContinueRun, GameEnded, GameLevel, GameScore},
+};
+use crate::{menus::Menu, save::SaveFile, screens::Screen, upload};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<TelemetrySettings>();
+    app.init_resource::<TelemetryLog>();
+    app.init_resource::<CurrentRun>();
+    app.register_type::<TelemetrySettings>();
+
+    app.add_systems(Startup, load_telemetry);
+    app.add_systems(
+        Update,
+        save_telemetry_settings.run_if(resource_changed::<TelemetrySettings>),
+    );
+
+    app.add_systems(OnEnter(Screen::Gameplay), start_run);
+    app.add_systems(
+        OnExit(Screen::Gameplay),
+        finish_run.run_if(telemetry_enabled),
+    );
+    app.add_systems(
+        Update,
+        (note_game_end, note_continue)
+            .chain()
+            .run_if(in_state(Screen::Gameplay)),
+    );
+    app.add_systems(
+        OnEnter(Menu::PowerUpSelect),
+        note_powerup_offers.run_if(in_state(Screen::Gameplay)),
+    );
+}
+
+/// Maximum number of runs kept in the local telemetry file.
+const MAX_RUN_RECORDS: usize = 200;
+
+/// Telemetry settings (opt-in, disabled by default).
+#[derive(Resource, Debug, Clone, Default, PartialEq, Reflect, Serialize, Deserialize)]
+#[reflect(Resource)]
+pub struct TelemetrySettings {
+    pub enabled: bool,
+    /// Optional URL that run summaries are posted to as JSON.
+    #[serde(default)]
+    pub endpoint: Option<String>,
+}
+
+/// How a run ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RunOutcome {
+    /// All bubbles cleared.
+    Won,
+    /// The grid descended past the danger line.
+    GridReachedDanger,
+    /// A shot tried to land in the danger zone.
+    DangerLanding,
+    /// The player left the run before it ended.
+    Quit,
+    /// The player ended the run from the pause menu, keeping the score.
+    Abandoned,
+    /// The time attack countdown ran out.
+    TimeUp,
+}
+
+impl RunOutcome {
+    pub fn label(self) -> &'static str {
+        match self {
+            RunOutcome::Won => "Won",
+            RunOutcome::GridReachedDanger => "Grid reached danger",
+            RunOutcome::DangerLanding => "Danger landing",
+            RunOutcome::Quit => "Quit",
+            RunOutcome::Abandoned => "Abandoned",
+            RunOutcome::TimeUp => "Time up",
+        }
+    }
+}
+
+/// Anonymized summary of a single run.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunRecord {
+    pub game_version: String,
+    pub session_secs: f32,
+    pub level_reached: u32,
+    pub score: u32,
+    pub powerups_offered: Vec<String>,
+    pub powerups_picked: Vec<String>,
+    pub outcome: RunOutcome,
+}
+
+/// Contents of the local telemetry file.
+#[derive(Resource, Debug, Default, Serialize, Deserialize)]
+pub struct TelemetryLog {
+    #[serde(default)]
+    pub settings: TelemetrySettings,
+    #[serde(default)]
+    pub runs: Vec<RunRecord>,
+}
+
+impl SaveFile for TelemetryLog {
+    const FILE_NAME: &'static str = "telemetry.json";
+    const DESCRIPTION: &'static str = "telemetry";
+}
+
+impl TelemetryLog {
+    /// Add a run, dropping the oldest once the log is full.
+    fn push(&mut self, record: RunRecord) {
+        self.runs.push(record);
+        if self.runs.len() > MAX_RUN_RECORDS {
+            let excess = self.runs.len() - MAX_RUN_RECORDS;
+            self.runs.drain(..excess);
+        }
+    }
+}
+
+/// The run currently being recorded.
+#[derive(Resource, Debug, Default)]
+struct CurrentRun {
+    started_at: f32,
+    powerups_offered: Vec<String>,
+    outcome: Option<RunOutcome>,
+}
+
+fn telemetry_enabled(settings: Res<TelemetrySettings>) -> bool {
+    settings.enabled
+}
+
+/// Load telemetry settings and past runs on startup.
+fn load_telemetry(mut settings: ResMut<TelemetrySettings>, mut log: ResMut<TelemetryLog>) {
+    *log = TelemetryLog::load();
+    *settings = log.settings.clone();
+}
+
+/// Persist settings whenever they change (e.g. toggled in the settings menu).
+fn save_telemetry_settings(settings: Res<TelemetrySettings>, mut log: ResMut<TelemetryLog>) {
+    // Loading the file also counts as a change
+    if log.settings == *settings {
+        return;
+    }
+    log.settings = settings.clone();
+    log.save();
+}
+
+fn start_run(time: Res<Time<Real>>, mut run: ResMut<CurrentRun>) {
+    *run = CurrentRun {
+        started_at: time.elapsed_secs(),
+        ..default()
+    };
+}
+
+fn note_game_end(mut ended_events: MessageReader<GameEnded>, mut run: ResMut<CurrentRun>) {
+    if let Some(event) = ended_events.read().last() {
+        run.outcome = Some(event.outcome);
+    }
+}
+
+fn note_powerup_offers(choices: Res<PowerUpChoices>, mut run: ResMut<CurrentRun>) {
+    run.powerups_offered
+        .extend(choices.choices.iter().map(|power| power.name().to_string()));
+}
+
+fn note_continue(mut continue_events: MessageReader<ContinueRun>, mut run: ResMut<CurrentRun>) {
+    if continue_events.read().count() > 0 {
+        run.outcome = None;
+    }
+}
+
+/// Summarize the finished run, store it, and upload it if an endpoint is set.
+fn finish_run(
+    time: Res<Time<Real>>,
+    mut run: ResMut<CurrentRun>,
+    level: Res<GameLevel>,
+    score: Res<GameScore>,
+    powerups: Res<UnlockedPowerUps>,
+    settings: Res<TelemetrySettings>,
+    mut log: ResMut<TelemetryLog>,
+) {
+    let record = RunRecord {
+        game_version: env!("CARGO_PKG_VERSION").to_string(),
+        session_secs: time.elapsed_secs() - run.started_at,
+        level_reached: level.level,
+        score: score.score,
+        powerups_offered: std::mem::take(&mut run.powerups_offered),
+        powerups_picked: powerups
+            .powers
+            .iter()
+            .map(|power| power.name().to_string())
+            .collect(),
+        outcome: run.outcome.take().unwrap_or(RunOutcome::Quit),
+    };
+
+    if let Some(endpoint) = &settings.endpoint {
+        upload::post_json(endpoint.clone(), &record, "telemetry run");
+    }
+
+    log.push(record);
+    log.save();
+}
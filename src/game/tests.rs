@@ -0,0 +1,2675 @@
+//! Headless integration tests for gameplay flows.
+//!
+//! These drive the real game plugins through a Bevy `App` without a window or
+//! GPU, with a fixed timestep. Each test replaces the random starting grid
+//! with a known layout, so shots land and pop deterministically.
+
+use std::time::Duration;
+
+use bevy::{
+    camera::NormalizedRenderTarget,
+    ecs::system::RunSystemOnce,
+    input::{
+        ButtonState,
+        keyboard::{Key, KeyboardInput},
+    },
+    picking::{
+        backend::HitData,
+        pointer::{Location, PointerButton, PointerId},
+    },
+    prelude::*,
+    render::{
+        RenderPlugin,
+        settings::{RenderCreation, WgpuSettings},
+    },
+    time::TimeUpdateStrategy,
+    window::{CursorMoved, ExitCondition, WindowCloseRequested},
+    winit::WinitPlugin,
+};
+
+use super::{
+    autosave::{Autosave, PendingResume},
+    bubble::{
+        Bubble, BubbleAge, BubbleColor, GameAssets, StartingBoard, spawn_bomb, spawn_bubble,
+        spawn_stone,
+    },
+    bubble_material::BubbleMaterial,
+    bumpers::{BUMPER_POINTS, Bumper, Bumpers},
+    campaign::{ActiveLevel, CampaignLevels},
+    clock::GameClock,
+    cluster::{ClusterPopped, GameAudioAssets, LevelAnchors},
+    color_clear::ColorCounts,
+    companion::Companion,
+    debug::LandingHeatmap,
+    descent_diff::{CellChange, DescentDiff},
+    dialogue::{DialogueLine, DialogueScenes},
+    drills::{ActiveDrill, DrillBests, Drills},
+    endless::BoardClears,
+    gameplay_entities::{ClearGameplayEntities, GameRoot, GameplayEntity},
+    grading::LevelGrade,
+    grid::{GridBounds, GridCommands, HexGrid},
+    handicap::Handicap,
+    hex::{GRID_ORIGIN_Y, GridOffset, HexCoord},
+    highlight::{HighlightLayer, Highlights},
+    highscore::HighScores,
+    hints::{HintLine, HintPrompt, HintRequested},
+    history::{RetrySeed, RunSeed},
+    layout::LayoutTransform,
+    mini_board::{FinalBoard, MiniBoard},
+    mode::{GameMode, SelectedMode},
+    mood::SnordMood,
+    patterns::ColorSymbol,
+    polish::age_tint,
+    portals::{Portal, Portals},
+    powerups::{PowerUpCatalog, PowerUpChoices, UnlockedPowerUps},
+    projectile::{BubbleLanded, DangerGrace, FireProjectile, Projectile, WallSide, Walls},
+    replay::{FinalShots, LastReplay, ReplayPlayback, WatchReplay},
+    score_zones::{FallingBubble, ScoreZone, ScoreZones},
+    shooter::{
+        AimDirection, AimGuideSettings, LoadedBubble, LoadedBubbleVisual, NextBubble, SHOOTER_Y,
+        Shooter, ShooterState, TrajectorySegment,
+    },
+    shot_clock::{ShotClock, ShotClockConfig, ShotClockRules, ShotClockTimeout},
+    shot_trace::ShotHistory,
+    snapshot::{GameSnapshot, GridCell, STRESS_TEST_ROUNDS, stress_test},
+    state::{
+        BOARD_CLEAR_BONUS, COLOR_CLEAR_BONUS, ContinueRun, GameEnded, GameLevel, GameScore,
+        PERFECT_CLEAR_BONUS, POINTS_PER_BUBBLE, TIME_PER_BUBBLE, TimeAttackClock, TriggerDescent,
+    },
+    sticky_walls::{StickyWalls, StickyZone},
+    telemetry::RunOutcome,
+    weekly::{ActiveChallenge, Modifier, WeeklyChallenge, WeeklyScores},
+    wildfire::{ColorMerge, WILDFIRE_SHOTS, Wildfire},
+};
+use crate::{
+    CorePlugin, Pause,
+    accessibility::{AccessibilitySettings, ColorblindMode},
+    controls::{InputAction, InputBindings},
+    input_replay::{InputReplay, InputSession, RecordedFrame, RecordedInput},
+    menus::Menu,
+    profile::Profile,
+    save::SaveFile,
+    screens::{RunPhase, Screen},
+};
+
+/// Frames to wait for assets to load before giving up.
+const MAX_LOADING_FRAMES: usize = 2000;
+
+/// Frames to wait for a projectile to land before giving up.
+const MAX_FLIGHT_FRAMES: usize = 300;
+
+/// Build the game without a window or renderer and enter gameplay.
+fn gameplay_app() -> App {
+    let mut app = App::new();
+    app.add_plugins(
+        DefaultPlugins
+            .set(RenderPlugin {
+                render_creation: RenderCreation::Automatic(WgpuSettings {
+                    backends: None,
+                    ..default()
+                }),
+                ..default()
+            })
+            .set(WindowPlugin {
+                primary_window: None,
+                exit_condition: ExitCondition::DontExit,
+                ..default()
+            })
+            .disable::<WinitPlugin>()
+            .disable::<bevy::log::LogPlugin>(),
+    );
+    app.add_plugins(CorePlugin);
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f32(
+        1.0 / 60.0,
+    )));
+    app.finish();
+    app.cleanup();
+
+    app.world_mut()
+        .resource_mut::<NextState<Screen>>()
+        .set(Screen::Loading);
+    // Load the saved profile, then leave its companion at home: tests that
+    // want one pick it themselves
+    app.update();
+    app.world_mut().resource_mut::<Profile>().companion = None;
+    for _ in 0..MAX_LOADING_FRAMES {
+        app.update();
+        if *app.world().resource::<State<Screen>>().get() == Screen::Gameplay {
+            // Let OnEnter systems and their commands settle
+            app.update();
+            return app;
+        }
+        std::thread::sleep(Duration::from_millis(1));
+    }
+    panic!("assets never finished loading");
+}
+
+/// Leave gameplay and start a fresh run through the loading screen.
+fn restart_run(app: &mut App) {
+    app.world_mut()
+        .resource_mut::<NextState<Screen>>()
+        .set(Screen::Loading);
+    app.update();
+    for _ in 0..MAX_LOADING_FRAMES {
+        app.update();
+        if *app.world().resource::<State<Screen>>().get() == Screen::Gameplay {
+            break;
+        }
+    }
+    app.update();
+}
+
+/// Replace every bubble on the grid with the given layout.
+fn set_grid(app: &mut App, layout: &[(i32, i32, BubbleColor)]) {
+    let layout = layout.to_vec();
+    app.world_mut()
+        .run_system_once(
+            move |mut commands: Commands,
+                  mut grid: ResMut<HexGrid>,
+                  mut meshes: ResMut<Assets<Mesh>>,
+                  mut materials: ResMut<Assets<BubbleMaterial>>,
+                  bubbles: Query<Entity, With<Bubble>>,
+                  grid_offset: Res<GridOffset>,
+                  game_assets: Res<GameAssets>| {
+                for entity in &bubbles {
+                    commands.entity(entity).despawn();
+                }
+                grid.clear();
+
+                for &(q, r, color) in &layout {
+                    let coord = HexCoord::new(q, r);
+                    let entity = spawn_bubble(
+                        &mut commands,
+                        &mut meshes,
+                        &mut materials,
+                        coord,
+                        color,
+                        grid.hex_size,
+                        grid_offset.y,
+                        Some(&game_assets),
+                    );
+                    grid.insert(coord, entity);
+                }
+            },
+        )
+        .expect("grid setup system should run");
+    app.update();
+}
+
+/// Fire a bubble straight up from the shooter and run until it lands.
+fn fire_straight_up(app: &mut App, color: BubbleColor) {
+    fire_and_land(
+        app,
+        FireProjectile {
+            position: Vec2::new(0.0, SHOOTER_Y),
+            direction: Vec2::Y,
+            color,
+            wildcard: false,
+        },
+    );
+}
+
+/// Fire a shot and run until it lands.
+fn fire_and_land(app: &mut App, shot: FireProjectile) {
+    app.world_mut().write_message(shot);
+    app.update();
+
+    for _ in 0..MAX_FLIGHT_FRAMES {
+        let mut projectiles = app.world_mut().query_filtered::<(), With<Projectile>>();
+        if projectiles.iter(app.world()).next().is_none() {
+            // One more frame for cluster detection and scoring to react
+            app.update();
+            return;
+        }
+        app.update();
+    }
+    panic!("projectile never landed");
+}
+
+/// Press and release Enter, a frame each.
+fn press_enter(app: &mut App) {
+    press_key(app, KeyCode::Enter, Key::Enter);
+}
+
+/// Press and release a key, a frame each.
+fn press_key(app: &mut App, key_code: KeyCode, logical_key: Key) {
+    for state in [ButtonState::Pressed, ButtonState::Released] {
+        app.world_mut().write_message(KeyboardInput {
+            key_code,
+            logical_key: logical_key.clone(),
+            state,
+            text: None,
+            repeat: false,
+            window: Entity::PLACEHOLDER,
+        });
+        app.update();
+    }
+}
+
+/// Colors on the grid by coordinate. Bombs and stones are left out.
+fn grid_colors(app: &mut App) -> Vec<(HexCoord, BubbleColor)> {
+    let mut colors: Vec<(HexCoord, BubbleColor)> = app
+        .world_mut()
+        .query::<&Bubble>()
+        .iter(app.world())
+        .filter(|bubble| {
+            app.world()
+                .resource::<HexGrid>()
+                .get(bubble.coord)
+                .is_some()
+        })
+        .filter_map(|bubble| Some((bubble.coord, bubble.match_color()?)))
+        .collect();
+    colors.sort_by_key(|(coord, _)| (coord.r, coord.q));
+    colors
+}
+
+#[test]
+fn shot_sticks_below_mismatched_bubble() {
+    let mut app = gameplay_app();
+    set_grid(
+        &mut app,
+        &[(0, 0, BubbleColor::Blue), (-5, 0, BubbleColor::Blue)],
+    );
+
+    fire_straight_up(&mut app, BubbleColor::Red);
+
+    let colors = grid_colors(&mut app);
+    assert_eq!(colors.len(), 3);
+    let landed: Vec<_> = colors
+        .iter()
+        .filter(|(_, color)| *color == BubbleColor::Red)
+        .collect();
+    assert_eq!(landed.len(), 1);
+    assert_eq!(
+        landed[0].0.r, 1,
+        "shot should stick directly below the top row"
+    );
+    assert_eq!(app.world().resource::<GameScore>().score, 0);
+
+    let heatmap = app.world().resource::<LandingHeatmap>();
+    assert_eq!(heatmap.counts.get(&landed[0].0), Some(&1));
+    assert!(heatmap.to_csv().contains(&format!("{},1,1", landed[0].0.q)));
+}
+
+#[test]
+fn landing_next_to_a_bomb_clears_two_cells_around_it() {
+    let mut app = gameplay_app();
+    let row: Vec<(i32, i32, BubbleColor)> = (-6..=6)
+        .filter(|&q| q != 0)
+        .map(|q| {
+            let color = if q % 2 == 0 {
+                BubbleColor::Blue
+            } else {
+                BubbleColor::Green
+            };
+            (q, 0, color)
+        })
+        .collect();
+    set_grid(&mut app, &row);
+    app.world_mut()
+        .run_system_once(
+            |mut commands: Commands,
+             mut grid: ResMut<HexGrid>,
+             mut meshes: ResMut<Assets<Mesh>>,
+             mut materials: ResMut<Assets<BubbleMaterial>>,
+             grid_offset: Res<GridOffset>,
+             game_assets: Res<GameAssets>| {
+                let coord = HexCoord::new(0, 0);
+                let entity = spawn_bomb(
+                    &mut commands,
+                    &mut meshes,
+                    &mut materials,
+                    coord,
+                    grid.hex_size,
+                    grid_offset.y,
+                    Some(&game_assets),
+                );
+                grid.insert(coord, entity);
+            },
+        )
+        .expect("bomb setup system should run");
+    app.update();
+
+    fire_straight_up(&mut app, BubbleColor::Red);
+
+    let left: Vec<i32> = grid_colors(&mut app)
+        .iter()
+        .map(|(coord, _)| coord.q)
+        .collect();
+    assert_eq!(left, [-6, -5, -4, -3, 3, 4, 5, 6]);
+    assert!(app.world().resource::<GameScore>().score > 0);
+}
+
+#[test]
+fn matching_shot_pops_cluster_and_drops_floaters() {
+    let mut app = gameplay_app();
+    set_grid(
+        &mut app,
+        &[
+            (0, 0, BubbleColor::Red),
+            (1, 0, BubbleColor::Red),
+            // Hangs only from the reds, so it falls when they pop
+            (1, 1, BubbleColor::Green),
+            // Anchor so clearing the reds doesn't win the game
+            (-5, 0, BubbleColor::Blue),
+        ],
+    );
+
+    fire_straight_up(&mut app, BubbleColor::Red);
+
+    assert_eq!(
+        grid_colors(&mut app),
+        vec![(HexCoord::new(-5, 0), BubbleColor::Blue)]
+    );
+    let score = app.world().resource::<GameScore>();
+    // 3 popped * 10 + 1 floating * 10 * 2, and no red or green left
+    assert_eq!(score.score, 50 + 2 * COLOR_CLEAR_BONUS);
+    assert_eq!(score.bubbles_popped, 4);
+    assert_eq!(score.clusters_popped, 1);
+}
+
+#[test]
+fn stones_never_pop_but_fall_when_cut_off() {
+    let mut app = gameplay_app();
+    set_grid(
+        &mut app,
+        &[
+            (0, 0, BubbleColor::Red),
+            (1, 0, BubbleColor::Red),
+            (-5, 0, BubbleColor::Blue),
+        ],
+    );
+    app.world_mut()
+        .run_system_once(
+            |mut commands: Commands,
+             mut grid: ResMut<HexGrid>,
+             mut meshes: ResMut<Assets<Mesh>>,
+             mut materials: ResMut<Assets<BubbleMaterial>>,
+             grid_offset: Res<GridOffset>,
+             game_assets: Res<GameAssets>| {
+                // One next to the reds on the top row, one hanging from them
+                for coord in [HexCoord::new(-1, 0), HexCoord::new(1, 1)] {
+                    let entity = spawn_stone(
+                        &mut commands,
+                        &mut meshes,
+                        &mut materials,
+                        coord,
+                        grid.hex_size,
+                        grid_offset.y,
+                        Some(&game_assets),
+                    );
+                    grid.insert(coord, entity);
+                }
+            },
+        )
+        .expect("stone setup system should run");
+    app.update();
+
+    fire_straight_up(&mut app, BubbleColor::Red);
+
+    assert_eq!(
+        grid_colors(&mut app),
+        vec![(HexCoord::new(-5, 0), BubbleColor::Blue)]
+    );
+    let stones: Vec<HexCoord> = app
+        .world()
+        .resource::<HexGrid>()
+        .coords()
+        .filter(|&coord| coord != HexCoord::new(-5, 0))
+        .collect();
+    assert_eq!(stones, [HexCoord::new(-1, 0)]);
+    let score = app.world().resource::<GameScore>();
+    assert_eq!(score.bubbles_popped, 4);
+    assert_eq!(score.clusters_popped, 1);
+}
+
+#[test]
+fn wildcard_shot_matches_the_color_around_it() {
+    let mut app = gameplay_app();
+    set_grid(
+        &mut app,
+        &[
+            (0, 0, BubbleColor::Red),
+            (1, 0, BubbleColor::Red),
+            (-1, 0, BubbleColor::Green),
+            (-5, 0, BubbleColor::Blue),
+        ],
+    );
+
+    // A green shot, but it's a wildcard: it joins the bigger red cluster
+    fire_and_land(
+        &mut app,
+        FireProjectile {
+            position: Vec2::new(0.0, SHOOTER_Y),
+            direction: Vec2::Y,
+            color: BubbleColor::Green,
+            wildcard: true,
+        },
+    );
+
+    assert_eq!(
+        grid_colors(&mut app),
+        vec![
+            (HexCoord::new(-5, 0), BubbleColor::Blue),
+            (HexCoord::new(-1, 0), BubbleColor::Green),
+        ]
+    );
+    assert_eq!(app.world().resource::<GameScore>().clusters_popped, 1);
+}
+
+#[test]
+fn companions_bring_their_passives_to_the_run() {
+    let mut app = gameplay_app();
+    assert_eq!(app.world().resource::<DangerGrace>().remaining, 1);
+    assert!(app.world().resource::<UnlockedPowerUps>().powers.is_empty());
+
+    app.world_mut().resource_mut::<Profile>().companion = Some(Companion::Boing);
+    restart_run(&mut app);
+    assert_eq!(app.world().resource::<DangerGrace>().remaining, 2);
+
+    app.world_mut().resource_mut::<Profile>().companion = Some(Companion::Pip);
+    restart_run(&mut app);
+    let powers = app.world().resource::<UnlockedPowerUps>().powers.clone();
+    let catalog = app.world().resource::<PowerUpCatalog>();
+    assert_eq!(powers.len(), 1);
+    assert_eq!(catalog.get(powers[0]).map(|def| def.tier), Some(1));
+
+    // Goldie's cut on top of the same pop, on a board that isn't cleared
+    let pop = |app: &mut App| {
+        set_grid(
+            app,
+            &[
+                (0, 0, BubbleColor::Red),
+                (1, 0, BubbleColor::Red),
+                (-5, 0, BubbleColor::Blue),
+            ],
+        );
+        app.world_mut().resource_mut::<GameScore>().reset();
+        fire_straight_up(app, BubbleColor::Red);
+        app.world().resource::<GameScore>().score
+    };
+    app.world_mut().resource_mut::<Profile>().companion = None;
+    let plain = pop(&mut app);
+    app.world_mut().resource_mut::<Profile>().companion = Some(Companion::Goldie);
+    assert_eq!(pop(&mut app), plain + plain * 5 / 100);
+}
+
+#[test]
+fn missed_shots_offer_a_hint_good_for_one_shot() {
+    let mut app = gameplay_app();
+    set_grid(&mut app, &[(0, 0, BubbleColor::Blue)]);
+
+    let mut prompts = app.world_mut().query_filtered::<(), With<HintPrompt>>();
+    for color in [
+        BubbleColor::Green,
+        BubbleColor::Yellow,
+        BubbleColor::Green,
+        BubbleColor::Yellow,
+    ] {
+        fire_straight_up(&mut app, color);
+    }
+    assert_eq!(prompts.iter(app.world()).count(), 0);
+    fire_straight_up(&mut app, BubbleColor::Green);
+    assert_eq!(prompts.iter(app.world()).count(), 1);
+
+    // Accepting draws the shot's path once it's been traced in the background
+    app.world_mut().write_message(HintRequested);
+    let mut lines = app.world_mut().query_filtered::<(), With<HintLine>>();
+    for _ in 0..MAX_LOADING_FRAMES {
+        app.update();
+        if lines.iter(app.world()).next().is_some() {
+            break;
+        }
+        std::thread::sleep(Duration::from_millis(1));
+    }
+    assert_ne!(lines.iter(app.world()).count(), 0);
+
+    fire_straight_up(&mut app, BubbleColor::Yellow);
+    assert_eq!(lines.iter(app.world()).count(), 0);
+    assert_eq!(prompts.iter(app.world()).count(), 0);
+}
+
+#[test]
+fn simultaneous_landings_pop_shared_cluster_once() {
+    let mut app = gameplay_app();
+    // Both bottom reds "just landed" and join the same cluster
+    set_grid(
+        &mut app,
+        &[
+            (0, 0, BubbleColor::Red),
+            (1, 0, BubbleColor::Red),
+            (0, 1, BubbleColor::Red),
+            (1, 1, BubbleColor::Red),
+            (-5, 0, BubbleColor::Blue),
+            // Another red, so the pop isn't a color clear
+            (5, 0, BubbleColor::Red),
+        ],
+    );
+
+    for q in [0, 1] {
+        let coord = HexCoord::new(q, 1);
+        let entity = app
+            .world()
+            .resource::<HexGrid>()
+            .get(coord)
+            .expect("landed bubble should be on the grid");
+        app.world_mut().write_message(BubbleLanded {
+            coord,
+            color: BubbleColor::Red,
+            entity,
+        });
+    }
+    app.update();
+    app.update();
+
+    let score = app.world().resource::<GameScore>();
+    assert_eq!(score.score, 40);
+    assert_eq!(score.bubbles_popped, 4);
+    assert_eq!(score.clusters_popped, 1);
+}
+
+#[test]
+fn descent_adds_row_and_advances_level() {
+    let mut app = gameplay_app();
+    set_grid(&mut app, &[(0, 0, BubbleColor::Blue)]);
+    let offset_before = app.world().resource::<GridOffset>().y;
+
+    app.world_mut().write_message(TriggerDescent);
+    app.update();
+    app.update();
+
+    assert_eq!(app.world().resource::<GameLevel>().level, 2);
+    assert!(app.world().resource::<GridOffset>().y < offset_before);
+    let colors = grid_colors(&mut app);
+    let new_row = colors.iter().filter(|(coord, _)| coord.r == -1).count();
+    let bounds = app.world().resource::<HexGrid>().bounds;
+    assert_eq!(new_row as i32, bounds.max_q - bounds.min_q + 1);
+    assert_eq!(*app.world().resource::<State<Menu>>().get(), Menu::None);
+}
+
+#[test]
+fn descents_past_level_twenty_shrink_the_board() {
+    let mut app = gameplay_app();
+    set_grid(
+        &mut app,
+        &[(0, 0, BubbleColor::Blue), (3, 5, BubbleColor::Red)],
+    );
+    app.world_mut().resource_mut::<GameLevel>().level = 19;
+    app.update();
+    assert_eq!(app.world().resource::<HexGrid>().hex_size, 20.0);
+
+    app.world_mut().write_message(TriggerDescent);
+    for _ in 0..3 {
+        app.update();
+    }
+    assert_eq!(app.world().resource::<GameLevel>().level, 20);
+    let grid = app.world().resource::<HexGrid>();
+    assert_eq!(grid.hex_size, 17.5);
+    assert_eq!(grid.bounds.columns_in_row(0), 15);
+
+    // Bubbles keep their cells, with the ceiling row still at the ceiling
+    let offset = app.world().resource::<GridOffset>().y;
+    let mut bubbles = app.world_mut().query::<(&Bubble, &Transform)>();
+    for (bubble, transform) in bubbles.iter(app.world()) {
+        let expected = bubble.coord.to_pixel_with_offset(17.5, offset);
+        assert_eq!(transform.translation.truncate(), expected);
+        if bubble.coord.r == -1 {
+            assert_eq!(expected.y, GRID_ORIGIN_Y);
+        }
+    }
+
+    // Past the level 20 power-up pick, the next row down comes in with the
+    // extra columns
+    app.world_mut()
+        .resource_mut::<NextState<RunPhase>>()
+        .set(RunPhase::Playing);
+    app.world_mut()
+        .resource_mut::<NextState<Menu>>()
+        .set(Menu::None);
+    app.update();
+    app.world_mut().write_message(TriggerDescent);
+    for _ in 0..3 {
+        app.update();
+    }
+    let colors = grid_colors(&mut app);
+    assert_eq!(colors.iter().filter(|(coord, _)| coord.r == -2).count(), 15);
+}
+
+#[test]
+fn wildfire_matches_one_color_as_another_until_it_burns_out() {
+    let mut app = gameplay_app();
+    set_grid(
+        &mut app,
+        &[
+            (-1, 0, BubbleColor::Red),
+            (0, 0, BubbleColor::Red),
+            (1, 0, BubbleColor::Red),
+            (5, 0, BubbleColor::Green),
+        ],
+    );
+    app.insert_resource(Wildfire(Some(ColorMerge {
+        from: BubbleColor::Blue,
+        into: BubbleColor::Red,
+        shots_left: WILDFIRE_SHOTS,
+    })));
+
+    // The queue shows a blue snord as the red it'll match as
+    let mut shooters = app
+        .world_mut()
+        .query_filtered::<&mut LoadedBubble, With<Shooter>>();
+    shooters.single_mut(app.world_mut()).unwrap().0 = BubbleColor::Blue;
+    app.update();
+    let mut visuals = app
+        .world_mut()
+        .query_filtered::<&BubbleColor, With<LoadedBubbleVisual>>();
+    assert_eq!(*visuals.single(app.world()).unwrap(), BubbleColor::Red);
+
+    fire_straight_up(&mut app, BubbleColor::Blue);
+    assert_eq!(grid_colors(&mut app).len(), 1);
+    assert_eq!(
+        app.world().resource::<Wildfire>().0.unwrap().shots_left,
+        WILDFIRE_SHOTS - 1
+    );
+
+    // The last shot still matches as red, then blue is blue again
+    app.world_mut()
+        .resource_mut::<Wildfire>()
+        .0
+        .as_mut()
+        .unwrap()
+        .shots_left = 1;
+    for expected in [1, 5] {
+        set_grid(
+            &mut app,
+            &[
+                (-1, 0, BubbleColor::Red),
+                (0, 0, BubbleColor::Red),
+                (1, 0, BubbleColor::Red),
+                (5, 0, BubbleColor::Green),
+            ],
+        );
+        fire_straight_up(&mut app, BubbleColor::Blue);
+        assert_eq!(grid_colors(&mut app).len(), expected);
+        assert_eq!(*app.world().resource::<Wildfire>(), Wildfire::default());
+    }
+}
+
+#[test]
+fn letters_mode_labels_every_bubble_with_its_color() {
+    let mut app = gameplay_app();
+    set_grid(
+        &mut app,
+        &[(0, 0, BubbleColor::Blue), (1, 0, BubbleColor::Orange)],
+    );
+    app.insert_resource(ColorblindMode::Off);
+    app.update();
+    let mut symbols = app
+        .world_mut()
+        .query::<(&ColorSymbol, &Text2d, &Visibility, &ChildOf)>();
+    assert!(
+        symbols
+            .iter(app.world())
+            .all(|(_, _, visibility, _)| *visibility == Visibility::Hidden)
+    );
+
+    app.insert_resource(ColorblindMode::Letters);
+    app.update();
+    let mut shown = 0;
+    for (_, text, visibility, child_of) in symbols.iter(app.world()) {
+        assert_eq!(*visibility, Visibility::Inherited);
+        let color = app.world().get::<BubbleColor>(child_of.parent());
+        if let Some(color) = color {
+            assert_eq!(text.0, color.letter());
+            shown += 1;
+        }
+    }
+    // Both bubbles and the shooter's four queued snords
+    assert_eq!(shown, 6);
+
+    // The mode is saved, so put it back
+    app.insert_resource(ColorblindMode::Off);
+    app.update();
+}
+
+#[test]
+fn descent_diff_sees_only_the_new_row() {
+    let mut app = gameplay_app();
+    set_grid(
+        &mut app,
+        &[
+            (0, 0, BubbleColor::Blue),
+            (1, 1, BubbleColor::Red),
+            (-2, 2, BubbleColor::Green),
+        ],
+    );
+
+    app.world_mut().write_message(TriggerDescent);
+    app.update();
+
+    let diff = app.world().resource::<DescentDiff>();
+    let columns = app.world().resource::<HexGrid>().bounds.columns_in_row(-1);
+    assert_eq!(diff.before.len(), 3);
+    assert_eq!(diff.count(CellChange::Added), columns as usize);
+    assert_eq!(diff.count(CellChange::Moved), 0);
+    assert_eq!(diff.count(CellChange::Removed), 0);
+}
+
+#[test]
+fn twenty_descents_keep_every_row_one_hex_from_its_neighbors() {
+    let mut app = gameplay_app();
+    set_grid(
+        &mut app,
+        &[
+            (0, 0, BubbleColor::Blue),
+            (1, 0, BubbleColor::Red),
+            (0, 1, BubbleColor::Green),
+            (1, 1, BubbleColor::Blue),
+            (0, 2, BubbleColor::Red),
+        ],
+    );
+
+    for descent in 1..=20 {
+        app.world_mut().write_message(TriggerDescent);
+        app.update();
+
+        let diff = app.world().resource::<DescentDiff>();
+        assert!(
+            diff.broken.is_empty(),
+            "descent {descent} broke {:?}",
+            diff.broken
+        );
+        assert_eq!(diff.count(CellChange::Moved), 0, "descent {descent}");
+        // Each new row is one up, along the ceiling
+        let top = diff.after.iter().map(|cell| cell.coord.r).min().unwrap();
+        assert_eq!(top, -descent);
+        for cell in diff.after.iter().filter(|cell| cell.coord.r == top) {
+            assert!((cell.position.y - GRID_ORIGIN_Y).abs() < 0.01);
+        }
+
+        // Keep the board above the danger line, and get past power-up picks
+        app.world_mut()
+            .run_system_once(|mut commands: Commands, mut grid: ResMut<HexGrid>| {
+                for entity in grid.trim_bottom_rows(1) {
+                    commands.entity(entity).despawn();
+                }
+            })
+            .expect("trim system should run");
+        app.world_mut()
+            .resource_mut::<NextState<RunPhase>>()
+            .set(RunPhase::Playing);
+        app.world_mut()
+            .resource_mut::<NextState<Menu>>()
+            .set(Menu::None);
+        app.update();
+    }
+}
+
+#[test]
+fn descent_onto_an_empty_board_starts_along_the_ceiling() {
+    let mut app = gameplay_app();
+    set_grid(&mut app, &[(0, 0, BubbleColor::Blue)]);
+    for _ in 0..2 {
+        app.world_mut().write_message(TriggerDescent);
+        app.update();
+    }
+
+    set_grid(&mut app, &[]);
+    app.world_mut().write_message(TriggerDescent);
+    app.update();
+
+    let grid = app.world().resource::<HexGrid>();
+    let offset = app.world().resource::<GridOffset>().y;
+    let rows: Vec<i32> = grid.coords().map(|coord| coord.r).collect();
+    assert!(
+        !rows.is_empty() && rows.iter().all(|&r| r == -3),
+        "{rows:?}"
+    );
+    let ceiling = HexCoord::new(0, -3).to_pixel_with_offset(grid.hex_size, offset);
+    assert_eq!(ceiling.y, GRID_ORIGIN_Y);
+}
+
+#[test]
+fn arrow_keys_turn_the_aim_until_the_mouse_moves() {
+    let mut app = gameplay_app();
+    let aim_angle = |app: &mut App| {
+        let mut shooters = app
+            .world_mut()
+            .query_filtered::<&AimDirection, With<Shooter>>();
+        let aim = shooters.single(app.world()).unwrap().0;
+        aim.x.atan2(aim.y)
+    };
+    let hold = |app: &mut App, keys: &[KeyCode], frames: usize| {
+        let mut input = app.world_mut().resource_mut::<ButtonInput<KeyCode>>();
+        for &key in keys {
+            input.press(key);
+        }
+        for _ in 0..frames {
+            app.update();
+        }
+        app.world_mut()
+            .resource_mut::<ButtonInput<KeyCode>>()
+            .release_all();
+        app.update();
+    };
+
+    // Half a second to the right
+    hold(&mut app, &[KeyCode::ArrowRight], 30);
+    let turned = aim_angle(&mut app);
+    assert!((turned - 0.6).abs() < 0.03, "turned to {turned}");
+
+    // Shift turns a quarter as fast
+    hold(&mut app, &[KeyCode::KeyA, KeyCode::ShiftLeft], 30);
+    let fine = aim_angle(&mut app);
+    assert!((turned - fine - 0.15).abs() < 0.01, "turned back to {fine}");
+
+    // Right after the mouse moves, the keys leave the aim alone
+    app.world_mut().write_message(CursorMoved {
+        window: Entity::PLACEHOLDER,
+        position: Vec2::ZERO,
+        delta: None,
+    });
+    hold(&mut app, &[KeyCode::ArrowRight], 10);
+    assert_eq!(aim_angle(&mut app), fine);
+}
+
+#[test]
+fn gamepad_stick_points_the_aim() {
+    let mut app = gameplay_app();
+    let gamepad = app.world_mut().spawn(Gamepad::default()).id();
+    let push_stick = |app: &mut App, x: f32, y: f32| {
+        let mut gamepad = app.world_mut().get_mut::<Gamepad>(gamepad).unwrap();
+        gamepad.analog_mut().set(GamepadAxis::LeftStickX, x);
+        gamepad.analog_mut().set(GamepadAxis::LeftStickY, y);
+        app.update();
+        let mut shooters = app
+            .world_mut()
+            .query_filtered::<&AimDirection, With<Shooter>>();
+        shooters.single(app.world()).unwrap().0
+    };
+
+    // Up and to the left
+    let aim = push_stick(&mut app, -0.5, 0.5);
+    assert!(
+        aim.abs_diff_eq(Vec2::new(-1.0, 1.0).normalize(), 1e-4),
+        "aimed {aim}"
+    );
+
+    // A stick resting in its deadzone leaves the aim alone
+    assert_eq!(push_stick(&mut app, 0.1, 0.1), aim);
+
+    // Straight down still shoots upward
+    let aim = push_stick(&mut app, 0.0, -1.0);
+    assert!(aim.y > 0.0, "aimed {aim}");
+}
+
+#[test]
+fn swap_key_trades_the_loaded_snord_for_the_next() {
+    let mut app = gameplay_app();
+    let mut shooters = app
+        .world_mut()
+        .query_filtered::<(&LoadedBubble, &NextBubble), With<Shooter>>();
+    let mut queue = |app: &mut App| {
+        let (loaded, next) = shooters.single(app.world()).unwrap();
+        (loaded.0, next.0)
+    };
+    let (loaded, next) = queue(&mut app);
+
+    let swap = app.world().resource::<InputBindings>().swap.key;
+    press_key(&mut app, swap, Key::Character("s".into()));
+    assert_eq!(queue(&mut app), (next, loaded));
+}
+
+#[test]
+fn everyone_gets_a_short_aim_guide_that_can_be_turned_off() {
+    let mut app = gameplay_app();
+    set_grid(&mut app, &[(0, 0, BubbleColor::Red)]);
+    app.update();
+
+    // Shown segments and their lengths (the guide image is 300px wide)
+    let mut segments = app
+        .world_mut()
+        .query::<(&TrajectorySegment, &Transform, &Visibility)>();
+    let mut shown = |app: &mut App| -> Vec<f32> {
+        segments
+            .iter(app.world())
+            .filter(|(.., visibility)| **visibility != Visibility::Hidden)
+            .map(|(_, transform, _)| transform.scale.x * 300.0)
+            .collect()
+    };
+    let lengths = shown(&mut app);
+    assert_eq!(lengths.len(), 1, "{lengths:?}");
+    assert!(lengths[0] > 50.0 && lengths[0] <= 120.0, "{lengths:?}");
+
+    app.world_mut().resource_mut::<AimGuideSettings>().enabled = false;
+    app.update();
+    assert!(shown(&mut app).is_empty());
+}
+
+/// Click a UI button, the way a gamepad press on it does.
+fn click(app: &mut App, entity: Entity) {
+    let location = Location {
+        target: NormalizedRenderTarget::None {
+            width: 0,
+            height: 0,
+        },
+        position: Vec2::ZERO,
+    };
+    let click = Click {
+        button: PointerButton::Primary,
+        hit: HitData::new(Entity::PLACEHOLDER, 0.0, None, None),
+        duration: Duration::ZERO,
+    };
+    app.world_mut()
+        .trigger(Pointer::new(PointerId::Mouse, location, click, entity));
+    app.update();
+}
+
+#[test]
+fn controls_menu_rebinds_fire_to_the_next_key() {
+    let mut app = gameplay_app();
+    app.world_mut()
+        .resource_mut::<NextState<Menu>>()
+        .set(Menu::Controls);
+    app.update();
+    app.update();
+
+    let rebind_button = |app: &mut App, action: InputAction| {
+        let row = format!("{} Row", action.label());
+        let mut rows = app.world_mut().query::<(&Name, &Children)>();
+        let (_, children) = rows
+            .iter(app.world())
+            .find(|(name, _)| name.as_str() == row)
+            .unwrap();
+        children[1]
+    };
+
+    // Escape gives up on rebinding without leaving the menu
+    let swap_button = rebind_button(&mut app, InputAction::Swap);
+    click(&mut app, swap_button);
+    press_key(&mut app, KeyCode::Escape, Key::Escape);
+    assert_eq!(*app.world().resource::<State<Menu>>().get(), Menu::Controls);
+    assert_eq!(
+        app.world().resource::<InputBindings>().swap,
+        InputBindings::default().swap
+    );
+
+    let fire_button = rebind_button(&mut app, InputAction::Fire);
+    click(&mut app, fire_button);
+    let mut texts = app.world_mut().query::<&Text>();
+    assert!(
+        texts
+            .iter(app.world())
+            .any(|text| text.0 == "Press a key...")
+    );
+    press_enter(&mut app);
+    assert_eq!(
+        app.world().resource::<InputBindings>().fire.key,
+        KeyCode::Enter
+    );
+
+    // Back in the run, Enter fires
+    app.world_mut()
+        .resource_mut::<NextState<Menu>>()
+        .set(Menu::None);
+    app.update();
+    app.update();
+    press_enter(&mut app);
+    let mut shooters = app
+        .world_mut()
+        .query_filtered::<&ShooterState, With<Shooter>>();
+    assert_eq!(
+        *shooters.single(app.world()).unwrap(),
+        ShooterState::Reloading
+    );
+
+    // Leave the saved bindings as they were
+    app.insert_resource(InputBindings::default());
+    app.update();
+}
+
+#[test]
+fn descent_preview_ghosts_the_board_a_row_lower_before_the_last_shot() {
+    let mut app = gameplay_app();
+    set_grid(
+        &mut app,
+        &[(0, 0, BubbleColor::Red), (1, 0, BubbleColor::Blue)],
+    );
+    app.world_mut()
+        .resource_mut::<AccessibilitySettings>()
+        .descent_preview = true;
+    app.update();
+
+    // The ghost quads' positions, if the preview is showing
+    let ghost_positions = |app: &mut App| -> Option<Vec<Vec2>> {
+        let mut previews = app.world_mut().query::<(&Name, &Visibility, &Mesh2d)>();
+        let (_, visibility, mesh) = previews
+            .iter(app.world())
+            .find(|(name, ..)| name.as_str() == "Descent Preview")?;
+        if *visibility == Visibility::Hidden {
+            return None;
+        }
+        let mesh = app.world().resource::<Assets<Mesh>>().get(&mesh.0)?;
+        let positions = mesh.attribute(Mesh::ATTRIBUTE_POSITION)?.as_float3()?;
+        Some(positions.iter().map(|p| Vec2::new(p[0], p[1])).collect())
+    };
+    assert_eq!(ghost_positions(&mut app), None);
+
+    // One shot left before the descent
+    let powerups = app.world().resource::<UnlockedPowerUps>().clone();
+    let mut level = app.world_mut().resource_mut::<GameLevel>();
+    level.shots_this_round = level.descent_threshold(&powerups) - 1;
+    app.update();
+
+    let positions = ghost_positions(&mut app).expect("preview should show");
+    assert_eq!(positions.len(), 2 * 4);
+    let grid = app.world().resource::<HexGrid>();
+    let lowered = app.world().resource::<GridOffset>().y - grid.hex_size * 1.5;
+    let expected = (HexCoord::new(0, 0).to_pixel_with_offset(grid.hex_size, lowered)
+        + HexCoord::new(1, 0).to_pixel_with_offset(grid.hex_size, lowered))
+        / 2.0;
+    let center = positions.iter().sum::<Vec2>() / positions.len() as f32;
+    assert!(center.abs_diff_eq(expected, 1e-3), "{center} vs {expected}");
+
+    // Leave the saved settings as they were
+    app.world_mut()
+        .resource_mut::<AccessibilitySettings>()
+        .descent_preview = false;
+    app.update();
+    assert_eq!(ghost_positions(&mut app), None);
+}
+
+#[test]
+fn whats_new_opens_once_after_an_update() {
+    let mut app = gameplay_app();
+    let saved = app.world().resource::<AccessibilitySettings>().clone();
+    app.insert_resource(AccessibilitySettings {
+        prompt_seen: true,
+        whats_new_seen: Some("0.0.0".to_string()),
+        ..saved.clone()
+    });
+    let go_to_title = |app: &mut App| {
+        app.world_mut()
+            .resource_mut::<NextState<Screen>>()
+            .set(Screen::Title);
+        app.update();
+        app.update();
+    };
+
+    go_to_title(&mut app);
+    assert_eq!(*app.world().resource::<State<Menu>>().get(), Menu::WhatsNew);
+    let version = format!("Version {}", env!("CARGO_PKG_VERSION"));
+    let mut texts = app.world_mut().query::<&Text>();
+    assert!(texts.iter(app.world()).any(|text| text.0 == version));
+    press_key(&mut app, KeyCode::Escape, Key::Escape);
+    assert_eq!(*app.world().resource::<State<Menu>>().get(), Menu::Main);
+
+    // Seen, so the next visit goes straight to the main menu
+    app.world_mut()
+        .resource_mut::<NextState<Screen>>()
+        .set(Screen::Loading);
+    app.update();
+    go_to_title(&mut app);
+    assert_eq!(*app.world().resource::<State<Menu>>().get(), Menu::Main);
+
+    // Leave the saved settings as they were
+    app.insert_resource(saved);
+    app.update();
+}
+
+#[test]
+fn descent_into_danger_zone_ends_game() {
+    let mut app = gameplay_app();
+    // Row 14 sits exactly on the danger line; one descent pushes it past
+    set_grid(
+        &mut app,
+        &[(0, 0, BubbleColor::Blue), (0, 14, BubbleColor::Blue)],
+    );
+    assert_eq!(*app.world().resource::<State<Menu>>().get(), Menu::None);
+
+    app.world_mut().write_message(TriggerDescent);
+    // Descent, game over message, menu transition, then the pause it requests
+    for _ in 0..5 {
+        app.update();
+    }
+
+    assert_eq!(*app.world().resource::<State<Menu>>().get(), Menu::GameOver);
+    assert_eq!(
+        *app.world().resource::<State<RunPhase>>().get(),
+        RunPhase::GameEnding
+    );
+}
+
+#[test]
+fn time_attack_pops_add_time_until_the_clock_runs_out() {
+    let mut app = gameplay_app();
+    app.insert_resource(GameMode::TimeAttack);
+    set_grid(
+        &mut app,
+        &[(0, 0, BubbleColor::Blue), (1, 0, BubbleColor::Red)],
+    );
+    app.world_mut().resource_mut::<TimeAttackClock>().remaining = 1.0;
+
+    app.world_mut().write_message(ClusterPopped {
+        coords: vec![HexCoord::new(2, 0)],
+        color: BubbleColor::Green,
+        count: 4,
+    });
+    app.update();
+    let remaining = app.world().resource::<TimeAttackClock>().remaining;
+    assert!(remaining > 1.0 + 4.0 * TIME_PER_BUBBLE - 0.1);
+
+    for _ in 0..((remaining * 60.0) as usize + 10) {
+        app.update();
+    }
+    assert_eq!(*app.world().resource::<State<Menu>>().get(), Menu::GameOver);
+    assert_eq!(
+        *app.world().resource::<State<RunPhase>>().get(),
+        RunPhase::GameEnding
+    );
+}
+
+#[test]
+fn descents_autosave_the_run_for_resuming() {
+    let mut app = gameplay_app();
+    set_grid(
+        &mut app,
+        &[(0, 0, BubbleColor::Blue), (1, 0, BubbleColor::Red)],
+    );
+    app.world_mut().write_message(TriggerDescent);
+    app.update();
+    app.update();
+
+    let saved = app
+        .world()
+        .resource::<Autosave>()
+        .run
+        .clone()
+        .expect("the descent should autosave the run");
+    assert_eq!(saved.snapshot.level.level, 2);
+    let colors = grid_colors(&mut app);
+
+    // Leaving the run clears the autosave; resuming puts the board back
+    app.world_mut().resource_mut::<PendingResume>().0 = Some(saved);
+    restart_run(&mut app);
+    app.update();
+
+    assert_eq!(grid_colors(&mut app), colors);
+    assert_eq!(app.world().resource::<GameLevel>().level, 2);
+    assert!(app.world().resource::<PendingResume>().0.is_none());
+    assert!(app.world().resource::<Autosave>().run.is_some());
+}
+
+#[test]
+fn power_up_milestone_freezes_the_run_until_a_pick() {
+    let mut app = gameplay_app();
+    assert_eq!(
+        *app.world().resource::<State<RunPhase>>().get(),
+        RunPhase::Playing
+    );
+    set_grid(&mut app, &[(0, 0, BubbleColor::Blue)]);
+    app.world_mut().resource_mut::<GameLevel>().level = 4;
+
+    app.world_mut().write_message(TriggerDescent);
+    app.update();
+    app.update();
+
+    assert_eq!(
+        *app.world().resource::<State<RunPhase>>().get(),
+        RunPhase::PowerUpChoice
+    );
+    assert_eq!(
+        *app.world().resource::<State<Menu>>().get(),
+        Menu::PowerUpSelect
+    );
+    let elapsed = app.world().resource::<Time<GameClock>>().elapsed();
+    app.update();
+    assert_eq!(app.world().resource::<Time<GameClock>>().elapsed(), elapsed);
+
+    app.world_mut()
+        .resource_mut::<NextState<RunPhase>>()
+        .set(RunPhase::Playing);
+    app.update();
+    app.update();
+    assert!(app.world().resource::<Time<GameClock>>().elapsed() > elapsed);
+}
+
+#[test]
+fn handicaps_start_further_along_with_their_picks_up_front() {
+    let mut app = gameplay_app();
+    *app.world_mut().resource_mut::<Handicap>() = Handicap::Level10;
+    restart_run(&mut app);
+    app.update();
+
+    assert_eq!(app.world().resource::<GameLevel>().level, 10);
+    let deepest = grid_colors(&mut app)
+        .iter()
+        .map(|(coord, _)| coord.r)
+        .max()
+        .unwrap();
+    assert_eq!(deepest, 6, "two extra starting rows");
+
+    // One pick per skipped milestone, before the first shot
+    for level in [5, 10] {
+        assert_eq!(
+            *app.world().resource::<State<Menu>>().get(),
+            Menu::PowerUpSelect
+        );
+        let choices = app.world().resource::<PowerUpChoices>();
+        assert_eq!(choices.level, level);
+        let pick = choices.choices[0];
+        app.world_mut().resource_mut::<UnlockedPowerUps>().add(pick);
+        app.world_mut()
+            .resource_mut::<NextState<Menu>>()
+            .set(Menu::None);
+        app.world_mut()
+            .resource_mut::<NextState<RunPhase>>()
+            .set(RunPhase::Playing);
+        app.update();
+        app.update();
+    }
+    assert_eq!(*app.world().resource::<State<Menu>>().get(), Menu::None);
+    assert_eq!(app.world().resource::<UnlockedPowerUps>().powers.len(), 2);
+
+    // Points are worth half again as much
+    app.world_mut().resource_mut::<GameScore>().score = 0;
+    app.world_mut().write_message(ClusterPopped {
+        coords: vec![HexCoord::new(0, 0), HexCoord::new(1, 0)],
+        color: BubbleColor::Blue,
+        count: 2,
+    });
+    app.update();
+    let scored = app.world().resource::<GameScore>().score;
+    assert_eq!(scored, 2 * POINTS_PER_BUBBLE * 3 / 2);
+}
+
+#[test]
+fn weekly_challenge_runs_share_a_seed_and_keep_their_own_scores() {
+    let mut app = gameplay_app();
+    let challenge = WeeklyChallenge::current();
+    *app.world_mut().resource_mut::<SelectedMode>() = SelectedMode::Weekly;
+    *app.world_mut().resource_mut::<Handicap>() = Handicap::Level10;
+    restart_run(&mut app);
+
+    assert_eq!(app.world().resource::<ActiveChallenge>().0, Some(challenge));
+    assert_eq!(app.world().resource::<RunSeed>().0, Some(challenge.seed));
+    // Everyone starts level 1, whatever their handicap
+    assert_eq!(app.world().resource::<GameLevel>().level, 1);
+    let deepest = grid_colors(&mut app)
+        .iter()
+        .map(|(coord, _)| coord.r)
+        .max()
+        .unwrap();
+    let extra_rows = if challenge.modifiers.contains(&Modifier::ExtraRows) {
+        2
+    } else {
+        0
+    };
+    assert_eq!(deepest, 4 + extra_rows);
+    if challenge.modifiers.contains(&Modifier::NoGrace) {
+        assert_eq!(app.world().resource::<DangerGrace>().remaining, 0);
+    }
+
+    app.world_mut().resource_mut::<GameScore>().score = 1_000_000;
+    let top_scores = app.world().resource::<HighScores>().entries.len();
+    app.world_mut().write_message(GameEnded {
+        outcome: RunOutcome::GridReachedDanger,
+    });
+    app.update();
+
+    assert_eq!(
+        app.world().resource::<HighScores>().entries.len(),
+        top_scores
+    );
+    let weekly = app.world().resource::<WeeklyScores>();
+    assert_eq!(weekly.week, challenge.week);
+    assert_eq!(weekly.scores.entries[0].score, 1_000_000);
+}
+
+#[test]
+fn snapshot_round_trips_through_json() {
+    let mut app = gameplay_app();
+    set_grid(
+        &mut app,
+        &[
+            (0, 0, BubbleColor::Red),
+            (1, 0, BubbleColor::Purple),
+            (0, 1, BubbleColor::Orange),
+        ],
+    );
+    app.world_mut().resource_mut::<GameScore>().score = 120;
+    let saved = GameSnapshot::capture(app.world_mut());
+    let json = serde_json::to_string(&saved).expect("snapshot should serialize");
+
+    app.world_mut().write_message(TriggerDescent);
+    app.update();
+    app.update();
+    assert_ne!(GameSnapshot::capture(app.world_mut()), saved);
+
+    let loaded: GameSnapshot = serde_json::from_str(&json).expect("snapshot should deserialize");
+    loaded.restore(app.world_mut());
+    app.update();
+
+    assert_eq!(GameSnapshot::capture(app.world_mut()), saved);
+    assert_eq!(grid_colors(&mut app).len(), 3);
+}
+
+#[test]
+fn snapshot_survives_the_stress_test() {
+    let mut app = gameplay_app();
+    set_grid(
+        &mut app,
+        &[
+            (0, 0, BubbleColor::Red),
+            (1, 0, BubbleColor::Purple),
+            (0, 1, BubbleColor::Orange),
+        ],
+    );
+    app.world_mut().resource_mut::<GameScore>().score = 75;
+    let saved = GameSnapshot::capture(app.world_mut());
+
+    assert_eq!(stress_test(app.world_mut(), STRESS_TEST_ROUNDS), Ok(()));
+    app.update();
+    assert_eq!(GameSnapshot::capture(app.world_mut()), saved);
+}
+
+#[test]
+fn gameplay_entities_hang_off_the_root_and_clear_together() {
+    let mut app = gameplay_app();
+    set_grid(&mut app, &[(0, 0, BubbleColor::Blue)]);
+    let sparkle = app
+        .world_mut()
+        .spawn((Transform::default(), GameplayEntity))
+        .id();
+    let label = app
+        .world_mut()
+        .spawn((Node::default(), GameplayEntity))
+        .id();
+    app.update();
+
+    let mut roots = app.world_mut().query_filtered::<Entity, With<GameRoot>>();
+    let root = roots.single(app.world()).expect("game root should exist");
+    assert_eq!(
+        app.world().get::<ChildOf>(sparkle).map(ChildOf::parent),
+        Some(root)
+    );
+    assert!(app.world().get::<ChildOf>(label).is_none());
+
+    app.world_mut().write_message(ClearGameplayEntities);
+    app.update();
+
+    assert!(app.world().get_entity(sparkle).is_err());
+    assert!(app.world().get_entity(label).is_err());
+    let mut tagged = app.world_mut().query_filtered::<(), With<GameplayEntity>>();
+    assert_eq!(tagged.iter(app.world()).count(), 0);
+    assert!(app.world().get_entity(root).is_ok());
+    // The grid's bubbles aren't per-run effects
+    assert_eq!(grid_colors(&mut app).len(), 1);
+    let grid = app.world().resource::<HexGrid>();
+    assert!(
+        grid.iter()
+            .all(|(_, &bubble)| app.world().get_entity(bubble).is_ok())
+    );
+}
+
+#[test]
+fn drill_fails_once_shots_run_out() {
+    let mut app = gameplay_app();
+    let drills = app.world().resource::<Drills>();
+    assert!(!drills.0.is_empty());
+    for drill in &drills.0 {
+        assert!(drill.cells().is_ok(), "bad board in {}", drill.name);
+        assert!(drill.queue_colors().is_ok(), "bad queue in {}", drill.name);
+    }
+    let shots = drills.0[0].shots;
+    let bests_before = app.world().resource::<DrillBests>().times.len();
+
+    app.world_mut().resource_mut::<ActiveDrill>().0 = Some(0);
+    app.update();
+    set_grid(&mut app, &[(0, 0, BubbleColor::Red)]);
+
+    // Alternate colors so nothing pops
+    for shot in 0..shots {
+        let color = if shot % 2 == 0 {
+            BubbleColor::Blue
+        } else {
+            BubbleColor::Green
+        };
+        fire_straight_up(&mut app, color);
+    }
+    assert_eq!(*app.world().resource::<State<Menu>>().get(), Menu::None);
+
+    // Wait out the settle time after the last shot
+    for _ in 0..90 {
+        app.update();
+    }
+
+    assert_eq!(*app.world().resource::<State<Menu>>().get(), Menu::Drills);
+    assert_eq!(
+        *app.world().resource::<State<RunPhase>>().get(),
+        RunPhase::GameEnding
+    );
+    assert_eq!(
+        app.world().resource::<DrillBests>().times.len(),
+        bests_before
+    );
+}
+
+#[test]
+fn zen_mode_skips_descent_and_refills_sparse_board() {
+    let mut app = gameplay_app();
+    app.insert_resource(GameMode::Zen);
+    set_grid(&mut app, &[(0, 0, BubbleColor::Blue)]);
+    app.update();
+
+    let bounds = app.world().resource::<HexGrid>().bounds;
+    let row = (bounds.max_q - bounds.min_q + 1) as usize;
+    assert_eq!(grid_colors(&mut app).len(), 4 * row);
+
+    app.world_mut().write_message(TriggerDescent);
+    app.update();
+    app.update();
+
+    assert_eq!(app.world().resource::<GameLevel>().level, 1);
+    assert_eq!(grid_colors(&mut app).len(), 4 * row);
+    assert_eq!(*app.world().resource::<State<Menu>>().get(), Menu::None);
+}
+
+#[test]
+fn selected_mode_sets_the_rules_and_layout() {
+    let mut app = gameplay_app();
+    *app.world_mut().resource_mut::<SelectedMode>() = SelectedMode::Kids;
+    app.update();
+
+    assert_eq!(*app.world().resource::<GameMode>(), GameMode::Kids);
+    assert_eq!(
+        app.world().resource::<HexGrid>().hex_size,
+        GameMode::Kids.hex_size()
+    );
+
+    // Modes without rules yet leave the current ones alone
+    *app.world_mut().resource_mut::<SelectedMode>() = SelectedMode::Daily;
+    app.update();
+    assert_eq!(*app.world().resource::<GameMode>(), GameMode::Kids);
+}
+
+#[test]
+fn campaign_walls_close_in_and_bounce_shots() {
+    let mut app = gameplay_app();
+    app.world_mut().resource_mut::<Profile>().campaign_cleared = 0;
+    *app.world_mut().resource_mut::<SelectedMode>() = SelectedMode::Campaign;
+    app.update();
+
+    assert_eq!(app.world().resource::<ActiveLevel>().0, Some(0));
+    // A plain level may start mirrored, turned, or both
+    let cells = app.world().resource::<CampaignLevels>().0[0]
+        .cells()
+        .unwrap();
+    let bounds = GameMode::Campaign.grid_bounds();
+    let variants: Vec<Vec<GridCell>> = [
+        &[][..],
+        &[LayoutTransform::Mirror],
+        &[LayoutTransform::Rotate180],
+        &[LayoutTransform::Mirror, LayoutTransform::Rotate180],
+    ]
+    .iter()
+    .filter_map(|transforms| {
+        transforms
+            .iter()
+            .try_fold(cells.clone(), |cells, transform| {
+                transform.apply(&cells, bounds)
+            })
+    })
+    .collect();
+    let board = app.world().resource::<StartingBoard>().0.clone().unwrap();
+    assert!(variants.contains(&board));
+
+    for _ in 0..60 {
+        app.update();
+    }
+    let walls = *app.world().resource::<Walls>();
+    assert!(walls.left > Walls::default().left);
+    assert!(walls.right < Walls::default().right);
+
+    // A shot at the left wall turns around at the moved wall, not the old one
+    app.insert_resource(Walls {
+        left: -150.0,
+        right: 150.0,
+    });
+    set_grid(&mut app, &[(0, 0, BubbleColor::Blue)]);
+    app.world_mut().write_message(FireProjectile {
+        position: Vec2::new(0.0, SHOOTER_Y),
+        direction: Vec2::new(-1.0, 0.2),
+        color: BubbleColor::Red,
+        wildcard: false,
+    });
+    let mut leftmost = f32::MAX;
+    for _ in 0..MAX_FLIGHT_FRAMES {
+        app.update();
+        let mut projectiles = app
+            .world_mut()
+            .query_filtered::<&Transform, With<Projectile>>();
+        let Some(transform) = projectiles.iter(app.world()).next() else {
+            break;
+        };
+        leftmost = leftmost.min(transform.translation.x);
+    }
+    let walls = *app.world().resource::<Walls>();
+    assert!(
+        leftmost > walls.left - 1.0,
+        "{leftmost} went past {walls:?}"
+    );
+}
+
+#[test]
+fn winning_a_campaign_level_starts_the_next() {
+    let mut app = gameplay_app();
+    // Start from the first level, whatever the saved profile says
+    let cleared = std::mem::take(&mut app.world_mut().resource_mut::<Profile>().campaign_cleared);
+    *app.world_mut().resource_mut::<SelectedMode>() = SelectedMode::Campaign;
+    app.update();
+    assert_eq!(*app.world().resource::<GameMode>(), GameMode::Campaign);
+    assert_eq!(app.world().resource::<ActiveLevel>().0, Some(0));
+
+    set_grid(
+        &mut app,
+        &[(0, 0, BubbleColor::Red), (1, 0, BubbleColor::Red)],
+    );
+    fire_straight_up(&mut app, BubbleColor::Red);
+
+    // Graded on the results screen first, once the pop settles
+    for _ in 0..MAX_FLIGHT_FRAMES {
+        if *app.world().resource::<State<Menu>>().get() == Menu::LevelResults {
+            break;
+        }
+        app.update();
+    }
+    assert_eq!(
+        *app.world().resource::<State<Menu>>().get(),
+        Menu::LevelResults
+    );
+    let grade = app.world().resource::<LevelGrade>().0.clone().unwrap();
+    assert_eq!(grade.stats.shots, 1);
+    assert_eq!(grade.rank, grade.stats.rank(grade.par));
+
+    // Then back into gameplay through the loading screen
+    press_enter(&mut app);
+    let mut reloaded = false;
+    for _ in 0..MAX_LOADING_FRAMES {
+        app.update();
+        match app.world().resource::<State<Screen>>().get() {
+            Screen::Loading => reloaded = true,
+            Screen::Gameplay if reloaded => break,
+            _ => {}
+        }
+    }
+    assert!(reloaded);
+    app.update();
+
+    assert_eq!(app.world().resource::<ActiveLevel>().0, Some(1));
+    assert_eq!(app.world().resource::<Profile>().campaign_cleared, 1);
+    assert_ne!(*app.world().resource::<State<Menu>>().get(), Menu::Credits);
+    let board = app.world().resource::<CampaignLevels>().0[1]
+        .cells()
+        .unwrap();
+    assert_eq!(grid_colors(&mut app).len(), board.len());
+
+    // Winning saved the profile; put the real progress back
+    let mut profile = app.world_mut().resource_mut::<Profile>();
+    profile.campaign_cleared = cleared;
+    profile.save();
+}
+
+#[test]
+fn level_scripts_place_bubbles_when_shots_are_fired() {
+    let mut app = gameplay_app();
+    let mut levels = app.world_mut().resource_mut::<CampaignLevels>();
+    let mut level = levels.0[0].clone();
+    level.script = vec![
+        "fn on_shot(n) {".to_string(),
+        "    place(n, 2, \"P\");".to_string(),
+        "}".to_string(),
+    ];
+    levels.0.push(level);
+    let index = levels.0.len() - 1;
+    app.world_mut().resource_mut::<Profile>().campaign_cleared = index as u32;
+    *app.world_mut().resource_mut::<SelectedMode>() = SelectedMode::Campaign;
+    app.update();
+    assert_eq!(app.world().resource::<ActiveLevel>().0, Some(index));
+
+    set_grid(&mut app, &[(0, 0, BubbleColor::Blue)]);
+    fire_straight_up(&mut app, BubbleColor::Red);
+
+    let min_q = GridBounds::default().min_q;
+    assert!(grid_colors(&mut app).contains(&(HexCoord::new(min_q + 1, 2), BubbleColor::Purple)));
+}
+
+#[test]
+fn script_dialogue_freezes_the_board_until_clicked_through() {
+    let mut app = gameplay_app();
+    app.world_mut().resource_mut::<DialogueScenes>().0.insert(
+        "boss".to_string(),
+        vec![DialogueLine {
+            speaker: "The Big Grump".to_string(),
+            portrait: "angry".to_string(),
+            text: "Hmph.".to_string(),
+        }],
+    );
+    let mut levels = app.world_mut().resource_mut::<CampaignLevels>();
+    let mut level = levels.0[0].clone();
+    level.script = vec!["fn on_shot(n) { talk(\"boss\"); }".to_string()];
+    levels.0.push(level);
+    let index = levels.0.len() - 1;
+    app.world_mut().resource_mut::<Profile>().campaign_cleared = index as u32;
+    *app.world_mut().resource_mut::<SelectedMode>() = SelectedMode::Campaign;
+    app.update();
+
+    set_grid(&mut app, &[(0, 0, BubbleColor::Blue)]);
+    app.world_mut().write_message(FireProjectile {
+        position: Vec2::new(0.0, SHOOTER_Y),
+        direction: Vec2::Y,
+        color: BubbleColor::Red,
+        wildcard: false,
+    });
+    for _ in 0..3 {
+        app.update();
+    }
+    assert_eq!(
+        *app.world().resource::<State<RunPhase>>().get(),
+        RunPhase::Dialogue
+    );
+
+    // The shot hangs in the air while the scene plays
+    let mut projectiles = app
+        .world_mut()
+        .query_filtered::<&Transform, With<Projectile>>();
+    let before = projectiles.single(app.world()).unwrap().translation;
+    app.update();
+    let after = projectiles.single(app.world()).unwrap().translation;
+    assert_eq!(before, after);
+
+    // One press finishes typing the line, the next ends the scene
+    press_enter(&mut app);
+    press_enter(&mut app);
+    app.update();
+    assert_eq!(
+        *app.world().resource::<State<RunPhase>>().get(),
+        RunPhase::Playing
+    );
+}
+
+#[test]
+fn shots_through_a_portal_come_out_of_its_twin() {
+    let mut app = gameplay_app();
+    // The shot reaches the left wall around y=-120
+    app.insert_resource(Portals(vec![[
+        Portal {
+            side: WallSide::Left,
+            y: -120.0,
+        },
+        Portal {
+            side: WallSide::Right,
+            y: 0.0,
+        },
+    ]]));
+    set_grid(&mut app, &[(0, 0, BubbleColor::Blue)]);
+    app.world_mut().write_message(FireProjectile {
+        position: Vec2::new(0.0, SHOOTER_Y),
+        direction: Vec2::new(-1.0, 0.4),
+        color: BubbleColor::Red,
+        wildcard: false,
+    });
+
+    let mut positions = Vec::new();
+    for _ in 0..MAX_FLIGHT_FRAMES {
+        app.update();
+        let mut projectiles = app
+            .world_mut()
+            .query_filtered::<&Transform, With<Projectile>>();
+        let Some(transform) = projectiles.iter(app.world()).next() else {
+            break;
+        };
+        positions.push(transform.translation.truncate());
+    }
+
+    // It jumped from the left wall to the right one, still heading left
+    let jump = positions
+        .windows(2)
+        .find(|pair| pair[1].x - pair[0].x > 200.0)
+        .expect("shot never went through the portal");
+    assert!((jump[1].y - jump[0].y - 120.0).abs() < 20.0);
+    assert!(positions.last().unwrap().x < jump[1].x);
+}
+
+#[test]
+fn bumpers_knock_shots_back_and_score() {
+    let mut app = gameplay_app();
+    app.insert_resource(Bumpers(vec![Bumper {
+        x: 0.0,
+        y: -50.0,
+        radius: 16.0,
+    }]));
+    set_grid(&mut app, &[(0, 0, BubbleColor::Blue)]);
+    let before = app.world().resource::<GameScore>().score;
+
+    // Straight into the bumper and straight back down past the shooter
+    fire_straight_up(&mut app, BubbleColor::Red);
+
+    assert_eq!(
+        app.world().resource::<GameScore>().score,
+        before + BUMPER_POINTS
+    );
+    assert_eq!(grid_colors(&mut app).len(), 1);
+}
+
+#[test]
+fn sticky_walls_catch_shots_and_hold_them_up() {
+    let mut app = gameplay_app();
+    app.insert_resource(StickyWalls(vec![StickyZone {
+        side: WallSide::Left,
+        top: 0.0,
+        bottom: -160.0,
+    }]));
+    set_grid(
+        &mut app,
+        &[
+            (0, 0, BubbleColor::Red),
+            (1, 0, BubbleColor::Red),
+            (-5, 0, BubbleColor::Blue),
+        ],
+    );
+
+    // Reaches the left wall around y=-120 and sticks there
+    app.world_mut().write_message(FireProjectile {
+        position: Vec2::new(0.0, SHOOTER_Y),
+        direction: Vec2::new(-1.0, 0.4),
+        color: BubbleColor::Green,
+        wildcard: false,
+    });
+    for _ in 0..MAX_FLIGHT_FRAMES {
+        app.update();
+        let mut projectiles = app.world_mut().query_filtered::<(), With<Projectile>>();
+        if projectiles.iter(app.world()).next().is_none() {
+            break;
+        }
+    }
+    let stuck: Vec<HexCoord> = grid_colors(&mut app)
+        .into_iter()
+        .filter(|(_, color)| *color == BubbleColor::Green)
+        .map(|(coord, _)| coord)
+        .collect();
+    assert_eq!(stuck.len(), 1);
+    assert!(stuck[0].r > 5, "stuck at {:?}", stuck[0]);
+
+    // Popping the reds rechecks what's anchored; the wall holds the green up
+    fire_straight_up(&mut app, BubbleColor::Red);
+    assert_eq!(
+        grid_colors(&mut app),
+        vec![
+            (HexCoord::new(-5, 0), BubbleColor::Blue),
+            (stuck[0], BubbleColor::Green)
+        ]
+    );
+}
+
+#[test]
+fn level_anchors_replace_the_top_row() {
+    let mut app = gameplay_app();
+    app.insert_resource(LevelAnchors(Some(vec![HexCoord::new(-4, 6)])));
+    set_grid(
+        &mut app,
+        &[
+            (0, 0, BubbleColor::Red),
+            (1, 0, BubbleColor::Red),
+            // On the top row, but not an anchor, so it falls
+            (-5, 0, BubbleColor::Yellow),
+            // The island and what hangs from it
+            (-4, 6, BubbleColor::Blue),
+            (-4, 7, BubbleColor::Green),
+        ],
+    );
+
+    fire_straight_up(&mut app, BubbleColor::Red);
+
+    assert_eq!(
+        grid_colors(&mut app),
+        vec![
+            (HexCoord::new(-4, 6), BubbleColor::Blue),
+            (HexCoord::new(-4, 7), BubbleColor::Green),
+        ]
+    );
+}
+
+#[test]
+fn dropped_bubbles_fall_into_score_zones() {
+    let mut app = gameplay_app();
+    app.insert_resource(ScoreZones(vec![ScoreZone {
+        left: -400.0,
+        right: 400.0,
+        multiplier: 5,
+    }]));
+    set_grid(
+        &mut app,
+        &[
+            (0, 0, BubbleColor::Red),
+            (1, 0, BubbleColor::Red),
+            (1, 1, BubbleColor::Green),
+            (-5, 0, BubbleColor::Blue),
+        ],
+    );
+
+    fire_straight_up(&mut app, BubbleColor::Red);
+    let mut falling = app.world_mut().query_filtered::<(), With<FallingBubble>>();
+    assert_eq!(falling.iter(app.world()).count(), 1);
+
+    for _ in 0..MAX_FLIGHT_FRAMES {
+        app.update();
+        if falling.iter(app.world()).next().is_none() {
+            break;
+        }
+    }
+    assert_eq!(falling.iter(app.world()).count(), 0);
+    // One more frame for scoring to react
+    app.update();
+    // As in matching_shot_pops_cluster_and_drops_floaters, plus the drop
+    // bonus four more times for the x5 zone
+    let score = app.world().resource::<GameScore>();
+    assert_eq!(score.score, 50 + 2 * COLOR_CLEAR_BONUS + 4 * 20);
+}
+
+#[test]
+fn kids_mode_uses_bigger_hexes_and_fewer_columns() {
+    let mut app = gameplay_app();
+    app.insert_resource(GameMode::Kids);
+    app.update();
+
+    let grid = app.world().resource::<HexGrid>();
+    assert_eq!(grid.hex_size, GameMode::Kids.hex_size());
+    assert_eq!(grid.bounds, GameMode::Kids.grid_bounds());
+
+    set_grid(&mut app, &[(0, 0, BubbleColor::Blue)]);
+    fire_straight_up(&mut app, BubbleColor::Red);
+
+    let colors = grid_colors(&mut app);
+    assert_eq!(colors.len(), 2);
+    let (coord, _) = colors[1];
+    assert_eq!(coord.r, 1, "shot should stick directly below the top row");
+
+    // The landed bubble sits on the kids-sized grid
+    let offset = app.world().resource::<GridOffset>().y;
+    let expected = coord.to_pixel_with_offset(GameMode::Kids.hex_size(), offset);
+    let mut bubbles = app.world_mut().query::<(&Bubble, &Transform)>();
+    let (_, transform) = bubbles
+        .iter(app.world())
+        .find(|(bubble, _)| bubble.coord == coord)
+        .expect("landed bubble should exist");
+    assert_eq!(transform.translation.truncate(), expected);
+}
+
+#[test]
+fn abandoned_run_stays_off_leaderboard_by_default() {
+    let mut app = gameplay_app();
+    app.world_mut().resource_mut::<GameScore>().score = 1_000_000;
+    let before = app.world().resource::<HighScores>().entries.len();
+
+    app.world_mut().write_message(GameEnded {
+        outcome: RunOutcome::Abandoned,
+    });
+    app.update();
+
+    assert_eq!(app.world().resource::<HighScores>().entries.len(), before);
+}
+
+#[test]
+fn continued_runs_make_the_leaderboard_once_with_the_halved_score() {
+    let mut app = gameplay_app();
+    let saved = HighScores::load();
+    app.world_mut().resource_mut::<HighScores>().entries.clear();
+    app.world_mut().resource_mut::<GameScore>().score = 1000;
+
+    app.world_mut().write_message(GameEnded {
+        outcome: RunOutcome::GridReachedDanger,
+    });
+    app.update();
+    assert!(app.world().resource::<HighScores>().entries.is_empty());
+
+    app.world_mut().write_message(ContinueRun);
+    app.update();
+    assert_eq!(app.world().resource::<GameScore>().score, 500);
+
+    app.world_mut().write_message(GameEnded {
+        outcome: RunOutcome::GridReachedDanger,
+    });
+    app.update();
+    app.world_mut()
+        .resource_mut::<NextState<Screen>>()
+        .set(Screen::Title);
+    app.update();
+
+    let entries = &app.world().resource::<HighScores>().entries;
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].score, 500);
+    saved.save();
+}
+
+#[test]
+fn sounds_come_back_for_runs_that_skip_the_loading_screen() {
+    let mut app = gameplay_app();
+    app.world_mut()
+        .resource_mut::<NextState<Screen>>()
+        .set(Screen::Title);
+    app.update();
+    assert!(!app.world().contains_resource::<GameAudioAssets>());
+
+    // The demo and the replay viewer go straight in
+    app.world_mut()
+        .resource_mut::<NextState<Screen>>()
+        .set(Screen::Gameplay);
+    for _ in 0..MAX_LOADING_FRAMES {
+        app.update();
+        if app.world().contains_resource::<GameAudioAssets>() {
+            return;
+        }
+        std::thread::sleep(Duration::from_millis(1));
+    }
+    panic!("the run's sounds never loaded");
+}
+
+#[test]
+fn a_modes_shot_clock_can_waste_the_shot_when_it_runs_out() {
+    let mut app = gameplay_app();
+    let mut config = ShotClockConfig::default();
+    *config.get_mut(GameMode::Classic) = ShotClockRules {
+        enabled: true,
+        seconds: 0.5,
+        on_timeout: ShotClockTimeout::WastedShot,
+    };
+    // Inserted fresh, so it isn't saved over the player's settings
+    app.world_mut().remove_resource::<ShotClockConfig>();
+    app.insert_resource(config);
+    app.world_mut().resource_mut::<ShotClock>().remaining = 0.5;
+    let shots = app.world().resource::<GameLevel>().shots_this_round;
+
+    for _ in 0..40 {
+        app.update();
+    }
+
+    assert_eq!(
+        app.world().resource::<GameLevel>().shots_this_round,
+        shots + 1
+    );
+    let mut projectiles = app.world_mut().query_filtered::<(), With<Projectile>>();
+    assert_eq!(projectiles.iter(app.world()).count(), 0);
+}
+
+#[test]
+fn shot_history_records_bounces() {
+    let mut app = gameplay_app();
+    set_grid(&mut app, &[(0, 0, BubbleColor::Blue)]);
+
+    app.world_mut().write_message(FireProjectile {
+        position: Vec2::new(0.0, SHOOTER_Y),
+        direction: Vec2::new(1.0, 1.0),
+        color: BubbleColor::Red,
+        wildcard: false,
+    });
+    for _ in 0..MAX_FLIGHT_FRAMES {
+        app.update();
+    }
+
+    let history = app.world().resource::<ShotHistory>();
+    assert_eq!(history.paths.len(), 1);
+    let path = &history.paths[0];
+    // Launch, at least one wall bounce, and the end point
+    assert!(path.len() >= 3, "expected a bounce in {path:?}");
+    assert!(path[0].y < path[path.len() - 1].y);
+}
+
+#[test]
+fn integrity_check_repairs_grid_desync() {
+    let mut app = gameplay_app();
+    set_grid(
+        &mut app,
+        &[(0, 0, BubbleColor::Blue), (1, 0, BubbleColor::Green)],
+    );
+
+    // Corrupt the grid three ways
+    let gone = app.world_mut().spawn_empty().id();
+    app.world_mut().despawn(gone);
+    let world = app.world_mut();
+    let mut grid = world.resource_mut::<HexGrid>();
+    let blue = grid.get(HexCoord::new(0, 0)).unwrap();
+    let green = grid.get(HexCoord::new(1, 0)).unwrap();
+    grid.insert(HexCoord::new(5, 0), gone);
+    grid.insert(HexCoord::new(-1, 0), blue);
+    world.get_mut::<Bubble>(green).unwrap().coord = HexCoord::new(3, 3);
+
+    // Checks run every few seconds
+    for _ in 0..4 * 60 {
+        app.update();
+    }
+
+    let grid = app.world().resource::<HexGrid>();
+    assert_eq!(grid.len(), 2);
+    assert_eq!(grid.get(HexCoord::new(0, 0)), Some(blue));
+    assert_eq!(grid.get(HexCoord::new(1, 0)), Some(green));
+    assert_eq!(
+        app.world().get::<Bubble>(green).unwrap().coord,
+        HexCoord::new(1, 0)
+    );
+}
+
+#[test]
+fn grid_commands_move_bubbles_with_their_components() {
+    let mut app = gameplay_app();
+    set_grid(
+        &mut app,
+        &[(0, 0, BubbleColor::Blue), (1, 0, BubbleColor::Green)],
+    );
+    let blue = app
+        .world()
+        .resource::<HexGrid>()
+        .get(HexCoord::new(0, 0))
+        .unwrap();
+
+    app.world_mut()
+        .run_system_once(|mut grid: GridCommands| {
+            let entity = grid.remove(HexCoord::new(0, 0)).unwrap();
+            grid.insert(HexCoord::new(0, 1), entity);
+            grid.shift_down(30.0);
+        })
+        .expect("grid commands system should run");
+
+    let grid = app.world().resource::<HexGrid>();
+    let offset_y = app.world().resource::<GridOffset>().y;
+    let coord = HexCoord::new(0, 1);
+    assert_eq!(grid.get(coord), Some(blue));
+    assert!(!grid.is_occupied(HexCoord::new(0, 0)));
+    assert_eq!(app.world().get::<Bubble>(blue).unwrap().coord, coord);
+    assert_eq!(
+        app.world()
+            .get::<Transform>(blue)
+            .unwrap()
+            .translation
+            .truncate(),
+        coord.to_pixel_with_offset(grid.hex_size, offset_y)
+    );
+}
+
+#[test]
+fn pausing_freezes_game_clock_and_projectiles() {
+    let mut app = gameplay_app();
+    set_grid(&mut app, &[(0, 0, BubbleColor::Red)]);
+    app.world_mut().write_message(FireProjectile {
+        position: Vec2::new(0.0, SHOOTER_Y),
+        direction: Vec2::Y,
+        color: BubbleColor::Blue,
+        wildcard: false,
+    });
+    app.update();
+    app.update();
+
+    app.world_mut()
+        .resource_mut::<NextState<Pause>>()
+        .set(Pause(true));
+    app.update();
+    let elapsed = app.world().resource::<Time<GameClock>>().elapsed();
+    let mut projectiles = app
+        .world_mut()
+        .query_filtered::<&Transform, With<Projectile>>();
+    let position = projectiles.single(app.world()).unwrap().translation;
+
+    for _ in 0..30 {
+        app.update();
+    }
+    assert_eq!(app.world().resource::<Time<GameClock>>().elapsed(), elapsed);
+    assert_eq!(
+        projectiles.single(app.world()).unwrap().translation,
+        position
+    );
+
+    app.world_mut()
+        .resource_mut::<NextState<Pause>>()
+        .set(Pause(false));
+    app.update();
+    app.update();
+    assert!(app.world().resource::<Time<GameClock>>().elapsed() > elapsed);
+}
+
+#[test]
+fn replayed_keys_go_through_the_input_layer() {
+    let mut app = gameplay_app();
+    let frame = app.world().resource::<bevy::diagnostic::FrameCount>().0;
+    let key = |frame, pressed| RecordedFrame {
+        frame,
+        inputs: vec![RecordedInput::Key {
+            key: "Escape".to_string(),
+            pressed,
+        }],
+    };
+    app.insert_resource(InputReplay {
+        session: InputSession {
+            seed: None,
+            frames: vec![key(frame + 1, true), key(frame + 3, false)],
+        },
+        next: 0,
+        exit_when_done: false,
+    });
+    for _ in 0..5 {
+        app.update();
+    }
+
+    assert_eq!(*app.world().resource::<State<Menu>>().get(), Menu::Pause);
+    assert_eq!(*app.world().resource::<State<Pause>>().get(), Pause(true));
+}
+
+#[test]
+fn closing_the_window_mid_run_asks_first() {
+    let mut app = gameplay_app();
+    let window = app.world_mut().spawn_empty().id();
+    app.world_mut()
+        .write_message(WindowCloseRequested { window });
+    app.update();
+    app.update();
+
+    assert_eq!(
+        *app.world().resource::<State<Menu>>().get(),
+        Menu::QuitPrompt
+    );
+    assert_eq!(*app.world().resource::<State<Pause>>().get(), Pause(true));
+}
+
+#[test]
+fn typing_feedback_mid_run_leaves_the_shortcuts_alone() {
+    let mut app = gameplay_app();
+    app.world_mut()
+        .resource_mut::<NextState<Menu>>()
+        .set(Menu::Feedback);
+    app.update();
+    app.update();
+
+    // P would close the menu, and M open the message panel
+    for (key_code, text) in [(KeyCode::KeyP, "p"), (KeyCode::KeyM, "m")] {
+        for state in [ButtonState::Pressed, ButtonState::Released] {
+            app.world_mut().write_message(KeyboardInput {
+                key_code,
+                logical_key: Key::Character(text.into()),
+                state,
+                text: Some(text.into()),
+                repeat: false,
+                window: Entity::PLACEHOLDER,
+            });
+            app.update();
+        }
+    }
+    assert_eq!(*app.world().resource::<State<Menu>>().get(), Menu::Feedback);
+
+    let mut texts = app.world_mut().query::<(&Name, &Text)>();
+    let typed: Vec<&str> = texts
+        .iter(app.world())
+        .filter(|(name, _)| name.as_str() == "Field Text")
+        .map(|(_, text)| text.0.as_str())
+        .collect();
+    assert!(typed.contains(&"pm|"), "{typed:?}");
+
+    // Enter moves on to the message
+    press_enter(&mut app);
+    let typed: Vec<&str> = texts
+        .iter(app.world())
+        .filter(|(name, _)| name.as_str() == "Field Text")
+        .map(|(_, text)| text.0.as_str())
+        .collect();
+    assert!(typed.contains(&"pm") && typed.contains(&"|"), "{typed:?}");
+
+    app.world_mut().write_message(KeyboardInput {
+        key_code: KeyCode::Escape,
+        logical_key: Key::Escape,
+        state: ButtonState::Pressed,
+        text: None,
+        repeat: false,
+        window: Entity::PLACEHOLDER,
+    });
+    app.update();
+    app.update();
+    assert_eq!(*app.world().resource::<State<Menu>>().get(), Menu::Settings);
+}
+
+#[test]
+fn replays_play_the_last_run_back_shot_for_shot() {
+    let mut app = gameplay_app();
+    let seed = app.world().resource::<RunSeed>().0;
+    assert!(seed.is_some());
+    for _ in 0..3 {
+        let color = app
+            .world_mut()
+            .query_filtered::<&LoadedBubble, With<Shooter>>()
+            .single(app.world())
+            .unwrap()
+            .0;
+        fire_straight_up(&mut app, color);
+    }
+    let board = grid_colors(&mut app);
+
+    app.world_mut()
+        .resource_mut::<NextState<Screen>>()
+        .set(Screen::Title);
+    app.update();
+    let log = app
+        .world()
+        .resource::<LastReplay>()
+        .log
+        .clone()
+        .expect("leaving the run should keep its replay");
+    assert_eq!(log.seed, seed);
+    assert_eq!(log.shots.len(), 3);
+
+    app.world_mut().write_message(WatchReplay);
+    app.update();
+    app.update();
+    assert_eq!(
+        *app.world().resource::<State<Screen>>().get(),
+        Screen::ReplayViewer
+    );
+    for _ in 0..MAX_FLIGHT_FRAMES * 3 {
+        app.update();
+        let done = app.world().resource::<ReplayPlayback>().is_done();
+        let mut projectiles = app.world_mut().query_filtered::<(), With<Projectile>>();
+        if done && projectiles.iter(app.world()).next().is_none() {
+            break;
+        }
+    }
+    app.update();
+    assert!(app.world().resource::<ReplayPlayback>().is_done());
+    assert_eq!(grid_colors(&mut app), board);
+}
+
+#[test]
+fn final_shots_play_back_slowed_from_before_the_oldest() {
+    let mut app = gameplay_app();
+    // Not the seed's board, so only the checkpoint can bring it back
+    set_grid(
+        &mut app,
+        &[
+            (0, 0, BubbleColor::Red),
+            (1, 0, BubbleColor::Blue),
+            (2, 0, BubbleColor::Green),
+            (3, 0, BubbleColor::Red),
+            (-1, 0, BubbleColor::Blue),
+            (-2, 0, BubbleColor::Green),
+        ],
+    );
+    let before = grid_colors(&mut app);
+    for _ in 0..2 {
+        let color = app
+            .world_mut()
+            .query_filtered::<&LoadedBubble, With<Shooter>>()
+            .single(app.world())
+            .unwrap()
+            .0;
+        fire_straight_up(&mut app, color);
+    }
+    let after = grid_colors(&mut app);
+    assert!(!app.world().resource::<FinalShots>().is_empty());
+
+    // What the game over menu's button does
+    app.world_mut().resource_mut::<FinalShots>().request();
+    app.world_mut().write_message(WatchReplay);
+    app.world_mut()
+        .resource_mut::<NextState<Screen>>()
+        .set(Screen::Title);
+    for _ in 0..3 {
+        app.update();
+    }
+    assert_eq!(
+        *app.world().resource::<State<Screen>>().get(),
+        Screen::ReplayViewer
+    );
+    assert!(app.world().resource::<ReplayPlayback>().is_rewound());
+    assert_eq!(grid_colors(&mut app), before);
+    assert_eq!(
+        app.world().resource::<Time<Virtual>>().relative_speed(),
+        0.5
+    );
+
+    for _ in 0..MAX_FLIGHT_FRAMES * 4 {
+        app.update();
+        let done = app.world().resource::<ReplayPlayback>().is_done();
+        let mut projectiles = app.world_mut().query_filtered::<(), With<Projectile>>();
+        if done && projectiles.iter(app.world()).next().is_none() {
+            break;
+        }
+    }
+    app.update();
+    assert_eq!(grid_colors(&mut app), after);
+}
+
+#[test]
+fn demo_bot_plays_seeded_board_until_input() {
+    let mut app = gameplay_app();
+    app.world_mut()
+        .resource_mut::<NextState<Screen>>()
+        .set(Screen::Title);
+    app.update();
+    app.world_mut()
+        .resource_mut::<NextState<Screen>>()
+        .set(Screen::Demo);
+    app.update();
+    app.update();
+
+    let board = grid_colors(&mut app);
+    assert!(!board.is_empty());
+    assert!(app.world().resource::<StartingBoard>().0.is_some());
+
+    for _ in 0..MAX_FLIGHT_FRAMES {
+        if app.world().resource::<GameLevel>().shots_this_round > 0 {
+            break;
+        }
+        app.update();
+    }
+    assert!(
+        app.world().resource::<GameLevel>().shots_this_round > 0,
+        "bot never fired"
+    );
+
+    app.world_mut().write_message(KeyboardInput {
+        key_code: KeyCode::Space,
+        logical_key: Key::Space,
+        state: ButtonState::Pressed,
+        text: None,
+        repeat: false,
+        window: Entity::PLACEHOLDER,
+    });
+    app.update();
+    app.update();
+    assert_eq!(
+        *app.world().resource::<State<Screen>>().get(),
+        Screen::Title
+    );
+    assert!(app.world().resource::<StartingBoard>().0.is_none());
+
+    // Same seed, same board
+    app.world_mut()
+        .resource_mut::<NextState<Screen>>()
+        .set(Screen::Demo);
+    app.update();
+    app.update();
+    assert_eq!(grid_colors(&mut app), board);
+}
+
+#[test]
+fn descents_age_the_bubbles_already_on_the_board() {
+    let mut app = gameplay_app();
+    set_grid(&mut app, &[(0, 0, BubbleColor::Blue)]);
+
+    for _ in 0..3 {
+        app.world_mut().write_message(TriggerDescent);
+        app.update();
+    }
+    app.update();
+
+    let grid = app.world().resource::<HexGrid>();
+    let oldest = grid.get(HexCoord::new(0, 0)).unwrap();
+    let newest = grid.get(HexCoord::new(0, -3)).unwrap();
+    assert_eq!(app.world().get::<BubbleAge>(oldest), Some(&BubbleAge(3)));
+    assert_eq!(app.world().get::<BubbleAge>(newest), Some(&BubbleAge(0)));
+    assert_eq!(
+        app.world().get::<Sprite>(oldest).unwrap().color,
+        age_tint(3)
+    );
+    assert_eq!(
+        app.world().get::<Sprite>(newest).unwrap().color,
+        Color::WHITE
+    );
+}
+
+/// Load the shooter with `color`, aim straight up, and read the mood.
+fn mood_for(app: &mut App, color: BubbleColor) -> SnordMood {
+    let mut shooter = app
+        .world_mut()
+        .query_filtered::<(&mut AimDirection, &mut LoadedBubble), With<Shooter>>();
+    let (mut aim, mut loaded) = shooter.single_mut(app.world_mut()).unwrap();
+    aim.0 = Vec2::Y;
+    loaded.0 = color;
+    app.update();
+    *app.world().resource::<SnordMood>()
+}
+
+#[test]
+fn loaded_snord_reacts_to_predicted_shot() {
+    let mut app = gameplay_app();
+    let row: Vec<_> = (-2..=2).map(|q| (q, 0, BubbleColor::Red)).collect();
+    set_grid(&mut app, &row);
+
+    assert_eq!(mood_for(&mut app, BubbleColor::Red), SnordMood::Excited);
+    assert_eq!(mood_for(&mut app, BubbleColor::Blue), SnordMood::Worried);
+
+    let mut near_danger = row.clone();
+    near_danger.push((-6, 13, BubbleColor::Green));
+    set_grid(&mut app, &near_danger);
+    assert_eq!(mood_for(&mut app, BubbleColor::Red), SnordMood::Terrified);
+}
+
+#[test]
+fn aimed_match_glows_in_a_single_mesh() {
+    let mut app = gameplay_app();
+    let row: Vec<_> = (-2..=2).map(|q| (q, 0, BubbleColor::Red)).collect();
+    set_grid(&mut app, &row);
+
+    mood_for(&mut app, BubbleColor::Red);
+    app.update();
+    let highlights = app.world().resource::<Highlights>();
+    assert_eq!(highlights.get(HighlightLayer::AimedMatch).len(), row.len());
+
+    // Every glowing cell is a quad in the same mesh
+    let mut glows = app.world_mut().query::<(&Name, &Mesh2d)>();
+    let meshes: Vec<_> = glows
+        .iter(app.world())
+        .filter(|(name, _)| name.as_str() == "Highlight Glow")
+        .map(|(_, mesh)| mesh.0.clone())
+        .collect();
+    let [mesh] = meshes.as_slice() else {
+        panic!("expected one glow mesh, found {}", meshes.len());
+    };
+    let mesh = app.world().resource::<Assets<Mesh>>().get(mesh).unwrap();
+    assert_eq!(mesh.count_vertices(), 4 * row.len());
+
+    mood_for(&mut app, BubbleColor::Blue);
+    app.update();
+    let highlights = app.world().resource::<Highlights>();
+    assert!(highlights.get(HighlightLayer::AimedMatch).is_empty());
+}
+
+#[test]
+fn color_clear_needs_the_last_bubble_of_the_color() {
+    let mut app = gameplay_app();
+    set_grid(
+        &mut app,
+        &[
+            (0, 0, BubbleColor::Red),
+            (1, 0, BubbleColor::Red),
+            (-5, 0, BubbleColor::Red),
+            (5, 0, BubbleColor::Blue),
+        ],
+    );
+    assert_eq!(
+        app.world().resource::<ColorCounts>().get(BubbleColor::Red),
+        3
+    );
+
+    fire_straight_up(&mut app, BubbleColor::Red);
+
+    // One red is still hanging on, so no bonus
+    let counts = app.world().resource::<ColorCounts>();
+    assert_eq!(counts.get(BubbleColor::Red), 1);
+    assert_eq!(counts.get(BubbleColor::Blue), 1);
+    assert_eq!(app.world().resource::<GameScore>().score, 30);
+}
+
+#[test]
+fn clearing_the_board_without_waste_is_a_perfect_clear() {
+    let mut app = gameplay_app();
+    set_grid(
+        &mut app,
+        &[(0, 0, BubbleColor::Red), (1, 0, BubbleColor::Red)],
+    );
+
+    fire_straight_up(&mut app, BubbleColor::Red);
+    // The win check may see the empty board a frame later
+    app.update();
+
+    // Endless keeps going after the clear, with its own bonus on top
+    assert_eq!(
+        app.world().resource::<GameScore>().score,
+        30 + COLOR_CLEAR_BONUS + PERFECT_CLEAR_BONUS + BOARD_CLEAR_BONUS
+    );
+}
+
+#[test]
+fn clearing_an_endless_board_refills_it_and_keeps_going() {
+    let mut app = gameplay_app();
+    set_grid(&mut app, &[(0, 0, BubbleColor::Blue)]);
+    app.world_mut().write_message(TriggerDescent);
+    app.update();
+    app.update();
+    assert!(app.world().resource::<GridOffset>().y < GRID_ORIGIN_Y);
+
+    set_grid(
+        &mut app,
+        &[(0, 0, BubbleColor::Red), (1, 0, BubbleColor::Red)],
+    );
+    fire_straight_up(&mut app, BubbleColor::Red);
+    app.update();
+
+    // Back up under the ceiling, a row deeper than a new run
+    assert_eq!(app.world().resource::<BoardClears>().0, 1);
+    assert_eq!(app.world().resource::<GridOffset>().y, GRID_ORIGIN_Y);
+    let grid = app.world().resource::<HexGrid>();
+    let columns = grid.bounds.columns_in_row(0);
+    assert_eq!(grid.len() as i32, BoardClears(1).rows() * columns);
+    assert!(grid.coords().all(|coord| (0..6).contains(&coord.r)));
+    assert!(app.world().resource::<GameScore>().score >= BOARD_CLEAR_BONUS);
+    assert_eq!(
+        *app.world().resource::<State<RunPhase>>().get(),
+        RunPhase::Playing
+    );
+    assert_eq!(*app.world().resource::<State<Menu>>().get(), Menu::None);
+}
+
+#[test]
+fn clearing_the_board_still_wins_outside_endless() {
+    let mut app = gameplay_app();
+    app.insert_resource(GameMode::TimeAttack);
+    set_grid(
+        &mut app,
+        &[(0, 0, BubbleColor::Red), (1, 0, BubbleColor::Red)],
+    );
+
+    fire_straight_up(&mut app, BubbleColor::Red);
+    app.update();
+    app.update();
+
+    assert!(grid_colors(&mut app).is_empty());
+    assert_eq!(app.world().resource::<BoardClears>().0, 0);
+    assert_eq!(
+        *app.world().resource::<State<RunPhase>>().get(),
+        RunPhase::GameEnding
+    );
+}
+
+#[test]
+fn wasted_shot_spoils_the_perfect_clear() {
+    let mut app = gameplay_app();
+    set_grid(&mut app, &[(-5, 0, BubbleColor::Blue)]);
+
+    // Sticks to the top without popping anything
+    fire_straight_up(&mut app, BubbleColor::Red);
+    assert_eq!(
+        app.world().resource::<GameLevel>().wasted_shots_this_round,
+        1
+    );
+
+    set_grid(
+        &mut app,
+        &[(0, 0, BubbleColor::Red), (1, 0, BubbleColor::Red)],
+    );
+    fire_straight_up(&mut app, BubbleColor::Red);
+    app.update();
+
+    assert_eq!(
+        app.world().resource::<GameScore>().score,
+        30 + COLOR_CLEAR_BONUS + BOARD_CLEAR_BONUS
+    );
+
+    // A descent starts a fresh round
+    app.world_mut().resource_mut::<GameLevel>().advance_level();
+    assert_eq!(
+        app.world().resource::<GameLevel>().wasted_shots_this_round,
+        0
+    );
+}
+
+#[test]
+fn game_over_shows_the_final_board() {
+    let mut app = gameplay_app();
+    // Row 15 is already past the danger line
+    set_grid(
+        &mut app,
+        &[
+            (0, 0, BubbleColor::Blue),
+            (1, 0, BubbleColor::Red),
+            (0, 15, BubbleColor::Green),
+        ],
+    );
+    for _ in 0..5 {
+        app.update();
+    }
+    assert_eq!(*app.world().resource::<State<Menu>>().get(), Menu::GameOver);
+
+    let board = app.world().resource::<FinalBoard>();
+    let cells: Vec<_> = board
+        .cells
+        .iter()
+        .map(|cell| (cell.coord, cell.color))
+        .collect();
+    assert_eq!(
+        cells,
+        vec![
+            (HexCoord::new(0, 0), BubbleColor::Blue),
+            (HexCoord::new(1, 0), BubbleColor::Red),
+            (HexCoord::new(0, 15), BubbleColor::Green),
+        ]
+    );
+
+    // The danger line plus one mini bubble per cell
+    let mut minis = app
+        .world_mut()
+        .query_filtered::<&Children, With<MiniBoard>>();
+    let children = minis.single(app.world()).unwrap();
+    assert_eq!(children.len(), 4);
+}
+
+#[test]
+fn finished_runs_are_recorded_and_retry_their_seed() {
+    let mut app = gameplay_app();
+    let seed = app
+        .world()
+        .resource::<RunSeed>()
+        .0
+        .expect("random boards are seeded");
+    let board = grid_colors(&mut app);
+
+    app.world_mut().write_message(GameEnded {
+        outcome: RunOutcome::GridReachedDanger,
+    });
+    app.update();
+
+    // Leave gameplay and come back on the same seed
+    app.world_mut().resource_mut::<RetrySeed>().0 = Some(seed);
+    restart_run(&mut app);
+
+    let run = &app.world().resource::<Profile>().recent_runs[0];
+    assert_eq!(run.seed, Some(seed));
+    assert_eq!(run.outcome, RunOutcome::GridReachedDanger);
+    assert_eq!(app.world().resource::<RunSeed>().0, Some(seed));
+    assert_eq!(grid_colors(&mut app), board);
+}
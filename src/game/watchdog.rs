@@ -0,0 +1,223 @@
+//! Entity-count watchdog.
+//!
+//! Effects like combo texts, confetti, and trajectory segments are spawned
+//! and despawned by different systems, and a missed despawn leaks quietly.
+//! The watchdog warns when:
+//! - Entity, grid, or effect counts pass their limits during a run
+//! - Effect entities are still alive after a run has ended
+//! - The entity count left after each run keeps climbing for several runs
+//!   without dropping back to where it started
+//!
+//! Limits are in [`Watchdog`]. It runs in dev builds by default.
+
+use std::time::Duration;
+
+use bevy::{ecs::system::SystemParam, prelude::*};
+
+use super::{
+    clock::on_game_timer,
+    grid::HexGrid,
+    kids::Confetti,
+    polish::{ComboText, PopAnimation},
+    shooter::TrajectorySegment,
+};
+use crate::screens::InGame;
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<Watchdog>();
+    app.init_resource::<RunCounts>();
+
+    app.add_systems(
+        Update,
+        check_limits.run_if(
+            in_state(InGame)
+                .and(watchdog_enabled)
+                .and(on_game_timer(Duration::from_secs_f32(CHECK_INTERVAL_SECS))),
+        ),
+    );
+    app.add_systems(OnExit(InGame), mark_run_ended.run_if(watchdog_enabled));
+    // Effects are despawned on the way out of the run, so count a frame later
+    app.add_systems(
+        Update,
+        check_after_run.run_if(not(in_state(InGame)).and(run_just_ended)),
+    );
+}
+
+/// Seconds of game time between limit checks.
+const CHECK_INTERVAL_SECS: f32 = 2.0;
+
+/// Limits the watchdog warns past.
+#[derive(Resource, Debug, Clone)]
+pub struct Watchdog {
+    pub enabled: bool,
+    /// Entities in the whole world.
+    pub max_entities: usize,
+    /// Bubbles on the grid.
+    pub max_grid: usize,
+    /// Confetti and popping bubbles.
+    pub max_particles: usize,
+    /// Floating combo texts.
+    pub max_combo_texts: usize,
+    /// Runs in a row the leftover entity count can grow before it counts as
+    /// a leak.
+    pub leak_runs: usize,
+}
+
+impl Default for Watchdog {
+    fn default() -> Self {
+        Self {
+            enabled: cfg!(feature = "dev"),
+            max_entities: 5000,
+            max_grid: 250,
+            max_particles: 600,
+            max_combo_texts: 20,
+            leak_runs: 5,
+        }
+    }
+}
+
+impl Watchdog {
+    /// The counts past their limits, as (name, count, limit).
+    pub fn over_limits(&self, counts: EntityCounts) -> Vec<(&'static str, usize, usize)> {
+        [
+            ("entities", counts.entities, self.max_entities),
+            ("grid bubbles", counts.grid, self.max_grid),
+            ("particles", counts.particles, self.max_particles),
+            ("combo texts", counts.combo_texts, self.max_combo_texts),
+        ]
+        .into_iter()
+        .filter(|(_, count, limit)| count > limit)
+        .collect()
+    }
+
+    /// Whether the counts left after each run grew every run for the last
+    /// [`leak_runs`](Self::leak_runs) runs.
+    pub fn is_leaking(&self, after_runs: &[usize]) -> bool {
+        self.leak_runs > 0
+            && after_runs.len() > self.leak_runs
+            && after_runs[after_runs.len() - self.leak_runs - 1..]
+                .windows(2)
+                .all(|pair| pair[1] > pair[0])
+    }
+}
+
+fn watchdog_enabled(watchdog: Res<Watchdog>) -> bool {
+    watchdog.enabled
+}
+
+/// What the watchdog counts.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct EntityCounts {
+    pub entities: usize,
+    pub grid: usize,
+    pub particles: usize,
+    pub combo_texts: usize,
+    pub trajectory_segments: usize,
+}
+
+impl EntityCounts {
+    /// Effect entities, which should all be gone once a run ends.
+    fn effects(self) -> usize {
+        self.particles + self.combo_texts + self.trajectory_segments
+    }
+}
+
+#[derive(SystemParam)]
+struct Counter<'w, 's> {
+    entities: Query<'w, 's, Entity>,
+    grid: Res<'w, HexGrid>,
+    pops: Query<'w, 's, (), With<PopAnimation>>,
+    confetti: Query<'w, 's, (), With<Confetti>>,
+    combo_texts: Query<'w, 's, (), With<ComboText>>,
+    trajectory_segments: Query<'w, 's, (), With<TrajectorySegment>>,
+}
+
+impl Counter<'_, '_> {
+    fn count(&self) -> EntityCounts {
+        EntityCounts {
+            entities: self.entities.iter().len(),
+            grid: self.grid.len(),
+            particles: self.pops.iter().len() + self.confetti.iter().len(),
+            combo_texts: self.combo_texts.iter().len(),
+            trajectory_segments: self.trajectory_segments.iter().len(),
+        }
+    }
+}
+
+/// Entity counts left after each run this session.
+#[derive(Resource, Debug, Default)]
+struct RunCounts {
+    run_ended: bool,
+    after_runs: Vec<usize>,
+}
+
+fn run_just_ended(runs: Res<RunCounts>) -> bool {
+    runs.run_ended
+}
+
+fn mark_run_ended(mut runs: ResMut<RunCounts>) {
+    runs.run_ended = true;
+}
+
+/// Warn about counts past their limits, once each time they go over.
+fn check_limits(counter: Counter, watchdog: Res<Watchdog>, mut warned: Local<Vec<&'static str>>) {
+    let over = watchdog.over_limits(counter.count());
+    for &(name, count, limit) in &over {
+        if !warned.contains(&name) {
+            warn!(
+                "Watchdog: {} {} is over the limit of {}",
+                count, name, limit
+            );
+        }
+    }
+    *warned = over.into_iter().map(|(name, _, _)| name).collect();
+}
+
+fn check_after_run(counter: Counter, watchdog: Res<Watchdog>, mut runs: ResMut<RunCounts>) {
+    runs.run_ended = false;
+    let counts = counter.count();
+    if counts.effects() > 0 {
+        warn!(
+            "Watchdog: effects outlived the run ({} particles, {} combo texts, {} trajectory segments)",
+            counts.particles, counts.combo_texts, counts.trajectory_segments
+        );
+    }
+
+    runs.after_runs.push(counts.entities);
+    if watchdog.is_leaking(&runs.after_runs) {
+        let first = runs.after_runs.len() - watchdog.leak_runs - 1;
+        warn!(
+            "Watchdog: entities left after each run grew for {} runs in a row: {:?}",
+            watchdog.leak_runs,
+            &runs.after_runs[first..]
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn leaks_need_growth_every_run() {
+        let watchdog = Watchdog {
+            leak_runs: 3,
+            ..default()
+        };
+        assert!(!watchdog.is_leaking(&[100, 110, 120]));
+        assert!(watchdog.is_leaking(&[100, 110, 120, 130]));
+        // Dropping back down, even briefly, isn't a leak
+        assert!(!watchdog.is_leaking(&[100, 110, 100, 120, 130]));
+        assert!(watchdog.is_leaking(&[100, 110, 100, 120, 130, 140]));
+
+        let counts = EntityCounts {
+            entities: 6000,
+            combo_texts: 3,
+            ..default()
+        };
+        assert_eq!(
+            watchdog.over_limits(counts),
+            vec![("entities", 6000, watchdog.max_entities)]
+        );
+    }
+}
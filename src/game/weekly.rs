@@ -0,0 +1,417 @@
+//! The weekly challenge.
+//!
+//! Every week (Monday to Monday, UTC) has a [`WeeklyChallenge`]: a seed
+//! everyone gets the same starting board and queue from, two run
+//! [`Modifier`]s, and a [`ScoringRule`]. The modifiers and rule come from a
+//! small rotation table, stepped through one entry a week; there's no
+//! backend to fetch them from yet, so the table ships with the game.
+//!
+//! Picking Weekly on the mode select screen makes [`ActiveChallenge`] this
+//! week's for the run. Challenge runs keep their own top 10 in
+//! [`WeeklyScores`] (`weekly.json`), which starts over when the week does,
+//! and stay off the main leaderboard. They aren't autosaved and don't take
+//! a handicap, so every entry on the board played the same run.
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    highscore::{HighScores, LeaderboardSettings, ScoreEntry},
+    history::{RetrySeed, pick_run_seed},
+    mode::SelectedMode,
+    powerups::{PowerUpCatalog, UnlockedPowerUps},
+    projectile::{DangerGrace, reset_danger_grace},
+    replay::ReplayPlayback,
+    rng::{GameRng, seed_game_rng},
+    state::{GameEnded, GameScore, reset_powerups},
+    telemetry::RunOutcome,
+};
+use crate::{
+    save::SaveFile,
+    screens::{InGame, Screen},
+    theme::toast::ShowToast,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<ActiveChallenge>();
+    app.init_resource::<WeeklyScores>();
+
+    app.add_systems(Startup, load_weekly_scores);
+    app.add_systems(OnEnter(InGame), start_challenge.before(pick_run_seed));
+    app.add_systems(
+        OnEnter(InGame),
+        apply_modifiers
+            .after(reset_danger_grace)
+            .after(reset_powerups)
+            .after(seed_game_rng)
+            .run_if(challenge_active),
+    );
+    app.add_systems(
+        Update,
+        record_weekly_score.run_if(
+            in_state(Screen::Gameplay)
+                .and(challenge_active)
+                .and(on_message::<GameEnded>),
+        ),
+    );
+}
+
+const SECS_PER_DAY: u64 = 86_400;
+
+/// Days from the Unix epoch (a Thursday) back to the Monday before it, so
+/// weeks start on Mondays.
+const EPOCH_WEEKDAY: u64 = 3;
+
+/// Mixed into the week number for its seed.
+const WEEK_SEED_SALT: u64 = 0x7EE4_1E5E_ED00_0000;
+
+/// Rows added to the starting board by [`Modifier::ExtraRows`].
+const EXTRA_ROWS: i32 = 2;
+
+/// Projectile speed multiplier for [`Modifier::FastShots`].
+const FAST_SHOT_SCALE: f32 = 1.3;
+
+/// Smallest cluster doubled by [`ScoringRule::BigClusters`].
+const BIG_CLUSTER: usize = 5;
+
+/// A twist on the usual rules for a challenge run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Modifier {
+    /// The starting board is two rows deeper.
+    ExtraRows,
+    /// Shots fly 30% faster.
+    FastShots,
+    /// No grace bounce off the danger line.
+    NoGrace,
+    /// A random tier 1 power-up from the start.
+    StartingPower,
+}
+
+impl Modifier {
+    pub fn label(self) -> &'static str {
+        match self {
+            Modifier::ExtraRows => "Extra Rows",
+            Modifier::FastShots => "Fast Shots",
+            Modifier::NoGrace => "No Grace",
+            Modifier::StartingPower => "Head Start",
+        }
+    }
+}
+
+/// How a challenge run's score differs from the usual.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ScoringRule {
+    /// Dropped bubbles score double.
+    DoubleDrops,
+    /// Clusters of five or more score double.
+    BigClusters,
+    /// Clearing a color is worth three times the bonus.
+    ColorClears,
+}
+
+impl ScoringRule {
+    pub fn description(self) -> &'static str {
+        match self {
+            ScoringRule::DoubleDrops => "drops score double",
+            ScoringRule::BigClusters => "clusters of 5+ score double",
+            ScoringRule::ColorClears => "color clears score triple",
+        }
+    }
+}
+
+/// The weeks' modifiers and scoring rules, in order.
+const ROTATION: [([Modifier; 2], ScoringRule); 6] = [
+    (
+        [Modifier::ExtraRows, Modifier::FastShots],
+        ScoringRule::DoubleDrops,
+    ),
+    (
+        [Modifier::NoGrace, Modifier::StartingPower],
+        ScoringRule::BigClusters,
+    ),
+    (
+        [Modifier::FastShots, Modifier::NoGrace],
+        ScoringRule::ColorClears,
+    ),
+    (
+        [Modifier::ExtraRows, Modifier::StartingPower],
+        ScoringRule::BigClusters,
+    ),
+    (
+        [Modifier::StartingPower, Modifier::FastShots],
+        ScoringRule::DoubleDrops,
+    ),
+    (
+        [Modifier::ExtraRows, Modifier::NoGrace],
+        ScoringRule::ColorClears,
+    ),
+];
+
+/// One week's challenge.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WeeklyChallenge {
+    /// Weeks since the one the Unix epoch fell in.
+    pub week: u64,
+    pub seed: u64,
+    pub modifiers: [Modifier; 2],
+    pub rule: ScoringRule,
+}
+
+impl WeeklyChallenge {
+    pub fn for_week(week: u64) -> Self {
+        let (modifiers, rule) = ROTATION[(week % ROTATION.len() as u64) as usize];
+        Self {
+            week,
+            seed: week.wrapping_mul(0x9E37_79B9_7F4A_7C15) ^ WEEK_SEED_SALT,
+            modifiers,
+            rule,
+        }
+    }
+
+    /// This week's challenge.
+    pub fn current() -> Self {
+        Self::for_week(week_of(now_secs()))
+    }
+
+    /// e.g. "Extra Rows + Fast Shots, drops score double".
+    pub fn summary(&self) -> String {
+        format!(
+            "{} + {}, {}",
+            self.modifiers[0].label(),
+            self.modifiers[1].label(),
+            self.rule.description()
+        )
+    }
+}
+
+/// Seconds since the Unix epoch, or 0 if the clock is before it.
+fn now_secs() -> u64 {
+    web_time::SystemTime::now()
+        .duration_since(web_time::UNIX_EPOCH)
+        .map_or(0, |now| now.as_secs())
+}
+
+/// The week `secs` falls in.
+fn week_of(secs: u64) -> u64 {
+    (secs / SECS_PER_DAY + EPOCH_WEEKDAY) / 7
+}
+
+/// Seconds from `secs` until the next week starts.
+fn secs_until_reset(secs: u64) -> u64 {
+    let next_week = (week_of(secs) + 1) * 7 - EPOCH_WEEKDAY;
+    next_week * SECS_PER_DAY - secs
+}
+
+/// Time left in this week's challenge, e.g. "2d 5h" or "3h 20m".
+pub fn reset_countdown() -> String {
+    let left = secs_until_reset(now_secs());
+    let (days, hours, minutes) = (
+        left / SECS_PER_DAY,
+        left % SECS_PER_DAY / 3600,
+        left % 3600 / 60,
+    );
+    if days > 0 {
+        format!("{days}d {hours}h")
+    } else {
+        format!("{hours}h {minutes}m")
+    }
+}
+
+/// The challenge the run in play is part of, if any.
+#[derive(Resource, Debug, Default)]
+pub struct ActiveChallenge(pub Option<WeeklyChallenge>);
+
+impl ActiveChallenge {
+    pub fn has(&self, modifier: Modifier) -> bool {
+        self.0
+            .is_some_and(|challenge| challenge.modifiers.contains(&modifier))
+    }
+
+    fn rule(&self) -> Option<ScoringRule> {
+        self.0.map(|challenge| challenge.rule)
+    }
+
+    /// Rows added to the usual starting board.
+    pub fn extra_rows(&self) -> i32 {
+        if self.has(Modifier::ExtraRows) {
+            EXTRA_ROWS
+        } else {
+            0
+        }
+    }
+
+    /// Multiplier on projectile speed.
+    pub fn shot_speed_scale(&self) -> f32 {
+        if self.has(Modifier::FastShots) {
+            FAST_SHOT_SCALE
+        } else {
+            1.0
+        }
+    }
+
+    /// Points for popping a cluster of `count` that would usually score
+    /// `points`.
+    pub fn cluster_points(&self, count: usize, points: u32) -> u32 {
+        match self.rule() {
+            Some(ScoringRule::BigClusters) if count >= BIG_CLUSTER => points * 2,
+            _ => points,
+        }
+    }
+
+    /// Points for dropping bubbles that would usually score `points`.
+    pub fn drop_points(&self, points: u32) -> u32 {
+        match self.rule() {
+            Some(ScoringRule::DoubleDrops) => points * 2,
+            _ => points,
+        }
+    }
+
+    /// The bonus for clearing a color, from the usual `bonus`.
+    pub fn color_clear_bonus(&self, bonus: u32) -> u32 {
+        match self.rule() {
+            Some(ScoringRule::ColorClears) => bonus * 3,
+            _ => bonus,
+        }
+    }
+}
+
+/// Run condition: the run in play is a weekly challenge.
+pub fn challenge_active(active: Res<ActiveChallenge>) -> bool {
+    active.0.is_some()
+}
+
+/// This week's challenge leaderboard, mirrored in `weekly.json`.
+#[derive(Resource, Debug, Default, Serialize, Deserialize)]
+pub struct WeeklyScores {
+    /// The week the scores are from.
+    pub week: u64,
+    pub scores: HighScores,
+}
+
+impl SaveFile for WeeklyScores {
+    const FILE_NAME: &'static str = "weekly.json";
+    const DESCRIPTION: &'static str = "weekly challenge scores";
+}
+
+impl WeeklyScores {
+    /// Drop last week's scores once a new week has started.
+    pub fn roll_over(&mut self, week: u64) {
+        if self.week != week {
+            self.week = week;
+            self.scores.entries.clear();
+        }
+    }
+}
+
+fn load_weekly_scores(mut scores: ResMut<WeeklyScores>) {
+    *scores = WeeklyScores::load();
+    scores.roll_over(week_of(now_secs()));
+}
+
+/// Make this week's challenge the run's when Weekly is picked, or the
+/// recorded one when watching a replay of it.
+pub(super) fn start_challenge(
+    screen: Res<State<Screen>>,
+    selected: Res<SelectedMode>,
+    playback: Res<ReplayPlayback>,
+    mut active: ResMut<ActiveChallenge>,
+    mut retry: ResMut<RetrySeed>,
+) {
+    active.0 = match screen.get() {
+        Screen::Gameplay if *selected == SelectedMode::Weekly => Some(WeeklyChallenge::current()),
+        Screen::ReplayViewer => playback.log.challenge,
+        _ => None,
+    };
+    let Some(challenge) = active.0 else {
+        return;
+    };
+    info!(
+        "Weekly challenge {}: {}",
+        challenge.week,
+        challenge.summary()
+    );
+    retry.0 = Some(challenge.seed);
+}
+
+/// Apply the modifiers that change how the run starts.
+fn apply_modifiers(
+    active: Res<ActiveChallenge>,
+    catalog: Res<PowerUpCatalog>,
+    mut grace: ResMut<DangerGrace>,
+    mut powerups: ResMut<UnlockedPowerUps>,
+    mut rng: ResMut<GameRng>,
+) {
+    if active.has(Modifier::NoGrace) {
+        grace.remaining = 0;
+    }
+    if active.has(Modifier::StartingPower)
+        && let Some(power) = catalog.random_from_tier(1, &mut rng.gameplay)
+    {
+        powerups.add(power);
+    }
+}
+
+fn record_weekly_score(
+    active: Res<ActiveChallenge>,
+    score: Res<GameScore>,
+    settings: Res<LeaderboardSettings>,
+    mut ended_events: MessageReader<GameEnded>,
+    mut weekly: ResMut<WeeklyScores>,
+    mut toasts: MessageWriter<ShowToast>,
+) {
+    let Some(challenge) = active.0 else {
+        return;
+    };
+    for event in ended_events.read() {
+        let abandoned = event.outcome == RunOutcome::Abandoned;
+        if abandoned && !settings.include_abandoned {
+            continue;
+        }
+        weekly.roll_over(challenge.week);
+        let entry = ScoreEntry {
+            abandoned,
+            ..ScoreEntry::new(score.score, score.bubbles_popped)
+        };
+        if weekly.scores.add_score(entry) {
+            info!("New weekly challenge score: {}", score.score);
+            weekly.save();
+            toasts.write(ShowToast::success("New weekly challenge score!"));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn weeks_start_on_monday_and_rotate() {
+        // 2026-10-19 00:00 UTC was a Monday
+        let monday = 20_745 * SECS_PER_DAY;
+        assert_eq!(week_of(monday), week_of(monday - 1) + 1);
+        assert_eq!(week_of(monday), week_of(monday + 7 * SECS_PER_DAY - 1));
+        assert_eq!(secs_until_reset(monday - 60), 60);
+        assert_eq!(secs_until_reset(monday), 7 * SECS_PER_DAY);
+
+        let this_week = WeeklyChallenge::for_week(week_of(monday));
+        let next_week = WeeklyChallenge::for_week(week_of(monday) + 1);
+        assert_ne!(this_week.seed, next_week.seed);
+        assert_ne!(this_week.modifiers, next_week.modifiers);
+        assert_eq!(
+            WeeklyChallenge::for_week(this_week.week + ROTATION.len() as u64).modifiers,
+            this_week.modifiers
+        );
+    }
+
+    #[test]
+    fn scoring_rules_only_touch_their_own_points() {
+        let active = ActiveChallenge(Some(WeeklyChallenge {
+            rule: ScoringRule::BigClusters,
+            ..WeeklyChallenge::for_week(0)
+        }));
+        assert_eq!(active.cluster_points(4, 40), 40);
+        assert_eq!(active.cluster_points(5, 50), 100);
+        assert_eq!(active.drop_points(20), 20);
+        assert_eq!(ActiveChallenge(None).cluster_points(5, 50), 50);
+    }
+}
@@ -0,0 +1,189 @@
+//! Wildfire - two colors burning as one, now and then late in a run.
+//!
+//! From level [`MIN_LEVEL`] on, each descent has a small chance of merging
+//! two colors next to each other in the palette: for the next
+//! [`WILDFIRE_SHOTS`] shots, one matches as the other. A banner announces the
+//! merge and another one the split.
+//!
+//! [`Wildfire::resolve`] is the aliasing layer. Cluster detection, landing
+//! forecasts and the aim mood look colors up through it, and the shooter
+//! queue shows its snords as the color they'll match as. Bubbles on the board
+//! keep their own colors.
+
+use bevy::prelude::*;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    bubble::BubbleColor,
+    campaign::campaign_active,
+    cluster::ClusterSystems,
+    mode::{GameMode, LevelColors, pressure_mode},
+    polish::{ComboText, combo_text},
+    projectile::BubbleLanded,
+    rng::GameRng,
+    state::{GameLevel, TriggerDescent, handle_descent},
+};
+use crate::{PausableSystems, screens::InGame, theme::GameFont};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<Wildfire>();
+    app.init_resource::<Wildfire>();
+
+    app.add_systems(OnEnter(InGame), put_out_wildfire);
+    app.add_systems(
+        Update,
+        (
+            // Draws from the gameplay rng, so it goes after the new row
+            ignite_wildfire
+                .after(handle_descent)
+                .run_if(pressure_mode.and(not(campaign_active))),
+            burn_down.after(ClusterSystems),
+        )
+            .in_set(PausableSystems)
+            .run_if(in_state(InGame)),
+    );
+}
+
+/// First level a wildfire can start on.
+pub const MIN_LEVEL: u32 = 30;
+
+/// Chance of a wildfire starting at each descent.
+const WILDFIRE_CHANCE: f64 = 0.15;
+
+/// Shots a wildfire lasts.
+pub const WILDFIRE_SHOTS: u32 = 10;
+
+/// Two colors matching as one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Reflect, Serialize, Deserialize)]
+#[reflect(Serialize, Deserialize)]
+pub struct ColorMerge {
+    /// The color that matches as `into`.
+    pub from: BubbleColor,
+    pub into: BubbleColor,
+    /// Shots until the colors split again.
+    pub shots_left: u32,
+}
+
+/// The merge in play, if any.
+#[derive(Resource, Debug, Clone, Copy, Default, PartialEq, Reflect, Serialize, Deserialize)]
+#[reflect(Resource, Serialize, Deserialize)]
+pub struct Wildfire(pub Option<ColorMerge>);
+
+impl Wildfire {
+    /// The color `color` matches as.
+    pub fn resolve(&self, color: BubbleColor) -> BubbleColor {
+        match self.0 {
+            Some(merge) if merge.from == color => merge.into,
+            _ => color,
+        }
+    }
+}
+
+fn put_out_wildfire(mut wildfire: ResMut<Wildfire>) {
+    *wildfire = Wildfire::default();
+}
+
+/// Now and then, merge two neighboring colors as the board descends.
+fn ignite_wildfire(
+    mut commands: Commands,
+    mut descent_events: MessageReader<TriggerDescent>,
+    mut wildfire: ResMut<Wildfire>,
+    mut rng: ResMut<GameRng>,
+    level: Res<GameLevel>,
+    mode: Res<GameMode>,
+    level_colors: Res<LevelColors>,
+    game_font: Res<GameFont>,
+) {
+    if descent_events.read().count() == 0 || wildfire.0.is_some() || level.level < MIN_LEVEL {
+        return;
+    }
+    // Merging one of two colors would leave nothing to tell apart
+    let palette = level_colors.palette(*mode);
+    if palette.len() < 3 || !rng.gameplay.random_bool(WILDFIRE_CHANCE) {
+        return;
+    }
+
+    let i = rng.gameplay.random_range(0..palette.len() - 1);
+    let merge = ColorMerge {
+        from: palette[i + 1],
+        into: palette[i],
+        shots_left: WILDFIRE_SHOTS,
+    };
+    info!(
+        "Wildfire! {:?} matches as {:?} for {} shots",
+        merge.from, merge.into, WILDFIRE_SHOTS
+    );
+    wildfire.0 = Some(merge);
+
+    commands.spawn(combo_text(
+        "Wildfire Text",
+        "WILDFIRE!",
+        ComboText::new(Vec2::new(0.0, 60.0), 2.2, 40.0, merge.into.to_color()),
+        game_font.0.clone(),
+        40.0,
+    ));
+    commands.spawn(combo_text(
+        "Wildfire Detail Text",
+        format!(
+            "{:?} burns as {:?} for {} shots",
+            merge.from, merge.into, WILDFIRE_SHOTS
+        ),
+        ComboText::new(Vec2::new(0.0, 15.0), 2.2, 40.0, merge.from.to_color()),
+        game_font.0.clone(),
+        24.0,
+    ));
+}
+
+/// Count down the shots, once per frame with a landing, and split the colors
+/// after the last.
+fn burn_down(
+    mut commands: Commands,
+    mut landed_events: MessageReader<BubbleLanded>,
+    mut wildfire: ResMut<Wildfire>,
+    game_font: Res<GameFont>,
+) {
+    if landed_events.read().count() == 0 {
+        return;
+    }
+    let Some(mut merge) = wildfire.0 else {
+        return;
+    };
+
+    merge.shots_left = merge.shots_left.saturating_sub(1);
+    if merge.shots_left > 0 {
+        wildfire.0 = Some(merge);
+        return;
+    }
+
+    info!("Wildfire out: {:?} and {:?} split", merge.from, merge.into);
+    wildfire.0 = None;
+    commands.spawn(combo_text(
+        "Wildfire Out Text",
+        "COLORS SPLIT",
+        ComboText::new(Vec2::new(0.0, 60.0), 1.6, 40.0, merge.from.to_color()),
+        game_font.0.clone(),
+        32.0,
+    ));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_the_merged_color_is_aliased() {
+        let wildfire = Wildfire(Some(ColorMerge {
+            from: BubbleColor::Blue,
+            into: BubbleColor::Red,
+            shots_left: WILDFIRE_SHOTS,
+        }));
+        assert_eq!(wildfire.resolve(BubbleColor::Blue), BubbleColor::Red);
+        assert_eq!(wildfire.resolve(BubbleColor::Red), BubbleColor::Red);
+        assert_eq!(wildfire.resolve(BubbleColor::Green), BubbleColor::Green);
+        assert_eq!(
+            Wildfire::default().resolve(BubbleColor::Blue),
+            BubbleColor::Blue
+        );
+    }
+}
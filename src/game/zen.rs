@@ -0,0 +1,126 @@
+//! Zen mode - relaxed play with nothing to lose.
+//!
+//! The grid never descends and there's no danger line or score (those systems
+//! are gated on [`pressure_mode`](super::mode::pressure_mode)). Instead:
+//! - The top rows refill when the board gets sparse, so play never ends.
+//! - A shot that would land in the danger zone clears the bottom rows.
+//! - The music plays softer and slower.
+
+use bevy::{audio::Volume, prelude::*};
+
+use super::{
+    bubble::{BubbleColor, GameAssets, spawn_bubble},
+    bubble_material::BubbleMaterial,
+    grid::HexGrid,
+    hex::{GridOffset, HexCoord},
+    mode::GameMode,
+    polish::PopAnimation,
+    projectile::{BubbleInDangerZone, Projectile},
+    rng::GameRng,
+};
+use crate::{PausableSystems, audio::Music, screens::InGame};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(OnEnter(InGame), play_zen_music.run_if(zen_mode));
+
+    app.add_systems(
+        Update,
+        (refill_sparse_board, clear_danger_rows)
+            .in_set(PausableSystems)
+            .run_if(in_state(InGame).and(zen_mode)),
+    );
+}
+
+/// Refill once fewer than this many bubbles are left.
+const REFILL_BELOW: usize = 20;
+
+/// Number of rows from the top filled in on refill.
+const REFILL_ROWS: i32 = 4;
+
+/// Bottom rows cleared when a shot reaches the danger zone.
+const DANGER_CLEAR_ROWS: i32 = 3;
+
+fn zen_mode(mode: Res<GameMode>) -> bool {
+    *mode == GameMode::Zen
+}
+
+fn play_zen_music(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.spawn((
+        Name::new("Zen Music"),
+        AudioPlayer::new(asset_server.load("audio/music/Monkeys Spinning Monkeys.ogg")),
+        PlaybackSettings::LOOP
+            .with_speed(0.85)
+            .with_volume(Volume::Linear(0.4)),
+        Music,
+        DespawnOnExit(InGame),
+    ));
+}
+
+/// Fill the empty cells of the top rows once the board gets sparse.
+fn refill_sparse_board(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<BubbleMaterial>>,
+    mut grid: ResMut<HexGrid>,
+    grid_offset: Res<GridOffset>,
+    game_assets: Res<GameAssets>,
+    projectiles: Query<(), With<Projectile>>,
+    mode: Res<GameMode>,
+    mut rng: ResMut<GameRng>,
+) {
+    // Wait for shots to land so a refill never spawns on top of one
+    if grid.len() >= REFILL_BELOW || !projectiles.is_empty() {
+        return;
+    }
+
+    let bounds = grid.bounds;
+    let mut count = 0;
+    for r in 0..REFILL_ROWS {
+        for q in bounds.min_q..=bounds.max_q {
+            let coord = HexCoord::new(q, r);
+            if grid.is_occupied(coord) {
+                continue;
+            }
+            let entity = spawn_bubble(
+                &mut commands,
+                &mut meshes,
+                &mut materials,
+                coord,
+                BubbleColor::random_with(mode.palette(), &mut rng.gameplay),
+                grid.hex_size,
+                grid_offset.y,
+                Some(&game_assets),
+            );
+            grid.insert(coord, entity);
+            count += 1;
+        }
+    }
+    info!("Zen refill: added {} bubbles", count);
+}
+
+/// Clear the bottom rows instead of ending the run.
+fn clear_danger_rows(
+    mut commands: Commands,
+    mut danger_events: MessageReader<BubbleInDangerZone>,
+    mut grid: ResMut<HexGrid>,
+    transform_query: Query<&Transform>,
+) {
+    if danger_events.read().next().is_none() {
+        return;
+    }
+
+    let trimmed = grid.trim_bottom_rows(DANGER_CLEAR_ROWS);
+    for entity in &trimmed {
+        let current_scale = transform_query
+            .get(*entity)
+            .map(|t| t.scale)
+            .unwrap_or(Vec3::ONE);
+        commands
+            .entity(*entity)
+            .insert(PopAnimation::new(current_scale));
+    }
+    info!(
+        "Zen: cleared {} bubbles near the danger zone",
+        trimmed.len()
+    );
+}
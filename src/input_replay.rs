@@ -0,0 +1,338 @@
+//! Recording and replaying raw input sessions, for reproducing bugs.
+//!
+//! ```text
+//! snord --record session.json
+//! snord --replay session.json
+//! snord --replay session.json --headless
+//! ```
+//!
+//! `--record` keeps every key, mouse button, and cursor move along with the
+//! frame it arrived on, and writes them out when the game exits. `--replay`
+//! feeds them back in through the input layer on the same frames, so menus,
+//! picking, and gameplay see exactly what the player did. The recording also
+//! keeps the first run's seed, so the starting board comes back too.
+//!
+//! With `--headless` the replay runs without a window or GPU at a fixed 60 fps
+//! and exits once the session is over. A panic along the way fails the run,
+//! which is what QA scripts check for.
+
+use std::{fs, path::PathBuf, time::Duration};
+
+use bevy::{
+    input::{
+        ButtonState, InputSystems,
+        keyboard::{Key, KeyboardInput, NativeKey},
+        mouse::MouseButtonInput,
+    },
+    prelude::*,
+    reflect::{DynamicEnum, DynamicVariant, Enum, VariantType},
+    window::{PrimaryWindow, WindowEvent},
+};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::{game::history::RetrySeed, launch::LaunchOptions};
+
+pub(super) fn plugin(app: &mut App) {
+    let options = app
+        .world()
+        .get_resource::<LaunchOptions>()
+        .cloned()
+        .unwrap_or_default();
+
+    if let Some(path) = options.replay {
+        match InputSession::read(&path) {
+            Ok(session) => {
+                info!(
+                    "Replaying {} ({} frames of input)",
+                    path.display(),
+                    session.frames.len()
+                );
+                if let Some(seed) = session.seed {
+                    app.insert_resource(RetrySeed(Some(seed)));
+                }
+                app.insert_resource(InputReplay {
+                    session,
+                    next: 0,
+                    exit_when_done: options.headless,
+                });
+            }
+            Err(error) => {
+                error!("Couldn't read replay {}: {}", path.display(), error);
+                if options.headless {
+                    app.add_systems(Startup, |mut app_exit: MessageWriter<AppExit>| {
+                        app_exit.write(AppExit::error());
+                    });
+                }
+            }
+        }
+    } else if let Some(path) = options.record {
+        let seed = options.seed.unwrap_or_else(|| rand::rng().random());
+        app.insert_resource(RetrySeed(Some(seed)));
+        app.insert_resource(InputRecorder {
+            path,
+            session: InputSession {
+                seed: Some(seed),
+                frames: Vec::new(),
+            },
+        });
+    }
+
+    app.add_systems(
+        PreUpdate,
+        (
+            replay_input
+                .before(InputSystems)
+                .run_if(resource_exists::<InputReplay>),
+            record_input.run_if(resource_exists::<InputRecorder>),
+        ),
+    );
+    app.add_systems(
+        Last,
+        save_recording.run_if(resource_exists::<InputRecorder>.and(on_message::<AppExit>)),
+    );
+}
+
+/// Frames to keep running after the last recorded input, so whatever it
+/// started can play out before a headless replay exits.
+const SETTLE_FRAMES: u32 = 120;
+
+/// Frame rate headless replays are stepped at.
+pub const HEADLESS_FRAME_TIME: Duration = Duration::from_nanos(1_000_000_000 / 60);
+
+/// A recorded input session, as saved to disk.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct InputSession {
+    /// Seed for the first run's starting board.
+    pub seed: Option<u64>,
+    /// Frames that had input, in order.
+    pub frames: Vec<RecordedFrame>,
+}
+
+/// All input that arrived on one frame.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedFrame {
+    /// Frames since the app started.
+    pub frame: u32,
+    pub inputs: Vec<RecordedInput>,
+}
+
+/// One raw input message. Keys and buttons are stored by name.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum RecordedInput {
+    Key { key: String, pressed: bool },
+    MouseButton { button: String, pressed: bool },
+    CursorMoved { position: Vec2 },
+}
+
+impl InputSession {
+    pub fn read(path: &PathBuf) -> Result<Self, String> {
+        let json = fs::read_to_string(path).map_err(|error| error.to_string())?;
+        serde_json::from_str(&json).map_err(|error| error.to_string())
+    }
+
+    pub fn write(&self, path: &PathBuf) -> Result<(), String> {
+        let json = serde_json::to_string(self).map_err(|error| error.to_string())?;
+        fs::write(path, json).map_err(|error| error.to_string())
+    }
+
+    /// The frame the session is over on.
+    fn last_frame(&self) -> u32 {
+        self.frames.last().map_or(0, |frame| frame.frame) + SETTLE_FRAMES
+    }
+}
+
+/// The session being played back.
+#[derive(Resource, Debug)]
+pub struct InputReplay {
+    pub session: InputSession,
+    /// Index of the next frame in `session.frames` to play.
+    pub next: usize,
+    /// Exit once the session is over (headless runs).
+    pub exit_when_done: bool,
+}
+
+/// The session being recorded, and where it's saved.
+#[derive(Resource, Debug)]
+struct InputRecorder {
+    path: PathBuf,
+    session: InputSession,
+}
+
+/// Name of a unit enum variant, e.g. `"KeyP"` for `KeyCode::KeyP`. Other
+/// variants (like keys the platform couldn't identify) aren't recorded.
+pub(crate) fn variant_name(value: &dyn Enum) -> Option<String> {
+    (value.variant_type() == VariantType::Unit).then(|| value.variant_name().to_string())
+}
+
+/// The unit variant of `T` named `name`.
+pub(crate) fn from_variant_name<T: FromReflect>(name: &str) -> Option<T> {
+    T::from_reflect(&DynamicEnum::new(name, DynamicVariant::Unit))
+}
+
+fn button_state(pressed: bool) -> ButtonState {
+    if pressed {
+        ButtonState::Pressed
+    } else {
+        ButtonState::Released
+    }
+}
+
+fn record_input(
+    frame: Res<bevy::diagnostic::FrameCount>,
+    mut keys: MessageReader<KeyboardInput>,
+    mut buttons: MessageReader<MouseButtonInput>,
+    mut cursor_moves: MessageReader<CursorMoved>,
+    mut recorder: ResMut<InputRecorder>,
+) {
+    // Cursor moves first, so a click lands where the cursor ended up
+    let mut inputs = Vec::new();
+    for event in cursor_moves.read() {
+        inputs.push(RecordedInput::CursorMoved {
+            position: event.position,
+        });
+    }
+    for event in keys.read() {
+        // Held keys repeat; the press is enough to replay them
+        if event.repeat {
+            continue;
+        }
+        if let Some(key) = variant_name(&event.key_code) {
+            inputs.push(RecordedInput::Key {
+                key,
+                pressed: event.state.is_pressed(),
+            });
+        }
+    }
+    for event in buttons.read() {
+        if let Some(button) = variant_name(&event.button) {
+            inputs.push(RecordedInput::MouseButton {
+                button,
+                pressed: event.state.is_pressed(),
+            });
+        }
+    }
+
+    if !inputs.is_empty() {
+        recorder.session.frames.push(RecordedFrame {
+            frame: frame.0,
+            inputs,
+        });
+    }
+}
+
+fn save_recording(recorder: Res<InputRecorder>) {
+    match recorder.session.write(&recorder.path) {
+        Ok(()) => info!(
+            "Saved input recording to {} ({} frames of input)",
+            recorder.path.display(),
+            recorder.session.frames.len()
+        ),
+        Err(error) => warn!(
+            "Couldn't save input recording to {}: {}",
+            recorder.path.display(),
+            error
+        ),
+    }
+}
+
+/// Write this frame's recorded input as if it came from the window.
+fn replay_input(
+    frame: Res<bevy::diagnostic::FrameCount>,
+    mut replay: ResMut<InputReplay>,
+    mut windows: Query<(Entity, &mut Window), With<PrimaryWindow>>,
+    mut keys: MessageWriter<KeyboardInput>,
+    mut buttons: MessageWriter<MouseButtonInput>,
+    mut cursor_moves: MessageWriter<CursorMoved>,
+    mut window_events: MessageWriter<WindowEvent>,
+    mut app_exit: MessageWriter<AppExit>,
+) {
+    if frame.0 > replay.session.last_frame() {
+        return;
+    }
+    if frame.0 == replay.session.last_frame() {
+        info!("Replay finished after {} frames", frame.0);
+        if replay.exit_when_done {
+            app_exit.write(AppExit::Success);
+        }
+        return;
+    }
+
+    let Some(recorded) = replay.session.frames.get(replay.next) else {
+        return;
+    };
+    if recorded.frame > frame.0 {
+        return;
+    }
+    let inputs = recorded.inputs.clone();
+    replay.next += 1;
+
+    let mut window = windows.single_mut().ok();
+    let window_entity = window
+        .as_ref()
+        .map_or(Entity::PLACEHOLDER, |(entity, _)| *entity);
+    for input in inputs {
+        match input {
+            RecordedInput::Key { key, pressed } => {
+                let Some(key_code) = from_variant_name::<KeyCode>(&key) else {
+                    warn!("Replay skipped unknown key {}", key);
+                    continue;
+                };
+                let event = KeyboardInput {
+                    key_code,
+                    logical_key: Key::Unidentified(NativeKey::Unidentified),
+                    state: button_state(pressed),
+                    text: None,
+                    repeat: false,
+                    window: window_entity,
+                };
+                window_events.write(WindowEvent::KeyboardInput(event.clone()));
+                keys.write(event);
+            }
+            RecordedInput::MouseButton { button, pressed } => {
+                let Some(button) = from_variant_name::<MouseButton>(&button) else {
+                    warn!("Replay skipped unknown mouse button {}", button);
+                    continue;
+                };
+                let event = MouseButtonInput {
+                    button,
+                    state: button_state(pressed),
+                    window: window_entity,
+                };
+                window_events.write(WindowEvent::MouseButtonInput(event));
+                buttons.write(event);
+            }
+            RecordedInput::CursorMoved { position } => {
+                if let Some((_, window)) = window.as_mut() {
+                    window.set_cursor_position(Some(position));
+                }
+                let event = CursorMoved {
+                    window: window_entity,
+                    position,
+                    delta: None,
+                };
+                window_events.write(WindowEvent::CursorMoved(event.clone()));
+                cursor_moves.write(event);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keys_and_buttons_round_trip_by_name() {
+        let key = variant_name(&KeyCode::KeyP).unwrap();
+        assert_eq!(key, "KeyP");
+        assert_eq!(from_variant_name::<KeyCode>(&key), Some(KeyCode::KeyP));
+
+        let button = variant_name(&MouseButton::Left).unwrap();
+        assert_eq!(
+            from_variant_name::<MouseButton>(&button),
+            Some(MouseButton::Left)
+        );
+        assert_eq!(variant_name(&MouseButton::Other(7)), None);
+    }
+}
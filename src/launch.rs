@@ -0,0 +1,127 @@
+//! Command-line flags for native builds.
+//!
+//! ```text
+//! snord --skip-menu --level 12 --mute
+//! ```
+//!
+//! `--record`, `--replay`, and `--headless` are handled by
+//! [`input_replay`](crate::input_replay).
+//!
+//! Flags are parsed in `main` and applied while the app is built, so they're
+//! in place before the first screen is entered. Web builds always use the
+//! defaults.
+
+use std::path::PathBuf;
+
+use bevy::{audio::Volume, prelude::*};
+
+use crate::{
+    game::{history::RetrySeed, mode::GameMode},
+    screens::Screen,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    let Some(options) = app.world().get_resource::<LaunchOptions>().cloned() else {
+        app.init_resource::<LaunchOptions>();
+        return;
+    };
+
+    if options.mute {
+        app.insert_resource(GlobalVolume::new(Volume::SILENT));
+    }
+    if options.skip_menu {
+        // Replaces the initial `Screen::Title` before it's ever entered
+        app.insert_state(Screen::Loading);
+    }
+
+    if let Some(name) = &options.mode {
+        match GameMode::from_name(name) {
+            Some(mode) => {
+                app.insert_resource(mode);
+            }
+            None => warn!("--mode {} ignored: unknown mode", name),
+        }
+    }
+
+    if let Some(seed) = options.seed {
+        // Only the first run; later runs get fresh seeds
+        app.insert_resource(RetrySeed(Some(seed)));
+    }
+}
+
+/// Options given on the command line.
+#[derive(Resource, Debug, Clone, Default, PartialEq)]
+pub struct LaunchOptions {
+    /// Seed for the first run's starting board.
+    pub seed: Option<u64>,
+    /// Level every run starts at, instead of 1.
+    pub level: Option<u32>,
+    /// Game mode to play.
+    pub mode: Option<String>,
+    /// Start with the volume at zero.
+    pub mute: bool,
+    /// Start in a window rather than fullscreen.
+    pub windowed: bool,
+    /// Go straight to gameplay, skipping the title screen.
+    pub skip_menu: bool,
+    /// File to record this session's input to.
+    pub record: Option<PathBuf>,
+    /// Input recording to play back.
+    pub replay: Option<PathBuf>,
+    /// Run the replay without a window and exit when it's done.
+    pub headless: bool,
+    /// Check grid integrity in release builds too.
+    pub validate_grid: bool,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl LaunchOptions {
+    /// What `--help` prints, and what's shown after a bad flag.
+    pub const USAGE: &str = "usage: snord [--seed N] [--level N] [--mode NAME] [--mute] \
+[--windowed] [--skip-menu] [--record FILE] [--replay FILE [--headless]] [--validate-grid]";
+
+    /// Parse flags (without the program name). Returns `None` if `--help`
+    /// was asked for, so the game shouldn't start.
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Option<Self>, String> {
+        let mut options = Self::default();
+
+        let mut iter = args.into_iter();
+        while let Some(flag) = iter.next() {
+            match flag.as_str() {
+                "--help" | "-h" => return Ok(None),
+                "--mute" => options.mute = true,
+                "--windowed" => options.windowed = true,
+                "--skip-menu" => options.skip_menu = true,
+                "--validate-grid" => options.validate_grid = true,
+                "--headless" => options.headless = true,
+                "--seed" | "--level" | "--mode" | "--record" | "--replay" => {
+                    let value = iter
+                        .next()
+                        .ok_or_else(|| format!("missing value for {flag}\n{}", Self::USAGE))?;
+                    let invalid = || format!("invalid value for {flag}: {value}\n{}", Self::USAGE);
+                    match flag.as_str() {
+                        "--seed" => options.seed = Some(value.parse().map_err(|_| invalid())?),
+                        "--level" => {
+                            options.level = Some(
+                                value
+                                    .parse()
+                                    .ok()
+                                    .filter(|&level| level > 0)
+                                    .ok_or_else(invalid)?,
+                            )
+                        }
+                        "--mode" => options.mode = Some(value),
+                        "--record" => options.record = Some(PathBuf::from(value)),
+                        _ => options.replay = Some(PathBuf::from(value)),
+                    }
+                }
+                _ => return Err(format!("unknown flag {flag}\n{}", Self::USAGE)),
+            }
+        }
+
+        if options.headless && options.replay.is_none() {
+            return Err(format!("--headless needs --replay\n{}", Self::USAGE));
+        }
+        Ok(Some(options))
+    }
+}
@@ -0,0 +1,202 @@
+// Support configuring Bevy lints within code.
+#![cfg_attr(bevy_lint, feature(register_tool), register_tool(bevy))]
+
+mod accessibility;
+mod asset_tracking;
+mod audio;
+mod compute;
+mod controls;
+mod crash_log;
+#[cfg(feature = "dev")]
+mod dev_tools;
+mod diagnostics;
+mod display;
+mod feedback;
+mod game;
+mod input_replay;
+mod launch;
+mod menus;
+mod mods;
+mod profile;
+mod save;
+mod screens;
+mod seasons;
+pub mod sim;
+#[cfg(all(feature = "steam", not(target_arch = "wasm32")))]
+mod steam;
+mod textures;
+mod theme;
+mod upload;
+mod web_support;
+
+use std::time::Duration;
+
+use bevy::{
+    app::ScheduleRunnerPlugin,
+    asset::AssetMetaCheck,
+    log::LogPlugin,
+    prelude::*,
+    render::{
+        RenderPlugin,
+        settings::{RenderCreation, WgpuSettings},
+    },
+    time::TimeUpdateStrategy,
+    window::{WindowMode, WindowPlugin},
+    winit::WinitPlugin,
+};
+
+#[cfg(feature = "bench")]
+pub use game::bench;
+pub use launch::LaunchOptions;
+
+pub struct AppPlugin;
+
+impl Plugin for AppPlugin {
+    fn build(&self, app: &mut App) {
+        let options = app
+            .world()
+            .get_resource::<LaunchOptions>()
+            .cloned()
+            .unwrap_or_default();
+        let windowed = options.windowed || options.headless;
+
+        // Asset packs replace the game's files, so they're found before the
+        // asset server is built.
+        #[cfg(not(target_family = "wasm"))]
+        mods::register(app);
+
+        // Add Bevy plugins.
+        let plugins = DefaultPlugins
+            .set(AssetPlugin {
+                // Wasm builds will check for meta files (that don't exist) if this isn't set.
+                // This causes errors and even panics on web build on itch.
+                // See https://github.com/bevyengine/bevy_github_ci_template/issues/48.
+                meta_check: AssetMetaCheck::Never,
+                ..default()
+            })
+            .set(WindowPlugin {
+                primary_window: Window {
+                    title: "snord".to_string(),
+                    resolution: (800, 600).into(),
+                    fit_canvas_to_parent: true,
+                    mode: if windowed {
+                        WindowMode::Windowed
+                    } else {
+                        default()
+                    },
+                    ..default()
+                }
+                .into(),
+                // Closing mid-run asks first, see `menus/quit.rs`
+                close_when_requested: cfg!(target_family = "wasm"),
+                ..default()
+            })
+            .set(LogPlugin {
+                // Keep recent warnings and errors around for crash logs.
+                custom_layer: crash_log::log_layer,
+                ..default()
+            });
+        if options.headless {
+            // Headless replays: no GPU, no OS window, and a fixed frame rate.
+            // The window entity stays so UI layout and picking still work.
+            app.add_plugins((
+                plugins
+                    .set(RenderPlugin {
+                        render_creation: RenderCreation::Automatic(WgpuSettings {
+                            backends: None,
+                            ..default()
+                        }),
+                        ..default()
+                    })
+                    .disable::<WinitPlugin>(),
+                ScheduleRunnerPlugin::run_loop(Duration::ZERO),
+            ));
+            app.insert_resource(TimeUpdateStrategy::ManualDuration(
+                input_replay::HEADLESS_FRAME_TIME,
+            ));
+        } else {
+            app.add_plugins(plugins);
+        }
+
+        app.add_plugins((crash_log::plugin, CorePlugin));
+    }
+}
+
+/// Everything except Bevy's default plugins and crash reporting, so tests can
+/// run the game headlessly.
+pub struct CorePlugin;
+
+impl Plugin for CorePlugin {
+    fn build(&self, app: &mut App) {
+        // Add other plugins.
+        app.add_plugins((
+            (accessibility::plugin, controls::plugin),
+            asset_tracking::plugin,
+            audio::plugin,
+            game::plugin,
+            input_replay::plugin,
+            launch::plugin,
+            #[cfg(feature = "dev")]
+            dev_tools::plugin,
+            diagnostics::plugin,
+            (display::plugin, seasons::plugin),
+            menus::plugin,
+            (
+                profile::plugin,
+                #[cfg(all(feature = "steam", not(target_arch = "wasm32")))]
+                steam::plugin,
+            ),
+            screens::plugin,
+            textures::plugin,
+            theme::plugin,
+            web_support::plugin,
+        ));
+
+        // Order new `AppSystems` variants by adding them here:
+        app.configure_sets(
+            Update,
+            (
+                AppSystems::TickTimers,
+                AppSystems::RecordInput,
+                AppSystems::Update,
+            )
+                .chain(),
+        );
+
+        // Set up the `Pause` state. Pausable systems also stop while the run
+        // phase has the board frozen.
+        app.init_state::<Pause>();
+        app.configure_sets(
+            Update,
+            PausableSystems.run_if(in_state(Pause(false)).and(not(screens::run_frozen))),
+        );
+
+        // Spawn the main camera.
+        app.add_systems(Startup, spawn_camera);
+    }
+}
+
+/// High-level groupings of systems for the app in the `Update` schedule.
+/// When adding a new variant, make sure to order it in the `configure_sets`
+/// call above.
+#[derive(SystemSet, Debug, Clone, Copy, Eq, PartialEq, Hash, PartialOrd, Ord)]
+enum AppSystems {
+    /// Tick timers.
+    TickTimers,
+    /// Record player input.
+    RecordInput,
+    /// Do everything else (consider splitting this into further variants).
+    Update,
+}
+
+/// Whether or not the game is paused.
+#[derive(States, Copy, Clone, Eq, PartialEq, Hash, Debug, Default)]
+struct Pause(pub bool);
+
+/// A system set for systems that shouldn't run while the game is paused.
+#[derive(SystemSet, Copy, Clone, Eq, PartialEq, Hash, Debug)]
+struct PausableSystems;
+
+fn spawn_camera(mut commands: Commands) {
+    commands.spawn((Name::new("Camera"), Camera2d));
+}
@@ -0,0 +1,207 @@
+//! The first-run accessibility prompt.
+//!
+//! Shown once, in place of the main menu, the first time the game starts.
+//! It warns about screen shake and offers the options that matter before
+//! playing; everything here can be changed later in the settings menu.
+
+use bevy::{ecs::spawn::SpawnWith, input::common_conditions::input_just_pressed, prelude::*};
+
+use crate::{
+    accessibility::AccessibilitySettings,
+    menus::{
+        Menu,
+        settings::{
+            ColorblindLabel, GlobalVolumeLabel, ReducedMotionLabel, cycle_colorblind_mode,
+            lower_global_volume, raise_global_volume, spawn_text_button, toggle_reduced_motion,
+            update_colorblind_label, update_global_volume_label, update_reduced_motion_label,
+        },
+    },
+    theme::{
+        GameFont,
+        interaction::ImageInteractionPalette,
+        palette::{HEADER_TEXT, LABEL_TEXT},
+        widget,
+    },
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(OnEnter(Menu::Accessibility), spawn_accessibility_prompt);
+    app.add_systems(
+        Update,
+        (
+            update_reduced_motion_label,
+            update_colorblind_label,
+            update_global_volume_label,
+            finish_prompt.run_if(input_just_pressed(KeyCode::Escape)),
+        )
+            .run_if(in_state(Menu::Accessibility)),
+    );
+}
+
+fn spawn_accessibility_prompt(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    game_font: Res<GameFont>,
+) {
+    let minus_button = asset_server.load("images/minus_button.png");
+    let plus_button = asset_server.load("images/plus_button.png");
+    let font = game_font.0.clone();
+
+    commands.spawn((
+        Name::new("Accessibility Prompt"),
+        Node {
+            position_type: PositionType::Absolute,
+            width: Val::Percent(100.0),
+            height: Val::Percent(100.0),
+            align_items: AlignItems::Center,
+            justify_content: JustifyContent::Center,
+            flex_direction: FlexDirection::Column,
+            row_gap: Val::Px(10.0),
+            ..default()
+        },
+        BackgroundColor(Color::srgb(0.96, 0.92, 0.84)),
+        GlobalZIndex(2),
+        DespawnOnExit(Menu::Accessibility),
+        Children::spawn(SpawnWith(move |parent: &mut ChildSpawner| {
+            parent.spawn((
+                Name::new("Accessibility Header"),
+                Text::new("Before You Play"),
+                TextFont {
+                    font: font.clone(),
+                    font_size: 48.0,
+                    ..default()
+                },
+                TextColor(HEADER_TEXT),
+            ));
+            parent.spawn((
+                Name::new("Content Warning"),
+                Text::new("Popping bubbles shakes the screen.\nYou can change these any time in Settings."),
+                TextFont {
+                    font: font.clone(),
+                    font_size: 20.0,
+                    ..default()
+                },
+                TextColor(LABEL_TEXT),
+                TextLayout::new_with_justify(Justify::Center),
+                Node {
+                    margin: UiRect::bottom(Val::Px(20.0)),
+                    ..default()
+                },
+            ));
+
+            // Reduced motion row
+            parent
+                .spawn((Name::new("Reduced Motion Row"), option_row()))
+                .with_children(|row| {
+                    row.spawn(option_label("Reduced Motion", font.clone()));
+                    spawn_text_button(row, font.clone(), "Off", 80.0, ReducedMotionLabel)
+                        .observe(toggle_reduced_motion);
+                });
+
+            // Colorblind symbols row
+            parent
+                .spawn((Name::new("Colorblind Row"), option_row()))
+                .with_children(|row| {
+                    row.spawn(option_label("Colorblind Symbols", font.clone()));
+                    spawn_text_button(row, font.clone(), "Off", 100.0, ColorblindLabel)
+                        .observe(cycle_colorblind_mode);
+                });
+
+            // Volume row
+            parent
+                .spawn((Name::new("Volume Row"), option_row()))
+                .with_children(|row| {
+                    row.spawn(option_label("Volume", font.clone()));
+                    row.spawn(volume_button(minus_button))
+                        .observe(lower_global_volume);
+                    row.spawn((
+                        Name::new("Volume Value"),
+                        Text::new("100%"),
+                        TextFont {
+                            font: font.clone(),
+                            font_size: 24.0,
+                            ..default()
+                        },
+                        TextColor(LABEL_TEXT),
+                        GlobalVolumeLabel,
+                        Node {
+                            width: Val::Px(60.0),
+                            justify_content: JustifyContent::Center,
+                            ..default()
+                        },
+                    ));
+                    row.spawn(volume_button(plus_button))
+                        .observe(raise_global_volume);
+                });
+
+            parent.spawn((
+                Node {
+                    margin: UiRect::top(Val::Px(20.0)),
+                    ..default()
+                },
+                children![widget::button("Continue", finish_prompt_on_click)],
+            ));
+        })),
+    ));
+}
+
+fn option_row() -> Node {
+    Node {
+        flex_direction: FlexDirection::Row,
+        align_items: AlignItems::Center,
+        column_gap: Val::Px(15.0),
+        margin: UiRect::bottom(Val::Px(10.0)),
+        ..default()
+    }
+}
+
+fn option_label(text: &'static str, font: Handle<Font>) -> impl Bundle {
+    (
+        Name::new("Option Label"),
+        Text::new(text),
+        TextFont {
+            font,
+            font_size: 24.0,
+            ..default()
+        },
+        TextColor(LABEL_TEXT),
+    )
+}
+
+fn volume_button(image: Handle<Image>) -> impl Bundle {
+    (
+        Name::new("Volume Button"),
+        Button,
+        ImageNode::new(image),
+        ImageInteractionPalette {
+            none: Color::WHITE,
+            hovered: Color::srgb(0.85, 0.85, 0.85),
+            pressed: Color::srgb(0.7, 0.7, 0.7),
+        },
+        Node {
+            width: Val::Px(30.0),
+            height: Val::Px(35.0),
+            ..default()
+        },
+    )
+}
+
+fn finish_prompt_on_click(
+    _: On<Pointer<Click>>,
+    settings: ResMut<AccessibilitySettings>,
+    next_menu: ResMut<NextState<Menu>>,
+) {
+    finish_prompt(settings, next_menu);
+}
+
+/// Remember that the prompt was answered and go on to the main menu.
+fn finish_prompt(
+    mut settings: ResMut<AccessibilitySettings>,
+    mut next_menu: ResMut<NextState<Menu>>,
+) {
+    settings.prompt_seen = true;
+    // Everything is new on a first launch, so there's no What's New to show
+    settings.mark_whats_new_seen();
+    info!("Accessibility prompt answered: {:?}", *settings);
+    next_menu.set(Menu::Main);
+}
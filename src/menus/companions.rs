@@ -0,0 +1,188 @@
+//! The companions screen, opened from the mode select screen.
+//!
+//! Lists every [`Companion`] with its passive, or what unlocks it if the
+//! [`Profile`] hasn't earned it yet. Picking one (or going without) saves the
+//! choice to the profile, so it comes along on every run until changed.
+
+use bevy::{ecs::spawn::SpawnWith, input::common_conditions::input_just_pressed, prelude::*};
+
+use crate::{
+    game::companion::Companion,
+    menus::{Menu, settings::spawn_text_button},
+    profile::Profile,
+    save::SaveFile,
+    theme::{
+        GameFont,
+        interaction::InteractionPalette,
+        palette::{HEADER_TEXT, LABEL_TEXT},
+        widget,
+    },
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(OnEnter(Menu::Companions), spawn_companions_menu);
+    app.add_systems(
+        Update,
+        go_back.run_if(in_state(Menu::Companions).and(input_just_pressed(KeyCode::Escape))),
+    );
+}
+
+/// Background of a locked companion's button.
+const LOCKED_BUTTON: Color = Color::srgb(0.62, 0.62, 0.62);
+
+/// Text color for locked companions.
+const LOCKED_TEXT: Color = Color::srgb(0.45, 0.45, 0.45);
+
+/// What one row of the screen shows.
+struct CompanionRow {
+    companion: Option<Companion>,
+    unlocked: bool,
+    chosen: bool,
+}
+
+fn spawn_companions_menu(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    game_font: Res<GameFont>,
+    profile: Res<Profile>,
+) {
+    let back_button = asset_server.load("images/back_button.png");
+    let font = game_font.0.clone();
+    let rows: Vec<CompanionRow> = std::iter::once(None)
+        .chain(Companion::ALL.into_iter().map(Some))
+        .map(|companion| CompanionRow {
+            companion,
+            unlocked: companion.is_none_or(|companion| companion.is_unlocked(&profile)),
+            chosen: profile.companion == companion,
+        })
+        .collect();
+
+    commands.spawn((
+        Name::new("Companions Menu"),
+        Node {
+            position_type: PositionType::Absolute,
+            width: Val::Percent(100.0),
+            height: Val::Percent(100.0),
+            align_items: AlignItems::Center,
+            justify_content: JustifyContent::Center,
+            flex_direction: FlexDirection::Column,
+            row_gap: Val::Px(6.0),
+            ..default()
+        },
+        BackgroundColor(Color::srgb(0.96, 0.92, 0.84)),
+        GlobalZIndex(2),
+        DespawnOnExit(Menu::Companions),
+        Children::spawn(SpawnWith(move |parent: &mut ChildSpawner| {
+            parent.spawn((
+                Name::new("Companions Header"),
+                Text::new("Choose a Companion"),
+                TextFont {
+                    font: font.clone(),
+                    font_size: 40.0,
+                    ..default()
+                },
+                TextColor(HEADER_TEXT),
+                Node {
+                    margin: UiRect::bottom(Val::Px(10.0)),
+                    ..default()
+                },
+            ));
+
+            for row in rows {
+                spawn_companion_row(parent, &font, row);
+            }
+
+            parent.spawn(widget::button_image(
+                back_button,
+                266.0,
+                105.0,
+                go_back_on_click,
+            ));
+        })),
+    ));
+}
+
+fn spawn_companion_row(parent: &mut ChildSpawner, font: &Handle<Font>, row: CompanionRow) {
+    let text_color = if row.unlocked {
+        LABEL_TEXT
+    } else {
+        LOCKED_TEXT
+    };
+    let (label, description) = match row.companion {
+        Some(companion) if row.unlocked => (companion.label(), companion.description()),
+        Some(companion) => (companion.label(), companion.unlock_hint()),
+        None => ("Nobody", "Play on your own."),
+    };
+    parent
+        .spawn((
+            Name::new("Companion Row"),
+            Node {
+                width: Val::Px(680.0),
+                align_items: AlignItems::Center,
+                column_gap: Val::Px(12.0),
+                ..default()
+            },
+        ))
+        .with_children(|line| {
+            let mut button = spawn_text_button(line, font.clone(), label, 140.0, ());
+            if row.unlocked {
+                button.observe(choose_companion(row.companion));
+            } else {
+                button.insert((
+                    BackgroundColor(LOCKED_BUTTON),
+                    InteractionPalette {
+                        none: LOCKED_BUTTON,
+                        hovered: LOCKED_BUTTON,
+                        pressed: LOCKED_BUTTON,
+                    },
+                ));
+            }
+
+            line.spawn((
+                Name::new("Companion Description"),
+                Text::new(description),
+                TextFont {
+                    font: font.clone(),
+                    font_size: 16.0,
+                    ..default()
+                },
+                TextColor(text_color),
+                Node {
+                    flex_grow: 1.0,
+                    ..default()
+                },
+            ));
+            line.spawn((
+                Name::new("Companion Status"),
+                Text::new(match (row.chosen, row.unlocked) {
+                    (true, _) => "Chosen",
+                    (false, true) => "",
+                    (false, false) => "Locked",
+                }),
+                TextFont {
+                    font: font.clone(),
+                    font_size: 16.0,
+                    ..default()
+                },
+                TextColor(text_color),
+            ));
+        });
+}
+
+fn choose_companion(
+    companion: Option<Companion>,
+) -> impl Fn(On<Pointer<Click>>, ResMut<Profile>, ResMut<NextState<Menu>>) {
+    move |_, mut profile, mut next_menu| {
+        profile.companion = companion;
+        profile.save();
+        next_menu.set(Menu::ModeSelect);
+    }
+}
+
+fn go_back_on_click(_: On<Pointer<Click>>, mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::ModeSelect);
+}
+
+fn go_back(mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::ModeSelect);
+}
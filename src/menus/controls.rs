@@ -0,0 +1,266 @@
+//! The controls menu, opened from the settings menu.
+//!
+//! Lists each [`InputAction`] with its key and gamepad button. Clicking a key
+//! waits for the next key press and binds that instead; Escape backs out
+//! without changing anything, and reserved keys are turned down with a toast. While waiting, the menu takes every key press,
+//! so the new key doesn't also do what it's bound to.
+
+use bevy::{
+    ecs::spawn::SpawnWith,
+    input::{
+        ButtonState, InputSystems, common_conditions::input_just_pressed, keyboard::KeyboardInput,
+    },
+    prelude::*,
+};
+
+use crate::{
+    controls::{InputAction, InputBindings, is_reserved, key_label},
+    input_replay::variant_name,
+    menus::{Menu, settings::spawn_text_button},
+    theme::{
+        GameFont,
+        palette::{HEADER_TEXT, LABEL_TEXT},
+        toast::ShowToast,
+        widget,
+    },
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<Rebinding>();
+
+    app.add_systems(
+        OnEnter(Menu::Controls),
+        (stop_rebinding, spawn_controls_menu),
+    );
+    app.add_systems(
+        PreUpdate,
+        capture_rebind
+            .after(InputSystems)
+            .run_if(in_state(Menu::Controls)),
+    );
+    app.add_systems(
+        Update,
+        (
+            go_back.run_if(input_just_pressed(KeyCode::Escape)),
+            update_binding_labels.run_if(
+                resource_changed::<InputBindings>
+                    .or(resource_changed::<Rebinding>)
+                    .or(any_match_filter::<Added<BindingLabel>>),
+            ),
+        )
+            .run_if(in_state(Menu::Controls)),
+    );
+}
+
+/// The action waiting for a key, if any.
+#[derive(Resource, Debug, Default)]
+struct Rebinding(Option<InputAction>);
+
+/// The button that rebinds an action's key.
+#[derive(Component, Debug)]
+struct RebindButton(InputAction);
+
+/// The text showing an action's key.
+#[derive(Component, Debug)]
+struct BindingLabel(InputAction);
+
+fn stop_rebinding(mut rebinding: ResMut<Rebinding>) {
+    rebinding.0 = None;
+}
+
+fn spawn_controls_menu(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    game_font: Res<GameFont>,
+    bindings: Res<InputBindings>,
+) {
+    let back_button = asset_server.load("images/back_button.png");
+    let font = game_font.0.clone();
+    let bindings = bindings.clone();
+
+    commands.spawn((
+        Name::new("Controls Menu"),
+        Node {
+            position_type: PositionType::Absolute,
+            width: Val::Percent(100.0),
+            height: Val::Percent(100.0),
+            align_items: AlignItems::Center,
+            justify_content: JustifyContent::Center,
+            flex_direction: FlexDirection::Column,
+            row_gap: Val::Px(10.0),
+            ..default()
+        },
+        BackgroundColor(Color::srgb(0.96, 0.92, 0.84)),
+        GlobalZIndex(2),
+        DespawnOnExit(Menu::Controls),
+        Children::spawn(SpawnWith(move |parent: &mut ChildSpawner| {
+            parent.spawn((
+                Name::new("Controls Header"),
+                Text::new("Controls"),
+                TextFont {
+                    font: font.clone(),
+                    font_size: 40.0,
+                    ..default()
+                },
+                TextColor(HEADER_TEXT),
+                Node {
+                    margin: UiRect::bottom(Val::Px(10.0)),
+                    ..default()
+                },
+            ));
+
+            for action in InputAction::ALL {
+                let button = variant_name(&bindings.get(action).button).unwrap_or_default();
+                parent
+                    .spawn((
+                        Name::new(format!("{} Row", action.label())),
+                        Node {
+                            flex_direction: FlexDirection::Row,
+                            align_items: AlignItems::Center,
+                            column_gap: Val::Px(15.0),
+                            ..default()
+                        },
+                    ))
+                    .with_children(|row| {
+                        row.spawn((
+                            Name::new("Action Label"),
+                            Text::new(action.label()),
+                            TextFont {
+                                font: font.clone(),
+                                font_size: 24.0,
+                                ..default()
+                            },
+                            TextColor(LABEL_TEXT),
+                            Node {
+                                width: Val::Px(180.0),
+                                ..default()
+                            },
+                        ));
+                        spawn_text_button(row, font.clone(), "", 180.0, BindingLabel(action))
+                            .insert(RebindButton(action))
+                            .observe(start_rebinding);
+                        row.spawn((
+                            Name::new("Gamepad Label"),
+                            Text::new(format!("Pad: {button}")),
+                            TextFont {
+                                font: font.clone(),
+                                font_size: 18.0,
+                                ..default()
+                            },
+                            TextColor(LABEL_TEXT),
+                            Node {
+                                width: Val::Px(120.0),
+                                ..default()
+                            },
+                        ));
+                    });
+            }
+
+            parent
+                .spawn((
+                    Name::new("Reset Row"),
+                    Node {
+                        margin: UiRect::top(Val::Px(6.0)),
+                        ..default()
+                    },
+                ))
+                .with_children(|row| {
+                    spawn_text_button(row, font.clone(), "Reset to Defaults", 220.0, ())
+                        .observe(reset_bindings);
+                });
+
+            parent.spawn(widget::button_image(
+                back_button,
+                266.0,
+                105.0,
+                go_back_on_click,
+            ));
+        })),
+    ));
+}
+
+fn start_rebinding(
+    click: On<Pointer<Click>>,
+    buttons: Query<&RebindButton>,
+    mut rebinding: ResMut<Rebinding>,
+) {
+    if let Ok(button) = buttons.get(click.entity) {
+        rebinding.0 = Some(button.0);
+    }
+}
+
+/// Bind the next key pressed to the action waiting for one.
+fn capture_rebind(
+    mut keys: MessageReader<KeyboardInput>,
+    mut keyboard: ResMut<ButtonInput<KeyCode>>,
+    mut rebinding: ResMut<Rebinding>,
+    mut bindings: ResMut<InputBindings>,
+    mut toasts: MessageWriter<ShowToast>,
+) {
+    // Read every frame, so presses from before the click aren't taken
+    let presses: Vec<KeyCode> = keys
+        .read()
+        .filter(|input| input.state == ButtonState::Pressed && !input.repeat)
+        .map(|input| input.key_code)
+        .collect();
+    let Some(action) = rebinding.0 else {
+        return;
+    };
+
+    for key in presses {
+        if key == KeyCode::Escape {
+            rebinding.0 = None;
+            break;
+        }
+        // Keys the platform couldn't name can't be saved
+        if variant_name(&key).is_none() {
+            continue;
+        }
+        if bindings.rebind_key(action, key) {
+            info!("Bound {} to {:?}", action.label(), key);
+            rebinding.0 = None;
+            break;
+        }
+        // Keep waiting for a key that's free to bind
+        if is_reserved(key) {
+            toasts.write(ShowToast::info(format!("{} is reserved", key_label(key))));
+        } else {
+            toasts.write(ShowToast::info(format!(
+                "{} can't be swapped onto a reserved key",
+                key_label(key)
+            )));
+        }
+    }
+    keyboard.reset_all();
+}
+
+fn update_binding_labels(
+    bindings: Res<InputBindings>,
+    rebinding: Res<Rebinding>,
+    mut labels: Query<(&BindingLabel, &mut Text)>,
+) {
+    for (label, mut text) in &mut labels {
+        text.0 = if rebinding.0 == Some(label.0) {
+            "Press a key...".to_string()
+        } else {
+            key_label(bindings.get(label.0).key)
+        };
+    }
+}
+
+fn reset_bindings(
+    _: On<Pointer<Click>>,
+    mut bindings: ResMut<InputBindings>,
+    mut rebinding: ResMut<Rebinding>,
+) {
+    *bindings = InputBindings::default();
+    rebinding.0 = None;
+}
+
+fn go_back_on_click(_: On<Pointer<Click>>, mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::Settings);
+}
+
+fn go_back(mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::Settings);
+}
@@ -0,0 +1,165 @@
+//! The practice drills menu, listing each drill with its best time and rank.
+//!
+//! Opened from the main menu, and again after a drill ends so the next one is
+//! a click away.
+
+use bevy::{ecs::spawn::SpawnWith, input::common_conditions::input_just_pressed, prelude::*};
+
+use crate::{
+    game::{
+        drills::{ActiveDrill, DrillBests, Drills},
+        grading::{GradedLevel, LevelRanks, Rank},
+        mode::GameMode,
+    },
+    menus::Menu,
+    screens::Screen,
+    theme::{
+        GameFont,
+        palette::{HEADER_TEXT, LABEL_TEXT},
+        widget,
+    },
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(OnEnter(Menu::Drills), spawn_drills_menu);
+    app.add_systems(
+        Update,
+        go_back.run_if(in_state(Menu::Drills).and(input_just_pressed(KeyCode::Escape))),
+    );
+}
+
+fn spawn_drills_menu(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    game_font: Res<GameFont>,
+    drills: Res<Drills>,
+    bests: Res<DrillBests>,
+    ranks: Res<LevelRanks>,
+) {
+    let back_button = asset_server.load("images/back_button.png");
+    let font = game_font.0.clone();
+    let entries: Vec<(String, String, Option<Rank>)> = drills
+        .0
+        .iter()
+        .map(|drill| {
+            let best = match bests.times.get(&drill.name) {
+                Some(secs) => format!("Best: {secs:.1}s"),
+                None => "Not cleared yet".to_string(),
+            };
+            (
+                drill.name.clone(),
+                format!("{} {}", drill.description, best),
+                ranks.best(&GradedLevel::Drill(drill.name.clone())),
+            )
+        })
+        .collect();
+
+    commands.spawn((
+        Name::new("Drills Menu"),
+        Node {
+            position_type: PositionType::Absolute,
+            width: Val::Percent(100.0),
+            height: Val::Percent(100.0),
+            align_items: AlignItems::Center,
+            justify_content: JustifyContent::Center,
+            flex_direction: FlexDirection::Column,
+            row_gap: Val::Px(6.0),
+            ..default()
+        },
+        BackgroundColor(Color::srgb(0.96, 0.92, 0.84)),
+        GlobalZIndex(2),
+        DespawnOnExit(Menu::Drills),
+        Children::spawn(SpawnWith(move |parent: &mut ChildSpawner| {
+            parent.spawn((
+                Name::new("Drills Header"),
+                Text::new("Practice Drills"),
+                TextFont {
+                    font: font.clone(),
+                    font_size: 40.0,
+                    ..default()
+                },
+                TextColor(HEADER_TEXT),
+                Node {
+                    margin: UiRect::bottom(Val::Px(14.0)),
+                    ..default()
+                },
+            ));
+
+            for (index, (name, line, rank)) in entries.into_iter().enumerate() {
+                parent.spawn(widget::button(
+                    name,
+                    move |_: On<Pointer<Click>>,
+                          mut active: ResMut<ActiveDrill>,
+                          mut mode: ResMut<GameMode>,
+                          mut next_screen: ResMut<NextState<Screen>>,
+                          mut next_menu: ResMut<NextState<Menu>>| {
+                        active.0 = Some(index);
+                        *mode = GameMode::Classic;
+                        // Go through Loading so gameplay restarts from scratch
+                        next_menu.set(Menu::None);
+                        next_screen.set(Screen::Loading);
+                    },
+                ));
+                let mut description = parent.spawn((
+                    Name::new("Drill Description"),
+                    Text::new(line),
+                    TextFont {
+                        font: font.clone(),
+                        font_size: 16.0,
+                        ..default()
+                    },
+                    TextColor(LABEL_TEXT),
+                    Node {
+                        margin: UiRect::bottom(Val::Px(8.0)),
+                        ..default()
+                    },
+                ));
+                // Completion badge in the best rank's color
+                if let Some(rank) = rank {
+                    description.with_child((
+                        TextSpan::new(format!("  Rank {}", rank.label())),
+                        TextFont {
+                            font: font.clone(),
+                            font_size: 16.0,
+                            ..default()
+                        },
+                        TextColor(rank.color()),
+                    ));
+                }
+            }
+
+            parent.spawn(widget::button_image(
+                back_button,
+                266.0,
+                105.0,
+                go_back_on_click,
+            ));
+        })),
+    ));
+}
+
+/// After a drill, leave to the title screen; otherwise return to the main menu.
+fn go_back_on_click(
+    _: On<Pointer<Click>>,
+    screen: Res<State<Screen>>,
+    mut next_screen: ResMut<NextState<Screen>>,
+    mut next_menu: ResMut<NextState<Menu>>,
+) {
+    if screen.get() == &Screen::Gameplay {
+        next_screen.set(Screen::Title);
+    } else {
+        next_menu.set(Menu::Main);
+    }
+}
+
+fn go_back(
+    screen: Res<State<Screen>>,
+    mut next_screen: ResMut<NextState<Screen>>,
+    mut next_menu: ResMut<NextState<Menu>>,
+) {
+    if screen.get() == &Screen::Gameplay {
+        next_screen.set(Screen::Title);
+    } else {
+        next_menu.set(Menu::Main);
+    }
+}
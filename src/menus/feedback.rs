@@ -0,0 +1,317 @@
+//! The feedback form, opened from the settings menu.
+//!
+//! Two text boxes, a subject and a message, typed into with the keyboard:
+//! Tab (or clicking a box) switches between them, and Enter moves from the
+//! subject to the message. While the form is open it takes every key press,
+//! so gameplay shortcuts behind the pause menu don't fire while typing.
+
+use bevy::{
+    ecs::spawn::SpawnWith,
+    input::{
+        ButtonState, InputSystems,
+        keyboard::{Key, KeyboardInput},
+    },
+    prelude::*,
+};
+
+use crate::{
+    feedback::{self, Feedback, MAX_MESSAGE_CHARS, MAX_SUBJECT_CHARS},
+    game::history::RunSeed,
+    menus::{Menu, settings::spawn_text_button},
+    theme::{
+        GameFont,
+        palette::{HEADER_TEXT, LABEL_TEXT},
+        toast::ShowToast,
+        widget,
+    },
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<FeedbackDraft>();
+
+    app.add_systems(OnEnter(Menu::Feedback), spawn_feedback_menu);
+    app.add_systems(
+        PreUpdate,
+        type_into_form
+            .after(InputSystems)
+            .run_if(in_state(Menu::Feedback)),
+    );
+    app.add_systems(
+        Update,
+        update_fields.run_if(
+            in_state(Menu::Feedback)
+                .and(resource_changed::<FeedbackDraft>.or(any_match_filter::<Added<FieldText>>)),
+        ),
+    );
+}
+
+/// Background of the text boxes.
+const FIELD_BACKGROUND: Color = Color::srgb(1.0, 0.99, 0.96);
+
+/// Border of the text box being typed into.
+const FOCUSED_BORDER: Color = Color::srgb(0.275, 0.400, 0.750);
+
+/// Border of the other text box.
+const UNFOCUSED_BORDER: Color = Color::srgb(0.6, 0.56, 0.5);
+
+/// One of the form's text boxes.
+#[derive(Component, Debug, Clone, Copy, Default, PartialEq, Eq)]
+enum FeedbackField {
+    #[default]
+    Subject,
+    Message,
+}
+
+impl FeedbackField {
+    fn other(self) -> Self {
+        match self {
+            FeedbackField::Subject => FeedbackField::Message,
+            FeedbackField::Message => FeedbackField::Subject,
+        }
+    }
+}
+
+/// What's been typed so far. Kept when the form is closed, until it's sent.
+#[derive(Resource, Debug, Default)]
+struct FeedbackDraft {
+    subject: String,
+    message: String,
+    focus: FeedbackField,
+}
+
+impl FeedbackDraft {
+    fn text(&self, field: FeedbackField) -> &str {
+        match field {
+            FeedbackField::Subject => &self.subject,
+            FeedbackField::Message => &self.message,
+        }
+    }
+
+    /// Add to the focused box, up to its length limit.
+    fn type_char(&mut self, c: char) {
+        let (text, max) = match self.focus {
+            FeedbackField::Subject => (&mut self.subject, MAX_SUBJECT_CHARS),
+            FeedbackField::Message => (&mut self.message, MAX_MESSAGE_CHARS),
+        };
+        if text.chars().count() < max {
+            text.push(c);
+        }
+    }
+
+    fn backspace(&mut self) {
+        match self.focus {
+            FeedbackField::Subject => self.subject.pop(),
+            FeedbackField::Message => self.message.pop(),
+        };
+    }
+}
+
+/// The text inside a box.
+#[derive(Component, Debug)]
+struct FieldText(FeedbackField);
+
+fn spawn_feedback_menu(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    game_font: Res<GameFont>,
+) {
+    let back_button = asset_server.load("images/back_button.png");
+    let font = game_font.0.clone();
+
+    commands.spawn((
+        Name::new("Feedback Menu"),
+        Node {
+            position_type: PositionType::Absolute,
+            width: Val::Percent(100.0),
+            height: Val::Percent(100.0),
+            align_items: AlignItems::Center,
+            justify_content: JustifyContent::Center,
+            flex_direction: FlexDirection::Column,
+            row_gap: Val::Px(8.0),
+            ..default()
+        },
+        BackgroundColor(Color::srgb(0.96, 0.92, 0.84)),
+        GlobalZIndex(2),
+        DespawnOnExit(Menu::Feedback),
+        Children::spawn(SpawnWith(move |parent: &mut ChildSpawner| {
+            parent.spawn((
+                Name::new("Feedback Header"),
+                Text::new("Send Feedback"),
+                TextFont {
+                    font: font.clone(),
+                    font_size: 40.0,
+                    ..default()
+                },
+                TextColor(HEADER_TEXT),
+                Node {
+                    margin: UiRect::bottom(Val::Px(10.0)),
+                    ..default()
+                },
+            ));
+
+            for (field, title, height) in [
+                (FeedbackField::Subject, "Subject", 40.0),
+                (FeedbackField::Message, "Message", 180.0),
+            ] {
+                parent.spawn((
+                    Name::new(format!("{title} Label")),
+                    Text::new(title),
+                    TextFont {
+                        font: font.clone(),
+                        font_size: 20.0,
+                        ..default()
+                    },
+                    TextColor(LABEL_TEXT),
+                ));
+                parent
+                    .spawn((
+                        Name::new(format!("{title} Box")),
+                        field,
+                        Button,
+                        BackgroundColor(FIELD_BACKGROUND),
+                        BorderColor::all(UNFOCUSED_BORDER),
+                        Node {
+                            width: Val::Px(520.0),
+                            height: Val::Px(height),
+                            border: UiRect::all(Val::Px(2.0)),
+                            padding: UiRect::all(Val::Px(8.0)),
+                            overflow: Overflow::clip(),
+                            ..default()
+                        },
+                        BorderRadius::all(Val::Px(6.0)),
+                    ))
+                    .observe(focus_field)
+                    .with_children(|field_box| {
+                        field_box.spawn((
+                            Name::new("Field Text"),
+                            FieldText(field),
+                            Text::default(),
+                            TextFont {
+                                font: font.clone(),
+                                font_size: 18.0,
+                                ..default()
+                            },
+                            TextColor(LABEL_TEXT),
+                            Pickable::IGNORE,
+                        ));
+                    });
+            }
+
+            parent.spawn((
+                Name::new("Feedback Note"),
+                Text::new("Your game version and run seed are sent along."),
+                TextFont {
+                    font: font.clone(),
+                    font_size: 16.0,
+                    ..default()
+                },
+                TextColor(LABEL_TEXT),
+            ));
+
+            parent
+                .spawn((
+                    Name::new("Send Row"),
+                    Node {
+                        margin: UiRect::top(Val::Px(6.0)),
+                        ..default()
+                    },
+                ))
+                .with_children(|row| {
+                    spawn_text_button(row, font.clone(), "Send", 120.0, ()).observe(send_feedback);
+                });
+
+            parent.spawn(widget::button_image(
+                back_button,
+                266.0,
+                105.0,
+                go_back_on_click,
+            ));
+        })),
+    ));
+}
+
+/// Type into the focused box, taking the key presses from everything else.
+fn type_into_form(
+    mut keys: MessageReader<KeyboardInput>,
+    mut keyboard: ResMut<ButtonInput<KeyCode>>,
+    mut draft: ResMut<FeedbackDraft>,
+    mut next_menu: ResMut<NextState<Menu>>,
+) {
+    for input in keys.read() {
+        if input.state != ButtonState::Pressed {
+            continue;
+        }
+        match &input.logical_key {
+            Key::Escape => next_menu.set(Menu::Settings),
+            Key::Tab => draft.focus = draft.focus.other(),
+            Key::Enter if draft.focus == FeedbackField::Subject => {
+                draft.focus = FeedbackField::Message;
+            }
+            Key::Enter => draft.type_char('\n'),
+            Key::Backspace => draft.backspace(),
+            _ => {
+                for c in input.text.iter().flat_map(|text| text.chars()) {
+                    if !c.is_control() {
+                        draft.type_char(c);
+                    }
+                }
+            }
+        }
+    }
+    keyboard.reset_all();
+}
+
+/// Show what's been typed, with a cursor in the focused box.
+fn update_fields(
+    draft: Res<FeedbackDraft>,
+    mut texts: Query<(&FieldText, &mut Text)>,
+    mut boxes: Query<(&FeedbackField, &mut BorderColor)>,
+) {
+    for (field, mut text) in &mut texts {
+        let cursor = if field.0 == draft.focus { "|" } else { "" };
+        text.0 = format!("{}{}", draft.text(field.0), cursor);
+    }
+    for (field, mut border) in &mut boxes {
+        *border = BorderColor::all(if *field == draft.focus {
+            FOCUSED_BORDER
+        } else {
+            UNFOCUSED_BORDER
+        });
+    }
+}
+
+fn focus_field(
+    click: On<Pointer<Click>>,
+    fields: Query<&FeedbackField>,
+    mut draft: ResMut<FeedbackDraft>,
+) {
+    if let Ok(&field) = fields.get(click.entity) {
+        draft.focus = field;
+    }
+}
+
+fn send_feedback(
+    _: On<Pointer<Click>>,
+    mut draft: ResMut<FeedbackDraft>,
+    seed: Res<RunSeed>,
+    mut toasts: MessageWriter<ShowToast>,
+    mut next_menu: ResMut<NextState<Menu>>,
+) {
+    let note = Feedback::new(&draft.subject, &draft.message, seed.0);
+    if note.is_empty() {
+        toasts.write(ShowToast::info("Write a subject or message first"));
+        return;
+    }
+    let sending = feedback::send(note);
+    toasts.write(ShowToast::info(if sending {
+        "Thanks! Sending feedback..."
+    } else {
+        "Thanks! Feedback saved"
+    }));
+    *draft = FeedbackDraft::default();
+    next_menu.set(Menu::Settings);
+}
+
+fn go_back_on_click(_: On<Pointer<Click>>, mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::Settings);
+}
@@ -1,22 +1,39 @@
 //! The game over menu.
+//!
+//! Beside the usual buttons, "See What Went Wrong" plays back the run's last
+//! shots slowed down, when the run was recorded.
 
-use bevy::prelude::*;
+use bevy::{ecs::spawn::SpawnWith, prelude::*};
 
-use crate::{Pause, menus::Menu, screens::Screen, theme::widget};
+use crate::{
+    game::{
+        mini_board::MiniBoard,
+        replay::{FinalShots, WatchReplay},
+        state::{ContinueRun, ContinueState},
+    },
+    menus::Menu,
+    screens::Screen,
+    theme::{GameFont, palette::LABEL_TEXT, widget},
+};
 
 pub(super) fn plugin(app: &mut App) {
-    app.add_systems(OnEnter(Menu::GameOver), (pause_game, spawn_gameover_menu));
+    app.add_systems(OnEnter(Menu::GameOver), spawn_gameover_menu);
 }
 
-fn pause_game(mut next_pause: ResMut<NextState<Pause>>) {
-    next_pause.set(Pause(true));
-}
-
-fn spawn_gameover_menu(mut commands: Commands, asset_server: Res<AssetServer>) {
+fn spawn_gameover_menu(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    continue_state: Res<ContinueState>,
+    final_shots: Res<FinalShots>,
+    game_font: Res<GameFont>,
+) {
     let game_over_title = asset_server.load("images/game_over.png");
     let play_button = asset_server.load("images/play_button.png");
     let settings_button = asset_server.load("images/settings_button.png");
     let exit_button = asset_server.load("images/exit_button.png");
+    let can_continue = !continue_state.used;
+    let can_rewind = !final_shots.is_empty();
+    let font = game_font.0.clone();
 
     commands.spawn((
         Name::new("Game Over Menu"),
@@ -26,30 +43,121 @@ fn spawn_gameover_menu(mut commands: Commands, asset_server: Res<AssetServer>) {
             height: Val::Percent(100.0),
             align_items: AlignItems::Center,
             justify_content: JustifyContent::Center,
-            flex_direction: FlexDirection::Column,
-            row_gap: Val::Px(10.0),
+            column_gap: Val::Px(40.0),
             ..default()
         },
         // Solid off-white background to cover the game (same as main menu/splash)
         BackgroundColor(Color::srgb(0.96, 0.92, 0.84)),
         GlobalZIndex(2),
         DespawnOnExit(Menu::GameOver),
-        children![
-            (
-                Name::new("Game Over Title"),
-                ImageNode::new(game_over_title),
+        Children::spawn(SpawnWith(move |parent: &mut ChildSpawner| {
+            // What the board looked like when the run ended, and how it got
+            // there
+            parent
+                .spawn((
+                    Name::new("Final Board"),
+                    Node {
+                        flex_direction: FlexDirection::Column,
+                        align_items: AlignItems::Center,
+                        row_gap: Val::Px(8.0),
+                        ..default()
+                    },
+                    children![
+                        (
+                            Name::new("Final Board Label"),
+                            Text::new("Final Board"),
+                            widget::game_font(font, 20.0),
+                            TextColor(LABEL_TEXT),
+                        ),
+                        (Name::new("Final Board Render"), MiniBoard),
+                    ],
+                ))
+                .with_children(|column| {
+                    if can_rewind {
+                        column.spawn(widget::button_medium(
+                            "See What Went Wrong",
+                            watch_final_shots,
+                        ));
+                    }
+                });
+            parent.spawn((
+                Name::new("Game Over Buttons"),
                 Node {
-                    width: Val::Px(500.0),
-                    height: Val::Px(200.0),
-                    margin: UiRect::bottom(Val::Px(20.0)),
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    row_gap: Val::Px(10.0),
                     ..default()
                 },
-            ),
-            widget::button_image(play_button, 266.0, 105.0, restart_game),
-            widget::button_image(settings_button, 266.0, 105.0, open_settings_menu),
-            widget::button_image(exit_button, 266.0, 105.0, quit_to_title),
-        ],
+                Children::spawn(SpawnWith(move |parent: &mut ChildSpawner| {
+                    spawn_gameover_buttons(
+                        parent,
+                        game_over_title,
+                        can_continue,
+                        [play_button, settings_button, exit_button],
+                    );
+                })),
+            ));
+        })),
+    ));
+}
+
+fn spawn_gameover_buttons(
+    parent: &mut ChildSpawner,
+    game_over_title: Handle<Image>,
+    can_continue: bool,
+    [play_button, settings_button, exit_button]: [Handle<Image>; 3],
+) {
+    parent.spawn((
+        Name::new("Game Over Title"),
+        ImageNode::new(game_over_title),
+        Node {
+            width: Val::Px(500.0),
+            height: Val::Px(200.0),
+            margin: UiRect::bottom(Val::Px(20.0)),
+            ..default()
+        },
+    ));
+    // Once per game: trim the board and keep going at half score
+    if can_continue {
+        parent.spawn(widget::button("Continue?", continue_run));
+    }
+    parent.spawn(widget::button_image(
+        play_button,
+        266.0,
+        105.0,
+        restart_game,
+    ));
+    parent.spawn(widget::button_image(
+        settings_button,
+        266.0,
+        105.0,
+        open_settings_menu,
     ));
+    parent.spawn(widget::button_image(
+        exit_button,
+        266.0,
+        105.0,
+        quit_to_title,
+    ));
+}
+
+fn continue_run(_: On<Pointer<Click>>, mut continue_events: MessageWriter<ContinueRun>) {
+    continue_events.write(ContinueRun);
+}
+
+/// Leave the run for the replay viewer, which plays its final shots.
+fn watch_final_shots(
+    _: On<Pointer<Click>>,
+    mut final_shots: ResMut<FinalShots>,
+    mut watch: MessageWriter<WatchReplay>,
+    mut next_screen: ResMut<NextState<Screen>>,
+    mut next_menu: ResMut<NextState<Menu>>,
+) {
+    final_shots.request();
+    watch.write(WatchReplay);
+    // The viewer is opened from the title screen, once the run is left
+    next_menu.set(Menu::None);
+    next_screen.set(Screen::Title);
 }
 
 fn open_settings_menu(_: On<Pointer<Click>>, mut next_menu: ResMut<NextState<Menu>>) {
@@ -64,10 +172,8 @@ fn restart_game(
     _: On<Pointer<Click>>,
     mut next_screen: ResMut<NextState<Screen>>,
     mut next_menu: ResMut<NextState<Menu>>,
-    mut next_pause: ResMut<NextState<Pause>>,
 ) {
     // Go through Loading screen to properly restart (triggers all OnExit/OnEnter systems)
     next_menu.set(Menu::None);
-    next_pause.set(Pause(false));
     next_screen.set(Screen::Loading);
 }
@@ -0,0 +1,335 @@
+//! The stats screen, opened from the main menu's Scores button.
+//!
+//! Three tabs:
+//! - Scores: the top 10 leaderboard. Entries that fail their signature check
+//!   (edited by hand, or saved before scores were signed) are marked as
+//!   unverified.
+//! - Weekly: this week's challenge, its top 10, and when it resets.
+//! - History: the most recent completed runs, each with a "Retry seed"
+//!   button that starts a new run on the same starting board. Flagged when
+//!   the profile failed its signature check.
+
+use bevy::{ecs::spawn::SpawnWith, input::common_conditions::input_just_pressed, prelude::*};
+
+use crate::{
+    game::{
+        highscore::HighScores,
+        history::RetrySeed,
+        mode::GameMode,
+        weekly::{WeeklyChallenge, WeeklyScores, reset_countdown},
+    },
+    menus::{Menu, settings::spawn_text_button},
+    profile::{Profile, RecentRun},
+    screens::Screen,
+    theme::{
+        GameFont,
+        palette::{HEADER_TEXT, LABEL_TEXT},
+        widget,
+    },
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<StatsTab>();
+    app.add_systems(OnEnter(Menu::HighScores), open_scores_tab);
+    app.add_systems(
+        Update,
+        (
+            spawn_high_scores_menu.run_if(resource_changed::<StatsTab>),
+            go_back.run_if(input_just_pressed(KeyCode::Escape)),
+        )
+            .run_if(in_state(Menu::HighScores)),
+    );
+}
+
+/// Text color for unverified entries.
+const UNVERIFIED_TEXT: Color = Color::srgb(0.6, 0.35, 0.3);
+
+/// Runs shown per page of the history tab.
+const HISTORY_PAGE_SIZE: usize = 8;
+
+/// Which tab of the stats screen is showing.
+#[derive(Resource, Debug, Clone, Copy, Default, PartialEq, Eq)]
+enum StatsTab {
+    #[default]
+    Scores,
+    Weekly,
+    /// Page 0 is the newest runs.
+    History {
+        page: usize,
+    },
+}
+
+/// Root of the stats screen, respawned when the tab changes.
+#[derive(Component)]
+struct StatsMenu;
+
+fn open_scores_tab(mut tab: ResMut<StatsTab>) {
+    // Always counts as a change, so the menu spawns
+    *tab = StatsTab::Scores;
+}
+
+fn spawn_high_scores_menu(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    game_font: Res<GameFont>,
+    high_scores: Res<HighScores>,
+    weekly: Res<WeeklyScores>,
+    profile: Res<Profile>,
+    tab: Res<StatsTab>,
+    existing: Query<Entity, With<StatsMenu>>,
+) {
+    for entity in &existing {
+        commands.entity(entity).despawn();
+    }
+
+    let back_button = asset_server.load("images/back_button.png");
+    let font = game_font.0.clone();
+    let tab = *tab;
+    let scores = score_lines(&high_scores);
+    let challenge = WeeklyChallenge::current();
+    // Scores from a week that's since ended don't count
+    let weekly_scores = if weekly.week == challenge.week {
+        score_lines(&weekly.scores)
+    } else {
+        Vec::new()
+    };
+    let runs = profile.recent_runs.clone();
+    let profile_verified = !profile.unverified;
+
+    commands.spawn((
+        Name::new("High Scores Menu"),
+        StatsMenu,
+        Node {
+            position_type: PositionType::Absolute,
+            width: Val::Percent(100.0),
+            height: Val::Percent(100.0),
+            align_items: AlignItems::Center,
+            justify_content: JustifyContent::Center,
+            flex_direction: FlexDirection::Column,
+            row_gap: Val::Px(6.0),
+            ..default()
+        },
+        BackgroundColor(Color::srgb(0.96, 0.92, 0.84)),
+        GlobalZIndex(2),
+        DespawnOnExit(Menu::HighScores),
+        Children::spawn(SpawnWith(move |parent: &mut ChildSpawner| {
+            parent.spawn((
+                Name::new("High Scores Header"),
+                Text::new(match tab {
+                    StatsTab::Scores => "Top Scores",
+                    StatsTab::Weekly => "Weekly Challenge",
+                    StatsTab::History { .. } => "Recent Games",
+                }),
+                TextFont {
+                    font: font.clone(),
+                    font_size: 40.0,
+                    ..default()
+                },
+                TextColor(HEADER_TEXT),
+            ));
+
+            parent
+                .spawn((
+                    Name::new("Stats Tabs"),
+                    Node {
+                        column_gap: Val::Px(10.0),
+                        margin: UiRect::bottom(Val::Px(10.0)),
+                        ..default()
+                    },
+                ))
+                .with_children(|row| {
+                    spawn_text_button(row, font.clone(), "Scores", 110.0, ())
+                        .observe(show_tab(StatsTab::Scores));
+                    spawn_text_button(row, font.clone(), "Weekly", 110.0, ())
+                        .observe(show_tab(StatsTab::Weekly));
+                    spawn_text_button(row, font.clone(), "History", 110.0, ())
+                        .observe(show_tab(StatsTab::History { page: 0 }));
+
+                    let StatsTab::History { page } = tab else {
+                        return;
+                    };
+                    if page > 0 {
+                        spawn_text_button(row, font.clone(), "Newer", 90.0, ())
+                            .observe(show_tab(StatsTab::History { page: page - 1 }));
+                    }
+                    if (page + 1) * HISTORY_PAGE_SIZE < runs.len() {
+                        spawn_text_button(row, font.clone(), "Older", 90.0, ())
+                            .observe(show_tab(StatsTab::History { page: page + 1 }));
+                    }
+                });
+
+            match tab {
+                StatsTab::Scores => spawn_scores_tab(parent, &font, scores),
+                StatsTab::Weekly => {
+                    parent.spawn(list_text(
+                        "Weekly Challenge",
+                        challenge.summary(),
+                        &font,
+                        HEADER_TEXT,
+                    ));
+                    parent.spawn(list_text(
+                        "Weekly Reset",
+                        format!("Resets in {}", reset_countdown()),
+                        &font,
+                        LABEL_TEXT,
+                    ));
+                    spawn_scores_tab(parent, &font, weekly_scores);
+                }
+                StatsTab::History { page } => {
+                    let page_runs = runs.chunks(HISTORY_PAGE_SIZE).nth(page).unwrap_or_default();
+                    spawn_history_tab(parent, &font, page_runs, profile_verified);
+                }
+            }
+
+            parent.spawn(widget::button_image(
+                back_button,
+                266.0,
+                105.0,
+                go_back_on_click,
+            ));
+        })),
+    ));
+}
+
+/// Leaderboard lines, and whether each entry is verified.
+fn score_lines(high_scores: &HighScores) -> Vec<(String, bool)> {
+    high_scores
+        .entries
+        .iter()
+        .enumerate()
+        .map(|(index, entry)| {
+            let mut line = format!(
+                "{}. {}  ({} popped)",
+                index + 1,
+                entry.score,
+                entry.bubbles_popped
+            );
+            if entry.abandoned {
+                line.push_str("  abandoned");
+            }
+            let verified = entry.is_verified(index);
+            if !verified {
+                line.push_str("  unverified");
+            }
+            (line, verified)
+        })
+        .collect()
+}
+
+fn spawn_scores_tab(parent: &mut ChildSpawner, font: &Handle<Font>, lines: Vec<(String, bool)>) {
+    if lines.is_empty() {
+        parent.spawn(list_text("No Scores", "No scores yet", font, LABEL_TEXT));
+    }
+    for (line, verified) in lines {
+        let color = if verified {
+            LABEL_TEXT
+        } else {
+            UNVERIFIED_TEXT
+        };
+        parent.spawn(list_text("Score Entry", line, font, color));
+    }
+}
+
+fn spawn_history_tab(
+    parent: &mut ChildSpawner,
+    font: &Handle<Font>,
+    runs: &[RecentRun],
+    verified: bool,
+) {
+    if !verified {
+        parent.spawn(list_text(
+            "Unverified Profile",
+            "Profile unverified",
+            font,
+            UNVERIFIED_TEXT,
+        ));
+    }
+    if runs.is_empty() {
+        parent.spawn(list_text("No History", "No games yet", font, LABEL_TEXT));
+    }
+    for run in runs {
+        parent
+            .spawn((
+                Name::new("History Entry"),
+                Node {
+                    align_items: AlignItems::Center,
+                    column_gap: Val::Px(12.0),
+                    ..default()
+                },
+            ))
+            .with_children(|row| {
+                row.spawn(list_text(
+                    "History Line",
+                    history_line(run),
+                    font,
+                    LABEL_TEXT,
+                ));
+                if let Some(seed) = run.seed {
+                    spawn_text_button(row, font.clone(), "Retry seed", 120.0, ())
+                        .observe(retry_seed(run.mode, seed));
+                }
+            });
+    }
+}
+
+/// One run as a line of text, e.g. "Classic  1200 pts  L4  3:05  Won".
+fn history_line(run: &RecentRun) -> String {
+    let secs = run.duration_secs.max(0.0) as u32;
+    let mut mode = run.mode.name().to_string();
+    mode[..1].make_ascii_uppercase();
+    format!(
+        "{}  {} pts  L{}  {}:{:02}  {}",
+        mode,
+        run.score,
+        run.level,
+        secs / 60,
+        secs % 60,
+        run.outcome.label()
+    )
+}
+
+fn list_text(
+    name: &'static str,
+    text: impl Into<String>,
+    font: &Handle<Font>,
+    color: Color,
+) -> impl Bundle {
+    (
+        Name::new(name),
+        Text::new(text),
+        TextFont {
+            font: font.clone(),
+            font_size: 18.0,
+            ..default()
+        },
+        TextColor(color),
+    )
+}
+
+fn show_tab(tab: StatsTab) -> impl Fn(On<Pointer<Click>>, ResMut<StatsTab>) {
+    move |_, mut current| {
+        current.set_if_neq(tab);
+    }
+}
+
+/// Start a new run in the entry's mode on the entry's starting board. Goes
+/// through the loading screen, which brings back the run's sounds.
+fn retry_seed(
+    mode: GameMode,
+    seed: u64,
+) -> impl Fn(On<Pointer<Click>>, ResMut<GameMode>, ResMut<RetrySeed>, ResMut<NextState<Screen>>) {
+    move |_, mut current_mode, mut retry, mut next_screen| {
+        info!("Retrying {} seed {}", mode.name(), seed);
+        *current_mode = mode;
+        retry.0 = Some(seed);
+        next_screen.set(Screen::Loading);
+    }
+}
+
+fn go_back_on_click(_: On<Pointer<Click>>, mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::Main);
+}
+
+fn go_back(mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::Main);
+}
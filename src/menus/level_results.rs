@@ -0,0 +1,183 @@
+//! The results screen after winning a campaign level or puzzle drill.
+//!
+//! Shows the level's [`Grade`]: the shots, time, and best chain against par,
+//! and the rank stamped on top. Continuing moves on to the next campaign
+//! level, the win screen after the last one, or back to the drills menu.
+
+use bevy::{
+    ecs::{spawn::SpawnWith, system::SystemParam},
+    input::common_conditions::input_just_pressed,
+    prelude::*,
+};
+
+use crate::{
+    game::{
+        campaign::{ActiveLevel, CampaignLevels},
+        drills::ActiveDrill,
+        grading::{Grade, LevelGrade, SECS_PER_PAR_SHOT, grade_level},
+    },
+    menus::Menu,
+    screens::Screen,
+    theme::{
+        GameFont,
+        palette::{HEADER_TEXT, LABEL_TEXT},
+        widget,
+    },
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(
+        OnEnter(Menu::LevelResults),
+        spawn_level_results.after(grade_level),
+    );
+    app.add_systems(
+        Update,
+        (
+            stamp_rank,
+            continue_on_key
+                .run_if(input_just_pressed(KeyCode::Enter).or(input_just_pressed(KeyCode::Space))),
+        )
+            .run_if(in_state(Menu::LevelResults)),
+    );
+}
+
+/// How long the stamp takes to land, in seconds.
+const STAMP_SECS: f32 = 0.3;
+
+/// How big the stamp starts before it lands.
+const STAMP_START_SCALE: f32 = 3.0;
+
+/// The stamp's tilt once it's landed, in radians.
+const STAMP_TILT: f32 = -0.2;
+
+/// The rank letter, slamming down onto the screen.
+#[derive(Component, Debug, Default)]
+struct RankStamp {
+    elapsed: f32,
+}
+
+fn spawn_level_results(mut commands: Commands, game_font: Res<GameFont>, grade: Res<LevelGrade>) {
+    let font = game_font.0.clone();
+    let Some(grade) = grade.0.clone() else {
+        warn!("Results screen opened without a graded level");
+        return;
+    };
+    let lines = stat_lines(&grade);
+
+    commands.spawn((
+        Name::new("Level Results"),
+        Node {
+            position_type: PositionType::Absolute,
+            width: Val::Percent(100.0),
+            height: Val::Percent(100.0),
+            align_items: AlignItems::Center,
+            justify_content: JustifyContent::Center,
+            flex_direction: FlexDirection::Column,
+            row_gap: Val::Px(8.0),
+            ..default()
+        },
+        BackgroundColor(Color::srgb(0.96, 0.92, 0.84)),
+        GlobalZIndex(2),
+        DespawnOnExit(Menu::LevelResults),
+        Children::spawn(SpawnWith(move |parent: &mut ChildSpawner| {
+            parent.spawn((
+                Name::new("Level Results Header"),
+                Text::new(format!("{} cleared!", grade.level.name())),
+                widget::game_font(font.clone(), 40.0),
+                TextColor(HEADER_TEXT),
+            ));
+
+            for line in lines {
+                parent.spawn((
+                    Name::new("Level Results Stat"),
+                    Text::new(line),
+                    widget::game_font(font.clone(), 20.0),
+                    TextColor(LABEL_TEXT),
+                ));
+            }
+
+            parent.spawn((
+                Name::new("Rank Stamp"),
+                RankStamp::default(),
+                Text::new(grade.rank.label()),
+                widget::game_font(font.clone(), 120.0),
+                TextColor(grade.rank.color()),
+                UiTransform {
+                    scale: Vec2::splat(STAMP_START_SCALE),
+                    ..default()
+                },
+            ));
+            if grade.new_best {
+                parent.spawn((
+                    Name::new("New Best Rank"),
+                    Text::new("New best!"),
+                    widget::game_font(font.clone(), 20.0),
+                    TextColor(grade.rank.color()),
+                ));
+            }
+
+            parent.spawn(widget::button("Continue", continue_on_click));
+        })),
+    ));
+}
+
+/// The stats the rank was worked out from, each against par.
+fn stat_lines(grade: &Grade) -> [String; 3] {
+    let par_secs = grade.par.max(1) as f32 * SECS_PER_PAR_SHOT;
+    [
+        format!("Shots: {} (par {})", grade.stats.shots, grade.par),
+        format!("Time: {:.1}s (par {par_secs:.0}s)", grade.stats.secs),
+        format!("Best chain: {}", grade.stats.best_chain),
+    ]
+}
+
+/// Shrink the stamp down onto the screen, tilting as it lands.
+fn stamp_rank(time: Res<Time<Real>>, mut stamps: Query<(&mut UiTransform, &mut RankStamp)>) {
+    for (mut transform, mut stamp) in &mut stamps {
+        stamp.elapsed += time.delta_secs();
+        let t = (stamp.elapsed / STAMP_SECS).min(1.0);
+        // Ease in, so it speeds up into the landing
+        let eased = t * t;
+        transform.scale = Vec2::splat(STAMP_START_SCALE + (1.0 - STAMP_START_SCALE) * eased);
+        transform.rotation = Rot2::radians(STAMP_TILT * eased);
+    }
+}
+
+fn continue_on_click(_: On<Pointer<Click>>, mut next: NextAfterResults) {
+    next.go();
+}
+
+fn continue_on_key(mut next: NextAfterResults) {
+    next.go();
+}
+
+/// Where the results screen leads.
+#[derive(SystemParam)]
+struct NextAfterResults<'w> {
+    drill: Res<'w, ActiveDrill>,
+    levels: Res<'w, CampaignLevels>,
+    active: ResMut<'w, ActiveLevel>,
+    next_screen: ResMut<'w, NextState<Screen>>,
+    next_menu: ResMut<'w, NextState<Menu>>,
+}
+
+impl NextAfterResults<'_> {
+    /// On to the next campaign level, the win screen, or the drills menu.
+    fn go(&mut self) {
+        if self.drill.0.is_some() {
+            self.next_menu.set(Menu::Drills);
+            return;
+        }
+        match self.active.0 {
+            Some(index) if self.levels.has_next(index) => {
+                self.active.0 = Some(index + 1);
+                // Always through Loading, so the level is applied before the
+                // board is spawned
+                self.next_menu.set(Menu::None);
+                self.next_screen.set(Screen::Loading);
+            }
+            // Show win screen (using credits menu as placeholder)
+            _ => self.next_menu.set(Menu::Credits),
+        }
+    }
+}
@@ -3,7 +3,14 @@
 use bevy::prelude::*;
 
 use crate::{
-    asset_tracking::ResourceHandles, audio::sound_effect, menus::Menu, screens::Screen,
+    audio::sound_effect,
+    game::{
+        autosave::{Autosave, PendingResume},
+        mode::GameMode,
+        replay::{LastReplay, WatchReplay},
+    },
+    menus::Menu,
+    screens::Screen,
     theme::widget,
 };
 
@@ -11,7 +18,12 @@ pub(super) fn plugin(app: &mut App) {
     app.add_systems(OnEnter(Menu::Main), spawn_main_menu);
 }
 
-fn spawn_main_menu(mut commands: Commands, asset_server: Res<AssetServer>) {
+fn spawn_main_menu(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    autosave: Res<Autosave>,
+    last_replay: Res<LastReplay>,
+) {
     // Play the snord sound on menu enter
     let snord_sound = asset_server.load("audio/sound_effects/snord.ogg");
     commands.spawn(sound_effect(snord_sound));
@@ -49,12 +61,7 @@ fn spawn_main_menu(mut commands: Commands, asset_server: Res<AssetServer>) {
                     ..default()
                 },
             ),
-            widget::button_image(
-                play_button.clone(),
-                266.0,
-                105.0,
-                enter_loading_or_gameplay_screen
-            ),
+            widget::button_image(play_button.clone(), 266.0, 105.0, open_mode_select_menu),
             widget::button_image(settings_button.clone(), 266.0, 105.0, open_settings_menu),
             widget::button_image(credits_button.clone(), 266.0, 105.0, open_credits_menu),
             widget::button_image(exit_button.clone(), 266.0, 105.0, exit_app),
@@ -71,29 +78,158 @@ fn spawn_main_menu(mut commands: Commands, asset_server: Res<AssetServer>) {
                     ..default()
                 },
             ),
-            widget::button_image(play_button, 266.0, 105.0, enter_loading_or_gameplay_screen),
+            widget::button_image(play_button, 266.0, 105.0, open_mode_select_menu),
             widget::button_image(settings_button, 266.0, 105.0, open_settings_menu),
             widget::button_image(credits_button, 266.0, 105.0, open_credits_menu),
         ],
     ));
+
+    // The column is full, so drills and scores get corners of their own
+    commands.spawn((
+        Name::new("Drills Button"),
+        Node {
+            position_type: PositionType::Absolute,
+            left: Val::Px(20.0),
+            top: Val::Px(20.0),
+            ..default()
+        },
+        GlobalZIndex(2),
+        DespawnOnExit(Menu::Main),
+        children![widget::button_medium("Drills", open_drills_menu)],
+    ));
+    commands.spawn((
+        Name::new("Scores Button"),
+        Node {
+            position_type: PositionType::Absolute,
+            left: Val::Px(20.0),
+            top: Val::Px(90.0),
+            ..default()
+        },
+        GlobalZIndex(2),
+        DespawnOnExit(Menu::Main),
+        children![widget::button_medium("Scores", open_high_scores_menu)],
+    ));
+    // Levels are saved to files, which the web build can't write
+    #[cfg(not(target_family = "wasm"))]
+    commands.spawn((
+        Name::new("Editor Button"),
+        Node {
+            position_type: PositionType::Absolute,
+            left: Val::Px(20.0),
+            top: Val::Px(160.0),
+            ..default()
+        },
+        GlobalZIndex(2),
+        DespawnOnExit(Menu::Main),
+        children![widget::button_medium("Editor", open_editor)],
+    ));
+    // Mods are read from a folder next to the game
+    #[cfg(not(target_family = "wasm"))]
+    commands.spawn((
+        Name::new("Mods Button"),
+        Node {
+            position_type: PositionType::Absolute,
+            left: Val::Px(20.0),
+            top: Val::Px(230.0),
+            ..default()
+        },
+        GlobalZIndex(2),
+        DespawnOnExit(Menu::Main),
+        children![widget::button_medium("Mods", open_mods_menu)],
+    ));
+    commands.spawn((
+        Name::new("What's New Button"),
+        Node {
+            position_type: PositionType::Absolute,
+            right: Val::Px(20.0),
+            top: Val::Px(20.0),
+            ..default()
+        },
+        GlobalZIndex(2),
+        DespawnOnExit(Menu::Main),
+        children![widget::button_medium("What's New", open_whats_new_menu)],
+    ));
+    // Left behind by a game that closed mid-run
+    if autosave.run.is_some() {
+        commands.spawn((
+            Name::new("Resume Button"),
+            Node {
+                position_type: PositionType::Absolute,
+                right: Val::Px(20.0),
+                bottom: Val::Px(20.0),
+                ..default()
+            },
+            GlobalZIndex(2),
+            DespawnOnExit(Menu::Main),
+            children![widget::button_medium("Resume Run", resume_run)],
+        ));
+    }
+    if last_replay.log.is_some() {
+        commands.spawn((
+            Name::new("Replay Button"),
+            Node {
+                position_type: PositionType::Absolute,
+                left: Val::Px(20.0),
+                bottom: Val::Px(20.0),
+                ..default()
+            },
+            GlobalZIndex(2),
+            DespawnOnExit(Menu::Main),
+            children![widget::button_medium("Watch Replay", watch_replay)],
+        ));
+    }
+}
+
+fn watch_replay(_: On<Pointer<Click>>, mut watch: MessageWriter<WatchReplay>) {
+    watch.write(WatchReplay);
 }
 
-fn enter_loading_or_gameplay_screen(
+fn resume_run(
     _: On<Pointer<Click>>,
-    resource_handles: Res<ResourceHandles>,
+    autosave: Res<Autosave>,
+    mut pending: ResMut<PendingResume>,
+    mut mode: ResMut<GameMode>,
     mut next_screen: ResMut<NextState<Screen>>,
 ) {
-    if resource_handles.is_all_done() {
-        next_screen.set(Screen::Gameplay);
-    } else {
-        next_screen.set(Screen::Loading);
-    }
+    let Some(run) = &autosave.run else {
+        return;
+    };
+    pending.resume(run, &mut mode);
+    // Through Loading like any other run, so the mode's layout is applied
+    // before the board is spawned
+    next_screen.set(Screen::Loading);
+}
+
+fn open_mode_select_menu(_: On<Pointer<Click>>, mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::ModeSelect);
 }
 
 fn open_settings_menu(_: On<Pointer<Click>>, mut next_menu: ResMut<NextState<Menu>>) {
     next_menu.set(Menu::Settings);
 }
 
+fn open_drills_menu(_: On<Pointer<Click>>, mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::Drills);
+}
+
+fn open_high_scores_menu(_: On<Pointer<Click>>, mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::HighScores);
+}
+
+#[cfg(not(target_family = "wasm"))]
+fn open_editor(_: On<Pointer<Click>>, mut next_screen: ResMut<NextState<Screen>>) {
+    next_screen.set(Screen::Editor);
+}
+
+#[cfg(not(target_family = "wasm"))]
+fn open_mods_menu(_: On<Pointer<Click>>, mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::Mods);
+}
+
+fn open_whats_new_menu(_: On<Pointer<Click>>, mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::WhatsNew);
+}
+
 fn open_credits_menu(_: On<Pointer<Click>>, mut next_menu: ResMut<NextState<Menu>>) {
     next_menu.set(Menu::Credits);
 }
@@ -1,11 +1,24 @@
 //! The game's menus and transitions between them.
 
+mod accessibility;
+mod companions;
+mod controls;
 mod credits;
+mod drills;
+mod feedback;
 mod gameover;
+mod highscores;
+mod level_results;
 mod main;
+mod mode_select;
+mod mods;
 mod pause;
 mod powerup_select;
+mod quit;
 mod settings;
+mod shot_clock;
+mod telemetry;
+mod whats_new;
 
 use bevy::prelude::*;
 
@@ -13,12 +26,21 @@ pub(super) fn plugin(app: &mut App) {
     app.init_state::<Menu>();
 
     app.add_plugins((
+        accessibility::plugin,
+        (companions::plugin, controls::plugin),
         credits::plugin,
-        gameover::plugin,
+        drills::plugin,
+        (feedback::plugin, gameover::plugin),
+        highscores::plugin,
+        level_results::plugin,
         main::plugin,
+        mode_select::plugin,
+        mods::plugin,
         pause::plugin,
         powerup_select::plugin,
-        settings::plugin,
+        quit::plugin,
+        (settings::plugin, shot_clock::plugin),
+        (telemetry::plugin, whats_new::plugin),
     ));
 }
 
@@ -27,9 +49,31 @@ pub enum Menu {
     #[default]
     None,
     Main,
+    ModeSelect,
+    /// Picking a companion for the next runs, from the mode select screen.
+    Companions,
     Credits,
     Settings,
+    /// Rebinding keys, from the settings menu.
+    Controls,
+    /// Each mode's shot clock, from the settings menu.
+    ShotClock,
     Pause,
     GameOver,
     PowerUpSelect,
+    /// The grade for a won campaign level or drill.
+    LevelResults,
+    Telemetry,
+    /// The feedback form, from the settings menu.
+    Feedback,
+    Drills,
+    HighScores,
+    /// The list of loaded asset packs.
+    Mods,
+    /// The one-time accessibility prompt shown on first launch.
+    Accessibility,
+    /// Asks before quitting when the window is closed mid-run.
+    QuitPrompt,
+    /// The changelog, shown once after an update and from the main menu.
+    WhatsNew,
 }
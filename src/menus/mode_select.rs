@@ -0,0 +1,274 @@
+//! The mode select screen, opened from the main menu's Play button.
+//!
+//! Lists every mode with a short description and the best score from the
+//! [`Profile`]; Weekly shows this week's challenge and when it resets
+//! instead. Modes the profile hasn't unlocked are greyed out. Picking a
+//! mode sets [`SelectedMode`], which gameplay setup turns into the rules for
+//! the run; Puzzle opens the drills menu to pick a board first. The
+//! companion button under the list opens the companions menu, and the one
+//! beside it cycles the starting [`Handicap`].
+
+use bevy::{ecs::spawn::SpawnWith, input::common_conditions::input_just_pressed, prelude::*};
+
+use crate::{
+    game::{
+        campaign::CampaignLevels,
+        handicap::Handicap,
+        mode::SelectedMode,
+        weekly::{WeeklyChallenge, reset_countdown},
+    },
+    menus::{Menu, settings::spawn_text_button},
+    profile::Profile,
+    screens::Screen,
+    theme::{
+        GameFont,
+        interaction::InteractionPalette,
+        palette::{HEADER_TEXT, LABEL_TEXT},
+        widget,
+    },
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(OnEnter(Menu::ModeSelect), spawn_mode_select_menu);
+    app.add_systems(
+        Update,
+        go_back.run_if(in_state(Menu::ModeSelect).and(input_just_pressed(KeyCode::Escape))),
+    );
+    app.add_systems(
+        Update,
+        update_handicap_label.run_if(in_state(Menu::ModeSelect).and(resource_changed::<Handicap>)),
+    );
+}
+
+/// Background of a locked mode's button.
+const LOCKED_BUTTON: Color = Color::srgb(0.62, 0.62, 0.62);
+
+/// Text color for locked modes.
+const LOCKED_TEXT: Color = Color::srgb(0.45, 0.45, 0.45);
+
+/// What one row of the screen shows.
+struct ModeRow {
+    mode: SelectedMode,
+    unlocked: bool,
+    description: String,
+    best: String,
+}
+
+fn spawn_mode_select_menu(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    game_font: Res<GameFont>,
+    profile: Res<Profile>,
+    levels: Res<CampaignLevels>,
+    handicap: Res<Handicap>,
+) {
+    let back_button = asset_server.load("images/back_button.png");
+    let font = game_font.0.clone();
+    let rows: Vec<ModeRow> = SelectedMode::ALL
+        .into_iter()
+        .map(|mode| ModeRow {
+            mode,
+            unlocked: profile.is_unlocked(mode),
+            description: match mode {
+                SelectedMode::Weekly => WeeklyChallenge::current().summary(),
+                _ => mode.description().to_string(),
+            },
+            best: best_label(&profile, &levels, mode),
+        })
+        .collect();
+    let handicap_label = start_label(*handicap);
+    let companion_label = format!(
+        "Companion: {}",
+        profile
+            .companion
+            .map_or("Nobody", |companion| companion.label())
+    );
+
+    commands.spawn((
+        Name::new("Mode Select Menu"),
+        Node {
+            position_type: PositionType::Absolute,
+            width: Val::Percent(100.0),
+            height: Val::Percent(100.0),
+            align_items: AlignItems::Center,
+            justify_content: JustifyContent::Center,
+            flex_direction: FlexDirection::Column,
+            row_gap: Val::Px(6.0),
+            ..default()
+        },
+        BackgroundColor(Color::srgb(0.96, 0.92, 0.84)),
+        GlobalZIndex(2),
+        DespawnOnExit(Menu::ModeSelect),
+        Children::spawn(SpawnWith(move |parent: &mut ChildSpawner| {
+            parent.spawn((
+                Name::new("Mode Select Header"),
+                Text::new("Choose a Mode"),
+                TextFont {
+                    font: font.clone(),
+                    font_size: 40.0,
+                    ..default()
+                },
+                TextColor(HEADER_TEXT),
+                Node {
+                    margin: UiRect::bottom(Val::Px(10.0)),
+                    ..default()
+                },
+            ));
+
+            for row in rows {
+                spawn_mode_row(parent, &font, row);
+            }
+
+            parent
+                .spawn(Node {
+                    margin: UiRect::top(Val::Px(10.0)),
+                    column_gap: Val::Px(12.0),
+                    ..default()
+                })
+                .with_children(|line| {
+                    spawn_text_button(line, font.clone(), &companion_label, 240.0, ())
+                        .observe(open_companions);
+                    spawn_text_button(line, font.clone(), &handicap_label, 240.0, HandicapLabel)
+                        .observe(cycle_handicap);
+                });
+
+            parent.spawn(widget::button_image(
+                back_button,
+                266.0,
+                105.0,
+                go_back_on_click,
+            ));
+        })),
+    ));
+}
+
+fn spawn_mode_row(parent: &mut ChildSpawner, font: &Handle<Font>, row: ModeRow) {
+    let text_color = if row.unlocked {
+        LABEL_TEXT
+    } else {
+        LOCKED_TEXT
+    };
+    parent
+        .spawn((
+            Name::new("Mode Row"),
+            Node {
+                width: Val::Px(680.0),
+                align_items: AlignItems::Center,
+                column_gap: Val::Px(12.0),
+                ..default()
+            },
+        ))
+        .with_children(|line| {
+            let mut button = spawn_text_button(line, font.clone(), row.mode.label(), 140.0, ());
+            if row.unlocked {
+                button.observe(select_mode(row.mode));
+            } else {
+                button.insert((
+                    BackgroundColor(LOCKED_BUTTON),
+                    InteractionPalette {
+                        none: LOCKED_BUTTON,
+                        hovered: LOCKED_BUTTON,
+                        pressed: LOCKED_BUTTON,
+                    },
+                ));
+            }
+
+            line.spawn((
+                Name::new("Mode Description"),
+                Text::new(row.description),
+                TextFont {
+                    font: font.clone(),
+                    font_size: 16.0,
+                    ..default()
+                },
+                TextColor(text_color),
+                Node {
+                    flex_grow: 1.0,
+                    ..default()
+                },
+            ));
+            line.spawn((
+                Name::new("Mode Best"),
+                Text::new(row.best),
+                TextFont {
+                    font: font.clone(),
+                    font_size: 16.0,
+                    ..default()
+                },
+                TextColor(text_color),
+            ));
+        });
+}
+
+/// Marker for the handicap button's text.
+#[derive(Component)]
+struct HandicapLabel;
+
+fn start_label(handicap: Handicap) -> String {
+    format!("Start: {}", handicap.label())
+}
+
+fn cycle_handicap(_: On<Pointer<Click>>, mut handicap: ResMut<Handicap>) {
+    *handicap = handicap.next();
+}
+
+fn update_handicap_label(
+    handicap: Res<Handicap>,
+    mut label: Single<&mut Text, With<HandicapLabel>>,
+) {
+    label.0 = start_label(*handicap);
+}
+
+/// The right-hand column of a row: the best score, or why there isn't one.
+/// Campaign shows the level it'll start from instead.
+fn best_label(profile: &Profile, levels: &CampaignLevels, mode: SelectedMode) -> String {
+    if !profile.is_unlocked(mode) {
+        return "Locked".to_string();
+    }
+    if mode == SelectedMode::Weekly {
+        return format!("Resets in {}", reset_countdown());
+    }
+    if mode == SelectedMode::Campaign {
+        let total = levels.0.len();
+        return match levels.next_index(profile.campaign_cleared) {
+            _ if profile.campaign_cleared as usize >= total => "All cleared".to_string(),
+            Some(index) => format!("Level {} of {}", index + 1, total),
+            None => String::new(),
+        };
+    }
+    match mode.game_mode() {
+        // Drill runs aren't kept in the profile, and zen isn't scored
+        Some(game_mode) if mode != SelectedMode::Puzzle && game_mode.has_pressure() => profile
+            .best_score(game_mode)
+            .map_or("No runs yet".to_string(), |best| format!("Best {best}")),
+        _ => String::new(),
+    }
+}
+
+fn select_mode(
+    mode: SelectedMode,
+) -> impl Fn(On<Pointer<Click>>, ResMut<SelectedMode>, ResMut<NextState<Screen>>, ResMut<NextState<Menu>>)
+{
+    move |_, mut selected, mut next_screen, mut next_menu| {
+        *selected = mode;
+        if mode == SelectedMode::Puzzle {
+            next_menu.set(Menu::Drills);
+            return;
+        }
+        // Always through Loading, so the selection is applied before the
+        // board is spawned
+        next_screen.set(Screen::Loading);
+    }
+}
+
+fn open_companions(_: On<Pointer<Click>>, mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::Companions);
+}
+
+fn go_back_on_click(_: On<Pointer<Click>>, mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::Main);
+}
+
+fn go_back(mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::Main);
+}
@@ -0,0 +1,154 @@
+//! The mod list - which asset packs are loaded, and which files each one
+//! lost to a higher priority pack.
+
+use bevy::{ecs::spawn::SpawnWith, input::common_conditions::input_just_pressed, prelude::*};
+
+use crate::{
+    menus::Menu,
+    mods::{MODS_DIR, ModList},
+    theme::{GameFont, palette::HEADER_TEXT, widget},
+};
+
+pub(super) fn plugin(app: &mut App) {
+    // Only native builds discover packs
+    app.init_resource::<ModList>();
+
+    app.add_systems(OnEnter(Menu::Mods), spawn_mods_menu);
+    app.add_systems(
+        Update,
+        go_back.run_if(in_state(Menu::Mods).and(input_just_pressed(KeyCode::Escape))),
+    );
+}
+
+/// Conflicts listed per pack before the rest are counted instead.
+const MAX_CONFLICTS_SHOWN: usize = 3;
+
+/// A pack's lines on the mod list: heading, details, and lost files.
+fn pack_lines(mods: &ModList, index: usize) -> (String, Vec<String>) {
+    let pack = &mods.packs[index];
+    let heading = format!(
+        "{} - priority {}, {} files",
+        pack.name,
+        pack.priority,
+        pack.files.len()
+    );
+    let mut lines = Vec::new();
+    if !pack.description.is_empty() {
+        lines.push(pack.description.clone());
+    }
+    let conflicts = mods.conflicts(index);
+    for (file, winner) in conflicts.iter().take(MAX_CONFLICTS_SHOWN) {
+        lines.push(format!("{} is from {}", file.display(), winner));
+    }
+    if conflicts.len() > MAX_CONFLICTS_SHOWN {
+        lines.push(format!(
+            "...and {} more from other packs",
+            conflicts.len() - MAX_CONFLICTS_SHOWN
+        ));
+    }
+    (heading, lines)
+}
+
+fn spawn_mods_menu(
+    mut commands: Commands,
+    mods: Res<ModList>,
+    asset_server: Res<AssetServer>,
+    game_font: Res<GameFont>,
+) {
+    let back_button = asset_server.load("images/back_button.png");
+    let font = game_font.0.clone();
+    let packs: Vec<(String, Vec<String>)> = (0..mods.packs.len())
+        .map(|index| pack_lines(&mods, index))
+        .collect();
+
+    commands.spawn((
+        Name::new("Mods Menu"),
+        Node {
+            position_type: PositionType::Absolute,
+            width: Val::Percent(100.0),
+            height: Val::Percent(100.0),
+            align_items: AlignItems::Center,
+            justify_content: JustifyContent::Center,
+            flex_direction: FlexDirection::Column,
+            row_gap: Val::Px(6.0),
+            ..default()
+        },
+        BackgroundColor(Color::srgb(0.96, 0.92, 0.84)),
+        GlobalZIndex(2),
+        DespawnOnExit(Menu::Mods),
+        Children::spawn(SpawnWith(move |parent: &mut ChildSpawner| {
+            parent.spawn((
+                Name::new("Mods Header"),
+                Text::new("Mods"),
+                TextFont {
+                    font: font.clone(),
+                    font_size: 48.0,
+                    ..default()
+                },
+                TextColor(HEADER_TEXT),
+                Node {
+                    margin: UiRect::bottom(Val::Px(20.0)),
+                    ..default()
+                },
+            ));
+
+            if packs.is_empty() {
+                parent.spawn((
+                    Text::new(format!(
+                        "No mods loaded.\nAdd asset packs to the {MODS_DIR}/ folder\nnext to assets/ and restart the game."
+                    )),
+                    TextFont {
+                        font: font.clone(),
+                        font_size: 20.0,
+                        ..default()
+                    },
+                    TextLayout::new_with_justify(Justify::Center),
+                    TextColor(Color::srgb(0.3, 0.3, 0.3)),
+                ));
+            }
+            for (heading, lines) in packs {
+                parent.spawn((
+                    Text::new(heading),
+                    TextFont {
+                        font: font.clone(),
+                        font_size: 24.0,
+                        ..default()
+                    },
+                    TextColor(HEADER_TEXT),
+                ));
+                for line in lines {
+                    parent.spawn((
+                        Text::new(line),
+                        TextFont {
+                            font: font.clone(),
+                            font_size: 16.0,
+                            ..default()
+                        },
+                        TextColor(Color::srgb(0.3, 0.3, 0.3)),
+                    ));
+                }
+            }
+
+            parent.spawn((
+                Node {
+                    margin: UiRect::top(Val::Px(20.0)),
+                    ..default()
+                },
+                children![widget::button_image(
+                    back_button,
+                    266.0,
+                    105.0,
+                    go_back_on_click,
+                )],
+            ));
+        })),
+    ));
+}
+
+fn go_back_on_click(_: On<Pointer<Click>>, mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::Main);
+}
+
+fn go_back(mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::Main);
+}
@@ -2,7 +2,14 @@
 
 use bevy::{ecs::spawn::SpawnWith, input::common_conditions::input_just_pressed, prelude::*};
 
-use crate::{menus::Menu, screens::Screen, theme::widget};
+use crate::{
+    game::{
+        drills::ActiveDrill, mode::GameMode, rng::GameRng, state::GameEnded, telemetry::RunOutcome,
+    },
+    menus::Menu,
+    screens::Screen,
+    theme::widget,
+};
 
 pub(super) fn plugin(app: &mut App) {
     app.add_systems(OnEnter(Menu::Pause), spawn_pause_menu);
@@ -12,7 +19,16 @@ pub(super) fn plugin(app: &mut App) {
     );
 }
 
-fn spawn_pause_menu(mut commands: Commands, asset_server: Res<AssetServer>) {
+fn spawn_pause_menu(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mode: Res<GameMode>,
+    active_drill: Res<ActiveDrill>,
+    rng: Res<GameRng>,
+) {
+    // Only scored runs have anything to save
+    let can_abandon = mode.has_pressure() && active_drill.0.is_none();
+    let seed = rng.seed();
     let paused_header = asset_server.load("images/paused.png");
     let play_button = asset_server.load("images/play_button.png");
     let settings_button = asset_server.load("images/settings_button.png");
@@ -53,12 +69,17 @@ fn spawn_pause_menu(mut commands: Commands, asset_server: Res<AssetServer>) {
                 105.0,
                 open_settings_menu,
             ));
+            if can_abandon {
+                parent.spawn(widget::button_medium("Abandon", abandon_run));
+            }
             parent.spawn(widget::button_image(
                 exit_button,
                 266.0,
                 105.0,
                 quit_to_title,
             ));
+            // For bug reports and sharing a run
+            parent.spawn(widget::label(format!("Seed {seed}")));
         })),
     ));
 }
@@ -75,6 +96,18 @@ fn quit_to_title(_: On<Pointer<Click>>, mut next_screen: ResMut<NextState<Screen
     next_screen.set(Screen::Title);
 }
 
+/// End the run now but keep its score.
+fn abandon_run(
+    _: On<Pointer<Click>>,
+    mut ended_events: MessageWriter<GameEnded>,
+    mut next_screen: ResMut<NextState<Screen>>,
+) {
+    ended_events.write(GameEnded {
+        outcome: RunOutcome::Abandoned,
+    });
+    next_screen.set(Screen::Title);
+}
+
 fn go_back(mut next_menu: ResMut<NextState<Menu>>) {
     next_menu.set(Menu::None);
 }
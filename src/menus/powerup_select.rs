@@ -3,8 +3,9 @@
 use bevy::{ecs::spawn::SpawnWith, prelude::*};
 
 use crate::{
-    game::powerups::{PowerUp, PowerUpChoices, UnlockedPowerUps},
+    game::powerups::{PowerUp, PowerUpCatalog, PowerUpChoices, UnlockedPowerUps},
     menus::Menu,
+    screens::RunPhase,
     theme::{GameFont, interaction::ImageInteractionPalette, palette::*},
 };
 
@@ -19,11 +20,19 @@ struct PowerUpButton(PowerUp);
 fn spawn_powerup_menu(
     mut commands: Commands,
     choices: Res<PowerUpChoices>,
+    catalog: Res<PowerUpCatalog>,
     asset_server: Res<AssetServer>,
     game_font: Res<GameFont>,
 ) {
     let level = choices.level;
-    let power_choices = choices.choices.clone();
+    let power_choices: Vec<(PowerUp, String, String)> = choices
+        .choices
+        .iter()
+        .map(|&power| {
+            let name = catalog.name(power).to_string();
+            (power, name, catalog.description(power).to_string())
+        })
+        .collect();
     let button_template = asset_server.load("images/button_template.png");
     let font = game_font.0.clone();
 
@@ -58,8 +67,15 @@ fn spawn_powerup_menu(
             ));
 
             // Spawn buttons for each power-up choice
-            for power in &power_choices {
-                spawn_powerup_button(parent, *power, button_template.clone(), font.clone());
+            for (power, name, description) in power_choices {
+                spawn_powerup_button(
+                    parent,
+                    power,
+                    &name,
+                    &description,
+                    button_template.clone(),
+                    font.clone(),
+                );
             }
         })),
     ));
@@ -68,12 +84,14 @@ fn spawn_powerup_menu(
 fn spawn_powerup_button(
     parent: &mut ChildSpawner,
     power: PowerUp,
+    name: &str,
+    description: &str,
     button_image: Handle<Image>,
     font: Handle<Font>,
 ) {
     parent
         .spawn((
-            Name::new(format!("PowerUp Button: {}", power.name())),
+            Name::new(format!("PowerUp Button: {name}")),
             Node::default(),
         ))
         .with_children(|button_parent| {
@@ -100,7 +118,7 @@ fn spawn_powerup_button(
                 .with_children(|inner| {
                     // Power-up name
                     inner.spawn((
-                        Text(power.name().to_string()),
+                        Text(name.to_string()),
                         TextFont {
                             font: font.clone(),
                             font_size: 24.0,
@@ -111,7 +129,7 @@ fn spawn_powerup_button(
                     ));
                     // Power-up description
                     inner.spawn((
-                        Text(power.description().to_string()),
+                        Text(description.to_string()),
                         TextFont {
                             font: font.clone(),
                             font_size: 14.0,
@@ -130,9 +148,11 @@ fn select_powerup(
     button_query: Query<&PowerUpButton>,
     mut unlocked: ResMut<UnlockedPowerUps>,
     mut next_menu: ResMut<NextState<Menu>>,
+    mut next_phase: ResMut<NextState<RunPhase>>,
 ) {
     if let Ok(power_button) = button_query.get(trigger.entity) {
         unlocked.add(power_button.0);
         next_menu.set(Menu::None);
+        next_phase.set(RunPhase::Playing);
     }
 }
@@ -0,0 +1,185 @@
+//! The prompt shown when the window is closed mid-run.
+//!
+//! Closing the window normally quits on the spot. During a run, the close is
+//! held back and this menu asks first: save the run to the autosave and quit,
+//! quit without saving, or go back to the game. Closing again while it's
+//! open quits straight away, so the window can always be closed.
+
+#[cfg(not(target_family = "wasm"))]
+use bevy::window::{ClosingWindow, WindowCloseRequested};
+use bevy::{ecs::spawn::SpawnWith, input::common_conditions::input_just_pressed, prelude::*};
+
+#[cfg(not(target_family = "wasm"))]
+use crate::screens::{RunPhase, Screen};
+use crate::{
+    Pause,
+    game::{
+        autosave::{Autosave, save_run},
+        campaign::ActiveLevel,
+        drills::ActiveDrill,
+        weekly::ActiveChallenge,
+    },
+    menus::Menu,
+    theme::{
+        GameFont,
+        palette::{HEADER_TEXT, LABEL_TEXT},
+        widget,
+    },
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<QuitPromptReturn>();
+
+    // The web build leaves closing to the browser
+    #[cfg(not(target_family = "wasm"))]
+    app.add_systems(Update, close_when_allowed);
+    app.add_systems(OnEnter(Menu::QuitPrompt), spawn_quit_prompt);
+    app.add_systems(
+        Update,
+        go_back.run_if(in_state(Menu::QuitPrompt).and(input_just_pressed(KeyCode::Escape))),
+    );
+}
+
+/// Where the game was when the prompt opened, to go back to on cancel.
+#[derive(Resource, Debug, Default)]
+struct QuitPromptReturn {
+    menu: Menu,
+    paused: bool,
+}
+
+/// Close windows as they ask to be, unless a run is in play.
+///
+/// Stands in for Bevy's `close_when_requested`, which is turned off in
+/// `AppPlugin`.
+#[cfg(not(target_family = "wasm"))]
+fn close_when_allowed(
+    mut commands: Commands,
+    mut close_events: MessageReader<WindowCloseRequested>,
+    closing: Query<Entity, With<ClosingWindow>>,
+    screen: Res<State<Screen>>,
+    phase: Option<Res<State<RunPhase>>>,
+    menu: Res<State<Menu>>,
+    pause: Res<State<Pause>>,
+    mut prompt_return: ResMut<QuitPromptReturn>,
+    mut next_menu: ResMut<NextState<Menu>>,
+    mut next_pause: ResMut<NextState<Pause>>,
+) {
+    // Marked on an earlier frame, as Bevy does
+    for window in &closing {
+        commands.entity(window).despawn();
+    }
+    for event in close_events.read() {
+        let run_in_play = *screen.get() == Screen::Gameplay
+            && phase
+                .as_ref()
+                .is_some_and(|phase| *phase.get() != RunPhase::GameEnding);
+        if !run_in_play || *menu.get() == Menu::QuitPrompt {
+            commands.entity(event.window).try_insert(ClosingWindow);
+            continue;
+        }
+        info!("Window closed mid-run, asking before quitting");
+        *prompt_return = QuitPromptReturn {
+            menu: *menu.get(),
+            paused: pause.get().0,
+        };
+        next_pause.set(Pause(true));
+        next_menu.set(Menu::QuitPrompt);
+    }
+}
+
+fn spawn_quit_prompt(
+    mut commands: Commands,
+    game_font: Res<GameFont>,
+    active_level: Res<ActiveLevel>,
+    active_drill: Res<ActiveDrill>,
+    challenge: Res<ActiveChallenge>,
+) {
+    let font = game_font.0.clone();
+    // The autosave doesn't carry campaign or drill goals, and challenge runs
+    // are played in one sitting
+    let can_save = active_level.0.is_none() && active_drill.0.is_none() && challenge.0.is_none();
+    let note = if can_save {
+        "Save the run to pick it up from the title screen."
+    } else {
+        "This run can't be saved, so it will be lost."
+    };
+
+    commands.spawn((
+        Name::new("Quit Prompt"),
+        Node {
+            position_type: PositionType::Absolute,
+            width: Val::Percent(100.0),
+            height: Val::Percent(100.0),
+            align_items: AlignItems::Center,
+            justify_content: JustifyContent::Center,
+            flex_direction: FlexDirection::Column,
+            row_gap: Val::Px(10.0),
+            ..default()
+        },
+        BackgroundColor(Color::srgba(0.96, 0.92, 0.84, 0.95)),
+        GlobalZIndex(2),
+        DespawnOnExit(Menu::QuitPrompt),
+        Children::spawn(SpawnWith(move |parent: &mut ChildSpawner| {
+            parent.spawn((
+                Name::new("Quit Prompt Header"),
+                Text::new("Quit mid-run?"),
+                widget::game_font(font.clone(), 48.0),
+                TextColor(HEADER_TEXT),
+            ));
+            parent.spawn((
+                Name::new("Quit Prompt Note"),
+                Text::new(note),
+                widget::game_font(font, 20.0),
+                TextColor(LABEL_TEXT),
+                Node {
+                    margin: UiRect::bottom(Val::Px(10.0)),
+                    ..default()
+                },
+            ));
+            if can_save {
+                parent.spawn(widget::button_medium("Save and Quit", save_and_quit));
+            }
+            parent.spawn(widget::button_medium(
+                "Quit Without Saving",
+                quit_without_saving,
+            ));
+            parent.spawn(widget::button_medium("Cancel", cancel));
+        })),
+    ));
+}
+
+fn save_and_quit(_: On<Pointer<Click>>, mut commands: Commands) {
+    commands.queue(|world: &mut World| {
+        save_run(world);
+        world.write_message(AppExit::Success);
+    });
+}
+
+/// Quit, and drop the last descent's autosave so it isn't offered on the
+/// title screen either.
+fn quit_without_saving(
+    _: On<Pointer<Click>>,
+    mut autosave: ResMut<Autosave>,
+    mut app_exit: MessageWriter<AppExit>,
+) {
+    autosave.clear();
+    app_exit.write(AppExit::Success);
+}
+
+fn cancel(
+    _: On<Pointer<Click>>,
+    prompt_return: Res<QuitPromptReturn>,
+    next_menu: ResMut<NextState<Menu>>,
+    next_pause: ResMut<NextState<Pause>>,
+) {
+    go_back(prompt_return, next_menu, next_pause);
+}
+
+fn go_back(
+    prompt_return: Res<QuitPromptReturn>,
+    mut next_menu: ResMut<NextState<Menu>>,
+    mut next_pause: ResMut<NextState<Pause>>,
+) {
+    next_menu.set(prompt_return.menu);
+    next_pause.set(Pause(prompt_return.paused));
+}
@@ -7,9 +7,26 @@ use bevy::{
 };
 
 use crate::{
+    accessibility::{AccessibilitySettings, ColorblindMode},
+    crash_log::{self, ReportSaved},
+    display::DisplaySettings,
+    game::{
+        breaks::BreakReminderSettings, event_feed::EventFeedSettings,
+        highscore::LeaderboardSettings, hints::HintSettings, history::RunSeed, mode::GameMode,
+        shooter::AimGuideSettings, shot_clock::ShotClockConfig, telemetry::TelemetrySettings,
+    },
     menus::Menu,
     screens::Screen,
-    theme::{GameFont, interaction::ImageInteractionPalette, palette::LABEL_TEXT, widget},
+    theme::{
+        GameFont,
+        interaction::{ImageInteractionPalette, InteractionPalette},
+        palette::{
+            BUTTON_BACKGROUND, BUTTON_HOVERED_BACKGROUND, BUTTON_PRESSED_BACKGROUND, BUTTON_TEXT,
+            LABEL_TEXT,
+        },
+        toast::ShowToast,
+        widget,
+    },
 };
 
 pub(super) fn plugin(app: &mut App) {
@@ -21,7 +38,25 @@ pub(super) fn plugin(app: &mut App) {
 
     app.add_systems(
         Update,
-        update_global_volume_label.run_if(in_state(Menu::Settings)),
+        (
+            update_global_volume_label,
+            update_shot_clock_label,
+            update_hints_label,
+            update_aim_guide_label,
+            update_event_feed_label,
+            update_telemetry_label,
+            update_abandoned_label,
+            update_breaks_label,
+            update_vsync_label,
+            update_frame_limit_label,
+            update_quality_label,
+            update_seasons_label,
+            update_power_saver_label,
+            update_reduced_motion_label,
+            update_colorblind_label,
+            update_descent_preview_label,
+        )
+            .run_if(in_state(Menu::Settings)),
     );
 }
 
@@ -58,8 +93,8 @@ fn spawn_settings_menu(
                 Name::new("Settings Title"),
                 ImageNode::new(settings_title),
                 Node {
-                    width: Val::Px(500.0),
-                    height: Val::Px(200.0),
+                    width: Val::Px(250.0),
+                    height: Val::Px(100.0),
                     margin: UiRect::bottom(Val::Px(20.0)),
                     ..default()
                 },
@@ -73,7 +108,7 @@ fn spawn_settings_menu(
                         flex_direction: FlexDirection::Row,
                         align_items: AlignItems::Center,
                         column_gap: Val::Px(15.0),
-                        margin: UiRect::bottom(Val::Px(20.0)),
+                        margin: UiRect::bottom(Val::Px(10.0)),
                         ..default()
                     },
                 ))
@@ -145,6 +180,326 @@ fn spawn_settings_menu(
                     .observe(raise_global_volume);
                 });
 
+            // Reduced motion, colorblind symbols, and the descent preview
+            // share a row
+            parent
+                .spawn((
+                    Name::new("Accessibility Row"),
+                    Node {
+                        flex_direction: FlexDirection::Row,
+                        align_items: AlignItems::Center,
+                        column_gap: Val::Px(15.0),
+                        margin: UiRect::bottom(Val::Px(10.0)),
+                        ..default()
+                    },
+                ))
+                .with_children(|row| {
+                    row.spawn((
+                        Name::new("Reduced Motion Label"),
+                        Text::new("Reduced Motion"),
+                        TextFont {
+                            font: font.clone(),
+                            font_size: 24.0,
+                            ..default()
+                        },
+                        TextColor(LABEL_TEXT),
+                    ));
+
+                    spawn_text_button(row, font.clone(), "Off", 80.0, ReducedMotionLabel)
+                        .observe(toggle_reduced_motion);
+
+                    row.spawn((
+                        Name::new("Colorblind Label"),
+                        Text::new("Colorblind"),
+                        TextFont {
+                            font: font.clone(),
+                            font_size: 24.0,
+                            ..default()
+                        },
+                        TextColor(LABEL_TEXT),
+                    ));
+
+                    spawn_text_button(row, font.clone(), "Off", 100.0, ColorblindLabel)
+                        .observe(cycle_colorblind_mode);
+
+                    row.spawn((
+                        Name::new("Descent Preview Label"),
+                        Text::new("Descent Preview"),
+                        TextFont {
+                            font: font.clone(),
+                            font_size: 24.0,
+                            ..default()
+                        },
+                        TextColor(LABEL_TEXT),
+                    ));
+
+                    spawn_text_button(row, font.clone(), "Off", 80.0, DescentPreviewLabel)
+                        .observe(toggle_descent_preview);
+                });
+
+            // Shot clock toggle, hint frequency, and the aim guide share a row
+            parent
+                .spawn((
+                    Name::new("Shot Clock Row"),
+                    Node {
+                        flex_direction: FlexDirection::Row,
+                        align_items: AlignItems::Center,
+                        column_gap: Val::Px(15.0),
+                        margin: UiRect::bottom(Val::Px(10.0)),
+                        ..default()
+                    },
+                ))
+                .with_children(|row| {
+                    row.spawn((
+                        Name::new("Shot Clock Label"),
+                        Text::new("Shot Clock"),
+                        TextFont {
+                            font: font.clone(),
+                            font_size: 24.0,
+                            ..default()
+                        },
+                        TextColor(LABEL_TEXT),
+                    ));
+
+                    spawn_text_button(row, font.clone(), "Off", 80.0, ShotClockLabel)
+                        .observe(open_shot_clock_menu);
+
+                    row.spawn((
+                        Name::new("Hints Label"),
+                        Text::new("Hints"),
+                        TextFont {
+                            font: font.clone(),
+                            font_size: 24.0,
+                            ..default()
+                        },
+                        TextColor(LABEL_TEXT),
+                    ));
+
+                    spawn_text_button(row, font.clone(), "Sometimes", 130.0, HintsLabel)
+                        .observe(cycle_hints);
+
+                    row.spawn((
+                        Name::new("Aim Guide Label"),
+                        Text::new("Aim Guide"),
+                        TextFont {
+                            font: font.clone(),
+                            font_size: 24.0,
+                            ..default()
+                        },
+                        TextColor(LABEL_TEXT),
+                    ));
+
+                    spawn_text_button(row, font.clone(), "On", 80.0, AimGuideLabel)
+                        .observe(toggle_aim_guide);
+                });
+
+            // Event feed toggle, seasonal content, and power saver share a row
+            parent
+                .spawn((
+                    Name::new("Event Feed Row"),
+                    Node {
+                        flex_direction: FlexDirection::Row,
+                        align_items: AlignItems::Center,
+                        column_gap: Val::Px(15.0),
+                        margin: UiRect::bottom(Val::Px(10.0)),
+                        ..default()
+                    },
+                ))
+                .with_children(|row| {
+                    row.spawn((
+                        Name::new("Event Feed Label"),
+                        Text::new("Event Feed"),
+                        TextFont {
+                            font: font.clone(),
+                            font_size: 24.0,
+                            ..default()
+                        },
+                        TextColor(LABEL_TEXT),
+                    ));
+
+                    spawn_text_button(row, font.clone(), "Off", 80.0, EventFeedLabel)
+                        .observe(toggle_event_feed);
+
+                    row.spawn((
+                        Name::new("Seasons Label"),
+                        Text::new("Seasons"),
+                        TextFont {
+                            font: font.clone(),
+                            font_size: 24.0,
+                            ..default()
+                        },
+                        TextColor(LABEL_TEXT),
+                    ));
+
+                    spawn_text_button(row, font.clone(), "Auto", 100.0, SeasonsLabel)
+                        .observe(cycle_seasons);
+
+                    row.spawn((
+                        Name::new("Power Saver Label"),
+                        Text::new("Power Saver"),
+                        TextFont {
+                            font: font.clone(),
+                            font_size: 24.0,
+                            ..default()
+                        },
+                        TextColor(LABEL_TEXT),
+                    ));
+
+                    spawn_text_button(row, font.clone(), "Off", 80.0, PowerSaverLabel)
+                        .observe(toggle_power_saver);
+                });
+
+            // Telemetry opt-in row
+            parent
+                .spawn((
+                    Name::new("Telemetry Row"),
+                    Node {
+                        flex_direction: FlexDirection::Row,
+                        align_items: AlignItems::Center,
+                        column_gap: Val::Px(15.0),
+                        margin: UiRect::bottom(Val::Px(10.0)),
+                        ..default()
+                    },
+                ))
+                .with_children(|row| {
+                    row.spawn((
+                        Name::new("Telemetry Label"),
+                        Text::new("Telemetry"),
+                        TextFont {
+                            font: font.clone(),
+                            font_size: 24.0,
+                            ..default()
+                        },
+                        TextColor(LABEL_TEXT),
+                    ));
+
+                    spawn_text_button(row, font.clone(), "Off", 80.0, TelemetryLabel)
+                        .observe(toggle_telemetry);
+                    spawn_text_button(row, font.clone(), "Data", 80.0, ())
+                        .observe(open_telemetry_menu);
+                });
+
+            // Whether abandoned runs can make the top 10, and break reminders
+            parent
+                .spawn((
+                    Name::new("Abandoned Runs Row"),
+                    Node {
+                        flex_direction: FlexDirection::Row,
+                        align_items: AlignItems::Center,
+                        column_gap: Val::Px(15.0),
+                        margin: UiRect::bottom(Val::Px(10.0)),
+                        ..default()
+                    },
+                ))
+                .with_children(|row| {
+                    row.spawn((
+                        Name::new("Abandoned Runs Label"),
+                        Text::new("Abandoned Runs in Top 10"),
+                        TextFont {
+                            font: font.clone(),
+                            font_size: 24.0,
+                            ..default()
+                        },
+                        TextColor(LABEL_TEXT),
+                    ));
+
+                    spawn_text_button(row, font.clone(), "Off", 80.0, AbandonedLabel)
+                        .observe(toggle_abandoned);
+
+                    row.spawn((
+                        Name::new("Breaks Label"),
+                        Text::new("Breaks"),
+                        TextFont {
+                            font: font.clone(),
+                            font_size: 24.0,
+                            ..default()
+                        },
+                        TextColor(LABEL_TEXT),
+                    ));
+
+                    spawn_text_button(row, font.clone(), "Off", 100.0, BreaksLabel)
+                        .observe(cycle_breaks);
+                });
+
+            // Vsync, frame rate cap, and quality share a row
+            parent
+                .spawn((
+                    Name::new("Display Row"),
+                    Node {
+                        flex_direction: FlexDirection::Row,
+                        align_items: AlignItems::Center,
+                        column_gap: Val::Px(15.0),
+                        margin: UiRect::bottom(Val::Px(10.0)),
+                        ..default()
+                    },
+                ))
+                .with_children(|row| {
+                    row.spawn((
+                        Name::new("VSync Label"),
+                        Text::new("VSync"),
+                        TextFont {
+                            font: font.clone(),
+                            font_size: 24.0,
+                            ..default()
+                        },
+                        TextColor(LABEL_TEXT),
+                    ));
+
+                    spawn_text_button(row, font.clone(), "On", 80.0, VsyncLabel)
+                        .observe(toggle_vsync);
+
+                    // Browsers pace frames themselves
+                    #[cfg(not(target_arch = "wasm32"))]
+                    {
+                        row.spawn((
+                            Name::new("Frame Limit Label"),
+                            Text::new("FPS Cap"),
+                            TextFont {
+                                font: font.clone(),
+                                font_size: 24.0,
+                                ..default()
+                            },
+                            TextColor(LABEL_TEXT),
+                        ));
+
+                        spawn_text_button(row, font.clone(), "Uncapped", 120.0, FrameLimitLabel)
+                            .observe(cycle_frame_limit);
+                    }
+
+                    row.spawn((
+                        Name::new("Quality Label"),
+                        Text::new("Quality"),
+                        TextFont {
+                            font: font.clone(),
+                            font_size: 24.0,
+                            ..default()
+                        },
+                        TextColor(LABEL_TEXT),
+                    ));
+
+                    spawn_text_button(row, font.clone(), "Auto", 100.0, QualityLabel)
+                        .observe(cycle_quality);
+                });
+
+            // Bundle logs and settings into a report file, or write in
+            parent
+                .spawn((
+                    Name::new("Report Row"),
+                    Node {
+                        column_gap: Val::Px(15.0),
+                        margin: UiRect::bottom(Val::Px(10.0)),
+                        ..default()
+                    },
+                ))
+                .with_children(|row| {
+                    spawn_text_button(row, font.clone(), "Report a Problem", 220.0, ())
+                        .observe(report_problem);
+                    spawn_text_button(row, font.clone(), "Send Feedback", 200.0, ())
+                        .observe(open_feedback_menu);
+                    spawn_text_button(row, font.clone(), "Controls", 140.0, ())
+                        .observe(open_controls_menu);
+                });
+
             // Back button
             parent.spawn(widget::button_image(
                 back_button,
@@ -159,21 +514,21 @@ fn spawn_settings_menu(
 const MIN_VOLUME: f32 = 0.0;
 const MAX_VOLUME: f32 = 3.0;
 
-fn lower_global_volume(_: On<Pointer<Click>>, mut global_volume: ResMut<GlobalVolume>) {
+pub(super) fn lower_global_volume(_: On<Pointer<Click>>, mut global_volume: ResMut<GlobalVolume>) {
     let linear = (global_volume.volume.to_linear() - 0.1).max(MIN_VOLUME);
     global_volume.volume = Volume::Linear(linear);
 }
 
-fn raise_global_volume(_: On<Pointer<Click>>, mut global_volume: ResMut<GlobalVolume>) {
+pub(super) fn raise_global_volume(_: On<Pointer<Click>>, mut global_volume: ResMut<GlobalVolume>) {
     let linear = (global_volume.volume.to_linear() + 0.1).min(MAX_VOLUME);
     global_volume.volume = Volume::Linear(linear);
 }
 
 #[derive(Component, Reflect)]
 #[reflect(Component)]
-struct GlobalVolumeLabel;
+pub(super) struct GlobalVolumeLabel;
 
-fn update_global_volume_label(
+pub(super) fn update_global_volume_label(
     global_volume: Res<GlobalVolume>,
     mut label: Single<&mut Text, With<GlobalVolumeLabel>>,
 ) {
@@ -181,6 +536,349 @@ fn update_global_volume_label(
     label.0 = format!("{percent:3.0}%");
 }
 
+/// Spawn a small text button, with `marker` inserted on its label.
+pub(super) fn spawn_text_button<'a>(
+    parent: &'a mut ChildSpawner,
+    font: Handle<Font>,
+    text: &str,
+    width: f32,
+    marker: impl Bundle,
+) -> EntityWorldMut<'a> {
+    let mut button = parent.spawn((
+        Name::new("Text Button"),
+        Button,
+        BackgroundColor(BUTTON_BACKGROUND),
+        InteractionPalette {
+            none: BUTTON_BACKGROUND,
+            hovered: BUTTON_HOVERED_BACKGROUND,
+            pressed: BUTTON_PRESSED_BACKGROUND,
+        },
+        Node {
+            width: Val::Px(width),
+            height: Val::Px(35.0),
+            align_items: AlignItems::Center,
+            justify_content: JustifyContent::Center,
+            ..default()
+        },
+        BorderRadius::all(Val::Px(8.0)),
+    ));
+    button.with_children(|inner| {
+        inner.spawn((
+            Name::new("Button Text"),
+            Text::new(text),
+            TextFont {
+                font,
+                font_size: 20.0,
+                ..default()
+            },
+            TextColor(BUTTON_TEXT),
+            marker,
+            // Don't bubble picking events from the text up to the button.
+            Pickable::IGNORE,
+        ));
+    });
+    button
+}
+
+pub(super) fn on_off(enabled: bool) -> &'static str {
+    if enabled { "On" } else { "Off" }
+}
+
+fn open_shot_clock_menu(_: On<Pointer<Click>>, mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::ShotClock);
+}
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct ShotClockLabel;
+
+/// "On" if any mode has the clock on.
+fn update_shot_clock_label(
+    config: Res<ShotClockConfig>,
+    mut label: Single<&mut Text, With<ShotClockLabel>>,
+) {
+    let enabled = GameMode::ALL
+        .into_iter()
+        .any(|mode| config.get(mode).enabled);
+    label.0 = on_off(enabled).to_string();
+}
+
+fn toggle_aim_guide(_: On<Pointer<Click>>, mut settings: ResMut<AimGuideSettings>) {
+    settings.enabled = !settings.enabled;
+}
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct AimGuideLabel;
+
+fn update_aim_guide_label(
+    settings: Res<AimGuideSettings>,
+    mut label: Single<&mut Text, With<AimGuideLabel>>,
+) {
+    label.0 = on_off(settings.enabled).to_string();
+}
+
+fn cycle_hints(_: On<Pointer<Click>>, mut settings: ResMut<HintSettings>) {
+    settings.frequency = settings.frequency.next();
+}
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct HintsLabel;
+
+fn update_hints_label(settings: Res<HintSettings>, mut label: Single<&mut Text, With<HintsLabel>>) {
+    label.0 = settings.frequency.label().to_string();
+}
+
+fn cycle_breaks(_: On<Pointer<Click>>, mut settings: ResMut<BreakReminderSettings>) {
+    settings.interval = settings.interval.next();
+}
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct BreaksLabel;
+
+fn update_breaks_label(
+    settings: Res<BreakReminderSettings>,
+    mut label: Single<&mut Text, With<BreaksLabel>>,
+) {
+    label.0 = settings.interval.label().to_string();
+}
+
+fn toggle_event_feed(_: On<Pointer<Click>>, mut settings: ResMut<EventFeedSettings>) {
+    settings.enabled = !settings.enabled;
+}
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct EventFeedLabel;
+
+fn update_event_feed_label(
+    settings: Res<EventFeedSettings>,
+    mut label: Single<&mut Text, With<EventFeedLabel>>,
+) {
+    label.0 = on_off(settings.enabled).to_string();
+}
+
+fn toggle_telemetry(_: On<Pointer<Click>>, mut settings: ResMut<TelemetrySettings>) {
+    settings.enabled = !settings.enabled;
+}
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct TelemetryLabel;
+
+fn update_telemetry_label(
+    settings: Res<TelemetrySettings>,
+    mut label: Single<&mut Text, With<TelemetryLabel>>,
+) {
+    label.0 = on_off(settings.enabled).to_string();
+}
+
+fn toggle_abandoned(_: On<Pointer<Click>>, mut settings: ResMut<LeaderboardSettings>) {
+    settings.include_abandoned = !settings.include_abandoned;
+}
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct AbandonedLabel;
+
+fn update_abandoned_label(
+    settings: Res<LeaderboardSettings>,
+    mut label: Single<&mut Text, With<AbandonedLabel>>,
+) {
+    label.0 = on_off(settings.include_abandoned).to_string();
+}
+
+fn toggle_vsync(_: On<Pointer<Click>>, mut settings: ResMut<DisplaySettings>) {
+    settings.vsync = !settings.vsync;
+}
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct VsyncLabel;
+
+fn update_vsync_label(
+    settings: Res<DisplaySettings>,
+    mut label: Single<&mut Text, With<VsyncLabel>>,
+) {
+    label.0 = on_off(settings.vsync).to_string();
+}
+
+#[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+fn cycle_frame_limit(_: On<Pointer<Click>>, mut settings: ResMut<DisplaySettings>) {
+    settings.frame_limit = settings.frame_limit.next();
+}
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+#[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+struct FrameLimitLabel;
+
+fn update_frame_limit_label(
+    settings: Res<DisplaySettings>,
+    mut label: Query<&mut Text, With<FrameLimitLabel>>,
+) {
+    for mut text in &mut label {
+        text.0 = settings.frame_limit.label().to_string();
+    }
+}
+
+fn cycle_quality(_: On<Pointer<Click>>, mut settings: ResMut<DisplaySettings>) {
+    settings.cycle_quality();
+}
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct QualityLabel;
+
+fn update_quality_label(
+    settings: Res<DisplaySettings>,
+    mut label: Single<&mut Text, With<QualityLabel>>,
+) {
+    label.0 = settings.quality_label().to_string();
+}
+
+fn cycle_seasons(_: On<Pointer<Click>>, mut settings: ResMut<DisplaySettings>) {
+    settings.seasonal = settings.seasonal.next();
+}
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct SeasonsLabel;
+
+fn update_seasons_label(
+    settings: Res<DisplaySettings>,
+    mut label: Single<&mut Text, With<SeasonsLabel>>,
+) {
+    label.0 = settings.seasonal.label().to_string();
+}
+
+fn toggle_power_saver(_: On<Pointer<Click>>, mut settings: ResMut<DisplaySettings>) {
+    settings.power_saver = !settings.power_saver;
+}
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct PowerSaverLabel;
+
+fn update_power_saver_label(
+    settings: Res<DisplaySettings>,
+    mut label: Single<&mut Text, With<PowerSaverLabel>>,
+) {
+    label.0 = on_off(settings.power_saver).to_string();
+}
+
+pub(super) fn toggle_reduced_motion(
+    _: On<Pointer<Click>>,
+    mut settings: ResMut<AccessibilitySettings>,
+) {
+    settings.reduced_motion = !settings.reduced_motion;
+}
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub(super) struct ReducedMotionLabel;
+
+pub(super) fn update_reduced_motion_label(
+    settings: Res<AccessibilitySettings>,
+    mut label: Single<&mut Text, With<ReducedMotionLabel>>,
+) {
+    label.0 = on_off(settings.reduced_motion).to_string();
+}
+
+fn toggle_descent_preview(_: On<Pointer<Click>>, mut settings: ResMut<AccessibilitySettings>) {
+    settings.descent_preview = !settings.descent_preview;
+}
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct DescentPreviewLabel;
+
+fn update_descent_preview_label(
+    settings: Res<AccessibilitySettings>,
+    mut label: Single<&mut Text, With<DescentPreviewLabel>>,
+) {
+    label.0 = on_off(settings.descent_preview).to_string();
+}
+
+pub(super) fn cycle_colorblind_mode(_: On<Pointer<Click>>, mut colorblind: ResMut<ColorblindMode>) {
+    *colorblind = colorblind.next();
+}
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub(super) struct ColorblindLabel;
+
+pub(super) fn update_colorblind_label(
+    colorblind: Res<ColorblindMode>,
+    mut label: Single<&mut Text, With<ColorblindLabel>>,
+) {
+    label.0 = colorblind.label().to_string();
+}
+
+fn open_telemetry_menu(_: On<Pointer<Click>>, mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::Telemetry);
+}
+
+fn open_feedback_menu(_: On<Pointer<Click>>, mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::Feedback);
+}
+
+fn open_controls_menu(_: On<Pointer<Click>>, mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::Controls);
+}
+
+fn report_problem(
+    _: On<Pointer<Click>>,
+    global_volume: Res<GlobalVolume>,
+    shot_clock: Res<ShotClockConfig>,
+    event_feed: Res<EventFeedSettings>,
+    display: Res<DisplaySettings>,
+    accessibility: Res<AccessibilitySettings>,
+    run_seed: Res<RunSeed>,
+    mut toasts: MessageWriter<ShowToast>,
+) {
+    let context = [
+        // Only the starting board is seeded
+        (
+            "seed",
+            run_seed
+                .0
+                .map_or("none".to_string(), |seed| seed.to_string()),
+        ),
+        (
+            "volume",
+            format!("{:.0}%", 100.0 * global_volume.volume.to_linear()),
+        ),
+        ("shot clock", shot_clock.summary()),
+        ("event feed", on_off(event_feed.enabled).to_string()),
+        ("vsync", on_off(display.vsync).to_string()),
+        ("fps cap", display.frame_limit.label().to_string()),
+        ("quality", display.quality_label().to_string()),
+        ("seasons", display.seasonal.label().to_string()),
+        ("power saver", on_off(display.power_saver).to_string()),
+        (
+            "reduced motion",
+            on_off(accessibility.reduced_motion).to_string(),
+        ),
+        ("colorblind", accessibility.colorblind.label().to_string()),
+        (
+            "descent preview",
+            on_off(accessibility.descent_preview).to_string(),
+        ),
+    ];
+
+    toasts.write(match crash_log::write_problem_report(&context) {
+        Some(ReportSaved::File(path)) => {
+            ShowToast::info(format!("Report saved to {}", path.display()))
+        }
+        #[cfg(target_arch = "wasm32")]
+        Some(ReportSaved::Download) => ShowToast::info("Report downloaded"),
+        None => ShowToast::info("Report printed to the log"),
+    });
+}
+
 fn go_back_on_click(
     _: On<Pointer<Click>>,
     screen: Res<State<Screen>>,
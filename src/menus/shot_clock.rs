@@ -0,0 +1,154 @@
+//! The shot clock menu, opened from the settings menu.
+//!
+//! Lists each mode that has score pressure with its shot clock: the length
+//! (or off), and what happens when it runs out.
+
+use bevy::{ecs::spawn::SpawnWith, input::common_conditions::input_just_pressed, prelude::*};
+
+use crate::{
+    game::{mode::GameMode, shot_clock::ShotClockConfig},
+    menus::{Menu, settings::spawn_text_button},
+    theme::{
+        GameFont,
+        palette::{HEADER_TEXT, LABEL_TEXT},
+        widget,
+    },
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(OnEnter(Menu::ShotClock), spawn_shot_clock_menu);
+    app.add_systems(
+        Update,
+        (
+            go_back.run_if(input_just_pressed(KeyCode::Escape)),
+            update_shot_clock_labels.run_if(
+                resource_changed::<ShotClockConfig>
+                    .or(any_match_filter::<Added<LengthLabel>>)
+                    .or(any_match_filter::<Added<TimeoutLabel>>),
+            ),
+        )
+            .run_if(in_state(Menu::ShotClock)),
+    );
+}
+
+/// The text showing a mode's shot clock length.
+#[derive(Component, Debug)]
+struct LengthLabel(GameMode);
+
+/// The text showing what a mode's shot clock does when it runs out.
+#[derive(Component, Debug)]
+struct TimeoutLabel(GameMode);
+
+fn spawn_shot_clock_menu(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    game_font: Res<GameFont>,
+) {
+    let back_button = asset_server.load("images/back_button.png");
+    let font = game_font.0.clone();
+
+    commands.spawn((
+        Name::new("Shot Clock Menu"),
+        Node {
+            position_type: PositionType::Absolute,
+            width: Val::Percent(100.0),
+            height: Val::Percent(100.0),
+            align_items: AlignItems::Center,
+            justify_content: JustifyContent::Center,
+            flex_direction: FlexDirection::Column,
+            row_gap: Val::Px(10.0),
+            ..default()
+        },
+        BackgroundColor(Color::srgb(0.96, 0.92, 0.84)),
+        GlobalZIndex(2),
+        DespawnOnExit(Menu::ShotClock),
+        Children::spawn(SpawnWith(move |parent: &mut ChildSpawner| {
+            parent.spawn((
+                Name::new("Shot Clock Header"),
+                Text::new("Shot Clock"),
+                TextFont {
+                    font: font.clone(),
+                    font_size: 40.0,
+                    ..default()
+                },
+                TextColor(HEADER_TEXT),
+                Node {
+                    margin: UiRect::bottom(Val::Px(10.0)),
+                    ..default()
+                },
+            ));
+
+            for mode in GameMode::ALL.into_iter().filter(|mode| mode.has_pressure()) {
+                parent
+                    .spawn((
+                        Name::new(format!("{} Row", mode.label())),
+                        Node {
+                            flex_direction: FlexDirection::Row,
+                            align_items: AlignItems::Center,
+                            column_gap: Val::Px(15.0),
+                            ..default()
+                        },
+                    ))
+                    .with_children(|row| {
+                        row.spawn((
+                            Name::new("Mode Label"),
+                            Text::new(mode.label()),
+                            TextFont {
+                                font: font.clone(),
+                                font_size: 24.0,
+                                ..default()
+                            },
+                            TextColor(LABEL_TEXT),
+                            Node {
+                                width: Val::Px(180.0),
+                                ..default()
+                            },
+                        ));
+                        spawn_text_button(row, font.clone(), "", 100.0, LengthLabel(mode))
+                            .observe(cycle_length(mode));
+                        spawn_text_button(row, font.clone(), "", 160.0, TimeoutLabel(mode))
+                            .observe(cycle_timeout(mode));
+                    });
+            }
+
+            parent.spawn(widget::button_image(
+                back_button,
+                266.0,
+                105.0,
+                go_back_on_click,
+            ));
+        })),
+    ));
+}
+
+fn cycle_length(mode: GameMode) -> impl Fn(On<Pointer<Click>>, ResMut<ShotClockConfig>) {
+    move |_, mut config| config.get_mut(mode).cycle_length()
+}
+
+fn cycle_timeout(mode: GameMode) -> impl Fn(On<Pointer<Click>>, ResMut<ShotClockConfig>) {
+    move |_, mut config| {
+        let rules = config.get_mut(mode);
+        rules.on_timeout = rules.on_timeout.next();
+    }
+}
+
+fn update_shot_clock_labels(
+    config: Res<ShotClockConfig>,
+    mut lengths: Query<(&LengthLabel, &mut Text), Without<TimeoutLabel>>,
+    mut timeouts: Query<(&TimeoutLabel, &mut Text), Without<LengthLabel>>,
+) {
+    for (label, mut text) in &mut lengths {
+        text.0 = config.get(label.0).length_label();
+    }
+    for (label, mut text) in &mut timeouts {
+        text.0 = config.get(label.0).on_timeout.label().to_string();
+    }
+}
+
+fn go_back_on_click(_: On<Pointer<Click>>, mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::Settings);
+}
+
+fn go_back(mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::Settings);
+}
@@ -0,0 +1,159 @@
+//! The telemetry data viewer, showing what has been recorded locally.
+
+use bevy::{ecs::spawn::SpawnWith, input::common_conditions::input_just_pressed, prelude::*};
+
+use crate::{
+    game::telemetry::{RunOutcome, TelemetryLog, TelemetrySettings},
+    menus::Menu,
+    theme::{
+        GameFont,
+        palette::{HEADER_TEXT, LABEL_TEXT},
+        widget,
+    },
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(OnEnter(Menu::Telemetry), spawn_telemetry_menu);
+    app.add_systems(
+        Update,
+        go_back.run_if(in_state(Menu::Telemetry).and(input_just_pressed(KeyCode::Escape))),
+    );
+}
+
+fn spawn_telemetry_menu(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    game_font: Res<GameFont>,
+    settings: Res<TelemetrySettings>,
+    log: Res<TelemetryLog>,
+) {
+    let back_button = asset_server.load("images/back_button.png");
+    let font = game_font.0.clone();
+    let lines = summary_lines(&settings, &log);
+
+    commands.spawn((
+        Name::new("Telemetry Menu"),
+        Node {
+            position_type: PositionType::Absolute,
+            width: Val::Percent(100.0),
+            height: Val::Percent(100.0),
+            align_items: AlignItems::Center,
+            justify_content: JustifyContent::Center,
+            flex_direction: FlexDirection::Column,
+            row_gap: Val::Px(6.0),
+            ..default()
+        },
+        BackgroundColor(Color::srgb(0.96, 0.92, 0.84)),
+        GlobalZIndex(2),
+        DespawnOnExit(Menu::Telemetry),
+        Children::spawn(SpawnWith(move |parent: &mut ChildSpawner| {
+            parent.spawn((
+                Name::new("Telemetry Header"),
+                Text::new("Telemetry Data"),
+                TextFont {
+                    font: font.clone(),
+                    font_size: 40.0,
+                    ..default()
+                },
+                TextColor(HEADER_TEXT),
+                Node {
+                    margin: UiRect::bottom(Val::Px(14.0)),
+                    ..default()
+                },
+            ));
+
+            for line in lines {
+                parent.spawn((
+                    Name::new("Telemetry Line"),
+                    Text::new(line),
+                    TextFont {
+                        font: font.clone(),
+                        font_size: 16.0,
+                        ..default()
+                    },
+                    TextColor(LABEL_TEXT),
+                ));
+            }
+
+            parent.spawn(widget::button_image(
+                back_button,
+                266.0,
+                105.0,
+                go_back_on_click,
+            ));
+        })),
+    ));
+}
+
+/// Maximum number of power-up pick rate lines shown.
+const MAX_POWERUP_LINES: usize = 8;
+
+/// Summarize the recorded runs as lines of text.
+fn summary_lines(settings: &TelemetrySettings, log: &TelemetryLog) -> Vec<String> {
+    let mut lines = vec![
+        format!("Recording: {}", if settings.enabled { "On" } else { "Off" }),
+        format!(
+            "Upload: {}",
+            settings.endpoint.as_deref().unwrap_or("none (local only)")
+        ),
+        format!("Runs recorded: {}", log.runs.len()),
+    ];
+
+    if log.runs.is_empty() {
+        return lines;
+    }
+
+    let runs = log.runs.len() as f32;
+    let avg_secs = log.runs.iter().map(|r| r.session_secs).sum::<f32>() / runs;
+    let avg_level = log.runs.iter().map(|r| r.level_reached).sum::<u32>() as f32 / runs;
+    let best_level = log.runs.iter().map(|r| r.level_reached).max().unwrap_or(0);
+    lines.push(format!("Average session: {avg_secs:.0}s"));
+    lines.push(format!("Average level: {avg_level:.1} (best {best_level})"));
+
+    for outcome in [
+        RunOutcome::Won,
+        RunOutcome::GridReachedDanger,
+        RunOutcome::DangerLanding,
+        RunOutcome::Quit,
+        RunOutcome::Abandoned,
+    ] {
+        let count = log.runs.iter().filter(|r| r.outcome == outcome).count();
+        if count > 0 {
+            lines.push(format!("{}: {}", outcome.label(), count));
+        }
+    }
+
+    // Pick rate = times picked / times offered
+    let mut offered: Vec<(&str, u32, u32)> = Vec::new();
+    for run in &log.runs {
+        for name in &run.powerups_offered {
+            match offered.iter_mut().find(|(n, _, _)| n == name) {
+                Some(entry) => entry.1 += 1,
+                None => offered.push((name, 1, 0)),
+            }
+        }
+        for name in &run.powerups_picked {
+            if let Some(entry) = offered.iter_mut().find(|(n, _, _)| n == name) {
+                entry.2 += 1;
+            }
+        }
+    }
+    // Only the most-offered power-ups fit on screen
+    offered.sort_by_key(|entry| std::cmp::Reverse(entry.1));
+    for (name, offers, picks) in offered.into_iter().take(MAX_POWERUP_LINES) {
+        lines.push(format!(
+            "{name}: picked {picks}/{offers} ({:.0}%)",
+            100.0 * picks as f32 / offers as f32
+        ));
+    }
+
+    lines
+}
+
+fn go_back_on_click(_: On<Pointer<Click>>, mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::Settings);
+}
+
+fn go_back(mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::Settings);
+}
@@ -0,0 +1,229 @@
+//! The What's New menu: the bundled changelog, newest version first.
+//!
+//! Opens by itself on the title screen the first time an updated game is
+//! started, and from the main menu any time after. The changelog is a
+//! small markdown-like file (see the comments at its top), compiled in like
+//! the loading screen tips.
+
+use bevy::{ecs::spawn::SpawnWith, input::common_conditions::input_just_pressed, prelude::*};
+
+use crate::{
+    accessibility::AccessibilitySettings,
+    menus::Menu,
+    theme::{
+        GameFont,
+        palette::{HEADER_TEXT, LABEL_TEXT},
+        widget,
+    },
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(
+        OnEnter(Menu::WhatsNew),
+        (spawn_whats_new_menu, mark_whats_new_seen),
+    );
+    app.add_systems(
+        Update,
+        go_back.run_if(in_state(Menu::WhatsNew).and(input_just_pressed(KeyCode::Escape))),
+    );
+}
+
+const CHANGELOG_FILE: &str = include_str!("../../assets/data/changelog.md");
+
+/// Versions shown at once. Older ones stay in the file but would run off the
+/// bottom of the screen.
+const SHOWN_VERSIONS: usize = 2;
+
+/// A line of the changelog, by how it's styled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ChangelogLine<'a> {
+    /// `## 0.1.0`
+    Version(&'a str),
+    /// `### Modes`
+    Section(&'a str),
+    /// `- Something new`
+    Bullet(&'a str),
+    Text(&'a str),
+}
+
+impl ChangelogLine<'_> {
+    fn font_size(self) -> f32 {
+        match self {
+            ChangelogLine::Version(_) => 36.0,
+            ChangelogLine::Section(_) => 26.0,
+            ChangelogLine::Bullet(_) | ChangelogLine::Text(_) => 18.0,
+        }
+    }
+
+    fn color(self) -> Color {
+        match self {
+            ChangelogLine::Version(_) | ChangelogLine::Section(_) => HEADER_TEXT,
+            ChangelogLine::Bullet(_) | ChangelogLine::Text(_) => LABEL_TEXT,
+        }
+    }
+
+    /// Headings get a gap above them, bullets are indented.
+    fn margin(self) -> UiRect {
+        match self {
+            ChangelogLine::Version(_) => UiRect::top(Val::Px(10.0)),
+            ChangelogLine::Section(_) => UiRect::top(Val::Px(6.0)),
+            ChangelogLine::Bullet(_) => UiRect::left(Val::Px(20.0)),
+            ChangelogLine::Text(_) => UiRect::ZERO,
+        }
+    }
+
+    fn text(self) -> String {
+        match self {
+            ChangelogLine::Version(version) => format!("Version {version}"),
+            ChangelogLine::Bullet(text) => format!("\u{2022} {text}"),
+            ChangelogLine::Section(text) | ChangelogLine::Text(text) => text.to_string(),
+        }
+    }
+}
+
+/// The non-empty, non-comment lines of `changelog`, up to the
+/// `max_versions`th version heading.
+fn parse_changelog(changelog: &str, max_versions: usize) -> Vec<ChangelogLine<'_>> {
+    let mut versions = 0;
+    changelog
+        .lines()
+        .map(str::trim)
+        .filter_map(|line| {
+            if let Some(version) = line.strip_prefix("## ") {
+                Some(ChangelogLine::Version(version.trim()))
+            } else if let Some(section) = line.strip_prefix("### ") {
+                Some(ChangelogLine::Section(section.trim()))
+            } else if let Some(bullet) = line.strip_prefix("- ") {
+                Some(ChangelogLine::Bullet(bullet.trim()))
+            } else if line.is_empty() || line.starts_with('#') {
+                None
+            } else {
+                Some(ChangelogLine::Text(line))
+            }
+        })
+        .take_while(|line| {
+            if matches!(line, ChangelogLine::Version(_)) {
+                versions += 1;
+            }
+            versions <= max_versions
+        })
+        .collect()
+}
+
+fn spawn_whats_new_menu(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    game_font: Res<GameFont>,
+) {
+    let back_button = asset_server.load("images/back_button.png");
+    let font = game_font.0.clone();
+
+    commands.spawn((
+        Name::new("What's New Menu"),
+        Node {
+            position_type: PositionType::Absolute,
+            width: Val::Percent(100.0),
+            height: Val::Percent(100.0),
+            align_items: AlignItems::Center,
+            justify_content: JustifyContent::Center,
+            flex_direction: FlexDirection::Column,
+            row_gap: Val::Px(10.0),
+            ..default()
+        },
+        BackgroundColor(Color::srgb(0.96, 0.92, 0.84)),
+        GlobalZIndex(2),
+        DespawnOnExit(Menu::WhatsNew),
+        Children::spawn(SpawnWith(move |parent: &mut ChildSpawner| {
+            parent.spawn((
+                Name::new("What's New Header"),
+                Text::new("What's New"),
+                TextFont {
+                    font: font.clone(),
+                    font_size: 48.0,
+                    ..default()
+                },
+                TextColor(HEADER_TEXT),
+            ));
+
+            parent
+                .spawn((
+                    Name::new("Changelog"),
+                    Node {
+                        width: Val::Px(640.0),
+                        max_height: Val::Percent(65.0),
+                        flex_direction: FlexDirection::Column,
+                        row_gap: Val::Px(4.0),
+                        overflow: Overflow::clip(),
+                        ..default()
+                    },
+                ))
+                .with_children(|changelog| {
+                    for line in parse_changelog(CHANGELOG_FILE, SHOWN_VERSIONS) {
+                        changelog.spawn((
+                            Name::new("Changelog Line"),
+                            Text::new(line.text()),
+                            TextFont {
+                                font: font.clone(),
+                                font_size: line.font_size(),
+                                ..default()
+                            },
+                            TextColor(line.color()),
+                            Node {
+                                margin: line.margin(),
+                                ..default()
+                            },
+                        ));
+                    }
+                });
+
+            parent.spawn(widget::button_image(
+                back_button,
+                266.0,
+                105.0,
+                go_back_on_click,
+            ));
+        })),
+    ));
+}
+
+/// Opening the menu counts as seeing it, however it was opened.
+fn mark_whats_new_seen(mut settings: ResMut<AccessibilitySettings>) {
+    settings.mark_whats_new_seen();
+}
+
+fn go_back_on_click(_: On<Pointer<Click>>, mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::Main);
+}
+
+fn go_back(mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::Main);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn changelog_lines_are_styled_by_prefix() {
+        let changelog =
+            "# A comment\n\n## 0.2.0\n### Modes\n- Zen\nPlain words.\n## 0.1.0\n- Old\n";
+        assert_eq!(
+            parse_changelog(changelog, 1),
+            vec![
+                ChangelogLine::Version("0.2.0"),
+                ChangelogLine::Section("Modes"),
+                ChangelogLine::Bullet("Zen"),
+                ChangelogLine::Text("Plain words."),
+            ]
+        );
+        assert_eq!(parse_changelog(changelog, 2).len(), 6);
+    }
+
+    #[test]
+    fn bundled_changelog_starts_with_this_version() {
+        assert_eq!(
+            parse_changelog(CHANGELOG_FILE, SHOWN_VERSIONS).first(),
+            Some(&ChangelogLine::Version(env!("CARGO_PKG_VERSION")))
+        );
+    }
+}
@@ -0,0 +1,328 @@
+//! Mods - user asset packs from a `mods/` folder next to `assets/` (native
+//! only).
+//!
+//! Each folder in `mods/` is a pack. A file in a pack replaces the game's
+//! file at the same path under `assets/`: sprites, sounds, and the data
+//! files for campaign levels, drills, and power-ups (`data/campaign.json`,
+//! `data/drills.json`, and `data/powerups.json`). A pack can describe itself
+//! with a `mod.json`:
+//!
+//! ```json
+//! { "name": "Winter Snords", "description": "Hats!", "priority": 10 }
+//! ```
+//!
+//! When packs replace the same file, the highest priority wins, then the
+//! folder name that sorts first. The mod list screen shows what's loaded and
+//! which files each pack lost to another.
+//!
+//! Packs are found once at startup, before the asset server is built, so
+//! adding one needs a restart. Sprites and sounds go through a reader that
+//! serves the winning pack's file in place of the game's; data files are
+//! compiled in, so they're read from the pack when their plugin builds.
+
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+use bevy::prelude::*;
+#[cfg(not(target_family = "wasm"))]
+use serde::Deserialize;
+use serde::de::DeserializeOwned;
+
+/// The folder packs are loaded from, next to `assets/`.
+pub const MODS_DIR: &str = "mods";
+
+/// A pack's optional description of itself.
+#[cfg(not(target_family = "wasm"))]
+const MANIFEST_FILE: &str = "mod.json";
+
+/// What a pack's `mod.json` can say.
+#[cfg(not(target_family = "wasm"))]
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+struct ModManifest {
+    name: Option<String>,
+    description: String,
+    priority: i32,
+}
+
+/// A loaded asset pack.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ModPack {
+    /// The pack's folder name in `mods/`.
+    pub folder: String,
+    pub name: String,
+    pub description: String,
+    /// Packs with a higher priority win when they replace the same file.
+    pub priority: i32,
+    /// The pack's folder.
+    pub root: PathBuf,
+    /// Files the pack replaces, relative to `assets/`, sorted.
+    pub files: Vec<PathBuf>,
+}
+
+/// The loaded packs, and which one each replaced file comes from.
+#[derive(Resource, Debug, Clone, Default)]
+pub struct ModList {
+    /// Highest priority first.
+    pub packs: Vec<ModPack>,
+    /// Index into `packs` of the pack serving each replaced file.
+    winners: HashMap<PathBuf, usize>,
+}
+
+impl ModList {
+    /// Resolve which pack serves each file.
+    #[cfg(not(target_family = "wasm"))]
+    pub fn new(mut packs: Vec<ModPack>) -> Self {
+        packs.sort_by(|a, b| {
+            b.priority
+                .cmp(&a.priority)
+                .then_with(|| a.folder.cmp(&b.folder))
+        });
+        let mut winners = HashMap::new();
+        for (index, pack) in packs.iter().enumerate() {
+            for file in &pack.files {
+                winners.entry(file.clone()).or_insert(index);
+            }
+        }
+        Self { packs, winners }
+    }
+
+    /// Find the packs in `mods/`. Folders that can't be read are skipped.
+    #[cfg(not(target_family = "wasm"))]
+    pub fn discover() -> Self {
+        let dir = bevy::asset::io::file::FileAssetReader::get_base_path().join(MODS_DIR);
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            return Self::default();
+        };
+        let packs = entries
+            .filter_map(Result::ok)
+            .filter(|entry| entry.path().is_dir())
+            .filter_map(|entry| read_pack(&entry.path()))
+            .collect();
+        let mods = Self::new(packs);
+        for pack in &mods.packs {
+            info!(
+                "Loaded mod '{}' ({} files, priority {})",
+                pack.name,
+                pack.files.len(),
+                pack.priority
+            );
+        }
+        mods
+    }
+
+    #[cfg(not(target_family = "wasm"))]
+    pub fn is_empty(&self) -> bool {
+        self.packs.is_empty()
+    }
+
+    /// The file serving `path` (relative to `assets/`) and the pack it's
+    /// from, if a pack replaces it.
+    pub fn source(&self, path: &Path) -> Option<(&ModPack, PathBuf)> {
+        let pack = &self.packs[*self.winners.get(path)?];
+        Some((pack, pack.root.join(path)))
+    }
+
+    /// Files `pack` replaces that another pack wins, with the winner's name.
+    pub fn conflicts(&self, pack: usize) -> Vec<(&Path, &str)> {
+        let Some(loser) = self.packs.get(pack) else {
+            return Vec::new();
+        };
+        loser
+            .files
+            .iter()
+            .filter_map(|file| {
+                let winner = *self.winners.get(file)?;
+                (winner != pack).then(|| (file.as_path(), self.packs[winner].name.as_str()))
+            })
+            .collect()
+    }
+
+    /// Every replaced file and the file serving it.
+    #[cfg(not(target_family = "wasm"))]
+    fn overrides(&self) -> HashMap<PathBuf, PathBuf> {
+        self.winners
+            .iter()
+            .map(|(path, &index)| (path.clone(), self.packs[index].root.join(path)))
+            .collect()
+    }
+}
+
+/// A pack's manifest and files, or None if its folder can't be read.
+#[cfg(not(target_family = "wasm"))]
+fn read_pack(root: &Path) -> Option<ModPack> {
+    let folder = root.file_name()?.to_string_lossy().into_owned();
+    let manifest = match std::fs::read_to_string(root.join(MANIFEST_FILE)) {
+        Ok(text) => serde_json::from_str(&text).unwrap_or_else(|e| {
+            warn!("Bad {} in mod '{}': {}", MANIFEST_FILE, folder, e);
+            ModManifest::default()
+        }),
+        Err(_) => ModManifest::default(),
+    };
+
+    let mut files = Vec::new();
+    let mut dirs = vec![root.to_path_buf()];
+    while let Some(dir) = dirs.pop() {
+        for entry in std::fs::read_dir(&dir).ok()?.filter_map(Result::ok) {
+            let path = entry.path();
+            if path.is_dir() {
+                dirs.push(path);
+            } else if let Ok(relative) = path.strip_prefix(root)
+                && relative != Path::new(MANIFEST_FILE)
+            {
+                files.push(relative.to_path_buf());
+            }
+        }
+    }
+    files.sort();
+
+    Some(ModPack {
+        name: manifest.name.unwrap_or_else(|| folder.clone()),
+        folder,
+        description: manifest.description,
+        priority: manifest.priority,
+        root: root.to_path_buf(),
+        files,
+    })
+}
+
+/// Find the packs and serve their files in place of the game's. Must run
+/// before the asset plugin is added.
+#[cfg(not(target_family = "wasm"))]
+pub fn register(app: &mut App) {
+    use bevy::asset::io::{AssetSource, AssetSourceId};
+
+    let mods = ModList::discover();
+    if !mods.is_empty() {
+        let overrides = mods.overrides();
+        // Hot reloading of `assets/` is off while packs are loaded
+        app.register_asset_source(
+            AssetSourceId::Default,
+            AssetSource::build().with_reader(move || {
+                Box::new(ModdedAssetReader {
+                    assets: AssetSource::get_default_reader("assets".to_string())(),
+                    overrides: overrides.clone(),
+                })
+            }),
+        );
+    }
+    app.insert_resource(mods);
+}
+
+/// Parse the JSON data file at `path` (relative to `assets/`), preferring a
+/// pack's replacement over the compiled-in `builtin`. A replacement that
+/// doesn't parse is skipped with a warning.
+pub fn parse_data<T: DeserializeOwned>(
+    mods: Option<&ModList>,
+    path: &str,
+    builtin: &str,
+) -> serde_json::Result<T> {
+    if let Some((pack, file)) = mods.and_then(|mods| mods.source(Path::new(path))) {
+        let parsed = std::fs::read_to_string(&file)
+            .map_err(|e| e.to_string())
+            .and_then(|text| serde_json::from_str(&text).map_err(|e| e.to_string()));
+        match parsed {
+            Ok(data) => {
+                info!("Using {} from mod '{}'", path, pack.name);
+                return Ok(data);
+            }
+            Err(e) => warn!("Ignoring {} from mod '{}': {}", path, pack.name, e),
+        }
+    }
+    serde_json::from_str(builtin)
+}
+
+/// Reads replaced files from their pack, and everything else from `assets/`.
+#[cfg(not(target_family = "wasm"))]
+struct ModdedAssetReader {
+    assets: Box<dyn bevy::asset::io::ErasedAssetReader>,
+    overrides: HashMap<PathBuf, PathBuf>,
+}
+
+#[cfg(not(target_family = "wasm"))]
+impl bevy::asset::io::AssetReader for ModdedAssetReader {
+    async fn read<'a>(
+        &'a self,
+        path: &'a Path,
+    ) -> Result<impl bevy::asset::io::Reader + 'a, bevy::asset::io::AssetReaderError> {
+        use bevy::asset::io::{AssetReaderError, Reader, VecReader};
+
+        let Some(file) = self.overrides.get(path) else {
+            return self.assets.read(path).await;
+        };
+        let reader: Box<dyn Reader> = match std::fs::read(file) {
+            Ok(bytes) => Box::new(VecReader::new(bytes)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                return Err(AssetReaderError::NotFound(file.clone()));
+            }
+            Err(e) => return Err(AssetReaderError::Io(e.into())),
+        };
+        Ok(reader)
+    }
+
+    async fn read_meta<'a>(
+        &'a self,
+        path: &'a Path,
+    ) -> Result<impl bevy::asset::io::Reader + 'a, bevy::asset::io::AssetReaderError> {
+        self.assets.read_meta(path).await
+    }
+
+    async fn read_directory<'a>(
+        &'a self,
+        path: &'a Path,
+    ) -> Result<Box<bevy::asset::io::PathStream>, bevy::asset::io::AssetReaderError> {
+        self.assets.read_directory(path).await
+    }
+
+    async fn is_directory<'a>(
+        &'a self,
+        path: &'a Path,
+    ) -> Result<bool, bevy::asset::io::AssetReaderError> {
+        self.assets.is_directory(path).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pack(folder: &str, priority: i32, files: &[&str]) -> ModPack {
+        ModPack {
+            folder: folder.to_string(),
+            name: folder.to_string(),
+            description: String::new(),
+            priority,
+            root: PathBuf::from(MODS_DIR).join(folder),
+            files: files.iter().map(PathBuf::from).collect(),
+        }
+    }
+
+    #[test]
+    fn higher_priority_packs_win_conflicts() {
+        let mods = ModList::new(vec![
+            pack("hats", 0, &["images/derpy.png", "images/happy.png"]),
+            pack("levels", 0, &["data/campaign.json", "images/derpy.png"]),
+            pack("sounds", 5, &["audio/pop.ogg", "images/happy.png"]),
+        ]);
+
+        // Priority first, then folder name
+        let order: Vec<&str> = mods.packs.iter().map(|p| p.folder.as_str()).collect();
+        assert_eq!(order, ["sounds", "hats", "levels"]);
+
+        let (winner, file) = mods.source(Path::new("images/derpy.png")).unwrap();
+        assert_eq!(winner.folder, "hats");
+        assert_eq!(file, Path::new("mods/hats/images/derpy.png"));
+        let (winner, _) = mods.source(Path::new("images/happy.png")).unwrap();
+        assert_eq!(winner.folder, "sounds");
+        assert!(mods.source(Path::new("images/sad.png")).is_none());
+
+        assert_eq!(
+            mods.conflicts(1),
+            [(Path::new("images/happy.png"), "sounds")]
+        );
+        assert_eq!(mods.conflicts(2), [(Path::new("images/derpy.png"), "hats")]);
+        assert!(mods.conflicts(0).is_empty());
+    }
+}
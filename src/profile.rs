@@ -0,0 +1,194 @@
+//! The player profile: what the game remembers about past play.
+//!
+//! The most recent completed runs (shown in the history tab of the scores
+//! screen), the best score in each mode, which modes are unlocked on the
+//! mode select screen, campaign progress, and the companion picked for
+//! runs. Saved to `profile.json` next to the other save files, signed so
+//! progress can't be unlocked by editing it unnoticed: a profile whose
+//! signature doesn't match keeps its progress but stays marked unverified.
+
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{
+    game::{
+        companion::Companion,
+        mode::{GameMode, SelectedMode},
+        telemetry::RunOutcome,
+    },
+    save::{Migration, SaveFile},
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<Profile>();
+    app.add_systems(Startup, load_profile);
+}
+
+/// Number of completed runs kept in the history.
+pub const MAX_RECENT_RUNS: usize = 20;
+
+/// A completed run, as listed in the history.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecentRun {
+    pub mode: GameMode,
+    pub score: u32,
+    pub level: u32,
+    /// Time spent playing, not counting pauses.
+    pub duration_secs: f32,
+    pub outcome: RunOutcome,
+    /// Seed the starting board was generated from. `None` for fixed boards
+    /// (drills), which can't be retried by seed.
+    pub seed: Option<u64>,
+}
+
+/// Everything the game remembers about the player.
+#[derive(Resource, Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Profile {
+    /// Completed runs, newest first.
+    #[serde(default)]
+    pub recent_runs: Vec<RecentRun>,
+    /// Highest score of any completed run, per mode.
+    #[serde(default)]
+    pub best_scores: HashMap<GameMode, u32>,
+    /// Entries on the mode select screen unlocked on top of
+    /// [`SelectedMode::STARTER`].
+    #[serde(default)]
+    pub unlocked_modes: Vec<SelectedMode>,
+    /// Campaign levels cleared so far. The next campaign run plays the level
+    /// at this index.
+    #[serde(default)]
+    pub campaign_cleared: u32,
+    /// The companion that comes along on runs, if one was picked.
+    #[serde(default)]
+    pub companion: Option<Companion>,
+    /// Set when the profile loaded without a matching signature. Saved with
+    /// the profile, so re-signing it doesn't clear it.
+    #[serde(default)]
+    pub unverified: bool,
+}
+
+impl SaveFile for Profile {
+    const FILE_NAME: &'static str = "profile.json";
+    const DESCRIPTION: &'static str = "profile";
+    const MIGRATIONS: &'static [Migration] = &[start_signing];
+    const SIGNED: bool = true;
+
+    fn mark_unverified(&mut self) {
+        if !self.unverified {
+            warn!("Profile failed its signature check, marking it unverified");
+        }
+        self.unverified = true;
+    }
+}
+
+/// Version 2 is the same data, signed.
+fn start_signing(value: Value) -> Result<Value, String> {
+    Ok(value)
+}
+
+impl Profile {
+    /// Add a completed run, dropping the oldest once the history is full.
+    pub fn push_run(&mut self, run: RecentRun) {
+        let best = self.best_scores.entry(run.mode).or_default();
+        *best = (*best).max(run.score);
+        self.recent_runs.insert(0, run);
+        self.recent_runs.truncate(MAX_RECENT_RUNS);
+    }
+
+    /// Best score in `mode`, if a run has been completed in it.
+    pub fn best_score(&self, mode: GameMode) -> Option<u32> {
+        self.best_scores.get(&mode).copied()
+    }
+
+    /// Whether `mode` can be picked on the mode select screen. Entries with
+    /// no rules yet stay locked even if the profile lists them.
+    pub fn is_unlocked(&self, mode: SelectedMode) -> bool {
+        mode.game_mode().is_some()
+            && (SelectedMode::STARTER.contains(&mode) || self.unlocked_modes.contains(&mode))
+    }
+}
+
+fn load_profile(mut profile: ResMut<Profile>) {
+    *profile = Profile::load();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::save::{decode, encode};
+
+    fn run(score: u32) -> RecentRun {
+        RecentRun {
+            mode: GameMode::Classic,
+            score,
+            level: 1,
+            duration_secs: 60.0,
+            outcome: RunOutcome::GridReachedDanger,
+            seed: Some(7),
+        }
+    }
+
+    #[test]
+    fn keeps_the_newest_runs_first() {
+        let mut profile = Profile::default();
+        for score in 0..(MAX_RECENT_RUNS as u32 + 5) {
+            profile.push_run(run(score));
+        }
+
+        assert_eq!(profile.recent_runs.len(), MAX_RECENT_RUNS);
+        assert_eq!(profile.recent_runs[0].score, MAX_RECENT_RUNS as u32 + 4);
+        assert_eq!(profile.recent_runs.last().unwrap().score, 5);
+    }
+
+    #[test]
+    fn keeps_the_best_score_per_mode() {
+        let mut profile = Profile::default();
+        profile.push_run(run(300));
+        profile.push_run(run(100));
+
+        assert_eq!(profile.best_score(GameMode::Classic), Some(300));
+        assert_eq!(profile.best_score(GameMode::Kids), None);
+    }
+
+    #[test]
+    fn edited_profiles_load_unverified() {
+        let mut profile = Profile::default();
+        profile.push_run(run(300));
+        let json = encode(&profile).unwrap();
+        assert_eq!(decode::<Profile>(&json), Ok(profile));
+
+        let edited = json.replace("\"campaign_cleared\": 0", "\"campaign_cleared\": 40");
+        assert_ne!(edited, json);
+        let loaded = decode::<Profile>(&edited).unwrap();
+        assert_eq!(loaded.campaign_cleared, 40);
+        assert!(loaded.unverified);
+
+        // Re-saving keeps the mark
+        assert!(
+            decode::<Profile>(&encode(&loaded).unwrap())
+                .unwrap()
+                .unverified
+        );
+
+        // Saved before signing existed, or with the signature stripped
+        let signed = json.replace("\"version\": 2", "\"version\": 1");
+        let unsigned = r#"{ "version": 2, "data": { "campaign_cleared": 3 } }"#;
+        let bare = r#"{ "campaign_cleared": 3 }"#;
+        for contents in [signed.as_str(), unsigned, bare] {
+            let loaded = decode::<Profile>(contents).unwrap();
+            assert!(loaded.unverified, "{contents}");
+        }
+    }
+
+    #[test]
+    fn old_profiles_get_the_starter_modes() {
+        let profile: Profile = serde_json::from_str(r#"{"recent_runs": []}"#).unwrap();
+
+        assert!(profile.is_unlocked(SelectedMode::Endless));
+        assert!(profile.is_unlocked(SelectedMode::Puzzle));
+        assert!(!profile.is_unlocked(SelectedMode::Daily));
+    }
+}
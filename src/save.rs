@@ -0,0 +1,535 @@
+//! Versioned save files.
+//!
+//! Everything the game saves (high scores, settings, telemetry, drill times)
+//! goes through [`SaveFile`]. Where it ends up is up to the platform's
+//! [`Storage`]: files in the data directory on native builds (mirrored to
+//! Steam Cloud with the `steam` feature), and the browser's localStorage on
+//! the web. Each file is wrapped in an envelope
+//! that records its schema version:
+//!
+//! ```json
+//! { "version": 2, "data": { ... } }
+//! ```
+//!
+//! When a type's schema changes, add a [`Migration`] to its `MIGRATIONS`
+//! that rewrites the previous version's JSON. Loading runs every migration
+//! newer than the file, so old files keep loading after schema changes.
+//! Files from before envelopes existed are read as version 1.
+//!
+//! Files the player shouldn't edit by hand (the profile) set `SIGNED`, and
+//! their envelope also carries an HMAC of the version and data. A file whose
+//! signature is missing or doesn't match still loads, but is marked
+//! unverified through [`SaveFile::mark_unverified`]. The key ships inside the
+//! binary, so this stops casual edits, not a determined cheater.
+//!
+//! A file that still can't be loaded is copied to `<name>.bak` before the
+//! defaults are used, so a failed load never silently loses data.
+
+#[cfg(not(target_arch = "wasm32"))]
+use std::fs;
+use std::path::PathBuf;
+
+use bevy::prelude::*;
+use hmac_sha256::HMAC;
+use serde::{Serialize, de::DeserializeOwned};
+use serde_json::{Value, json};
+
+/// Rewrite one version's JSON into the next version's.
+pub type Migration = fn(Value) -> Result<Value, String>;
+
+/// Why a save file couldn't be loaded.
+#[derive(Debug, Clone, PartialEq)]
+pub enum LoadError {
+    /// Not valid JSON, or not the expected shape after migrating.
+    Parse(String),
+    /// Written by a newer build than this one.
+    TooNew { version: u32, supported: u32 },
+    /// A migration gave up.
+    Migration { from: u32, message: String },
+}
+
+impl std::fmt::Display for LoadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LoadError::Parse(message) => write!(f, "{message}"),
+            LoadError::TooNew { version, supported } => {
+                write!(f, "version {version} is newer than supported {supported}")
+            }
+            LoadError::Migration { from, message } => {
+                write!(f, "migrating from version {from} failed: {message}")
+            }
+        }
+    }
+}
+
+/// A JSON file in the platform's [`Storage`] with a versioned schema.
+pub trait SaveFile: Serialize + DeserializeOwned + Default {
+    /// File name inside the data directory, or localStorage key on the web.
+    const FILE_NAME: &'static str;
+    /// What the file holds, for log messages.
+    const DESCRIPTION: &'static str;
+    /// Schema upgrades, oldest first: `MIGRATIONS[i]` turns version `i + 1`
+    /// into version `i + 2`.
+    const MIGRATIONS: &'static [Migration] = &[];
+    /// Whether the envelope carries a signature.
+    const SIGNED: bool = false;
+
+    /// The schema version this build writes.
+    fn version() -> u32 {
+        Self::MIGRATIONS.len() as u32 + 1
+    }
+
+    /// Called after loading a signed file whose signature is missing or
+    /// doesn't match: edited by hand, saved before signing, or signed by
+    /// another build.
+    fn mark_unverified(&mut self) {}
+
+    /// Load from the platform's storage, or the default if there's no file
+    /// or it can't be read.
+    fn load() -> Self {
+        storage().map_or_else(Self::default, |storage| Self::load_from(&*storage))
+    }
+
+    /// Save to the platform's storage in the current version's envelope.
+    fn save(&self) {
+        if let Some(storage) = storage() {
+            self.save_to(&*storage);
+        }
+    }
+
+    /// Load from `storage`, or the default if there's no file or it can't be
+    /// read.
+    fn load_from(storage: &dyn Storage) -> Self {
+        let contents = match storage.read(Self::FILE_NAME) {
+            Ok(Some(contents)) => contents,
+            Ok(None) => return Self::default(),
+            Err(e) => {
+                warn!("Failed to read {} file: {}", Self::DESCRIPTION, e);
+                return Self::default();
+            }
+        };
+
+        match decode::<Self>(&contents) {
+            Ok(data) => {
+                info!(
+                    "Loaded {} from {}",
+                    Self::DESCRIPTION,
+                    storage.describe(Self::FILE_NAME)
+                );
+                data
+            }
+            Err(e) => {
+                warn!("Failed to load {}: {}", Self::DESCRIPTION, e);
+                let backup = format!("{}.bak", Self::FILE_NAME);
+                match storage.write(&backup, &contents) {
+                    Ok(()) => warn!("Kept the unreadable file as {}", storage.describe(&backup)),
+                    Err(e) => warn!("Failed to back up {}: {}", Self::DESCRIPTION, e),
+                }
+                Self::default()
+            }
+        }
+    }
+
+    /// Save to `storage` in the current version's envelope.
+    fn save_to(&self, storage: &dyn Storage) {
+        match encode(self) {
+            Ok(json) => {
+                if let Err(e) = storage.write(Self::FILE_NAME, &json) {
+                    warn!("Failed to write {}: {}", Self::DESCRIPTION, e);
+                }
+            }
+            Err(e) => warn!("Failed to serialize {}: {}", Self::DESCRIPTION, e),
+        }
+    }
+}
+
+/// Somewhere save files can be kept, by name.
+pub trait Storage {
+    /// What's saved under `name`, or `None` if nothing is.
+    fn read(&self, name: &str) -> Result<Option<String>, String>;
+    /// Save `contents` under `name`, replacing what was there.
+    fn write(&self, name: &str, contents: &str) -> Result<(), String>;
+    /// Where `name` is kept, for log messages.
+    fn describe(&self, name: &str) -> String;
+}
+
+/// This platform's storage, or `None` if there's nowhere to save: no data
+/// directory, or a browser with localStorage turned off.
+pub fn storage() -> Option<Box<dyn Storage>> {
+    #[cfg(target_arch = "wasm32")]
+    return LocalStorage::open().map(|storage| Box::new(storage) as Box<dyn Storage>);
+
+    #[cfg(not(target_arch = "wasm32"))]
+    data_dir().map(|dir| {
+        let files = FileStorage { dir };
+        #[cfg(feature = "steam")]
+        if let Some(client) = crate::steam::client() {
+            let cloud = client.remote_storage();
+            if cloud.is_cloud_enabled_for_account() && cloud.is_cloud_enabled_for_app() {
+                return Box::new(SteamCloudStorage { files, cloud }) as Box<dyn Storage>;
+            }
+        }
+        Box::new(files) as Box<dyn Storage>
+    })
+}
+
+/// Persist a saved resource whenever it changes. Run it with
+/// `resource_changed::<T>`, which also fires when the resource is first
+/// added; there's nothing new to save then, so that run is skipped.
+pub fn save_on_change<T: Resource + SaveFile>(resource: Res<T>) {
+    if resource.is_added() {
+        return;
+    }
+    resource.save();
+}
+
+/// Files in the data directory.
+#[cfg(not(target_arch = "wasm32"))]
+struct FileStorage {
+    dir: PathBuf,
+}
+
+#[cfg(all(feature = "steam", not(target_arch = "wasm32")))]
+impl FileStorage {
+    /// When `name` was last written, in seconds since the Unix epoch.
+    fn modified(&self, name: &str) -> Option<i64> {
+        let modified = fs::metadata(self.dir.join(name)).ok()?.modified().ok()?;
+        let since_epoch = modified.duration_since(std::time::UNIX_EPOCH).ok()?;
+        i64::try_from(since_epoch.as_secs()).ok()
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Storage for FileStorage {
+    fn read(&self, name: &str) -> Result<Option<String>, String> {
+        let path = self.dir.join(name);
+        if !path.exists() {
+            return Ok(None);
+        }
+        fs::read_to_string(path)
+            .map(Some)
+            .map_err(|e| e.to_string())
+    }
+
+    fn write(&self, name: &str, contents: &str) -> Result<(), String> {
+        fs::create_dir_all(&self.dir).map_err(|e| format!("creating directory: {e}"))?;
+        fs::write(self.dir.join(name), contents).map_err(|e| e.to_string())
+    }
+
+    fn describe(&self, name: &str) -> String {
+        format!("{:?}", self.dir.join(name))
+    }
+}
+
+/// Files in the data directory, mirrored to Steam Cloud. Reads take whichever
+/// copy was saved last, so progress follows the player between machines
+/// without a newer local save (from a run played offline, say) being
+/// replaced by an older cloud one.
+#[cfg(all(feature = "steam", not(target_arch = "wasm32")))]
+struct SteamCloudStorage {
+    files: FileStorage,
+    cloud: steamworks::RemoteStorage,
+}
+
+#[cfg(all(feature = "steam", not(target_arch = "wasm32")))]
+impl Storage for SteamCloudStorage {
+    fn read(&self, name: &str) -> Result<Option<String>, String> {
+        use std::io::Read;
+
+        let file = self.cloud.file(name);
+        if !file.exists() {
+            return self.files.read(name);
+        }
+        if self
+            .files
+            .modified(name)
+            .is_some_and(|local| local > file.timestamp())
+        {
+            info!("Local {} is newer than the Steam Cloud copy", name);
+            return self.files.read(name);
+        }
+        let mut contents = String::new();
+        file.read()
+            .read_to_string(&mut contents)
+            .map_err(|e| format!("reading from Steam Cloud: {e}"))?;
+        Ok(Some(contents))
+    }
+
+    fn write(&self, name: &str, contents: &str) -> Result<(), String> {
+        use std::io::Write;
+
+        // Cloud first, so the local copy is never the older one. The cloud
+        // write is committed when the writer is dropped.
+        let cloud = self
+            .cloud
+            .file(name)
+            .write()
+            .write_all(contents.as_bytes())
+            .map_err(|e| format!("saved locally, but not to Steam Cloud: {e}"));
+        self.files.write(name, contents)?;
+        cloud
+    }
+
+    fn describe(&self, name: &str) -> String {
+        format!("{} and Steam Cloud", self.files.describe(name))
+    }
+}
+
+/// The browser's localStorage, under keys prefixed with [`LocalStorage::PREFIX`]
+/// so they don't clash with other games on the same site.
+#[cfg(target_arch = "wasm32")]
+struct LocalStorage(web_sys::Storage);
+
+#[cfg(target_arch = "wasm32")]
+impl LocalStorage {
+    const PREFIX: &'static str = "snord/";
+
+    fn open() -> Option<Self> {
+        let storage = web_sys::window()?.local_storage().ok().flatten();
+        if storage.is_none() {
+            warn!("localStorage isn't available, so nothing will be saved");
+        }
+        storage.map(Self)
+    }
+
+    fn key(name: &str) -> String {
+        format!("{}{name}", Self::PREFIX)
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+impl Storage for LocalStorage {
+    fn read(&self, name: &str) -> Result<Option<String>, String> {
+        self.0
+            .get_item(&Self::key(name))
+            .map_err(|e| format!("{e:?}"))
+    }
+
+    fn write(&self, name: &str, contents: &str) -> Result<(), String> {
+        // Fails when the browser's quota is full
+        self.0
+            .set_item(&Self::key(name), contents)
+            .map_err(|e| format!("{e:?}"))
+    }
+
+    fn describe(&self, name: &str) -> String {
+        format!("localStorage {:?}", Self::key(name))
+    }
+}
+
+/// The directory save files are written to.
+/// Returns None on WASM targets where filesystem access is not available.
+pub fn data_dir() -> Option<PathBuf> {
+    #[cfg(target_arch = "wasm32")]
+    return None;
+
+    #[cfg(not(target_arch = "wasm32"))]
+    dirs::data_local_dir().map(|dir| dir.join("snord"))
+}
+
+/// Key for save file and high score signatures. Builds can embed their own
+/// by setting `SNORD_SIGNING_KEY` at compile time.
+const SIGNING_KEY: &str = match option_env!("SNORD_SIGNING_KEY") {
+    Some(key) => key,
+    None => "snord-local-saves",
+};
+
+/// Hex-encoded HMAC-SHA256 of `message`.
+pub fn sign(message: &str) -> String {
+    HMAC::mac(message.as_bytes(), SIGNING_KEY.as_bytes())
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+/// The signature of a signed file's envelope.
+fn envelope_signature(version: u32, data: &Value) -> String {
+    sign(&format!("{version}:{data}"))
+}
+
+/// Wrap `data` in an envelope with the current version, signed if `T` is.
+pub fn encode<T: SaveFile>(data: &T) -> serde_json::Result<String> {
+    let version = T::version();
+    let data = serde_json::to_value(data)?;
+    let mut envelope = json!({ "version": version });
+    if T::SIGNED {
+        envelope["signature"] = json!(envelope_signature(version, &data));
+    }
+    envelope["data"] = data;
+    serde_json::to_string_pretty(&envelope)
+}
+
+/// Read an envelope (or a pre-envelope file), migrating it to the current
+/// version.
+pub fn decode<T: SaveFile>(contents: &str) -> Result<T, LoadError> {
+    let value: Value =
+        serde_json::from_str(contents).map_err(|e| LoadError::Parse(e.to_string()))?;
+    let (mut version, mut data, signature) = split_envelope(value);
+
+    let supported = T::version();
+    if version > supported {
+        return Err(LoadError::TooNew { version, supported });
+    }
+    let verified = !T::SIGNED || signature == Some(envelope_signature(version, &data));
+    while version < supported {
+        let migrate = T::MIGRATIONS[version as usize - 1];
+        data = migrate(data).map_err(|message| LoadError::Migration {
+            from: version,
+            message,
+        })?;
+        version += 1;
+    }
+
+    let mut loaded: T =
+        serde_json::from_value(data).map_err(|e| LoadError::Parse(e.to_string()))?;
+    if !verified {
+        loaded.mark_unverified();
+    }
+    Ok(loaded)
+}
+
+/// Split an envelope into its version, data, and signature. Anything else is
+/// a file from before envelopes, which is version 1.
+fn split_envelope(value: Value) -> (u32, Value, Option<String>) {
+    if let Value::Object(mut map) = value {
+        let version = map
+            .get("version")
+            .and_then(Value::as_u64)
+            .and_then(|version| u32::try_from(version).ok())
+            .filter(|&version| version >= 1);
+        let signature = map
+            .get("signature")
+            .and_then(Value::as_str)
+            .map(str::to_string);
+        let expected_len = if signature.is_some() { 3 } else { 2 };
+        if let Some(version) = version
+            && map.len() == expected_len
+            && let Some(data) = map.remove("data")
+        {
+            return (version, data, signature);
+        }
+        return (1, Value::Object(map), None);
+    }
+    (1, value, None)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, collections::HashMap};
+
+    use serde::Deserialize;
+
+    use super::*;
+
+    /// Storage that only lasts as long as the test.
+    #[derive(Default)]
+    struct MemoryStorage(RefCell<HashMap<String, String>>);
+
+    impl Storage for MemoryStorage {
+        fn read(&self, name: &str) -> Result<Option<String>, String> {
+            Ok(self.0.borrow().get(name).cloned())
+        }
+
+        fn write(&self, name: &str, contents: &str) -> Result<(), String> {
+            self.0
+                .borrow_mut()
+                .insert(name.to_string(), contents.to_string());
+            Ok(())
+        }
+
+        fn describe(&self, name: &str) -> String {
+            name.to_string()
+        }
+    }
+
+    /// Version 1 stored a name; version 2 splits it; version 3 adds a level.
+    #[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+    struct Profile {
+        first: String,
+        last: String,
+        level: u32,
+    }
+
+    impl SaveFile for Profile {
+        const FILE_NAME: &'static str = "profile.json";
+        const DESCRIPTION: &'static str = "profile";
+        const MIGRATIONS: &'static [Migration] = &[split_name, add_level];
+    }
+
+    fn split_name(mut value: Value) -> Result<Value, String> {
+        let name = value
+            .get("name")
+            .and_then(Value::as_str)
+            .ok_or("missing name")?
+            .to_string();
+        let (first, last) = name.split_once(' ').unwrap_or((&name, ""));
+        value["first"] = json!(first);
+        value["last"] = json!(last);
+        Ok(value)
+    }
+
+    fn add_level(mut value: Value) -> Result<Value, String> {
+        value["level"] = json!(1);
+        Ok(value)
+    }
+
+    fn ada() -> Profile {
+        Profile {
+            first: "Ada".to_string(),
+            last: "Lovelace".to_string(),
+            level: 1,
+        }
+    }
+
+    #[test]
+    fn round_trips_current_version() {
+        let profile = ada();
+        let json = encode(&profile).unwrap();
+        assert!(json.contains("\"version\": 3"));
+        assert_eq!(decode::<Profile>(&json), Ok(profile));
+    }
+
+    #[test]
+    fn migrates_files_from_before_envelopes() {
+        let legacy = r#"{ "name": "Ada Lovelace" }"#;
+        assert_eq!(decode::<Profile>(legacy), Ok(ada()));
+    }
+
+    #[test]
+    fn migrates_older_envelopes() {
+        let v2 = r#"{ "version": 2, "data": { "first": "Ada", "last": "Lovelace" } }"#;
+        assert_eq!(decode::<Profile>(v2), Ok(ada()));
+    }
+
+    #[test]
+    fn rejects_newer_versions_and_failed_migrations() {
+        let v9 = r#"{ "version": 9, "data": {} }"#;
+        assert_eq!(
+            decode::<Profile>(v9),
+            Err(LoadError::TooNew {
+                version: 9,
+                supported: 3
+            })
+        );
+        assert!(matches!(
+            decode::<Profile>("{}"),
+            Err(LoadError::Migration { from: 1, .. })
+        ));
+    }
+
+    #[test]
+    fn saves_through_storage_and_backs_up_unreadable_files() {
+        let storage = MemoryStorage::default();
+        assert_eq!(Profile::load_from(&storage), Profile::default());
+
+        ada().save_to(&storage);
+        assert_eq!(Profile::load_from(&storage), ada());
+
+        storage.write("profile.json", "not json").unwrap();
+        assert_eq!(Profile::load_from(&storage), Profile::default());
+        assert_eq!(
+            storage.read("profile.json.bak"),
+            Ok(Some("not json".to_string()))
+        );
+    }
+}
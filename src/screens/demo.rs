@@ -0,0 +1,92 @@
+//! The attract-mode screen: a bot plays under a "DEMO" banner until any input.
+//!
+//! The title screen switches here after sitting idle. The board and the bot
+//! live in `game/demo.rs`; this screen only adds the banner and the ways out.
+
+use bevy::{input::touch::Touches, prelude::*};
+
+use crate::{
+    PausableSystems, Pause,
+    menus::Menu,
+    screens::Screen,
+    theme::{GameFont, prelude::*},
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(OnEnter(Screen::Demo), spawn_demo_banner);
+    app.add_systems(OnExit(Screen::Demo), unpause);
+    app.add_systems(
+        Update,
+        (
+            return_to_title.run_if(any_input_just_pressed),
+            end_demo_on_menu_request.after(PausableSystems),
+        )
+            .run_if(in_state(Screen::Demo)),
+    );
+}
+
+fn spawn_demo_banner(mut commands: Commands, game_font: Option<Res<GameFont>>) {
+    let font = game_font.map(|font| font.0.clone()).unwrap_or_default();
+    commands.spawn((
+        Name::new("Demo Banner"),
+        Node {
+            position_type: PositionType::Absolute,
+            width: percent(100),
+            top: px(40),
+            flex_direction: FlexDirection::Column,
+            align_items: AlignItems::Center,
+            row_gap: px(6),
+            ..default()
+        },
+        GlobalZIndex(1),
+        Pickable::IGNORE,
+        DespawnOnExit(Screen::Demo),
+        children![
+            (
+                Name::new("Demo Title"),
+                Text::new("DEMO"),
+                widget::game_font(font.clone(), 56.0),
+                TextColor(ui_palette::HEADER_TEXT),
+            ),
+            (
+                Name::new("Demo Hint"),
+                Text::new("Press any key"),
+                widget::game_font(font, 22.0),
+                TextColor(ui_palette::LABEL_TEXT),
+            ),
+        ],
+    ));
+}
+
+fn any_input_just_pressed(
+    keys: Res<ButtonInput<KeyCode>>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    touches: Res<Touches>,
+) -> bool {
+    keys.get_just_pressed().next().is_some()
+        || mouse.get_just_pressed().next().is_some()
+        || touches.any_just_pressed()
+}
+
+fn return_to_title(mut next_screen: ResMut<NextState<Screen>>) {
+    next_screen.set(Screen::Title);
+}
+
+/// The run tried to open a menu (game over, a win, a power-up pick). Nobody is
+/// there to answer it, so cancel it and go back to the title screen instead.
+fn end_demo_on_menu_request(
+    mut next_menu: ResMut<NextState<Menu>>,
+    mut next_screen: ResMut<NextState<Screen>>,
+) {
+    // Leaving the title screen queues `Menu::None`, which is fine
+    if !matches!(*next_menu, NextState::Pending(menu) if menu != Menu::None) {
+        return;
+    }
+    info!("Demo run over, returning to title");
+    next_menu.reset();
+    next_screen.set(Screen::Title);
+}
+
+fn unpause(mut next_pause: ResMut<NextState<Pause>>) {
+    next_pause.set(Pause(false));
+}
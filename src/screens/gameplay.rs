@@ -2,24 +2,44 @@
 
 use bevy::{input::common_conditions::input_just_pressed, prelude::*};
 
-use crate::{Pause, game::spawn_game, menus::Menu, screens::Screen};
+use crate::{
+    PausableSystems, Pause,
+    controls::{InputAction, action_just_pressed},
+    game::spawn_game,
+    menus::Menu,
+    screens::{InGame, RunPhase, Screen},
+    web_support::page_hidden,
+};
 
 pub(super) fn plugin(app: &mut App) {
-    app.add_systems(OnEnter(Screen::Gameplay), spawn_game);
+    app.add_systems(OnEnter(InGame), spawn_game);
 
-    // Toggle pause on key press.
+    // The board is spawned on entering, so play starts on the next frame. The
+    // descent is instant, so its phase only holds for a frame too.
+    app.add_systems(OnEnter(RunPhase::Setup), start_playing);
+    app.add_systems(
+        Update,
+        start_playing
+            .before(PausableSystems)
+            .run_if(in_state(RunPhase::DescentAnimation)),
+    );
+
+    // Toggle pause on the pause key or button, and pause when the tab is
+    // hidden. Escape pauses too, but closes menus on its own.
     app.add_systems(
         Update,
         (
             (pause, spawn_pause_overlay, open_pause_menu).run_if(
-                in_state(Screen::Gameplay)
-                    .and(in_state(Menu::None))
-                    .and(input_just_pressed(KeyCode::KeyP).or(input_just_pressed(KeyCode::Escape))),
+                in_state(Screen::Gameplay).and(in_state(Menu::None)).and(
+                    action_just_pressed(InputAction::Pause)
+                        .or(input_just_pressed(KeyCode::Escape))
+                        .or(page_hidden),
+                ),
             ),
             close_menu.run_if(
                 in_state(Screen::Gameplay)
                     .and(not(in_state(Menu::None)))
-                    .and(input_just_pressed(KeyCode::KeyP)),
+                    .and(action_just_pressed(InputAction::Pause)),
             ),
         ),
     );
@@ -30,6 +50,10 @@ pub(super) fn plugin(app: &mut App) {
     );
 }
 
+fn start_playing(mut next_phase: ResMut<NextState<RunPhase>>) {
+    next_phase.set(RunPhase::Playing);
+}
+
 fn unpause(mut next_pause: ResMut<NextState<Pause>>) {
     next_pause.set(Pause(false));
 }
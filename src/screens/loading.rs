@@ -1,27 +1,170 @@
 //! A loading screen during which game assets are loaded if necessary.
 //! This reduces stuttering, especially for audio on Wasm.
+//!
+//! Shows a progress bar and rotating gameplay tips so long web loads don't
+//! look frozen.
 
 use bevy::prelude::*;
+use rand::Rng;
 
-use crate::{asset_tracking::ResourceHandles, screens::Screen, theme::prelude::*};
+use crate::{AppSystems, asset_tracking::ResourceHandles, screens::Screen, theme::prelude::*};
 
 pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<TipTimer>();
     app.add_systems(OnEnter(Screen::Loading), spawn_loading_screen);
 
+    app.add_systems(
+        Update,
+        (
+            tick_tip_timer.in_set(AppSystems::TickTimers),
+            (update_progress, rotate_tip).in_set(AppSystems::Update),
+        )
+            .run_if(in_state(Screen::Loading)),
+    );
     app.add_systems(
         Update,
         enter_gameplay_screen.run_if(in_state(Screen::Loading).and(all_assets_loaded)),
     );
 }
 
-fn spawn_loading_screen(mut commands: Commands) {
+/// Tips are compiled in, so they show before anything has loaded.
+const TIPS_FILE: &str = include_str!("../../assets/data/tips.txt");
+
+/// Seconds each tip stays on screen.
+const TIP_SECONDS: f32 = 4.0;
+
+/// Width of the progress bar in pixels.
+const BAR_WIDTH: f32 = 320.0;
+
+/// Non-empty, non-comment lines of the tips file.
+fn tips() -> Vec<&'static str> {
+    TIPS_FILE
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .collect()
+}
+
+/// Timer for switching to the next tip.
+#[derive(Resource)]
+struct TipTimer(Timer);
+
+impl Default for TipTimer {
+    fn default() -> Self {
+        Self(Timer::from_seconds(TIP_SECONDS, TimerMode::Repeating))
+    }
+}
+
+/// Marker for the filled part of the progress bar.
+#[derive(Component)]
+struct ProgressFill;
+
+/// Marker for the "Loading X/Y assets" text.
+#[derive(Component)]
+struct ProgressText;
+
+/// The tip currently shown, by index into [`tips`].
+#[derive(Component)]
+struct TipText(usize);
+
+fn spawn_loading_screen(mut commands: Commands, mut tip_timer: ResMut<TipTimer>) {
+    tip_timer.0.reset();
+    let tips = tips();
+    let first_tip = if tips.is_empty() {
+        0
+    } else {
+        rand::rng().random_range(0..tips.len())
+    };
+
     commands.spawn((
         widget::ui_root("Loading Screen"),
         DespawnOnExit(Screen::Loading),
-        children![widget::label("Loading...")],
+        children![
+            widget::label("Loading..."),
+            (
+                Name::new("Progress Bar"),
+                Node {
+                    width: Val::Px(BAR_WIDTH),
+                    height: Val::Px(16.0),
+                    ..default()
+                },
+                BackgroundColor(ui_palette::BUTTON_PRESSED_BACKGROUND),
+                children![(
+                    Name::new("Progress Fill"),
+                    ProgressFill,
+                    Node {
+                        width: Val::Percent(0.0),
+                        height: Val::Percent(100.0),
+                        ..default()
+                    },
+                    BackgroundColor(ui_palette::BUTTON_HOVERED_BACKGROUND),
+                )],
+            ),
+            (
+                Name::new("Progress Text"),
+                ProgressText,
+                Text::default(),
+                TextFont::from_font_size(18.0),
+                TextColor(ui_palette::LABEL_TEXT),
+            ),
+            (
+                Name::new("Tip"),
+                TipText(first_tip),
+                Text(
+                    tips.get(first_tip)
+                        .map(|tip| format!("Tip: {tip}"))
+                        .unwrap_or_default()
+                ),
+                TextFont::from_font_size(18.0),
+                TextColor(ui_palette::LABEL_TEXT),
+                TextLayout::new_with_justify(Justify::Center),
+                Node {
+                    max_width: Val::Px(480.0),
+                    ..default()
+                },
+            ),
+        ],
     ));
 }
 
+fn update_progress(
+    resource_handles: Res<ResourceHandles>,
+    mut fill_query: Query<&mut Node, With<ProgressFill>>,
+    mut text_query: Query<&mut Text, With<ProgressText>>,
+) {
+    let (done, total) = resource_handles.progress();
+    let fraction = if total == 0 {
+        1.0
+    } else {
+        done as f32 / total as f32
+    };
+
+    for mut node in &mut fill_query {
+        node.width = Val::Percent(fraction * 100.0);
+    }
+    for mut text in &mut text_query {
+        text.0 = format!("Loading {done}/{total} assets");
+    }
+}
+
+fn tick_tip_timer(time: Res<Time<Real>>, mut tip_timer: ResMut<TipTimer>) {
+    tip_timer.0.tick(time.delta());
+}
+
+fn rotate_tip(tip_timer: Res<TipTimer>, mut tip_query: Query<(&mut Text, &mut TipText)>) {
+    if !tip_timer.0.just_finished() {
+        return;
+    }
+    let tips = tips();
+    if tips.is_empty() {
+        return;
+    }
+    for (mut text, mut tip) in &mut tip_query {
+        tip.0 = (tip.0 + 1) % tips.len();
+        text.0 = format!("Tip: {}", tips[tip.0]);
+    }
+}
+
 fn enter_gameplay_screen(mut next_screen: ResMut<NextState<Screen>>) {
     next_screen.set(Screen::Gameplay);
 }
@@ -1,7 +1,9 @@
 //! The game's main screen states and transitions between them.
 
+mod demo;
 mod gameplay;
 mod loading;
+mod replay;
 mod splash;
 mod title;
 
@@ -9,10 +11,14 @@ use bevy::prelude::*;
 
 pub(super) fn plugin(app: &mut App) {
     app.init_state::<Screen>();
+    app.add_computed_state::<InGame>();
+    app.add_sub_state::<RunPhase>();
 
     app.add_plugins((
+        demo::plugin,
         gameplay::plugin,
         loading::plugin,
+        replay::plugin,
         splash::plugin,
         title::plugin,
     ));
@@ -26,4 +32,65 @@ pub enum Screen {
     Title,
     Loading,
     Gameplay,
+    /// Attract mode: a bot plays while the title screen sits idle.
+    Demo,
+    /// The level editor.
+    Editor,
+    /// The last recorded run playing back.
+    ReplayViewer,
+}
+
+/// Active while a board is in play, whether the player, the demo bot, or a
+/// replay is playing it. Board, projectile, and effect systems run in this state;
+/// systems that need a human (input, pausing, scores) stay on
+/// [`Screen::Gameplay`].
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct InGame;
+
+impl ComputedStates for InGame {
+    type SourceStates = Screen;
+
+    fn compute(screen: Screen) -> Option<Self> {
+        matches!(
+            screen,
+            Screen::Gameplay | Screen::Demo | Screen::ReplayViewer
+        )
+        .then_some(InGame)
+    }
+}
+
+/// The phase of the run in play, from the board being set up to the run
+/// ending. Gameplay flow (power-up picks, game over) moves between phases
+/// rather than pausing the game, so systems can gate on the phase they
+/// belong to. [`Pause`](crate::Pause) is only for the player pausing.
+#[derive(SubStates, Copy, Clone, Eq, PartialEq, Hash, Debug, Default)]
+#[source(InGame = InGame)]
+pub enum RunPhase {
+    /// The board is being spawned. Lasts one frame.
+    #[default]
+    Setup,
+    Playing,
+    /// The grid is descending a row. Shots can't be fired.
+    DescentAnimation,
+    /// The power-up menu is open; the board is frozen.
+    PowerUpChoice,
+    /// The run is over and its menu is showing; the board is frozen.
+    GameEnding,
+    /// A dialogue scene is playing over the board, which is frozen.
+    Dialogue,
+}
+
+impl RunPhase {
+    /// Whether the board stands still in this phase.
+    pub fn is_frozen(self) -> bool {
+        matches!(
+            self,
+            RunPhase::PowerUpChoice | RunPhase::GameEnding | RunPhase::Dialogue
+        )
+    }
+}
+
+/// Run condition: a run is in play and its phase has the board frozen.
+pub fn run_frozen(phase: Option<Res<State<RunPhase>>>) -> bool {
+    phase.is_some_and(|phase| phase.is_frozen())
 }
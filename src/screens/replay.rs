@@ -0,0 +1,102 @@
+//! The replay viewer: the last recorded run plays back under a "REPLAY"
+//! banner, or "FINAL SHOTS" when only its last shots are playing.
+//!
+//! Playback lives in `game/replay.rs`; this screen only adds the banner, the
+//! shot counter, and the way out.
+
+use bevy::{input::common_conditions::input_just_pressed, prelude::*};
+
+use crate::{
+    Pause,
+    game::replay::ReplayPlayback,
+    screens::Screen,
+    theme::{GameFont, prelude::*},
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(OnEnter(Screen::ReplayViewer), spawn_replay_banner);
+    app.add_systems(OnExit(Screen::ReplayViewer), unpause);
+    app.add_systems(
+        Update,
+        (
+            update_shot_counter,
+            return_to_title.run_if(input_just_pressed(KeyCode::Escape)),
+        )
+            .run_if(in_state(Screen::ReplayViewer)),
+    );
+}
+
+/// Marker for the "Shot 3 of 40" line under the banner.
+#[derive(Component)]
+struct ShotCounter;
+
+fn spawn_replay_banner(
+    mut commands: Commands,
+    game_font: Option<Res<GameFont>>,
+    playback: Res<ReplayPlayback>,
+) {
+    let font = game_font.map(|font| font.0.clone()).unwrap_or_default();
+    let title = if playback.is_rewound() {
+        "FINAL SHOTS"
+    } else {
+        "REPLAY"
+    };
+    commands.spawn((
+        Name::new("Replay Banner"),
+        Node {
+            position_type: PositionType::Absolute,
+            width: percent(100),
+            top: px(40),
+            flex_direction: FlexDirection::Column,
+            align_items: AlignItems::Center,
+            row_gap: px(6),
+            ..default()
+        },
+        GlobalZIndex(1),
+        Pickable::IGNORE,
+        DespawnOnExit(Screen::ReplayViewer),
+        children![
+            (
+                Name::new("Replay Title"),
+                Text::new(title),
+                widget::game_font(font.clone(), 56.0),
+                TextColor(ui_palette::HEADER_TEXT),
+            ),
+            (
+                Name::new("Replay Shot Counter"),
+                ShotCounter,
+                Text::new(""),
+                widget::game_font(font.clone(), 22.0),
+                TextColor(ui_palette::LABEL_TEXT),
+            ),
+            (
+                Name::new("Replay Hint"),
+                Text::new("Esc to leave"),
+                widget::game_font(font, 18.0),
+                TextColor(ui_palette::LABEL_TEXT),
+            ),
+        ],
+    ));
+}
+
+fn update_shot_counter(
+    playback: Res<ReplayPlayback>,
+    mut counter: Single<&mut Text, With<ShotCounter>>,
+) {
+    if !playback.is_changed() {
+        return;
+    }
+    counter.0 = format!(
+        "Shot {} of {}",
+        playback.next_shot,
+        playback.log.shots.len()
+    );
+}
+
+fn return_to_title(mut next_screen: ResMut<NextState<Screen>>) {
+    next_screen.set(Screen::Title);
+}
+
+fn unpause(mut next_pause: ResMut<NextState<Pause>>) {
+    next_pause.set(Pause(false));
+}
@@ -100,7 +100,7 @@ impl ImageNodeFadeInOut {
     }
 }
 
-fn tick_fade_in_out(time: Res<Time>, mut animation_query: Query<&mut ImageNodeFadeInOut>) {
+fn tick_fade_in_out(time: Res<Time<Real>>, mut animation_query: Query<&mut ImageNodeFadeInOut>) {
     for mut anim in &mut animation_query {
         anim.t += time.delta_secs();
     }
@@ -130,7 +130,7 @@ fn remove_splash_timer(mut commands: Commands) {
     commands.remove_resource::<SplashTimer>();
 }
 
-fn tick_splash_timer(time: Res<Time>, mut timer: ResMut<SplashTimer>) {
+fn tick_splash_timer(time: Res<Time<Real>>, mut timer: ResMut<SplashTimer>) {
     timer.0.tick(time.delta());
 }
 
@@ -1,18 +1,88 @@
 //! The title screen that appears after the splash screen.
+//!
+//! Left idle on the main menu, it switches to the attract-mode demo.
 
-use bevy::prelude::*;
+use bevy::{input::touch::Touches, prelude::*};
 
-use crate::{menus::Menu, screens::Screen};
+use crate::{
+    accessibility::AccessibilitySettings, asset_tracking::ResourceHandles, game::mode::GameMode,
+    menus::Menu, screens::Screen,
+};
 
 pub(super) fn plugin(app: &mut App) {
-    app.add_systems(OnEnter(Screen::Title), open_main_menu);
+    app.init_resource::<TitleIdle>();
+
+    app.add_systems(OnEnter(Screen::Title), (open_main_menu, reset_idle_timer));
     app.add_systems(OnExit(Screen::Title), close_menu);
+    app.add_systems(
+        Update,
+        start_demo_when_idle.run_if(in_state(Screen::Title).and(in_state(Menu::Main))),
+    );
+}
+
+/// Seconds without input on the main menu before the demo starts.
+const IDLE_SECS: f32 = 30.0;
+
+/// Time since the last input on the title screen.
+#[derive(Resource)]
+struct TitleIdle(Timer);
+
+impl Default for TitleIdle {
+    fn default() -> Self {
+        Self(Timer::from_seconds(IDLE_SECS, TimerMode::Once))
+    }
 }
 
-fn open_main_menu(mut next_menu: ResMut<NextState<Menu>>) {
-    next_menu.set(Menu::Main);
+/// Open the main menu, or the accessibility prompt if it hasn't been answered,
+/// or What's New if the game has been updated since it was last shown.
+fn open_main_menu(
+    accessibility: Res<AccessibilitySettings>,
+    mut next_menu: ResMut<NextState<Menu>>,
+) {
+    next_menu.set(if !accessibility.prompt_seen {
+        Menu::Accessibility
+    } else if accessibility.whats_new_pending() {
+        Menu::WhatsNew
+    } else {
+        Menu::Main
+    });
 }
 
 fn close_menu(mut next_menu: ResMut<NextState<Menu>>) {
     next_menu.set(Menu::None);
 }
+
+fn reset_idle_timer(mut idle: ResMut<TitleIdle>) {
+    idle.0.reset();
+}
+
+fn start_demo_when_idle(
+    time: Res<Time<Real>>,
+    mut idle: ResMut<TitleIdle>,
+    keys: Res<ButtonInput<KeyCode>>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    touches: Res<Touches>,
+    mut cursor_moves: MessageReader<CursorMoved>,
+    resource_handles: Res<ResourceHandles>,
+    mut mode: ResMut<GameMode>,
+    mut next_screen: ResMut<NextState<Screen>>,
+) {
+    let moved = cursor_moves.read().count() > 0;
+    if moved
+        || keys.get_pressed().next().is_some()
+        || mouse.get_pressed().next().is_some()
+        || touches.iter().next().is_some()
+    {
+        idle.0.reset();
+        return;
+    }
+
+    idle.0.tick(time.delta());
+    // The demo plays without a loading screen, so wait for the assets
+    if !idle.0.is_finished() || !resource_handles.is_all_done() {
+        return;
+    }
+    info!("Title screen idle, starting demo");
+    *mode = GameMode::Classic;
+    next_screen.set(Screen::Demo);
+}
@@ -0,0 +1,190 @@
+//! Seasonal content: snords in winter hats through December, and spooky
+//! doodles through October.
+//!
+//! The season comes from today's date. Sprites with a seasonal variant are
+//! listed in `assets/images/seasons/seasons.txt`, and while their season is
+//! active [`SpriteLoader`](crate::textures::SpriteLoader) loads the variant
+//! from `images/seasons/<season>/` instead. The settings menu can force a
+//! season or turn seasonal content off, saved with the display settings.
+//! Game sprites are loaded as each game starts, so a change shows from the
+//! next game.
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::display::DisplaySettings;
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<ActiveSeason>();
+    app.register_type::<ActiveSeason>();
+
+    app.add_systems(
+        Update,
+        resolve_season.run_if(resource_changed::<DisplaySettings>),
+    );
+}
+
+/// Sprites with seasonal variants. One per line: `<season> <path without
+/// extension>`, e.g. `winter images/derpy`.
+const SEASONS_MANIFEST: &str = include_str!("../assets/images/seasons/seasons.txt");
+
+/// A season with its own sprites.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Reflect, Serialize, Deserialize)]
+pub enum Season {
+    Winter,
+    Spooky,
+}
+
+impl Season {
+    /// The season for a month (1 to 12), if it has one.
+    pub fn for_month(month: u32) -> Option<Self> {
+        match month {
+            10 => Some(Season::Spooky),
+            12 => Some(Season::Winter),
+            _ => None,
+        }
+    }
+
+    /// The folder under `images/seasons/`, and the name in the manifest.
+    pub fn dir(self) -> &'static str {
+        match self {
+            Season::Winter => "winter",
+            Season::Spooky => "spooky",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Season::Winter => "Winter",
+            Season::Spooky => "Spooky",
+        }
+    }
+}
+
+/// Whether seasonal content follows the date, is forced, or is off.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Reflect, Serialize, Deserialize)]
+pub enum SeasonalContent {
+    #[default]
+    Auto,
+    Forced(Season),
+    Off,
+}
+
+impl SeasonalContent {
+    /// The next option, wrapping around (for a cycling settings button).
+    pub fn next(self) -> Self {
+        match self {
+            SeasonalContent::Auto => SeasonalContent::Forced(Season::Winter),
+            SeasonalContent::Forced(Season::Winter) => SeasonalContent::Forced(Season::Spooky),
+            SeasonalContent::Forced(Season::Spooky) => SeasonalContent::Off,
+            SeasonalContent::Off => SeasonalContent::Auto,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            SeasonalContent::Auto => "Auto",
+            SeasonalContent::Forced(season) => season.label(),
+            SeasonalContent::Off => "Off",
+        }
+    }
+
+    /// The season to show in `month` (1 to 12, or None if the date is
+    /// unknown).
+    pub fn season(self, month: Option<u32>) -> Option<Season> {
+        match self {
+            SeasonalContent::Auto => month.and_then(Season::for_month),
+            SeasonalContent::Forced(season) => Some(season),
+            SeasonalContent::Off => None,
+        }
+    }
+}
+
+/// The season whose sprites are in use, if any.
+#[derive(Resource, Debug, Default, Clone, Copy, PartialEq, Eq, Reflect)]
+#[reflect(Resource)]
+pub struct ActiveSeason(pub Option<Season>);
+
+/// The path of `path`'s variant for `season` (both under `assets/`), if the
+/// manifest lists one.
+pub fn seasonal_variant(path: &str, season: Season) -> Option<String> {
+    let stem = path.strip_suffix(".png").unwrap_or(path);
+    let listed = SEASONS_MANIFEST
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| line.split_once(char::is_whitespace))
+        .any(|(name, listed)| name == season.dir() && listed.trim() == stem);
+    if !listed {
+        return None;
+    }
+    let (dir, file) = stem.rsplit_once('/')?;
+    Some(format!("{dir}/seasons/{}/{file}.png", season.dir()))
+}
+
+/// Today's month (1 to 12, in UTC), if the clock can be read.
+fn current_month() -> Option<u32> {
+    let now = web_time::SystemTime::now()
+        .duration_since(web_time::UNIX_EPOCH)
+        .ok()?;
+    Some(month_from_days((now.as_secs() / 86_400) as i64))
+}
+
+/// The month (1 to 12) of a day counted from 1970-01-01, using Howard
+/// Hinnant's civil calendar algorithm.
+fn month_from_days(days: i64) -> u32 {
+    let z = days + 719_468;
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    (if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    }) as u32
+}
+
+fn resolve_season(settings: Res<DisplaySettings>, mut active: ResMut<ActiveSeason>) {
+    let season = settings.seasonal.season(current_month());
+    if active.0 != season {
+        info!("Seasonal content: {}", season.map_or("none", Season::label));
+        active.0 = season;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn seasons_follow_the_calendar_unless_overridden() {
+        // 2025-10-31, 2025-11-01, 2025-12-25, and 2026-01-01
+        assert_eq!(month_from_days(20_392), 10);
+        assert_eq!(month_from_days(20_393), 11);
+        assert_eq!(month_from_days(20_447), 12);
+        assert_eq!(month_from_days(20_454), 1);
+
+        assert_eq!(SeasonalContent::Auto.season(Some(10)), Some(Season::Spooky));
+        assert_eq!(SeasonalContent::Auto.season(Some(12)), Some(Season::Winter));
+        assert_eq!(SeasonalContent::Auto.season(Some(7)), None);
+        assert_eq!(SeasonalContent::Auto.season(None), None);
+        assert_eq!(
+            SeasonalContent::Forced(Season::Winter).season(Some(7)),
+            Some(Season::Winter)
+        );
+        assert_eq!(SeasonalContent::Off.season(Some(12)), None);
+
+        assert_eq!(
+            seasonal_variant("images/derpy.png", Season::Winter).as_deref(),
+            Some("images/seasons/winter/derpy.png")
+        );
+        assert_eq!(
+            seasonal_variant("images/doodle_3.png", Season::Spooky).as_deref(),
+            Some("images/seasons/spooky/doodle_3.png")
+        );
+        assert_eq!(seasonal_variant("images/derpy.png", Season::Spooky), None);
+        assert_eq!(seasonal_variant("images/title.png", Season::Winter), None);
+    }
+}
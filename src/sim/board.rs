@@ -0,0 +1,158 @@
+//! The bubble grid: placement, clusters, and floating bubbles.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use bevy::prelude::Entity;
+
+use crate::game::rules::{
+    BoardLayout, BubbleColor, HexCoord, HexGrid, MIN_CLUSTER_SIZE, danger_depth, find_cluster,
+    find_floating_bubbles,
+};
+
+/// What would happen if a bubble landed in a cell.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ShotEvaluation {
+    /// Size of the same-colored group the bubble would join (including itself).
+    pub cluster: usize,
+    /// Bubbles popped (0 unless `cluster` reaches the minimum size).
+    pub popped: usize,
+    /// Bubbles that would fall after the pop.
+    pub dropped: usize,
+    /// Rows below the ceiling.
+    pub depth: i32,
+    /// Landing here ends the run (unless forgiven by the grace bounce).
+    pub in_danger: bool,
+}
+
+/// The game's grid with placeholder entities, the color of each colored
+/// cell, and the ceiling position.
+#[derive(Debug, Clone, Default)]
+pub struct Board {
+    /// Occupied cells, along with the bounds and hex size they're laid out in.
+    pub grid: HexGrid,
+    /// Colors by cell. Occupied cells without one (stones, bombs) block and
+    /// hold up bubbles but never join a cluster.
+    pub colors: HashMap<HexCoord, BubbleColor>,
+    /// Row of the ceiling; decreases by one with every descent.
+    pub top_r: i32,
+}
+
+impl Board {
+    /// Rows below the ceiling, as drawn on screen.
+    pub fn depth(&self, coord: HexCoord) -> i32 {
+        coord.r - self.top_r
+    }
+
+    /// Rows below the ceiling that a bubble ends the run at.
+    pub fn danger_depth(&self) -> i32 {
+        danger_depth(self.grid.hex_size)
+    }
+
+    /// Put a bubble of `color` at `coord`, or a colorless one (a stone) for
+    /// `None`.
+    pub fn insert(&mut self, coord: HexCoord, color: Option<BubbleColor>) {
+        self.grid.insert(coord, Entity::PLACEHOLDER);
+        match color {
+            Some(color) => self.colors.insert(coord, color),
+            None => self.colors.remove(&coord),
+        };
+    }
+
+    /// Empty cells a bubble could stick to.
+    ///
+    /// A cell counts as reachable if it touches the ceiling or another bubble
+    /// and is connected to the open space below the grid, so holes sealed off
+    /// by other bubbles can't be filled. Aiming and bank shots aren't modeled.
+    pub fn reachable_cells(&self) -> Vec<HexCoord> {
+        let bounds = self.grid.bounds;
+        let danger_depth = self.danger_depth();
+        let in_bounds = |c: &HexCoord| {
+            (bounds.min_q..=bounds.max_q).contains(&c.q)
+                && c.r >= self.top_r
+                && self.depth(*c) <= danger_depth
+        };
+
+        // Flood the open space up from the danger row
+        let mut open: HashSet<HexCoord> = (bounds.min_q..=bounds.max_q)
+            .map(|q| HexCoord::new(q, self.top_r + danger_depth))
+            .collect();
+        let mut queue: VecDeque<HexCoord> = open.iter().copied().collect();
+        while let Some(coord) = queue.pop_front() {
+            for neighbor in self.grid.layout.neighbors(coord) {
+                if in_bounds(&neighbor) && !self.grid.is_occupied(neighbor) && open.insert(neighbor)
+                {
+                    queue.push_back(neighbor);
+                }
+            }
+        }
+
+        let mut cells: Vec<HexCoord> = open
+            .into_iter()
+            .filter(|&c| {
+                c.r == self.top_r
+                    || self
+                        .grid
+                        .layout
+                        .neighbors(c)
+                        .any(|n| self.grid.is_occupied(n))
+            })
+            .collect();
+        // HashSet order isn't stable; sort so seeded runs are reproducible
+        cells.sort_by_key(|c| (c.r, c.q));
+        cells
+    }
+
+    /// Predict the result of landing `color` at the empty cell `target`.
+    pub fn evaluate(&self, target: HexCoord, color: BubbleColor) -> ShotEvaluation {
+        let depth = self.depth(target);
+        let cluster = self.cluster(target, color);
+        let (popped, dropped) = if cluster.len() >= MIN_CLUSTER_SIZE {
+            let mut grid = self.grid.clone();
+            for &coord in &cluster {
+                grid.remove(coord);
+            }
+            (
+                cluster.len(),
+                find_floating_bubbles(&grid, grid.top_row_coords(), &cluster).len(),
+            )
+        } else {
+            (0, 0)
+        };
+
+        ShotEvaluation {
+            cluster: cluster.len(),
+            popped,
+            dropped,
+            depth,
+            in_danger: depth >= self.danger_depth(),
+        }
+    }
+
+    /// Land `color` at `target`, pop its cluster, and drop floating bubbles.
+    /// Returns `(popped, dropped)`.
+    pub fn place(&mut self, target: HexCoord, color: BubbleColor) -> (usize, usize) {
+        let cluster = self.cluster(target, color);
+        self.insert(target, Some(color));
+        if cluster.len() < MIN_CLUSTER_SIZE {
+            return (0, 0);
+        }
+
+        for coord in &cluster {
+            self.grid.remove(*coord);
+            self.colors.remove(coord);
+        }
+        let floating = find_floating_bubbles(&self.grid, self.grid.top_row_coords(), &cluster);
+        for coord in &floating {
+            self.grid.remove(*coord);
+            self.colors.remove(coord);
+        }
+        (cluster.len(), floating.len())
+    }
+
+    /// Same-colored bubbles connected to `start`, treating `start` as `color`.
+    fn cluster(&self, start: HexCoord, color: BubbleColor) -> Vec<HexCoord> {
+        find_cluster(&self.grid.layout, start, color, |coord| {
+            self.colors.get(&coord).copied()
+        })
+    }
+}
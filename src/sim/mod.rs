@@ -0,0 +1,17 @@
+//! Headless game core - the bubble grid rules without the ECS.
+//!
+//! Plays by the game's own rules (grid bounds, initial rows, descent pacing,
+//! scoring, danger line, grace bounce, clusters and floating bubbles, all from
+//! `game::rules`) on a grid of placeholder entities, so bots can play
+//! thousands of games quickly. [`policy::ShotPolicy`] implementations pick where to
+//! shoot and are shared by everything that needs a bot's opinion.
+//!
+//! The game uses the board and policies for its attract-mode demo and hints,
+//! and the `simulate` binary uses the whole module from the library.
+
+pub mod board;
+pub mod policy;
+pub mod run;
+
+pub use policy::policy_by_name;
+pub use run::{GameResult, Outcome, SimConfig, play_game};
@@ -0,0 +1,129 @@
+//! Bot strategies for choosing where to shoot.
+
+use rand::{RngCore, seq::IndexedRandom};
+
+use super::board::{Board, ShotEvaluation};
+use crate::game::rules::{BubbleColor, HexCoord};
+
+/// A strategy for picking a landing cell for the loaded bubble.
+pub trait ShotPolicy {
+    /// Short name used on the command line and in reports.
+    fn name(&self) -> &'static str;
+
+    /// Pick one of the board's reachable cells for a bubble of `color`.
+    fn choose_target(
+        &mut self,
+        board: &Board,
+        color: BubbleColor,
+        rng: &mut dyn RngCore,
+    ) -> Option<HexCoord>;
+}
+
+/// Look up a policy by its [`ShotPolicy::name`].
+pub fn policy_by_name(name: &str) -> Option<Box<dyn ShotPolicy>> {
+    match name {
+        "random" => Some(Box::new(RandomPolicy)),
+        "greedy" => Some(Box::new(GreedyPolicy)),
+        "drops" => Some(Box::new(DropMaximizer)),
+        _ => None,
+    }
+}
+
+/// Shoot at any reachable cell.
+pub struct RandomPolicy;
+
+impl ShotPolicy for RandomPolicy {
+    fn name(&self) -> &'static str {
+        "random"
+    }
+
+    fn choose_target(
+        &mut self,
+        board: &Board,
+        _color: BubbleColor,
+        rng: &mut dyn RngCore,
+    ) -> Option<HexCoord> {
+        board.reachable_cells().choose(rng).copied()
+    }
+}
+
+/// Pop the largest cluster available, otherwise build on matching colors.
+pub struct GreedyPolicy;
+
+impl ShotPolicy for GreedyPolicy {
+    fn name(&self) -> &'static str {
+        "greedy"
+    }
+
+    fn choose_target(
+        &mut self,
+        board: &Board,
+        color: BubbleColor,
+        rng: &mut dyn RngCore,
+    ) -> Option<HexCoord> {
+        best_target(board, color, rng, |eval| {
+            if eval.popped > 0 {
+                1000 + (eval.popped + eval.dropped * 2) as i32 * 10
+            } else {
+                build_value(eval)
+            }
+        })
+    }
+}
+
+/// Prefer shots that drop the most floating bubbles, then the largest pop.
+pub struct DropMaximizer;
+
+impl ShotPolicy for DropMaximizer {
+    fn name(&self) -> &'static str {
+        "drops"
+    }
+
+    fn choose_target(
+        &mut self,
+        board: &Board,
+        color: BubbleColor,
+        rng: &mut dyn RngCore,
+    ) -> Option<HexCoord> {
+        best_target(board, color, rng, |eval| {
+            if eval.popped > 0 {
+                1000 + eval.dropped as i32 * 100 + eval.popped as i32
+            } else {
+                build_value(eval)
+            }
+        })
+    }
+}
+
+/// Value of a shot that doesn't pop: grow same-colored groups, stay high.
+fn build_value(eval: &ShotEvaluation) -> i32 {
+    eval.cluster as i32 * 10 - eval.depth
+}
+
+/// Evaluate every reachable cell and pick randomly among the best.
+/// Danger zone cells are only chosen when nothing else is reachable.
+fn best_target(
+    board: &Board,
+    color: BubbleColor,
+    rng: &mut dyn RngCore,
+    value: impl Fn(&ShotEvaluation) -> i32,
+) -> Option<HexCoord> {
+    let mut best = Vec::new();
+    let mut best_value = i32::MIN;
+    for cell in board.reachable_cells() {
+        let eval = board.evaluate(cell, color);
+        let cell_value = if eval.in_danger {
+            i32::MIN + 1
+        } else {
+            value(&eval)
+        };
+        if cell_value > best_value {
+            best_value = cell_value;
+            best.clear();
+        }
+        if cell_value == best_value {
+            best.push(cell);
+        }
+    }
+    best.choose(rng).copied()
+}
@@ -0,0 +1,120 @@
+//! Playing whole games with a [`ShotPolicy`].
+
+use rand::{Rng, RngCore};
+
+use super::{board::Board, policy::ShotPolicy};
+use crate::game::rules::{
+    BubbleColor, FLOATING_BONUS_MULTIPLIER, GRACE_BOUNCES_PER_RUN, HexCoord, INITIAL_ROWS,
+    POINTS_PER_BUBBLE, shots_per_descent,
+};
+
+/// Tunable rules for a simulated game.
+#[derive(Debug, Clone, Copy)]
+pub struct SimConfig {
+    /// Number of bubble colors in play, up to 6 (the game uses 6).
+    pub colors: u8,
+    /// Shots before the first descent (the game uses 8).
+    pub shots_per_descent: u32,
+    /// Safety cap so a stalemate can't loop forever.
+    pub max_shots: u32,
+}
+
+/// How a simulated game ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Won,
+    GridReachedDanger,
+    DangerLanding,
+    ShotLimit,
+}
+
+/// Summary of a simulated game.
+pub struct GameResult {
+    pub level: u32,
+    pub score: u32,
+    pub outcome: Outcome,
+}
+
+/// Play one game from a fresh board until it ends.
+pub fn play_game(
+    config: SimConfig,
+    policy: &mut dyn ShotPolicy,
+    rng: &mut dyn RngCore,
+) -> GameResult {
+    let mut board = Board::default();
+    for r in 0..INITIAL_ROWS {
+        fill_row(&mut board, r, config.colors, rng);
+    }
+
+    let mut level = 1;
+    let mut shots_until_descent = config.shots_per_descent;
+    let mut shots_this_round = 0;
+    let mut score = 0;
+    let mut grace = GRACE_BOUNCES_PER_RUN;
+    let mut loaded = random_color(config.colors, rng);
+    let mut next = random_color(config.colors, rng);
+
+    let mut outcome = Outcome::ShotLimit;
+    for _ in 0..config.max_shots {
+        if let Some(target) = policy.choose_target(&board, loaded, rng) {
+            if board.depth(target) >= board.danger_depth() {
+                if grace == 0 {
+                    outcome = Outcome::DangerLanding;
+                    break;
+                }
+                grace -= 1;
+            } else {
+                let (popped, dropped) = board.place(target, loaded);
+                score += popped as u32 * POINTS_PER_BUBBLE
+                    + dropped as u32 * POINTS_PER_BUBBLE * FLOATING_BONUS_MULTIPLIER;
+                if board.grid.is_empty() {
+                    outcome = Outcome::Won;
+                    break;
+                }
+            }
+        }
+
+        loaded = next;
+        next = random_color(config.colors, rng);
+
+        shots_this_round += 1;
+        if shots_this_round < shots_until_descent {
+            continue;
+        }
+
+        // Descent: move the grid down and add a row at the top
+        board.top_r -= 1;
+        let top_r = board.top_r;
+        fill_row(&mut board, top_r, config.colors, rng);
+        if board
+            .grid
+            .coords()
+            .any(|c| board.depth(c) >= board.danger_depth())
+        {
+            outcome = Outcome::GridReachedDanger;
+            break;
+        }
+
+        level += 1;
+        shots_this_round = 0;
+        shots_until_descent = shots_per_descent(config.shots_per_descent, level);
+    }
+
+    GameResult {
+        level,
+        score,
+        outcome,
+    }
+}
+
+fn fill_row(board: &mut Board, r: i32, colors: u8, rng: &mut dyn RngCore) {
+    let bounds = board.grid.bounds;
+    for q in bounds.min_q..=bounds.max_q {
+        board.insert(HexCoord::new(q, r), Some(random_color(colors, rng)));
+    }
+}
+
+/// One of the first `colors` of [`BubbleColor::ALL`].
+fn random_color(colors: u8, rng: &mut dyn RngCore) -> BubbleColor {
+    BubbleColor::ALL[rng.random_range(0..colors as usize)]
+}
@@ -0,0 +1,97 @@
+//! Steam achievements and cloud saves, with the `steam` feature.
+//!
+//! Achievements are read off the [`Profile`]: whenever it changes, every
+//! achievement it qualifies for is unlocked. Steam ignores ones that already
+//! are, so there's nothing to track on this side. Their API names have to
+//! match the ones set up in the Steamworks app admin.
+//!
+//! Cloud saves happen in [`save::storage`](crate::save::storage), which
+//! writes every save file to Steam Cloud as well as the data directory while
+//! Steam is running.
+//!
+//! Steam has to be running and know the app ID (from the launcher, or a
+//! `steam_appid.txt` next to the game). Without it, achievements are skipped
+//! and saves stay local, the same as a build without the feature.
+
+use std::sync::OnceLock;
+
+use bevy::prelude::*;
+use steamworks::Client;
+
+use crate::{game::campaign::CampaignLevels, profile::Profile};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(
+        Update,
+        (
+            run_callbacks,
+            unlock_achievements.run_if(resource_changed::<Profile>),
+        )
+            .run_if(|| client().is_some()),
+    );
+}
+
+/// Best score, in any mode, that earns `HIGH_SCORER`.
+const HIGH_SCORE: u32 = 10_000;
+
+/// Every achievement, by API name, with what earns it.
+const ACHIEVEMENTS: &[(&str, fn(&Profile, &CampaignLevels) -> bool)] = &[
+    ("FIRST_RUN", |profile, _| !profile.recent_runs.is_empty()),
+    ("FIRST_LEVEL", |profile, _| profile.campaign_cleared >= 1),
+    ("CAMPAIGN_COMPLETE", |profile, levels| {
+        !levels.0.is_empty() && profile.campaign_cleared as usize >= levels.0.len()
+    }),
+    ("HIGH_SCORER", |profile, _| {
+        profile
+            .best_scores
+            .values()
+            .any(|&score| score >= HIGH_SCORE)
+    }),
+];
+
+/// The Steam client, or `None` if Steam isn't running. Connects on first use,
+/// so save files loaded at startup can already come from the cloud.
+pub fn client() -> Option<&'static Client> {
+    static CLIENT: OnceLock<Option<Client>> = OnceLock::new();
+    CLIENT
+        .get_or_init(|| match Client::init() {
+            Ok(client) => {
+                info!("Connected to Steam");
+                Some(client)
+            }
+            Err(e) => {
+                info!("Steam isn't available, so saves stay local: {}", e);
+                None
+            }
+        })
+        .as_ref()
+}
+
+/// Let Steam deliver its results (like stats being stored).
+fn run_callbacks() {
+    if let Some(client) = client() {
+        client.run_callbacks();
+    }
+}
+
+fn unlock_achievements(profile: Res<Profile>, levels: Res<CampaignLevels>) {
+    let Some(client) = client() else {
+        return;
+    };
+    let stats = client.user_stats();
+    let mut unlocked = false;
+    for (name, earned) in ACHIEVEMENTS {
+        if !earned(&profile, &levels) || stats.achievement(name).get() == Ok(true) {
+            continue;
+        }
+        if stats.achievement(name).set().is_ok() {
+            info!("Unlocked Steam achievement {}", name);
+            unlocked = true;
+        } else {
+            warn!("Steam doesn't know achievement {}", name);
+        }
+    }
+    if unlocked && stats.store_stats().is_err() {
+        warn!("Failed to store Steam achievements");
+    }
+}
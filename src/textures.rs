@@ -0,0 +1,129 @@
+//! Sprite loading with optional KTX2 compressed variants, and texture memory
+//! diagnostics.
+//!
+//! `assets/images/compressed.txt` lists sprites that also ship as KTX2 in a
+//! GPU compressed format. Those load as KTX2 when the GPU supports the format
+//! and fall back to the PNG otherwise, so the web build can stay small without
+//! breaking devices that lack a format.
+//!
+//! No sprites ship compressed yet, so the manifest is empty and every sprite
+//! loads as PNG. Only BCn, ASTC, and ETC2 variants are supported: Basis
+//! Universal would need Bevy's `basis-universal` feature.
+
+use bevy::{
+    diagnostic::{Diagnostic, DiagnosticPath, Diagnostics, RegisterDiagnostic},
+    ecs::system::SystemParam,
+    image::{CompressedImageFormatSupport, CompressedImageFormats},
+    prelude::*,
+};
+
+use crate::seasons::{ActiveSeason, seasonal_variant};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_diagnostic(Diagnostic::new(TEXTURE_MEMORY).with_suffix(" KiB"));
+    app.register_diagnostic(Diagnostic::new(TEXTURE_COUNT));
+
+    app.add_systems(Update, measure_texture_memory);
+}
+
+/// Approximate GPU memory held by loaded textures, in KiB.
+pub const TEXTURE_MEMORY: DiagnosticPath = DiagnosticPath::const_new("textures/memory");
+
+/// Number of loaded textures.
+pub const TEXTURE_COUNT: DiagnosticPath = DiagnosticPath::const_new("textures/count");
+
+/// Sprites with a KTX2 variant, and the compressed format it uses.
+const COMPRESSED_MANIFEST: &str = include_str!("../assets/images/compressed.txt");
+
+/// Loads sprites, preferring a compressed KTX2 variant when one is usable.
+#[derive(SystemParam)]
+pub struct SpriteLoader<'w> {
+    asset_server: Res<'w, AssetServer>,
+    format_support: Option<Res<'w, CompressedImageFormatSupport>>,
+    season: Option<Res<'w, ActiveSeason>>,
+}
+
+impl SpriteLoader<'_> {
+    /// Load `path` (a `.png` under `assets/`), or its KTX2 variant if listed
+    /// in the manifest and supported by the GPU. While a season is active,
+    /// its variant of the sprite is loaded instead, if it has one.
+    pub fn load(&self, path: &str) -> Handle<Image> {
+        let seasonal = self
+            .season
+            .as_ref()
+            .and_then(|season| season.0)
+            .and_then(|season| seasonal_variant(path, season));
+        let path = seasonal.as_deref().unwrap_or(path);
+        let stem = path.strip_suffix(".png").unwrap_or(path);
+        let supported = self
+            .format_support
+            .as_ref()
+            .map_or(CompressedImageFormats::NONE, |support| support.0);
+
+        match compressed_format(COMPRESSED_MANIFEST, stem) {
+            Some(format) if supported.contains(format) => {
+                self.asset_server.load(format!("{stem}.ktx2"))
+            }
+            _ => self.asset_server.load(path.to_string()),
+        }
+    }
+}
+
+/// The compressed format of `stem`'s KTX2 variant, if `manifest` lists one.
+fn compressed_format(manifest: &str, stem: &str) -> Option<CompressedImageFormats> {
+    manifest
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .find_map(|line| {
+            let (name, format) = line.split_once(char::is_whitespace)?;
+            if name != stem {
+                return None;
+            }
+            match format.trim() {
+                "bc" => Some(CompressedImageFormats::BC),
+                "astc" => Some(CompressedImageFormats::ASTC_LDR),
+                "etc2" => Some(CompressedImageFormats::ETC2),
+                other => {
+                    warn!("Unknown compressed format '{}' for {}", other, stem);
+                    None
+                }
+            }
+        })
+}
+
+/// Sample how much texture memory is in use, for the diagnostics overlay.
+fn measure_texture_memory(mut diagnostics: Diagnostics, images: Res<Assets<Image>>) {
+    let bytes: u64 = images
+        .iter()
+        .map(|(_, image)| {
+            let descriptor = &image.texture_descriptor;
+            descriptor
+                .format
+                .theoretical_memory_footprint(descriptor.size)
+        })
+        .sum();
+    diagnostics.add_measurement(&TEXTURE_MEMORY, || bytes as f64 / 1024.0);
+    diagnostics.add_measurement(&TEXTURE_COUNT, || images.len() as f64);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_formats_from_the_manifest() {
+        let manifest = "# comment\n\nimages/derpy bc\n  images/ducky   astc  \nimages/odd basis\n";
+
+        assert_eq!(
+            compressed_format(manifest, "images/derpy"),
+            Some(CompressedImageFormats::BC)
+        );
+        assert_eq!(
+            compressed_format(manifest, "images/ducky"),
+            Some(CompressedImageFormats::ASTC_LDR)
+        );
+        assert_eq!(compressed_format(manifest, "images/odd"), None);
+        assert_eq!(compressed_format(manifest, "images/splash"), None);
+    }
+}
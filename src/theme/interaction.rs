@@ -1,12 +1,34 @@
-use bevy::prelude::*;
+use std::time::Duration;
 
-use crate::{asset_tracking::LoadResource, audio::sound_effect};
+use bevy::{
+    camera::NormalizedRenderTarget,
+    picking::{
+        backend::HitData,
+        pointer::{Location, PointerButton, PointerId},
+    },
+    prelude::*,
+    ui::UiGlobalTransform,
+};
+
+use crate::{
+    asset_tracking::LoadResource, audio::sound_effect, menus::Menu,
+    theme::palette::BUTTON_FOCUS_OUTLINE,
+};
 
 pub(super) fn plugin(app: &mut App) {
+    app.register_type::<Focused>();
+
     app.add_systems(
         Update,
         (apply_interaction_palette, apply_image_interaction_palette),
     );
+    // Gamepad navigation through whichever menu is open
+    app.add_systems(
+        Update,
+        (move_button_focus, press_focused_button)
+            .chain()
+            .run_if(not(in_state(Menu::None))),
+    );
 
     app.load_resource::<InteractionAssets>();
     app.add_observer(play_on_hover_sound_effect);
@@ -65,6 +87,113 @@ fn apply_image_interaction_palette(
     }
 }
 
+/// The button a gamepad has focus on. The d-pad moves it to the nearest
+/// button in that direction, and the south face button clicks it.
+#[derive(Component, Debug, Reflect)]
+#[reflect(Component)]
+pub struct Focused;
+
+/// Which way the d-pad was just pushed, in UI space (y down).
+fn dpad_step(gamepad: &Gamepad) -> Option<Vec2> {
+    [
+        (GamepadButton::DPadUp, Vec2::NEG_Y),
+        (GamepadButton::DPadDown, Vec2::Y),
+        (GamepadButton::DPadLeft, Vec2::NEG_X),
+        (GamepadButton::DPadRight, Vec2::X),
+    ]
+    .into_iter()
+    .find_map(|(button, step)| gamepad.just_pressed(button).then_some(step))
+}
+
+/// The button nearest `from` in the direction of `step`. Buttons off to the
+/// side count as further away, so moving down a column stays in it.
+fn next_in_direction(from: Vec2, step: Vec2, buttons: &[(Entity, Vec2)]) -> Option<Entity> {
+    buttons
+        .iter()
+        .filter_map(|&(entity, position)| {
+            let offset = position - from;
+            let along = offset.dot(step);
+            let across = offset.perp_dot(step).abs();
+            (along > 1.0).then_some((entity, along + 2.0 * across))
+        })
+        .min_by(|(_, a), (_, b)| a.total_cmp(b))
+        .map(|(entity, _)| entity)
+}
+
+fn move_button_focus(
+    mut commands: Commands,
+    gamepads: Query<&Gamepad>,
+    buttons: Query<(Entity, &UiGlobalTransform, &InheritedVisibility), With<Button>>,
+    focused: Query<Entity, With<Focused>>,
+    interaction_assets: Option<Res<InteractionAssets>>,
+) {
+    let Some(step) = gamepads.iter().find_map(dpad_step) else {
+        return;
+    };
+    let visible: Vec<(Entity, Vec2)> = buttons
+        .iter()
+        .filter(|(_, _, visibility)| visibility.get())
+        .map(|(entity, transform, _)| (entity, transform.translation))
+        .collect();
+
+    let current = focused
+        .iter()
+        .find_map(|entity| visible.iter().find(|(button, _)| *button == entity));
+    let next = match current {
+        Some(&(_, from)) => next_in_direction(from, step, &visible),
+        // Nothing focused yet: start at the top
+        None => visible
+            .iter()
+            .min_by(|(_, a), (_, b)| a.y.total_cmp(&b.y).then(a.x.total_cmp(&b.x)))
+            .map(|&(entity, _)| entity),
+    };
+    let Some(next) = next else {
+        return;
+    };
+
+    for entity in &focused {
+        commands.entity(entity).remove::<(Focused, Outline)>();
+    }
+    commands
+        .entity(next)
+        .insert((Focused, Outline::new(px(4), px(2), BUTTON_FOCUS_OUTLINE)));
+    if let Some(interaction_assets) = interaction_assets {
+        commands.spawn(sound_effect(interaction_assets.hover.clone()));
+    }
+}
+
+/// Click the focused button as if with the mouse, so its observers run.
+fn press_focused_button(
+    mut commands: Commands,
+    gamepads: Query<&Gamepad>,
+    focused: Query<(Entity, &UiGlobalTransform, &InheritedVisibility), With<Focused>>,
+) {
+    if !gamepads
+        .iter()
+        .any(|gamepad| gamepad.just_pressed(GamepadButton::South))
+    {
+        return;
+    }
+    let Some((entity, transform, _)) = focused.iter().find(|(_, _, visibility)| visibility.get())
+    else {
+        return;
+    };
+
+    let location = Location {
+        target: NormalizedRenderTarget::None {
+            width: 0,
+            height: 0,
+        },
+        position: transform.translation,
+    };
+    let click = Click {
+        button: PointerButton::Primary,
+        hit: HitData::new(Entity::PLACEHOLDER, 0.0, None, None),
+        duration: Duration::ZERO,
+    };
+    commands.trigger(Pointer::new(PointerId::Mouse, location, click, entity));
+}
+
 #[derive(Resource, Asset, Clone, Reflect)]
 #[reflect(Resource)]
 struct InteractionAssets {
@@ -113,3 +242,29 @@ fn play_on_click_sound_effect(
         commands.spawn(sound_effect(interaction_assets.click.clone()));
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn focus_moves_to_the_nearest_button_that_way() {
+        let button = |index: u32| Entity::from_raw_u32(index).unwrap();
+        // A column of three with one off to the right of the middle
+        let buttons = [
+            (button(1), Vec2::new(100.0, 100.0)),
+            (button(2), Vec2::new(100.0, 200.0)),
+            (button(3), Vec2::new(100.0, 300.0)),
+            (button(4), Vec2::new(300.0, 210.0)),
+        ];
+        let from = Vec2::new(100.0, 200.0);
+
+        assert_eq!(next_in_direction(from, Vec2::Y, &buttons), Some(button(3)));
+        assert_eq!(
+            next_in_direction(from, Vec2::NEG_Y, &buttons),
+            Some(button(1))
+        );
+        assert_eq!(next_in_direction(from, Vec2::X, &buttons), Some(button(4)));
+        assert_eq!(next_in_direction(from, Vec2::NEG_X, &buttons), None);
+    }
+}
@@ -5,6 +5,7 @@
 
 pub mod interaction;
 pub mod palette;
+pub mod toast;
 pub mod widget;
 
 #[allow(unused_imports)]
@@ -19,7 +20,7 @@ use bevy::prelude::*;
 pub struct GameFont(pub Handle<Font>);
 
 pub(super) fn plugin(app: &mut App) {
-    app.add_plugins(interaction::plugin);
+    app.add_plugins((interaction::plugin, toast::plugin));
     app.add_systems(Startup, load_game_font);
 }
 
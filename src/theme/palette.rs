@@ -14,3 +14,37 @@ pub const BUTTON_BACKGROUND: Color = Color::srgb(0.275, 0.400, 0.750);
 pub const BUTTON_HOVERED_BACKGROUND: Color = Color::srgb(0.384, 0.600, 0.820);
 /// #3d4999
 pub const BUTTON_PRESSED_BACKGROUND: Color = Color::srgb(0.239, 0.286, 0.600);
+/// #f2c14e
+pub const BUTTON_FOCUS_OUTLINE: Color = Color::srgb(0.949, 0.757, 0.306);
+
+/// Colors for one time of day in the gameplay backdrop.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DaylightPalette {
+    /// Clear color behind everything.
+    pub backdrop: Color,
+    /// Tint on the game panel, like the light falling on it.
+    pub light: Color,
+    /// Tint on the background doodles.
+    pub doodle: Color,
+}
+
+/// Morning light: the menus' paper color, nothing tinted.
+pub const DAY: DaylightPalette = DaylightPalette {
+    backdrop: Color::srgb(0.96, 0.92, 0.84),
+    light: Color::WHITE,
+    doodle: Color::WHITE,
+};
+
+/// Warm evening light.
+pub const DUSK: DaylightPalette = DaylightPalette {
+    backdrop: Color::srgb(0.93, 0.74, 0.6),
+    light: Color::srgb(1.0, 0.88, 0.78),
+    doodle: Color::srgb(1.0, 0.76, 0.62),
+};
+
+/// Cool and dim, but light enough for the dark UI text.
+pub const NIGHT: DaylightPalette = DaylightPalette {
+    backdrop: Color::srgb(0.45, 0.47, 0.62),
+    light: Color::srgb(0.72, 0.76, 0.92),
+    doodle: Color::srgb(0.5, 0.55, 0.82),
+};
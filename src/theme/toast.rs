@@ -0,0 +1,178 @@
+//! Toast notifications - short messages that slide in and dismiss themselves.
+//!
+//! Any system can write a [`ShowToast`] message. Toasts are queued and shown
+//! one at a time in the top-right corner, above menus.
+
+use std::collections::VecDeque;
+
+use bevy::prelude::*;
+
+use crate::{AppSystems, theme::GameFont};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_message::<ShowToast>();
+    app.init_resource::<ToastQueue>();
+
+    app.add_systems(
+        Update,
+        (
+            queue_toasts.in_set(AppSystems::RecordInput),
+            (show_next_toast, animate_toasts)
+                .chain()
+                .in_set(AppSystems::Update),
+        ),
+    );
+}
+
+/// Message to show a toast notification.
+#[derive(Message, Debug, Clone)]
+pub struct ShowToast {
+    pub text: String,
+    pub kind: ToastKind,
+}
+
+impl ShowToast {
+    /// A neutral informational toast.
+    pub fn info(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            kind: ToastKind::Info,
+        }
+    }
+
+    /// A toast celebrating something good (high scores, achievements).
+    pub fn success(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            kind: ToastKind::Success,
+        }
+    }
+
+    /// A toast warning about a problem (failed saves, lost connection).
+    pub fn warning(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            kind: ToastKind::Warning,
+        }
+    }
+}
+
+/// The visual style of a toast.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ToastKind {
+    #[default]
+    Info,
+    Success,
+    Warning,
+}
+
+impl ToastKind {
+    fn background(self) -> Color {
+        match self {
+            ToastKind::Info => Color::srgb(0.275, 0.400, 0.750),
+            ToastKind::Success => Color::srgb(0.25, 0.6, 0.3),
+            ToastKind::Warning => Color::srgb(0.8, 0.35, 0.2),
+        }
+    }
+}
+
+/// Toasts waiting to be shown.
+#[derive(Resource, Default)]
+struct ToastQueue {
+    pending: VecDeque<ShowToast>,
+}
+
+/// A toast currently on screen.
+#[derive(Component)]
+struct Toast {
+    /// Seconds since the toast appeared.
+    t: f32,
+}
+
+/// Seconds to slide in (and out).
+const TOAST_SLIDE_SECS: f32 = 0.25;
+/// Seconds the toast stays fully visible.
+const TOAST_HOLD_SECS: f32 = 2.5;
+/// Width of a toast in pixels.
+const TOAST_WIDTH: f32 = 240.0;
+/// Distance from the right edge of the window when fully visible.
+const TOAST_MARGIN: f32 = 10.0;
+
+fn queue_toasts(mut toast_events: MessageReader<ShowToast>, mut queue: ResMut<ToastQueue>) {
+    queue.pending.extend(toast_events.read().cloned());
+}
+
+/// Spawn the next queued toast once the previous one is gone.
+fn show_next_toast(
+    mut commands: Commands,
+    mut queue: ResMut<ToastQueue>,
+    toast_query: Query<(), With<Toast>>,
+    game_font: Option<Res<GameFont>>,
+) {
+    if !toast_query.is_empty() {
+        return;
+    }
+    let Some(toast) = queue.pending.pop_front() else {
+        return;
+    };
+
+    let font = game_font.map(|f| f.0.clone()).unwrap_or_default();
+    commands.spawn((
+        Name::new("Toast"),
+        Toast { t: 0.0 },
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(10.0),
+            right: Val::Px(-TOAST_WIDTH),
+            width: Val::Px(TOAST_WIDTH),
+            padding: UiRect::all(Val::Px(10.0)),
+            justify_content: JustifyContent::Center,
+            ..default()
+        },
+        BackgroundColor(toast.kind.background()),
+        BorderRadius::all(Val::Px(8.0)),
+        GlobalZIndex(10),
+        Pickable::IGNORE,
+        children![(
+            Name::new("Toast Text"),
+            Text(toast.text),
+            TextFont {
+                font,
+                font_size: 16.0,
+                ..default()
+            },
+            TextColor(Color::WHITE),
+            Pickable::IGNORE,
+        )],
+    ));
+}
+
+/// Slide toasts in, hold, slide them out, and despawn them.
+fn animate_toasts(
+    mut commands: Commands,
+    time: Res<Time<Real>>,
+    mut toast_query: Query<(Entity, &mut Toast, &mut Node)>,
+) {
+    for (entity, mut toast, mut node) in &mut toast_query {
+        toast.t += time.delta_secs();
+
+        let slide_out_start = TOAST_SLIDE_SECS + TOAST_HOLD_SECS;
+        let visible = if toast.t < TOAST_SLIDE_SECS {
+            toast.t / TOAST_SLIDE_SECS
+        } else if toast.t < slide_out_start {
+            1.0
+        } else {
+            1.0 - (toast.t - slide_out_start) / TOAST_SLIDE_SECS
+        };
+
+        if visible <= 0.0 {
+            commands.entity(entity).despawn();
+            continue;
+        }
+
+        // Ease out for a snappier slide
+        let eased = 1.0 - (1.0 - visible.clamp(0.0, 1.0)).powi(3);
+        let hidden_right = -TOAST_WIDTH;
+        node.right = Val::Px(hidden_right + (TOAST_MARGIN - hidden_right) * eased);
+    }
+}
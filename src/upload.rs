@@ -0,0 +1,41 @@
+//! Posting records (run summaries, feedback) to an endpoint set in their
+//! save file.
+//!
+//! Uploads run on their own thread so the game never waits on the network.
+//! Nothing reports back: a failed upload is only logged, and the record stays
+//! in its local file either way.
+
+use bevy::prelude::*;
+use serde::Serialize;
+
+/// Start posting `record` to `endpoint` as JSON. `what` names the record in
+/// log messages.
+///
+/// Returns whether the upload was started, not whether it arrived.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn post_json(endpoint: String, record: &impl Serialize, what: &str) -> bool {
+    let body = match serde_json::to_string(record) {
+        Ok(body) => body,
+        Err(e) => {
+            warn!("Failed to serialize {}: {}", what, e);
+            return false;
+        }
+    };
+
+    let what = what.to_string();
+    std::thread::spawn(move || {
+        if let Err(e) = ureq::post(&endpoint)
+            .set("Content-Type", "application/json")
+            .send_string(&body)
+        {
+            warn!("Failed to upload {}: {}", what, e);
+        }
+    });
+    true
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn post_json(_endpoint: String, _record: &impl Serialize, what: &str) -> bool {
+    info!("Uploading {} is not supported on the web build", what);
+    false
+}
@@ -0,0 +1,123 @@
+//! Browser-friendly window handling, mainly for the itch.io build.
+//!
+//! - Clicking or tapping the canvas gives it keyboard focus, and keeps the
+//!   pointer until it's released, so an aim drag can leave the canvas.
+//! - The camera zooms to keep the whole playfield visible when the canvas is
+//!   resized or the device pixel ratio changes.
+//! - Gameplay pauses when the tab is hidden (see [`page_hidden`]).
+
+use bevy::{
+    prelude::*,
+    window::{PrimaryWindow, WindowOccluded, WindowResized, WindowScaleFactorChanged},
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(
+        Update,
+        fit_camera_to_window.run_if(
+            on_message::<WindowResized>
+                .or(on_message::<WindowScaleFactorChanged>)
+                .or(any_match_filter::<Added<Camera2d>>),
+        ),
+    );
+
+    #[cfg(target_arch = "wasm32")]
+    app.add_systems(Update, capture_canvas_presses);
+}
+
+/// The playfield size the game is laid out for, matching the initial window
+/// resolution in `lib.rs`.
+const VIEW_SIZE: Vec2 = Vec2::new(800.0, 600.0);
+
+/// Run condition: the window was hidden this frame.
+///
+/// On the web, winit reports the canvas as occluded when the tab is hidden
+/// (Page Visibility) or the canvas scrolls out of view. Native builds report
+/// it when minimized.
+pub fn page_hidden(mut occluded: MessageReader<WindowOccluded>) -> bool {
+    occluded.read().any(|event| event.occluded)
+}
+
+/// Zoom the camera out (or in) so the whole playfield fits the window.
+///
+/// Window sizes are logical, so a device pixel ratio change only matters
+/// through the resize that comes with it.
+fn fit_camera_to_window(
+    window: Single<&Window, With<PrimaryWindow>>,
+    mut projections: Query<&mut Projection, With<Camera2d>>,
+) {
+    let size = window.size();
+    if size.x <= 0.0 || size.y <= 0.0 {
+        return;
+    }
+    let scale = (VIEW_SIZE / size).max_element();
+
+    for mut projection in &mut projections {
+        if let Projection::Orthographic(ortho) = projection.as_mut()
+            && ortho.scale != scale
+        {
+            ortho.scale = scale;
+            debug!(
+                "Camera scale {:.2} for {}x{} window (scale factor {})",
+                scale,
+                size.x,
+                size.y,
+                window.scale_factor()
+            );
+        }
+    }
+}
+
+/// Keyboard input only reaches the canvas while it has focus, so take it on
+/// any click or tap, and capture the pointer until it's released.
+///
+/// Setting [`Window::focused`] does nothing in the browser, so this listens
+/// on the canvas itself, once it exists. [`CursorGrabMode`] isn't used: the
+/// browser only offers [`CursorGrabMode::Locked`], which freezes the cursor
+/// the aim follows.
+///
+/// [`CursorGrabMode`]: bevy::window::CursorGrabMode
+/// [`CursorGrabMode::Locked`]: bevy::window::CursorGrabMode::Locked
+#[cfg(target_arch = "wasm32")]
+fn capture_canvas_presses(
+    window: Single<&Window, With<PrimaryWindow>>,
+    mut listening: Local<bool>,
+) {
+    use wasm_bindgen::{JsCast, closure::Closure};
+    use web_sys::{HtmlElement, PointerEvent};
+
+    if *listening {
+        return;
+    }
+    let selector = window.canvas.as_deref().unwrap_or("canvas");
+    let Some(canvas) = web_sys::window()
+        .and_then(|window| window.document())
+        .and_then(|document| document.query_selector(selector).ok().flatten())
+    else {
+        // winit adds the canvas after the first frames
+        return;
+    };
+
+    let on_press = Closure::<dyn FnMut(PointerEvent)>::new(|event: PointerEvent| {
+        let Some(canvas) = event
+            .current_target()
+            .and_then(|target| target.dyn_into::<HtmlElement>().ok())
+        else {
+            return;
+        };
+        if let Err(error) = canvas.focus() {
+            warn!("Couldn't focus the canvas: {error:?}");
+        }
+        if let Err(error) = canvas.set_pointer_capture(event.pointer_id()) {
+            warn!("Couldn't capture the pointer: {error:?}");
+        }
+    });
+    if let Err(error) =
+        canvas.add_event_listener_with_callback("pointerdown", on_press.as_ref().unchecked_ref())
+    {
+        warn!("Couldn't listen for presses on the canvas: {error:?}");
+    }
+    // The listener lives as long as the page
+    on_press.forget();
+    *listening = true;
+}